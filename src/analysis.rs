@@ -0,0 +1,489 @@
+//! Configurable library entry point
+//!
+//! [`analyze_project`](crate::analyze_project) and friends take a path and
+//! nothing else. [`Analysis::builder`] runs the same pipeline as the command
+//! line (static analysis, custom temporal rules, git volatility, config
+//! overrides) with the options set on the builder, and returns a
+//! [`CouplingReport`]:
+//!
+//! ```no_run
+//! use cargo_coupling::{Analysis, GitWindow};
+//!
+//! let report = Analysis::builder()
+//!     .path("./")
+//!     .discover_config()
+//!     .git_window(GitWindow::LastCommits(200))
+//!     .threads(4)
+//!     .exclude("src/generated/**")
+//!     .skip("temporal::dropped-guard")
+//!     .build()?
+//!     .run()?;
+//! println!("{} issues, grade {}", report.issues.len(), report.balance.grade);
+//! # Ok::<(), cargo_coupling::AnalysisError>(())
+//! ```
+//!
+//! Findings are filtered with the same rule selectors as `--only` and
+//! `--skip` (`temporal`, `connascence::position`, ...); the analyzers still
+//! run. `no_git` leaves the git history out entirely, like `--no-git`.
+//!
+//! The command line and the language server run their analyses through this
+//! builder too; [`Analysis::refresh`] repeats the static analysis after an
+//! edit without reading the git history again.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use crate::analyzer::{AnalysisScope, AnalyzerError, analyze_workspace_scoped};
+use crate::balance::IssueThresholds;
+use crate::config::{CompiledConfig, ConfigError, load_compiled_config, load_compiled_config_file};
+use crate::coupling_report::CouplingReport;
+use crate::metrics::ProjectMetrics;
+use crate::progress::{AnalysisPhase, ProgressEvent, ProgressHook};
+use crate::rules::UnknownRule;
+use crate::temporal::reanalyze_project;
+use crate::volatility::{
+    CoChangePair, GitWindow, MIN_CO_CHANGE_RATIO, MIN_CO_CHANGES, VolatilityAnalyzer,
+    VolatilityError,
+};
+use crate::workspace::FeatureSelection;
+
+/// Errors that can occur while setting up or running an analysis
+#[derive(Error, Debug)]
+pub enum AnalysisError {
+    #[error("Invalid config file: {0}")]
+    Config(#[from] ConfigError),
+
+    #[error("Invalid path pattern: {0}")]
+    Pattern(#[from] glob::PatternError),
+
+    #[error(transparent)]
+    UnknownRule(#[from] UnknownRule),
+
+    #[error("Failed to create thread pool: {0}")]
+    ThreadPool(#[from] rayon::ThreadPoolBuildError),
+
+    #[error(transparent)]
+    Analyzer(#[from] AnalyzerError),
+
+    #[error(transparent)]
+    Git(#[from] VolatilityError),
+}
+
+/// Where the configuration comes from
+#[derive(Debug, Default)]
+enum ConfigSource {
+    /// No configuration file
+    #[default]
+    None,
+    /// Search from the analyzed path up to the workspace root
+    Discover,
+    /// A specific file
+    File(PathBuf),
+    /// Already loaded
+    Loaded(Box<CompiledConfig>),
+}
+
+/// Options for an [`Analysis`]
+#[derive(Debug, Default)]
+pub struct AnalysisBuilder {
+    path: Option<PathBuf>,
+    config: ConfigSource,
+    git_window: GitWindow,
+    no_git: bool,
+    threads: Option<usize>,
+    packages: Vec<String>,
+    workspace: bool,
+    features: FeatureSelection,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    include_tests: bool,
+    only: Vec<String>,
+    skip: Vec<String>,
+    all_issues: bool,
+    progress: ProgressHook,
+}
+
+impl AnalysisBuilder {
+    /// Project directory or source directory to analyze (default: `.`)
+    pub fn path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Use an already loaded configuration
+    pub fn config(mut self, config: CompiledConfig) -> Self {
+        self.config = ConfigSource::Loaded(Box::new(config));
+        self
+    }
+
+    /// Load the configuration from a file (`.coupling.toml` or `Cargo.toml`)
+    pub fn config_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config = ConfigSource::File(path.into());
+        self
+    }
+
+    /// Use the nearest configuration file, like the command line does
+    pub fn discover_config(mut self) -> Self {
+        self.config = ConfigSource::Discover;
+        self
+    }
+
+    /// Part of the git history used for volatility (default: 6 months)
+    pub fn git_window(mut self, window: GitWindow) -> Self {
+        self.git_window = window;
+        self
+    }
+
+    /// Do not read the git history (no volatility, co-change or ownership data)
+    pub fn no_git(mut self) -> Self {
+        self.no_git = true;
+        self
+    }
+
+    /// Number of threads for parsing (default: one per CPU core)
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = Some(threads);
+        self
+    }
+
+    /// Only analyze these workspace members (names or globs like `cargo -p`)
+    pub fn package(mut self, package: impl Into<String>) -> Self {
+        self.packages.push(package.into());
+        self
+    }
+
+    /// Analyze every workspace member instead of the default members
+    /// (like `--workspace`; ignored when packages are selected)
+    pub fn workspace(mut self, workspace: bool) -> Self {
+        self.workspace = workspace;
+        self
+    }
+
+    /// Enable a feature when resolving dependencies (like `--features`)
+    pub fn feature(mut self, feature: impl Into<String>) -> Self {
+        self.features.features.push(feature.into());
+        self
+    }
+
+    /// Resolve dependencies with all features (like `--all-features`)
+    pub fn all_features(mut self, all_features: bool) -> Self {
+        self.features.all_features = all_features;
+        self
+    }
+
+    /// Resolve dependencies without the default features (like
+    /// `--no-default-features`)
+    pub fn no_default_features(mut self, no_default_features: bool) -> Self {
+        self.features.no_default_features = no_default_features;
+        self
+    }
+
+    /// Only analyze files matching this glob (in addition to `[paths]`)
+    pub fn include(mut self, pattern: impl Into<String>) -> Self {
+        self.include.push(pattern.into());
+        self
+    }
+
+    /// Skip files matching this glob (in addition to `[paths]`)
+    pub fn exclude(mut self, pattern: impl Into<String>) -> Self {
+        self.exclude.push(pattern.into());
+        self
+    }
+
+    /// Analyze test code like production code
+    pub fn include_tests(mut self, include_tests: bool) -> Self {
+        self.include_tests = include_tests;
+        self
+    }
+
+    /// Only report findings of rules matching this selector (like
+    /// `--only`); the analyzers still run
+    pub fn only(mut self, selector: impl Into<String>) -> Self {
+        self.only.push(selector.into());
+        self
+    }
+
+    /// Do not report findings of rules matching this selector (like
+    /// `--skip`); the analyzers still run
+    pub fn skip(mut self, selector: impl Into<String>) -> Self {
+        self.skip.push(selector.into());
+        self
+    }
+
+    /// Receive phase changes and per-file progress while the analysis runs
+    pub fn progress(mut self, hook: ProgressHook) -> Self {
+        self.progress = hook;
+        self
+    }
+
+    /// Report Low severity findings too (like `--all`)
+    pub fn all_issues(mut self, all_issues: bool) -> Self {
+        self.all_issues = all_issues;
+        self
+    }
+
+    /// Load the configuration and check the options
+    pub fn build(self) -> Result<Analysis, AnalysisError> {
+        let path = self.path.unwrap_or_else(|| PathBuf::from("."));
+        let config = match self.config {
+            ConfigSource::None => CompiledConfig::empty(),
+            ConfigSource::Discover => load_compiled_config(&path)?,
+            ConfigSource::File(file) => load_compiled_config_file(&file)?,
+            ConfigSource::Loaded(config) => *config,
+        };
+
+        let mut rules = config.rules.clone();
+        if !self.only.is_empty() {
+            rules.enabled = self.only;
+        }
+        rules.disabled.extend(self.skip);
+        rules.validate()?;
+
+        let mut scope = AnalysisScope {
+            packages: self.packages,
+            workspace: self.workspace,
+            features: self.features,
+            paths: config.paths.clone(),
+            include_tests: self.include_tests || config.include_tests,
+            progress: self.progress,
+            ..AnalysisScope::default()
+        };
+        scope.paths.extend(&self.include, &self.exclude)?;
+
+        let thresholds = IssueThresholds {
+            rules,
+            strict_mode: !self.all_issues,
+            ..IssueThresholds::from_config(&config)
+        };
+        let git = (!self.no_git).then_some(self.git_window);
+
+        let pool = match self.threads {
+            Some(threads) => Some(
+                rayon::ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .build()?,
+            ),
+            None => None,
+        };
+
+        Ok(Analysis {
+            path,
+            config,
+            scope,
+            thresholds,
+            git,
+            pool,
+            history: None,
+        })
+    }
+}
+
+/// What the last run read from the git history
+#[derive(Debug)]
+struct GitHistory {
+    file_changes: BTreeMap<String, usize>,
+    file_authors: BTreeMap<String, BTreeMap<String, usize>>,
+    co_changes: Vec<CoChangePair>,
+}
+
+impl GitHistory {
+    /// Copy the history into `metrics` and rate volatility from it
+    fn apply(&self, metrics: &mut ProjectMetrics) {
+        metrics.file_changes = self.file_changes.clone();
+        metrics.file_authors = self.file_authors.clone();
+        metrics.co_changes = self.co_changes.clone();
+        metrics.update_volatility_from_git();
+    }
+}
+
+/// A configured analysis, ready to run
+#[derive(Debug)]
+pub struct Analysis {
+    path: PathBuf,
+    config: CompiledConfig,
+    scope: AnalysisScope,
+    thresholds: IssueThresholds,
+    /// `None` without git analysis
+    git: Option<GitWindow>,
+    pool: Option<rayon::ThreadPool>,
+    /// Git history of the last run, reused by [`Analysis::refresh`]
+    history: Option<GitHistory>,
+}
+
+impl Analysis {
+    /// Start configuring an analysis
+    pub fn builder() -> AnalysisBuilder {
+        AnalysisBuilder::default()
+    }
+
+    /// Analyzed path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Thresholds and rule selection the report is generated with
+    pub fn thresholds(&self) -> &IssueThresholds {
+        &self.thresholds
+    }
+
+    /// Files and options the analysis covers
+    pub fn scope(&self) -> &AnalysisScope {
+        &self.scope
+    }
+
+    /// Change the scope after building, e.g. to add macro-expanded sources
+    /// that were produced for this scope
+    pub fn scope_mut(&mut self) -> &mut AnalysisScope {
+        &mut self.scope
+    }
+
+    /// Run the analysis and summarize it
+    pub fn run(&mut self) -> Result<CouplingReport, AnalysisError> {
+        let metrics = self.metrics()?;
+        Ok(CouplingReport::from_metrics(&metrics, &self.thresholds))
+    }
+
+    /// Run the analysis and return the raw metrics
+    ///
+    /// Git failures other than an unknown revision (no repository, git not
+    /// installed) leave the volatility at its static default, as on the
+    /// command line.
+    pub fn metrics(&mut self) -> Result<ProjectMetrics, AnalysisError> {
+        let metrics = self.static_metrics()?;
+        self.history = None;
+        if let Some(window) = &self.git {
+            self.scope
+                .progress
+                .emit(ProgressEvent::Phase(AnalysisPhase::GitHistory));
+            tracing::debug!(window = %window, "analyzing git history");
+            let mut volatility = VolatilityAnalyzer::with_window(window.clone());
+            match volatility.analyze(&self.path) {
+                Ok(()) => {
+                    let stats = volatility.statistics();
+                    tracing::debug!(
+                        backend = volatility.backend.unwrap_or("git"),
+                        files = stats.total_files,
+                        changes = stats.total_changes,
+                        "git analysis complete"
+                    );
+                    // Co-change pairs are derived from file_changes, so
+                    // compute them first
+                    self.history = Some(GitHistory {
+                        co_changes: volatility.co_change_pairs(MIN_CO_CHANGES, MIN_CO_CHANGE_RATIO),
+                        file_changes: volatility.file_changes,
+                        file_authors: volatility.file_authors,
+                    });
+                }
+                // An explicitly requested tag that does not exist is a usage error
+                Err(e @ VolatilityError::UnknownRevision(_)) => return Err(e.into()),
+                Err(e) => tracing::warn!(error = %e, "git analysis failed"),
+            }
+        }
+        Ok(self.finish(metrics))
+    }
+
+    /// Run the static analysis again, reusing the git history of the last
+    /// [`metrics`](Self::metrics) run
+    ///
+    /// This is what the language server does after each save: parsing is
+    /// fast enough to repeat, reading the history is not.
+    pub fn refresh(&mut self) -> Result<ProjectMetrics, AnalysisError> {
+        let metrics = self.static_metrics()?;
+        Ok(self.finish(metrics))
+    }
+
+    /// Parse the files and apply custom temporal rules
+    fn static_metrics(&self) -> Result<ProjectMetrics, AnalysisError> {
+        let mut metrics = match &self.pool {
+            Some(pool) => pool.install(|| analyze_workspace_scoped(&self.path, &self.scope))?,
+            None => analyze_workspace_scoped(&self.path, &self.scope)?,
+        };
+        if self.config.temporal.customizes_analysis() {
+            self.scope
+                .progress
+                .emit(ProgressEvent::Phase(AnalysisPhase::Temporal));
+            let rules = self.config.temporal.rules();
+            let count = reanalyze_project(&mut metrics, &rules, self.scope.include_tests);
+            tracing::debug!(modules = count, "applied custom temporal rules");
+        }
+        Ok(metrics)
+    }
+
+    /// Add the git history and the config's volatility overrides
+    fn finish(&mut self, mut metrics: ProjectMetrics) -> ProjectMetrics {
+        if let Some(history) = &self.history {
+            history.apply(&mut metrics);
+        }
+        let overrides = self.config.apply_volatility_overrides(&mut metrics);
+        if overrides > 0 {
+            tracing::debug!(overrides, "applied volatility overrides");
+        }
+        self.scope.progress.emit(ProgressEvent::Finished);
+        metrics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::balance::IssueType;
+
+    #[test]
+    fn test_builder_options() {
+        let analysis = Analysis::builder()
+            .path("src")
+            .skip("git")
+            .exclude("src/web/**")
+            .git_window(GitWindow::LastCommits(50))
+            .threads(1)
+            .build()
+            .unwrap();
+        assert_eq!(analysis.git, Some(GitWindow::LastCommits(50)));
+        assert!(
+            !analysis
+                .thresholds()
+                .rules
+                .allows(IssueType::HiddenCoupling)
+        );
+        assert_eq!(analysis.scope.paths.exclude.len(), 1);
+        assert!(Analysis::builder().no_git().build().unwrap().git.is_none());
+
+        assert!(matches!(
+            Analysis::builder().skip("temporal::nope").build(),
+            Err(AnalysisError::UnknownRule(_))
+        ));
+        assert!(matches!(
+            Analysis::builder().exclude("[").build(),
+            Err(AnalysisError::Pattern(_))
+        ));
+    }
+
+    #[test]
+    fn test_run_on_own_sources() {
+        let report = Analysis::builder()
+            .path(env!("CARGO_MANIFEST_DIR"))
+            .no_git()
+            .threads(2)
+            .build()
+            .unwrap()
+            .run()
+            .unwrap();
+        assert!(report.workspace.modules > 10);
+        assert_eq!(report.volatility.total_files, 0);
+    }
+
+    #[test]
+    fn test_refresh_repeats_the_static_analysis() {
+        let mut analysis = Analysis::builder()
+            .path(env!("CARGO_MANIFEST_DIR"))
+            .no_git()
+            .build()
+            .unwrap();
+        let first = analysis.metrics().unwrap();
+        let refreshed = analysis.refresh().unwrap();
+        assert_eq!(refreshed.module_count(), first.module_count());
+        assert!(refreshed.file_changes.is_empty());
+    }
+}
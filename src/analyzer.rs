@@ -5,24 +5,47 @@
 //! Supports parallel processing via Rayon for large projects.
 
 use std::collections::{HashMap, HashSet};
-use std::ffi::OsStr;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
+use proc_macro2::Span;
 use rayon::prelude::*;
+use syn::spanned::Spanned;
 use syn::visit::Visit;
 use syn::{
     Expr, ExprCall, ExprField, ExprMethodCall, ExprStruct, File, FnArg, ItemFn, ItemImpl, ItemMod,
-    ItemStruct, ItemTrait, ItemUse, ReturnType, Signature, Type, UseTree,
+    ItemStruct, ItemTrait, ItemUse, ReturnType, Signature, TraitBound, TraitBoundModifier, Type,
+    UseTree,
 };
 use thiserror::Error;
-use walkdir::WalkDir;
 
+use crate::analysis::{Analysis, AnalysisError};
+use crate::build_coupling::classify_dependencies;
+use crate::connascence::ConnascenceAnalyzer;
+use crate::doc_coverage::DocAnalyzer;
+use crate::edition::{Edition, parse_file};
+use crate::error_surface::ErrorAnalyzer;
+use crate::exhaustive_match::MatchAnalyzer;
+use crate::expand::ExpandedSources;
+use crate::ffi::FfiUsage;
+use crate::functions::FunctionAnalyzer;
 use crate::metrics::{
-    CouplingMetrics, Distance, IntegrationStrength, ModuleMetrics, ProjectMetrics, Visibility,
-    Volatility,
+    CouplingMetrics, Distance, ExposedType, IntegrationStrength, ModuleMetrics, ProjectMetrics,
+    ReExport, SkippedFile, TestCodeMetrics, TraitUse, Visibility, Volatility,
 };
-use crate::workspace::{WorkspaceError, WorkspaceInfo, resolve_crate_from_path};
+use crate::mode_switch::ModeAnalyzer;
+use crate::module_graph::module_name_from_file;
+use crate::paths::{PathFilter, rs_files};
+use crate::platform::PlatformUsage;
+use crate::progress::{AnalysisPhase, ProgressEvent, ProgressHook};
+use crate::rustdoc::RustdocIndex;
+use crate::stability::compute_crate_stability;
+use crate::stamp::StampAnalyzer;
+use crate::struct_literal::LiteralAnalyzer;
+use crate::suppress::parse_directives;
+use crate::temporal::TemporalAnalyzer;
+use crate::workspace::{FeatureSelection, WorkspaceError, WorkspaceInfo, resolve_crate_from_path};
 
 /// Convert syn's Visibility to our Visibility enum
 fn convert_visibility(vis: &syn::Visibility) -> Visibility {
@@ -49,6 +72,12 @@ fn convert_visibility(vis: &syn::Visibility) -> Visibility {
     }
 }
 
+/// Get the 1-based line and column where a span starts
+pub fn span_position(span: Span) -> (usize, usize) {
+    let start = span.start();
+    (start.line, start.column + 1)
+}
+
 /// Errors that can occur during analysis
 #[derive(Error, Debug)]
 pub enum AnalyzerError {
@@ -74,6 +103,8 @@ pub struct Dependency {
     pub kind: DependencyKind,
     /// Line number where the dependency is declared
     pub line: usize,
+    /// Column number where the dependency is declared (1-based)
+    pub column: usize,
     /// Usage context for more accurate strength determination
     pub usage: UsageContext,
 }
@@ -184,6 +215,12 @@ pub struct CouplingAnalyzer {
     current_item: Option<(String, ItemKind)>,
     /// Item-level dependencies (detailed tracking)
     pub item_dependencies: Vec<ItemDependency>,
+    /// Nesting depth of inline `mod name { ... }` blocks
+    inline_mod_depth: usize,
+    /// Analyze `#[cfg(test)]` modules and `#[test]` functions like production code
+    include_tests: bool,
+    /// Edition the source is written in
+    edition: Edition,
 }
 
 /// Statistics about usage patterns
@@ -212,6 +249,8 @@ pub struct ItemDependency {
     pub dep_type: ItemDepType,
     /// Line number in source
     pub line: usize,
+    /// Column number in source (1-based)
+    pub column: usize,
     /// The actual expression/code (e.g., "config.thresholds" or "self.couplings")
     pub expression: Option<String>,
 }
@@ -266,31 +305,90 @@ impl CouplingAnalyzer {
             type_visibility: HashMap::new(),
             current_item: None,
             item_dependencies: Vec::new(),
+            inline_mod_depth: 0,
+            include_tests: false,
+            edition: Edition::default(),
         }
     }
 
+    /// Parse the source as written for `edition` (default: the latest)
+    pub fn with_edition(mut self, edition: Edition) -> Self {
+        self.edition = edition;
+        self.metrics.edition = edition;
+        self
+    }
+
+    /// Analyze test code like production code instead of skipping it
+    pub fn with_tests(mut self, include_tests: bool) -> Self {
+        self.include_tests = include_tests;
+        self
+    }
+
     /// Analyze a Rust source file
+    ///
+    /// Unless test code is included, `#[cfg(test)]` modules and `#[test]`
+    /// functions are removed first and only counted in `metrics.tests`.
     pub fn analyze_file(&mut self, content: &str) -> Result<(), AnalyzerError> {
-        let syntax: File =
-            syn::parse_file(content).map_err(|e| AnalyzerError::ParseError(e.to_string()))?;
-
+        let mut syntax: File = parse_file(content, self.edition).map_err(|e| {
+            let start = e.span().start();
+            AnalyzerError::ParseError(format!(
+                "{} (line {}, column {})",
+                e,
+                start.line,
+                start.column + 1
+            ))
+        })?;
+
+        if !self.include_tests {
+            self.metrics.tests = strip_test_code(&mut syntax, content);
+        }
         self.visit_file(&syntax);
+        self.metrics.temporal = TemporalAnalyzer::analyze(&syntax);
+        self.metrics.connascence = ConnascenceAnalyzer::analyze(&syntax);
+        self.metrics.param_uses = StampAnalyzer::analyze(&syntax);
+        self.metrics.enum_matches = MatchAnalyzer::analyze(&syntax);
+        self.metrics.struct_literals = LiteralAnalyzer::analyze(&syntax);
+        self.metrics.function_stats = FunctionAnalyzer::analyze(&syntax);
+        self.metrics.doc_coverage = DocAnalyzer::analyze(&syntax);
+        self.metrics.error_surface = ErrorAnalyzer::analyze(&syntax);
+        self.metrics.mode_switches = ModeAnalyzer::analyze(&syntax);
+        self.metrics.platforms = PlatformUsage::analyze(&syntax);
+        self.metrics.ffi = FfiUsage::analyze(&syntax);
+        self.metrics.lines_of_code =
+            count_code_lines(content).saturating_sub(self.metrics.tests.lines_of_code);
+        self.metrics.suppressions = parse_directives(content);
 
         Ok(())
     }
 
-    /// Add a dependency with deduplication
-    fn add_dependency(&mut self, path: String, kind: DependencyKind, usage: UsageContext) {
+    /// Add a dependency with deduplication (the first occurrence's location is kept)
+    ///
+    /// Names brought in by a `use` are recorded by the path they were
+    /// imported from (`User` -> `corelib::model::User`).
+    fn add_dependency(
+        &mut self,
+        path: String,
+        kind: DependencyKind,
+        usage: UsageContext,
+        span: Span,
+    ) {
+        let path = if usage == UsageContext::Import {
+            path
+        } else {
+            self.resolve_imported(&path)
+        };
         let key = (path.clone(), usage);
         if self.seen_dependencies.contains(&key) {
             return;
         }
         self.seen_dependencies.insert(key);
 
+        let (line, column) = span_position(span);
         self.dependencies.push(Dependency {
             path,
             kind,
-            line: 0,
+            line,
+            column,
             usage,
         });
     }
@@ -300,9 +398,10 @@ impl CouplingAnalyzer {
         &mut self,
         target: String,
         dep_type: ItemDepType,
-        line: usize,
+        span: Span,
         expression: Option<String>,
     ) {
+        let (line, column) = span_position(span);
         if let Some((ref source_item, source_kind)) = self.current_item {
             // Determine target module
             let target_module = self.imported_types.get(&target).cloned().or_else(|| {
@@ -322,13 +421,18 @@ impl CouplingAnalyzer {
                 target_module,
                 dep_type,
                 line,
+                column,
                 expression,
             });
         }
     }
 
     /// Extract full path from UseTree recursively
-    fn extract_use_paths(&self, tree: &UseTree, prefix: &str) -> Vec<(String, DependencyKind)> {
+    fn extract_use_paths(
+        &self,
+        tree: &UseTree,
+        prefix: &str,
+    ) -> Vec<(String, DependencyKind, Span)> {
         let mut paths = Vec::new();
 
         match tree {
@@ -351,7 +455,7 @@ impl CouplingAnalyzer {
                 } else {
                     DependencyKind::ExternalUse
                 };
-                paths.push((full_path, kind));
+                paths.push((full_path, kind, name.ident.span()));
             }
             UseTree::Rename(rename) => {
                 let full_path = if prefix.is_empty() {
@@ -364,16 +468,16 @@ impl CouplingAnalyzer {
                 } else {
                     DependencyKind::ExternalUse
                 };
-                paths.push((full_path, kind));
+                paths.push((full_path, kind, rename.ident.span()));
             }
-            UseTree::Glob(_) => {
+            UseTree::Glob(glob) => {
                 let full_path = format!("{}::*", prefix);
                 let kind = if prefix.starts_with("crate") || prefix.starts_with("super") {
                     DependencyKind::InternalUse
                 } else {
                     DependencyKind::ExternalUse
                 };
-                paths.push((full_path, kind));
+                paths.push((full_path, kind, glob.star_token.span));
             }
             UseTree::Group(group) => {
                 for item in &group.items {
@@ -385,6 +489,85 @@ impl CouplingAnalyzer {
         paths
     }
 
+    /// Replace an imported first segment with the path it was imported from
+    fn resolve_imported(&self, path: &str) -> String {
+        let (first, rest) = path.split_once("::").unwrap_or((path, ""));
+        match self.imported_types.get(first) {
+            Some(full) if rest.is_empty() => full.clone(),
+            Some(full) => format!("{}::{}", full, rest),
+            None => path.to_string(),
+        }
+    }
+
+    /// Record the types `ty` names as exposed by the public `item`
+    fn expose_type(&mut self, item: &str, ty: &Type) {
+        for (path, line) in type_paths(ty) {
+            let exposed = ExposedType {
+                item: item.to_string(),
+                type_path: self.resolve_imported(&path),
+                line,
+            };
+            if !self.metrics.exposed_types.contains(&exposed) {
+                self.metrics.exposed_types.push(exposed);
+            }
+        }
+    }
+
+    /// Record a qualified macro, attribute or derive path; single names are
+    /// resolved through the imports, which are recorded already
+    fn record_macro_path(&mut self, path: &syn::Path) {
+        if path.segments.len() < 2 {
+            return;
+        }
+        let joined = path
+            .segments
+            .iter()
+            .map(|s| s.ident.to_string())
+            .collect::<Vec<_>>()
+            .join("::");
+        if !self.metrics.macro_paths.contains(&joined) {
+            self.metrics.macro_paths.push(joined);
+        }
+    }
+
+    /// Record the parameter and return types of a public function
+    fn expose_signature(&mut self, item: &str, sig: &Signature) {
+        self.metrics.signature_complexity += signature_complexity(sig);
+        if leaks_lifetimes(sig) {
+            self.record_lifetime_leak(item);
+        }
+        for arg in &sig.inputs {
+            if let FnArg::Typed(pat_type) = arg {
+                self.expose_type(item, &pat_type.ty);
+            }
+        }
+        if let ReturnType::Type(_, ty) = &sig.output {
+            self.expose_type(item, ty);
+        }
+    }
+
+    /// Record a public item whose signature ties callers to borrows
+    fn record_lifetime_leak(&mut self, item: &str) {
+        if !self.metrics.lifetime_leaks.iter().any(|i| i == item) {
+            self.metrics.lifetime_leaks.push(item.to_string());
+        }
+    }
+
+    /// Record the field types of a public struct or enum variant; only
+    /// public fields count for structs
+    fn expose_fields(&mut self, item: &str, fields: &syn::Fields, all: bool) {
+        for (index, field) in fields.iter().enumerate() {
+            if !all && !matches!(field.vis, syn::Visibility::Public(_)) {
+                continue;
+            }
+            let name = match &field.ident {
+                Some(ident) => format!("{}.{}", item, ident),
+                None => format!("{}.{}", item, index),
+            };
+            self.expose_type(&name, &field.ty);
+        }
+    }
+
     /// Extract type name from a Type
     fn extract_type_name(&self, ty: &Type) -> Option<String> {
         match ty {
@@ -419,6 +602,7 @@ impl CouplingAnalyzer {
                     type_name,
                     DependencyKind::TypeRef,
                     UsageContext::FunctionParameter,
+                    pat_type.ty.span(),
                 );
             }
         }
@@ -428,7 +612,12 @@ impl CouplingAnalyzer {
             && let Some(type_name) = self.extract_type_name(ty)
             && !self.is_primitive_type(&type_name)
         {
-            self.add_dependency(type_name, DependencyKind::TypeRef, UsageContext::ReturnType);
+            self.add_dependency(
+                type_name,
+                DependencyKind::TypeRef,
+                UsageContext::ReturnType,
+                ty.span(),
+            );
         }
     }
 
@@ -488,9 +677,16 @@ impl CouplingAnalyzer {
 
 impl<'ast> Visit<'ast> for CouplingAnalyzer {
     fn visit_item_use(&mut self, node: &'ast ItemUse) {
+        if matches!(node.vis, syn::Visibility::Public(_)) && self.inline_mod_depth == 0 {
+            collect_reexports(&node.tree, "", &mut self.metrics.reexports);
+        }
         let paths = self.extract_use_paths(&node.tree, "");
 
-        for (path, kind) in paths {
+        for (path, kind, span) in paths {
+            if let Some(file_path) = file_relative_use_path(&path, self.inline_mod_depth) {
+                self.metrics.use_paths.push(file_path);
+            }
+
             // Skip self references
             if path == "self" || path.starts_with("self::") {
                 continue;
@@ -502,7 +698,7 @@ impl<'ast> Visit<'ast> for CouplingAnalyzer {
                     .insert(type_name.to_string(), path.clone());
             }
 
-            self.add_dependency(path.clone(), kind, UsageContext::Import);
+            self.add_dependency(path.clone(), kind, UsageContext::Import, span);
 
             // Update metrics
             if kind == DependencyKind::InternalUse {
@@ -534,10 +730,27 @@ impl<'ast> Visit<'ast> for CouplingAnalyzer {
                 .collect::<Vec<_>>()
                 .join("::");
 
+            self.metrics.trait_impls.push(TraitUse {
+                trait_path: self.resolve_imported(&trait_name),
+                item: self
+                    .extract_type_name(&node.self_ty)
+                    .unwrap_or_else(|| "?".to_string()),
+                line: trait_path.span().start().line,
+                methods: node
+                    .items
+                    .iter()
+                    .filter_map(|item| match item {
+                        syn::ImplItem::Fn(method) => Some(method.sig.ident.to_string()),
+                        _ => None,
+                    })
+                    .collect(),
+            });
+
             self.add_dependency(
                 trait_name,
                 DependencyKind::TraitImpl,
                 UsageContext::TraitBound,
+                trait_path.span(),
             );
             self.usage_counts.trait_bounds += 1;
         } else {
@@ -545,14 +758,23 @@ impl<'ast> Visit<'ast> for CouplingAnalyzer {
             self.metrics.inherent_impl_count += 1;
 
             // Get the type being implemented
-            if let Some(type_name) = self.extract_type_name(&node.self_ty)
-                && !self.defined_types.contains(&type_name)
-            {
-                self.add_dependency(
-                    type_name,
-                    DependencyKind::InherentImpl,
-                    UsageContext::InherentImplBlock,
-                );
+            if let Some(type_name) = self.extract_type_name(&node.self_ty) {
+                for impl_item in &node.items {
+                    if let syn::ImplItem::Fn(method) = impl_item
+                        && matches!(method.vis, syn::Visibility::Public(_))
+                    {
+                        let item = format!("{}::{}", type_name, method.sig.ident);
+                        self.expose_signature(&item, &method.sig);
+                    }
+                }
+                if !self.defined_types.contains(&type_name) {
+                    self.add_dependency(
+                        type_name,
+                        DependencyKind::InherentImpl,
+                        UsageContext::InherentImplBlock,
+                        node.self_ty.span(),
+                    );
+                }
             }
         }
         syn::visit::visit_item_impl(self, node);
@@ -589,6 +811,13 @@ impl<'ast> Visit<'ast> for CouplingAnalyzer {
             primitive_param_count,
             param_types,
         );
+        if let Some(def) = self.metrics.function_definitions.get_mut(&fn_name) {
+            (def.line, def.column) = span_position(node.sig.ident.span());
+        }
+
+        if visibility == Visibility::Public {
+            self.expose_signature(&fn_name, &node.sig);
+        }
 
         // Set current item context for dependency tracking
         let previous_item = self.current_item.take();
@@ -615,7 +844,8 @@ impl<'ast> Visit<'ast> for CouplingAnalyzer {
                 let inner = fields
                     .unnamed
                     .first()
-                    .and_then(|f| self.extract_type_name(&f.ty));
+                    .and_then(|f| self.extract_type_name(&f.ty))
+                    .map(|inner| self.resolve_imported(&inner));
                 (true, inner)
             }
             _ => (false, None),
@@ -669,7 +899,7 @@ impl<'ast> Visit<'ast> for CouplingAnalyzer {
 
         // Register in module metrics with full details
         self.metrics.add_type_definition_full(
-            name,
+            name.clone(),
             visibility,
             false, // is_trait
             is_newtype,
@@ -678,6 +908,19 @@ impl<'ast> Visit<'ast> for CouplingAnalyzer {
             public_field_count,
             total_field_count,
         );
+        if let Some(def) = self.metrics.type_definitions.get_mut(&name) {
+            (def.line, def.column) = span_position(node.ident.span());
+            def.non_exhaustive = node
+                .attrs
+                .iter()
+                .any(|attr| attr.path().is_ident("non_exhaustive"));
+        }
+        if visibility == Visibility::Public {
+            self.expose_fields(&name, &node.fields, false);
+            if node.generics.lifetimes().count() >= 2 {
+                self.record_lifetime_leak(&name);
+            }
+        }
 
         // Analyze struct fields for type dependencies
         match &node.fields {
@@ -691,6 +934,7 @@ impl<'ast> Visit<'ast> for CouplingAnalyzer {
                             type_name,
                             DependencyKind::TypeRef,
                             UsageContext::TypeParameter,
+                            field.ty.span(),
                         );
                         self.usage_counts.type_parameters += 1;
                     }
@@ -705,6 +949,7 @@ impl<'ast> Visit<'ast> for CouplingAnalyzer {
                             type_name,
                             DependencyKind::TypeRef,
                             UsageContext::TypeParameter,
+                            field.ty.span(),
                         );
                     }
                 }
@@ -722,10 +967,27 @@ impl<'ast> Visit<'ast> for CouplingAnalyzer {
         self.type_visibility.insert(name.clone(), visibility);
 
         // Register in module metrics with visibility
-        self.metrics.add_type_definition(name, visibility, false);
+        self.metrics
+            .add_type_definition(name.clone(), visibility, false);
+        if let Some(def) = self.metrics.type_definitions.get_mut(&name) {
+            (def.line, def.column) = span_position(node.ident.span());
+            def.variants = node.variants.len();
+            def.non_exhaustive = node
+                .attrs
+                .iter()
+                .any(|attr| attr.path().is_ident("non_exhaustive"));
+        }
+
+        if visibility == Visibility::Public && node.generics.lifetimes().count() >= 2 {
+            self.record_lifetime_leak(&name);
+        }
 
         // Analyze enum variants for type dependencies
         for variant in &node.variants {
+            if visibility == Visibility::Public {
+                let item = format!("{}::{}", name, variant.ident);
+                self.expose_fields(&item, &variant.fields, true);
+            }
             match &variant.fields {
                 syn::Fields::Named(fields) => {
                     for field in &fields.named {
@@ -736,6 +998,7 @@ impl<'ast> Visit<'ast> for CouplingAnalyzer {
                                 type_name,
                                 DependencyKind::TypeRef,
                                 UsageContext::TypeParameter,
+                                field.ty.span(),
                             );
                         }
                     }
@@ -749,6 +1012,7 @@ impl<'ast> Visit<'ast> for CouplingAnalyzer {
                                 type_name,
                                 DependencyKind::TypeRef,
                                 UsageContext::TypeParameter,
+                                field.ty.span(),
                             );
                         }
                     }
@@ -767,17 +1031,103 @@ impl<'ast> Visit<'ast> for CouplingAnalyzer {
         self.type_visibility.insert(name.clone(), visibility);
 
         // Register in module metrics with visibility (is_trait = true)
-        self.metrics.add_type_definition(name, visibility, true);
+        self.metrics
+            .add_type_definition(name.clone(), visibility, true);
+        if let Some(def) = self.metrics.type_definitions.get_mut(&name) {
+            (def.line, def.column) = span_position(node.ident.span());
+            for trait_item in &node.items {
+                if let syn::TraitItem::Fn(method) = trait_item {
+                    if method.default.is_some() {
+                        def.default_methods += 1;
+                        def.default_method_names.push(method.sig.ident.to_string());
+                    } else {
+                        def.required_methods += 1;
+                    }
+                }
+            }
+        }
+        if visibility == Visibility::Public {
+            if node.generics.lifetimes().count() >= 2 {
+                self.record_lifetime_leak(&name);
+            }
+            for trait_item in &node.items {
+                if let syn::TraitItem::Fn(method) = trait_item {
+                    let item = format!("{}::{}", name, method.sig.ident);
+                    self.expose_signature(&item, &method.sig);
+                }
+            }
+        }
 
         self.metrics.trait_impl_count += 1;
         syn::visit::visit_item_trait(self, node);
     }
 
+    fn visit_trait_bound(&mut self, node: &'ast TraitBound) {
+        if !matches!(node.modifier, TraitBoundModifier::Maybe(_)) {
+            let trait_name = node
+                .path
+                .segments
+                .iter()
+                .map(|s| s.ident.to_string())
+                .collect::<Vec<_>>()
+                .join("::");
+            let item = match &self.current_item {
+                Some((name, _)) => name.clone(),
+                None => self.current_module.clone(),
+            };
+            self.metrics.trait_bounds.push(TraitUse {
+                trait_path: self.resolve_imported(&trait_name),
+                item,
+                line: node.path.span().start().line,
+                methods: Vec::new(),
+            });
+        }
+        syn::visit::visit_trait_bound(self, node);
+    }
+
+    fn visit_item_type(&mut self, node: &'ast syn::ItemType) {
+        if contains_trait_object(&node.ty) {
+            self.metrics.trait_object_aliases += 1;
+        }
+        if matches!(node.vis, syn::Visibility::Public(_)) {
+            self.expose_type(&node.ident.to_string(), &node.ty);
+        }
+        syn::visit::visit_item_type(self, node);
+    }
+
+    fn visit_macro(&mut self, node: &'ast syn::Macro) {
+        self.record_macro_path(&node.path);
+        syn::visit::visit_macro(self, node);
+    }
+
+    fn visit_attribute(&mut self, node: &'ast syn::Attribute) {
+        if node.path().is_ident("derive") {
+            if let Ok(derives) = node.parse_args_with(
+                syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated,
+            ) {
+                for derive in &derives {
+                    self.record_macro_path(derive);
+                }
+            }
+        } else {
+            self.record_macro_path(node.path());
+        }
+        syn::visit::visit_attribute(self, node);
+    }
+
     fn visit_item_mod(&mut self, node: &'ast ItemMod) {
+        if matches!(node.vis, syn::Visibility::Public(_)) && self.inline_mod_depth == 0 {
+            self.metrics.public_modules.push(node.ident.to_string());
+        }
         if node.content.is_some() {
             self.metrics.internal_deps.push(node.ident.to_string());
+            self.inline_mod_depth += 1;
+            syn::visit::visit_item_mod(self, node);
+            self.inline_mod_depth -= 1;
+        } else {
+            self.metrics.declared_modules.push(node.ident.to_string());
+            syn::visit::visit_item_mod(self, node);
         }
-        syn::visit::visit_item_mod(self, node);
     }
 
     // Detect field access: `foo.bar`
@@ -809,6 +1159,7 @@ impl<'ast> Visit<'ast> for CouplingAnalyzer {
                     full_path.clone(),
                     DependencyKind::TypeRef,
                     UsageContext::FieldAccess,
+                    node.span(),
                 );
                 self.usage_counts.field_accesses += 1;
             }
@@ -818,7 +1169,7 @@ impl<'ast> Visit<'ast> for CouplingAnalyzer {
             self.add_item_dependency(
                 format!("{}.{}", full_path, field_name),
                 ItemDepType::FieldAccess,
-                0,
+                node.span(),
                 Some(expr),
             );
         }
@@ -850,6 +1201,7 @@ impl<'ast> Visit<'ast> for CouplingAnalyzer {
                     full_path.clone(),
                     DependencyKind::TypeRef,
                     UsageContext::MethodCall,
+                    node.method.span(),
                 );
                 self.usage_counts.method_calls += 1;
             }
@@ -859,7 +1211,7 @@ impl<'ast> Visit<'ast> for CouplingAnalyzer {
             self.add_item_dependency(
                 format!("{}::{}", full_path, method_name),
                 ItemDepType::MethodCall,
-                0, // TODO: get line number from span
+                node.method.span(),
                 Some(expr),
             );
         }
@@ -891,6 +1243,7 @@ impl<'ast> Visit<'ast> for CouplingAnalyzer {
                         full_path.clone(),
                         DependencyKind::TypeRef,
                         UsageContext::FunctionCall,
+                        node.func.span(),
                     );
                     self.usage_counts.function_calls += 1;
                 }
@@ -899,7 +1252,7 @@ impl<'ast> Visit<'ast> for CouplingAnalyzer {
                 self.add_item_dependency(
                     full_path,
                     ItemDepType::FunctionCall,
-                    0,
+                    node.func.span(),
                     Some(format!("{}()", path_str)),
                 );
             } else {
@@ -907,7 +1260,7 @@ impl<'ast> Visit<'ast> for CouplingAnalyzer {
                 self.add_item_dependency(
                     path_str.clone(),
                     ItemDepType::FunctionCall,
-                    0,
+                    node.func.span(),
                     Some(format!("{}()", path_str)),
                 );
             }
@@ -942,6 +1295,7 @@ impl<'ast> Visit<'ast> for CouplingAnalyzer {
                 full_path,
                 DependencyKind::TypeRef,
                 UsageContext::StructConstruction,
+                node.path.span(),
             );
             self.usage_counts.struct_constructions += 1;
         }
@@ -963,29 +1317,47 @@ struct AnalyzedFile {
     item_dependencies: Vec<ItemDependency>,
 }
 
-/// Analyze an entire project (parallel version)
-pub fn analyze_project(path: &Path) -> Result<ProjectMetrics, AnalyzerError> {
-    analyze_project_parallel(path)
+/// Which files of a project to analyze
+#[derive(Debug, Clone, Default)]
+pub struct AnalysisScope {
+    /// Workspace members to analyze (names or globs like `cargo -p`; the
+    /// default members when empty)
+    pub packages: Vec<String>,
+    /// Analyze every workspace member when no packages are given, not just
+    /// the default members (`--workspace`)
+    pub workspace: bool,
+    /// Features dependencies are resolved with
+    pub features: FeatureSelection,
+    /// Include/exclude globs for source files
+    pub paths: PathFilter,
+    /// Analyze test code (`tests/` files, `#[cfg(test)]` modules, `#[test]`
+    /// functions) instead of reporting it separately
+    pub include_tests: bool,
+    /// Macro-expanded sources analyzed instead of the files on disk (`--expand`)
+    pub expanded: ExpandedSources,
+    /// Where re-exported items are defined (`--rustdoc`)
+    pub rustdoc: RustdocIndex,
+    /// Receives phase changes and per-file progress
+    pub progress: ProgressHook,
 }
 
-/// Get an iterator over all non-hidden rust files in `dir`
-fn rs_files(dir: &Path) -> impl Iterator<Item = PathBuf> {
-    WalkDir::new(dir)
-        .follow_links(true)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(move |entry| {
-            let file_path = entry.path();
-            // strip parent dir to avoid false positives for `target` and hidden checks
-            let file_path = file_path.strip_prefix(dir).unwrap_or(file_path);
-
-            // Skip target directory and hidden directories
-            !file_path.components().any(|c| {
-                let s = c.as_os_str().to_string_lossy();
-                s == "target" || s.starts_with('.')
-            }) && file_path.extension() == Some(OsStr::new("rs"))
-        })
-        .map(|e| e.path().to_path_buf())
+impl AnalysisScope {
+    /// Workspace members this scope covers
+    pub fn select_members(&self, workspace: &WorkspaceInfo) -> Result<Vec<String>, WorkspaceError> {
+        if self.workspace && self.packages.is_empty() {
+            return Ok(workspace.members.clone());
+        }
+        workspace.select_members(&self.packages)
+    }
+}
+
+/// Analyze an entire project
+///
+/// Static analysis only, with default options: [`Analysis::builder`] with
+/// `no_git`, which also takes config files, git volatility, path filters and
+/// rule selection.
+pub fn analyze_project(path: &Path) -> Result<ProjectMetrics, AnalysisError> {
+    Analysis::builder().path(path).no_git().build()?.metrics()
 }
 
 /// Analyze a project using parallel processing with Rayon
@@ -993,12 +1365,76 @@ fn rs_files(dir: &Path) -> impl Iterator<Item = PathBuf> {
 /// Automatically scales to available CPU cores. The parallel processing
 /// uses work-stealing for optimal load balancing across cores.
 pub fn analyze_project_parallel(path: &Path) -> Result<ProjectMetrics, AnalyzerError> {
+    analyze_project_filtered(path, &AnalysisScope::default())
+}
+
+/// Whether the scope's path filter keeps a file, logging the files it drops
+fn path_allowed(scope: &AnalysisScope, file: &Path, root: &Path) -> bool {
+    let allowed = scope.paths.allows(file, root);
+    if !allowed {
+        tracing::debug!(file = %file.display(), "skipped file excluded by path filter");
+    }
+    allowed
+}
+
+/// Record a file that failed to analyze
+fn skipped_file(path: &Path, error: AnalyzerError) -> SkippedFile {
+    tracing::warn!(file = %path.display(), error = %error, "skipped file");
+    SkippedFile {
+        path: path.to_path_buf(),
+        error: error.to_string(),
+    }
+}
+
+/// Separate analyzed files from skipped ones, sorting the skipped by path
+fn split_skipped<T>(results: Vec<Result<T, SkippedFile>>) -> (Vec<T>, Vec<SkippedFile>) {
+    let mut analyzed = Vec::with_capacity(results.len());
+    let mut skipped = Vec::new();
+    for result in results {
+        match result {
+            Ok(file) => analyzed.push(file),
+            Err(file) => skipped.push(file),
+        }
+    }
+    skipped.sort_by(|a, b| a.path.cmp(&b.path));
+    (analyzed, skipped)
+}
+
+/// Count a parsed file and report it to the scope's progress hook
+fn report_file_done(scope: &AnalysisScope, done: &AtomicUsize, total: usize) {
+    let done = done.fetch_add(1, Ordering::Relaxed) + 1;
+    scope
+        .progress
+        .emit(ProgressEvent::FileAnalyzed { done, total });
+}
+
+/// Analyze a project, skipping files outside `scope`
+pub fn analyze_project_filtered(
+    path: &Path,
+    scope: &AnalysisScope,
+) -> Result<ProjectMetrics, AnalyzerError> {
+    let _span = tracing::debug_span!("analyze_project", path = %path.display()).entered();
     if !path.exists() {
         return Err(AnalyzerError::InvalidPath(path.display().to_string()));
     }
 
     // Collect all .rs file paths first (sequential, but fast)
-    let file_paths: Vec<PathBuf> = rs_files(path).collect();
+    scope
+        .progress
+        .emit(ProgressEvent::Phase(AnalysisPhase::Discovering));
+    let (file_paths, test_files) = partition_test_files(
+        rs_files(path).filter(|file| path_allowed(scope, file, path)),
+        path,
+        scope.include_tests,
+    );
+    scope
+        .progress
+        .emit(ProgressEvent::FilesDiscovered(file_paths.len()));
+    scope
+        .progress
+        .emit(ProgressEvent::Phase(AnalysisPhase::Parsing));
+    let done = AtomicUsize::new(0);
+    let parse_span = tracing::debug_span!("parse_files").entered();
 
     // Calculate optimal chunk size based on file count and available parallelism
     // Smaller chunks = better load balancing, but more overhead
@@ -1022,24 +1458,35 @@ pub fn analyze_project_parallel(path: &Path) -> Result<ProjectMetrics, AnalyzerE
         .flat_map(|chunk| {
             chunk
                 .iter()
-                .filter_map(|file_path| match analyze_rust_file_full(file_path) {
-                    Ok(result) => Some(AnalyzedFile {
-                        module_name: result.metrics.name.clone(),
-                        file_path: file_path.clone(),
-                        metrics: result.metrics,
-                        dependencies: result.dependencies,
-                        type_visibility: result.type_visibility,
-                        item_dependencies: result.item_dependencies,
-                    }),
-                    Err(e) => {
-                        eprintln!("Warning: Failed to analyze {}: {}", file_path.display(), e);
-                        None
+                .map(|file_path| {
+                    let _span =
+                        tracing::trace_span!("analyze_file", file = %file_path.display()).entered();
+                    let analyzed = analyze_file_in_scope(file_path, scope, Edition::default());
+                    report_file_done(scope, &done, file_count);
+                    match analyzed {
+                        Ok(result) => Ok(AnalyzedFile {
+                            module_name: result.metrics.name.clone(),
+                            file_path: file_path.clone(),
+                            metrics: result.metrics,
+                            dependencies: result.dependencies,
+                            type_visibility: result.type_visibility,
+                            item_dependencies: result.item_dependencies,
+                        }),
+                        Err(e) => Err(skipped_file(file_path, e)),
                     }
                 })
                 .collect::<Vec<_>>()
         })
         .collect();
 
+    let (mut analyzed_results, skipped_files) = split_skipped(analyzed_results);
+    disambiguate_module_names(&mut analyzed_results);
+    drop(parse_span);
+    scope
+        .progress
+        .emit(ProgressEvent::Phase(AnalysisPhase::Resolving));
+    let _resolve_span = tracing::debug_span!("resolve_couplings").entered();
+
     // Build module names set
     let module_names: HashSet<String> = analyzed_results
         .iter()
@@ -1048,7 +1495,10 @@ pub fn analyze_project_parallel(path: &Path) -> Result<ProjectMetrics, AnalyzerE
 
     // Build project metrics (sequential, but fast)
     let mut project = ProjectMetrics::new();
+    project.workspace_root = Some(path.to_path_buf());
     project.total_files = analyzed_results.len();
+    project.test_files = test_files;
+    project.skipped_files = skipped_files;
 
     // First pass: register all types with their visibility
     for analyzed in &analyzed_results {
@@ -1071,7 +1521,7 @@ pub fn analyze_project_parallel(path: &Path) -> Result<ProjectMetrics, AnalyzerE
             }
 
             // Determine if this is an internal coupling
-            let target_module = extract_target_module(&dep.path);
+            let target_module = extract_target_module(&dep.path, &module_names);
 
             // Skip if target module looks invalid (but allow known module names)
             if !module_names.contains(&target_module) && !is_valid_dependency_path(&target_module) {
@@ -1094,7 +1544,7 @@ pub fn analyze_project_parallel(path: &Path) -> Result<ProjectMetrics, AnalyzerE
                 .unwrap_or(Visibility::Public); // Default to public if unknown
 
             // Create coupling metric with location
-            let coupling = CouplingMetrics::with_location(
+            let mut coupling = CouplingMetrics::with_location(
                 analyzed.module_name.clone(),
                 target_module.clone(),
                 strength,
@@ -1104,6 +1554,7 @@ pub fn analyze_project_parallel(path: &Path) -> Result<ProjectMetrics, AnalyzerE
                 analyzed.file_path.clone(),
                 dep.line,
             );
+            coupling.location.column = dep.column;
 
             project.add_coupling(coupling);
         }
@@ -1115,11 +1566,61 @@ pub fn analyze_project_parallel(path: &Path) -> Result<ProjectMetrics, AnalyzerE
     Ok(project)
 }
 
+/// Prefix module names shared by several files with their crate directory
+///
+/// Without workspace metadata every crate under the analyzed path has its
+/// own `lib`; the directory holding `src/` tells them apart (`app::lib`).
+fn disambiguate_module_names(files: &mut [AnalyzedFile]) {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for file in files.iter() {
+        *counts.entry(file.module_name.clone()).or_default() += 1;
+    }
+    for file in files.iter_mut() {
+        if counts[&file.module_name] < 2 {
+            continue;
+        }
+        if let Some(dir) = crate_dir_name(&file.file_path) {
+            let name = format!("{}::{}", dir, file.module_name);
+            file.metrics.name = name.clone();
+            file.module_name = name;
+        }
+    }
+}
+
+/// Name of the directory holding a file's `src/` directory
+fn crate_dir_name(file: &Path) -> Option<String> {
+    let components: Vec<_> = file.components().collect();
+    let src = components.iter().rposition(|c| c.as_os_str() == "src")?;
+    let dir = components.get(src.checked_sub(1)?)?;
+    dir.as_os_str().to_str().map(str::to_string)
+}
+
 /// Analyze a workspace using cargo metadata for better accuracy
 pub fn analyze_workspace(path: &Path) -> Result<ProjectMetrics, AnalyzerError> {
+    analyze_workspace_scoped(path, &AnalysisScope::default())
+}
+
+/// Analyze only the files in `scope`: the selected workspace members, minus
+/// the paths rejected by the include/exclude globs
+///
+/// Crates outside the package selection are still known to the analysis, so
+/// references into them are resolved as workspace references.
+pub fn analyze_workspace_scoped(
+    path: &Path,
+    scope: &AnalysisScope,
+) -> Result<ProjectMetrics, AnalyzerError> {
+    let _span = tracing::info_span!("analyze_workspace", path = %path.display()).entered();
+
     // Try to get workspace info
-    let workspace = match WorkspaceInfo::from_path(path) {
+    scope
+        .progress
+        .emit(ProgressEvent::Phase(AnalysisPhase::Discovering));
+    let workspace = match WorkspaceInfo::from_path_with(path, &scope.features) {
         Ok(ws) => Some(ws),
+        // Package and feature selections need the metadata
+        Err(e) if !scope.packages.is_empty() || scope.features != FeatureSelection::default() => {
+            return Err(e.into());
+        }
         Err(e) => {
             eprintln!("Note: Could not load workspace metadata: {}", e);
             eprintln!("Falling back to basic analysis...");
@@ -1128,17 +1629,19 @@ pub fn analyze_workspace(path: &Path) -> Result<ProjectMetrics, AnalyzerError> {
     };
 
     if let Some(ws) = workspace {
-        analyze_with_workspace(path, &ws)
+        let selected = scope.select_members(&ws)?;
+        analyze_with_workspace(&ws, &selected, scope)
     } else {
         // Fall back to basic analysis
-        analyze_project(path)
+        analyze_project_filtered(path, scope)
     }
 }
 
 /// Analyze project with workspace information (parallel version)
 fn analyze_with_workspace(
-    _path: &Path,
     workspace: &WorkspaceInfo,
+    selected: &[String],
+    scope: &AnalysisScope,
 ) -> Result<ProjectMetrics, AnalyzerError> {
     let mut project = ProjectMetrics::new();
 
@@ -1152,22 +1655,45 @@ fn analyze_with_workspace(
             .to_string(),
     );
     project.workspace_members = workspace.members.clone();
+    project.analyzed_members = selected.to_vec();
+    project.workspace_root = Some(workspace.root.clone());
 
     // Collect all file paths with their crate names (sequential, fast)
-    let mut file_crate_pairs: Vec<(PathBuf, String)> = Vec::new();
+    let mut file_crate_pairs: Vec<(PathBuf, String, Edition)> = Vec::new();
 
-    for member_name in &workspace.members {
+    for member_name in selected {
         if let Some(crate_info) = workspace.get_crate(member_name) {
             if !crate_info.src_path.exists() {
+                tracing::debug!(
+                    krate = %member_name,
+                    src = %crate_info.src_path.display(),
+                    "skipped crate without source directory"
+                );
                 continue;
             }
 
-            for file_path in rs_files(&crate_info.src_path) {
-                file_crate_pairs.push((file_path.to_path_buf(), member_name.clone()));
+            let (files, tests) = partition_test_files(
+                rs_files(&crate_info.src_path)
+                    .filter(|file| path_allowed(scope, file, &workspace.root)),
+                &crate_info.src_path,
+                scope.include_tests,
+            );
+            project.test_files.add(&tests);
+            for file_path in files {
+                file_crate_pairs.push((file_path, member_name.clone(), crate_info.edition));
             }
         }
     }
 
+    scope
+        .progress
+        .emit(ProgressEvent::FilesDiscovered(file_crate_pairs.len()));
+    scope
+        .progress
+        .emit(ProgressEvent::Phase(AnalysisPhase::Parsing));
+    let done = AtomicUsize::new(0);
+    let parse_span = tracing::debug_span!("parse_files").entered();
+
     // Calculate optimal chunk size for parallel processing
     let num_threads = rayon::current_num_threads();
     let file_count = file_crate_pairs.len();
@@ -1178,14 +1704,18 @@ fn analyze_with_workspace(
     };
 
     // Parallel file analysis with optimized chunking
-    let analyzed_files: Vec<AnalyzedFileWithCrate> = file_crate_pairs
+    let analyzed_files: Vec<_> = file_crate_pairs
         .par_chunks(chunk_size)
         .flat_map(|chunk| {
             chunk
                 .iter()
-                .filter_map(
-                    |(file_path, crate_name)| match analyze_rust_file_full(file_path) {
-                        Ok(result) => Some(AnalyzedFileWithCrate {
+                .map(|(file_path, crate_name, edition)| {
+                    let _span =
+                        tracing::trace_span!("analyze_file", file = %file_path.display()).entered();
+                    let analyzed = analyze_file_in_scope(file_path, scope, *edition);
+                    report_file_done(scope, &done, file_count);
+                    match analyzed {
+                        Ok(result) => Ok(AnalyzedFileWithCrate {
                             module_name: result.metrics.name.clone(),
                             crate_name: crate_name.clone(),
                             file_path: file_path.clone(),
@@ -1193,51 +1723,104 @@ fn analyze_with_workspace(
                             dependencies: result.dependencies,
                             item_dependencies: result.item_dependencies,
                         }),
-                        Err(e) => {
-                            eprintln!("Warning: Failed to analyze {}: {}", file_path.display(), e);
-                            None
-                        }
-                    },
-                )
+                        Err(e) => Err(skipped_file(file_path, e)),
+                    }
+                })
                 .collect::<Vec<_>>()
         })
         .collect();
 
+    let (mut analyzed_files, skipped_files) = split_skipped(analyzed_files);
+    qualify_module_names(&mut analyzed_files);
     project.total_files = analyzed_files.len();
+    project.skipped_files = skipped_files;
+    drop(parse_span);
+    scope
+        .progress
+        .emit(ProgressEvent::Phase(AnalysisPhase::Resolving));
+    let _resolve_span = tracing::debug_span!("resolve_couplings").entered();
+
+    // Known modules of each crate by their in-crate name, for validation and
+    // references into members
+    let mut crate_modules: HashMap<&str, HashSet<String>> = HashMap::new();
+    for analyzed in &analyzed_files {
+        crate_modules
+            .entry(analyzed.crate_name.as_str())
+            .or_default()
+            .insert(module_name_from_file(&analyzed.file_path));
+    }
 
-    // Build set of known module names for validation
-    let module_names: HashSet<String> = analyzed_files
-        .iter()
-        .map(|a| a.module_name.clone())
-        .collect();
+    // Types referenced across workspace crates: crate -> type name -> uses
+    let mut cross_crate_type_uses: HashMap<String, HashMap<String, usize>> = HashMap::new();
 
     // Second pass: build coupling relationships with workspace context
     for analyzed in &analyzed_files {
         // Clone metrics and add item_dependencies
         let mut metrics = analyzed.metrics.clone();
         metrics.item_dependencies = analyzed.item_dependencies.clone();
+        metrics.crate_name = Some(analyzed.crate_name.clone());
         project.add_module(metrics);
 
+        let own_modules = &crate_modules[analyzed.crate_name.as_str()];
         for dep in &analyzed.dependencies {
             // Skip invalid dependency paths (local variables, Self, etc.)
             if !is_valid_dependency_path(&dep.path) {
                 continue;
             }
 
+            // Paths through re-exports point at the defining crate
+            let canonical = scope
+                .rustdoc
+                .resolve_cross_crate(&dep.path, &analyzed.crate_name);
+            let dep_path = canonical.as_deref().unwrap_or(&dep.path);
+
             // Resolve the target crate using workspace info
-            let resolved_crate =
-                resolve_crate_from_path(&dep.path, &analyzed.crate_name, workspace);
+            let resolved_crate = resolve_crate_from_path(dep_path, &analyzed.crate_name, workspace);
 
-            let target_module = extract_target_module(&dep.path);
+            // Code behind a disabled feature does not build
+            if let Some(target_crate) = &resolved_crate
+                && workspace.is_inactive_dependency(&analyzed.crate_name, target_crate)
+            {
+                tracing::debug!(
+                    krate = %analyzed.crate_name,
+                    dependency = %target_crate,
+                    "skipped coupling to disabled optional dependency"
+                );
+                continue;
+            }
+
+            if let Some(target_crate) = &resolved_crate
+                && target_crate != &analyzed.crate_name
+                && workspace.is_workspace_member(target_crate)
+                && let Some(type_name) = dep_path.rsplit("::").next()
+                && type_name.starts_with(char::is_uppercase)
+            {
+                *cross_crate_type_uses
+                    .entry(target_crate.clone())
+                    .or_default()
+                    .entry(type_name.to_string())
+                    .or_default() += 1;
+            }
+
+            let member_crate = resolved_crate.as_ref().filter(|krate| {
+                *krate != &analyzed.crate_name && workspace.is_workspace_member(krate)
+            });
+            let target_module = match member_crate {
+                Some(krate) => member_target_module(dep_path, crate_modules.get(krate.as_str())),
+                None => extract_target_module(dep_path, own_modules),
+            };
 
             // Skip if target module looks invalid (but allow known module names)
-            if !module_names.contains(&target_module) && !is_valid_dependency_path(&target_module) {
+            if member_crate.is_none()
+                && !own_modules.contains(&target_module)
+                && !is_valid_dependency_path(&target_module)
+            {
                 continue;
             }
 
             // Calculate distance with workspace awareness
             let distance =
-                calculate_distance_with_workspace(&dep.path, &analyzed.crate_name, workspace);
+                calculate_distance_with_workspace(dep_path, &analyzed.crate_name, workspace);
 
             // Determine strength from usage context (more accurate)
             let strength = dep.usage.to_strength();
@@ -1247,7 +1830,14 @@ fn analyze_with_workspace(
 
             // Create coupling metric with location info
             let mut coupling = CouplingMetrics::with_location(
-                format!("{}::{}", analyzed.crate_name, analyzed.module_name),
+                if analyzed
+                    .module_name
+                    .starts_with(&format!("{}::", analyzed.crate_name))
+                {
+                    analyzed.module_name.clone()
+                } else {
+                    format!("{}::{}", analyzed.crate_name, analyzed.module_name)
+                },
                 if let Some(ref crate_name) = resolved_crate {
                     format!("{}::{}", crate_name, target_module)
                 } else {
@@ -1261,6 +1851,8 @@ fn analyze_with_workspace(
                 dep.line,
             );
 
+            coupling.location.column = dep.column;
+
             // Add crate-level info
             coupling.source_crate = Some(analyzed.crate_name.clone());
             coupling.target_crate = resolved_crate;
@@ -1271,7 +1863,7 @@ fn analyze_with_workspace(
 
     // Add crate-level dependency information
     for (crate_name, deps) in &workspace.dependency_graph {
-        if workspace.is_workspace_member(crate_name) {
+        if selected.contains(crate_name) {
             for dep in deps {
                 // Track crate-level dependencies
                 project
@@ -1282,10 +1874,65 @@ fn analyze_with_workspace(
             }
         }
     }
+    for crate_name in selected {
+        if let Some(info) = workspace.get_crate(crate_name)
+            && !info.target_dependencies.is_empty()
+        {
+            project
+                .target_dependencies
+                .insert(crate_name.clone(), info.target_dependencies.clone());
+        }
+    }
+    project.dependency_phases = classify_dependencies(workspace);
+    project
+        .dependency_phases
+        .retain(|dep| selected.contains(&dep.source));
+    project.crate_stability = compute_crate_stability(workspace);
+    project
+        .crate_stability
+        .retain(|krate| selected.contains(&krate.name));
+    project.crate_cycles = workspace.crate_cycles();
+    project
+        .crate_cycles
+        .retain(|cycle| cycle.members.iter().any(|m| selected.contains(m)));
+
+    // Abstract vs concrete types per crate (for the abstractness metric)
+    for krate in &mut project.crate_stability {
+        for analyzed in analyzed_files.iter().filter(|a| a.crate_name == krate.name) {
+            krate.abstract_types += analyzed.metrics.abstract_type_count();
+            krate.concrete_types += analyzed.metrics.concrete_type_count();
+            if let Some(uses) = cross_crate_type_uses.get(&krate.name) {
+                for def in analyzed.metrics.type_definitions.values() {
+                    if !def.is_trait
+                        && let Some(&count) = uses.get(&def.name)
+                    {
+                        krate.type_uses.insert(def.name.clone(), count);
+                    }
+                }
+            }
+        }
+    }
 
     Ok(project)
 }
 
+/// Prefix module names with their crate when several crates are analyzed
+///
+/// Module names are unique within a crate (`lib`, `net`, `net::client`), but
+/// every member has its own `lib`, so across crates they are keyed as
+/// `<crate>::<module>`.
+fn qualify_module_names(files: &mut [AnalyzedFileWithCrate]) {
+    let crates: HashSet<&str> = files.iter().map(|f| f.crate_name.as_str()).collect();
+    if crates.len() < 2 {
+        return;
+    }
+    for file in files {
+        let name = format!("{}::{}", file.crate_name, file.module_name);
+        file.metrics.name = name.clone();
+        file.module_name = name;
+    }
+}
+
 /// Calculate distance using workspace information
 fn calculate_distance_with_workspace(
     dep_path: &str,
@@ -1330,15 +1977,37 @@ struct AnalyzedFileWithCrate {
 }
 
 /// Extract target module name from a path
-fn extract_target_module(path: &str) -> String {
+///
+/// The longest prefix naming one of `modules` (`net::client` for
+/// `crate::net::client::Conn`), else the first segment.
+fn extract_target_module(path: &str, modules: &HashSet<String>) -> String {
     // Remove common prefixes and get the module name
     let cleaned = path
         .trim_start_matches("crate::")
         .trim_start_matches("super::")
         .trim_start_matches("::");
 
-    // Get first significant segment
-    cleaned.split("::").next().unwrap_or(path).to_string()
+    let segments: Vec<&str> = cleaned.split("::").collect();
+    (2..=segments.len())
+        .rev()
+        .map(|len| segments[..len].join("::"))
+        .find(|prefix| modules.contains(prefix))
+        .unwrap_or_else(|| segments[0].to_string())
+}
+
+/// Module of a workspace member a path into it points at
+///
+/// `corelib::model::User` is in `model` when corelib has that module; items
+/// named directly after the crate (`corelib::helper`) are in its root.
+fn member_target_module(path: &str, modules: Option<&HashSet<String>>) -> String {
+    let segments: Vec<&str> = path.split("::").skip(1).collect();
+    let known = |name: &String| modules.is_some_and(|m| m.contains(name));
+    (1..=segments.len())
+        .rev()
+        .map(|len| segments[..len].join("::"))
+        .find(known)
+        .or_else(|| ["lib", "main"].map(String::from).into_iter().find(known))
+        .unwrap_or_else(|| "lib".to_string())
 }
 
 /// Check if a path looks like a valid module/type reference (not a local variable)
@@ -1434,18 +2103,359 @@ pub fn analyze_rust_file(path: &Path) -> Result<(ModuleMetrics, Vec<Dependency>)
     Ok((result.metrics, result.dependencies))
 }
 
+/// Count non-blank lines that are not line comments
+fn count_code_lines(content: &str) -> usize {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("//"))
+        .count()
+}
+
+/// Record the items a `pub use` tree exports
+fn collect_reexports(tree: &UseTree, prefix: &str, reexports: &mut Vec<ReExport>) {
+    let join = |name: &dyn std::fmt::Display| {
+        if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}::{}", prefix, name)
+        }
+    };
+    match tree {
+        UseTree::Path(path) => collect_reexports(&path.tree, &join(&path.ident), reexports),
+        UseTree::Group(group) => {
+            for tree in &group.items {
+                collect_reexports(tree, prefix, reexports);
+            }
+        }
+        UseTree::Name(name) if name.ident != "self" => reexports.push(ReExport {
+            source: join(&name.ident),
+            name: name.ident.to_string(),
+            line: name.ident.span().start().line,
+        }),
+        UseTree::Name(name) => reexports.push(ReExport {
+            source: prefix.to_string(),
+            name: prefix.rsplit("::").next().unwrap_or(prefix).to_string(),
+            line: name.ident.span().start().line,
+        }),
+        UseTree::Rename(rename) => reexports.push(ReExport {
+            source: join(&rename.ident),
+            name: rename.rename.to_string(),
+            line: rename.ident.span().start().line,
+        }),
+        UseTree::Glob(glob) => reexports.push(ReExport {
+            source: join(&"*"),
+            name: "*".to_string(),
+            line: glob.star_token.span.start().line,
+        }),
+    }
+}
+
+/// Paths a type names, with their line: the type itself, generic arguments
+/// and the traits of `impl Trait` and `dyn Trait`
+fn type_paths(ty: &Type) -> Vec<(String, usize)> {
+    struct Collector(Vec<(String, usize)>);
+    impl<'ast> Visit<'ast> for Collector {
+        fn visit_path(&mut self, path: &'ast syn::Path) {
+            let joined = path
+                .segments
+                .iter()
+                .map(|s| s.ident.to_string())
+                .collect::<Vec<_>>()
+                .join("::");
+            self.0.push((joined, path.span().start().line));
+            syn::visit::visit_path(self, path);
+        }
+    }
+
+    let mut collector = Collector(Vec::new());
+    collector.visit_type(ty);
+    collector.0
+}
+
+/// Generic complexity of a signature, apart from its parameter count
+///
+/// Every generic parameter, trait or lifetime bound and where-clause
+/// predicate counts 1, every higher-ranked `for<'a>` binder 2, and every
+/// generic argument its nesting depth: `HashMap<K, Vec<Box<dyn Trait>>>`
+/// scores 1 (`K`) + 1 (`Vec`) + 2 (`Box`) + 3 (`dyn Trait`) + 1 (the
+/// `Trait` bound).
+fn signature_complexity(sig: &Signature) -> usize {
+    #[derive(Default)]
+    struct Scorer {
+        score: usize,
+        depth: usize,
+    }
+    impl<'ast> Visit<'ast> for Scorer {
+        fn visit_generic_param(&mut self, node: &'ast syn::GenericParam) {
+            self.score += 1;
+            syn::visit::visit_generic_param(self, node);
+        }
+        fn visit_type_param_bound(&mut self, node: &'ast syn::TypeParamBound) {
+            self.score += 1;
+            syn::visit::visit_type_param_bound(self, node);
+        }
+        fn visit_where_predicate(&mut self, node: &'ast syn::WherePredicate) {
+            self.score += 1;
+            syn::visit::visit_where_predicate(self, node);
+        }
+        // Not visited further: the binder's lifetimes are no generic
+        // parameters of the function
+        fn visit_bound_lifetimes(&mut self, _node: &'ast syn::BoundLifetimes) {
+            self.score += 2;
+        }
+        fn visit_angle_bracketed_generic_arguments(
+            &mut self,
+            node: &'ast syn::AngleBracketedGenericArguments,
+        ) {
+            self.depth += 1;
+            self.score += self.depth * node.args.len();
+            syn::visit::visit_angle_bracketed_generic_arguments(self, node);
+            self.depth -= 1;
+        }
+    }
+
+    let mut scorer = Scorer::default();
+    scorer.visit_signature(sig);
+    scorer.score
+}
+
+/// Whether a public signature couples callers to how the data is stored:
+/// it names two or more lifetimes (`'static` and `'_` aside), or returns
+/// borrowed data from a `&self`/`&mut self` method
+fn leaks_lifetimes(sig: &Signature) -> bool {
+    #[derive(Default)]
+    struct Lifetimes {
+        named: Vec<String>,
+        borrowed: bool,
+    }
+    impl<'ast> Visit<'ast> for Lifetimes {
+        fn visit_lifetime(&mut self, node: &'ast syn::Lifetime) {
+            let name = node.ident.to_string();
+            if name != "static" {
+                self.borrowed = true;
+                if name != "_" && !self.named.contains(&name) {
+                    self.named.push(name);
+                }
+            }
+        }
+        fn visit_type_reference(&mut self, node: &'ast syn::TypeReference) {
+            if node.lifetime.is_none() {
+                self.borrowed = true;
+            }
+            syn::visit::visit_type_reference(self, node);
+        }
+    }
+
+    let mut lifetimes = Lifetimes::default();
+    lifetimes.visit_signature(sig);
+    if lifetimes.named.len() >= 2 {
+        return true;
+    }
+    let borrows_self = matches!(sig.receiver(), Some(r) if r.reference.is_some());
+    let ReturnType::Type(_, output) = &sig.output else {
+        return false;
+    };
+    let mut returned = Lifetimes::default();
+    returned.visit_type(output);
+    borrows_self && returned.borrowed
+}
+
+/// Check whether a type mentions a trait object (`dyn Trait`)
+fn contains_trait_object(ty: &Type) -> bool {
+    struct Finder(bool);
+    impl<'ast> Visit<'ast> for Finder {
+        fn visit_type_trait_object(&mut self, _: &'ast syn::TypeTraitObject) {
+            self.0 = true;
+        }
+    }
+
+    let mut finder = Finder(false);
+    finder.visit_type(ty);
+    finder.0
+}
+
+/// Rewrite a `use` path found inside inline modules (`mod tests { use
+/// super::*; }`) so it is relative to the file's module
+///
+/// Returns `None` when the path stays within the file.
+fn file_relative_use_path(path: &str, inline_depth: usize) -> Option<String> {
+    if inline_depth == 0 {
+        return Some(path.to_string());
+    }
+    if path == "self" || path.starts_with("self::") {
+        return None;
+    }
+
+    let segments: Vec<&str> = path.split("::").collect();
+    let supers = segments.iter().take_while(|s| **s == "super").count();
+    if supers == 0 {
+        return Some(path.to_string());
+    }
+    if supers <= inline_depth {
+        // Climbs out of the inline modules but not out of the file
+        return None;
+    }
+    let mut rewritten = vec!["super"; supers - inline_depth];
+    rewritten.extend(&segments[supers..]);
+    Some(rewritten.join("::"))
+}
+
+/// Whether attributes mark test-only code (`#[test]`, `#[cfg(test)]`)
+pub fn is_test_code(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path().is_ident("test")
+            || (attr.path().is_ident("cfg")
+                && attr
+                    .parse_args::<syn::Ident>()
+                    .is_ok_and(|ident| ident == "test"))
+    })
+}
+
+/// Remove `#[cfg(test)]` modules and `#[test]` functions from a parsed file
+///
+/// Returns what was removed, so test code can be reported separately
+/// instead of inflating coupling, connascence and temporal counts.
+pub fn strip_test_code(file: &mut File, content: &str) -> TestCodeMetrics {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut tests = TestCodeMetrics::default();
+    strip_test_items(&mut file.items, &lines, &mut tests);
+    tests
+}
+
+fn strip_test_items(items: &mut Vec<syn::Item>, lines: &[&str], tests: &mut TestCodeMetrics) {
+    items.retain_mut(|item| {
+        let test_lines = |span: Span| {
+            let start = span.start().line.saturating_sub(1);
+            let end = span.end().line.min(lines.len());
+            count_code_lines(&lines[start.min(end)..end].join("\n"))
+        };
+        match item {
+            syn::Item::Mod(module) if is_test_code(&module.attrs) => {
+                tests.modules += 1;
+                tests.functions += count_test_functions(module);
+                tests.lines_of_code += test_lines(module.span());
+                false
+            }
+            syn::Item::Fn(function) if is_test_code(&function.attrs) => {
+                tests.functions += 1;
+                tests.lines_of_code += test_lines(function.span());
+                false
+            }
+            syn::Item::Mod(module) => {
+                if let Some((_, content)) = &mut module.content {
+                    strip_test_items(content, lines, tests);
+                }
+                true
+            }
+            syn::Item::Impl(item_impl) => {
+                item_impl.items.retain(|impl_item| match impl_item {
+                    syn::ImplItem::Fn(method) if is_test_code(&method.attrs) => {
+                        tests.functions += 1;
+                        tests.lines_of_code += test_lines(method.span());
+                        false
+                    }
+                    _ => true,
+                });
+                true
+            }
+            _ => true,
+        }
+    });
+}
+
+/// Count `#[test]` functions inside a test module
+fn count_test_functions(module: &ItemMod) -> usize {
+    struct Counter(usize);
+    impl<'ast> Visit<'ast> for Counter {
+        fn visit_item_fn(&mut self, node: &'ast ItemFn) {
+            if node.attrs.iter().any(|attr| attr.path().is_ident("test")) {
+                self.0 += 1;
+            }
+            syn::visit::visit_item_fn(self, node);
+        }
+    }
+
+    let mut counter = Counter(0);
+    counter.visit_item_mod(module);
+    counter.0
+}
+
+/// Whether a file belongs to an integration test directory (`tests/`)
+fn is_test_file(path: &Path, root: &Path) -> bool {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .any(|c| c.as_os_str() == "tests")
+}
+
+/// Split source files into files to analyze and skipped `tests/` files
+fn partition_test_files(
+    files: impl Iterator<Item = PathBuf>,
+    root: &Path,
+    include_tests: bool,
+) -> (Vec<PathBuf>, TestCodeMetrics) {
+    let mut tests = TestCodeMetrics::default();
+    let files = files
+        .filter(|file| {
+            if include_tests || !is_test_file(file, root) {
+                return true;
+            }
+            tracing::debug!(file = %file.display(), "skipped test file");
+            tests.files += 1;
+            tests.lines_of_code += fs::read_to_string(file).map_or(0, |c| count_code_lines(&c));
+            false
+        })
+        .collect();
+    (files, tests)
+}
+
 /// Analyze a Rust file and return full results including visibility
 pub fn analyze_rust_file_full(path: &Path) -> Result<AnalyzedFileResult, AnalyzerError> {
+    analyze_rust_file_with(path, false)
+}
+
+/// Analyze a Rust file, optionally treating test code like production code
+pub fn analyze_rust_file_with(
+    path: &Path,
+    include_tests: bool,
+) -> Result<AnalyzedFileResult, AnalyzerError> {
     let content = fs::read_to_string(path)?;
+    analyze_rust_source(path, &content, include_tests, Edition::default())
+}
 
-    let module_name = path
-        .file_stem()
-        .and_then(|s| s.to_str())
-        .unwrap_or("unknown")
-        .to_string();
+/// Analyze a file of `scope`, preferring its macro-expanded source
+fn analyze_file_in_scope(
+    path: &Path,
+    scope: &AnalysisScope,
+    edition: Edition,
+) -> Result<AnalyzedFileResult, AnalyzerError> {
+    match scope.expanded.get(path) {
+        Some(source) => {
+            let mut result = analyze_rust_source(path, source, scope.include_tests, edition)?;
+            result.metrics.expanded = true;
+            Ok(result)
+        }
+        None => {
+            let content = fs::read_to_string(path)?;
+            analyze_rust_source(path, &content, scope.include_tests, edition)
+        }
+    }
+}
 
-    let mut analyzer = CouplingAnalyzer::new(module_name, path.to_path_buf());
-    analyzer.analyze_file(&content)?;
+/// Analyze Rust source that belongs to the file at `path`
+fn analyze_rust_source(
+    path: &Path,
+    content: &str,
+    include_tests: bool,
+    edition: Edition,
+) -> Result<AnalyzedFileResult, AnalyzerError> {
+    let module_name = module_name_from_file(path);
+
+    let mut analyzer = CouplingAnalyzer::new(module_name, path.to_path_buf())
+        .with_tests(include_tests)
+        .with_edition(edition);
+    analyzer.analyze_file(content)?;
 
     Ok(AnalyzedFileResult {
         metrics: analyzer.metrics,
@@ -1558,11 +2568,107 @@ mod tests {
             impl Printable for Document {
                 fn print(&self) {}
             }
+
+            type Printer = Box<dyn Printable>;
+            type Id = u64;
         "#;
 
         let result = analyzer.analyze_file(code);
         assert!(result.is_ok());
         assert!(analyzer.metrics.trait_impl_count >= 1);
+        assert_eq!(analyzer.metrics.abstract_type_count(), 2);
+        assert_eq!(analyzer.metrics.concrete_type_count(), 1);
+    }
+
+    #[test]
+    fn test_exposed_types() {
+        let mut analyzer =
+            CouplingAnalyzer::new("test".to_string(), std::path::PathBuf::from("test.rs"));
+
+        let code = r#"
+            use serde_json::Value;
+            use tokio::sync;
+
+            pub struct Config {
+                pub value: Value,
+                cache: sync::Mutex<u8>,
+            }
+            pub enum Event { Raw(bytes::Bytes) }
+            pub type Shared = std::sync::Arc<sync::Mutex<Config>>;
+            impl Config {
+                pub fn get(&self) -> Option<&Value> { None }
+                fn reset(&mut self, _: Value) {}
+            }
+            fn private(_: Value) {}
+        "#;
+
+        analyzer.analyze_file(code).unwrap();
+        // Option<&Value>
+        assert_eq!(analyzer.metrics.signature_complexity, 1);
+        let exposed: Vec<(&str, &str)> = analyzer
+            .metrics
+            .exposed_types
+            .iter()
+            .map(|e| (e.item.as_str(), e.type_path.as_str()))
+            .collect();
+        assert_eq!(
+            exposed,
+            vec![
+                ("Config.value", "serde_json::Value"),
+                ("Event::Raw.0", "bytes::Bytes"),
+                ("Shared", "std::sync::Arc"),
+                ("Shared", "tokio::sync::Mutex"),
+                ("Shared", "Config"),
+                ("Config::get", "Option"),
+                ("Config::get", "serde_json::Value"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_signature_complexity() {
+        let score = |code: &str| {
+            let item: ItemFn = syn::parse_str(code).unwrap();
+            signature_complexity(&item.sig)
+        };
+        assert_eq!(score("pub fn f(a: u32, b: &str) -> bool { true }"), 0);
+        assert_eq!(
+            score("pub fn f<K>(map: HashMap<K, Vec<Box<dyn Trait>>>) {}"),
+            9
+        );
+        // T, its two bounds, the predicate, its bound and the binder
+        assert_eq!(
+            score("pub fn f<T: Clone + Send>(t: T) where F: for<'a> Fn(&'a T) {}"),
+            7
+        );
+    }
+
+    #[test]
+    fn test_lifetime_leaks() {
+        let mut analyzer = CouplingAnalyzer::new("store".into(), PathBuf::from("src/store.rs"));
+        analyzer
+            .analyze_file(
+                r#"
+                pub struct Store { items: Vec<Item> }
+                pub struct Join<'a, 'b> { left: &'a str, right: &'b str }
+                pub struct View<'a> { items: &'a [Item] }
+                impl Store {
+                    pub fn items(&self) -> &[Item] { &self.items }
+                    pub fn iter(&self) -> impl Iterator<Item = Item> + '_ { todo!() }
+                    pub fn len(&self) -> usize { 0 }
+                    pub fn name(&self) -> &'static str { "store" }
+                    pub fn owned(&self) -> Vec<Item> { self.items.clone() }
+                    fn first(&self) -> &Item { &self.items[0] }
+                }
+                pub fn pick<'a, 'b>(a: &'a str, b: &'b str) -> &'a str { a }
+                pub fn parse(input: &str) -> &str { input }
+                "#,
+            )
+            .unwrap();
+        assert_eq!(
+            analyzer.metrics.lifetime_leaks,
+            vec!["Join", "Store::items", "Store::iter", "pick"]
+        );
     }
 
     #[test]
@@ -1597,6 +2703,33 @@ mod tests {
         assert!(!internal_deps.is_empty());
     }
 
+    #[test]
+    fn test_dependency_locations() {
+        let mut analyzer =
+            CouplingAnalyzer::new("test".to_string(), std::path::PathBuf::from("test.rs"));
+
+        let code = "use crate::models::User;\n\nfn f() {\n    let u = User { id: 1 };\n}\n";
+
+        analyzer.analyze_file(code).unwrap();
+        let use_dep = analyzer
+            .dependencies
+            .iter()
+            .find(|d| d.kind == DependencyKind::InternalUse)
+            .unwrap();
+        assert_eq!((use_dep.line, use_dep.column), (1, 20));
+
+        let construction = analyzer
+            .dependencies
+            .iter()
+            .find(|d| d.usage == UsageContext::StructConstruction)
+            .unwrap();
+        assert_eq!(construction.line, 4);
+        assert!(construction.column > 0);
+
+        let def = &analyzer.metrics.function_definitions["f"];
+        assert_eq!((def.line, def.column), (3, 4));
+    }
+
     #[test]
     fn test_extract_use_paths() {
         let analyzer =
@@ -1616,9 +2749,17 @@ mod tests {
 
     #[test]
     fn test_extract_target_module() {
-        assert_eq!(extract_target_module("crate::models::user"), "models");
-        assert_eq!(extract_target_module("super::utils"), "utils");
-        assert_eq!(extract_target_module("std::collections"), "std");
+        let modules: HashSet<String> = ["models".into(), "net::client".into()].into();
+        assert_eq!(
+            extract_target_module("crate::models::user", &modules),
+            "models"
+        );
+        assert_eq!(extract_target_module("super::utils", &modules), "utils");
+        assert_eq!(extract_target_module("std::collections", &modules), "std");
+        assert_eq!(
+            extract_target_module("crate::net::client::Conn", &modules),
+            "net::client"
+        );
     }
 
     #[test]
@@ -1708,4 +2849,152 @@ mod tests {
             IntegrationStrength::Contract
         );
     }
+
+    #[test]
+    fn test_test_code_is_skipped() {
+        let code = r#"
+            use crate::model::User;
+
+            pub fn run(tx: &mut Tx) {
+                tx.begin();
+                tx.commit();
+            }
+
+            impl User {
+                #[test]
+                fn inline_check() {}
+            }
+
+            #[cfg(test)]
+            mod tests {
+                use crate::fixtures::Fixture;
+
+                #[test]
+                fn leaks_a_transaction() {
+                    let f = Fixture::new();
+                    f.tx.begin();
+                }
+
+                #[test]
+                fn other() {}
+            }
+        "#;
+
+        let mut analyzer = CouplingAnalyzer::new("lib".into(), PathBuf::from("lib.rs"));
+        analyzer.analyze_file(code).unwrap();
+        let tests = analyzer.metrics.tests;
+        assert_eq!((tests.modules, tests.functions), (1, 3));
+        assert_eq!(tests.lines_of_code, 13);
+        assert_eq!(analyzer.metrics.lines_of_code, 7);
+        assert!(analyzer.metrics.temporal.issues.is_empty());
+        assert!(
+            !analyzer
+                .dependencies
+                .iter()
+                .any(|d| d.path.contains("fixtures"))
+        );
+
+        let mut analyzer =
+            CouplingAnalyzer::new("lib".into(), PathBuf::from("lib.rs")).with_tests(true);
+        analyzer.analyze_file(code).unwrap();
+        assert!(analyzer.metrics.tests.is_empty());
+        assert!(
+            analyzer
+                .dependencies
+                .iter()
+                .any(|d| d.path.contains("fixtures"))
+        );
+    }
+
+    #[test]
+    fn test_tests_directory_is_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::create_dir_all(dir.path().join("tests")).unwrap();
+        fs::write(dir.path().join("src/lib.rs"), "pub fn f() {}\n").unwrap();
+        fs::write(dir.path().join("tests/it.rs"), "#[test]\nfn it() {}\n").unwrap();
+
+        let metrics = analyze_project_filtered(dir.path(), &AnalysisScope::default()).unwrap();
+        assert_eq!(metrics.total_files, 1);
+        assert_eq!(metrics.test_code().files, 1);
+        assert_eq!(metrics.test_code().lines_of_code, 2);
+
+        let scope = AnalysisScope {
+            include_tests: true,
+            ..AnalysisScope::default()
+        };
+        let metrics = analyze_project_filtered(dir.path(), &scope).unwrap();
+        assert_eq!(metrics.total_files, 2);
+        assert!(metrics.test_code().is_empty());
+    }
+
+    #[test]
+    fn test_unparseable_files_are_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("ok.rs"), "pub fn f() {}\n").unwrap();
+        fs::write(
+            dir.path().join("broken.rs"),
+            "pub fn f() {\n    let = ;\n}\n",
+        )
+        .unwrap();
+
+        let metrics = analyze_project_filtered(dir.path(), &AnalysisScope::default()).unwrap();
+        assert_eq!(metrics.total_files, 1);
+        assert_eq!(metrics.skipped_files.len(), 1);
+        let skipped = &metrics.skipped_files[0];
+        assert_eq!(skipped.path, dir.path().join("broken.rs"));
+        assert!(skipped.error.contains("line 2"), "{}", skipped.error);
+    }
+
+    #[test]
+    fn test_module_names_are_unique_across_crates() {
+        let dir = crate::test_fixtures::two_lib_workspace();
+        let metrics = analyze_workspace(dir.path()).unwrap();
+        let names: Vec<&str> = metrics.modules.keys().map(String::as_str).collect();
+        assert_eq!(names, vec!["app::lib", "corelib::lib", "corelib::model"]);
+
+        // Without metadata the crate directories tell the roots apart
+        fs::remove_file(dir.path().join("Cargo.toml")).unwrap();
+        let metrics = analyze_project_filtered(dir.path(), &AnalysisScope::default()).unwrap();
+        let names: Vec<&str> = metrics.modules.keys().map(String::as_str).collect();
+        assert_eq!(names, vec!["app::lib", "corelib::lib", "model"]);
+    }
+
+    #[test]
+    fn test_default_members_are_reported_to_the_caller() {
+        let dir = crate::test_fixtures::two_lib_workspace();
+        crate::test_fixtures::write(
+            dir.path(),
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"corelib\", \"app\"]\n\
+             default-members = [\"corelib\"]\nresolver = \"2\"\n",
+        );
+
+        let metrics = analyze_workspace(dir.path()).unwrap();
+        assert_eq!(metrics.analyzed_members, vec!["corelib"]);
+        assert_eq!(metrics.workspace_members.len(), 2);
+        let names: Vec<&str> = metrics.modules.keys().map(String::as_str).collect();
+        assert_eq!(names, vec!["lib", "model"]);
+    }
+
+    #[test]
+    fn test_workspace_flag_analyzes_every_member() {
+        let dir = crate::test_fixtures::two_lib_workspace();
+        crate::test_fixtures::write(
+            dir.path(),
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"corelib\", \"app\"]\n\
+             default-members = [\"corelib\"]\nresolver = \"2\"\n",
+        );
+
+        let scope = AnalysisScope {
+            workspace: true,
+            ..AnalysisScope::default()
+        };
+        let metrics = analyze_workspace_scoped(dir.path(), &scope).unwrap();
+        let mut members = metrics.analyzed_members.clone();
+        members.sort();
+        assert_eq!(members, vec!["app", "corelib"]);
+        assert!(metrics.modules.contains_key("app::lib"));
+    }
 }
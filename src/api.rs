@@ -0,0 +1,952 @@
+//! Public API surface of workspace crates
+//!
+//! Lists the public items of every workspace member and which other
+//! workspace crates import each of them, so the blast radius of changing a
+//! public item is visible before the change is made.
+//!
+//! - Public items are `pub` functions, types and traits, plus `pub use`
+//!   re-exports, in modules reachable from `lib.rs` through `pub mod`
+//!   declarations (binary-only crates have no API)
+//! - Usage comes from `use` statements in other crates: `use my_core::model::User`
+//!   is matched against the item's public path, falling back to the item name
+//!   for paths that go through glob re-exports. Qualified paths in code
+//!   (`my_core::init()`, `my_core::model::User::new`) count too
+//!
+//! Items used downstream are semver-sensitive: changing them breaks those
+//! crates. Items nobody in the workspace imports are candidates for
+//! `pub(crate)`, unless they are meant for users outside the workspace.
+//!
+//! Crates no other workspace member depends on are the workspace's intended
+//! API: everything they export, and everything they re-export from other
+//! members, is meant for users outside the workspace. The remaining public
+//! items nobody uses are *unused public surface*: they count towards their
+//! module's interface complexity (and lower its APOSD depth ratio) without
+//! serving any caller. Without cargo metadata every crate counts as a root.
+//!
+//! `pub use` re-exports are followed back to the item they name. A type
+//! that `app` re-exports from `service`, which re-exports it from `core`, is
+//! part of three public APIs: renaming it in `core` breaks all of them, and
+//! users of `app` depend on `core` without declaring it.
+//!
+//! Types of third-party crates in public signatures, public fields, enum
+//! variants and type aliases (`pub fn parse() -> serde_json::Value`) are
+//! leaked third-party coupling: every user of the item depends on that
+//! crate's types too, and a semver-breaking release of it is a breaking
+//! change of the API. Standard library and workspace crates do not count;
+//! a crate counts as third-party when it is one of the exposing crate's
+//! dependencies (or, without cargo metadata, an imported crate).
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use crate::metrics::{ModuleMetrics, ProjectMetrics, Visibility};
+use crate::module_graph::module_path_from_file;
+
+/// Crate name used when the analysis has no workspace information
+const ROOT: &str = "crate";
+
+/// Crates that make up the standard library
+const STD_CRATES: &[&str] = &["std", "core", "alloc"];
+
+/// Kind of public item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ApiItemKind {
+    Function,
+    Type,
+    Trait,
+    ReExport,
+}
+
+impl fmt::Display for ApiItemKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiItemKind::Function => write!(f, "fn"),
+            ApiItemKind::Type => write!(f, "type"),
+            ApiItemKind::Trait => write!(f, "trait"),
+            ApiItemKind::ReExport => write!(f, "re-export"),
+        }
+    }
+}
+
+/// A public item of a crate
+#[derive(Debug, Clone)]
+pub struct ApiItem {
+    /// Crate defining (or re-exporting) the item
+    pub krate: String,
+    /// Public path inside the crate (`model::User`)
+    pub path: String,
+    pub name: String,
+    pub kind: ApiItemKind,
+    pub file: PathBuf,
+    pub line: usize,
+    /// Path a re-export names, as written (`my_core::model::User`)
+    pub source: Option<String>,
+    /// Downstream users: crate name -> modules importing the item
+    pub users: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl ApiItem {
+    /// Number of modules in other crates importing the item
+    pub fn user_modules(&self) -> usize {
+        self.users.values().map(BTreeSet::len).sum()
+    }
+}
+
+/// One step of a re-export chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainHop {
+    pub krate: String,
+    /// Public path of the item in `krate`
+    pub path: String,
+    pub file: PathBuf,
+    pub line: usize,
+}
+
+impl fmt::Display for ChainHop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}::{}", self.krate, self.path)
+    }
+}
+
+/// An item re-exported through `pub use`, from its definition to the
+/// outermost crate exporting it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReExportChain {
+    /// Defining item first, final exporter last
+    pub hops: Vec<ChainHop>,
+}
+
+impl ReExportChain {
+    /// Crate where the chain starts
+    pub fn origin(&self) -> &ChainHop {
+        &self.hops[0]
+    }
+
+    /// Crate that exports the item last
+    pub fn exporter(&self) -> &ChainHop {
+        &self.hops[self.hops.len() - 1]
+    }
+
+    /// Number of crate boundaries the item crosses
+    pub fn crate_boundaries(&self) -> usize {
+        self.hops
+            .windows(2)
+            .filter(|pair| pair[0].krate != pair[1].krate)
+            .count()
+    }
+}
+
+impl fmt::Display for ReExportChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hops: Vec<String> = self.hops.iter().map(ToString::to_string).collect();
+        write!(f, "{}", hops.join(" -> "))
+    }
+}
+
+/// A public item naming a third-party type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExposingItem {
+    /// Public path of the item (`config::Config.value`)
+    pub path: String,
+    /// Third-party type as resolved (`serde_json::Value`)
+    pub type_path: String,
+    pub file: PathBuf,
+    pub line: usize,
+}
+
+/// Third-party types a crate exposes in its public API
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThirdPartyExposure {
+    /// Exposing workspace crate
+    pub krate: String,
+    /// Third-party crate whose types leak (as named in code, `serde_json`)
+    pub external: String,
+    /// Items sorted by path and type
+    pub items: Vec<ExposingItem>,
+}
+
+/// Public items of every analyzed crate
+#[derive(Debug, Clone, Default)]
+pub struct ApiSurface {
+    /// Items sorted by crate and path
+    pub items: Vec<ApiItem>,
+    /// Leaked third-party coupling, sorted by crate and third-party crate
+    pub third_party: Vec<ThirdPartyExposure>,
+    /// Crates no other workspace member depends on
+    pub roots: BTreeSet<String>,
+}
+
+impl ApiSurface {
+    /// Collect the public API and its downstream usage
+    pub fn build(metrics: &ProjectMetrics) -> Self {
+        let mut crates: BTreeMap<&str, BTreeMap<String, &ModuleMetrics>> = BTreeMap::new();
+        for module in metrics.modules.values() {
+            let module_path = module_path_from_file(&module.path);
+            // Only lib.rs roots a public API; main.rs and src/bin are binaries
+            if module_path.is_empty() && module.path.file_name().is_none_or(|f| f != "lib.rs") {
+                continue;
+            }
+            crates
+                .entry(module.crate_name.as_deref().unwrap_or(ROOT))
+                .or_default()
+                .insert(module_path, module);
+        }
+
+        let mut items = Vec::new();
+        let mut exposures: BTreeMap<(String, String), Vec<ExposingItem>> = BTreeMap::new();
+        for (krate, modules) in &crates {
+            let dependencies = third_party_crates(metrics, krate, modules);
+            for (module_path, module) in modules {
+                if is_reachable(modules, module_path) {
+                    collect_items(krate, module_path, module, &mut items);
+                    collect_exposures(krate, module_path, module, &dependencies, &mut exposures);
+                }
+            }
+        }
+        items.sort_by(|a, b| (&a.krate, &a.path).cmp(&(&b.krate, &b.path)));
+        for item in &mut items {
+            item.file = PathBuf::from(metrics.relative_path(&item.file));
+        }
+        let third_party = exposures
+            .into_iter()
+            .map(|((krate, external), mut items)| {
+                items.sort_by(|a, b| (&a.path, &a.type_path).cmp(&(&b.path, &b.type_path)));
+                for item in &mut items {
+                    item.file = PathBuf::from(metrics.relative_path(&item.file));
+                }
+                ThirdPartyExposure {
+                    krate,
+                    external,
+                    items,
+                }
+            })
+            .collect();
+
+        let roots = crates
+            .keys()
+            .filter(|krate| {
+                !metrics.crate_dependencies.iter().any(|(dependent, deps)| {
+                    dependent != *krate && deps.iter().any(|d| d == *krate)
+                })
+            })
+            .map(|krate| krate.to_string())
+            .collect();
+
+        let mut surface = Self {
+            items,
+            third_party,
+            roots,
+        };
+        surface.resolve_usage(metrics);
+        surface
+    }
+
+    /// Match `use` statements and qualified paths of other crates against
+    /// the public items
+    ///
+    /// A crate can only name itself from its binaries, so imports through
+    /// the crate's own name count as downstream usage by `<crate> (bin)`.
+    fn resolve_usage(&mut self, metrics: &ProjectMetrics) {
+        for module in metrics.modules.values() {
+            let user_crate = module.crate_name.as_deref().unwrap_or(ROOT);
+            let imports = module.use_paths.iter().map(|path| (path.as_str(), true));
+            let references = module
+                .item_dependencies
+                .iter()
+                .map(|d| d.target.as_str())
+                .chain(module.exposed_types.iter().map(|e| e.type_path.as_str()))
+                .chain(module.macro_paths.iter().map(String::as_str))
+                .map(|path| (path, false));
+            for (path, imported) in imports.chain(references) {
+                let Some((first, rest)) = path.split_once("::") else {
+                    continue;
+                };
+                let name = rest.rsplit("::").next().unwrap_or(rest);
+                if name == "*" || matches!(first, "crate" | "self" | "super") {
+                    continue;
+                }
+
+                let in_crate = |item: &ApiItem| item.krate.replace('-', "_") == first;
+                // `model::User::new` names `model::User`
+                let exact =
+                    std::iter::successors(Some(rest), |p| p.rsplit_once("::").map(|(p, _)| p))
+                        .find(|prefix| {
+                            self.items
+                                .iter()
+                                .any(|item| in_crate(item) && item.path == *prefix)
+                        });
+                if exact.is_none() && !imported {
+                    continue;
+                }
+                for item in self.items.iter_mut().filter(|item| {
+                    in_crate(item)
+                        && match exact {
+                            Some(prefix) => item.path == prefix,
+                            None => item.name == name,
+                        }
+                }) {
+                    let user = if item.krate == user_crate {
+                        format!("{} (bin)", user_crate)
+                    } else {
+                        user_crate.to_string()
+                    };
+                    item.users
+                        .entry(user)
+                        .or_default()
+                        .insert(module.name.clone());
+                }
+            }
+        }
+    }
+
+    /// Items of one crate
+    pub fn crate_items<'a>(&'a self, krate: &'a str) -> impl Iterator<Item = &'a ApiItem> + 'a {
+        self.items.iter().filter(move |item| item.krate == krate)
+    }
+
+    /// Items imported by at least one other workspace crate
+    pub fn used_downstream(&self) -> impl Iterator<Item = &ApiItem> {
+        self.items.iter().filter(|item| !item.users.is_empty())
+    }
+
+    /// Public items no other workspace crate uses and no root crate exports
+    ///
+    /// An item re-exported by a used or root re-export is used through it,
+    /// as is every item further down the re-export chain.
+    pub fn unused_surface(&self) -> Vec<&ApiItem> {
+        let mut live: BTreeSet<usize> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| !item.users.is_empty() || self.roots.contains(&item.krate))
+            .map(|(index, _)| index)
+            .collect();
+        for (index, item) in self.items.iter().enumerate() {
+            if item.kind != ApiItemKind::ReExport {
+                continue;
+            }
+            let mut hops = vec![index];
+            while let Some(next) = self.resolve_reexport(hops[hops.len() - 1]) {
+                if hops.contains(&next) {
+                    break;
+                }
+                hops.push(next);
+            }
+            if let Some(first_live) = hops.iter().position(|hop| live.contains(hop)) {
+                live.extend(hops[first_live..].iter().copied());
+            }
+        }
+
+        self.items
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !live.contains(index))
+            .map(|(_, item)| item)
+            .collect()
+    }
+
+    /// Re-export chains that cross at least one crate boundary
+    ///
+    /// Only the longest chain is returned: when `app` re-exports what
+    /// `service` re-exports from `core`, the `service` part is not reported
+    /// separately.
+    pub fn reexport_chains(&self) -> Vec<ReExportChain> {
+        let mut chains = Vec::new();
+        let mut inner = BTreeSet::new();
+        for (index, item) in self.items.iter().enumerate() {
+            if item.kind != ApiItemKind::ReExport {
+                continue;
+            }
+            let mut hops = vec![index];
+            while let Some(next) = self.resolve_reexport(hops[hops.len() - 1]) {
+                if hops.contains(&next) {
+                    break;
+                }
+                hops.push(next);
+            }
+            inner.extend(hops[1..].iter().copied());
+            chains.push(hops);
+        }
+
+        chains
+            .into_iter()
+            .filter(|hops| !inner.contains(&hops[0]))
+            .map(|hops| ReExportChain {
+                hops: hops
+                    .into_iter()
+                    .rev()
+                    .map(|index| {
+                        let item = &self.items[index];
+                        ChainHop {
+                            krate: item.krate.clone(),
+                            path: item.path.clone(),
+                            file: item.file.clone(),
+                            line: item.line,
+                        }
+                    })
+                    .collect(),
+            })
+            .filter(|chain| chain.crate_boundaries() > 0)
+            .collect()
+    }
+
+    /// Item a re-export names, if it is a public item of a workspace crate
+    fn resolve_reexport(&self, index: usize) -> Option<usize> {
+        let item = &self.items[index];
+        let source = item.source.as_deref()?;
+        let (first, rest) = source.split_once("::")?;
+        let module_path = item.path.rsplit_once("::").map_or("", |(m, _)| m);
+        let join = |prefix: &str, path: &str| {
+            if prefix.is_empty() {
+                path.to_string()
+            } else {
+                format!("{}::{}", prefix, path)
+            }
+        };
+
+        let (krate, path) =
+            match first {
+                "crate" => (item.krate.as_str(), rest.to_string()),
+                "self" => (item.krate.as_str(), join(module_path, rest)),
+                "super" => {
+                    let parent = module_path.rsplit_once("::").map_or("", |(p, _)| p);
+                    (item.krate.as_str(), join(parent, rest))
+                }
+                _ => match self.items.iter().find(|other| {
+                    other.krate != item.krate && other.krate.replace('-', "_") == first
+                }) {
+                    Some(other) => (other.krate.as_str(), rest.to_string()),
+                    // Edition 2018 paths are relative to the current module
+                    None => (item.krate.as_str(), join(module_path, source)),
+                },
+            };
+
+        let name = path.rsplit("::").next().unwrap_or(&path);
+        let candidates = || {
+            self.items
+                .iter()
+                .enumerate()
+                .filter(move |(i, other)| *i != index && other.krate == krate)
+        };
+        // Paths through glob re-exports only match by name
+        candidates()
+            .find(|(_, other)| other.path == path)
+            .or_else(|| candidates().find(|(_, other)| other.name == name))
+            .map(|(i, _)| i)
+    }
+}
+
+/// Whether every module on the way from the crate root is declared `pub`
+fn is_reachable(modules: &BTreeMap<String, &ModuleMetrics>, module_path: &str) -> bool {
+    if module_path.is_empty() {
+        return modules.contains_key("");
+    }
+    let (parent, name) = module_path.rsplit_once("::").unwrap_or(("", module_path));
+    modules
+        .get(parent)
+        .is_some_and(|p| p.public_modules.iter().any(|m| m == name))
+        && is_reachable(modules, parent)
+}
+
+fn collect_items(krate: &str, module_path: &str, module: &ModuleMetrics, items: &mut Vec<ApiItem>) {
+    let mut push = |name: &str, kind: ApiItemKind, line: usize, source: Option<&str>| {
+        items.push(ApiItem {
+            krate: krate.to_string(),
+            path: if module_path.is_empty() {
+                name.to_string()
+            } else {
+                format!("{}::{}", module_path, name)
+            },
+            name: name.to_string(),
+            kind,
+            file: module.path.clone(),
+            line,
+            source: source.map(str::to_string),
+            users: BTreeMap::new(),
+        });
+    };
+
+    for def in module.type_definitions.values() {
+        if def.visibility == Visibility::Public {
+            let kind = if def.is_trait {
+                ApiItemKind::Trait
+            } else {
+                ApiItemKind::Type
+            };
+            push(&def.name, kind, def.line, None);
+        }
+    }
+    for def in module.function_definitions.values() {
+        if def.visibility == Visibility::Public {
+            push(&def.name, ApiItemKind::Function, def.line, None);
+        }
+    }
+    for reexport in &module.reexports {
+        if reexport.name != "*" {
+            push(
+                &reexport.name,
+                ApiItemKind::ReExport,
+                reexport.line,
+                Some(&reexport.source),
+            );
+        }
+    }
+}
+
+/// Third-party crates of a workspace crate, with `-` replaced by `_` as in
+/// code
+fn third_party_crates(
+    metrics: &ProjectMetrics,
+    krate: &str,
+    modules: &BTreeMap<String, &ModuleMetrics>,
+) -> BTreeSet<String> {
+    let is_member = |name: &str| {
+        metrics
+            .workspace_members
+            .iter()
+            .any(|m| m.replace('-', "_") == name)
+    };
+    let declared = metrics.crate_dependencies.get(krate);
+    let candidates: Vec<String> = match declared {
+        Some(deps) => deps.iter().map(|d| d.replace('-', "_")).collect(),
+        // Without cargo metadata, fall back to the crates the code imports
+        None => modules
+            .values()
+            .flat_map(|m| &m.external_deps)
+            .map(|d| d.replace('-', "_"))
+            .collect(),
+    };
+    candidates
+        .into_iter()
+        .filter(|name| !STD_CRATES.contains(&name.as_str()) && !is_member(name))
+        .collect()
+}
+
+fn collect_exposures(
+    krate: &str,
+    module_path: &str,
+    module: &ModuleMetrics,
+    dependencies: &BTreeSet<String>,
+    exposures: &mut BTreeMap<(String, String), Vec<ExposingItem>>,
+) {
+    for exposed in &module.exposed_types {
+        let Some((first, _)) = exposed.type_path.split_once("::") else {
+            continue;
+        };
+        if !dependencies.contains(first) {
+            continue;
+        }
+        let items = exposures
+            .entry((krate.to_string(), first.to_string()))
+            .or_default();
+        let path = if module_path.is_empty() {
+            exposed.item.clone()
+        } else {
+            format!("{}::{}", module_path, exposed.item)
+        };
+        if !items
+            .iter()
+            .any(|i| i.path == path && i.type_path == exposed.type_path)
+        {
+            items.push(ExposingItem {
+                path,
+                type_path: exposed.type_path.clone(),
+                file: module.path.clone(),
+                line: exposed.line,
+            });
+        }
+    }
+}
+
+/// Generate the public API report to writer
+pub fn generate_api_report<W: Write>(surface: &ApiSurface, writer: &mut W) -> io::Result<()> {
+    writeln!(writer, "Public API Surface")?;
+    writeln!(writer, "==================")?;
+    writeln!(writer)?;
+
+    if surface.items.is_empty() {
+        writeln!(
+            writer,
+            "No public items found (binary-only crates have no API)."
+        )?;
+        return Ok(());
+    }
+
+    let crates: BTreeSet<&str> = surface.items.iter().map(|i| i.krate.as_str()).collect();
+    for krate in &crates {
+        let mut items: Vec<&ApiItem> = surface.crate_items(krate).collect();
+        items.sort_by(|a, b| {
+            b.user_modules()
+                .cmp(&a.user_modules())
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        let used = items.iter().filter(|i| !i.users.is_empty()).count();
+        writeln!(
+            writer,
+            "{} ({} public items, {} used downstream)",
+            krate,
+            items.len(),
+            used
+        )?;
+
+        let width = items
+            .iter()
+            .map(|i| i.path.len())
+            .max()
+            .unwrap_or(0)
+            .max("Item".len());
+        writeln!(writer, "  {:<width$}  {:<9}  Used by", "Item", "Kind")?;
+        for item in items {
+            let users = if item.users.is_empty() {
+                "-".to_string()
+            } else {
+                item.users
+                    .iter()
+                    .map(|(krate, modules)| format!("{} ({})", krate, modules.len()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            writeln!(
+                writer,
+                "  {:<width$}  {:<9}  {}",
+                item.path,
+                item.kind.to_string(),
+                users
+            )?;
+        }
+        writeln!(writer)?;
+    }
+
+    let chains = surface.reexport_chains();
+    if !chains.is_empty() {
+        writeln!(writer, "Re-export chains")?;
+        for chain in &chains {
+            writeln!(
+                writer,
+                "  {} ({} crate boundaries)",
+                chain,
+                chain.crate_boundaries()
+            )?;
+        }
+        writeln!(writer)?;
+    }
+
+    if !surface.third_party.is_empty() {
+        writeln!(writer, "Leaked third-party coupling")?;
+        for exposure in &surface.third_party {
+            let items: BTreeSet<&str> = exposure.items.iter().map(|i| i.path.as_str()).collect();
+            writeln!(
+                writer,
+                "  {} exposes {} in {} item(s):",
+                exposure.krate,
+                exposure.external,
+                items.len()
+            )?;
+            for item in &exposure.items {
+                writeln!(
+                    writer,
+                    "    {} ({}, {}:{})",
+                    item.path,
+                    item.type_path,
+                    item.file.display(),
+                    item.line
+                )?;
+            }
+        }
+        writeln!(writer)?;
+    }
+
+    let unused = surface.unused_surface();
+    if !unused.is_empty() {
+        writeln!(writer, "Unused public surface")?;
+        for item in &unused {
+            writeln!(
+                writer,
+                "  {}::{} ({}, {}:{})",
+                item.krate,
+                item.path,
+                item.kind,
+                item.file.display(),
+                item.line
+            )?;
+        }
+        writeln!(writer)?;
+    }
+
+    writeln!(
+        writer,
+        "{} crates, {} public items, {} used downstream (semver-sensitive), {} unused",
+        crates.len(),
+        surface.items.len(),
+        surface.used_downstream().count(),
+        unused.len()
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::{ItemDepType, ItemDependency, ItemKind};
+    use crate::metrics::{ExposedType, ReExport};
+    use std::path::Path;
+
+    fn module(path: &str, krate: &str) -> ModuleMetrics {
+        let mut module = ModuleMetrics::new(
+            PathBuf::from(path),
+            Path::new(path)
+                .file_stem()
+                .unwrap()
+                .to_string_lossy()
+                .into(),
+        );
+        module.crate_name = Some(krate.to_string());
+        module
+    }
+
+    fn workspace() -> ProjectMetrics {
+        let mut lib = module("/w/core/src/lib.rs", "my-core");
+        lib.public_modules = vec!["model".into()];
+        lib.declared_modules = vec!["model".into(), "internal".into()];
+        lib.reexports = vec![ReExport {
+            source: "crate::internal::Engine".into(),
+            name: "Engine".into(),
+            line: 4,
+        }];
+        lib.add_function_definition("init".into(), Visibility::Public);
+
+        let mut model = module("/w/core/src/model.rs", "my-core");
+        model.add_type_definition("User".into(), Visibility::Public, false);
+        model.add_type_definition("Repo".into(), Visibility::Public, true);
+        model.add_type_definition("Cache".into(), Visibility::PubCrate, false);
+
+        let mut internal = module("/w/core/src/internal.rs", "my-core");
+        internal.add_type_definition("Engine".into(), Visibility::Public, false);
+
+        let mut app = module("/w/app/src/main.rs", "app");
+        app.use_paths = vec![
+            "my_core::model::User".into(),
+            "my_core::Engine".into(),
+            "crate::cli::Args".into(),
+        ];
+        app.add_function_definition("run".into(), Visibility::Public);
+        let mut handlers = module("/w/app/src/handlers.rs", "app");
+        handlers.use_paths = vec!["my_core::model::User".into()];
+
+        let mut metrics = ProjectMetrics::new();
+        metrics.workspace_members = vec!["my-core".into(), "app".into()];
+        for m in [lib, model, internal, app, handlers] {
+            metrics.add_module(m);
+        }
+        metrics
+    }
+
+    #[test]
+    fn test_public_items() {
+        let surface = ApiSurface::build(&workspace());
+        let items: Vec<(&str, &str, ApiItemKind)> = surface
+            .items
+            .iter()
+            .map(|i| (i.krate.as_str(), i.path.as_str(), i.kind))
+            .collect();
+        // internal is private, Cache is pub(crate), app is a binary
+        assert_eq!(
+            items,
+            vec![
+                ("my-core", "Engine", ApiItemKind::ReExport),
+                ("my-core", "init", ApiItemKind::Function),
+                ("my-core", "model::Repo", ApiItemKind::Trait),
+                ("my-core", "model::User", ApiItemKind::Type),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_downstream_usage_and_report() {
+        let surface = ApiSurface::build(&workspace());
+        let user = surface
+            .items
+            .iter()
+            .find(|i| i.path == "model::User")
+            .unwrap();
+        assert_eq!(user.user_modules(), 2);
+        assert_eq!(surface.used_downstream().count(), 2);
+
+        let mut out = Vec::new();
+        generate_api_report(&surface, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("my-core (4 public items, 2 used downstream)"));
+        assert!(text.contains("  model::User  type       app (2)"));
+        assert!(text.contains("  init         fn         -"));
+        assert!(text.contains("1 crates, 4 public items, 2 used downstream"));
+        // my-core is the only library, so its whole API is intended
+        assert!(surface.unused_surface().is_empty());
+    }
+
+    #[test]
+    fn test_unused_surface() {
+        let mut metrics = workspace();
+        metrics
+            .crate_dependencies
+            .insert("app".into(), vec!["my-core".into()]);
+        let mut handlers = metrics.modules.remove("handlers").unwrap();
+        handlers.item_dependencies = vec![ItemDependency {
+            source_item: "handle".into(),
+            source_kind: ItemKind::Function,
+            target: "my_core::model::Repo::find".into(),
+            target_module: None,
+            dep_type: ItemDepType::FunctionCall,
+            line: 7,
+            column: 5,
+            expression: None,
+        }];
+        metrics.add_module(handlers);
+
+        let surface = ApiSurface::build(&metrics);
+        assert_eq!(surface.roots, BTreeSet::from(["app".to_string()]));
+        let unused: Vec<&str> = surface
+            .unused_surface()
+            .iter()
+            .map(|i| i.path.as_str())
+            .collect();
+        // Repo is used through a qualified call, Engine through its re-export
+        assert_eq!(unused, vec!["init"]);
+
+        let mut out = Vec::new();
+        generate_api_report(&surface, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(
+            text.contains("Unused public surface\n  my-core::init (fn, /w/core/src/lib.rs:0)\n")
+        );
+    }
+
+    #[test]
+    fn test_reexport_chains() {
+        let mut metrics = workspace();
+        let mut service = module("/w/service/src/lib.rs", "service");
+        service.name = "service".into();
+        service.reexports = vec![ReExport {
+            source: "my_core::model::User".into(),
+            name: "User".into(),
+            line: 1,
+        }];
+        let mut facade = module("/w/facade/src/lib.rs", "facade");
+        facade.name = "facade".into();
+        facade.reexports = vec![
+            ReExport {
+                source: "service::User".into(),
+                name: "Account".into(),
+                line: 2,
+            },
+            ReExport {
+                source: "std::fmt::Display".into(),
+                name: "Display".into(),
+                line: 3,
+            },
+        ];
+        metrics.add_module(service);
+        metrics.add_module(facade);
+
+        let chains = ApiSurface::build(&metrics).reexport_chains();
+        // my-core's own `pub use crate::internal::Engine` stays inside the crate
+        assert_eq!(chains.len(), 1);
+        let chain = &chains[0];
+        assert_eq!(
+            chain.to_string(),
+            "my-core::model::User -> service::User -> facade::Account"
+        );
+        assert_eq!(chain.crate_boundaries(), 2);
+        assert_eq!(chain.origin().line, 0);
+        assert_eq!(chain.exporter().line, 2);
+
+        let mut out = Vec::new();
+        generate_api_report(&ApiSurface::build(&metrics), &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains(
+            "  my-core::model::User -> service::User -> facade::Account (2 crate boundaries)"
+        ));
+    }
+
+    #[test]
+    fn test_third_party_exposure() {
+        let mut metrics = workspace();
+        metrics.crate_dependencies.insert(
+            "my-core".into(),
+            vec!["serde-json".into(), "app".into(), "tokio".into()],
+        );
+        let exposed = |item: &str, type_path: &str| ExposedType {
+            item: item.into(),
+            type_path: type_path.into(),
+            line: 3,
+        };
+        let mut model = metrics.modules.remove("model").unwrap();
+        model.exposed_types = vec![
+            exposed("User.raw", "serde_json::Value"),
+            exposed("User::load", "serde_json::Value"),
+            exposed("User::load", "std::io::Result"),
+            exposed("User::owner", "app::Account"),
+        ];
+        metrics.add_module(model);
+        let mut internal = metrics.modules.remove("internal").unwrap();
+        // Not part of the public API
+        internal.exposed_types = vec![exposed("Engine.rt", "tokio::runtime::Runtime")];
+        metrics.add_module(internal);
+
+        let surface = ApiSurface::build(&metrics);
+        assert_eq!(surface.third_party.len(), 1);
+        let exposure = &surface.third_party[0];
+        assert_eq!(
+            (exposure.krate.as_str(), exposure.external.as_str()),
+            ("my-core", "serde_json")
+        );
+        let paths: Vec<&str> = exposure.items.iter().map(|i| i.path.as_str()).collect();
+        assert_eq!(paths, vec!["model::User.raw", "model::User::load"]);
+
+        let mut out = Vec::new();
+        generate_api_report(&surface, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("  my-core exposes serde_json in 2 item(s):"));
+    }
+
+    #[test]
+    fn test_usage_across_members_with_lib_roots() {
+        let dir = crate::test_fixtures::two_lib_workspace();
+        let metrics = crate::analyzer::analyze_workspace(dir.path()).unwrap();
+        let surface = ApiSurface::build(&metrics);
+
+        let users = |path: &str| {
+            let item = surface
+                .items
+                .iter()
+                .find(|i| i.krate == "corelib" && i.path == path)
+                .unwrap();
+            item.users.keys().cloned().collect::<Vec<_>>()
+        };
+        assert_eq!(users("helper"), vec!["app"]);
+        assert_eq!(users("model::User"), vec!["app"]);
+        assert!(users("Kind").is_empty());
+    }
+
+    #[test]
+    fn test_unused_surface_with_lib_consumer() {
+        let dir = crate::test_fixtures::two_lib_workspace();
+        let metrics = crate::analyzer::analyze_workspace(dir.path()).unwrap();
+        let surface = ApiSurface::build(&metrics);
+        assert_eq!(surface.roots, BTreeSet::from(["app".to_string()]));
+        let unused: Vec<&str> = surface
+            .unused_surface()
+            .iter()
+            .map(|i| i.path.as_str())
+            .collect();
+        assert_eq!(unused, vec!["Kind", "unused"]);
+
+        let mut out = Vec::new();
+        generate_api_report(&surface, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("  corelib::unused (fn, "));
+        assert!(!text.contains("  corelib::helper (fn, "));
+        assert!(text.contains("corelib (4 public items, 2 used downstream)"));
+    }
+}
@@ -0,0 +1,990 @@
+//! Module depth metrics from "A Philosophy of Software Design"
+//!
+//! John Ousterhout argues that the best modules are *deep*: they provide a
+//! lot of functionality behind a simple interface. A *shallow* module has an
+//! interface that is almost as complex as its implementation, so it adds
+//! cognitive load without hiding much.
+//!
+//! We approximate:
+//! - **Interface complexity**: every item visible outside the module
+//!   (`pub` / `pub(crate)`) counts 1, plus one per function parameter and
+//!   one per public field
+//! - **Implementation complexity**: non-blank, non-comment lines of code
+//! - **Depth ratio**: implementation / interface
+//!
+//! Public items no other workspace crate uses (see
+//! [`ApiSurface::unused_surface`]) still count as interface. Their share is
+//! reported separately as unused interface, with the depth ratio the module
+//! would have without them.
+//!
+//! Public fields of structs other workspace crates build with struct
+//! literals (see [`crate::struct_literal`]) count twice: those crates read
+//! the fields and must also name every one of them to construct the
+//! struct, so a new field changes their code.
+//!
+//! Signature complexity is kept apart from both: it scores the generics of
+//! the public signatures (generic parameters, bounds, where-clause
+//! predicates, higher-ranked `for<'a>` bounds and nesting of generic types)
+//! that a caller must understand beyond the number of parameters.
+//! Lifetime leakage counts the public items whose signatures tie callers to
+//! the module's internal storage: functions and types naming two or more
+//! lifetimes, and `&self` methods returning borrowed data. Storing the data
+//! differently (owned copies, an `Arc`, a lock) then changes every caller.
+//!
+//! Cognitive load estimates how much a reader must know to work with a
+//! module: every externally visible item, every function parameter beyond
+//! the third, and every distinct dependency (internal or external) count 1.
+//!
+//! Traits get their own measure: the methods implementors must provide
+//! (required) against everything callers see (required and default
+//! methods). A trait with few required methods and many provided ones, like
+//! `Iterator`, is deep. A wide trait with many implementors is a costly
+//! coupling point: every new required method, or changed signature, has to
+//! be written again for each implementor.
+//!
+//! A default method most implementors override anyway is a false
+//! abstraction: the shared body fits few of the types, so each of them
+//! still writes its own and the trait only pretends to factor it out.
+//! Overrides are counted from the methods of the impl blocks across the
+//! workspace.
+//!
+//! Each module's depth also carries the length, nesting and branching of
+//! its functions (see [`crate::functions`]): a deep module hides its
+//! complexity, but a p95 function of 200 lines shows where it went.
+//!
+//! Doc comments make an interface usable without reading the code behind
+//! it. A module with an interface complexity of at least
+//! `non_obvious_interface_complexity` whose public items are mostly
+//! undocumented (see [`crate::doc_coverage`]) has a *non-obvious
+//! interface*, one of Ousterhout's red flags.
+//!
+//! *Temporal decomposition* splits code by when it runs instead of by what
+//! it knows: sibling modules named after ordered steps (`step1_read`,
+//! `phase_2`, `stage_emit`, `parse_then_check`), or after lifecycle phases
+//! (`load`, `validate`, `transform`, `write`, ...) that all work on the
+//! same types. Each phase then needs the knowledge of those types, so a
+//! change to them touches every phase.
+//!
+//! The cutoffs for all classifications come from [`AposdConfig`] (the
+//! `[aposd]` section of `.coupling.toml`), since what counts as deep or
+//! heavy depends on the size and style of a codebase.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::api::{ApiItem, ApiItemKind, ApiSurface};
+use crate::error_surface::MAX_ERROR_CASES;
+use crate::functions::FunctionDistribution;
+use crate::metrics::{ModuleMetrics, ProjectMetrics, TraitUse, Visibility};
+use crate::struct_literal::find_literal_structs;
+
+/// Default depth ratio at or above which a module is "very deep"
+pub const VERY_DEEP_RATIO: f64 = 10.0;
+/// Default depth ratio at or above which a module is "deep"
+pub const DEEP_RATIO: f64 = 5.0;
+/// Default depth ratio at or above which a module is "moderate"; below is shallow
+pub const MODERATE_RATIO: f64 = 2.0;
+
+/// Default cognitive load at or above which a module is "moderate"
+pub const MODERATE_COGNITIVE_LOAD: usize = 5;
+/// Default cognitive load at or above which a module is "high"
+pub const HIGH_COGNITIVE_LOAD: usize = 15;
+/// Default cognitive load at or above which a module is "very high"
+pub const VERY_HIGH_COGNITIVE_LOAD: usize = 30;
+
+/// Default method count from which a trait is "wide"
+pub const WIDE_TRAIT_METHODS: usize = 5;
+/// Default implementor count from which a wide trait is a costly coupling point
+pub const WIDE_TRAIT_IMPLEMENTORS: usize = 3;
+/// Overriding implementors from which an overridden default is reported
+pub const MIN_OVERRIDING_IMPLEMENTORS: usize = 2;
+/// Default interface complexity from which a module needs documentation
+pub const NON_OBVIOUS_INTERFACE_COMPLEXITY: usize = 20;
+/// Default share of documented public items below which a large interface
+/// is non-obvious
+pub const MIN_DOC_COVERAGE: f64 = 0.5;
+/// Lifecycle-named sibling modules from which shared types make them a
+/// temporal decomposition
+pub const MIN_LIFECYCLE_PHASES: usize = 3;
+
+/// Leading words of module names naming a lifecycle phase
+const LIFECYCLE_PHASES: &[&str] = &[
+    "init",
+    "setup",
+    "prepare",
+    "load",
+    "loading",
+    "read",
+    "reading",
+    "parse",
+    "parsing",
+    "validate",
+    "validation",
+    "check",
+    "transform",
+    "process",
+    "processing",
+    "modify",
+    "execute",
+    "run",
+    "render",
+    "emit",
+    "write",
+    "writing",
+    "save",
+    "finalize",
+    "cleanup",
+    "teardown",
+    "shutdown",
+];
+/// Prefixes of module names naming an ordered step
+const ORDERED_PHASES: &[&str] = &["step", "phase", "stage"];
+
+/// Classification cutoffs (the `[aposd]` config section)
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct AposdConfig {
+    /// Depth ratio at or above which a module is "very deep"
+    pub very_deep_ratio: f64,
+    /// Depth ratio at or above which a module is "deep"
+    pub deep_ratio: f64,
+    /// Depth ratio at or above which a module is "moderate"; below is shallow
+    pub moderate_ratio: f64,
+    /// Cognitive load at or above which a module is "moderate"
+    pub moderate_cognitive_load: usize,
+    /// Cognitive load at or above which a module is "high"
+    pub high_cognitive_load: usize,
+    /// Cognitive load at or above which a module is "very high"
+    pub very_high_cognitive_load: usize,
+    /// Methods (required and default) from which a trait is "wide"
+    pub wide_trait_methods: usize,
+    /// Implementors from which a wide trait is a costly coupling point
+    pub wide_trait_implementors: usize,
+    /// Interface complexity from which a module needs documentation
+    pub non_obvious_interface_complexity: usize,
+    /// Share of documented public items below which a large interface is
+    /// non-obvious
+    pub min_doc_coverage: f64,
+    /// Error cases of a module's public functions from which callers
+    /// handle too many (see [`crate::error_surface`])
+    pub max_error_cases: usize,
+}
+
+impl Default for AposdConfig {
+    fn default() -> Self {
+        Self {
+            very_deep_ratio: VERY_DEEP_RATIO,
+            deep_ratio: DEEP_RATIO,
+            moderate_ratio: MODERATE_RATIO,
+            moderate_cognitive_load: MODERATE_COGNITIVE_LOAD,
+            high_cognitive_load: HIGH_COGNITIVE_LOAD,
+            very_high_cognitive_load: VERY_HIGH_COGNITIVE_LOAD,
+            wide_trait_methods: WIDE_TRAIT_METHODS,
+            wide_trait_implementors: WIDE_TRAIT_IMPLEMENTORS,
+            non_obvious_interface_complexity: NON_OBVIOUS_INTERFACE_COMPLEXITY,
+            min_doc_coverage: MIN_DOC_COVERAGE,
+            max_error_cases: MAX_ERROR_CASES,
+        }
+    }
+}
+
+/// Classification of a module by depth ratio
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DepthClass {
+    VeryDeep,
+    Deep,
+    Moderate,
+    Shallow,
+}
+
+impl DepthClass {
+    /// Classify a depth ratio with the default cutoffs
+    pub fn from_ratio(ratio: f64) -> Self {
+        Self::classify(ratio, &AposdConfig::default())
+    }
+
+    /// Classify a depth ratio with configured cutoffs
+    pub fn classify(ratio: f64, config: &AposdConfig) -> Self {
+        if ratio >= config.very_deep_ratio {
+            DepthClass::VeryDeep
+        } else if ratio >= config.deep_ratio {
+            DepthClass::Deep
+        } else if ratio >= config.moderate_ratio {
+            DepthClass::Moderate
+        } else {
+            DepthClass::Shallow
+        }
+    }
+}
+
+impl std::fmt::Display for DepthClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DepthClass::VeryDeep => write!(f, "Very Deep"),
+            DepthClass::Deep => write!(f, "Deep"),
+            DepthClass::Moderate => write!(f, "Moderate"),
+            DepthClass::Shallow => write!(f, "Shallow"),
+        }
+    }
+}
+
+/// Classification of a module by cognitive load
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum CognitiveLoadClass {
+    Low,
+    Moderate,
+    High,
+    VeryHigh,
+}
+
+impl CognitiveLoadClass {
+    /// Classify a cognitive load score with configured cutoffs
+    pub fn classify(load: usize, config: &AposdConfig) -> Self {
+        if load >= config.very_high_cognitive_load {
+            CognitiveLoadClass::VeryHigh
+        } else if load >= config.high_cognitive_load {
+            CognitiveLoadClass::High
+        } else if load >= config.moderate_cognitive_load {
+            CognitiveLoadClass::Moderate
+        } else {
+            CognitiveLoadClass::Low
+        }
+    }
+}
+
+impl std::fmt::Display for CognitiveLoadClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CognitiveLoadClass::Low => write!(f, "Low"),
+            CognitiveLoadClass::Moderate => write!(f, "Moderate"),
+            CognitiveLoadClass::High => write!(f, "High"),
+            CognitiveLoadClass::VeryHigh => write!(f, "Very High"),
+        }
+    }
+}
+
+/// Depth metrics for a single module
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModuleDepth {
+    pub module: String,
+    pub interface_complexity: usize,
+    pub implementation_complexity: usize,
+    pub depth_ratio: f64,
+    pub class: DepthClass,
+    pub cognitive_load: usize,
+    pub cognitive_class: CognitiveLoadClass,
+    /// Generic complexity of the public signatures (see module docs)
+    #[serde(default)]
+    pub signature_complexity: usize,
+    /// Public items leaking lifetimes (see module docs)
+    #[serde(default)]
+    pub lifetime_leaks: usize,
+    /// Part of the interface complexity no other workspace crate uses
+    #[serde(default)]
+    pub unused_interface: usize,
+    /// Part of the interface complexity added by fields other crates set
+    /// in struct literals (see module docs)
+    #[serde(default)]
+    pub literal_interface: usize,
+    /// Length, nesting and branching of the module's functions
+    #[serde(default)]
+    pub function_shape: FunctionDistribution,
+    /// Share of public items with a doc comment
+    #[serde(default)]
+    pub doc_coverage: Option<f64>,
+}
+
+impl ModuleDepth {
+    /// Depth ratio counting only the interface the workspace uses, if the
+    /// module has unused interface and some used interface
+    pub fn used_depth_ratio(&self) -> Option<f64> {
+        let used = self
+            .interface_complexity
+            .saturating_sub(self.unused_interface);
+        (self.unused_interface > 0 && used > 0)
+            .then(|| self.implementation_complexity as f64 / used as f64)
+    }
+
+    /// A large interface with few doc comments (see [`AposdConfig`])
+    pub fn is_non_obvious(&self, config: &AposdConfig) -> bool {
+        self.interface_complexity >= config.non_obvious_interface_complexity
+            && self
+                .doc_coverage
+                .is_some_and(|coverage| coverage < config.min_doc_coverage)
+    }
+}
+
+/// Interface complexity of a module (see module docs)
+pub fn interface_complexity(module: &ModuleMetrics) -> usize {
+    let functions: usize = module
+        .function_definitions
+        .values()
+        .filter(|f| f.visibility.allows_external_access())
+        .map(|f| 1 + f.param_count)
+        .sum();
+    let types: usize = module
+        .type_definitions
+        .values()
+        .filter(|t| t.visibility.allows_external_access())
+        .map(|t| 1 + t.public_field_count)
+        .sum();
+    functions + types
+}
+
+/// Interface complexity `items` of `module` add (public items of a
+/// module's file; re-exports add none)
+pub fn item_interface_complexity(module: &ModuleMetrics, items: &[&ApiItem]) -> usize {
+    items
+        .iter()
+        .filter(|item| item.file == module.path)
+        .map(|item| match item.kind {
+            ApiItemKind::Function => module
+                .function_definitions
+                .get(&item.name)
+                .map_or(1, |f| 1 + f.param_count),
+            ApiItemKind::Type | ApiItemKind::Trait => module
+                .type_definitions
+                .get(&item.name)
+                .map_or(1, |t| 1 + t.public_field_count),
+            ApiItemKind::ReExport => 0,
+        })
+        .sum()
+}
+
+/// Cognitive load of a module (see module docs)
+pub fn cognitive_load(module: &ModuleMetrics) -> usize {
+    let functions: usize = module
+        .function_definitions
+        .values()
+        .filter(|f| f.visibility.allows_external_access())
+        .map(|f| 1 + f.param_count.saturating_sub(3))
+        .sum();
+    let types = module
+        .type_definitions
+        .values()
+        .filter(|t| t.visibility.allows_external_access())
+        .count();
+    functions + types + module.internal_deps.len() + module.external_deps.len()
+}
+
+/// Depth of a module with the default cutoffs, or `None` if it exposes no
+/// interface
+pub fn module_depth(module: &ModuleMetrics) -> Option<ModuleDepth> {
+    module_depth_with(module, &AposdConfig::default())
+}
+
+/// Depth of a module with configured cutoffs, or `None` if it exposes no
+/// interface
+pub fn module_depth_with(module: &ModuleMetrics, config: &AposdConfig) -> Option<ModuleDepth> {
+    let interface = interface_complexity(module);
+    if interface == 0 {
+        return None;
+    }
+    let ratio = module.lines_of_code as f64 / interface as f64;
+    let load = cognitive_load(module);
+    Some(ModuleDepth {
+        module: module.name.clone(),
+        interface_complexity: interface,
+        implementation_complexity: module.lines_of_code,
+        depth_ratio: ratio,
+        class: DepthClass::classify(ratio, config),
+        cognitive_load: load,
+        cognitive_class: CognitiveLoadClass::classify(load, config),
+        signature_complexity: module.signature_complexity,
+        lifetime_leaks: module.lifetime_leaks.len(),
+        unused_interface: 0,
+        literal_interface: 0,
+        function_shape: FunctionDistribution::of(&module.function_stats),
+        doc_coverage: module.doc_coverage.ratio(),
+    })
+}
+
+/// Depth of every module with a public interface (default cutoffs), sorted
+/// by module name
+pub fn analyze_module_depths(metrics: &ProjectMetrics) -> Vec<ModuleDepth> {
+    analyze_module_depths_with(metrics, &AposdConfig::default())
+}
+
+/// Depth of every module with a public interface (configured cutoffs),
+/// sorted by module name
+pub fn analyze_module_depths_with(
+    metrics: &ProjectMetrics,
+    config: &AposdConfig,
+) -> Vec<ModuleDepth> {
+    let surface = ApiSurface::build(metrics);
+    let unused = surface.unused_surface();
+    let mut literal_fields: BTreeMap<String, usize> = BTreeMap::new();
+    for found in find_literal_structs(metrics) {
+        *literal_fields.entry(found.module).or_default() += found.fields;
+    }
+    let mut depths: Vec<ModuleDepth> = metrics
+        .modules
+        .values()
+        .filter_map(|m| {
+            let mut depth = module_depth_with(m, config)?;
+            depth.unused_interface = item_interface_complexity(m, &unused);
+            if let Some(&fields) = literal_fields.get(&m.name) {
+                depth.literal_interface = fields;
+                depth.interface_complexity += fields;
+                depth.depth_ratio =
+                    depth.implementation_complexity as f64 / depth.interface_complexity as f64;
+                depth.class = DepthClass::classify(depth.depth_ratio, config);
+            }
+            Some(depth)
+        })
+        .collect();
+    depths.sort_by(|a, b| a.module.cmp(&b.module));
+    depths
+}
+
+/// Method surface of a public trait and how widely it is implemented
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraitSurface {
+    /// Defining crate (if known from cargo metadata)
+    pub krate: Option<String>,
+    /// Defining module
+    pub module: String,
+    pub name: String,
+    /// Methods every implementor must write
+    pub required_methods: usize,
+    /// Methods with a default body
+    pub default_methods: usize,
+    /// Required methods / all methods: low for deep traits
+    pub interface_ratio: f64,
+    /// Implementing types across the workspace
+    pub implementors: usize,
+    /// Wide and implemented often (see [`AposdConfig`])
+    pub high_cost: bool,
+}
+
+impl TraitSurface {
+    /// Methods callers see
+    pub fn methods(&self) -> usize {
+        self.required_methods + self.default_methods
+    }
+
+    /// Method bodies implementors had to write: required methods times
+    /// implementors
+    pub fn cost(&self) -> usize {
+        self.required_methods * self.implementors
+    }
+}
+
+/// Surface of every public trait with methods, costliest first
+///
+/// Implementors are the impls anywhere in the workspace whose trait path
+/// names the trait: by its crate's name from other crates, or through
+/// `crate::`, `self::`, `super::` or a bare name inside the defining crate.
+pub fn analyze_trait_surfaces(metrics: &ProjectMetrics, config: &AposdConfig) -> Vec<TraitSurface> {
+    let mut surfaces: Vec<TraitSurface> = Vec::new();
+    for module in metrics.modules.values() {
+        let krate = module.crate_name.as_deref();
+        for def in module.type_definitions.values() {
+            let methods = def.required_methods + def.default_methods;
+            if !def.is_trait || def.visibility != Visibility::Public || methods == 0 {
+                continue;
+            }
+            let mut implementors: Vec<(Option<&str>, &str)> =
+                trait_impls(metrics, &def.name, krate)
+                    .map(|(impl_crate, i)| (impl_crate, i.item.as_str()))
+                    .collect();
+            implementors.sort();
+            implementors.dedup();
+            surfaces.push(TraitSurface {
+                krate: krate.map(str::to_string),
+                module: module.name.clone(),
+                name: def.name.clone(),
+                required_methods: def.required_methods,
+                default_methods: def.default_methods,
+                interface_ratio: def.required_methods as f64 / methods as f64,
+                implementors: implementors.len(),
+                high_cost: methods >= config.wide_trait_methods
+                    && implementors.len() >= config.wide_trait_implementors,
+            });
+        }
+    }
+    surfaces.sort_by(|a, b| {
+        b.cost()
+            .cmp(&a.cost())
+            .then_with(|| a.module.cmp(&b.module))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    surfaces
+}
+
+/// A trait default method that most implementors override
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OverriddenDefault {
+    /// Defining crate (if known from cargo metadata)
+    pub krate: Option<String>,
+    /// Defining module
+    pub module: String,
+    /// Trait defining the default method
+    pub trait_name: String,
+    pub method: String,
+    /// Implementing types writing their own body
+    pub overrides: usize,
+    /// Implementing types across the workspace
+    pub implementors: usize,
+}
+
+impl OverriddenDefault {
+    /// Whether no implementor uses the default body
+    pub fn never_used(&self) -> bool {
+        self.overrides == self.implementors
+    }
+}
+
+/// Default methods of public traits overridden by more than half of the
+/// implementors (and at least [`MIN_OVERRIDING_IMPLEMENTORS`]), most
+/// overridden first
+///
+/// Implementors are found as in [`analyze_trait_surfaces`]; a type
+/// overrides a method if any of its impls of the trait defines it.
+pub fn find_overridden_defaults(metrics: &ProjectMetrics) -> Vec<OverriddenDefault> {
+    let mut found = Vec::new();
+    for module in metrics.modules.values() {
+        let krate = module.crate_name.as_deref();
+        for def in module.type_definitions.values() {
+            if !def.is_trait
+                || def.visibility != Visibility::Public
+                || def.default_method_names.is_empty()
+            {
+                continue;
+            }
+            // Methods defined per implementing type
+            let mut implementors: BTreeMap<(Option<&str>, &str), BTreeSet<&str>> = BTreeMap::new();
+            for (impl_crate, trait_use) in trait_impls(metrics, &def.name, krate) {
+                implementors
+                    .entry((impl_crate, trait_use.item.as_str()))
+                    .or_default()
+                    .extend(trait_use.methods.iter().map(String::as_str));
+            }
+            for method in &def.default_method_names {
+                let overrides = implementors
+                    .values()
+                    .filter(|methods| methods.contains(method.as_str()))
+                    .count();
+                if overrides >= MIN_OVERRIDING_IMPLEMENTORS && overrides * 2 > implementors.len() {
+                    found.push(OverriddenDefault {
+                        krate: krate.map(str::to_string),
+                        module: module.name.clone(),
+                        trait_name: def.name.clone(),
+                        method: method.clone(),
+                        overrides,
+                        implementors: implementors.len(),
+                    });
+                }
+            }
+        }
+    }
+    found.sort_by(|a, b| {
+        b.overrides
+            .cmp(&a.overrides)
+            .then_with(|| a.module.cmp(&b.module))
+            .then_with(|| a.trait_name.cmp(&b.trait_name))
+            .then_with(|| a.method.cmp(&b.method))
+    });
+    found
+}
+
+/// Sibling modules organized by execution phase
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemporalDecomposition {
+    /// Crate of the modules (if known from cargo metadata)
+    pub krate: Option<String>,
+    /// Rust path of the module containing the phases (`my_crate::pipeline`)
+    pub parent: String,
+    /// Names of the phase modules, sorted
+    pub phases: Vec<String>,
+    /// Types of the crate that every phase module defines or uses
+    pub shared_types: Vec<String>,
+}
+
+/// Groups of sibling modules named after execution phases, most shared
+/// types first
+///
+/// A group is reported when two or more of its modules name ordered steps,
+/// one names a sequence (`_then_`), or [`MIN_LIFECYCLE_PHASES`] or more
+/// lifecycle phases share a type. Types are matched by name, so names
+/// defined more than once in a crate are left out.
+pub fn find_temporal_decompositions(metrics: &ProjectMetrics) -> Vec<TemporalDecomposition> {
+    // Structs and enums by crate, with the number of defining modules
+    let mut types: HashMap<(Option<&str>, &str), usize> = HashMap::new();
+    for module in metrics.modules.values() {
+        for def in module.type_definitions.values().filter(|d| !d.is_trait) {
+            *types
+                .entry((module.crate_name.as_deref(), def.name.as_str()))
+                .or_default() += 1;
+        }
+    }
+
+    // Phase modules by parent path, which starts with the crate name
+    let mut groups: BTreeMap<String, Vec<(String, &ModuleMetrics)>> = BTreeMap::new();
+    for module in metrics.modules.values() {
+        let path = module.rust_path();
+        let Some((parent, leaf)) = path.rsplit_once("::") else {
+            continue;
+        };
+        if is_ordered_phase(leaf) || is_lifecycle_phase(leaf) {
+            groups
+                .entry(parent.to_string())
+                .or_default()
+                .push((leaf.to_string(), module));
+        }
+    }
+
+    let mut found = Vec::new();
+    for (parent, mut phases) in groups {
+        phases.sort_by(|a, b| a.0.cmp(&b.0));
+        let krate = phases[0].1.crate_name.as_deref();
+        let ordered = phases
+            .iter()
+            .filter(|(leaf, _)| is_ordered_phase(leaf))
+            .count();
+        let sequence = phases.iter().any(|(leaf, _)| leaf.contains("_then_"));
+
+        let mut shared_types: Option<BTreeSet<&str>> = None;
+        for (_, module) in &phases {
+            let known: BTreeSet<&str> = module
+                .type_definitions
+                .keys()
+                .map(String::as_str)
+                .chain(
+                    module
+                        .referenced_paths()
+                        .filter_map(|path| path.rsplit("::").next()),
+                )
+                .filter(|name| types.get(&(krate, *name)) == Some(&1))
+                .collect();
+            shared_types = Some(match shared_types {
+                Some(shared) => shared.intersection(&known).copied().collect(),
+                None => known,
+            });
+        }
+        let shared_types: Vec<String> = shared_types
+            .unwrap_or_default()
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+
+        if ordered >= 2
+            || sequence
+            || (phases.len() >= MIN_LIFECYCLE_PHASES && !shared_types.is_empty())
+        {
+            found.push(TemporalDecomposition {
+                krate: krate.map(str::to_string),
+                parent,
+                phases: phases.into_iter().map(|(leaf, _)| leaf).collect(),
+                shared_types,
+            });
+        }
+    }
+    found.sort_by(|a, b| {
+        b.shared_types
+            .len()
+            .cmp(&a.shared_types.len())
+            .then_with(|| b.phases.len().cmp(&a.phases.len()))
+            .then_with(|| a.parent.cmp(&b.parent))
+    });
+    found
+}
+
+/// Whether a module name names an ordered step (`step1_read`, `phase_2`,
+/// `parse_then_check`)
+fn is_ordered_phase(name: &str) -> bool {
+    name.contains("_then_")
+        || ORDERED_PHASES.iter().any(|prefix| {
+            name.strip_prefix(prefix)
+                .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit() || c == '_'))
+        })
+}
+
+/// Whether the first word of a module name is a lifecycle phase
+fn is_lifecycle_phase(name: &str) -> bool {
+    name.split('_')
+        .next()
+        .is_some_and(|word| LIFECYCLE_PHASES.contains(&word))
+}
+
+/// Impls anywhere in the workspace of trait `name` defined in `krate`, with
+/// the implementing crate
+fn trait_impls<'a>(
+    metrics: &'a ProjectMetrics,
+    name: &'a str,
+    krate: Option<&'a str>,
+) -> impl Iterator<Item = (Option<&'a str>, &'a TraitUse)> {
+    metrics.modules.values().flat_map(move |m| {
+        m.trait_impls
+            .iter()
+            .filter(move |i| implements(i, m.crate_name.as_deref(), name, krate))
+            .map(move |i| (m.crate_name.as_deref(), i))
+    })
+}
+
+/// Whether an impl in `impl_crate` is of trait `name` defined in `krate`
+fn implements(
+    trait_use: &TraitUse,
+    impl_crate: Option<&str>,
+    name: &str,
+    krate: Option<&str>,
+) -> bool {
+    let path = &trait_use.trait_path;
+    let first = path.split("::").next().unwrap_or_default();
+    if path.rsplit("::").next() != Some(name) {
+        return false;
+    }
+    if first == path || matches!(first, "crate" | "self" | "super") {
+        impl_crate == krate
+    } else {
+        krate.is_some_and(|k| k.replace('-', "_") == first)
+    }
+}
+
+/// Average depth ratio across modules
+pub fn average_depth_ratio(depths: &[ModuleDepth]) -> Option<f64> {
+    if depths.is_empty() {
+        None
+    } else {
+        Some(depths.iter().map(|d| d.depth_ratio).sum::<f64>() / depths.len() as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_depth_classification() {
+        assert_eq!(DepthClass::from_ratio(12.0), DepthClass::VeryDeep);
+        assert_eq!(DepthClass::from_ratio(5.0), DepthClass::Deep);
+        assert_eq!(DepthClass::from_ratio(2.5), DepthClass::Moderate);
+        assert_eq!(DepthClass::from_ratio(1.0), DepthClass::Shallow);
+
+        let strict = AposdConfig {
+            moderate_ratio: 3.0,
+            ..AposdConfig::default()
+        };
+        assert_eq!(DepthClass::classify(2.5, &strict), DepthClass::Shallow);
+    }
+
+    #[test]
+    fn test_cognitive_load() {
+        let mut module = ModuleMetrics::new(PathBuf::from("src/a.rs"), "a".to_string());
+        module.add_function_definition_full(
+            "run".to_string(),
+            Visibility::Public,
+            5,
+            0,
+            Vec::new(),
+        );
+        module.add_type_definition("Config".to_string(), Visibility::Public, false);
+        module.internal_deps = vec!["crate::b".into(), "crate::c".into()];
+        module.external_deps = vec!["serde".into()];
+
+        // run (1 + 2 extra params) + Config + 3 dependencies
+        assert_eq!(cognitive_load(&module), 7);
+
+        let config = AposdConfig::default();
+        assert_eq!(
+            CognitiveLoadClass::classify(7, &config),
+            CognitiveLoadClass::Moderate
+        );
+        assert_eq!(
+            CognitiveLoadClass::classify(30, &config),
+            CognitiveLoadClass::VeryHigh
+        );
+    }
+
+    #[test]
+    fn test_module_depth() {
+        let mut module = ModuleMetrics::new(PathBuf::from("src/a.rs"), "a".to_string());
+        module.add_function_definition("private".to_string(), Visibility::Private);
+        assert!(module_depth(&module).is_none());
+
+        module.add_function_definition_full(
+            "run".to_string(),
+            Visibility::Public,
+            2,
+            0,
+            Vec::new(),
+        );
+        module.add_type_definition("Config".to_string(), Visibility::PubCrate, false);
+        module.lines_of_code = 40;
+
+        let depth = module_depth(&module).unwrap();
+        assert_eq!(depth.interface_complexity, 4);
+        assert_eq!(depth.depth_ratio, 10.0);
+        assert_eq!(depth.class, DepthClass::VeryDeep);
+    }
+
+    #[test]
+    fn test_unused_interface() {
+        let mut lib = ModuleMetrics::new(PathBuf::from("core/src/lib.rs"), "lib".to_string());
+        lib.crate_name = Some("core".into());
+        lib.add_function_definition_full("parse".into(), Visibility::Public, 1, 0, Vec::new());
+        lib.add_function_definition_full("legacy".into(), Visibility::Public, 3, 0, Vec::new());
+        lib.lines_of_code = 60;
+        let mut main = ModuleMetrics::new(PathBuf::from("app/src/main.rs"), "main".to_string());
+        main.crate_name = Some("app".into());
+        main.use_paths = vec!["core::parse".into()];
+
+        let mut metrics = ProjectMetrics::new();
+        metrics.add_module(lib);
+        metrics.add_module(main);
+        metrics
+            .crate_dependencies
+            .insert("app".into(), vec!["core".into()]);
+
+        let depths = analyze_module_depths(&metrics);
+        assert_eq!(depths.len(), 1);
+        // parse (1 + 1) + legacy (1 + 3), legacy unused
+        assert_eq!(depths[0].interface_complexity, 6);
+        assert_eq!(depths[0].unused_interface, 4);
+        assert_eq!(depths[0].depth_ratio, 10.0);
+        assert_eq!(depths[0].used_depth_ratio(), Some(30.0));
+    }
+
+    #[test]
+    fn test_trait_surfaces() {
+        let mut lib = ModuleMetrics::new(PathBuf::from("core/src/store.rs"), "store".to_string());
+        lib.crate_name = Some("my-core".into());
+        lib.add_type_definition("Store".into(), Visibility::Public, true);
+        lib.add_type_definition("Named".into(), Visibility::Public, true);
+        lib.add_type_definition("Marker".into(), Visibility::Public, true);
+        let store = lib.type_definitions.get_mut("Store").unwrap();
+        (store.required_methods, store.default_methods) = (4, 2);
+        lib.type_definitions
+            .get_mut("Named")
+            .unwrap()
+            .required_methods = 1;
+        lib.trait_impls = vec![TraitUse {
+            trait_path: "Store".into(),
+            item: "MemoryStore".into(),
+            line: 1,
+            methods: Vec::new(),
+        }];
+        let mut app = ModuleMetrics::new(PathBuf::from("app/src/db.rs"), "db".to_string());
+        app.crate_name = Some("app".into());
+        app.trait_impls = ["my_core::Store", "my_core::store::Store", "other::Store"]
+            .iter()
+            .zip(["Postgres", "Sqlite", "Foreign"])
+            .map(|(path, item)| TraitUse {
+                trait_path: path.to_string(),
+                item: item.into(),
+                line: 1,
+                methods: Vec::new(),
+            })
+            .collect();
+
+        let mut metrics = ProjectMetrics::new();
+        metrics.add_module(lib);
+        metrics.add_module(app);
+
+        let surfaces = analyze_trait_surfaces(&metrics, &AposdConfig::default());
+        // Marker traits have no surface
+        assert_eq!(surfaces.len(), 2);
+        assert_eq!(surfaces[0].name, "Store");
+        assert_eq!(surfaces[0].implementors, 3);
+        assert_eq!(surfaces[0].cost(), 12);
+        assert!((surfaces[0].interface_ratio - 4.0 / 6.0).abs() < 1e-9);
+        assert!(surfaces[0].high_cost);
+        assert_eq!(surfaces[1].name, "Named");
+        assert!(!surfaces[1].high_cost);
+    }
+
+    #[test]
+    fn test_overridden_defaults() {
+        let module = |name: &str, code: &str| {
+            let mut analyzer = crate::analyzer::CouplingAnalyzer::new(
+                name.to_string(),
+                PathBuf::from(format!("src/{name}.rs")),
+            );
+            analyzer.analyze_file(code).unwrap();
+            analyzer.metrics
+        };
+        let mut metrics = ProjectMetrics::new();
+        metrics.add_module(module(
+            "codec",
+            "pub trait Encoder {
+                 fn encode(&self) -> Vec<u8>;
+                 fn content_type(&self) -> &str { \"application/json\" }
+                 fn flush(&self) {}
+                 fn name(&self) -> &str { \"encoder\" }
+             }",
+        ));
+        metrics.add_module(module(
+            "formats",
+            "use crate::codec::Encoder;
+             impl Encoder for Json { fn encode(&self) -> Vec<u8> { vec![] } }
+             impl Encoder for Cbor {
+                 fn encode(&self) -> Vec<u8> { vec![] }
+                 fn content_type(&self) -> &str { \"application/cbor\" }
+                 fn name(&self) -> &str { \"cbor\" }
+             }
+             impl Encoder for Yaml {
+                 fn encode(&self) -> Vec<u8> { vec![] }
+                 fn content_type(&self) -> &str { \"application/yaml\" }
+             }",
+        ));
+
+        let found = find_overridden_defaults(&metrics);
+        // name is overridden by one of three implementors, flush by none
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].trait_name, "Encoder");
+        assert_eq!(found[0].method, "content_type");
+        assert_eq!((found[0].overrides, found[0].implementors), (2, 3));
+        assert!(!found[0].never_used());
+    }
+
+    #[test]
+    fn test_temporal_decompositions() {
+        let module = |path: &str, code: &str| {
+            let name = path.rsplit('/').next().unwrap().trim_end_matches(".rs");
+            let mut analyzer =
+                crate::analyzer::CouplingAnalyzer::new(name.to_string(), PathBuf::from(path));
+            analyzer.analyze_file(code).unwrap();
+            analyzer.metrics
+        };
+        let mut metrics = ProjectMetrics::new();
+        metrics.add_module(module(
+            "src/model.rs",
+            "pub struct Config; pub struct Report;",
+        ));
+        metrics.add_module(module(
+            "src/config/load.rs",
+            "use crate::model::Config; pub fn load() -> Config { Config }",
+        ));
+        metrics.add_module(module(
+            "src/config/validate.rs",
+            "use crate::model::{Config, Report}; pub fn validate(c: &Config) -> Report { Report }",
+        ));
+        metrics.add_module(module(
+            "src/config/write.rs",
+            "use crate::model::Config; pub fn write(c: &Config) {}",
+        ));
+        metrics.add_module(module("src/build/step1_fetch.rs", "pub fn fetch() {}"));
+        metrics.add_module(module("src/build/step2_compile.rs", "pub fn compile() {}"));
+        // Lifecycle names without shared types are not reported
+        metrics.add_module(module("src/net/read_frame.rs", "pub fn read() {}"));
+        metrics.add_module(module("src/net/write_frame.rs", "pub fn write() {}"));
+        metrics.add_module(module("src/net/process_frame.rs", "pub fn process() {}"));
+
+        let found = find_temporal_decompositions(&metrics);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].parent, "crate::config");
+        assert_eq!(found[0].phases, vec!["load", "validate", "write"]);
+        assert_eq!(found[0].shared_types, vec!["Config"]);
+        assert_eq!(found[1].parent, "crate::build");
+        assert_eq!(found[1].phases, vec!["step1_fetch", "step2_compile"]);
+        assert!(found[1].shared_types.is_empty());
+
+        assert!(is_ordered_phase("parse_then_check"));
+        assert!(is_ordered_phase("phase_2"));
+        assert!(!is_ordered_phase("stages"));
+        assert!(is_lifecycle_phase("parse_args"));
+        assert!(!is_lifecycle_phase("parser"));
+    }
+}
@@ -0,0 +1,209 @@
+//! README badges
+//!
+//! Renders the project health grade (see [`crate::health`]) or the average
+//! module depth ratio (see [`crate::aposd`]) as a flat SVG badge, or as
+//! the JSON a shields.io [endpoint badge] reads.
+//!
+//! [endpoint badge]: https://shields.io/badges/endpoint-badge
+
+use std::io::{self, Write};
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::aposd::{AposdConfig, DepthClass};
+use crate::balance::HealthGrade;
+use crate::health::HealthScore;
+
+/// Approximate width of a character in the 11px badge font
+const CHAR_WIDTH: f64 = 6.5;
+/// Horizontal padding on each side of a badge half
+const PADDING: f64 = 6.0;
+
+/// What a badge shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BadgeMetric {
+    /// Health grade and score
+    #[default]
+    Grade,
+    /// Average module depth ratio
+    Depth,
+}
+
+impl BadgeMetric {
+    /// Parse a metric name (`grade` or `depth`)
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "grade" | "health" => Some(BadgeMetric::Grade),
+            "depth" | "depth-ratio" => Some(BadgeMetric::Depth),
+            _ => None,
+        }
+    }
+}
+
+/// A two-part badge: label on grey, message on a color
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Badge {
+    pub label: String,
+    pub message: String,
+    /// shields.io color name
+    pub color: String,
+}
+
+impl Badge {
+    /// Badge with the health grade and score (`coupling | B (84)`)
+    pub fn health(health: &HealthScore) -> Self {
+        let (grade, color) = match health.grade {
+            HealthGrade::S | HealthGrade::A => ("A", "brightgreen"),
+            HealthGrade::B => ("B", "green"),
+            HealthGrade::C => ("C", "yellow"),
+            HealthGrade::D => ("D", "orange"),
+            HealthGrade::F => ("F", "red"),
+        };
+        Self {
+            label: "coupling".into(),
+            message: format!("{} ({:.0})", grade, health.score),
+            color: color.into(),
+        }
+    }
+
+    /// Badge with the average depth ratio, colored by its depth class
+    /// (`depth | 6.2`)
+    pub fn depth_ratio(ratio: Option<f64>, config: &AposdConfig) -> Self {
+        let (message, color) = match ratio {
+            Some(r) => {
+                let color = match DepthClass::classify(r, config) {
+                    DepthClass::VeryDeep => "brightgreen",
+                    DepthClass::Deep => "green",
+                    DepthClass::Moderate => "yellow",
+                    DepthClass::Shallow => "orange",
+                };
+                (format!("{:.1}", r), color)
+            }
+            None => ("n/a".to_string(), "lightgrey"),
+        };
+        Self {
+            label: "depth".into(),
+            message,
+            color: color.into(),
+        }
+    }
+
+    /// Hex value of the color
+    fn hex(&self) -> &'static str {
+        match self.color.as_str() {
+            "brightgreen" => "#4c1",
+            "green" => "#97ca00",
+            "yellow" => "#dfb317",
+            "orange" => "#fe7d37",
+            "red" => "#e05d44",
+            _ => "#9f9f9f",
+        }
+    }
+
+    /// Flat SVG badge
+    pub fn to_svg(&self) -> String {
+        let width = |text: &str| (text.chars().count() as f64 * CHAR_WIDTH + 2.0 * PADDING).round();
+        let (left, right) = (width(&self.label), width(&self.message));
+        let total = left + right;
+        let (label, message) = (escape(&self.label), escape(&self.message));
+        format!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="{total}" height="20" role="img" aria-label="{label}: {message}">
+  <title>{label}: {message}</title>
+  <linearGradient id="s" x2="0" y2="100%">
+    <stop offset="0" stop-color="#bbb" stop-opacity=".1"/>
+    <stop offset="1" stop-opacity=".1"/>
+  </linearGradient>
+  <clipPath id="r"><rect width="{total}" height="20" rx="3" fill="#fff"/></clipPath>
+  <g clip-path="url(#r)">
+    <rect width="{left}" height="20" fill="#555"/>
+    <rect x="{left}" width="{right}" height="20" fill="{color}"/>
+    <rect width="{total}" height="20" fill="url(#s)"/>
+  </g>
+  <g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11">
+    <text x="{label_x}" y="15" fill="#010101" fill-opacity=".3">{label}</text>
+    <text x="{label_x}" y="14">{label}</text>
+    <text x="{message_x}" y="15" fill="#010101" fill-opacity=".3">{message}</text>
+    <text x="{message_x}" y="14">{message}</text>
+  </g>
+</svg>
+"##,
+            color = self.hex(),
+            label_x = left / 2.0,
+            message_x = left + right / 2.0,
+        )
+    }
+
+    /// shields.io endpoint JSON
+    pub fn to_endpoint_json(&self) -> String {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Endpoint<'a> {
+            schema_version: u8,
+            #[serde(flatten)]
+            badge: &'a Badge,
+        }
+        serde_json::to_string_pretty(&Endpoint {
+            schema_version: 1,
+            badge: self,
+        })
+        .expect("badge serializes")
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Write a badge to writer: endpoint JSON if `path` ends in `.json`,
+/// otherwise SVG
+pub fn write_badge<W: Write>(badge: &Badge, path: &Path, writer: &mut W) -> io::Result<()> {
+    if path.extension().is_some_and(|e| e == "json") {
+        writeln!(writer, "{}", badge.to_endpoint_json())
+    } else {
+        write!(writer, "{}", badge.to_svg())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::health::ScoreComponent;
+
+    #[test]
+    fn test_health_badge() {
+        let health = HealthScore::combine(vec![ScoreComponent::new(
+            "balance",
+            84.2,
+            1.0,
+            String::new(),
+        )]);
+        let badge = Badge::health(&health);
+        assert_eq!(badge.message, "B (84)");
+        assert_eq!(badge.color, "green");
+
+        let svg = badge.to_svg();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(r#"aria-label="coupling: B (84)""#));
+        assert!(svg.contains(r##"fill="#97ca00""##));
+
+        let json: serde_json::Value = serde_json::from_str(&badge.to_endpoint_json()).unwrap();
+        assert_eq!(json["schemaVersion"], 1);
+        assert_eq!(json["label"], "coupling");
+        assert_eq!(json["message"], "B (84)");
+    }
+
+    #[test]
+    fn test_depth_badge() {
+        let config = AposdConfig::default();
+        assert_eq!(Badge::depth_ratio(Some(12.0), &config).color, "brightgreen");
+        assert_eq!(Badge::depth_ratio(Some(1.24), &config).message, "1.2");
+        assert_eq!(Badge::depth_ratio(Some(1.24), &config).color, "orange");
+        assert_eq!(Badge::depth_ratio(None, &config).message, "n/a");
+        assert_eq!(BadgeMetric::parse("Depth"), Some(BadgeMetric::Depth));
+        assert_eq!(BadgeMetric::parse("loc"), None);
+    }
+}
@@ -9,12 +9,43 @@
 //! - Strong coupling + far distance = Bad (global complexity)
 //! - High volatility + strong coupling = Bad (cascading changes)
 
-use std::collections::HashMap;
-
-use crate::metrics::{CouplingMetrics, Distance, IntegrationStrength, ProjectMetrics, Volatility};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::api::ApiSurface;
+use crate::aposd::item_interface_complexity;
+use crate::aposd::{
+    AposdConfig, CognitiveLoadClass, analyze_module_depths_with, analyze_trait_surfaces,
+    find_overridden_defaults, find_temporal_decompositions,
+};
+use crate::baseline::Baseline;
+use crate::config::{CompiledConfig, PathOverride, ThresholdOverrides};
+use crate::connascence::{
+    ConnascenceType, ExecutionEvidence, MIN_SIGNATURE_TYPE_USERS, SharedStateKind,
+    find_algorithm_couplings, find_code_clones, find_execution_orders, find_positional_risks,
+    find_shared_literals, find_shared_schema, find_shared_signature_types, find_shared_state,
+};
+use crate::error_surface::find_error_exposures;
+use crate::exhaustive_match::{MANY_ENUM_MATCHES, find_fragile_enums};
+use crate::interior_mutability::find_interior_mutability;
+use crate::layers::{Layer, find_layer_violations};
+use crate::metrics::{
+    CouplingLocation, CouplingMetrics, Distance, IntegrationStrength, ModuleMetrics,
+    ProjectMetrics, Volatility,
+};
+use crate::ownership::find_ownership_hotspots;
+use crate::rules::{RuleFilter, RuleLevel};
+use crate::stamp::find_stamp_couplings;
+use crate::struct_literal::find_literal_structs;
+use crate::suppress::{FileSuppression, apply_suppressions};
+use crate::temporal::{TemporalConfig, TemporalIssueKind};
+use crate::trait_coupling::find_orphan_workarounds;
 
 /// Issue severity levels
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Severity {
     /// Minor issue, consider addressing
     Low,
@@ -38,7 +69,7 @@ impl std::fmt::Display for Severity {
 }
 
 /// Types of coupling problems
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum IssueType {
     /// Strong coupling spanning a long distance
     GlobalComplexity,
@@ -54,6 +85,10 @@ pub enum IssueType {
     UnnecessaryAbstraction,
     /// Circular dependency detected
     CircularDependency,
+    /// Dependency against the layering declared in the config
+    LayerViolation,
+    /// Function taking a large struct but reading only one or two fields
+    StampCoupling,
 
     // === APOSD-inspired issues (A Philosophy of Software Design) ===
     /// Module with interface complexity close to implementation complexity
@@ -62,6 +97,20 @@ pub enum IssueType {
     PassThroughMethod,
     /// Module requiring too much knowledge to understand/modify
     HighCognitiveLoad,
+    /// Public item no other workspace crate uses
+    UnusedPublicSurface,
+    /// Public trait with many methods and many implementors
+    WideTrait,
+    /// Trait default method most implementors override
+    OverriddenDefault,
+    /// Large module interface with few doc comments
+    NonObviousInterface,
+    /// Sibling modules split by execution phase
+    TemporalDecomposition,
+    /// Public functions returning many distinct error cases
+    FineGrainedErrors,
+    /// Function splitting into separate paths on a mode parameter
+    SpecialCaseFlag,
 
     // === Khononov/Rust-specific issues ===
     /// Module with too many functions, types, or implementations
@@ -70,6 +119,54 @@ pub enum IssueType {
     PublicFieldExposure,
     /// Functions with too many primitive parameters (consider newtype)
     PrimitiveObsession,
+    /// Item re-exported with `pub use` through several crates
+    ReExportChain,
+    /// Foreign trait implemented for a newtype around a foreign type
+    OrphanRuleWorkaround,
+    /// Public fields or signatures exposing `RefCell`/`Mutex`/guards
+    InteriorMutabilityLeak,
+    /// Public enum matched without a wildcard arm in other modules
+    ExhaustiveEnumMatch,
+    /// Public struct with only public fields built by literal in other crates
+    FieldVisibilityLeak,
+
+    // === Temporal coupling issues ===
+    /// Opening operation without a matching closing operation
+    UnpairedOperation,
+    /// Lock/borrow guard bound to `_` and dropped immediately
+    DroppedGuard,
+    /// Builder whose `build()` panics unless the right setters were called
+    PanickingBuilder,
+    /// `unwrap()`/`expect()`/`panic!` on state another method initializes
+    PanicPath,
+    /// `select!` branch awaiting a future that is not cancellation safe
+    CancellationUnsafeSelect,
+    /// Spawned task whose `JoinHandle` is dropped
+    DetachedSpawn,
+    /// Two locks acquired in opposite orders by different functions
+    LockOrderInversion,
+    /// Fields torn down in an order written by hand
+    DropOrderCoupling,
+
+    // === Git history issues ===
+    /// Files that frequently change together without a static dependency
+    HiddenCoupling,
+    /// Module changed by many authors and coupled to many modules
+    OrganizationalCoupling,
+
+    // === Connascence issues ===
+    /// Concrete type in the public signatures of many other modules
+    ConnascenceOfType,
+    /// Call passing several same-typed arguments that could be swapped
+    ConnascenceOfPosition,
+    /// Same magic number used in several modules
+    ConnascenceOfMeaning,
+    /// Modules must agree on an encoding or hashing algorithm
+    ConnascenceOfAlgorithm,
+    /// Method must run after another one its callers do not call
+    ConnascenceOfExecution,
+    /// Global mutable state referenced from several modules
+    ConnascenceOfIdentity,
 }
 
 impl std::fmt::Display for IssueType {
@@ -82,19 +179,185 @@ impl std::fmt::Display for IssueType {
             IssueType::HighAfferentCoupling => write!(f, "High Afferent Coupling"),
             IssueType::UnnecessaryAbstraction => write!(f, "Unnecessary Abstraction"),
             IssueType::CircularDependency => write!(f, "Circular Dependency"),
+            IssueType::LayerViolation => write!(f, "Layer Violation"),
+            IssueType::StampCoupling => write!(f, "Stamp Coupling"),
             // APOSD-inspired
             IssueType::ShallowModule => write!(f, "Shallow Module"),
             IssueType::PassThroughMethod => write!(f, "Pass-Through Method"),
             IssueType::HighCognitiveLoad => write!(f, "High Cognitive Load"),
+            IssueType::UnusedPublicSurface => write!(f, "Unused Public Surface"),
+            IssueType::WideTrait => write!(f, "Wide Trait"),
+            IssueType::OverriddenDefault => write!(f, "Overridden Default Method"),
+            IssueType::NonObviousInterface => write!(f, "Non-Obvious Interface"),
+            IssueType::TemporalDecomposition => write!(f, "Temporal Decomposition"),
+            IssueType::FineGrainedErrors => write!(f, "Fine-Grained Errors"),
+            IssueType::SpecialCaseFlag => write!(f, "Special-Case Flag"),
             // Khononov/Rust-specific
             IssueType::GodModule => write!(f, "God Module"),
             IssueType::PublicFieldExposure => write!(f, "Public Field Exposure"),
             IssueType::PrimitiveObsession => write!(f, "Primitive Obsession"),
+            IssueType::ReExportChain => write!(f, "Re-export Chain"),
+            IssueType::OrphanRuleWorkaround => write!(f, "Orphan Rule Workaround"),
+            IssueType::InteriorMutabilityLeak => write!(f, "Interior Mutability Leak"),
+            IssueType::ExhaustiveEnumMatch => write!(f, "Exhaustive Enum Match"),
+            IssueType::FieldVisibilityLeak => write!(f, "Field Visibility Leak"),
+            // Temporal
+            IssueType::UnpairedOperation => write!(f, "Unpaired Operation"),
+            IssueType::DroppedGuard => write!(f, "Dropped Guard"),
+            IssueType::PanickingBuilder => write!(f, "Panicking Builder"),
+            IssueType::PanicPath => write!(f, "Panic Path"),
+            IssueType::CancellationUnsafeSelect => write!(f, "Cancellation-Unsafe Select"),
+            IssueType::DetachedSpawn => write!(f, "Detached Spawn"),
+            IssueType::LockOrderInversion => write!(f, "Lock Order Inversion"),
+            IssueType::DropOrderCoupling => write!(f, "Drop Order Coupling"),
+            // Git history
+            IssueType::HiddenCoupling => write!(f, "Hidden Coupling"),
+            IssueType::OrganizationalCoupling => write!(f, "Organizational Coupling"),
+            // Connascence
+            IssueType::ConnascenceOfType => write!(f, "Connascence of Type"),
+            IssueType::ConnascenceOfPosition => write!(f, "Connascence of Position"),
+            IssueType::ConnascenceOfMeaning => write!(f, "Connascence of Meaning"),
+            IssueType::ConnascenceOfAlgorithm => write!(f, "Connascence of Algorithm"),
+            IssueType::ConnascenceOfExecution => write!(f, "Connascence of Execution"),
+            IssueType::ConnascenceOfIdentity => write!(f, "Connascence of Identity"),
         }
     }
 }
 
 impl IssueType {
+    /// Every issue type, in report order
+    pub const ALL: &[IssueType] = &[
+        IssueType::GlobalComplexity,
+        IssueType::CascadingChangeRisk,
+        IssueType::InappropriateIntimacy,
+        IssueType::HighEfferentCoupling,
+        IssueType::HighAfferentCoupling,
+        IssueType::UnnecessaryAbstraction,
+        IssueType::CircularDependency,
+        IssueType::LayerViolation,
+        IssueType::StampCoupling,
+        IssueType::ShallowModule,
+        IssueType::PassThroughMethod,
+        IssueType::HighCognitiveLoad,
+        IssueType::UnusedPublicSurface,
+        IssueType::WideTrait,
+        IssueType::OverriddenDefault,
+        IssueType::NonObviousInterface,
+        IssueType::TemporalDecomposition,
+        IssueType::FineGrainedErrors,
+        IssueType::SpecialCaseFlag,
+        IssueType::GodModule,
+        IssueType::PublicFieldExposure,
+        IssueType::PrimitiveObsession,
+        IssueType::ReExportChain,
+        IssueType::OrphanRuleWorkaround,
+        IssueType::InteriorMutabilityLeak,
+        IssueType::ExhaustiveEnumMatch,
+        IssueType::FieldVisibilityLeak,
+        IssueType::UnpairedOperation,
+        IssueType::DroppedGuard,
+        IssueType::PanickingBuilder,
+        IssueType::PanicPath,
+        IssueType::CancellationUnsafeSelect,
+        IssueType::DetachedSpawn,
+        IssueType::LockOrderInversion,
+        IssueType::DropOrderCoupling,
+        IssueType::HiddenCoupling,
+        IssueType::OrganizationalCoupling,
+        IssueType::ConnascenceOfType,
+        IssueType::ConnascenceOfPosition,
+        IssueType::ConnascenceOfMeaning,
+        IssueType::ConnascenceOfAlgorithm,
+        IssueType::ConnascenceOfExecution,
+        IssueType::ConnascenceOfIdentity,
+    ];
+
+    /// Look up an issue type by rule id (`temporal::dropped-guard`), SARIF
+    /// rule id (`DroppedGuard`) or display name (`Dropped Guard`), ignoring case
+    pub fn from_rule_id(id: &str) -> Option<IssueType> {
+        let id = id.trim();
+        IssueType::ALL.iter().copied().find(|t| {
+            t.rule_id().eq_ignore_ascii_case(id)
+                || format!("{:?}", t).eq_ignore_ascii_case(id)
+                || t.to_string().eq_ignore_ascii_case(id)
+        })
+    }
+
+    /// Stable rule identifier as `category::name` (used by suppressions)
+    pub fn rule_id(&self) -> &'static str {
+        match self {
+            IssueType::GlobalComplexity => "coupling::global-complexity",
+            IssueType::CascadingChangeRisk => "coupling::cascading-change-risk",
+            IssueType::InappropriateIntimacy => "coupling::inappropriate-intimacy",
+            IssueType::HighEfferentCoupling => "coupling::high-efferent",
+            IssueType::HighAfferentCoupling => "coupling::high-afferent",
+            IssueType::UnnecessaryAbstraction => "coupling::unnecessary-abstraction",
+            IssueType::CircularDependency => "coupling::circular-dependency",
+            IssueType::LayerViolation => "coupling::layer-violation",
+            IssueType::StampCoupling => "coupling::stamp-coupling",
+            IssueType::ShallowModule => "aposd::shallow-module",
+            IssueType::PassThroughMethod => "aposd::pass-through-method",
+            IssueType::HighCognitiveLoad => "aposd::high-cognitive-load",
+            IssueType::UnusedPublicSurface => "aposd::unused-public-surface",
+            IssueType::WideTrait => "aposd::wide-trait",
+            IssueType::OverriddenDefault => "aposd::overridden-default",
+            IssueType::NonObviousInterface => "aposd::non-obvious-interface",
+            IssueType::TemporalDecomposition => "aposd::temporal-decomposition",
+            IssueType::FineGrainedErrors => "aposd::fine-grained-errors",
+            IssueType::SpecialCaseFlag => "aposd::special-case-flag",
+            IssueType::GodModule => "rust::god-module",
+            IssueType::PublicFieldExposure => "rust::public-field-exposure",
+            IssueType::PrimitiveObsession => "rust::primitive-obsession",
+            IssueType::ReExportChain => "rust::reexport-chain",
+            IssueType::OrphanRuleWorkaround => "rust::orphan-workaround",
+            IssueType::InteriorMutabilityLeak => "rust::interior-mutability-leak",
+            IssueType::ExhaustiveEnumMatch => "rust::exhaustive-match",
+            IssueType::FieldVisibilityLeak => "rust::field-visibility-leak",
+            IssueType::UnpairedOperation => "temporal::unpaired-operation",
+            IssueType::DroppedGuard => "temporal::dropped-guard",
+            IssueType::PanickingBuilder => "temporal::panicking-builder",
+            IssueType::PanicPath => "temporal::panic-path",
+            IssueType::CancellationUnsafeSelect => "temporal::cancellation-unsafe-select",
+            IssueType::DetachedSpawn => "temporal::detached-spawn",
+            IssueType::LockOrderInversion => "temporal::lock-order",
+            IssueType::DropOrderCoupling => "temporal::drop-order",
+            IssueType::HiddenCoupling => "git::hidden-coupling",
+            IssueType::OrganizationalCoupling => "git::organizational-coupling",
+            IssueType::ConnascenceOfType => "connascence::type",
+            IssueType::ConnascenceOfPosition => "connascence::position",
+            IssueType::ConnascenceOfMeaning => "connascence::meaning",
+            IssueType::ConnascenceOfAlgorithm => "connascence::algorithm",
+            IssueType::ConnascenceOfExecution => "connascence::execution",
+            IssueType::ConnascenceOfIdentity => "connascence::identity",
+        }
+    }
+
+    /// Whether a rule selector (`all`, a category like `temporal`, or a
+    /// full id like `connascence::meaning` or `connascence/meaning`)
+    /// covers this issue type
+    pub fn matches_rule(&self, selector: &str) -> bool {
+        let id = self.rule_id();
+        let selector = selector.replace('/', "::");
+        selector == "all"
+            || selector == id
+            || id
+                .strip_prefix(selector.as_str())
+                .is_some_and(|rest| rest.starts_with("::"))
+    }
+
+    /// Kind of connascence this issue represents, if any
+    pub fn connascence_type(&self) -> Option<ConnascenceType> {
+        match self {
+            IssueType::ConnascenceOfType => Some(ConnascenceType::Type),
+            IssueType::ConnascenceOfPosition => Some(ConnascenceType::Position),
+            IssueType::ConnascenceOfMeaning => Some(ConnascenceType::Meaning),
+            IssueType::ConnascenceOfAlgorithm => Some(ConnascenceType::Algorithm),
+            IssueType::ConnascenceOfExecution => Some(ConnascenceType::Execution),
+            IssueType::ConnascenceOfIdentity => Some(ConnascenceType::Identity),
+            _ => None,
+        }
+    }
+
     /// Get a detailed description of what this issue type means
     pub fn description(&self) -> &'static str {
         match self {
@@ -119,6 +382,12 @@ impl IssueType {
             IssueType::CircularDependency => {
                 "Circular dependencies make it impossible to understand, test, or modify components in isolation."
             }
+            IssueType::LayerViolation => {
+                "A component depends on a layer it is not allowed to depend on. Lower layers that know about higher ones can no longer be changed, tested, or reused on their own."
+            }
+            IssueType::StampCoupling => {
+                "A function takes a whole struct but only reads one or two of its fields. Callers must build the entire struct, and the function is coupled to every field it ignores. (Stamp coupling)"
+            }
             // APOSD-inspired descriptions
             IssueType::ShallowModule => {
                 "Interface complexity is close to implementation complexity. The module doesn't hide enough complexity behind a simple interface. (APOSD: Deep vs Shallow Modules)"
@@ -129,6 +398,27 @@ impl IssueType {
             IssueType::HighCognitiveLoad => {
                 "Module requires too much knowledge to understand and modify. Too many public APIs, dependencies, or complex type signatures. (APOSD: Cognitive Load)"
             }
+            IssueType::UnusedPublicSurface => {
+                "A public item of a workspace library is used by no other workspace crate and is not exported by the workspace's root crates. It widens the module's interface, and lowers its depth ratio, without serving any caller. (APOSD: Deep vs Shallow Modules)"
+            }
+            IssueType::WideTrait => {
+                "A public trait with many methods is implemented by many types. Every implementor writes the required methods, so adding one or changing a signature touches all of them. (APOSD: Deep vs Shallow Modules)"
+            }
+            IssueType::OverriddenDefault => {
+                "A trait method has a default body that most implementors replace with their own. The shared behavior fits few of the types, so the default is a false abstraction that callers and implementors must still reason about. (APOSD: Different Layer, Different Abstraction)"
+            }
+            IssueType::NonObviousInterface => {
+                "Module exposes a large interface but documents few of its public items. Callers have to read the implementation to learn what the items do and how to use them, which defeats the point of the abstraction. (APOSD: Obscurity, Comments Should Describe Things that Aren't Obvious)"
+            }
+            IssueType::TemporalDecomposition => {
+                "Modules are split by when their code runs (read, then process, then write) instead of by what it knows. Every phase needs the same knowledge of the shared types, so a change to them touches each phase. (APOSD: Temporal Decomposition, Information Leakage)"
+            }
+            IssueType::FineGrainedErrors => {
+                "The module's public functions can return many distinct error cases. Every caller has to decide how to handle each of them, although most callers treat them alike. (APOSD: Define Errors Out of Existence)"
+            }
+            IssueType::SpecialCaseFlag => {
+                "Function takes a flag or mode from its caller and branches on it into mostly separate code. It is several special-purpose functions behind one general-purpose signature: callers pick the behavior with an argument, and each mode's code is read and changed alongside the others. (APOSD: General-Purpose Modules are Deeper, Separate General-Purpose and Special-Purpose Code)"
+            }
             // Khononov/Rust-specific descriptions
             IssueType::GodModule => {
                 "Module has too many responsibilities - too many functions, types, or implementations. Consider splitting into focused, cohesive modules. (SRP violation)"
@@ -139,6 +429,72 @@ impl IssueType {
             IssueType::PrimitiveObsession => {
                 "Function has many primitive parameters of the same type. Consider using newtype pattern (e.g., `struct UserId(u64)`) for type safety and clarity."
             }
+            IssueType::OrphanRuleWorkaround => {
+                "A foreign trait is implemented for a local newtype around a foreign type to get around the orphan rule. The impl couples three crates and breaks when either upstream crate changes the trait or the type."
+            }
+            IssueType::InteriorMutabilityLeak => {
+                "Public fields of type RefCell/Cell/Mutex/RwLock, or public methods returning the lock or its guard, leave locking and borrowing to the callers. They decide how long the critical section lasts, and switching the synchronization strategy breaks them."
+            }
+            IssueType::ExhaustiveEnumMatch => {
+                "A public enum without #[non_exhaustive] is matched without a wildcard arm in other modules or crates. Every such match lists all variants, so adding a variant breaks each of them, and in other crates it is a breaking change. (Page-Jones: Connascence of Meaning)"
+            }
+            IssueType::FieldVisibilityLeak => {
+                "A public struct whose fields are all public is built with struct literals in other crates. Each literal names every field, so adding a field breaks them all and is a semver-breaking change; the fields are part of the interface twice, for reading and for construction."
+            }
+            IssueType::ReExportChain => {
+                "An item is re-exported with `pub use` through several crates. Every crate in the chain exposes it, so its name and shape leak to users who never depend on the defining crate (amplified connascence of name)."
+            }
+            // Temporal coupling descriptions
+            IssueType::UnpairedOperation => {
+                "An operation that starts a protocol (begin, acquire, connect) has no matching closing operation. Callers must remember the order; consider an RAII guard that closes on Drop."
+            }
+            IssueType::DroppedGuard => {
+                "A lock or borrow guard is bound to `_` and dropped at the end of the statement, so the critical section is empty. Bind it to a named variable such as `_guard`."
+            }
+            IssueType::PanickingBuilder => {
+                "A builder's `build()` unwraps fields that only some setters fill in, so callers must know which setters to call before building. Take required values in the constructor, return a Result, or use typestate."
+            }
+            IssueType::PanicPath => {
+                "A method unwraps or panics on a field that only another method sets (e.g. `connect()` before `send()`). Calling them in the wrong order is not a compile error or a returned error but a runtime panic."
+            }
+            IssueType::CancellationUnsafeSelect => {
+                "A `select!` branch awaits an operation that is not cancellation safe (read_exact, write_all, a hand-written future). When another branch completes first the future is dropped and the data it already read or wrote is lost."
+            }
+            IssueType::DetachedSpawn => {
+                "A task is spawned and its JoinHandle dropped. Nothing can await its result, abort it on shutdown, or notice that it panicked; its lifetime is decoupled from the code that started it."
+            }
+            IssueType::LockOrderInversion => {
+                "Two functions acquire the same two locks in opposite orders. Each works alone, but when they run concurrently each can hold the lock the other waits for, and both block forever."
+            }
+            IssueType::DropOrderCoupling => {
+                "A type tears its fields down in an order written by hand: its Drop impl uses several fields, or fields are released early with ManuallyDrop or mem::drop. Reordering the field declarations or the release calls silently changes what is still alive during teardown."
+            }
+            // Git history descriptions
+            IssueType::HiddenCoupling => {
+                "Files frequently change in the same commit although neither depends on the other in code. The shared knowledge is implicit (duplicated logic, formats, or protocols)."
+            }
+            IssueType::OrganizationalCoupling => {
+                "Many different people change a module that many other modules are coupled to. Every change needs coordination between its authors and ripples into its neighbours; team boundaries and module boundaries disagree. (Conway's law)"
+            }
+            // Connascence descriptions
+            IssueType::ConnascenceOfType => {
+                "The same concrete struct or enum appears in the public signatures of many modules. All of them must change when the type does, and none can be used with a different implementation. (Page-Jones: Connascence of Type)"
+            }
+            IssueType::ConnascenceOfPosition => {
+                "Several arguments of the same type are passed by position. Swapping two of them still compiles but changes behavior; caller and callee must agree on the order. (Page-Jones: Connascence of Position)"
+            }
+            IssueType::ConnascenceOfMeaning => {
+                "The same literal value, or SQL naming the same table, appears in several modules. They agree on what the value means without naming it, so changing it in one place silently breaks the others. (Page-Jones: Connascence of Meaning)"
+            }
+            IssueType::ConnascenceOfAlgorithm => {
+                "One side encodes (or hashes) data and another side decodes (or compares) it with the same algorithm, or two modules carry copies of the same function body. Changing the algorithm on one side breaks or diverges from the other at runtime, not at compile time. (Page-Jones: Connascence of Algorithm)"
+            }
+            IssueType::ConnascenceOfExecution => {
+                "A method only works after another one has run, but the type system does not enforce the order. Every caller has to know the protocol. (Page-Jones: Connascence of Execution)"
+            }
+            IssueType::ConnascenceOfIdentity => {
+                "Several modules read or write the same global instance. Each of them depends on what the others did to it, and none can be tested or reasoned about alone. (Page-Jones: Connascence of Identity)"
+            }
         }
     }
 }
@@ -160,6 +516,43 @@ pub struct CouplingIssue {
     pub refactoring: RefactoringAction,
     /// Balance score that triggered this issue
     pub balance_score: f64,
+    /// Where the issue was found (file, line, column when known)
+    pub location: CouplingLocation,
+}
+
+impl CouplingIssue {
+    /// File the issue is in: its location, or the file of its module (see
+    /// [`Self::module`])
+    pub fn file<'a>(&'a self, metrics: &'a ProjectMetrics) -> Option<&'a Path> {
+        self.location
+            .file_path
+            .as_deref()
+            .or_else(|| self.module(metrics).map(|m| m.path.as_path()))
+    }
+
+    /// Module the issue is in: the one defined by its file, or else its
+    /// source or target module (a crate name finds the crate root)
+    pub fn module<'a>(&self, metrics: &'a ProjectMetrics) -> Option<&'a ModuleMetrics> {
+        let by_rust_path = || {
+            // `package::crate_name` names the crate root as well
+            let names: Vec<String> = [&self.source, &self.target]
+                .into_iter()
+                .flat_map(|name| [name.as_str(), name.rsplit("::").next().unwrap_or(name)])
+                .map(|name| name.replace('-', "_"))
+                .collect();
+            metrics
+                .modules
+                .values()
+                .find(|m| names.contains(&m.rust_path()))
+        };
+        self.location
+            .file_path
+            .as_deref()
+            .and_then(|path| metrics.modules.values().find(|m| m.path == path))
+            .or_else(|| metrics.find_module(&self.source))
+            .or_else(|| metrics.find_module(&self.target))
+            .or_else(by_rust_path)
+    }
 }
 
 /// Specific refactoring actions
@@ -383,10 +776,40 @@ pub struct IssueThresholds {
     pub max_impls: usize,
     /// Minimum primitive parameter count for Primitive Obsession
     pub min_primitive_params: usize,
+    /// Crate boundaries a re-exported item may cross before Re-export Chain
+    pub max_reexport_boundaries: usize,
+    /// Distinct git authors of a coupled module before Organizational Coupling
+    pub max_authors: usize,
+    /// Per-crate `max_dependencies`/`max_dependents` overrides
+    pub crate_thresholds: BTreeMap<String, ThresholdOverrides>,
+    /// Per-path overrides; the first matching one wins over the crate's
+    pub path_thresholds: Vec<PathOverride>,
+    /// Rules to report (`[rules]`, `--only`, `--skip`)
+    pub rules: RuleFilter,
+    /// Report findings of rules set to `info` (verbose mode)
+    pub show_info: bool,
     /// Strict mode: only show Medium/High/Critical issues
     pub strict_mode: bool,
+    /// Only list findings at or above this severity (`--min-severity`);
+    /// the totals and grade still count the hidden ones
+    pub min_severity: Option<Severity>,
+    /// Only list the N worst findings of each issue type (`--top`); the
+    /// totals and grade still count the hidden ones
+    pub top_per_type: Option<usize>,
     /// Show explanations in Japanese
     pub japanese: bool,
+    /// Known findings to suppress ("new issues only" mode)
+    pub baseline: Option<Baseline>,
+    /// File-level suppressions from the config file
+    pub file_suppressions: Vec<FileSuppression>,
+    /// Findings suppressed by fingerprint in the config file
+    pub suppressed_fingerprints: BTreeSet<String>,
+    /// Architectural layers from the config file
+    pub layers: Vec<Layer>,
+    /// Module depth and cognitive load cutoffs
+    pub aposd: AposdConfig,
+    /// Temporal coupling severities
+    pub temporal: TemporalConfig,
 }
 
 impl Default for IssueThresholds {
@@ -402,11 +825,74 @@ impl Default for IssueThresholds {
             max_impls: 20,           // More than 20 implementations = God Module
             min_primitive_params: 3, // 3+ primitive params = Primitive Obsession
             strict_mode: true,       // Show only important issues by default
-            japanese: false,         // English by default
+            min_severity: None,      // List every counted issue
+            top_per_type: None,
+            japanese: false, // English by default
+            baseline: None,  // Report all issues
+            max_reexport_boundaries: 1,
+            max_authors: 5,
+            crate_thresholds: BTreeMap::new(),
+            path_thresholds: Vec::new(),
+            rules: RuleFilter::default(),
+            show_info: false,
+            file_suppressions: Vec::new(),
+            suppressed_fingerprints: BTreeSet::new(),
+            layers: Vec::new(),
+            aposd: AposdConfig::default(),
+            temporal: TemporalConfig::default(),
         }
     }
 }
 
+impl IssueThresholds {
+    /// Defaults overridden by a configuration file
+    pub fn from_config(config: &CompiledConfig) -> Self {
+        Self {
+            max_dependencies: config.thresholds.max_dependencies,
+            max_dependents: config.thresholds.max_dependents,
+            max_reexport_boundaries: config.thresholds.max_reexport_boundaries,
+            max_authors: config.thresholds.max_authors,
+            crate_thresholds: config.crate_thresholds.clone(),
+            path_thresholds: config.path_thresholds.clone(),
+            rules: config.rules.clone(),
+            file_suppressions: config.suppressions.clone(),
+            suppressed_fingerprints: config.suppressed_fingerprints.clone(),
+            layers: config.layers.clone(),
+            aposd: config.aposd.clone(),
+            temporal: config.temporal.clone(),
+            ..Self::default()
+        }
+    }
+
+    /// `max_dependencies` for a module of `crate_name` defined in `path`
+    pub fn max_dependencies_for(&self, crate_name: Option<&str>, path: Option<&Path>) -> usize {
+        self.override_for(crate_name, path, |o| o.max_dependencies)
+            .unwrap_or(self.max_dependencies)
+    }
+
+    /// `max_dependents` for a module of `crate_name` defined in `path`
+    pub fn max_dependents_for(&self, crate_name: Option<&str>, path: Option<&Path>) -> usize {
+        self.override_for(crate_name, path, |o| o.max_dependents)
+            .unwrap_or(self.max_dependents)
+    }
+
+    /// First value set by a matching path override, then by the crate's
+    fn override_for(
+        &self,
+        crate_name: Option<&str>,
+        path: Option<&Path>,
+        value: impl Fn(&ThresholdOverrides) -> Option<usize>,
+    ) -> Option<usize> {
+        path.and_then(|path| {
+            self.path_thresholds
+                .iter()
+                .filter(|o| o.applies_to(path))
+                .find_map(|o| value(&o.thresholds))
+        })
+        .or_else(|| crate_name.and_then(|name| value(self.crate_thresholds.get(name)?)))
+    }
+}
+
 /// Crate stability classification
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CrateStability {
@@ -539,6 +1025,7 @@ pub fn identify_issues_with_thresholds(
                 methods: vec!["// Extract required methods".to_string()],
             },
             balance_score: balance.score,
+            location: coupling.location.clone(),
         });
     }
 
@@ -560,6 +1047,7 @@ pub fn identify_issues_with_thresholds(
                 interface_name: format!("{}Interface", extract_type_name(&coupling.target)),
             },
             balance_score: balance.score,
+            location: coupling.location.clone(),
         });
     }
 
@@ -588,6 +1076,7 @@ pub fn identify_issues_with_thresholds(
                     methods: vec!["// Expose only necessary operations".to_string()],
                 },
                 balance_score: balance.score,
+                location: coupling.location.clone(),
             });
         }
     }
@@ -609,6 +1098,7 @@ pub fn analyze_project_balance_with_thresholds(
     metrics: &ProjectMetrics,
     thresholds: &IssueThresholds,
 ) -> ProjectBalanceReport {
+    let _span = tracing::debug_span!("balance", couplings = metrics.couplings.len()).entered();
     let thresholds = thresholds.clone();
     let mut all_issues = Vec::new();
     let mut internal_balance_scores: Vec<BalanceScore> = Vec::new();
@@ -636,16 +1126,109 @@ pub fn analyze_project_balance_with_thresholds(
     let rust_issues = analyze_rust_patterns(metrics, &thresholds);
     all_issues.extend(rust_issues);
 
-    // Strict mode: filter out Low severity issues to reduce noise
+    // Analyze items re-exported through several crates
+    all_issues.extend(analyze_reexport_chains(metrics, &thresholds));
+
+    // Analyze public items nothing else in the workspace uses
+    all_issues.extend(analyze_unused_surface(metrics));
+
+    // Analyze newtypes that exist only to implement foreign traits
+    all_issues.extend(analyze_orphan_workarounds(metrics));
+
+    // Analyze cells, locks and guards in public APIs
+    all_issues.extend(analyze_interior_mutability(metrics));
+
+    // Analyze public enums that other modules match exhaustively
+    all_issues.extend(analyze_exhaustive_matches(metrics));
+
+    // Analyze public structs that other crates build with literals
+    all_issues.extend(analyze_struct_literals(metrics));
+
+    // Analyze functions that take a large struct for one or two fields
+    all_issues.extend(analyze_stamp_coupling(metrics));
+
+    // Analyze modules that demand too much knowledge from their users
+    all_issues.extend(analyze_cognitive_load(metrics, &thresholds.aposd));
+
+    // Analyze large interfaces with few doc comments
+    all_issues.extend(analyze_non_obvious_interfaces(metrics, &thresholds.aposd));
+
+    // Analyze modules returning many error cases
+    all_issues.extend(analyze_fine_grained_errors(metrics, &thresholds.aposd));
+
+    // Analyze functions switching on a mode parameter
+    all_issues.extend(analyze_special_case_flags(metrics));
+
+    // Analyze wide traits that many types implement
+    all_issues.extend(analyze_wide_traits(metrics, &thresholds.aposd));
+
+    // Analyze trait default methods that implementors override anyway
+    all_issues.extend(analyze_overridden_defaults(metrics));
+
+    // Analyze modules split by execution phase
+    all_issues.extend(analyze_temporal_decompositions(metrics));
+
+    // Analyze temporal coupling (paired operations, guards)
+    all_issues.extend(analyze_temporal_patterns(metrics, &thresholds.temporal));
+
+    // Analyze logical coupling from git history
+    all_issues.extend(analyze_hidden_coupling(metrics));
+    all_issues.extend(analyze_organizational_coupling(
+        metrics,
+        thresholds.max_authors,
+    ));
+
+    // Analyze connascence at call sites
+    all_issues.extend(analyze_connascence(metrics));
+
+    // Analyze dependency cycles between modules and between crates
+    all_issues.extend(analyze_cycles(metrics));
+
+    // Check dependencies against the declared layers
+    all_issues.extend(analyze_layer_violations(metrics, &thresholds.layers));
+
+    // Drop findings of disabled rules, and of `info` rules unless verbose
+    all_issues.retain(|issue| {
+        thresholds.rules.allows(issue.issue_type)
+            && (thresholds.show_info
+                || thresholds.rules.level(issue.issue_type) != Some(RuleLevel::Info))
+    });
+
+    // Drop findings acknowledged with `coupling:ignore` or `[suppress]`
+    apply_suppressions(
+        metrics,
+        &thresholds.file_suppressions,
+        &thresholds.suppressed_fingerprints,
+        &mut all_issues,
+    );
+
+    // Baseline: only report issues introduced since it was recorded
+    if let Some(baseline) = &thresholds.baseline {
+        baseline.retain_new(&mut all_issues, metrics);
+    }
+
+    // Strict mode: filter out Low severity issues to reduce noise, unless
+    // their rule was explicitly set to `warning` or `error`
     if thresholds.strict_mode {
-        all_issues.retain(|issue| issue.severity >= Severity::Medium);
+        all_issues.retain(|issue| {
+            issue.severity >= Severity::Medium
+                || thresholds
+                    .rules
+                    .level(issue.issue_type)
+                    .is_some_and(|level| level >= RuleLevel::Warning)
+        });
     }
 
-    // Sort by severity (critical first), then by balance score (worst first)
+    // Sort by severity (critical first), then by balance score (worst
+    // first); ties are ordered by rule and location so reports are stable
     all_issues.sort_by(|a, b| {
         b.severity
             .cmp(&a.severity)
-            .then_with(|| a.balance_score.partial_cmp(&b.balance_score).unwrap())
+            .then_with(|| a.balance_score.total_cmp(&b.balance_score))
+            .then_with(|| a.issue_type.cmp(&b.issue_type))
+            .then_with(|| a.source.cmp(&b.source))
+            .then_with(|| a.target.cmp(&b.target))
+            .then_with(|| a.description.cmp(&b.description))
     });
 
     // Calculate summary statistics based on INTERNAL couplings only
@@ -674,13 +1257,13 @@ pub fn analyze_project_balance_with_thresholds(
     };
 
     // Count issues by severity
-    let mut issues_by_severity: HashMap<Severity, usize> = HashMap::new();
+    let mut issues_by_severity: BTreeMap<Severity, usize> = BTreeMap::new();
     for issue in &all_issues {
         *issues_by_severity.entry(issue.severity).or_insert(0) += 1;
     }
 
     // Count issues by type
-    let mut issues_by_type: HashMap<IssueType, usize> = HashMap::new();
+    let mut issues_by_type: BTreeMap<IssueType, usize> = BTreeMap::new();
     for issue in &all_issues {
         *issues_by_type.entry(issue.issue_type).or_insert(0) += 1;
     }
@@ -688,6 +1271,20 @@ pub fn analyze_project_balance_with_thresholds(
     // Determine overall health grade based on INTERNAL coupling issues
     let health_grade = calculate_health_grade(&issues_by_severity, internal_couplings);
 
+    // Listing filters: applied after counting so the grade is unaffected
+    if let Some(min_severity) = thresholds.min_severity {
+        all_issues.retain(|issue| issue.severity >= min_severity);
+    }
+    if let Some(top) = thresholds.top_per_type {
+        // Issues are sorted worst first, so keep the first N of each type
+        let mut listed: HashMap<IssueType, usize> = HashMap::new();
+        all_issues.retain(|issue| {
+            let count = listed.entry(issue.issue_type).or_insert(0);
+            *count += 1;
+            *count <= top
+        });
+    }
+
     ProjectBalanceReport {
         total_couplings,
         balanced_count,
@@ -714,6 +1311,8 @@ fn analyze_module_coupling(
     // Only count INTERNAL dependencies (within workspace), not external crates
     let mut efferent: HashMap<&str, usize> = HashMap::new();
     let mut afferent: HashMap<&str, usize> = HashMap::new();
+    // Crate of each module, for per-crate thresholds (workspace analysis)
+    let mut crate_of: HashMap<&str, &str> = HashMap::new();
 
     for coupling in &metrics.couplings {
         // Skip external crate dependencies entirely
@@ -723,14 +1322,22 @@ fn analyze_module_coupling(
 
         *efferent.entry(&coupling.source).or_insert(0) += 1;
         *afferent.entry(&coupling.target).or_insert(0) += 1;
+        if let Some(name) = &coupling.source_crate {
+            crate_of.insert(&coupling.source, name);
+        }
+        if let Some(name) = &coupling.target_crate {
+            crate_of.insert(&coupling.target, name);
+        }
     }
 
     // Check for high efferent coupling (depends on too many things)
     for (module, count) in &efferent {
-        if *count > thresholds.max_dependencies {
+        let path = metrics.find_module(module).map(|m| m.path.as_path());
+        let max_dependencies = thresholds.max_dependencies_for(crate_of.get(module).copied(), path);
+        if *count > max_dependencies {
             issues.push(CouplingIssue {
                 issue_type: IssueType::HighEfferentCoupling,
-                severity: if *count > thresholds.max_dependencies * 2 {
+                severity: if *count > max_dependencies * 2 {
                     Severity::High
                 } else {
                     Severity::Medium
@@ -739,7 +1346,7 @@ fn analyze_module_coupling(
                 target: format!("{} dependencies", count),
                 description: format!(
                     "Module {} depends on {} other components (threshold: {})",
-                    module, count, thresholds.max_dependencies
+                    module, count, max_dependencies
                 ),
                 refactoring: RefactoringAction::SplitModule {
                     suggested_modules: vec![
@@ -747,8 +1354,8 @@ fn analyze_module_coupling(
                         format!("{}_integration", module),
                     ],
                 },
-                balance_score: 1.0
-                    - (*count as f64 / (thresholds.max_dependencies * 3) as f64).min(1.0),
+                balance_score: 1.0 - (*count as f64 / (max_dependencies * 3) as f64).min(1.0),
+                location: module_location(metrics, module),
             });
         }
     }
@@ -756,10 +1363,12 @@ fn analyze_module_coupling(
     // Check for high afferent coupling (too many things depend on this)
     // Only internal modules are counted (external crates already filtered above)
     for (module, count) in &afferent {
-        if *count > thresholds.max_dependents {
+        let path = metrics.find_module(module).map(|m| m.path.as_path());
+        let max_dependents = thresholds.max_dependents_for(crate_of.get(module).copied(), path);
+        if *count > max_dependents {
             issues.push(CouplingIssue {
                 issue_type: IssueType::HighAfferentCoupling,
-                severity: if *count > thresholds.max_dependents * 2 {
+                severity: if *count > max_dependents * 2 {
                     Severity::High
                 } else {
                     Severity::Medium
@@ -768,14 +1377,14 @@ fn analyze_module_coupling(
                 target: module.to_string(),
                 description: format!(
                     "Module {} is depended on by {} other components (threshold: {})",
-                    module, count, thresholds.max_dependents
+                    module, count, max_dependents
                 ),
                 refactoring: RefactoringAction::IntroduceTrait {
                     suggested_name: format!("{}Interface", extract_type_name(module)),
                     methods: vec!["// Define stable public API".to_string()],
                 },
-                balance_score: 1.0
-                    - (*count as f64 / (thresholds.max_dependents * 3) as f64).min(1.0),
+                balance_score: 1.0 - (*count as f64 / (max_dependents * 3) as f64).min(1.0),
+                location: module_location(metrics, module),
             });
         }
     }
@@ -783,6 +1392,303 @@ fn analyze_module_coupling(
     issues
 }
 
+/// Report public items of non-root workspace crates nobody else uses
+fn analyze_unused_surface(metrics: &ProjectMetrics) -> Vec<CouplingIssue> {
+    let surface = ApiSurface::build(metrics);
+    surface
+        .unused_surface()
+        .into_iter()
+        .map(|item| {
+            let module = metrics.modules.values().find(|m| m.path == item.file);
+            let weight = module.map_or(0, |m| item_interface_complexity(m, &[item]));
+            let module_name = module.map_or("?", |m| m.name.as_str());
+            CouplingIssue {
+                issue_type: IssueType::UnusedPublicSurface,
+                severity: Severity::Low,
+                source: format!("{}::{}", item.krate, item.path),
+                target: format!("{} interface complexity", weight),
+                description: format!(
+                    "{} {}::{} is public, but no other workspace crate uses it (adds {} to {}'s interface complexity)",
+                    item.kind, item.krate, item.path, weight, module_name
+                ),
+                refactoring: RefactoringAction::General {
+                    action: "Make it pub(crate), or remove it if the crate does not use it either"
+                        .to_string(),
+                },
+                balance_score: 0.9,
+                location: CouplingLocation::new(item.file.clone(), item.line, 0),
+            }
+        })
+        .collect()
+}
+
+/// Report items re-exported across more crate boundaries than allowed
+fn analyze_reexport_chains(
+    metrics: &ProjectMetrics,
+    thresholds: &IssueThresholds,
+) -> Vec<CouplingIssue> {
+    ApiSurface::build(metrics)
+        .reexport_chains()
+        .into_iter()
+        .filter(|chain| chain.crate_boundaries() > thresholds.max_reexport_boundaries)
+        .map(|chain| {
+            let boundaries = chain.crate_boundaries();
+            let origin = chain.origin();
+            let exporter = chain.exporter();
+            CouplingIssue {
+                issue_type: IssueType::ReExportChain,
+                severity: if boundaries > thresholds.max_reexport_boundaries + 1 {
+                    Severity::High
+                } else {
+                    Severity::Medium
+                },
+                source: exporter.to_string(),
+                target: origin.to_string(),
+                description: format!(
+                    "{} re-exports {} across {} crate boundaries: {}",
+                    exporter.krate, origin, boundaries, chain
+                ),
+                refactoring: RefactoringAction::General {
+                    action: format!(
+                        "Depend on {} directly, or wrap the type in {} instead of re-exporting it",
+                        origin.krate, exporter.krate
+                    ),
+                },
+                balance_score: 1.0 / (boundaries as f64 + 1.0),
+                location: CouplingLocation::new(exporter.file.clone(), exporter.line, 0),
+            }
+        })
+        .collect()
+}
+
+/// Report functions that read only a few fields of a large struct parameter
+fn analyze_stamp_coupling(metrics: &ProjectMetrics) -> Vec<CouplingIssue> {
+    find_stamp_couplings(metrics)
+        .into_iter()
+        .map(|found| {
+            let fields = found.used_fields.join(", ");
+            CouplingIssue {
+                issue_type: IssueType::StampCoupling,
+                severity: if found.struct_module == found.module {
+                    Severity::Low
+                } else {
+                    Severity::Medium
+                },
+                source: format!("{}::{}", found.module, found.function),
+                target: format!("{}::{}", found.struct_module, found.struct_name),
+                description: format!(
+                    "{}({}: {}) uses only {} of {} fields: {}",
+                    found.function,
+                    found.param,
+                    found.struct_name,
+                    found.used_fields.len(),
+                    found.struct_fields,
+                    fields
+                ),
+                refactoring: RefactoringAction::General {
+                    action: format!(
+                        "Pass {} instead of the whole {}, or group them in a smaller struct",
+                        fields, found.struct_name
+                    ),
+                },
+                balance_score: found.used_fields.len() as f64 / found.struct_fields as f64,
+                location: found.location,
+            }
+        })
+        .collect()
+}
+
+/// Report modules whose public API hands out cells, locks or guards
+fn analyze_interior_mutability(metrics: &ProjectMetrics) -> Vec<CouplingIssue> {
+    find_interior_mutability(metrics)
+        .into_iter()
+        .map(|found| {
+            let items: Vec<String> = found
+                .fields
+                .iter()
+                .chain(&found.signatures)
+                .map(|l| format!("{} ({})", l.item, l.type_name))
+                .collect();
+            CouplingIssue {
+                issue_type: IssueType::InteriorMutabilityLeak,
+                severity: Severity::Medium,
+                source: found.module.clone(),
+                target: format!(
+                    "{} fields, {} signatures",
+                    found.fields.len(),
+                    found.signatures.len()
+                ),
+                description: format!(
+                    "Public API of {} exposes interior mutability: {}",
+                    found.module,
+                    items.join(", ")
+                ),
+                refactoring: RefactoringAction::General {
+                    action: found.suggestion(),
+                },
+                balance_score: 0.5,
+                location: CouplingLocation::new(found.file.clone(), found.line(), 0),
+            }
+        })
+        .collect()
+}
+
+/// Report public enums that other modules match without a wildcard arm
+fn analyze_exhaustive_matches(metrics: &ProjectMetrics) -> Vec<CouplingIssue> {
+    find_fragile_enums(metrics)
+        .into_iter()
+        .map(|found| {
+            let count = found.matches.len();
+            let modules = found.matching_modules();
+            CouplingIssue {
+                issue_type: IssueType::ExhaustiveEnumMatch,
+                severity: if count >= MANY_ENUM_MATCHES || found.matched_in_other_crates() {
+                    Severity::Medium
+                } else {
+                    Severity::Low
+                },
+                source: format!("{}::{}", found.module, found.enum_name),
+                target: format!("{} exhaustive matches", count),
+                description: format!(
+                    "Public enum {} ({} variants) is matched without a wildcard arm {} time{} in {}; adding a variant breaks each match",
+                    found.enum_name,
+                    found.variants,
+                    count,
+                    if count == 1 { "" } else { "s" },
+                    modules.join(", ")
+                ),
+                refactoring: RefactoringAction::General {
+                    action: format!(
+                        "Mark {} #[non_exhaustive] so other crates keep a wildcard arm, or move the per-variant behavior into methods on {}",
+                        found.enum_name, found.enum_name
+                    ),
+                },
+                balance_score: 1.0 / count as f64,
+                location: found.location,
+            }
+        })
+        .collect()
+}
+
+/// Report public structs other crates build with literals; tuple structs
+/// built by position are Connascence of Position as well
+fn analyze_struct_literals(metrics: &ProjectMetrics) -> Vec<CouplingIssue> {
+    let mut issues = Vec::new();
+    for found in find_literal_structs(metrics) {
+        let count = found.literals.len();
+        let source = format!("{}::{}", found.module, found.struct_name);
+        let crates = found.literal_crates().join(", ");
+        let action = if found.has_constructor {
+            format!(
+                "Build {} through its constructor in {}, and mark it #[non_exhaustive] so new fields stay compatible",
+                found.struct_name, crates
+            )
+        } else {
+            format!(
+                "Make the fields of {} private and offer a constructor or builder, or mark it #[non_exhaustive]",
+                found.struct_name
+            )
+        };
+        issues.push(CouplingIssue {
+            issue_type: IssueType::FieldVisibilityLeak,
+            severity: if found.has_constructor {
+                Severity::Low
+            } else {
+                Severity::Medium
+            },
+            source: source.clone(),
+            target: format!("{} struct literals", count),
+            description: format!(
+                "Public struct {} ({} public fields{}) is built with a literal {} time{} in {}; adding a field breaks each of them",
+                found.struct_name,
+                found.fields,
+                if found.has_constructor {
+                    ", has a constructor"
+                } else {
+                    ", no constructor"
+                },
+                count,
+                if count == 1 { "" } else { "s" },
+                crates
+            ),
+            refactoring: RefactoringAction::General { action },
+            balance_score: 1.0 / (1.0 + count as f64),
+            location: found.location.clone(),
+        });
+
+        if found.positional && found.fields >= 2 {
+            let first = &found.literals[0];
+            issues.push(CouplingIssue {
+                issue_type: IssueType::ConnascenceOfPosition,
+                severity: if found.fields >= 3 {
+                    Severity::Medium
+                } else {
+                    Severity::Low
+                },
+                source: format!("{}::{}", first.module, first.function)
+                    .trim_end_matches("::")
+                    .to_string(),
+                target: source,
+                description: format!(
+                    "Tuple struct {} is built from {} fields by position {} time{} in {}; callers must keep the field order",
+                    found.struct_name,
+                    found.fields,
+                    count,
+                    if count == 1 { "" } else { "s" },
+                    crates
+                ),
+                refactoring: RefactoringAction::General {
+                    action: format!(
+                        "Give {} named fields, or a constructor whose parameters have distinct types",
+                        found.struct_name
+                    ),
+                },
+                balance_score: 1.0 / (1.0 + found.fields as f64),
+                location: first.location.clone(),
+            });
+        }
+    }
+    issues
+}
+
+/// Report foreign traits implemented for newtypes around foreign types
+fn analyze_orphan_workarounds(metrics: &ProjectMetrics) -> Vec<CouplingIssue> {
+    find_orphan_workarounds(metrics)
+        .into_iter()
+        .map(|found| {
+            let implementor = found.krate.as_deref().unwrap_or(&found.module);
+            CouplingIssue {
+                issue_type: IssueType::OrphanRuleWorkaround,
+                severity: if found.is_three_way() {
+                    Severity::Medium
+                } else {
+                    Severity::Low
+                },
+                source: format!("{}::{}", found.module, found.wrapper),
+                target: found.trait_path.clone(),
+                description: format!(
+                    "{} wraps {} to implement {}, coupling {} to {} and {}",
+                    found.wrapper,
+                    found.inner_type,
+                    found.trait_path,
+                    implementor,
+                    found.trait_crate(),
+                    found.type_crate()
+                ),
+                refactoring: RefactoringAction::General {
+                    action: format!(
+                        "Use the impl from {} or {} if one exists (often behind a feature), or convert at the boundary instead of wrapping",
+                        found.trait_crate(),
+                        found.type_crate()
+                    ),
+                },
+                balance_score: 0.6,
+                location: CouplingLocation::new(found.file.clone(), found.line, 0),
+            }
+        })
+        .collect()
+}
+
 /// Analyze Rust-specific patterns (God Module, Public Field Exposure, Primitive Obsession)
 fn analyze_rust_patterns(
     metrics: &ProjectMetrics,
@@ -829,6 +1735,7 @@ fn analyze_rust_patterns(
                     ],
                 },
                 balance_score: 0.5,
+                location: CouplingLocation::file(module.path.clone()),
             });
         }
 
@@ -851,6 +1758,11 @@ fn analyze_rust_patterns(
                         fields: vec!["// Add getter methods".to_string()],
                     },
                     balance_score: 0.7,
+                    location: CouplingLocation::new(
+                        module.path.clone(),
+                        type_def.line,
+                        type_def.column,
+                    ),
                 });
             }
         }
@@ -879,6 +1791,11 @@ fn analyze_rust_patterns(
                             wrapped_type: "// Group related parameters".to_string(),
                         },
                         balance_score: 0.7,
+                        location: CouplingLocation::new(
+                            module.path.clone(),
+                            func_def.line,
+                            func_def.column,
+                        ),
                     });
                 }
             }
@@ -888,6 +1805,878 @@ fn analyze_rust_patterns(
     issues
 }
 
+/// Convert temporal coupling findings into issues
+fn analyze_temporal_patterns(
+    metrics: &ProjectMetrics,
+    config: &TemporalConfig,
+) -> Vec<CouplingIssue> {
+    let _span = tracing::debug_span!("temporal").entered();
+    let mut issues = Vec::new();
+
+    for (module_name, module) in &metrics.modules {
+        for finding in &module.temporal.issues {
+            let severity = config.severity(finding.kind, &finding.operation);
+            let (issue_type, refactoring) = match finding.kind {
+                TemporalIssueKind::UnpairedOperation => (
+                    IssueType::UnpairedOperation,
+                    RefactoringAction::General {
+                        action: format!(
+                            "Wrap `{}()` in a guard type that closes the operation on Drop",
+                            finding.operation
+                        ),
+                    },
+                ),
+                TemporalIssueKind::PanickingBuilder => (
+                    IssueType::PanickingBuilder,
+                    RefactoringAction::General {
+                        action: "Take required values in the builder's constructor, return a Result from build(), or track them with typestate".to_string(),
+                    },
+                ),
+                TemporalIssueKind::PanicPath => (
+                    IssueType::PanicPath,
+                    RefactoringAction::General {
+                        action: "Set the state in the constructor, return an error when it is missing, or split the type so the initialized state is its own type".to_string(),
+                    },
+                ),
+                TemporalIssueKind::CancellationUnsafeSelect => (
+                    IssueType::CancellationUnsafeSelect,
+                    RefactoringAction::General {
+                        action: format!(
+                            "Create the `{}` future once outside the loop and select on `&mut` of it, or move it into a task that owns its buffer",
+                            finding.operation
+                        ),
+                    },
+                ),
+                TemporalIssueKind::LockOrderInversion => (
+                    IssueType::LockOrderInversion,
+                    RefactoringAction::General {
+                        action: "Acquire the locks in one fixed order everywhere, or merge the state they protect behind a single lock".to_string(),
+                    },
+                ),
+                TemporalIssueKind::DropOrder => (
+                    IssueType::DropOrderCoupling,
+                    RefactoringAction::General {
+                        action: "Give each resource its own type with its own Drop so teardown follows ownership, and document the field order the remaining Drop relies on".to_string(),
+                    },
+                ),
+                TemporalIssueKind::DetachedSpawn => (
+                    IssueType::DetachedSpawn,
+                    RefactoringAction::General {
+                        action: "Keep the JoinHandle (or spawn into a JoinSet) and await or abort it on shutdown".to_string(),
+                    },
+                ),
+                TemporalIssueKind::DroppedGuard => (
+                    IssueType::DroppedGuard,
+                    RefactoringAction::General {
+                        action: format!(
+                            "Bind the `{}()` guard to a named variable (e.g. `_guard`)",
+                            finding.operation
+                        ),
+                    },
+                ),
+            };
+
+            let source = match &finding.function {
+                Some(function) => format!("{}::{}", module_name, function),
+                None => module_name.clone(),
+            };
+            issues.push(CouplingIssue {
+                issue_type,
+                severity,
+                source,
+                target: format!("{}()", finding.operation),
+                description: format!("{} (line {})", finding.message, finding.line),
+                refactoring,
+                balance_score: 0.5,
+                location: CouplingLocation::new(module.path.clone(), finding.line, finding.column),
+            });
+        }
+    }
+
+    issues
+}
+
+/// Report modules whose cognitive load is in the "very high" class
+fn analyze_cognitive_load(metrics: &ProjectMetrics, config: &AposdConfig) -> Vec<CouplingIssue> {
+    let _span = tracing::debug_span!("aposd").entered();
+    analyze_module_depths_with(metrics, config)
+        .into_iter()
+        .filter(|depth| depth.cognitive_class == CognitiveLoadClass::VeryHigh)
+        .map(|depth| CouplingIssue {
+            issue_type: IssueType::HighCognitiveLoad,
+            severity: Severity::Medium,
+            source: depth.module.clone(),
+            target: String::new(),
+            description: format!(
+                "Cognitive load {} (public items, extra parameters and dependencies; very high from {})",
+                depth.cognitive_load, config.very_high_cognitive_load
+            ),
+            refactoring: RefactoringAction::General {
+                action: "Hide more behind fewer public items, or split the module by responsibility"
+                    .to_string(),
+            },
+            balance_score: config.very_high_cognitive_load as f64
+                / depth.cognitive_load.max(1) as f64,
+            location: module_location(metrics, &depth.module),
+        })
+        .collect()
+}
+
+/// Report modules with a large interface and few documented public items
+fn analyze_non_obvious_interfaces(
+    metrics: &ProjectMetrics,
+    config: &AposdConfig,
+) -> Vec<CouplingIssue> {
+    analyze_module_depths_with(metrics, config)
+        .into_iter()
+        .filter(|depth| depth.is_non_obvious(config))
+        .map(|depth| {
+            let coverage = metrics
+                .modules
+                .get(&depth.module)
+                .map(|m| m.doc_coverage.clone())
+                .unwrap_or_default();
+            let mut examples = coverage
+                .undocumented
+                .iter()
+                .take(5)
+                .map(|name| format!("`{}`", name))
+                .collect::<Vec<_>>()
+                .join(", ");
+            if coverage.undocumented.len() > 5 {
+                examples.push_str(", ...");
+            }
+            CouplingIssue {
+                issue_type: IssueType::NonObviousInterface,
+                severity: Severity::Medium,
+                source: depth.module.clone(),
+                target: format!("{} undocumented items", coverage.undocumented.len()),
+                description: format!(
+                    "Interface complexity {} with {} of {} public items documented ({:.0}%, expected {:.0}%); undocumented: {}",
+                    depth.interface_complexity,
+                    coverage.documented,
+                    coverage.public_items,
+                    depth.doc_coverage.unwrap_or(0.0) * 100.0,
+                    config.min_doc_coverage * 100.0,
+                    examples
+                ),
+                refactoring: RefactoringAction::General {
+                    action: "Document what each public item does and how callers use it, or hide the items callers do not need"
+                        .to_string(),
+                },
+                balance_score: depth.doc_coverage.unwrap_or(0.0),
+                location: module_location(metrics, &depth.module),
+            }
+        })
+        .collect()
+}
+
+/// Report modules whose public functions return many error cases
+fn analyze_fine_grained_errors(
+    metrics: &ProjectMetrics,
+    config: &AposdConfig,
+) -> Vec<CouplingIssue> {
+    find_error_exposures(metrics)
+        .into_iter()
+        .filter(|exposure| exposure.error_cases() >= config.max_error_cases)
+        .map(|exposure| {
+            let cases = exposure.error_cases();
+            let errors = exposure
+                .errors
+                .iter()
+                .map(|e| format!("`{}` ({})", e.name, e.cases))
+                .collect::<Vec<_>>()
+                .join(", ");
+            CouplingIssue {
+                issue_type: IssueType::FineGrainedErrors,
+                severity: if cases >= config.max_error_cases * 2 {
+                    Severity::High
+                } else {
+                    Severity::Medium
+                },
+                source: exposure.module.clone(),
+                target: format!("{} error cases", cases),
+                description: format!(
+                    "{} public functions return `Result` with {} error cases: {}",
+                    exposure.functions.len(),
+                    cases,
+                    errors
+                ),
+                refactoring: RefactoringAction::General {
+                    action: "Handle or mask the cases callers cannot act on inside the module, and merge the cases callers treat alike into one variant"
+                        .to_string(),
+                },
+                balance_score: config.max_error_cases as f64 / cases.max(1) as f64,
+                location: exposure.location,
+            }
+        })
+        .collect()
+}
+
+/// Report functions splitting into separate paths on a mode parameter
+fn analyze_special_case_flags(metrics: &ProjectMetrics) -> Vec<CouplingIssue> {
+    let mut issues = Vec::new();
+    for module in metrics.modules.values() {
+        for switch in &module.mode_switches {
+            let branches = switch
+                .branches
+                .iter()
+                .map(|branch| {
+                    let arms = branch
+                        .arms
+                        .iter()
+                        .map(|arm| format!("`{}` {} lines", arm.label, arm.lines))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    format!(
+                        "{} at line {} ({}; {} of {} calls shared)",
+                        branch.keyword, branch.line, arms, branch.shared_calls, branch.calls
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("; ");
+            issues.push(CouplingIssue {
+                issue_type: IssueType::SpecialCaseFlag,
+                severity: if switch.is_public {
+                    Severity::Medium
+                } else {
+                    Severity::Low
+                },
+                source: format!("{}::{}", module.name, switch.function),
+                target: format!("{}: {}", switch.parameter, switch.parameter_type),
+                description: format!(
+                    "`{}` splits into {} paths on `{}`, covering {} of its {} lines: {}",
+                    switch.function,
+                    switch.paths(),
+                    switch.parameter,
+                    switch.switched_lines().min(switch.lines),
+                    switch.lines,
+                    branches
+                ),
+                refactoring: RefactoringAction::General {
+                    action: format!(
+                        "Split `{}` into one function per `{}` value and let callers call the one they need; move the shared steps into a helper",
+                        switch.function, switch.parameter
+                    ),
+                },
+                balance_score: 1.0 / switch.paths().max(1) as f64,
+                location: CouplingLocation::new(module.path.clone(), switch.line, switch.column),
+            });
+        }
+    }
+    issues
+}
+
+/// Report wide public traits with many implementors
+fn analyze_wide_traits(metrics: &ProjectMetrics, config: &AposdConfig) -> Vec<CouplingIssue> {
+    analyze_trait_surfaces(metrics, config)
+        .into_iter()
+        .filter(|surface| surface.high_cost)
+        .map(|surface| {
+            let location = metrics
+                .modules
+                .get(&surface.module)
+                .map(|m| {
+                    let def = m.type_definitions.get(&surface.name);
+                    CouplingLocation::new(
+                        m.path.clone(),
+                        def.map_or(0, |d| d.line),
+                        def.map_or(0, |d| d.column),
+                    )
+                })
+                .unwrap_or_default();
+            CouplingIssue {
+                issue_type: IssueType::WideTrait,
+                severity: Severity::Medium,
+                source: format!("{}::{}", surface.module, surface.name),
+                target: format!("{} implementors", surface.implementors),
+                description: format!(
+                    "Trait {} has {} methods ({} required) and {} implementors: {} method bodies to update when the trait changes",
+                    surface.name,
+                    surface.methods(),
+                    surface.required_methods,
+                    surface.implementors,
+                    surface.cost()
+                ),
+                refactoring: RefactoringAction::General {
+                    action: "Give methods default bodies built on a few required ones, or split the trait so implementors only write what they need"
+                        .to_string(),
+                },
+                balance_score: config.wide_trait_methods as f64 / surface.methods().max(1) as f64,
+                location,
+            }
+        })
+        .collect()
+}
+
+/// Report trait default methods most implementors override
+fn analyze_overridden_defaults(metrics: &ProjectMetrics) -> Vec<CouplingIssue> {
+    find_overridden_defaults(metrics)
+        .into_iter()
+        .map(|found| {
+            let location = metrics
+                .modules
+                .get(&found.module)
+                .map(|m| {
+                    let def = m.type_definitions.get(&found.trait_name);
+                    CouplingLocation::new(
+                        m.path.clone(),
+                        def.map_or(0, |d| d.line),
+                        def.map_or(0, |d| d.column),
+                    )
+                })
+                .unwrap_or_default();
+            let (severity, action) = if found.never_used() {
+                (
+                    Severity::Medium,
+                    format!(
+                        "Make `{}` a required method; no implementor uses its default body",
+                        found.method
+                    ),
+                )
+            } else {
+                (
+                    Severity::Low,
+                    format!(
+                        "Move the default body of `{}` to the few types that use it, or split them into their own trait",
+                        found.method
+                    ),
+                )
+            };
+            CouplingIssue {
+                issue_type: IssueType::OverriddenDefault,
+                severity,
+                source: format!("{}::{}", found.module, found.trait_name),
+                target: format!("{}()", found.method),
+                description: format!(
+                    "Default method {}::{} is overridden by {} of {} implementors",
+                    found.trait_name, found.method, found.overrides, found.implementors
+                ),
+                refactoring: RefactoringAction::General { action },
+                balance_score: 1.0 - found.overrides as f64 / found.implementors.max(1) as f64,
+                location,
+            }
+        })
+        .collect()
+}
+
+/// Report sibling modules organized by execution phase
+fn analyze_temporal_decompositions(metrics: &ProjectMetrics) -> Vec<CouplingIssue> {
+    find_temporal_decompositions(metrics)
+        .into_iter()
+        .map(|found| {
+            let phases = found
+                .phases
+                .iter()
+                .map(|p| format!("`{}`", p))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let (severity, description, action) = if found.shared_types.is_empty() {
+                (
+                    Severity::Low,
+                    format!(
+                        "Modules {} of `{}` are named after execution phases",
+                        phases, found.parent
+                    ),
+                    "Name and group the modules by the knowledge they hide rather than the order they run in".to_string(),
+                )
+            } else {
+                let types = found
+                    .shared_types
+                    .iter()
+                    .map(|t| format!("`{}`", t))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                (
+                    Severity::Medium,
+                    format!(
+                        "Modules {} of `{}` are split by execution phase and all work on {}",
+                        phases, found.parent, types
+                    ),
+                    format!(
+                        "Group the code that knows {} into one module, and let the phases call it",
+                        types
+                    ),
+                )
+            };
+            let first = format!("{}::{}", found.parent, found.phases[0]);
+            let location = metrics
+                .modules
+                .values()
+                .find(|m| m.crate_name == found.krate && m.rust_path() == first)
+                .map(|m| CouplingLocation::file(m.path.clone()))
+                .unwrap_or_default();
+            CouplingIssue {
+                issue_type: IssueType::TemporalDecomposition,
+                severity,
+                source: first,
+                target: format!("{} phase modules", found.phases.len()),
+                description,
+                refactoring: RefactoringAction::General { action },
+                balance_score: 1.0 / found.phases.len() as f64,
+                location,
+            }
+        })
+        .collect()
+}
+
+/// Report co-changing files that have no static dependency
+fn analyze_hidden_coupling(metrics: &ProjectMetrics) -> Vec<CouplingIssue> {
+    metrics
+        .hidden_couplings()
+        .into_iter()
+        .map(|pair| CouplingIssue {
+            issue_type: IssueType::HiddenCoupling,
+            severity: if pair.ratio >= 0.8 {
+                Severity::Medium
+            } else {
+                Severity::Low
+            },
+            source: pair.file_a.clone(),
+            target: pair.file_b.clone(),
+            description: format!(
+                "{} and {} changed together in {} commits ({:.0}%) but have no static dependency{}",
+                pair.file_a,
+                pair.file_b,
+                pair.co_changes,
+                pair.ratio * 100.0,
+                format_examples(&pair.examples)
+            ),
+            refactoring: RefactoringAction::General {
+                action: "Make the shared knowledge explicit (extract a common module or type)"
+                    .to_string(),
+            },
+            balance_score: 1.0 - pair.ratio,
+            location: CouplingLocation::file(pair.file_a.clone().into()),
+        })
+        .collect()
+}
+
+/// Example commit subjects as `, e.g. 'fix a', 'fix b'`, or nothing
+fn format_examples(examples: &[String]) -> String {
+    if examples.is_empty() {
+        return String::new();
+    }
+    let quoted: Vec<String> = examples.iter().map(|s| format!("'{}'", s)).collect();
+    format!(", e.g. {}", quoted.join(", "))
+}
+
+/// Report modules changed by many authors and coupled to many modules
+fn analyze_organizational_coupling(
+    metrics: &ProjectMetrics,
+    max_authors: usize,
+) -> Vec<CouplingIssue> {
+    find_ownership_hotspots(metrics, max_authors)
+        .into_iter()
+        .map(|hotspot| CouplingIssue {
+            issue_type: IssueType::OrganizationalCoupling,
+            severity: if hotspot.authors > max_authors * 2 {
+                Severity::High
+            } else {
+                Severity::Medium
+            },
+            source: format!("{} authors", hotspot.authors),
+            target: hotspot.module.clone(),
+            description: format!(
+                "{} was changed by {} authors (top: {} with {:.0}% of commits) and is coupled to {} modules",
+                hotspot.module,
+                hotspot.authors,
+                hotspot.top_author,
+                hotspot.top_share * 100.0,
+                hotspot.coupled_modules
+            ),
+            refactoring: RefactoringAction::General {
+                action: "Give the module a clear owner, or split it along the lines of the teams changing it".to_string(),
+            },
+            balance_score: max_authors as f64 / hotspot.authors as f64,
+            location: CouplingLocation::file(hotspot.file),
+        })
+        .collect()
+}
+
+/// Report calls whose same-typed arguments could be swapped unnoticed,
+/// magic numbers shared between modules, algorithms shared across module or
+/// crate boundaries, call order that callers must know, and global state
+/// shared between modules
+fn analyze_connascence(metrics: &ProjectMetrics) -> Vec<CouplingIssue> {
+    let _span = tracing::debug_span!("connascence").entered();
+    let mut issues: Vec<CouplingIssue> = find_positional_risks(metrics)
+        .into_iter()
+        .map(|risk| {
+            let source = match &risk.caller {
+                Some(caller) => format!("{}::{}", risk.module, caller),
+                None => risk.module.clone(),
+            };
+            CouplingIssue {
+                issue_type: IssueType::ConnascenceOfPosition,
+                severity: if risk.risk() >= 3 {
+                    Severity::Medium
+                } else {
+                    Severity::Low
+                },
+                source,
+                target: format!("{}::{}", risk.callee_module, risk.callee),
+                description: format!(
+                    "`{}()` takes {} swappable argument pair(s) of type {} ({} literal, {} call(s))",
+                    risk.callee,
+                    risk.swappable_pairs,
+                    risk.shared_types.join(", "),
+                    risk.literal_args,
+                    risk.call_sites
+                ),
+                refactoring: RefactoringAction::General {
+                    action: format!(
+                        "Give `{}()` distinct parameter types (newtypes) or a named-field argument struct",
+                        risk.callee
+                    ),
+                },
+                balance_score: 1.0 / (1.0 + risk.risk() as f64),
+                location: risk.location,
+            }
+        })
+        .collect();
+
+    issues.extend(find_shared_literals(metrics).into_iter().map(|shared| {
+        let (first, others) = shared
+            .modules
+            .split_first()
+            .expect("shared literal spans at least two modules");
+        CouplingIssue {
+            issue_type: IssueType::ConnascenceOfMeaning,
+            severity: if shared.modules.len() >= 3 {
+                Severity::Medium
+            } else {
+                Severity::Low
+            },
+            source: first.clone(),
+            target: others.join(", "),
+            description: format!(
+                "Magic number {} appears {} time(s) across {} modules",
+                shared.value,
+                shared.occurrences,
+                shared.modules.len()
+            ),
+            refactoring: RefactoringAction::General {
+                action: format!(
+                    "Replace {} with a named constant in a shared module",
+                    shared.value
+                ),
+            },
+            balance_score: 1.0 / shared.modules.len() as f64,
+            location: shared.location,
+        }
+    }));
+
+    issues.extend(find_shared_schema(metrics).into_iter().map(|shared| {
+        let (first, others) = shared
+            .modules
+            .split_first()
+            .expect("shared table spans at least two modules");
+        let columns = if shared.columns.is_empty() {
+            String::new()
+        } else {
+            format!(" (shared columns: {})", shared.columns.join(", "))
+        };
+        CouplingIssue {
+            issue_type: IssueType::ConnascenceOfMeaning,
+            severity: if shared.modules.len() >= 3 {
+                Severity::Medium
+            } else {
+                Severity::Low
+            },
+            source: first.clone(),
+            target: others.join(", "),
+            description: format!(
+                "SQL table `{}` is queried {} time(s) across {} modules{}",
+                shared.table,
+                shared.occurrences,
+                shared.modules.len(),
+                columns
+            ),
+            refactoring: RefactoringAction::General {
+                action: format!(
+                    "Move the queries on `{}` into one repository module, or share the table and column names as constants",
+                    shared.table
+                ),
+            },
+            balance_score: 1.0 / shared.modules.len() as f64,
+            location: shared.location,
+        }
+    }));
+
+    issues.extend(find_algorithm_couplings(metrics).into_iter().map(|pair| {
+        // Across crates with no dependency between them nothing ties the
+        // two sides together except the algorithm itself
+        let (severity, boundary) = if !pair.crosses_crate() {
+            (Severity::Low, "across modules")
+        } else if pair.crates_linked {
+            (Severity::Medium, "across crates")
+        } else {
+            (Severity::High, "across crates with no dependency between them")
+        };
+        let describe = |module: &str, krate: &Option<String>| match krate {
+            Some(krate) => format!("{}::{}", krate, module),
+            None => module.to_string(),
+        };
+        CouplingIssue {
+            issue_type: IssueType::ConnascenceOfAlgorithm,
+            severity,
+            source: describe(&pair.producer, &pair.producer_crate),
+            target: describe(&pair.consumer, &pair.consumer_crate),
+            description: format!(
+                "`{}` {} shared {}",
+                pair.scheme, pair.family, boundary
+            ),
+            refactoring: RefactoringAction::General {
+                action: format!(
+                    "Move both directions of the `{}` {} into one module and call it from both sides",
+                    pair.scheme, pair.family
+                ),
+            },
+            balance_score: match severity {
+                Severity::High => 0.3,
+                Severity::Medium => 0.5,
+                _ => 0.7,
+            },
+            location: pair.location,
+        }
+    }));
+
+    issues.extend(find_code_clones(metrics).into_iter().map(|clone| {
+        let percent = (clone.similarity * 100.0).round();
+        CouplingIssue {
+            issue_type: IssueType::ConnascenceOfAlgorithm,
+            severity: if clone.similarity >= 0.95 {
+                Severity::Medium
+            } else {
+                Severity::Low
+            },
+            source: format!("{}::{}", clone.first.module, clone.first.function),
+            target: format!("{}::{}", clone.second.module, clone.second.function),
+            description: format!(
+                "`{}` and `{}` are {}% identical ({} tokens); a fix to one must be copied to the other",
+                clone.first.function, clone.second.function, percent, clone.tokens
+            ),
+            refactoring: RefactoringAction::General {
+                action: format!(
+                    "Extract the shared body of `{}` into one function in {} and call it from both modules",
+                    clone.first.function,
+                    match &clone.extraction_target {
+                        Some(module) => format!("`{}`", module),
+                        None => "a crate both modules' crates depend on".to_string(),
+                    }
+                ),
+            },
+            balance_score: 1.0 - clone.similarity,
+            location: clone.first.location,
+        }
+    }));
+
+    issues.extend(find_execution_orders(metrics).into_iter().map(|order| {
+        let foreign = order.foreign_unguarded_callers();
+        let severity = if foreign > 0 {
+            Severity::Medium
+        } else {
+            Severity::Low
+        };
+        let reason = match &order.evidence {
+            ExecutionEvidence::StateCheck { field } => format!("checks `self.{}`", field),
+            ExecutionEvidence::Documented => "documented".to_string(),
+        };
+        let function = match &order.owner {
+            Some(owner) => format!("{}::{}", owner, order.function),
+            None => order.function.clone(),
+        };
+        CouplingIssue {
+            issue_type: IssueType::ConnascenceOfExecution,
+            severity,
+            source: format!("{}::{}", order.module, function),
+            target: order.prerequisites.join(", "),
+            description: format!(
+                "`{}` must run after `{}` ({}); {} of {} caller(s) do not call it first",
+                order.function,
+                order.prerequisites.join("`/`"),
+                reason,
+                order.unguarded_callers.len(),
+                order.callers.len()
+            ),
+            refactoring: RefactoringAction::General {
+                action: format!(
+                    "Make the order explicit: have `{}` return a value that `{}` requires (typestate)",
+                    order.prerequisites[0], order.function
+                ),
+            },
+            balance_score: 1.0 / (1.0 + foreign as f64),
+            location: order.location,
+        }
+    }));
+
+    issues.extend(find_shared_state(metrics).into_iter().map(|state| {
+        let severity = if state.kind == SharedStateKind::StaticMut {
+            Severity::High
+        } else if state.users.len() >= 2 {
+            Severity::Medium
+        } else {
+            Severity::Low
+        };
+        CouplingIssue {
+            issue_type: IssueType::ConnascenceOfIdentity,
+            severity,
+            source: format!("{}::{}", state.module, state.name),
+            target: state.users.join(", "),
+            description: format!(
+                "{} `{}` is shared with {} other module(s)",
+                state.kind,
+                state.name,
+                state.users.len()
+            ),
+            refactoring: RefactoringAction::General {
+                action: format!(
+                    "Pass `{}` explicitly (constructor or function argument) instead of reaching for the global",
+                    state.name
+                ),
+            },
+            balance_score: 1.0 / (1.0 + state.users.len() as f64),
+            location: state.location,
+        }
+    }));
+
+    issues.extend(find_shared_signature_types(metrics).into_iter().map(|shared| {
+        let severity = if shared.users.len() >= 2 * MIN_SIGNATURE_TYPE_USERS
+            || shared.user_crates.len() >= 2
+        {
+            Severity::Medium
+        } else {
+            Severity::Low
+        };
+        let crates = if shared.user_crates.is_empty() {
+            String::new()
+        } else {
+            format!(" in {} other crate(s)", shared.user_crates.len())
+        };
+        CouplingIssue {
+            issue_type: IssueType::ConnascenceOfType,
+            severity,
+            source: format!("{}::{}", shared.module, shared.name),
+            target: shared.users.join(", "),
+            description: format!(
+                "`{}` appears in the public signatures of {} other modules{}: {}",
+                shared.name,
+                shared.users.len(),
+                crates,
+                shared.users.join(", ")
+            ),
+            refactoring: RefactoringAction::General {
+                action: format!(
+                    "Introduce a trait for what callers need from `{}` and accept `impl Trait` or a generic there",
+                    shared.name
+                ),
+            },
+            balance_score: 1.0 / (1.0 + shared.users.len() as f64),
+            location: shared.location,
+        }
+    }));
+    issues
+}
+
+/// Report groups of modules or crates that depend on each other
+fn analyze_cycles(metrics: &ProjectMetrics) -> Vec<CouplingIssue> {
+    let module_cycles = metrics.module_cycles().into_iter().map(|c| (c, false));
+    let crate_cycles = metrics.crate_cycles.iter().cloned().map(|c| (c, true));
+
+    module_cycles
+        .chain(crate_cycles)
+        .map(|(cycle, crate_level)| {
+            let edge = &cycle.weakest_edge;
+            let (kind, severity, references) = if crate_level {
+                let via = if edge.weight == 1 {
+                    "dev-dependency"
+                } else {
+                    "dependency"
+                };
+                ("crates", Severity::Medium, via.to_string())
+            } else {
+                (
+                    "modules",
+                    Severity::High,
+                    format!("{} reference(s)", edge.weight),
+                )
+            };
+            CouplingIssue {
+                issue_type: IssueType::CircularDependency,
+                severity,
+                source: edge.from.clone(),
+                target: edge.to.clone(),
+                description: format!(
+                    "{} {} depend on each other: {}",
+                    cycle.members.len(),
+                    kind,
+                    cycle.members.join(", ")
+                ),
+                refactoring: RefactoringAction::BreakCycle {
+                    suggested_direction: format!(
+                        "removing {} → {} ({})",
+                        edge.from, edge.to, references
+                    ),
+                },
+                balance_score: 0.0,
+                location: if crate_level {
+                    CouplingLocation::default()
+                } else {
+                    module_location(metrics, &edge.from)
+                },
+            }
+        })
+        .collect()
+}
+
+/// Report dependencies that go against the declared layering
+fn analyze_layer_violations(metrics: &ProjectMetrics, layers: &[Layer]) -> Vec<CouplingIssue> {
+    find_layer_violations(metrics, layers)
+        .into_iter()
+        .map(|violation| CouplingIssue {
+            issue_type: IssueType::LayerViolation,
+            severity: Severity::High,
+            description: if violation.manifest {
+                format!(
+                    "Crate {} ({}) lists {} ({}) as a dependency",
+                    violation.source,
+                    violation.source_layer,
+                    violation.target,
+                    violation.target_layer
+                )
+            } else {
+                format!(
+                    "{} ({}) references {} ({}) {} time(s)",
+                    violation.source,
+                    violation.source_layer,
+                    violation.target,
+                    violation.target_layer,
+                    violation.references
+                )
+            },
+            refactoring: RefactoringAction::General {
+                action: format!(
+                    "Invert the dependency: define a trait in {} and implement it in {}",
+                    violation.source_layer, violation.target_layer
+                ),
+            },
+            balance_score: 0.0,
+            location: violation.location,
+            source: violation.source,
+            target: violation.target,
+        })
+        .collect()
+}
+
+/// Location of a module's file (line unknown)
+fn module_location(metrics: &ProjectMetrics, name: &str) -> CouplingLocation {
+    metrics
+        .find_module(name)
+        .map(|m| CouplingLocation::file(m.path.clone()))
+        .unwrap_or_default()
+}
+
 /// Capitalize first letter of a string
 fn capitalize_first(s: &str) -> String {
     let mut chars = s.chars();
@@ -898,16 +2687,38 @@ fn capitalize_first(s: &str) -> String {
 }
 
 /// Health grade for the overall project
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///
+/// Serialized as its letter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum HealthGrade {
+    #[serde(rename = "S")]
     S, // Over-optimized - stop! you're doing too much
+    #[serde(rename = "A")]
     A, // Well-balanced - coupling is appropriate for the architecture
+    #[serde(rename = "B")]
     B, // Healthy - minor issues exist but manageable
+    #[serde(rename = "C")]
     C, // Room for improvement - some structural issues
+    #[serde(rename = "D")]
     D, // Attention needed - significant issues affecting maintainability
+    #[serde(rename = "F")]
     F, // Immediate action required - critical issues blocking development
 }
 
+impl HealthGrade {
+    /// The grade letter (`A`)
+    pub fn letter(self) -> &'static str {
+        match self {
+            HealthGrade::S => "S",
+            HealthGrade::A => "A",
+            HealthGrade::B => "B",
+            HealthGrade::C => "C",
+            HealthGrade::D => "D",
+            HealthGrade::F => "F",
+        }
+    }
+}
+
 impl std::fmt::Display for HealthGrade {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -929,7 +2740,7 @@ impl std::fmt::Display for HealthGrade {
 /// - Balance score distribution
 /// - Internal coupling complexity
 fn calculate_health_grade(
-    issues_by_severity: &HashMap<Severity, usize>,
+    issues_by_severity: &BTreeMap<Severity, usize>,
     internal_couplings: usize,
 ) -> HealthGrade {
     let critical = *issues_by_severity.get(&Severity::Critical).unwrap_or(&0);
@@ -992,8 +2803,8 @@ pub struct ProjectBalanceReport {
     pub needs_refactoring: usize,
     pub average_score: f64,
     pub health_grade: HealthGrade,
-    pub issues_by_severity: HashMap<Severity, usize>,
-    pub issues_by_type: HashMap<IssueType, usize>,
+    pub issues_by_severity: BTreeMap<Severity, usize>,
+    pub issues_by_type: BTreeMap<IssueType, usize>,
     pub issues: Vec<CouplingIssue>,
     pub top_priorities: Vec<CouplingIssue>,
 }
@@ -1006,8 +2817,8 @@ impl ProjectBalanceReport {
     }
 
     /// Get issues grouped by type
-    pub fn issues_grouped_by_type(&self) -> HashMap<IssueType, Vec<&CouplingIssue>> {
-        let mut grouped: HashMap<IssueType, Vec<&CouplingIssue>> = HashMap::new();
+    pub fn issues_grouped_by_type(&self) -> BTreeMap<IssueType, Vec<&CouplingIssue>> {
+        let mut grouped: BTreeMap<IssueType, Vec<&CouplingIssue>> = BTreeMap::new();
         for issue in &self.issues {
             grouped.entry(issue.issue_type).or_default().push(issue);
         }
@@ -1239,7 +3050,7 @@ mod tests {
 
     #[test]
     fn test_health_grade_calculation() {
-        let mut issues = HashMap::new();
+        let mut issues = BTreeMap::new();
 
         // No issues with >= 20 couplings = S (over-optimized warning)
         assert_eq!(calculate_health_grade(&issues, 100), HealthGrade::S);
@@ -1279,4 +3090,269 @@ mod tests {
         issues.insert(Severity::Medium, 20); // 20% of 100
         assert_eq!(calculate_health_grade(&issues, 100), HealthGrade::B);
     }
+
+    #[test]
+    fn test_temporal_issues_reported() {
+        use crate::metrics::ModuleMetrics;
+        use crate::temporal::TemporalAnalyzer;
+
+        let mut module = ModuleMetrics::new("src/db.rs".into(), "db".to_string());
+        module.temporal = TemporalAnalyzer::analyze_source(
+            "fn f(m: &Mutex<()>, tx: &Tx) { let _ = m.lock(); tx.begin(); }",
+        )
+        .unwrap();
+        let mut project = ProjectMetrics::new();
+        project.add_module(module);
+
+        let report = analyze_project_balance(&project);
+        assert!(
+            report
+                .issues
+                .iter()
+                .any(|i| i.issue_type == IssueType::DroppedGuard && i.severity == Severity::High)
+        );
+        assert!(
+            report
+                .issues
+                .iter()
+                .any(|i| i.issue_type == IssueType::UnpairedOperation)
+        );
+    }
+
+    #[test]
+    fn test_reexport_chain_reported() {
+        use crate::metrics::{ModuleMetrics, ReExport, Visibility};
+
+        let mut project = ProjectMetrics::new();
+        let crates = [
+            ("base", None),
+            ("core", Some("base::User")),
+            ("service", Some("core::User")),
+            ("app", Some("service::User")),
+        ];
+        for (krate, source) in crates {
+            let mut module =
+                ModuleMetrics::new(format!("{}/src/lib.rs", krate).into(), krate.to_string());
+            module.crate_name = Some(krate.to_string());
+            match source {
+                Some(source) => module.reexports.push(ReExport {
+                    source: source.to_string(),
+                    name: "User".to_string(),
+                    line: 1,
+                }),
+                None => module.add_type_definition("User".into(), Visibility::Public, false),
+            }
+            project.add_module(module);
+        }
+
+        let mut thresholds = IssueThresholds::default();
+        let issues = analyze_reexport_chains(&project, &thresholds);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::High);
+        assert_eq!(issues[0].source, "app::User");
+        assert!(
+            issues[0]
+                .description
+                .ends_with("base::User -> core::User -> service::User -> app::User")
+        );
+
+        thresholds.max_reexport_boundaries = 3;
+        assert!(analyze_reexport_chains(&project, &thresholds).is_empty());
+    }
+
+    #[test]
+    fn test_crate_threshold_overrides() {
+        let mut project = ProjectMetrics::new();
+        for krate in ["legacy", "app"] {
+            for i in 0..4 {
+                let mut coupling = CouplingMetrics::new(
+                    format!("{}::main", krate),
+                    format!("{}::dep{}", krate, i),
+                    IntegrationStrength::Functional,
+                    Distance::DifferentModule,
+                    Volatility::Low,
+                );
+                coupling.source_crate = Some(krate.to_string());
+                coupling.target_crate = Some(krate.to_string());
+                project.add_coupling(coupling);
+            }
+        }
+
+        let mut thresholds = IssueThresholds {
+            max_dependencies: 3,
+            ..IssueThresholds::default()
+        };
+        thresholds.crate_thresholds.insert(
+            "legacy".to_string(),
+            ThresholdOverrides {
+                max_dependencies: Some(10),
+                max_dependents: None,
+            },
+        );
+        let flagged: Vec<String> = analyze_module_coupling(&project, &thresholds)
+            .into_iter()
+            .filter(|i| i.issue_type == IssueType::HighEfferentCoupling)
+            .map(|i| i.source)
+            .collect();
+        assert_eq!(flagged, vec!["app::main"]);
+        assert_eq!(thresholds.max_dependents_for(Some("legacy"), None), 30);
+
+        // A matching path override wins over the crate's
+        thresholds.path_thresholds.push(PathOverride {
+            pattern: glob::Pattern::new("crates/legacy/**").unwrap(),
+            thresholds: ThresholdOverrides {
+                max_dependencies: Some(2),
+                max_dependents: None,
+            },
+        });
+        let legacy = Path::new("/repo/crates/legacy/src/lib.rs");
+        assert_eq!(
+            thresholds.max_dependencies_for(Some("legacy"), Some(legacy)),
+            2
+        );
+        assert_eq!(
+            thresholds.max_dependencies_for(Some("legacy"), Some(Path::new("src/lib.rs"))),
+            10
+        );
+    }
+
+    #[test]
+    fn test_listing_filters_keep_counts() {
+        use crate::metrics::ModuleMetrics;
+        use std::path::PathBuf;
+
+        let mut project = ProjectMetrics::new();
+        for (name, functions) in [("a", 40), ("b", 60), ("c", 50)] {
+            let mut module = ModuleMetrics::new(PathBuf::from(format!("{}.rs", name)), name.into());
+            for i in 0..functions {
+                module.add_function_definition(format!("f{}", i), Default::default());
+            }
+            project.add_module(module);
+        }
+
+        let all = analyze_project_balance_with_thresholds(&project, &IssueThresholds::default());
+        let god_modules = |report: &ProjectBalanceReport| {
+            report
+                .issues
+                .iter()
+                .filter(|i| i.issue_type == IssueType::GodModule)
+                .count()
+        };
+        assert_eq!(god_modules(&all), 3);
+
+        let top = analyze_project_balance_with_thresholds(
+            &project,
+            &IssueThresholds {
+                top_per_type: Some(1),
+                ..IssueThresholds::default()
+            },
+        );
+        assert_eq!(god_modules(&top), 1);
+        assert_eq!(top.issues_by_severity, all.issues_by_severity);
+        assert_eq!(top.health_grade, all.health_grade);
+
+        let critical = analyze_project_balance_with_thresholds(
+            &project,
+            &IssueThresholds {
+                min_severity: Some(Severity::Critical),
+                ..IssueThresholds::default()
+            },
+        );
+        assert!(critical.issues.is_empty());
+        assert_eq!(critical.issues_by_severity, all.issues_by_severity);
+    }
+
+    #[test]
+    fn test_issue_order_breaks_ties() {
+        use crate::metrics::ModuleMetrics;
+        use std::path::PathBuf;
+
+        let mut project = ProjectMetrics::new();
+        for name in ["c", "a", "b"] {
+            let mut module = ModuleMetrics::new(PathBuf::from(format!("{}.rs", name)), name.into());
+            for i in 0..40 {
+                module.add_function_definition(format!("f{}", i), Default::default());
+            }
+            project.add_module(module);
+        }
+
+        let report = analyze_project_balance_with_thresholds(&project, &IssueThresholds::default());
+        let sources: Vec<&str> = report.issues.iter().map(|i| i.source.as_str()).collect();
+        assert_eq!(sources, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_issue_file_and_module_resolution() {
+        use std::path::PathBuf;
+
+        let mut project = ProjectMetrics::new();
+        let mut lib = ModuleMetrics::new(PathBuf::from("src/lib.rs"), "lib".into());
+        lib.crate_name = Some("my-app".into());
+        project.add_module(lib);
+        project.add_module(ModuleMetrics::new(
+            PathBuf::from("src/net.rs"),
+            "net".into(),
+        ));
+
+        let issue = |source: &str, target: &str| CouplingIssue {
+            issue_type: IssueType::HighAfferentCoupling,
+            severity: Severity::Medium,
+            source: source.into(),
+            target: target.into(),
+            description: String::new(),
+            refactoring: RefactoringAction::General {
+                action: String::new(),
+            },
+            balance_score: 0.0,
+            location: CouplingLocation::default(),
+        };
+
+        let by_source = issue("my-app::net", "12 dependents");
+        assert_eq!(by_source.file(&project), Some(Path::new("src/net.rs")));
+        let crate_root = issue("12 dependents", "my-app::my_app");
+        assert_eq!(crate_root.module(&project).unwrap().name, "lib");
+        assert!(issue("12 dependents", "other").file(&project).is_none());
+    }
+
+    #[test]
+    fn test_non_obvious_interface() {
+        use crate::doc_coverage::DocCoverage;
+        use crate::metrics::Visibility;
+        use std::path::PathBuf;
+
+        let mut project = ProjectMetrics::new();
+        let mut store = ModuleMetrics::new(PathBuf::from("src/store.rs"), "store".into());
+        for i in 0..24 {
+            store.add_function_definition(format!("f{}", i), Visibility::Public);
+        }
+        store.lines_of_code = 500;
+        store.doc_coverage = DocCoverage {
+            public_items: 24,
+            documented: 4,
+            undocumented: (4..24).map(|i| format!("f{}", i)).collect(),
+        };
+        project.add_module(store);
+
+        let config = AposdConfig::default();
+        let issues = analyze_non_obvious_interfaces(&project, &config);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].source, "store");
+        assert_eq!(issues[0].target, "20 undocumented items");
+        assert!(
+            issues[0]
+                .description
+                .contains("4 of 24 public items documented (17%")
+        );
+        assert!(
+            issues[0]
+                .description
+                .contains("`f4`, `f5`, `f6`, `f7`, `f8`, ...")
+        );
+
+        let lenient = AposdConfig {
+            min_doc_coverage: 0.1,
+            ..config
+        };
+        assert!(analyze_non_obvious_interfaces(&project, &lenient).is_empty());
+    }
 }
@@ -0,0 +1,272 @@
+//! Baseline files for "new issues only" mode
+//!
+//! A baseline records the findings of a run so that later runs can report
+//! only what was introduced since. This lets teams adopt cargo-coupling on
+//! an existing codebase and gate CI on regressions without first fixing
+//! every pre-existing issue.
+//!
+//! Findings are matched by fingerprint (see [`crate::fingerprint`]): rule,
+//! source, target and the file's path relative to the workspace root. Line
+//! numbers are not part of it, so unrelated edits that shift code around do
+//! not make old findings reappear. Entries without a fingerprint, including
+//! those of version 1 baselines whose fingerprints used only the file name,
+//! are matched by rule, source and target. Duplicate keys are counted: if the baseline
+//! holds two identical findings and the current run has three, one is
+//! reported.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::balance::{CouplingIssue, IssueType};
+use crate::fingerprint::fingerprint;
+use crate::metrics::ProjectMetrics;
+
+/// Current baseline file format version
+pub const BASELINE_VERSION: u32 = 2;
+
+/// Version whose fingerprints hashed the file name instead of its path
+const LEGACY_VERSION: u32 = 1;
+
+/// Errors that can occur while reading or writing a baseline
+#[derive(Error, Debug)]
+pub enum BaselineError {
+    #[error("Failed to access baseline file: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Failed to parse baseline file: {0}")]
+    ParseError(#[from] serde_json::Error),
+
+    #[error("Unsupported baseline version {0} (expected {BASELINE_VERSION})")]
+    UnsupportedVersion(u32),
+}
+
+/// A single recorded finding
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    /// Rule id (`coupling::god-module`), as accepted by `--skip` and
+    /// `[rules.severity]`
+    pub rule: String,
+    pub source: String,
+    pub target: String,
+    /// Content-based fingerprint; empty in baselines from older versions
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub fingerprint: String,
+}
+
+impl BaselineEntry {
+    /// Key identifying an issue across runs
+    pub fn from_issue(issue: &CouplingIssue, metrics: &ProjectMetrics) -> Self {
+        Self {
+            rule: issue.issue_type.rule_id().to_string(),
+            source: issue.source.clone(),
+            target: issue.target.clone(),
+            fingerprint: fingerprint(issue, metrics),
+        }
+    }
+
+    /// Rule, source and target, for entries without a fingerprint
+    fn legacy_key(&self) -> (String, String, String) {
+        (self.rule.clone(), self.source.clone(), self.target.clone())
+    }
+}
+
+/// Recorded set of findings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Baseline {
+    pub version: u32,
+    /// Version of cargo-coupling that wrote the baseline
+    pub tool_version: String,
+    pub findings: Vec<BaselineEntry>,
+}
+
+impl Baseline {
+    /// Create a baseline from a list of issues
+    pub fn from_issues(issues: &[CouplingIssue], metrics: &ProjectMetrics) -> Self {
+        let mut findings: Vec<BaselineEntry> = issues
+            .iter()
+            .map(|issue| BaselineEntry::from_issue(issue, metrics))
+            .collect();
+        findings.sort();
+        Self {
+            version: BASELINE_VERSION,
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            findings,
+        }
+    }
+
+    /// Load a baseline from a JSON file
+    pub fn load(path: &Path) -> Result<Self, BaselineError> {
+        let content = fs::read_to_string(path)?;
+        let mut baseline: Baseline = serde_json::from_str(&content)?;
+        match baseline.version {
+            BASELINE_VERSION => {}
+            LEGACY_VERSION => {
+                for entry in &mut baseline.findings {
+                    entry.fingerprint.clear();
+                }
+                baseline.version = BASELINE_VERSION;
+            }
+            version => return Err(BaselineError::UnsupportedVersion(version)),
+        }
+        Ok(baseline.with_rule_ids())
+    }
+
+    /// Replace the issue type names older versions recorded as rules
+    /// (`GodModule`) with rule ids
+    fn with_rule_ids(mut self) -> Self {
+        for entry in &mut self.findings {
+            if let Some(issue_type) = IssueType::ALL
+                .iter()
+                .find(|issue_type| format!("{:?}", issue_type) == entry.rule)
+            {
+                entry.rule = issue_type.rule_id().to_string();
+            }
+        }
+        self
+    }
+
+    /// Write the baseline to a JSON file
+    pub fn save(&self, path: &Path) -> Result<(), BaselineError> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json + "\n")?;
+        Ok(())
+    }
+
+    /// Remove issues already recorded in the baseline, keeping only new ones
+    pub fn retain_new(&self, issues: &mut Vec<CouplingIssue>, metrics: &ProjectMetrics) {
+        let mut new = self.new_flags(issues, metrics).into_iter();
+        issues.retain(|_| new.next().unwrap_or(true));
+    }
+
+    /// Whether each issue is new, i.e. not (or not as often) recorded in
+    /// the baseline
+    pub fn new_flags(&self, issues: &[CouplingIssue], metrics: &ProjectMetrics) -> Vec<bool> {
+        let mut known: HashMap<&str, usize> = HashMap::new();
+        let mut legacy: HashMap<(String, String, String), usize> = HashMap::new();
+        for entry in &self.findings {
+            if entry.fingerprint.is_empty() {
+                *legacy.entry(entry.legacy_key()).or_default() += 1;
+            } else {
+                *known.entry(&entry.fingerprint).or_default() += 1;
+            }
+        }
+
+        let take = |count: Option<&mut usize>| match count {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                true
+            }
+            _ => false,
+        };
+        issues
+            .iter()
+            .map(|issue| {
+                let key = BaselineEntry::from_issue(issue, metrics);
+                !take(known.get_mut(key.fingerprint.as_str()))
+                    && !take(legacy.get_mut(&key.legacy_key()))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::balance::{IssueType, RefactoringAction, Severity};
+    use crate::metrics::CouplingLocation;
+
+    fn issue(issue_type: IssueType, source: &str, target: &str, line: usize) -> CouplingIssue {
+        CouplingIssue {
+            issue_type,
+            severity: Severity::Medium,
+            source: source.to_string(),
+            target: target.to_string(),
+            description: String::new(),
+            refactoring: RefactoringAction::General {
+                action: String::new(),
+            },
+            balance_score: 0.5,
+            location: CouplingLocation::new("src/a.rs".into(), line, 1),
+        }
+    }
+
+    #[test]
+    fn test_retain_new_ignores_lines_and_counts_duplicates() {
+        let metrics = ProjectMetrics::new();
+        let baseline = Baseline::from_issues(
+            &[
+                issue(IssueType::GlobalComplexity, "a", "b", 10),
+                issue(IssueType::GlobalComplexity, "a", "b", 20),
+            ],
+            &metrics,
+        );
+
+        let mut current = vec![
+            issue(IssueType::GlobalComplexity, "a", "b", 11),
+            issue(IssueType::GlobalComplexity, "a", "b", 21),
+            issue(IssueType::GlobalComplexity, "a", "b", 31),
+            issue(IssueType::CascadingChangeRisk, "a", "b", 5),
+        ];
+        baseline.retain_new(&mut current, &metrics);
+
+        assert_eq!(current.len(), 2);
+        assert_eq!(current[0].location.line, 31);
+        assert_eq!(current[1].issue_type, IssueType::CascadingChangeRisk);
+    }
+
+    #[test]
+    fn test_legacy_entries_match_without_fingerprint() {
+        let metrics = ProjectMetrics::new();
+        let mut baseline =
+            Baseline::from_issues(&[issue(IssueType::GlobalComplexity, "a", "b", 1)], &metrics);
+        baseline.findings[0].fingerprint.clear();
+
+        let mut current = vec![
+            issue(IssueType::GlobalComplexity, "a", "b", 7),
+            issue(IssueType::GlobalComplexity, "a", "b", 8),
+        ];
+        baseline.retain_new(&mut current, &metrics);
+        assert_eq!(current.len(), 1);
+
+        let json = serde_json::to_string(&baseline).unwrap();
+        assert!(!json.contains("fingerprint"));
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let metrics = ProjectMetrics::new();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("baseline.json");
+        let baseline =
+            Baseline::from_issues(&[issue(IssueType::GlobalComplexity, "a", "b", 1)], &metrics);
+        baseline.save(&path).unwrap();
+
+        let loaded = Baseline::load(&path).unwrap();
+        assert_eq!(loaded.findings, baseline.findings);
+        assert_eq!(loaded.findings[0].rule, "coupling::global-complexity");
+
+        // Older versions recorded the issue type name
+        fs::write(
+            &path,
+            r#"{"version":1,"tool_version":"x","findings":[{"rule":"GlobalComplexity","source":"a","target":"b","fingerprint":"0123456789abcdef"}]}"#,
+        )
+        .unwrap();
+        let loaded = Baseline::load(&path).unwrap();
+        assert_eq!(loaded.findings[0].rule, "coupling::global-complexity");
+        assert_eq!(loaded.version, BASELINE_VERSION);
+        assert!(loaded.findings[0].fingerprint.is_empty());
+        let mut current = vec![issue(IssueType::GlobalComplexity, "a", "b", 3)];
+        loaded.retain_new(&mut current, &metrics);
+        assert!(current.is_empty());
+
+        fs::write(&path, r#"{"version":99,"tool_version":"x","findings":[]}"#).unwrap();
+        assert!(matches!(
+            Baseline::load(&path),
+            Err(BaselineError::UnsupportedVersion(99))
+        ));
+    }
+}
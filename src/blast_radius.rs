@@ -0,0 +1,438 @@
+//! Predicted blast radius of changing one item
+//!
+//! Shotgun surgery (one change spread over many files) can be predicted
+//! before it happens. For a type or function named as `path::to::Item`,
+//! three signals are combined:
+//!
+//! - **Name references** (Connascence of Name): files that import the item,
+//!   call it or mention the type. Renaming it or changing its signature
+//!   touches every one of them.
+//! - **Co-change history**: files that changed together with the defining
+//!   file in past commits (see [`CoChangePair`]). Each adds the share of
+//!   commits it was changed in as the likelihood of being touched again.
+//! - **Fan-in**: other modules importing from the defining module. They do
+//!   not name the item, so they are only touched when its behavior changes
+//!   in ways they rely on, and count toward the upper bound only.
+//!
+//! The prediction is the defining file plus the referencing files plus the
+//! co-change likelihoods; the upper bound adds every co-changing file and
+//! importer in full.
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use crate::metrics::{ModuleMetrics, ProjectMetrics};
+use crate::module_graph::{ModuleGraph, module_path_from_file};
+use crate::volatility::CoChangePair;
+
+/// Errors resolving the item to predict the blast radius of
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum BlastRadiusError {
+    #[error("no type or function named `{0}` found")]
+    NotFound(String),
+    #[error("`{item}` is ambiguous, qualify it with its module: {}", .candidates.join(", "))]
+    Ambiguous {
+        item: String,
+        candidates: Vec<String>,
+    },
+}
+
+/// Kind of the changed item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangedItemKind {
+    Type,
+    Function,
+}
+
+impl std::fmt::Display for ChangedItemKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChangedItemKind::Type => write!(f, "type"),
+            ChangedItemKind::Function => write!(f, "function"),
+        }
+    }
+}
+
+/// A file referring to the item by name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameReference {
+    pub module: String,
+    pub file: PathBuf,
+    /// Imports, calls and type mentions of the item
+    pub references: usize,
+}
+
+/// A file that changed together with the defining file
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoChangedFile {
+    pub file: String,
+    pub co_changes: usize,
+    /// Share of commits the two files changed together (0.0 - 1.0)
+    pub ratio: f64,
+}
+
+/// Files a change to one item is predicted to touch
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlastRadius {
+    /// Qualified item (`my_crate::metrics::ProjectMetrics`)
+    pub item: String,
+    pub kind: ChangedItemKind,
+    pub module: String,
+    pub file: PathBuf,
+    /// Other files naming the item, most references first
+    pub references: Vec<NameReference>,
+    /// Files co-changing with the defining file that do not name the item,
+    /// highest ratio first
+    pub co_changes: Vec<CoChangedFile>,
+    /// Other modules importing from the defining module without naming the
+    /// item, sorted
+    pub importers: Vec<String>,
+}
+
+impl BlastRadius {
+    /// Resolve `path::to::Item` (or just `Item`) and collect its blast radius
+    pub fn analyze(metrics: &ProjectMetrics, item_path: &str) -> Result<Self, BlastRadiusError> {
+        let (hint, name) = match item_path.rsplit_once("::") {
+            Some((hint, name)) => (Some(hint), name),
+            None => (None, item_path),
+        };
+        let graph = ModuleGraph::build(metrics);
+        let node_of = |module: &ModuleMetrics| {
+            graph
+                .nodes
+                .values()
+                .find(|node| node.file == module.path)
+                .map(|node| node.name.clone())
+                .unwrap_or_else(|| module.name.clone())
+        };
+
+        let mut candidates: Vec<(&ModuleMetrics, String, ChangedItemKind)> = metrics
+            .modules
+            .values()
+            .filter_map(|module| {
+                let kind = if module.type_definitions.contains_key(name) {
+                    ChangedItemKind::Type
+                } else if module.function_definitions.contains_key(name) {
+                    ChangedItemKind::Function
+                } else {
+                    return None;
+                };
+                let node = node_of(module);
+                let matches_hint = hint.is_none_or(|hint| {
+                    node == hint || node.ends_with(&format!("::{}", hint)) || module.name == hint
+                });
+                matches_hint.then_some((module, node, kind))
+            })
+            .collect();
+        candidates.sort_by(|a, b| a.1.cmp(&b.1));
+        let (module, node, kind) = match candidates.len() {
+            0 => return Err(BlastRadiusError::NotFound(item_path.to_string())),
+            1 => candidates.remove(0),
+            _ => {
+                return Err(BlastRadiusError::Ambiguous {
+                    item: item_path.to_string(),
+                    candidates: candidates
+                        .iter()
+                        .map(|(_, node, _)| format!("{}::{}", node, name))
+                        .collect(),
+                });
+            }
+        };
+        // Users of a name defined elsewhere too must import from this module
+        let unique = metrics
+            .modules
+            .values()
+            .filter(|m| {
+                m.type_definitions.contains_key(name) || m.function_definitions.contains_key(name)
+            })
+            .count()
+            == 1;
+
+        let mut references = Vec::new();
+        let mut importers = Vec::new();
+        for other in metrics.modules.values() {
+            if other.path == module.path {
+                continue;
+            }
+            let other_node = node_of(other);
+            let imports_module = graph
+                .edges
+                .get(&other_node)
+                .is_some_and(|targets| targets.contains_key(&node));
+            let count = name_references(other, name, kind, &module_path_from_file(&module.path));
+            if count > 0 && (unique || imports_module) {
+                references.push(NameReference {
+                    module: other_node,
+                    file: other.path.clone(),
+                    references: count,
+                });
+            } else if imports_module {
+                importers.push(other_node);
+            }
+        }
+        references.sort_by(|a, b| {
+            b.references
+                .cmp(&a.references)
+                .then_with(|| a.module.cmp(&b.module))
+        });
+        importers.sort();
+
+        let co_changes = co_changed_files(&metrics.co_changes, &module.path)
+            .into_iter()
+            .filter(|co| !references.iter().any(|r| same_file(&r.file, &co.file)))
+            .collect();
+        for reference in &mut references {
+            reference.file = PathBuf::from(metrics.relative_path(&reference.file));
+        }
+
+        Ok(Self {
+            item: format!("{}::{}", node, name),
+            kind,
+            module: node,
+            file: PathBuf::from(metrics.relative_path(&module.path)),
+            references,
+            co_changes,
+            importers,
+        })
+    }
+
+    /// Predicted number of files touched: the defining file, every file
+    /// naming the item, and the co-change likelihood of the others
+    pub fn expected_files(&self) -> f64 {
+        1.0 + self.references.len() as f64 + self.co_changes.iter().map(|c| c.ratio).sum::<f64>()
+    }
+
+    /// Files touched at least (the defining file and those naming the item)
+    pub fn min_files(&self) -> usize {
+        1 + self.references.len()
+    }
+
+    /// Files touched at most (every co-changing file and importer too)
+    pub fn max_files(&self) -> usize {
+        self.min_files() + self.co_changes.len() + self.importers.len()
+    }
+}
+
+/// Imports, calls and type mentions of `name` in a module
+fn name_references(
+    module: &ModuleMetrics,
+    name: &str,
+    kind: ChangedItemKind,
+    defining_path: &str,
+) -> usize {
+    let suffix = format!("::{}", name);
+    let imports = module
+        .use_paths
+        .iter()
+        .filter(|path| {
+            path.ends_with(&suffix) && (defining_path.is_empty() || path.contains(defining_path))
+        })
+        .count();
+    let uses = match kind {
+        ChangedItemKind::Type => usize::from(module.connascence.referenced_names.contains(name)),
+        ChangedItemKind::Function => module
+            .connascence
+            .calls
+            .iter()
+            .filter(|(_, callee)| callee == name)
+            .count(),
+    };
+    let item_deps = module
+        .item_dependencies
+        .iter()
+        .filter(|dep| dep.target == name || dep.target.ends_with(&suffix))
+        .count();
+    imports + uses.max(item_deps)
+}
+
+/// Files co-changing with `file`, highest ratio first
+fn co_changed_files(pairs: &[CoChangePair], file: &Path) -> Vec<CoChangedFile> {
+    let mut files: BTreeMap<&str, &CoChangePair> = BTreeMap::new();
+    for pair in pairs {
+        let other = if same_file(file, &pair.file_a) {
+            &pair.file_b
+        } else if same_file(file, &pair.file_b) {
+            &pair.file_a
+        } else {
+            continue;
+        };
+        files.insert(other, pair);
+    }
+    let mut files: Vec<CoChangedFile> = files
+        .into_iter()
+        .map(|(other, pair)| CoChangedFile {
+            file: other.to_string(),
+            co_changes: pair.co_changes,
+            ratio: pair.ratio,
+        })
+        .collect();
+    files.sort_by(|a, b| {
+        b.ratio
+            .total_cmp(&a.ratio)
+            .then_with(|| a.file.cmp(&b.file))
+    });
+    files
+}
+
+/// Whether an analyzed file is the repository-relative `git_path`
+fn same_file(path: &Path, git_path: &str) -> bool {
+    path.ends_with(git_path)
+}
+
+/// Generate the blast radius report to writer
+pub fn generate_blast_radius_report<W: Write>(
+    radius: &BlastRadius,
+    writer: &mut W,
+) -> io::Result<()> {
+    let title = format!("Blast Radius: {} ({})", radius.item, radius.kind);
+    writeln!(writer, "{}", title)?;
+    writeln!(writer, "{}", "=".repeat(title.chars().count()))?;
+    writeln!(writer)?;
+    writeln!(writer, "Defined in {}", radius.file.display())?;
+    writeln!(writer)?;
+
+    writeln!(
+        writer,
+        "Name references ({} files)",
+        radius.references.len()
+    )?;
+    if radius.references.is_empty() {
+        writeln!(writer, "  (none)")?;
+    }
+    for reference in &radius.references {
+        writeln!(
+            writer,
+            "  {} ({} references) - {}",
+            reference.module,
+            reference.references,
+            reference.file.display()
+        )?;
+    }
+    writeln!(writer)?;
+
+    writeln!(writer, "Co-changed files ({})", radius.co_changes.len())?;
+    if radius.co_changes.is_empty() {
+        writeln!(writer, "  (none)")?;
+    }
+    for co in &radius.co_changes {
+        writeln!(
+            writer,
+            "  {} ({:.0}%, {} commits)",
+            co.file,
+            co.ratio * 100.0,
+            co.co_changes
+        )?;
+    }
+    writeln!(writer)?;
+
+    writeln!(
+        writer,
+        "Other importers of {} ({})",
+        radius.module,
+        radius.importers.len()
+    )?;
+    if radius.importers.is_empty() {
+        writeln!(writer, "  (none)")?;
+    }
+    for importer in &radius.importers {
+        writeln!(writer, "  {}", importer)?;
+    }
+    writeln!(writer)?;
+
+    writeln!(
+        writer,
+        "Predicted blast radius: {:.1} files (at least {}, at most {})",
+        radius.expected_files(),
+        radius.min_files(),
+        radius.max_files()
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::CouplingAnalyzer;
+
+    fn module(path: &str, code: &str) -> ModuleMetrics {
+        let name = path.trim_start_matches("src/").trim_end_matches(".rs");
+        let mut analyzer = CouplingAnalyzer::new(name.into(), PathBuf::from(path));
+        analyzer.analyze_file(code).unwrap();
+        let mut module = analyzer.metrics;
+        module.crate_name = Some("app".into());
+        module
+    }
+
+    fn project() -> ProjectMetrics {
+        let mut metrics = ProjectMetrics::new();
+        metrics.add_module(module(
+            "src/store.rs",
+            "pub struct Store;\npub fn open() -> Store { Store }\npub fn close(s: Store) {}",
+        ));
+        metrics.add_module(module(
+            "src/api.rs",
+            "use crate::store::{Store, open};\nfn get() -> Store { open() }",
+        ));
+        metrics.add_module(module(
+            "src/cli.rs",
+            "use crate::store::close;\nfn quit() { close(x()) }",
+        ));
+        metrics.add_module(module(
+            "src/other.rs",
+            "pub struct Store;\nfn f(s: Store) {}",
+        ));
+        metrics.co_changes = vec![CoChangePair {
+            file_a: "src/cli.rs".into(),
+            file_b: "src/store.rs".into(),
+            co_changes: 4,
+            ratio: 0.5,
+            examples: Vec::new(),
+        }];
+        metrics
+    }
+
+    #[test]
+    fn test_blast_radius() {
+        let metrics = project();
+        let radius = BlastRadius::analyze(&metrics, "store::Store").unwrap();
+        assert_eq!(radius.item, "app::store::Store");
+        assert_eq!(radius.kind, ChangedItemKind::Type);
+        // `other` defines its own `Store`
+        let referencing: Vec<&str> = radius
+            .references
+            .iter()
+            .map(|r| r.module.as_str())
+            .collect();
+        assert_eq!(referencing, vec!["app::api"]);
+        assert_eq!(radius.importers, vec!["app::cli"]);
+        assert_eq!(radius.co_changes.len(), 1);
+        assert_eq!(radius.expected_files(), 2.5);
+        assert_eq!((radius.min_files(), radius.max_files()), (2, 4));
+
+        let mut out = Vec::new();
+        generate_blast_radius_report(&radius, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("Predicted blast radius: 2.5 files (at least 2, at most 4)"));
+
+        // Naming the item excludes the file from the co-change list
+        let close = BlastRadius::analyze(&metrics, "close").unwrap();
+        assert_eq!(close.references[0].module, "app::cli");
+        assert!(close.co_changes.is_empty());
+    }
+
+    #[test]
+    fn test_blast_radius_resolution() {
+        let metrics = project();
+        assert_eq!(
+            BlastRadius::analyze(&metrics, "Missing"),
+            Err(BlastRadiusError::NotFound("Missing".into()))
+        );
+        assert!(matches!(
+            BlastRadius::analyze(&metrics, "Store"),
+            Err(BlastRadiusError::Ambiguous { candidates, .. }) if candidates.len() == 2
+        ));
+    }
+}
@@ -0,0 +1,392 @@
+//! Build-time vs runtime coupling between crates
+//!
+//! Not every manifest dependency couples crates the same way:
+//!
+//! - **Runtime**: a normal dependency; its code ends up in the dependent
+//!   crate and its API changes break callers
+//! - **Build script**: a build-dependency only `build.rs` uses; it never
+//!   reaches the dependent's code, so API changes break one script
+//! - **Proc macro**: a dependency on a procedural macro crate; it runs in
+//!   the compiler and generates code inside every crate that uses it, so a
+//!   change to the macro changes code it never sees the source of, and all
+//!   crates downstream of its users are recompiled
+//! - **Dev**: a dev-dependency, used by tests, examples and benches only
+//!
+//! [`classify_dependencies`] sorts the manifest dependencies of workspace
+//! members into these phases. [`BuildCouplingReport`] adds the code-level
+//! references for each dependency and, for every procedural macro, the
+//! workspace crates that expand it and the ones rebuilt when it changes.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::io::{self, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::metrics::ProjectMetrics;
+use crate::workspace::WorkspaceInfo;
+
+/// When a dependency is used
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DependencyPhase {
+    /// Normal dependency, linked into the crate
+    Runtime,
+    /// Only used by the build script
+    BuildScript,
+    /// Procedural macro, run by the compiler
+    ProcMacro,
+    /// Only used by tests, examples and benches
+    Dev,
+}
+
+impl fmt::Display for DependencyPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DependencyPhase::Runtime => write!(f, "runtime"),
+            DependencyPhase::BuildScript => write!(f, "build script"),
+            DependencyPhase::ProcMacro => write!(f, "proc macro"),
+            DependencyPhase::Dev => write!(f, "dev"),
+        }
+    }
+}
+
+/// A manifest dependency and when it is used
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct PhasedDependency {
+    /// Depending workspace member
+    pub source: String,
+    /// Dependency crate
+    pub target: String,
+    pub phase: DependencyPhase,
+    /// Whether the dependency is a workspace member too
+    pub workspace: bool,
+    /// Name the source crate's code uses for the dependency (`serde_json`,
+    /// or the name it is renamed to)
+    pub library: String,
+    /// Resolved version of the dependency (requires a resolve graph)
+    pub version: Option<String>,
+}
+
+/// Classify the dependencies of every workspace member, sorted by source
+/// and target
+///
+/// Normal dependencies (even ones `build.rs` uses too) are runtime or,
+/// for proc macro crates, proc macro dependencies. Dependencies only the
+/// build script or only tests use come next; dev-only proc macros count as
+/// dev dependencies since they never affect downstream crates.
+pub fn classify_dependencies(workspace: &WorkspaceInfo) -> Vec<PhasedDependency> {
+    let mut dependencies = Vec::new();
+    for member in &workspace.members {
+        let Some(info) = workspace.get_crate(member) else {
+            continue;
+        };
+        let is_proc_macro = |dep: &str| workspace.get_crate(dep).is_some_and(|c| c.is_proc_macro);
+        let mut phases: BTreeMap<&str, DependencyPhase> = BTreeMap::new();
+        for dep in &info.dependencies {
+            let phase = if is_proc_macro(dep) {
+                DependencyPhase::ProcMacro
+            } else if info.build_dependencies.contains(dep) {
+                DependencyPhase::BuildScript
+            } else {
+                DependencyPhase::Runtime
+            };
+            phases.insert(dep, phase);
+        }
+        for dep in &info.dev_dependencies {
+            phases.entry(dep).or_insert(DependencyPhase::Dev);
+        }
+        dependencies.extend(phases.into_iter().map(|(dep, phase)| {
+            let resolved = info.resolved_dependencies.get(dep);
+            PhasedDependency {
+                source: member.clone(),
+                target: dep.to_string(),
+                phase,
+                workspace: workspace.is_workspace_member(dep),
+                library: resolved.map_or_else(|| dep.replace('-', "_"), |r| r.library.clone()),
+                version: resolved.and_then(|r| r.version.clone()),
+            }
+        }));
+    }
+    dependencies.sort();
+    dependencies
+}
+
+/// A manifest dependency with the code that uses it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyCoupling {
+    pub dependency: PhasedDependency,
+    /// Code-level couplings from the source crate into the dependency
+    pub references: usize,
+}
+
+/// Workspace crates affected by a procedural macro crate
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProcMacroReach {
+    /// Proc macro crate
+    pub krate: String,
+    /// Workspace crates that depend on it (and expand its macros)
+    pub users: Vec<String>,
+    /// Workspace crates rebuilt when it changes: its users and every crate
+    /// depending on them, directly or not (dev-dependencies excluded)
+    pub rebuilt: Vec<String>,
+}
+
+/// Build-time and runtime coupling of a workspace
+#[derive(Debug, Clone, Default)]
+pub struct BuildCouplingReport {
+    /// Dependencies of the analyzed crates, sorted by source and target
+    pub dependencies: Vec<DependencyCoupling>,
+    /// Proc macro dependencies, sorted by name
+    pub proc_macros: Vec<ProcMacroReach>,
+}
+
+impl BuildCouplingReport {
+    /// Count the references per dependency and follow proc macro changes
+    /// through the crate graph
+    pub fn build(metrics: &ProjectMetrics) -> Self {
+        let mut references: BTreeMap<(&str, &str), usize> = BTreeMap::new();
+        for coupling in &metrics.couplings {
+            if let (Some(source), Some(target)) = (&coupling.source_crate, &coupling.target_crate) {
+                *references
+                    .entry((source.as_str(), target.as_str()))
+                    .or_default() += 1;
+            }
+        }
+        let dependencies = metrics
+            .dependency_phases
+            .iter()
+            .map(|dependency| DependencyCoupling {
+                references: references
+                    .get(&(dependency.source.as_str(), dependency.target.as_str()))
+                    .copied()
+                    .unwrap_or(0),
+                dependency: dependency.clone(),
+            })
+            .collect();
+
+        // Dependents through anything that is part of the build
+        let mut dependents: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        let mut users: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        for dependency in &metrics.dependency_phases {
+            if dependency.phase == DependencyPhase::Dev {
+                continue;
+            }
+            dependents
+                .entry(&dependency.target)
+                .or_default()
+                .insert(&dependency.source);
+            if dependency.phase == DependencyPhase::ProcMacro {
+                users
+                    .entry(&dependency.target)
+                    .or_default()
+                    .insert(&dependency.source);
+            }
+        }
+        let proc_macros = users
+            .into_iter()
+            .map(|(krate, users)| {
+                let mut rebuilt = BTreeSet::new();
+                let mut queue: Vec<&str> = users.iter().copied().collect();
+                while let Some(current) = queue.pop() {
+                    if rebuilt.insert(current)
+                        && let Some(next) = dependents.get(current)
+                    {
+                        queue.extend(next);
+                    }
+                }
+                ProcMacroReach {
+                    krate: krate.to_string(),
+                    users: users.iter().map(|u| u.to_string()).collect(),
+                    rebuilt: rebuilt.iter().map(|r| r.to_string()).collect(),
+                }
+            })
+            .collect();
+
+        Self {
+            dependencies,
+            proc_macros,
+        }
+    }
+
+    /// Number of dependencies in a phase
+    pub fn count(&self, phase: DependencyPhase) -> usize {
+        self.dependencies
+            .iter()
+            .filter(|d| d.dependency.phase == phase)
+            .count()
+    }
+}
+
+/// Generate the build-time vs runtime coupling report to writer
+pub fn generate_build_coupling_report<W: Write>(
+    report: &BuildCouplingReport,
+    writer: &mut W,
+) -> io::Result<()> {
+    writeln!(writer, "Build-Time vs Runtime Coupling")?;
+    writeln!(writer, "==============================")?;
+    writeln!(writer)?;
+
+    if report.dependencies.is_empty() {
+        writeln!(
+            writer,
+            "No crate dependencies found (requires cargo metadata)."
+        )?;
+        return Ok(());
+    }
+
+    let labels: Vec<String> = report
+        .dependencies
+        .iter()
+        .map(|d| {
+            let marker = if d.dependency.workspace { "" } else { " (ext)" };
+            format!(
+                "{} -> {}{}",
+                d.dependency.source, d.dependency.target, marker
+            )
+        })
+        .collect();
+    let width = labels
+        .iter()
+        .map(String::len)
+        .max()
+        .unwrap_or(0)
+        .max("Dependency".len());
+    writeln!(
+        writer,
+        "{:<width$}  {:<12}  {:>10}",
+        "Dependency", "Phase", "References"
+    )?;
+    writeln!(writer, "{}", "-".repeat(width + 26))?;
+    for (label, coupling) in labels.iter().zip(&report.dependencies) {
+        writeln!(
+            writer,
+            "{:<width$}  {:<12}  {:>10}",
+            label,
+            coupling.dependency.phase.to_string(),
+            coupling.references
+        )?;
+    }
+
+    if !report.proc_macros.is_empty() {
+        writeln!(writer)?;
+        writeln!(writer, "Proc macro reach")?;
+        for reach in &report.proc_macros {
+            writeln!(
+                writer,
+                "  {}: expanded in {}; a change rebuilds {} crate(s): {}",
+                reach.krate,
+                reach.users.join(", "),
+                reach.rebuilt.len(),
+                reach.rebuilt.join(", ")
+            )?;
+        }
+    }
+
+    writeln!(writer)?;
+    writeln!(
+        writer,
+        "{} runtime, {} build script, {} proc macro, {} dev dependencies",
+        report.count(DependencyPhase::Runtime),
+        report.count(DependencyPhase::BuildScript),
+        report.count(DependencyPhase::ProcMacro),
+        report.count(DependencyPhase::Dev)
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{CouplingMetrics, Distance, IntegrationStrength, Volatility};
+
+    fn dependency(source: &str, target: &str, phase: DependencyPhase) -> PhasedDependency {
+        PhasedDependency {
+            source: source.to_string(),
+            target: target.to_string(),
+            phase,
+            workspace: true,
+            library: target.to_string(),
+            version: Some("0.1.0".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_classify_dependencies() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let write = |path: &str, content: &str| {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write(
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"app\", \"derive\", \"gen\"]\nresolver = \"2\"\n",
+        );
+        write(
+            "app/Cargo.toml",
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n\
+             [dependencies]\nderive = { path = \"../derive\" }\n\n\
+             [build-dependencies]\ngen = { path = \"../gen\" }\n",
+        );
+        write(
+            "derive/Cargo.toml",
+            "[package]\nname = \"derive\"\nversion = \"0.1.0\"\n\n[lib]\nproc-macro = true\n",
+        );
+        write(
+            "gen/Cargo.toml",
+            "[package]\nname = \"gen\"\nversion = \"0.1.0\"\n",
+        );
+        for name in ["app", "derive", "gen"] {
+            write(&format!("{name}/src/lib.rs"), "");
+        }
+
+        let workspace = WorkspaceInfo::from_path(root).unwrap();
+        assert_eq!(
+            classify_dependencies(&workspace),
+            vec![
+                dependency("app", "derive", DependencyPhase::ProcMacro),
+                dependency("app", "gen", DependencyPhase::BuildScript),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_proc_macro_reach() {
+        let mut metrics = ProjectMetrics::new();
+        metrics.dependency_phases = vec![
+            dependency("app", "core", DependencyPhase::Runtime),
+            dependency("cli", "app", DependencyPhase::Runtime),
+            dependency("core", "macros", DependencyPhase::ProcMacro),
+            dependency("tests", "core", DependencyPhase::Dev),
+        ];
+        let mut coupling = CouplingMetrics::new(
+            "app::run".into(),
+            "core::model".into(),
+            IntegrationStrength::Model,
+            Distance::DifferentCrate,
+            Volatility::Low,
+        );
+        coupling.source_crate = Some("app".into());
+        coupling.target_crate = Some("core".into());
+        metrics.add_coupling(coupling);
+
+        let report = BuildCouplingReport::build(&metrics);
+        assert_eq!(report.dependencies[0].references, 1);
+        assert_eq!(
+            report.proc_macros,
+            vec![ProcMacroReach {
+                krate: "macros".into(),
+                users: vec!["core".into()],
+                rebuilt: vec!["app".into(), "cli".into(), "core".into()],
+            }]
+        );
+
+        let mut output = Vec::new();
+        generate_build_coupling_report(&report, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("macros: expanded in core; a change rebuilds 3 crate(s)"));
+        assert!(output.contains("2 runtime, 0 build script, 1 proc macro, 1 dev dependencies"));
+    }
+}
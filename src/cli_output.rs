@@ -6,15 +6,20 @@
 //! - Check: CI/CD quality gate with exit codes
 //! - JSON: Machine-readable output for automation
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::io::{self, Write};
 
 use serde::Serialize;
 
+use crate::aposd::{AposdConfig, DepthClass, analyze_module_depths_with, average_depth_ratio};
 use crate::balance::{
-    BalanceScore, HealthGrade, IssueThresholds, Severity, analyze_project_balance_with_thresholds,
+    BalanceScore, HealthGrade, IssueThresholds, IssueType, Severity,
+    analyze_project_balance_with_thresholds,
 };
-use crate::metrics::{Distance, ProjectMetrics};
+use crate::config::ThresholdsConfig;
+use crate::fingerprint::fingerprint;
+use crate::metrics::{Distance, ProjectMetrics, SkippedFile};
+use crate::rules::RuleLevel;
 
 // ============================================================================
 // Hotspots: Refactoring Prioritization
@@ -31,7 +36,7 @@ pub struct Hotspot {
     pub issues: Vec<HotspotIssue>,
     /// Suggested fix action
     pub suggestion: String,
-    /// File path if available
+    /// File path relative to the workspace root, if available
     pub file_path: Option<String>,
     /// Whether this module is in a circular dependency
     pub in_cycle: bool,
@@ -42,6 +47,8 @@ pub struct Hotspot {
 pub struct HotspotIssue {
     pub severity: String,
     pub issue_type: String,
+    /// Stable rule id, for `--explain`
+    pub rule_id: String,
     pub description: String,
 }
 
@@ -112,6 +119,189 @@ pub fn get_issue_explanation(issue_type: &str) -> IssueExplanation {
             how_to_fix: "Access through public methods or traits instead",
             example: Some("e.g., foo.internal_field -> foo.get_value()"),
         },
+        "Orphan Rule Workaround" => IssueExplanation {
+            what_it_means: "A newtype exists only to implement a foreign trait for a foreign type",
+            why_its_bad: vec![
+                "The impl depends on two upstream crates at once",
+                "A change to either the trait or the wrapped type breaks it",
+                "Callers have to wrap and unwrap values at every use",
+            ],
+            how_to_fix: "Use an upstream impl (often behind a feature flag) or convert at the boundary",
+            example: Some(
+                "e.g., struct Json(serde_json::Value); impl sqlx::Encode for Json -> sqlx's json feature",
+            ),
+        },
+        "Re-export Chain" => IssueExplanation {
+            what_it_means: "A type is re-exported with `pub use` through several crates",
+            why_its_bad: vec![
+                "Every crate in the chain breaks when the type changes",
+                "Users depend on the defining crate without declaring it",
+            ],
+            how_to_fix: "Depend on the defining crate directly, or wrap the type at the boundary",
+            example: Some("e.g., pub use service::User; -> pub struct Account(service::User);"),
+        },
+        "Unpaired Operation" => IssueExplanation {
+            what_it_means: "An operation was started but never finished (e.g. begin without commit)",
+            why_its_bad: vec![
+                "Callers must remember the correct order of calls",
+                "Early returns and errors skip the closing call",
+                "Resources or transactions may be left open",
+            ],
+            how_to_fix: "Return a guard type that performs the closing operation in Drop",
+            example: Some("e.g., let tx = conn.begin(); -> tx commits or rolls back on drop"),
+        },
+        "Dropped Guard" => IssueExplanation {
+            what_it_means: "A lock guard is bound to `_` and released immediately",
+            why_its_bad: vec![
+                "The code that should be protected runs without the lock",
+                "Looks correct at a glance, so it is easy to miss in review",
+            ],
+            how_to_fix: "Bind the guard to a named variable that lives for the critical section",
+            example: Some("e.g., let _ = m.lock(); -> let _guard = m.lock();"),
+        },
+        "Panicking Builder" => IssueExplanation {
+            what_it_means: "The builder's build() panics if some setters were not called",
+            why_its_bad: vec![
+                "Callers must know which setters are required, and the compiler cannot tell them",
+                "A forgotten setter only shows up as a panic at runtime",
+            ],
+            how_to_fix: "Take required values in new(), return a Result from build(), or use typestate",
+            example: Some(
+                "e.g., Builder::new().port(80).build() -> Builder::new(host).port(80).build()",
+            ),
+        },
+        "Panic Path" => IssueExplanation {
+            what_it_means: "A method panics unless another method set up the state it unwraps",
+            why_its_bad: vec![
+                "Callers must know the call order, and the compiler cannot tell them",
+                "Calling the methods in the wrong order crashes at runtime",
+            ],
+            how_to_fix: "Set the state in the constructor, return an error, or use a separate type for the initialized state",
+            example: Some(
+                "e.g., client.send(..) before client.connect() -> let conn = client.connect()?; conn.send(..)",
+            ),
+        },
+        "Hidden Coupling" => IssueExplanation {
+            what_it_means: "Two files keep changing together, but the code doesn't show why",
+            why_its_bad: vec![
+                "Changing one file silently requires changing the other",
+                "The dependency is invisible to the compiler and to reviewers",
+                "Easy to forget one side and ship inconsistent behavior",
+            ],
+            how_to_fix: "Extract the shared knowledge into one place both files depend on",
+            example: Some("e.g., duplicated format constants -> shared `format.rs`"),
+        },
+        "Organizational Coupling" => IssueExplanation {
+            what_it_means: "Many different people change this module, and many other modules are coupled to it",
+            why_its_bad: vec![
+                "Every change needs several people to agree",
+                "Nobody feels responsible for keeping the module coherent",
+                "Changes from different teams ripple into each other's code",
+            ],
+            how_to_fix: "Give the module a clear owner, or split it so each team owns its part",
+            example: Some(
+                "e.g., one `api.rs` edited by 9 people -> `api/billing.rs` and `api/accounts.rs` per team",
+            ),
+        },
+        "Connascence of Position" => IssueExplanation {
+            what_it_means: "A call passes several values of the same type, so only their order tells them apart",
+            why_its_bad: vec![
+                "Swapping two arguments compiles and silently changes behavior",
+                "Literal arguments give reviewers no name to catch the mistake",
+                "Reordering parameters breaks every caller without a compile error",
+            ],
+            how_to_fix: "Use newtypes for the parameters or pass a struct with named fields",
+            example: Some("e.g., resize(u32, u32) -> resize(Width(u32), Height(u32))"),
+        },
+        "Connascence of Algorithm" => IssueExplanation {
+            what_it_means: "Data written with one algorithm in one place is read or compared with the same algorithm elsewhere",
+            why_its_bad: vec![
+                "Switching the scheme on one side compiles but fails at runtime",
+                "Across crates the two sides may be released independently",
+                "Nothing in the code says the two call sites belong together",
+            ],
+            how_to_fix: "Put encode and decode (or the hash function) behind one shared API",
+            example: Some(
+                "e.g., bincode::serialize in store, bincode::deserialize in api -> `codec::{write, read}`",
+            ),
+        },
+        "Connascence of Execution" => IssueExplanation {
+            what_it_means: "A method checks state that another method sets, so it must be called second",
+            why_its_bad: vec![
+                "Callers that skip the first call fail at runtime, not at compile time",
+                "The order is only documented in the callee's body or doc comment",
+                "Every new caller has to rediscover the protocol",
+            ],
+            how_to_fix: "Encode the order in types: return a connected handle from `connect()` and move `send()` onto it",
+            example: Some("e.g., client.connect(); client.send() -> client.connect()?.send()"),
+        },
+        "Connascence of Type" => IssueExplanation {
+            what_it_means: "One concrete type appears in the public signatures of many modules",
+            why_its_bad: vec![
+                "Changing the type changes every signature that mentions it",
+                "Callers cannot substitute another implementation or a test double",
+            ],
+            how_to_fix: "Depend on a trait describing what callers need, not on the concrete type",
+            example: Some("e.g., fn save(db: &PgPool) -> fn save(db: &impl Store)"),
+        },
+        "Connascence of Identity" => IssueExplanation {
+            what_it_means: "Several modules use the same global instance (static, lazy_static, shared Arc<Mutex>)",
+            why_its_bad: vec![
+                "A write in one module changes behavior in every other user",
+                "Tests interfere with each other through the shared instance",
+                "Initialization order becomes part of every user's contract",
+            ],
+            how_to_fix: "Create the state once at startup and pass it to the modules that need it",
+            example: Some(
+                "e.g., static CACHE: Mutex<..> -> struct App { cache: Cache } passed by reference",
+            ),
+        },
+        "High Cognitive Load" => IssueExplanation {
+            what_it_means: "Using this module requires knowing many public items, long parameter lists and dependencies",
+            why_its_bad: vec![
+                "Every change needs a reader to hold the whole interface in mind",
+                "Callers depend on many small details instead of a few concepts",
+                "Dependencies leak into everything that uses the module",
+            ],
+            how_to_fix: "Hide details behind fewer, more powerful public items (a deeper module) or split it",
+            example: Some("e.g., 20 pub helper fns -> 3 pub operations that call private helpers"),
+        },
+        "Layer Violation" => IssueExplanation {
+            what_it_means: "A module or crate depends on a layer that the configured architecture does not allow",
+            why_its_bad: vec![
+                "Inner layers start changing whenever outer layers change",
+                "The inner layer can no longer be tested or reused without the outer one",
+                "The declared architecture drifts away from the real one",
+            ],
+            how_to_fix: "Define a trait in the inner layer and implement it in the outer one (dependency inversion)",
+            example: Some(
+                "e.g., domain calls db::save -> domain defines `trait UserRepository`, db implements it",
+            ),
+        },
+        "Stamp Coupling" => IssueExplanation {
+            what_it_means: "A function takes a whole struct as a parameter but reads only one or two of its fields",
+            why_its_bad: vec![
+                "Callers must construct or borrow the entire struct just to pass two values",
+                "The function looks like it depends on every field, hiding what it really needs",
+                "Tests need a full struct instead of the values under test",
+            ],
+            how_to_fix: "Pass the fields that are used, or a smaller struct that holds only them",
+            example: Some(
+                "e.g., fn timeout(cfg: &Config) reads cfg.timeout -> fn timeout(timeout: Duration)",
+            ),
+        },
+        "Connascence of Meaning" => IssueExplanation {
+            what_it_means: "Several modules use the same unnamed literal and must agree on what it means",
+            why_its_bad: vec![
+                "Changing the value in one module silently breaks the others",
+                "Readers cannot tell whether two equal numbers are related",
+                "Search-and-replace catches unrelated uses of the same number",
+            ],
+            how_to_fix: "Name the value once as a const and import it where it is needed",
+            example: Some(
+                "e.g., 8080 in server.rs and client.rs -> `pub const DEFAULT_PORT: u16 = 8080;`",
+            ),
+        },
         _ => IssueExplanation {
             what_it_means: "A coupling-related issue was detected",
             why_its_bad: vec![
@@ -204,9 +394,8 @@ pub fn calculate_hotspots(
 
         // Get file path
         let file_path = metrics
-            .modules
-            .get(module)
-            .map(|m| m.path.display().to_string());
+            .find_module(module)
+            .map(|m| metrics.relative_path(&m.path));
 
         hotspots.push(Hotspot {
             module: module.clone(),
@@ -216,6 +405,7 @@ pub fn calculate_hotspots(
                 .map(|i| HotspotIssue {
                     severity: format!("{}", i.severity),
                     issue_type: format!("{}", i.issue_type),
+                    rule_id: i.issue_type.rule_id().to_string(),
                     description: i.description.clone(),
                 })
                 .collect(),
@@ -229,9 +419,8 @@ pub fn calculate_hotspots(
     for module in &cycle_modules {
         if !module_issues.contains_key(module) {
             let file_path = metrics
-                .modules
-                .get(module)
-                .map(|m| m.path.display().to_string());
+                .find_module(module)
+                .map(|m| metrics.relative_path(&m.path));
 
             hotspots.push(Hotspot {
                 module: module.clone(),
@@ -239,6 +428,7 @@ pub fn calculate_hotspots(
                 issues: vec![HotspotIssue {
                     severity: "Critical".into(),
                     issue_type: "CircularDependency".into(),
+                    rule_id: IssueType::CircularDependency.rule_id().to_string(),
                     description: "Part of a circular dependency cycle".into(),
                 }],
                 suggestion:
@@ -250,8 +440,8 @@ pub fn calculate_hotspots(
         }
     }
 
-    // Sort by score descending
-    hotspots.sort_by(|a, b| b.score.cmp(&a.score));
+    // Sort by score descending, ties by module name
+    hotspots.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.module.cmp(&b.module)));
     hotspots.truncate(limit);
 
     hotspots
@@ -307,8 +497,8 @@ pub fn generate_hotspots_output<W: Write>(
             };
             writeln!(
                 writer,
-                "   {} {}: {}",
-                icon, issue.severity, issue.issue_type
+                "   {} {}: {} [{}]",
+                icon, issue.severity, issue.issue_type, issue.rule_id
             )?;
 
             // Show beginner-friendly explanation in verbose mode
@@ -408,8 +598,8 @@ pub fn analyze_impact(metrics: &ProjectMetrics, module_name: &str) -> Option<Imp
     let in_cycle = cycle_modules.contains(&module);
 
     // Collect and group dependencies by target module
-    let mut dep_map: HashMap<String, (String, HashMap<String, usize>)> = HashMap::new();
-    let mut dependent_map: HashMap<String, (String, HashMap<String, usize>)> = HashMap::new();
+    let mut dep_map: BTreeMap<String, (String, BTreeMap<String, usize>)> = BTreeMap::new();
+    let mut dependent_map: BTreeMap<String, (String, BTreeMap<String, usize>)> = BTreeMap::new();
     let mut volatility_max = crate::metrics::Volatility::Low;
 
     for coupling in &metrics.couplings {
@@ -420,7 +610,7 @@ pub fn analyze_impact(metrics: &ProjectMetrics, module_name: &str) -> Option<Imp
         if coupling.source == module {
             let entry = dep_map
                 .entry(coupling.target.clone())
-                .or_insert_with(|| (format!("{:?}", coupling.distance), HashMap::new()));
+                .or_insert_with(|| (format!("{:?}", coupling.distance), BTreeMap::new()));
             *entry
                 .1
                 .entry(format!("{:?}", coupling.strength))
@@ -430,7 +620,7 @@ pub fn analyze_impact(metrics: &ProjectMetrics, module_name: &str) -> Option<Imp
         if coupling.target == module {
             let entry = dependent_map
                 .entry(coupling.source.clone())
-                .or_insert_with(|| (format!("{:?}", coupling.distance), HashMap::new()));
+                .or_insert_with(|| (format!("{:?}", coupling.distance), BTreeMap::new()));
             *entry
                 .1
                 .entry(format!("{:?}", coupling.strength))
@@ -444,7 +634,7 @@ pub fn analyze_impact(metrics: &ProjectMetrics, module_name: &str) -> Option<Imp
     }
 
     // Convert to DependencyInfo with grouped strengths
-    let dependencies: Vec<DependencyInfo> = dep_map
+    let mut dependencies: Vec<DependencyInfo> = dep_map
         .into_iter()
         .map(|(mod_name, (distance, strengths))| {
             let total_count: usize = strengths.values().sum();
@@ -456,7 +646,7 @@ pub fn analyze_impact(metrics: &ProjectMetrics, module_name: &str) -> Option<Imp
                 })
                 .collect();
             // Sort by count descending
-            strength_list.sort_by(|a, b| b.count.cmp(&a.count));
+            strength_list.sort_by_key(|s| std::cmp::Reverse(s.count));
             DependencyInfo {
                 module: mod_name,
                 distance,
@@ -466,7 +656,7 @@ pub fn analyze_impact(metrics: &ProjectMetrics, module_name: &str) -> Option<Imp
         })
         .collect();
 
-    let dependents: Vec<DependencyInfo> = dependent_map
+    let mut dependents: Vec<DependencyInfo> = dependent_map
         .into_iter()
         .map(|(mod_name, (distance, strengths))| {
             let total_count: usize = strengths.values().sum();
@@ -477,7 +667,7 @@ pub fn analyze_impact(metrics: &ProjectMetrics, module_name: &str) -> Option<Imp
                     count: c,
                 })
                 .collect();
-            strength_list.sort_by(|a, b| b.count.cmp(&a.count));
+            strength_list.sort_by_key(|s| std::cmp::Reverse(s.count));
             DependencyInfo {
                 module: mod_name,
                 distance,
@@ -487,8 +677,12 @@ pub fn analyze_impact(metrics: &ProjectMetrics, module_name: &str) -> Option<Imp
         })
         .collect();
 
+    // Most coupled first; the maps already order ties by name
+    dependencies.sort_by_key(|d| std::cmp::Reverse(d.total_count));
+    dependents.sort_by_key(|d| std::cmp::Reverse(d.total_count));
+
     // Calculate second-order impact (what depends on our dependents)
-    let mut second_order: HashSet<String> = HashSet::new();
+    let mut second_order: BTreeSet<String> = BTreeSet::new();
     let dependent_set: HashSet<String> = dependents.iter().map(|d| d.module.clone()).collect();
 
     for coupling in &metrics.couplings {
@@ -740,6 +934,12 @@ pub struct CheckConfig {
     pub max_circular: Option<usize>,
     /// Fail on any issue of this severity or higher
     pub fail_on: Option<Severity>,
+    /// Limits from the `[thresholds]` config section
+    pub limits: ThresholdsConfig,
+    /// Depth classification cutoffs from the `[aposd]` config section
+    pub aposd: AposdConfig,
+    /// Fail when source files could not be parsed (`--strict`)
+    pub strict: bool,
 }
 
 impl Default for CheckConfig {
@@ -749,6 +949,9 @@ impl Default for CheckConfig {
             max_critical: Some(0),
             max_circular: Some(0),
             fail_on: None,
+            limits: ThresholdsConfig::default(),
+            aposd: AposdConfig::default(),
+            strict: false,
         }
     }
 }
@@ -763,6 +966,10 @@ pub struct CheckResult {
     pub high_count: usize,
     pub medium_count: usize,
     pub circular_count: usize,
+    /// Findings of rules set to `error` in `[rules.severity]`
+    pub error_count: usize,
+    /// Files that could not be parsed
+    pub skipped_files: usize,
     pub failures: Vec<String>,
 }
 
@@ -785,6 +992,7 @@ pub fn run_check(
         .get(&Severity::Medium)
         .unwrap_or(&0);
     let circular_count = circular_deps.len();
+    let error_count = error_level_count(&report.issues, thresholds);
 
     let mut failures: Vec<String> = Vec::new();
     let mut passed = true;
@@ -846,6 +1054,26 @@ pub fn run_check(
         }
     }
 
+    // Rules remapped to `error` fail on any finding
+    if error_count > 0 {
+        passed = false;
+        failures.push(error_level_failure(error_count));
+    }
+
+    // Unparseable files are missing from every metric
+    let skipped_files = metrics.skipped_files.len();
+    if config.strict && skipped_files > 0 {
+        passed = false;
+        failures.push(format!("{} files could not be parsed", skipped_files));
+    }
+
+    // Check config limits
+    let limit_failures = threshold_failures(metrics, &config.limits, &config.aposd);
+    if !limit_failures.is_empty() {
+        passed = false;
+        failures.extend(limit_failures);
+    }
+
     CheckResult {
         passed,
         grade: format!("{:?}", report.health_grade),
@@ -854,10 +1082,101 @@ pub fn run_check(
         high_count,
         medium_count,
         circular_count,
+        error_count,
+        skipped_files,
         failures,
     }
 }
 
+/// Findings of rules set to `error` in `[rules.severity]`
+fn error_level_count(
+    issues: &[crate::balance::CouplingIssue],
+    thresholds: &IssueThresholds,
+) -> usize {
+    issues
+        .iter()
+        .filter(|issue| thresholds.rules.level(issue.issue_type) == Some(RuleLevel::Error))
+        .count()
+}
+
+fn error_level_failure(count: usize) -> String {
+    format!(
+        "{} findings of rules set to error in [rules.severity]",
+        count
+    )
+}
+
+/// Why a run without `--check` fails: findings of rules set to `error`,
+/// exceeded `[thresholds]` limits and, under `--strict`, unparseable files
+pub fn limit_failures(
+    metrics: &ProjectMetrics,
+    thresholds: &IssueThresholds,
+    limits: &ThresholdsConfig,
+    aposd: &AposdConfig,
+    strict: bool,
+) -> Vec<String> {
+    let mut failures = Vec::new();
+    let report = analyze_project_balance_with_thresholds(metrics, thresholds);
+    let error_count = error_level_count(&report.issues, thresholds);
+    if error_count > 0 {
+        failures.push(error_level_failure(error_count));
+    }
+    failures.extend(threshold_failures(metrics, limits, aposd));
+    if strict && !metrics.skipped_files.is_empty() {
+        failures.push(format!(
+            "{} files could not be parsed (--strict)",
+            metrics.skipped_files.len()
+        ));
+    }
+    failures
+}
+
+/// Evaluate the optional `[thresholds]` limits and describe each one exceeded
+pub fn threshold_failures(
+    metrics: &ProjectMetrics,
+    limits: &ThresholdsConfig,
+    aposd: &AposdConfig,
+) -> Vec<String> {
+    let mut failures = Vec::new();
+
+    if let Some(max) = limits.max_temporal_issues {
+        let count: usize = metrics
+            .modules
+            .values()
+            .map(|m| m.temporal.issues.len())
+            .sum();
+        if count > max {
+            failures.push(format!("{} temporal issues (max: {})", count, max));
+        }
+    }
+
+    if limits.max_shallow_modules.is_some() || limits.min_average_depth_ratio.is_some() {
+        let depths = analyze_module_depths_with(metrics, aposd);
+
+        if let Some(max) = limits.max_shallow_modules {
+            let count = depths
+                .iter()
+                .filter(|d| d.class == DepthClass::Shallow)
+                .count();
+            if count > max {
+                failures.push(format!("{} shallow modules (max: {})", count, max));
+            }
+        }
+
+        if let Some(min) = limits.min_average_depth_ratio
+            && let Some(average) = average_depth_ratio(&depths)
+            && average < min
+        {
+            failures.push(format!(
+                "Average depth ratio {:.2} is below minimum {:.2}",
+                average, min
+            ));
+        }
+    }
+
+    failures
+}
+
 /// Generate check output and return exit code (0 = pass, 1 = fail)
 pub fn generate_check_output<W: Write>(
     metrics: &ProjectMetrics,
@@ -892,6 +1211,9 @@ pub fn generate_check_output<W: Write>(
     writeln!(writer, "  High issues: {}", result.high_count)?;
     writeln!(writer, "  Medium issues: {}", result.medium_count)?;
     writeln!(writer, "  Circular dependencies: {}", result.circular_count)?;
+    if result.skipped_files > 0 {
+        writeln!(writer, "  Skipped files: {}", result.skipped_files)?;
+    }
 
     if !result.passed {
         writeln!(writer)?;
@@ -916,6 +1238,8 @@ pub struct JsonOutput {
     pub issues: Vec<JsonIssue>,
     pub circular_dependencies: Vec<Vec<String>>,
     pub modules: Vec<JsonModule>,
+    /// Files that could not be parsed
+    pub skipped_files: Vec<SkippedFile>,
 }
 
 /// Summary in JSON format
@@ -930,25 +1254,53 @@ pub struct JsonSummary {
     pub critical_issues: usize,
     pub high_issues: usize,
     pub medium_issues: usize,
+    /// Modules analyzed from macro-expanded source (`--expand`)
+    pub expanded_modules: usize,
+    /// `#[test]` functions kept out of the analysis
+    pub test_functions: usize,
+    /// Lines of test code kept out of the analysis
+    pub test_lines_of_code: usize,
+    /// Files that could not be parsed
+    pub skipped_files: usize,
 }
 
 /// Issue in JSON format
 #[derive(Debug, Clone, Serialize)]
 pub struct JsonIssue {
     pub issue_type: String,
+    /// Stable rule id (`category::name`)
+    pub rule_id: String,
+    /// Content-based id for baselines and `[suppress] fingerprints`
+    pub fingerprint: String,
     pub severity: String,
     pub source: String,
     pub target: String,
     pub description: String,
     pub suggestion: String,
     pub balance_score: f64,
+    /// File relative to the workspace root
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// Rust module path of the file (`my_crate::net::client`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub module_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+    /// Kind of connascence (Page-Jones), for connascence issues
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connascence: Option<String>,
 }
 
 /// Module in JSON format
 #[derive(Debug, Clone, Serialize)]
 pub struct JsonModule {
     pub name: String,
+    /// File relative to the workspace root
     pub file_path: Option<String>,
+    /// Rust module path (`my_crate::net::client`)
+    pub module_path: String,
     pub couplings_out: usize,
     pub couplings_in: usize,
     pub balance_score: f64,
@@ -997,6 +1349,7 @@ pub fn generate_json_output<W: Write>(
         .get(&Severity::Medium)
         .unwrap_or(&0);
 
+    let tests = metrics.test_code();
     let output = JsonOutput {
         summary: JsonSummary {
             health_grade: format!("{:?}", report.health_grade),
@@ -1008,6 +1361,10 @@ pub fn generate_json_output<W: Write>(
             critical_issues: critical,
             high_issues: high,
             medium_issues: medium,
+            expanded_modules: metrics.expanded_module_count(),
+            test_functions: tests.functions,
+            test_lines_of_code: tests.lines_of_code,
+            skipped_files: metrics.skipped_files.len(),
         },
         hotspots,
         issues: report
@@ -1015,12 +1372,19 @@ pub fn generate_json_output<W: Write>(
             .iter()
             .map(|i| JsonIssue {
                 issue_type: format!("{}", i.issue_type),
+                rule_id: i.issue_type.rule_id().to_string(),
+                fingerprint: fingerprint(i, metrics),
                 severity: format!("{}", i.severity),
                 source: i.source.clone(),
                 target: i.target.clone(),
                 description: i.description.clone(),
                 suggestion: format!("{}", i.refactoring),
                 balance_score: i.balance_score,
+                file: i.file(metrics).map(|p| metrics.relative_path(p)),
+                module_path: i.module(metrics).map(|m| m.rust_path()),
+                line: (i.location.line > 0).then_some(i.location.line),
+                column: (i.location.column > 0).then_some(i.location.column),
+                connascence: i.issue_type.connascence_type().map(|c| c.to_string()),
             })
             .collect(),
         circular_dependencies: circular_deps,
@@ -1034,7 +1398,8 @@ pub fn generate_json_output<W: Write>(
                     .unwrap_or(1.0);
                 JsonModule {
                     name: name.clone(),
-                    file_path: Some(module.path.display().to_string()),
+                    file_path: Some(metrics.relative_path(&module.path)),
+                    module_path: module.rust_path(),
                     couplings_out: couplings_out.get(name).copied().unwrap_or(0),
                     couplings_in: couplings_in.get(name).copied().unwrap_or(0),
                     balance_score: avg_score,
@@ -1042,6 +1407,7 @@ pub fn generate_json_output<W: Write>(
                 }
             })
             .collect(),
+        skipped_files: metrics.skipped_files.clone(),
     };
 
     let json = serde_json::to_string_pretty(&output).map_err(io::Error::other)?;
@@ -1116,6 +1482,17 @@ pub struct TraceDependency {
     pub line: usize,
 }
 
+/// Trace dependencies grouped by item, most entries first (ties by name)
+fn group_trace(deps: &[TraceDependency]) -> Vec<(&str, Vec<&TraceDependency>)> {
+    let mut groups: BTreeMap<&str, Vec<&TraceDependency>> = BTreeMap::new();
+    for dep in deps {
+        groups.entry(dep.item.as_str()).or_default().push(dep);
+    }
+    let mut groups: Vec<_> = groups.into_iter().collect();
+    groups.sort_by_key(|(_, deps)| std::cmp::Reverse(deps.len()));
+    groups
+}
+
 /// Generate trace output for a specific function/type
 pub fn generate_trace_output<W: Write>(
     metrics: &ProjectMetrics,
@@ -1156,7 +1533,7 @@ pub fn generate_trace_output<W: Write>(
                         .unwrap_or_else(|| "unknown".to_string()),
                     dep_type: format!("{:?}", dep.dep_type),
                     strength: strength.to_string(),
-                    file_path: Some(module.path.display().to_string()),
+                    file_path: Some(metrics.relative_path(&module.path)),
                     line: dep.line,
                 });
             }
@@ -1174,7 +1551,7 @@ pub fn generate_trace_output<W: Write>(
                     module: module_name.clone(),
                     dep_type: format!("{:?}", dep.dep_type),
                     strength: strength.to_string(),
-                    file_path: Some(module.path.display().to_string()),
+                    file_path: Some(metrics.relative_path(&module.path)),
                     line: dep.line,
                 });
             }
@@ -1238,7 +1615,7 @@ pub fn generate_trace_output<W: Write>(
                 "   {} ({}) - {}",
                 module_name,
                 item_type,
-                module.path.display()
+                metrics.relative_path(&module.path)
             )?;
         }
         writeln!(writer)?;
@@ -1249,12 +1626,8 @@ pub fn generate_trace_output<W: Write>(
     if outgoing.is_empty() {
         writeln!(writer, "   (none)")?;
     } else {
-        // Group by target
-        let mut by_target: HashMap<String, Vec<&TraceDependency>> = HashMap::new();
-        for dep in &outgoing {
-            by_target.entry(dep.item.clone()).or_default().push(dep);
-        }
-
+        // Group by target, most referenced first
+        let by_target = group_trace(&outgoing);
         for (target, deps) in by_target.iter().take(15) {
             let first = deps[0];
             let strength_icon = match first.strength.as_str() {
@@ -1281,12 +1654,8 @@ pub fn generate_trace_output<W: Write>(
     if incoming.is_empty() {
         writeln!(writer, "   (none)")?;
     } else {
-        // Group by source
-        let mut by_source: HashMap<String, Vec<&TraceDependency>> = HashMap::new();
-        for dep in &incoming {
-            by_source.entry(dep.item.clone()).or_default().push(dep);
-        }
-
+        // Group by source, most referencing first
+        let by_source = group_trace(&incoming);
         for (source, deps) in by_source.iter().take(15) {
             let first = deps[0];
             let strength_icon = match first.strength.as_str() {
@@ -1388,7 +1757,7 @@ pub fn generate_trace_output<W: Write>(
         "   If you modify '{}', you may need to update:",
         item_name
     )?;
-    let affected_modules: HashSet<_> = incoming.iter().map(|d| d.module.clone()).collect();
+    let affected_modules: BTreeSet<_> = incoming.iter().map(|d| d.module.clone()).collect();
     if affected_modules.is_empty() {
         writeln!(writer, "   (no other modules directly affected)")?;
     } else {
@@ -1443,4 +1812,190 @@ mod tests {
         let result = run_check(&metrics, &thresholds, &config);
         assert!(result.passed);
     }
+
+    #[test]
+    fn test_check_fails_on_config_limits() {
+        let mut metrics = ProjectMetrics::new();
+        let mut module = crate::metrics::ModuleMetrics::new("src/a.rs".into(), "a".into());
+        module.add_function_definition("f".into(), crate::metrics::Visibility::Public);
+        module.lines_of_code = 1;
+        metrics.add_module(module);
+
+        let config = CheckConfig {
+            limits: ThresholdsConfig {
+                max_shallow_modules: Some(0),
+                max_temporal_issues: Some(0),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let result = run_check(&metrics, &IssueThresholds::default(), &config);
+        assert!(!result.passed);
+        assert_eq!(result.failures, vec!["1 shallow modules (max: 0)"]);
+    }
+
+    #[test]
+    fn test_check_strict_fails_on_skipped_files() {
+        let mut metrics = ProjectMetrics::new();
+        metrics.skipped_files.push(SkippedFile {
+            path: "src/broken.rs".into(),
+            error: "expected expression (line 2, column 9)".to_string(),
+        });
+
+        let mut config = CheckConfig::default();
+        let result = run_check(&metrics, &IssueThresholds::default(), &config);
+        assert!(result.passed);
+        assert_eq!(result.skipped_files, 1);
+
+        config.strict = true;
+        let result = run_check(&metrics, &IssueThresholds::default(), &config);
+        assert!(!result.passed);
+        assert_eq!(result.failures, vec!["1 files could not be parsed"]);
+    }
+
+    #[test]
+    fn test_check_fails_on_error_level_rules() {
+        let mut metrics = ProjectMetrics::new();
+        for (source, target) in [("a", "b"), ("b", "a")] {
+            metrics.add_module(crate::metrics::ModuleMetrics::new(
+                format!("src/{}.rs", source).into(),
+                source.into(),
+            ));
+            metrics.add_coupling(crate::metrics::CouplingMetrics::new(
+                format!("crate::{}", source),
+                format!("crate::{}", target),
+                crate::metrics::IntegrationStrength::Functional,
+                Distance::DifferentModule,
+                crate::metrics::Volatility::Low,
+            ));
+        }
+        let config = CheckConfig {
+            min_grade: None,
+            max_critical: None,
+            max_circular: None,
+            ..Default::default()
+        };
+        let mut thresholds = IssueThresholds::default();
+        assert!(run_check(&metrics, &thresholds, &config).passed);
+
+        thresholds.rules.severity.insert(
+            "coupling::circular-dependency".to_string(),
+            RuleLevel::Error,
+        );
+        let result = run_check(&metrics, &thresholds, &config);
+        assert!(!result.passed);
+        assert!(result.error_count > 0);
+
+        // `info` findings are dropped unless verbose
+        thresholds
+            .rules
+            .severity
+            .insert("coupling".to_string(), RuleLevel::Info);
+        thresholds
+            .rules
+            .severity
+            .remove("coupling::circular-dependency");
+        let report = analyze_project_balance_with_thresholds(&metrics, &thresholds);
+        assert!(report.issues.is_empty());
+        thresholds.show_info = true;
+        let report = analyze_project_balance_with_thresholds(&metrics, &thresholds);
+        assert!(!report.issues.is_empty());
+    }
+
+    #[test]
+    fn test_limits_fail_on_error_level_rules_without_check() {
+        let mut metrics = ProjectMetrics::new();
+        for (source, target) in [("a", "b"), ("b", "a")] {
+            metrics.add_module(crate::metrics::ModuleMetrics::new(
+                format!("src/{}.rs", source).into(),
+                source.into(),
+            ));
+            metrics.add_coupling(crate::metrics::CouplingMetrics::new(
+                format!("crate::{}", source),
+                format!("crate::{}", target),
+                crate::metrics::IntegrationStrength::Functional,
+                Distance::DifferentModule,
+                crate::metrics::Volatility::Low,
+            ));
+        }
+        let limits = ThresholdsConfig::default();
+        let aposd = AposdConfig::default();
+        let mut thresholds = IssueThresholds::default();
+        assert!(limit_failures(&metrics, &thresholds, &limits, &aposd, false).is_empty());
+
+        // `warning` reports the cycle without failing
+        thresholds.rules.severity.insert(
+            "coupling::circular-dependency".to_string(),
+            RuleLevel::Warning,
+        );
+        assert!(limit_failures(&metrics, &thresholds, &limits, &aposd, false).is_empty());
+
+        thresholds.rules.severity.insert(
+            "coupling::circular-dependency".to_string(),
+            RuleLevel::Error,
+        );
+        let failures = limit_failures(&metrics, &thresholds, &limits, &aposd, false);
+        assert_eq!(failures.len(), 1);
+        assert!(failures[0].contains("rules set to error"));
+    }
+
+    #[test]
+    fn test_impact_and_trace_output_is_deterministic() {
+        use crate::analyzer::{ItemDepType, ItemDependency, ItemKind};
+        use crate::metrics::{CouplingMetrics, IntegrationStrength, ModuleMetrics, Volatility};
+
+        let mut metrics = ProjectMetrics::new();
+        let mut store = ModuleMetrics::new("src/store.rs".into(), "store".into());
+        store.add_type_definition("Store".into(), crate::metrics::Visibility::Public, false);
+        let dependency = |source: &str, target: &str, line| ItemDependency {
+            source_item: source.into(),
+            source_kind: ItemKind::Function,
+            target: target.into(),
+            target_module: Some("store".into()),
+            dep_type: ItemDepType::FunctionCall,
+            line,
+            column: 1,
+            expression: None,
+        };
+        for i in 0..20 {
+            let name = format!("user{:02}", i);
+            store
+                .item_dependencies
+                .push(dependency("Store::open", &format!("helper{:02}", i), i));
+            let mut module = ModuleMetrics::new(format!("src/{}.rs", name).into(), name.clone());
+            module.item_dependencies.push(dependency("run", "Store", 1));
+            metrics.add_module(module);
+            // user19 couples most, so it is listed first
+            for _ in 0..=(i / 19) {
+                metrics.add_coupling(CouplingMetrics::new(
+                    name.clone(),
+                    "store".into(),
+                    IntegrationStrength::Functional,
+                    Distance::DifferentModule,
+                    Volatility::Low,
+                ));
+            }
+        }
+        metrics.add_module(store);
+
+        let render = |trace: bool| {
+            let mut out = Vec::new();
+            if trace {
+                generate_trace_output(&metrics, "Store", &mut out).unwrap();
+            } else {
+                generate_impact_output(&metrics, "store", &mut out).unwrap();
+            }
+            String::from_utf8(out).unwrap()
+        };
+        assert_eq!(render(false), render(false));
+        assert_eq!(render(true), render(true));
+
+        let impact = analyze_impact(&metrics, "store").unwrap();
+        let dependents: Vec<&str> = impact
+            .dependents
+            .iter()
+            .map(|d| d.module.as_str())
+            .collect();
+        assert_eq!(&dependents[..3], ["user19", "user00", "user01"]);
+    }
 }
@@ -15,25 +15,141 @@
 //! # Stable modules (Low volatility)
 //! low = ["src/core/*", "src/contracts/*"]
 //!
-//! # Paths to ignore from analysis
+//! # Paths to ignore from analysis (same as `[paths] exclude`)
 //! ignore = ["src/generated/*", "tests/*"]
 //!
+//! [paths]
+//! # Only analyze matching files (default: everything)
+//! include = ["src/**"]
+//! # Skip generated code, vendored code and fixtures
+//! exclude = ["**/generated/**", "vendor/*"]
+//! # Analyze tests/ files and #[cfg(test)] code like production code
+//! include_tests = false
+//!
 //! [thresholds]
 //! # Maximum dependencies before flagging High Efferent Coupling
 //! max_dependencies = 15
 //!
 //! # Maximum dependents before flagging High Afferent Coupling
 //! max_dependents = 20
+//!
+//! # Crate boundaries an item may be re-exported across with `pub use`
+//! max_reexport_boundaries = 1
+//!
+//! # Distinct git authors before a coupled module is Organizational Coupling
+//! max_authors = 5
+//!
+//! # Optional limits; exceeding any of them makes the CLI exit non-zero
+//! max_shallow_modules = 5
+//! max_temporal_issues = 0
+//! min_average_depth_ratio = 3.0
+//!
+//! [crates.legacy-core]
+//! # Per-crate dependency thresholds (workspace analysis only)
+//! max_dependencies = 40
+//! max_dependents = 60
+//!
+//! [override."crates/legacy/**"]
+//! # Relaxed thresholds and disabled rules for matching files; wins over
+//! # [crates] and [thresholds]
+//! max_dependencies = 60
+//! disable = ["temporal", "aposd::shallow-module"]
+//!
+//! [aposd]
+//! # Depth ratio (lines of code per unit of interface) cutoffs
+//! very_deep_ratio = 10.0
+//! deep_ratio = 5.0
+//! moderate_ratio = 2.0
+//! # Cognitive load cutoffs (public items + extra parameters + dependencies)
+//! moderate_cognitive_load = 5
+//! high_cognitive_load = 15
+//! very_high_cognitive_load = 30
+//! # Traits with this many methods and implementors are costly to change
+//! wide_trait_methods = 5
+//! wide_trait_implementors = 3
+//! # Modules this complex with fewer documented public items are non-obvious
+//! non_obvious_interface_complexity = 20
+//! min_doc_coverage = 0.5
+//! # Error cases a module's public functions may return before callers
+//! # have too many to handle
+//! max_error_cases = 10
+//!
+//! [stability]
+//! # Crate stability findings (--stability, workspace analysis only)
+//! max_instability = 0.8
+//! min_dependents = 2
+//! max_distance = 0.7
+//! min_abstractness = 0.2
+//! # Crates below this instability count as stable for min_abstractness
+//! max_stable_instability = 0.5
+//!
+//! [health]
+//! # Weights of the project health score components (--health)
+//! balance = 0.4
+//! issues = 0.3
+//! depth = 0.15
+//! cycles = 0.15
+//!
+//! [layers.domain]
+//! # Crate names or module paths in the layer
+//! members = ["domain"]
+//!
+//! [layers.application]
+//! members = ["app"]
+//! # Layers this one may depend on
+//! allow = ["domain"]
+//!
+//! [temporal]
+//! # Extra paired operations; a pair with a built-in `open` replaces it
+//! pairs = [{ open = "checkout", close = ["release_lease"], severity = "high" }]
+//! guard_methods = ["checkout_conn"]
+//! unpaired_severity = "medium"
+//!
+//! [rules]
+//! # Only report these rules (all when empty) and never report these
+//! enabled = []
+//! disabled = ["temporal::panicking-builder"]
+//!
+//! [rules.severity]
+//! # info: verbose reports only; warning: reported; error: fails --check
+//! "temporal::dropped-guard" = "error"
+//! "aposd" = "info"
+//!
+//! [suppress]
+//! # Findings to suppress per file (rule ids or categories, or "all")
+//! "src/legacy/*" = ["temporal", "connascence::meaning"]
+//! # Single findings, by the fingerprint shown in --json and --sarif
+//! fingerprints = ["5c0f3a9d2b7e8146"]
 //! ```
+//!
+//! ## Discovery
+//!
+//! Starting at the analyzed path, each directory up to the workspace root
+//! (the first `Cargo.toml` with a `[workspace]` table) is searched for, in
+//! order, `.coupling.toml`, `coupling.toml` and a `Cargo.toml` with a
+//! `[package.metadata.coupling]` or `[workspace.metadata.coupling]` table.
+//! The nearest file wins; files are not merged. `--config` skips discovery,
+//! and CLI flags such as `--max-deps` override the file.
 
 use glob::Pattern;
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
-use crate::metrics::Volatility;
+use crate::aposd::AposdConfig;
+use crate::health::HealthWeights;
+use crate::layers::Layer;
+use crate::metrics::{ProjectMetrics, Volatility};
+use crate::paths::{PathFilter, matches_path_suffix};
+use crate::rules::{RuleFilter, UnknownRule};
+use crate::stability::StabilityThresholds;
+use crate::suppress::FileSuppression;
+use crate::temporal::TemporalConfig;
+
+/// `[suppress]` key holding fingerprints rather than a path pattern
+const FINGERPRINTS_KEY: &str = "fingerprints";
 
 /// Errors that can occur when loading configuration
 #[derive(Error, Debug)]
@@ -46,6 +162,18 @@ pub enum ConfigError {
 
     #[error("Invalid glob pattern: {0}")]
     PatternError(String),
+
+    #[error("Layer '{0}' allows unknown layer '{1}'")]
+    UnknownLayer(String, String),
+
+    #[error("Temporal pair '{0}' has no closing operation")]
+    EmptyTemporalPair(String),
+
+    #[error("{0} has no [package.metadata.coupling] or [workspace.metadata.coupling] table")]
+    NoCargoMetadata(PathBuf),
+
+    #[error(transparent)]
+    UnknownRule(#[from] UnknownRule),
 }
 
 /// Volatility configuration section
@@ -78,6 +206,35 @@ pub struct ThresholdsConfig {
     /// Maximum dependents before flagging High Afferent Coupling
     #[serde(default = "default_max_dependents")]
     pub max_dependents: usize,
+
+    /// Crate boundaries a `pub use` chain may cross before Re-export Chain
+    #[serde(default = "default_max_reexport_boundaries")]
+    pub max_reexport_boundaries: usize,
+
+    /// Distinct git authors of a coupled module before Organizational Coupling
+    #[serde(default = "default_max_authors")]
+    pub max_authors: usize,
+
+    /// Fail when more modules than this are classified as shallow
+    #[serde(default)]
+    pub max_shallow_modules: Option<usize>,
+
+    /// Fail when more temporal coupling issues than this are found
+    #[serde(default)]
+    pub max_temporal_issues: Option<usize>,
+
+    /// Fail when the average module depth ratio drops below this
+    #[serde(default)]
+    pub min_average_depth_ratio: Option<f64>,
+}
+
+impl ThresholdsConfig {
+    /// Check if any failure limit is configured
+    pub fn has_limits(&self) -> bool {
+        self.max_shallow_modules.is_some()
+            || self.max_temporal_issues.is_some()
+            || self.min_average_depth_ratio.is_some()
+    }
 }
 
 fn default_max_dependencies() -> usize {
@@ -88,15 +245,97 @@ fn default_max_dependents() -> usize {
     20
 }
 
+fn default_max_reexport_boundaries() -> usize {
+    1
+}
+
+fn default_max_authors() -> usize {
+    5
+}
+
 impl Default for ThresholdsConfig {
     fn default() -> Self {
         Self {
             max_dependencies: default_max_dependencies(),
             max_dependents: default_max_dependents(),
+            max_reexport_boundaries: default_max_reexport_boundaries(),
+            max_authors: default_max_authors(),
+            max_shallow_modules: None,
+            max_temporal_issues: None,
+            min_average_depth_ratio: None,
         }
     }
 }
 
+/// `[crates.<name>]` section: thresholds for one workspace crate
+///
+/// Unset values fall back to `[thresholds]`.
+#[derive(Debug, Clone, Deserialize, Default, PartialEq, Eq)]
+pub struct ThresholdOverrides {
+    /// Maximum dependencies of a module in this crate
+    pub max_dependencies: Option<usize>,
+
+    /// Maximum dependents of a module in this crate
+    pub max_dependents: Option<usize>,
+}
+
+/// `[override."<glob>"]` section: thresholds and disabled rules for
+/// matching files
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct OverrideConfig {
+    /// Maximum dependencies of a module in a matching file
+    pub max_dependencies: Option<usize>,
+
+    /// Maximum dependents of a module in a matching file
+    pub max_dependents: Option<usize>,
+
+    /// Rules not reported for matching files (same selectors as `[suppress]`)
+    #[serde(default)]
+    pub disable: Vec<String>,
+}
+
+/// Compiled `[override."<glob>"]` thresholds
+#[derive(Debug, Clone)]
+pub struct PathOverride {
+    pub pattern: Pattern,
+    pub thresholds: ThresholdOverrides,
+}
+
+impl PathOverride {
+    /// Whether the override applies to a file (see [`matches_path_suffix`])
+    pub fn applies_to(&self, path: &Path) -> bool {
+        matches_path_suffix(&self.pattern, path)
+    }
+}
+
+/// `[paths]` section: which source files to analyze
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct PathsConfig {
+    /// Only analyze files matching one of these globs (all when empty)
+    #[serde(default)]
+    pub include: Vec<String>,
+
+    /// Skip files matching any of these globs
+    #[serde(default)]
+    pub exclude: Vec<String>,
+
+    /// Analyze test code like production code instead of reporting it separately
+    #[serde(default)]
+    pub include_tests: bool,
+}
+
+/// A layer in the `[layers]` section
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct LayerConfig {
+    /// Crate name or module path patterns belonging to the layer
+    #[serde(default)]
+    pub members: Vec<String>,
+
+    /// Layers this layer may depend on
+    #[serde(default)]
+    pub allow: Vec<String>,
+}
+
 /// Root configuration structure
 #[derive(Debug, Clone, Deserialize, Default)]
 pub struct CouplingConfig {
@@ -107,10 +346,51 @@ pub struct CouplingConfig {
     /// Threshold configuration
     #[serde(default)]
     pub thresholds: ThresholdsConfig,
+
+    /// Threshold overrides per workspace crate
+    #[serde(default)]
+    pub crates: BTreeMap<String, ThresholdOverrides>,
+
+    /// Threshold overrides and disabled rules per path pattern
+    #[serde(default, rename = "override")]
+    pub overrides: BTreeMap<String, OverrideConfig>,
+
+    /// Enabled and disabled rules
+    #[serde(default)]
+    pub rules: RuleFilter,
+
+    /// Source files to include or exclude
+    #[serde(default)]
+    pub paths: PathsConfig,
+
+    /// Rules suppressed per path pattern, and findings suppressed by
+    /// fingerprint (the `fingerprints` key)
+    #[serde(default)]
+    pub suppress: HashMap<String, Vec<String>>,
+
+    /// Architectural layers by name
+    #[serde(default)]
+    pub layers: BTreeMap<String, LayerConfig>,
+
+    /// Module depth and cognitive load cutoffs
+    #[serde(default)]
+    pub aposd: AposdConfig,
+
+    /// Crate instability, abstractness and main sequence cutoffs
+    #[serde(default)]
+    pub stability: StabilityThresholds,
+
+    /// Custom paired operations and temporal severities
+    #[serde(default)]
+    pub temporal: TemporalConfig,
+
+    /// Weights of the project health score components
+    #[serde(default)]
+    pub health: HealthWeights,
 }
 
 /// Compiled configuration with glob patterns
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CompiledConfig {
     /// Patterns for high volatility paths
     high_patterns: Vec<Pattern>,
@@ -122,6 +402,32 @@ pub struct CompiledConfig {
     ignore_patterns: Vec<Pattern>,
     /// Threshold configuration
     pub thresholds: ThresholdsConfig,
+    /// Threshold overrides per workspace crate
+    pub crate_thresholds: BTreeMap<String, ThresholdOverrides>,
+    /// Threshold overrides per path pattern, sorted by pattern
+    pub path_thresholds: Vec<PathOverride>,
+    /// Enabled and disabled rules
+    pub rules: RuleFilter,
+    /// File the configuration was loaded from
+    pub source: Option<PathBuf>,
+    /// Source files to analyze (`[paths]` plus `[volatility] ignore`)
+    pub paths: PathFilter,
+    /// Analyze test code like production code
+    pub include_tests: bool,
+    /// File-level suppressions
+    pub suppressions: Vec<FileSuppression>,
+    /// Findings suppressed by fingerprint
+    pub suppressed_fingerprints: BTreeSet<String>,
+    /// Architectural layers
+    pub layers: Vec<Layer>,
+    /// Module depth and cognitive load cutoffs
+    pub aposd: AposdConfig,
+    /// Crate instability, abstractness and main sequence cutoffs
+    pub stability: StabilityThresholds,
+    /// Custom paired operations and temporal severities
+    pub temporal: TemporalConfig,
+    /// Weights of the project health score components
+    pub health: HealthWeights,
     /// Cache of path -> volatility mappings
     cache: HashMap<String, Option<Volatility>>,
 }
@@ -138,12 +444,87 @@ impl CompiledConfig {
                 .collect()
         };
 
+        let mut suppressions = config
+            .suppress
+            .iter()
+            .filter(|(pattern, _)| pattern.as_str() != FINGERPRINTS_KEY)
+            .map(|(pattern, rules)| {
+                Ok(FileSuppression {
+                    pattern: compile_patterns(std::slice::from_ref(pattern))?.remove(0),
+                    rules: rules.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>, ConfigError>>()?;
+        // Rules disabled by [override] sections are file-level suppressions
+        let mut path_thresholds = Vec::new();
+        for (pattern, overrides) in &config.overrides {
+            let pattern = compile_patterns(std::slice::from_ref(pattern))?.remove(0);
+            if !overrides.disable.is_empty() {
+                suppressions.push(FileSuppression {
+                    pattern: pattern.clone(),
+                    rules: overrides.disable.clone(),
+                });
+            }
+            path_thresholds.push(PathOverride {
+                pattern,
+                thresholds: ThresholdOverrides {
+                    max_dependencies: overrides.max_dependencies,
+                    max_dependents: overrides.max_dependents,
+                },
+            });
+        }
+        suppressions.sort_by(|a, b| a.pattern.as_str().cmp(b.pattern.as_str()));
+
+        let mut layers = Vec::new();
+        for (name, layer) in &config.layers {
+            if let Some(unknown) = layer.allow.iter().find(|a| !config.layers.contains_key(*a)) {
+                return Err(ConfigError::UnknownLayer(name.clone(), unknown.clone()));
+            }
+            layers.push(Layer {
+                name: name.clone(),
+                members: compile_patterns(&layer.members)?,
+                allow: layer.allow.clone(),
+            });
+        }
+
+        config.rules.validate()?;
+
+        if let Some(pair) = config.temporal.pairs.iter().find(|p| p.close.is_empty()) {
+            return Err(ConfigError::EmptyTemporalPair(pair.open.clone()));
+        }
+
+        let mut exclude = config.paths.exclude.clone();
+        exclude.extend(config.volatility.ignore.iter().cloned());
+        let paths = PathFilter {
+            include: compile_patterns(&config.paths.include)?,
+            exclude: compile_patterns(&exclude)?,
+        };
+
         Ok(Self {
             high_patterns: compile_patterns(&config.volatility.high)?,
             medium_patterns: compile_patterns(&config.volatility.medium)?,
             low_patterns: compile_patterns(&config.volatility.low)?,
             ignore_patterns: compile_patterns(&config.volatility.ignore)?,
             thresholds: config.thresholds,
+            crate_thresholds: config.crates,
+            path_thresholds,
+            rules: config.rules,
+            source: None,
+            paths,
+            include_tests: config.paths.include_tests,
+            suppressions,
+            suppressed_fingerprints: config
+                .suppress
+                .get(FINGERPRINTS_KEY)
+                .into_iter()
+                .flatten()
+                .cloned()
+                .collect(),
+            layers,
+            aposd: config.aposd,
+            stability: config.stability,
+            temporal: config.temporal,
+            health: config.health,
             cache: HashMap::new(),
         })
     }
@@ -156,6 +537,19 @@ impl CompiledConfig {
             low_patterns: Vec::new(),
             ignore_patterns: Vec::new(),
             thresholds: ThresholdsConfig::default(),
+            crate_thresholds: BTreeMap::new(),
+            path_thresholds: Vec::new(),
+            rules: RuleFilter::default(),
+            source: None,
+            paths: PathFilter::default(),
+            include_tests: false,
+            suppressions: Vec::new(),
+            suppressed_fingerprints: BTreeSet::new(),
+            layers: Vec::new(),
+            aposd: AposdConfig::default(),
+            stability: StabilityThresholds::default(),
+            temporal: TemporalConfig::default(),
+            health: HealthWeights::default(),
             cache: HashMap::new(),
         }
     }
@@ -193,6 +587,19 @@ impl CompiledConfig {
         self.get_volatility_override(path).unwrap_or(git_volatility)
     }
 
+    /// Replace the volatility of every coupling whose target matches a
+    /// `[volatility]` pattern; returns the number of couplings changed
+    pub fn apply_volatility_overrides(&mut self, metrics: &mut ProjectMetrics) -> usize {
+        let mut count = 0;
+        for coupling in &mut metrics.couplings {
+            if let Some(volatility) = self.get_volatility_override(&coupling.target) {
+                coupling.volatility = volatility;
+                count += 1;
+            }
+        }
+        count
+    }
+
     /// Check if config has any volatility overrides
     pub fn has_volatility_overrides(&self) -> bool {
         !self.high_patterns.is_empty()
@@ -203,23 +610,46 @@ impl CompiledConfig {
 
 /// Load configuration from the project directory
 ///
-/// Searches for `.coupling.toml` in the given directory and parent directories.
+/// Searches the given directory and its parents up to the workspace root
+/// (see [Discovery](self#discovery)). Returns the default configuration
+/// when no file is found.
 pub fn load_config(project_path: &Path) -> Result<CouplingConfig, ConfigError> {
-    // Search for config file
-    let config_path = find_config_file(project_path);
-
-    match config_path {
-        Some(path) => {
-            let content = fs::read_to_string(&path)?;
-            let config: CouplingConfig = toml::from_str(&content)?;
-            Ok(config)
-        }
+    match find_config_file(project_path) {
+        Some(path) => load_config_file(&path),
         None => Ok(CouplingConfig::default()),
     }
 }
 
-/// Find the config file by searching up the directory tree
-fn find_config_file(start_path: &Path) -> Option<std::path::PathBuf> {
+/// Load configuration from a specific file
+///
+/// A `Cargo.toml` is read from its `[package.metadata.coupling]` or
+/// `[workspace.metadata.coupling]` table; any other file as a whole.
+pub fn load_config_file(path: &Path) -> Result<CouplingConfig, ConfigError> {
+    let content = fs::read_to_string(path)?;
+    if path.file_name().is_some_and(|name| name == "Cargo.toml") {
+        let manifest: toml::Table = toml::from_str(&content)?;
+        let table = cargo_metadata_table(&manifest)
+            .ok_or_else(|| ConfigError::NoCargoMetadata(path.to_path_buf()))?;
+        Ok(table.clone().try_into()?)
+    } else {
+        Ok(toml::from_str(&content)?)
+    }
+}
+
+/// `[package.metadata.coupling]`, or `[workspace.metadata.coupling]`
+fn cargo_metadata_table(manifest: &toml::Table) -> Option<&toml::Value> {
+    ["package", "workspace"].iter().find_map(|section| {
+        manifest
+            .get(*section)?
+            .get("metadata")?
+            .get("coupling")
+            .filter(|v| v.is_table())
+    })
+}
+
+/// Find the config file by searching up the directory tree, stopping at
+/// the workspace root
+pub fn find_config_file(start_path: &Path) -> Option<PathBuf> {
     let config_names = [".coupling.toml", "coupling.toml"];
 
     let mut current = if start_path.is_file() {
@@ -236,6 +666,19 @@ fn find_config_file(start_path: &Path) -> Option<std::path::PathBuf> {
             }
         }
 
+        let manifest_path = current.join("Cargo.toml");
+        if let Some(manifest) = fs::read_to_string(&manifest_path)
+            .ok()
+            .and_then(|content| toml::from_str::<toml::Table>(&content).ok())
+        {
+            if cargo_metadata_table(&manifest).is_some() {
+                return Some(manifest_path);
+            }
+            if manifest.contains_key("workspace") {
+                break;
+            }
+        }
+
         // Move to parent directory
         if let Some(parent) = current.parent() {
             current = parent.to_path_buf();
@@ -249,13 +692,27 @@ fn find_config_file(start_path: &Path) -> Option<std::path::PathBuf> {
 
 /// Load and compile configuration
 pub fn load_compiled_config(project_path: &Path) -> Result<CompiledConfig, ConfigError> {
-    let config = load_config(project_path)?;
-    CompiledConfig::from_config(config)
+    let source = find_config_file(project_path);
+    let config = match &source {
+        Some(path) => load_config_file(path)?,
+        None => CouplingConfig::default(),
+    };
+    let mut compiled = CompiledConfig::from_config(config)?;
+    compiled.source = source;
+    Ok(compiled)
+}
+
+/// Load and compile a specific config file (`--config`)
+pub fn load_compiled_config_file(path: &Path) -> Result<CompiledConfig, ConfigError> {
+    let mut compiled = CompiledConfig::from_config(load_config_file(path)?)?;
+    compiled.source = Some(path.to_path_buf());
+    Ok(compiled)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::balance::Severity;
 
     #[test]
     fn test_default_config() {
@@ -277,6 +734,8 @@ mod tests {
             [thresholds]
             max_dependencies = 20
             max_dependents = 30
+            max_temporal_issues = 0
+            min_average_depth_ratio = 2.5
         "#;
 
         let config: CouplingConfig = toml::from_str(toml).unwrap();
@@ -285,6 +744,10 @@ mod tests {
         assert_eq!(config.volatility.ignore.len(), 1);
         assert_eq!(config.thresholds.max_dependencies, 20);
         assert_eq!(config.thresholds.max_dependents, 30);
+        assert_eq!(config.thresholds.max_temporal_issues, Some(0));
+        assert_eq!(config.thresholds.min_average_depth_ratio, Some(2.5));
+        assert_eq!(config.thresholds.max_shallow_modules, None);
+        assert!(config.thresholds.has_limits());
     }
 
     #[test]
@@ -324,6 +787,39 @@ mod tests {
         assert!(!compiled.should_ignore("src/lib.rs"));
     }
 
+    #[test]
+    fn test_paths_config() {
+        let toml = r#"
+            [volatility]
+            ignore = ["benches/*"]
+
+            [paths]
+            include = ["src/**"]
+            exclude = ["**/generated/**"]
+        "#;
+
+        let config: CouplingConfig = toml::from_str(toml).unwrap();
+        let compiled = CompiledConfig::from_config(config).unwrap();
+        let root = Path::new("/project");
+
+        assert!(
+            compiled
+                .paths
+                .allows(Path::new("/project/src/lib.rs"), root)
+        );
+        assert!(
+            !compiled
+                .paths
+                .allows(Path::new("/project/src/generated/api.rs"), root)
+        );
+        assert!(
+            !compiled
+                .paths
+                .allows(Path::new("/project/benches/perf.rs"), root)
+        );
+        assert!(!compiled.paths.allows(Path::new("/project/build.rs"), root));
+    }
+
     #[test]
     fn test_get_volatility_with_fallback() {
         let toml = r#"
@@ -346,4 +842,239 @@ mod tests {
             Volatility::Medium
         );
     }
+
+    #[test]
+    fn test_suppress_config() {
+        let toml = r#"
+            [suppress]
+            "src/legacy/*" = ["temporal", "connascence::meaning"]
+            fingerprints = ["5c0f3a9d2b7e8146"]
+        "#;
+
+        let config: CouplingConfig = toml::from_str(toml).unwrap();
+        let compiled = CompiledConfig::from_config(config).unwrap();
+
+        assert_eq!(compiled.suppressions.len(), 1);
+        assert_eq!(compiled.suppressions[0].pattern.as_str(), "src/legacy/*");
+        assert_eq!(compiled.suppressions[0].rules.len(), 2);
+        assert_eq!(
+            compiled.suppressed_fingerprints,
+            BTreeSet::from(["5c0f3a9d2b7e8146".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_layers_config() {
+        let toml = r#"
+            [layers.domain]
+            members = ["domain"]
+
+            [layers.application]
+            members = ["app", "app::*"]
+            allow = ["domain"]
+        "#;
+
+        let config: CouplingConfig = toml::from_str(toml).unwrap();
+        let compiled = CompiledConfig::from_config(config).unwrap();
+        let names: Vec<&str> = compiled.layers.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(names, vec!["application", "domain"]);
+        assert!(compiled.layers[0].may_depend_on("domain"));
+        assert_eq!(compiled.layers[0].members.len(), 2);
+
+        let invalid: CouplingConfig = toml::from_str(
+            r#"
+            [layers.domain]
+            allow = ["infra"]
+        "#,
+        )
+        .unwrap();
+        assert!(matches!(
+            CompiledConfig::from_config(invalid),
+            Err(ConfigError::UnknownLayer(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_temporal_config() {
+        let toml = r#"
+            [temporal]
+            guard_methods = ["checkout_conn"]
+            dropped_guard_severity = "critical"
+
+            [[temporal.pairs]]
+            open = "checkout"
+            close = ["release_lease"]
+            severity = "high"
+        "#;
+
+        let config: CouplingConfig = toml::from_str(toml).unwrap();
+        assert_eq!(config.temporal.pairs[0].close, vec!["release_lease"]);
+        assert_eq!(config.temporal.pairs[0].severity, Some(Severity::High));
+        assert_eq!(config.temporal.dropped_guard_severity, Severity::Critical);
+        assert_eq!(config.temporal.unpaired_severity, Severity::Medium);
+        assert!(CompiledConfig::from_config(config).is_ok());
+
+        let empty: CouplingConfig = toml::from_str(
+            r#"
+            [[temporal.pairs]]
+            open = "checkout"
+            close = []
+        "#,
+        )
+        .unwrap();
+        assert!(matches!(
+            CompiledConfig::from_config(empty),
+            Err(ConfigError::EmptyTemporalPair(open)) if open == "checkout"
+        ));
+    }
+
+    #[test]
+    fn test_aposd_config() {
+        let toml = r#"
+            [aposd]
+            moderate_ratio = 3.0
+            very_high_cognitive_load = 50
+            min_doc_coverage = 0.8
+            max_error_cases = 6
+        "#;
+
+        let config: CouplingConfig = toml::from_str(toml).unwrap();
+        assert_eq!(config.aposd.moderate_ratio, 3.0);
+        assert_eq!(config.aposd.very_high_cognitive_load, 50);
+        assert_eq!(config.aposd.min_doc_coverage, 0.8);
+        assert_eq!(config.aposd.max_error_cases, 6);
+        // Unset cutoffs keep their defaults
+        assert_eq!(config.aposd.deep_ratio, 5.0);
+        assert_eq!(config.aposd.high_cognitive_load, 15);
+    }
+
+    #[test]
+    fn test_stability_config() {
+        let toml = r#"
+            [stability]
+            max_instability = 0.5
+            min_dependents = 1
+        "#;
+
+        let compiled = CompiledConfig::from_config(toml::from_str(toml).unwrap()).unwrap();
+        assert_eq!(compiled.stability.max_instability, 0.5);
+        assert_eq!(compiled.stability.min_dependents, 1);
+        // Unset thresholds keep their defaults
+        assert_eq!(compiled.stability.max_distance, 0.7);
+        assert_eq!(compiled.stability.min_abstractness, 0.2);
+        assert_eq!(compiled.stability.max_stable_instability, 0.5);
+        assert_eq!(
+            CompiledConfig::empty().stability,
+            StabilityThresholds::default()
+        );
+    }
+
+    #[test]
+    fn test_discovery_and_cargo_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        let member = root.join("crates/app");
+        fs::create_dir_all(member.join("src")).unwrap();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/app\"]\n\n[workspace.metadata.coupling.thresholds]\nmax_dependencies = 7\n",
+        )
+        .unwrap();
+        fs::write(
+            member.join("Cargo.toml"),
+            "[package]\nname = \"app\"\n\n[package.metadata.coupling.crates.app]\nmax_dependents = 3\n",
+        )
+        .unwrap();
+
+        // The member's own metadata is nearest
+        let found = find_config_file(&member.join("src"));
+        assert_eq!(found, Some(member.join("Cargo.toml")));
+        let config = load_config(&member.join("src")).unwrap();
+        assert_eq!(config.crates["app"].max_dependents, Some(3));
+
+        // A .coupling.toml next to it takes precedence over Cargo.toml
+        fs::write(
+            member.join(".coupling.toml"),
+            "[thresholds]\nmax_dependencies = 9\n",
+        )
+        .unwrap();
+        let compiled = load_compiled_config(&member).unwrap();
+        assert_eq!(compiled.thresholds.max_dependencies, 9);
+        assert_eq!(compiled.source, Some(member.join(".coupling.toml")));
+
+        // The workspace manifest is used from the root, and the search stops there
+        assert_eq!(load_config(&root).unwrap().thresholds.max_dependencies, 7);
+        fs::write(root.join("Cargo.toml"), "[workspace]\n").unwrap();
+        assert_eq!(find_config_file(&root), None);
+        assert!(matches!(
+            load_config_file(&root.join("Cargo.toml")),
+            Err(ConfigError::NoCargoMetadata(_))
+        ));
+    }
+
+    #[test]
+    fn test_override_config() {
+        let toml = r#"
+            [suppress]
+            "src/generated/*" = ["all"]
+
+            [override."crates/legacy/**"]
+            max_dependencies = 60
+            disable = ["temporal", "aposd::shallow-module"]
+
+            [override."crates/legacy/src/ffi.rs"]
+            max_dependents = 80
+        "#;
+
+        let config: CouplingConfig = toml::from_str(toml).unwrap();
+        let compiled = CompiledConfig::from_config(config).unwrap();
+
+        let legacy = Path::new("crates/legacy/src/lib.rs");
+        let applied: Vec<&ThresholdOverrides> = compiled
+            .path_thresholds
+            .iter()
+            .filter(|o| o.applies_to(legacy))
+            .map(|o| &o.thresholds)
+            .collect();
+        assert_eq!(applied.len(), 1);
+        assert_eq!(applied[0].max_dependencies, Some(60));
+
+        // Disabled rules become file-level suppressions; thresholds-only
+        // overrides do not
+        assert_eq!(compiled.suppressions.len(), 2);
+        assert!(
+            compiled
+                .suppressions
+                .iter()
+                .any(|s| s.covers(legacy, crate::balance::IssueType::DroppedGuard))
+        );
+    }
+
+    #[test]
+    fn test_rules_config() {
+        let toml = r#"
+            [rules]
+            enabled = ["temporal", "connascence/position"]
+            disabled = ["temporal::panicking-builder"]
+        "#;
+
+        let config: CouplingConfig = toml::from_str(toml).unwrap();
+        let compiled = CompiledConfig::from_config(config).unwrap();
+        assert!(
+            compiled
+                .rules
+                .allows(crate::balance::IssueType::ConnascenceOfPosition)
+        );
+        assert!(
+            !compiled
+                .rules
+                .allows(crate::balance::IssueType::PanickingBuilder)
+        );
+
+        let unknown: CouplingConfig = toml::from_str("[rules]\ndisabled = [\"tempral\"]").unwrap();
+        assert!(matches!(
+            CompiledConfig::from_config(unknown),
+            Err(ConfigError::UnknownRule(_))
+        ));
+    }
 }
@@ -0,0 +1,2163 @@
+//! Connascence detection
+//!
+//! Connascence (Meilir Page-Jones) describes how two pieces of code must
+//! change together. This module detects forms of connascence that are
+//! visible in the syntax tree:
+//!
+//! - **Connascence of Position**: a call passes several arguments of the
+//!   same type positionally, so swapping two of them still compiles but
+//!   silently changes behavior (`resize(width, height)` vs
+//!   `resize(height, width)`).
+//! - **Connascence of Meaning**: the same magic number appears in several
+//!   modules, so they silently agree on what the value means. Literals in
+//!   `const`/`static` items, enum discriminants, array indices and test
+//!   code are not counted, nor are `0`, `1` and `2`.
+//! - **Connascence of Algorithm**: one module encodes (serializes,
+//!   compresses, encrypts) with a scheme and another decodes with the same
+//!   scheme, or two modules hash with the same algorithm. Only pairs that
+//!   cross a module or crate boundary are reported. The scheme is taken from
+//!   the call path (`base64::encode`, `STANDARD.decode(..)`, `Sha256::digest`);
+//!   unqualified calls are ignored because their scheme is unknown.
+//!   Copy-pasted functions are Connascence of Algorithm too: two function
+//!   bodies in different modules whose normalized tokens (local names and
+//!   literals replaced by placeholders) are near-identical must be fixed
+//!   together, and usually belong in one shared function.
+//! - **Connascence of Execution**: a method must run after another one,
+//!   because it checks state the other sets (see the temporal analyzer's
+//!   state checks) or because its doc comment says so ("must be called
+//!   after `connect`"). An intra-crate call graph then shows which callers
+//!   invoke it without calling the prerequisite themselves.
+//! - **Connascence of Identity**: global mutable state (`static mut`,
+//!   statics holding a `Mutex`, atomic, `OnceCell`/`Lazy`, `lazy_static!`
+//!   items, and `Arc<Mutex<..>>` type aliases) referenced from several
+//!   modules. Those modules must all see the same instance.
+//! - **Connascence of Type**: the same concrete struct or enum appears in
+//!   the public signatures of many other modules, so all of them change
+//!   when the type does. Trait impl methods are not counted (the trait
+//!   dictates their signatures), nor are types defined more than once.
+//!
+//! Collection happens per file ([`ConnascenceAnalyzer`]); findings are
+//! resolved across the whole project afterwards ([`find_positional_risks`],
+//! [`find_shared_literals`]), since the coupled code usually lives in
+//! different modules.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::Path;
+
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use syn::visit::Visit;
+use syn::{
+    Attribute, Expr, ExprCall, ExprIndex, ExprLit, ExprMethodCall, FnArg, ImplItemConst,
+    ImplItemFn, ItemConst, ItemFn, ItemImpl, ItemMacro, ItemMod, ItemStatic, ItemType, Lit,
+    ReturnType, Signature, TraitItemConst, UseName, UseRename, Variant,
+};
+
+use crate::analyzer::{is_test_code, span_position};
+use crate::metrics::{CouplingLocation, ModuleMetrics, ProjectMetrics};
+use crate::module_graph::module_path_from_file;
+use crate::sql::sql_tables;
+
+/// Kinds of connascence (Page-Jones), weakest first
+///
+/// Static kinds are visible in the source; dynamic kinds only show at
+/// runtime and are harder to find and to change safely.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ConnascenceType {
+    Name,
+    Type,
+    Meaning,
+    Position,
+    Algorithm,
+    Execution,
+    Timing,
+    Value,
+    Identity,
+}
+
+impl ConnascenceType {
+    /// Whether the connascence only shows at runtime
+    pub fn is_dynamic(&self) -> bool {
+        *self >= ConnascenceType::Execution
+    }
+}
+
+impl std::fmt::Display for ConnascenceType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ConnascenceType::Name => "Name",
+            ConnascenceType::Type => "Type",
+            ConnascenceType::Meaning => "Meaning",
+            ConnascenceType::Position => "Position",
+            ConnascenceType::Algorithm => "Algorithm",
+            ConnascenceType::Execution => "Execution",
+            ConnascenceType::Timing => "Timing",
+            ConnascenceType::Value => "Value",
+            ConnascenceType::Identity => "Identity",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A function or method signature with exact parameter types
+#[derive(Debug, Clone)]
+pub struct FnSignature {
+    pub name: String,
+    /// Self type for associated functions and methods
+    pub owner: Option<String>,
+    /// Whether the function takes `self`
+    pub is_method: bool,
+    /// Normalized parameter types, excluding `self`
+    pub params: Vec<String>,
+    /// Declared `pub` (trait impl methods never are)
+    pub is_public: bool,
+    /// Capitalized type names in parameters and return type, without the
+    /// function's own generic parameters
+    pub named_types: BTreeSet<String>,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl FnSignature {
+    /// Parameter types that occur more than once, with their positions
+    pub fn repeated_types(&self) -> Vec<(&str, Vec<usize>)> {
+        let mut positions: Vec<(&str, Vec<usize>)> = Vec::new();
+        for (i, ty) in self.params.iter().enumerate() {
+            match positions.iter_mut().find(|(t, _)| *t == ty) {
+                Some((_, p)) => p.push(i),
+                None => positions.push((ty, vec![i])),
+            }
+        }
+        positions.retain(|(_, p)| p.len() > 1);
+        positions
+    }
+
+    /// Number of argument pairs that could be swapped and still compile
+    pub fn swappable_pairs(&self) -> usize {
+        self.repeated_types()
+            .iter()
+            .map(|(_, p)| p.len() * (p.len() - 1) / 2)
+            .sum()
+    }
+}
+
+/// A call expression with positional arguments
+#[derive(Debug, Clone)]
+pub struct CallSite {
+    /// Function containing the call (`None` for module-level code)
+    pub caller: Option<String>,
+    /// Called function or method name
+    pub callee: String,
+    /// Type qualifier for path calls (`Config` in `Config::new(..)`)
+    pub qualifier: Option<String>,
+    /// Whether this is a method call (`x.f(..)`)
+    pub is_method: bool,
+    /// Argument positions that are literals (`3`, `"x"`, `-1`)
+    pub literal_args: Vec<usize>,
+    /// Number of arguments
+    pub arg_count: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// A numeric literal outside a named constant
+#[derive(Debug, Clone)]
+pub struct MagicNumber {
+    /// Normalized value (`0xFF` and `255u8` both become `255`)
+    pub value: String,
+    /// Function containing the literal
+    pub function: Option<String>,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// A table named by an SQL string literal
+#[derive(Debug, Clone)]
+pub struct QueryReference {
+    pub table: String,
+    /// Columns of `table` the query names, sorted
+    pub columns: Vec<String>,
+    /// Function containing the query
+    pub function: Option<String>,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Normalized tokens of a function body
+#[derive(Debug, Clone)]
+pub struct FunctionBody {
+    pub name: String,
+    pub owner: Option<String>,
+    /// Token hashes, local names and literals replaced by placeholders
+    pub tokens: Vec<u64>,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl FunctionBody {
+    /// `Owner::name` or `name`
+    pub fn qualified_name(&self) -> String {
+        match &self.owner {
+            Some(owner) => format!("{}::{}", owner, self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// Role of a call in an algorithm shared between modules
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AlgorithmRole {
+    /// encode, serialize, compress, encrypt
+    Encode,
+    /// decode, deserialize, decompress, decrypt
+    Decode,
+    /// hash, digest (both sides must use the same algorithm)
+    Hash,
+}
+
+/// Call to an encoding, serialization, compression, encryption or hashing
+/// function with a known scheme
+#[derive(Debug, Clone)]
+pub struct AlgorithmUse {
+    /// Path the call goes through (`base64`, `bincode`, `Sha256`)
+    pub scheme: String,
+    /// Algorithm family (`encoding`, `serialization`, ...)
+    pub family: &'static str,
+    pub role: AlgorithmRole,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Ordering stated in a doc comment
+#[derive(Debug, Clone)]
+pub struct OrderingHint {
+    pub owner: Option<String>,
+    /// Documented function
+    pub function: String,
+    /// Function named in the doc comment
+    pub other: String,
+    /// `true` for "call before `other`", `false` for "call after `other`"
+    pub before: bool,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Kind of global state item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SharedStateKind {
+    /// `static mut`
+    StaticMut,
+    /// `static` with interior mutability or lazy initialization
+    Static,
+    /// Item declared in `lazy_static!`
+    LazyStatic,
+    /// Type alias to `Arc<Mutex<..>>` / `Arc<RwLock<..>>`
+    SharedPointer,
+}
+
+impl std::fmt::Display for SharedStateKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SharedStateKind::StaticMut => write!(f, "static mut"),
+            SharedStateKind::Static => write!(f, "static"),
+            SharedStateKind::LazyStatic => write!(f, "lazy_static"),
+            SharedStateKind::SharedPointer => write!(f, "shared pointer"),
+        }
+    }
+}
+
+/// A global state item defined in a module
+#[derive(Debug, Clone)]
+pub struct SharedStateItem {
+    pub name: String,
+    pub kind: SharedStateKind,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Connascence data collected for a module
+#[derive(Debug, Clone, Default)]
+pub struct ConnascenceMetrics {
+    /// Signatures defined in the module
+    pub signatures: Vec<FnSignature>,
+    /// Calls with at least two arguments
+    pub call_sites: Vec<CallSite>,
+    /// `(caller, callee)` name pairs of all calls made inside functions
+    pub calls: BTreeSet<(String, String)>,
+    /// Magic numbers in non-test code
+    pub magic_numbers: Vec<MagicNumber>,
+    /// Tables named by SQL string literals in non-test code
+    pub query_references: Vec<QueryReference>,
+    /// Encoding/decoding and hashing calls
+    pub algorithm_uses: Vec<AlgorithmUse>,
+    /// Call order documented in doc comments
+    pub ordering_hints: Vec<OrderingHint>,
+    /// Global state items defined in the module
+    pub shared_state: Vec<SharedStateItem>,
+    /// Capitalized names (types, statics) referenced in non-test code
+    pub referenced_names: BTreeSet<String>,
+    /// Bodies of functions long enough to be compared for clones
+    pub function_bodies: Vec<FunctionBody>,
+}
+
+/// A caller/callee pair at risk of Connascence of Position
+#[derive(Debug, Clone)]
+pub struct PositionalRisk {
+    /// Module containing the call
+    pub module: String,
+    pub caller: Option<String>,
+    /// Module defining the callee
+    pub callee_module: String,
+    pub callee: String,
+    /// Parameter types shared by two or more parameters
+    pub shared_types: Vec<String>,
+    /// Argument pairs that could be swapped and still compile
+    pub swappable_pairs: usize,
+    /// Literal arguments in swappable positions (worst call site)
+    pub literal_args: usize,
+    /// Number of calls from `caller` to `callee`
+    pub call_sites: usize,
+    /// Location of the riskiest call
+    pub location: CouplingLocation,
+}
+
+impl PositionalRisk {
+    /// Risk score: swappable pairs, plus one per literal argument in a
+    /// swappable position (literals carry no name to catch a swap)
+    pub fn risk(&self) -> usize {
+        self.swappable_pairs + self.literal_args
+    }
+}
+
+/// A magic number shared by several modules (Connascence of Meaning)
+#[derive(Debug, Clone)]
+pub struct SharedLiteral {
+    pub value: String,
+    /// Modules using the value, sorted
+    pub modules: Vec<String>,
+    /// Total number of occurrences
+    pub occurrences: usize,
+    /// Location of the first occurrence
+    pub location: CouplingLocation,
+}
+
+/// A database table queried by several modules (Connascence of Meaning)
+#[derive(Debug, Clone)]
+pub struct SharedSchema {
+    pub table: String,
+    /// Modules querying the table, sorted
+    pub modules: Vec<String>,
+    /// Columns named by at least two of the modules, sorted
+    pub columns: Vec<String>,
+    /// Total number of queries naming the table
+    pub occurrences: usize,
+    /// Location of the first query
+    pub location: CouplingLocation,
+}
+
+/// One copy of a cloned function
+#[derive(Debug, Clone)]
+pub struct CloneSite {
+    pub module: String,
+    /// `Owner::name` or `name`
+    pub function: String,
+    pub location: CouplingLocation,
+}
+
+/// Two near-identical function bodies in different modules
+/// (Connascence of Algorithm)
+#[derive(Debug, Clone)]
+pub struct CodeClone {
+    pub first: CloneSite,
+    pub second: CloneSite,
+    /// Shared token shingles over all shingles, `0.0..=1.0`
+    pub similarity: f64,
+    /// Tokens of the shorter body
+    pub tokens: usize,
+    /// Closest module containing both copies (`app::web`); `None` when
+    /// they live in different crates
+    pub extraction_target: Option<String>,
+}
+
+/// Why a function must run after another one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExecutionEvidence {
+    /// The function checks a field the prerequisite assigns
+    StateCheck { field: String },
+    /// A doc comment states the order
+    Documented,
+}
+
+/// A function that must run after one of its prerequisites
+/// (Connascence of Execution)
+#[derive(Debug, Clone)]
+pub struct ExecutionOrder {
+    /// Module defining the function
+    pub module: String,
+    pub owner: Option<String>,
+    /// Functions one of which must run first
+    pub prerequisites: Vec<String>,
+    /// Function that must run afterwards
+    pub function: String,
+    pub evidence: ExecutionEvidence,
+    /// Callers of `function` in the same crate, as `module::caller`
+    pub callers: Vec<String>,
+    /// Callers that do not call any prerequisite themselves
+    pub unguarded_callers: Vec<String>,
+    pub location: CouplingLocation,
+}
+
+impl ExecutionOrder {
+    /// Unguarded callers outside the defining module
+    pub fn foreign_unguarded_callers(&self) -> usize {
+        let prefix = format!("{}::", self.module);
+        self.unguarded_callers
+            .iter()
+            .filter(|c| !c.starts_with(&prefix))
+            .count()
+    }
+}
+
+/// A concrete type in the public signatures of many modules
+/// (Connascence of Type)
+#[derive(Debug, Clone)]
+pub struct SharedSignatureType {
+    /// Module defining the type
+    pub module: String,
+    pub krate: Option<String>,
+    pub name: String,
+    /// Other modules with a public signature mentioning the type, sorted
+    pub users: Vec<String>,
+    /// Crates of the users other than the defining crate, sorted
+    pub user_crates: Vec<String>,
+    /// Location of the type definition
+    pub location: CouplingLocation,
+}
+
+/// Global state referenced from several modules (Connascence of Identity)
+#[derive(Debug, Clone)]
+pub struct SharedStateUse {
+    /// Module defining the state
+    pub module: String,
+    pub name: String,
+    pub kind: SharedStateKind,
+    /// Other modules referencing the state, sorted
+    pub users: Vec<String>,
+    /// Location of the definition
+    pub location: CouplingLocation,
+}
+
+/// Two modules that must agree on an algorithm (Connascence of Algorithm)
+#[derive(Debug, Clone)]
+pub struct AlgorithmCoupling {
+    pub scheme: String,
+    pub family: &'static str,
+    /// Encoding (or first hashing) module
+    pub producer: String,
+    /// Decoding (or second hashing) module
+    pub consumer: String,
+    pub producer_crate: Option<String>,
+    pub consumer_crate: Option<String>,
+    /// Whether either crate depends directly on the other
+    pub crates_linked: bool,
+    /// Location of the consumer's call
+    pub location: CouplingLocation,
+}
+
+impl AlgorithmCoupling {
+    /// Whether producer and consumer live in different crates
+    pub fn crosses_crate(&self) -> bool {
+        match (&self.producer_crate, &self.consumer_crate) {
+            (Some(a), Some(b)) => a != b,
+            _ => false,
+        }
+    }
+}
+
+/// Classify a function name as part of a paired or shared algorithm
+fn classify_algorithm(name: &str) -> Option<(&'static str, AlgorithmRole)> {
+    const PAIRS: &[(&str, &str, &str)] = &[
+        ("encode", "decode", "encoding"),
+        ("serialize", "deserialize", "serialization"),
+        ("compress", "decompress", "compression"),
+        ("encrypt", "decrypt", "encryption"),
+    ];
+    for (encode, decode, family) in PAIRS {
+        if name.starts_with(decode) {
+            return Some((family, AlgorithmRole::Decode));
+        }
+        if name.starts_with(encode) {
+            return Some((family, AlgorithmRole::Encode));
+        }
+    }
+    if name.starts_with("hash") || name == "digest" {
+        return Some(("hashing", AlgorithmRole::Hash));
+    }
+    None
+}
+
+/// Scheme named by a path (`crate::codec::base64` → `codec::base64`)
+fn path_scheme<'a>(segments: impl Iterator<Item = &'a syn::PathSegment>) -> Option<String> {
+    let names: Vec<String> = segments
+        .map(|s| s.ident.to_string())
+        .skip_while(|s| matches!(s.as_str(), "crate" | "self" | "super"))
+        .collect();
+    (!names.is_empty()).then(|| names.join("::"))
+}
+
+/// Normalize a type for comparison (`& 'a str` → `&'astr`)
+fn type_key(ty: &syn::Type) -> String {
+    ty.to_token_stream()
+        .to_string()
+        .split_whitespace()
+        .collect()
+}
+
+fn is_literal(expr: &Expr) -> bool {
+    match expr {
+        Expr::Lit(_) => true,
+        Expr::Unary(unary) => matches!(unary.op, syn::UnOp::Neg(_)) && is_literal(&unary.expr),
+        Expr::Paren(paren) => is_literal(&paren.expr),
+        _ => false,
+    }
+}
+
+/// Functions a doc comment says must be called before or after this one,
+/// as `(other, before)`
+fn doc_ordering(attrs: &[Attribute]) -> Vec<(String, bool)> {
+    let doc: String = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(nv) => match &nv.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(s), ..
+                }) => Some(s.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    if !["must", "call", "only", "require"]
+        .iter()
+        .any(|word| doc.contains(word))
+    {
+        return Vec::new();
+    }
+
+    let mut hints = Vec::new();
+    for (marker, before) in [("before `", true), ("after `", false)] {
+        for (start, _) in doc.match_indices(marker) {
+            let rest = &doc[start + marker.len()..];
+            let Some(end) = rest.find('`') else {
+                continue;
+            };
+            let name = rest[..end]
+                .trim_end_matches("()")
+                .rsplit(['.', ':'])
+                .next()
+                .unwrap_or_default();
+            if !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                hints.push((name.to_string(), before));
+            }
+        }
+    }
+    hints
+}
+
+/// Types that make a `static` mutable or lazily initialized
+const STATE_TYPES: &[&str] = &[
+    "Mutex", "RwLock", "RefCell", "Cell", "OnceCell", "OnceLock", "Lazy", "LazyLock",
+];
+
+/// Identifiers of all path segments in a type
+fn type_idents(ty: &syn::Type) -> Vec<String> {
+    struct Idents(Vec<String>);
+    impl<'ast> Visit<'ast> for Idents {
+        fn visit_path_segment(&mut self, node: &'ast syn::PathSegment) {
+            self.0.push(node.ident.to_string());
+            syn::visit::visit_path_segment(self, node);
+        }
+    }
+    let mut idents = Idents(Vec::new());
+    idents.visit_type(ty);
+    idents.0
+}
+
+/// Whether a type holds state that can change after initialization
+fn is_state_type(ty: &syn::Type) -> bool {
+    type_idents(ty)
+        .iter()
+        .any(|i| STATE_TYPES.contains(&i.as_str()) || i.starts_with("Atomic"))
+}
+
+/// Whether a type is `Arc<Mutex<..>>` or `Arc<RwLock<..>>`
+fn is_shared_pointer(ty: &syn::Type) -> bool {
+    let idents = type_idents(ty);
+    idents.first().is_some_and(|i| i == "Arc")
+        && idents.iter().any(|i| i == "Mutex" || i == "RwLock")
+}
+
+/// Names declared with `static ref NAME: ...` in a `lazy_static!` body
+fn lazy_static_names(tokens: proc_macro2::TokenStream) -> Vec<syn::Ident> {
+    use proc_macro2::TokenTree;
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    tokens
+        .windows(3)
+        .filter_map(|w| match w {
+            [
+                TokenTree::Ident(s),
+                TokenTree::Ident(r),
+                TokenTree::Ident(name),
+            ] if s == "static" && r == "ref" => Some(name.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn literal_positions<'a>(args: impl Iterator<Item = &'a Expr>) -> Vec<usize> {
+    args.enumerate()
+        .filter(|(_, arg)| is_literal(arg))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// AST visitor that collects signatures and call sites
+#[derive(Debug, Default)]
+pub struct ConnascenceAnalyzer {
+    metrics: ConnascenceMetrics,
+    /// Stack of enclosing function names
+    functions: Vec<String>,
+    /// Self type of the enclosing impl block
+    impl_owner: Option<String>,
+    /// Whether the enclosing impl block implements a trait
+    in_trait_impl: bool,
+}
+
+impl ConnascenceAnalyzer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Analyze a parsed file
+    pub fn analyze(file: &syn::File) -> ConnascenceMetrics {
+        let mut analyzer = Self::new();
+        analyzer.visit_file(file);
+        analyzer.metrics
+    }
+
+    /// Parse and analyze source code
+    pub fn analyze_source(content: &str) -> Result<ConnascenceMetrics, syn::Error> {
+        let file = syn::parse_file(content)?;
+        Ok(Self::analyze(&file))
+    }
+
+    fn record_signature(&mut self, sig: &Signature, vis: &syn::Visibility, owner: Option<String>) {
+        let generics: HashSet<String> = sig
+            .generics
+            .type_params()
+            .map(|p| p.ident.to_string())
+            .collect();
+        let mut is_method = false;
+        let mut params = Vec::new();
+        let mut named_types = BTreeSet::new();
+        let mut add_types = |ty: &syn::Type| {
+            named_types.extend(type_idents(ty).into_iter().filter(|i| {
+                i.starts_with(char::is_uppercase) && i != "Self" && !generics.contains(i)
+            }));
+        };
+        for input in &sig.inputs {
+            match input {
+                FnArg::Receiver(_) => is_method = true,
+                FnArg::Typed(pat_type) => {
+                    add_types(&pat_type.ty);
+                    params.push(type_key(&pat_type.ty));
+                }
+            }
+        }
+        if let ReturnType::Type(_, ty) = &sig.output {
+            add_types(ty);
+        }
+        let (line, column) = span_position(sig.ident.span());
+        self.metrics.signatures.push(FnSignature {
+            name: sig.ident.to_string(),
+            owner,
+            is_method,
+            params,
+            is_public: matches!(vis, syn::Visibility::Public(_)) && !self.in_trait_impl,
+            named_types,
+            line,
+            column,
+        });
+    }
+
+    fn record_call(
+        &mut self,
+        callee: String,
+        qualifier: Option<String>,
+        is_method: bool,
+        literal_args: Vec<usize>,
+        arg_count: usize,
+        span: proc_macro2::Span,
+    ) {
+        if let Some(caller) = self.functions.last() {
+            self.metrics.calls.insert((caller.clone(), callee.clone()));
+        }
+        if arg_count < 2 {
+            return;
+        }
+        let (line, column) = span_position(span);
+        self.metrics.call_sites.push(CallSite {
+            caller: self.functions.last().cloned(),
+            callee,
+            qualifier,
+            is_method,
+            literal_args,
+            arg_count,
+            line,
+            column,
+        });
+    }
+
+    fn record_ordering_hints(&mut self, attrs: &[Attribute], ident: &syn::Ident) {
+        let (line, column) = span_position(ident.span());
+        for (other, before) in doc_ordering(attrs) {
+            self.metrics.ordering_hints.push(OrderingHint {
+                owner: self.impl_owner.clone(),
+                function: ident.to_string(),
+                other,
+                before,
+                line,
+                column,
+            });
+        }
+    }
+
+    fn record_shared_state(&mut self, ident: &syn::Ident, kind: SharedStateKind) {
+        let (line, column) = span_position(ident.span());
+        self.metrics.shared_state.push(SharedStateItem {
+            name: ident.to_string(),
+            kind,
+            line,
+            column,
+        });
+    }
+
+    /// Record an algorithm call if its name and scheme are recognized
+    fn record_algorithm(&mut self, name: &str, scheme: Option<String>, span: proc_macro2::Span) {
+        let (Some((family, role)), Some(scheme)) = (classify_algorithm(name), scheme) else {
+            return;
+        };
+        let (line, column) = span_position(span);
+        self.metrics.algorithm_uses.push(AlgorithmUse {
+            scheme,
+            family,
+            role,
+            line,
+            column,
+        });
+    }
+
+    /// Record a numeric literal unless it is trivial (`0`, `1`, `2`)
+    fn record_magic_number(&mut self, value: String, span: proc_macro2::Span) {
+        if matches!(value.as_str(), "0" | "1" | "2" | "0.0" | "1.0" | "2.0") {
+            return;
+        }
+        let (line, column) = span_position(span);
+        self.metrics.magic_numbers.push(MagicNumber {
+            value,
+            function: self.functions.last().cloned(),
+            line,
+            column,
+        });
+    }
+
+    /// Record a function body long enough to be compared for clones
+    fn record_body(&mut self, ident: &syn::Ident, owner: Option<String>, block: &syn::Block) {
+        let mut tokens = Vec::new();
+        normalize_tokens(block.to_token_stream(), &mut tokens);
+        if tokens.len() < MIN_CLONE_TOKENS {
+            return;
+        }
+        let (line, column) = span_position(ident.span());
+        self.metrics.function_bodies.push(FunctionBody {
+            name: ident.to_string(),
+            owner,
+            tokens,
+            line,
+            column,
+        });
+    }
+
+    /// Record the tables of a string literal if it is an SQL statement
+    fn record_query(&mut self, text: &str, span: proc_macro2::Span) {
+        let (line, column) = span_position(span);
+        for (table, columns) in sql_tables(text) {
+            self.metrics.query_references.push(QueryReference {
+                table,
+                columns: columns.into_iter().collect(),
+                function: self.functions.last().cloned(),
+                line,
+                column,
+            });
+        }
+    }
+
+    /// Record the SQL string literals of a macro invocation
+    /// (`format!`, `sqlx::query!`, ...)
+    fn record_macro_queries(&mut self, tokens: proc_macro2::TokenStream) {
+        for token in tokens {
+            match token {
+                proc_macro2::TokenTree::Group(group) => self.record_macro_queries(group.stream()),
+                proc_macro2::TokenTree::Literal(literal) => {
+                    if let Ok(Lit::Str(lit)) = syn::parse2::<Lit>(literal.into_token_stream()) {
+                        self.record_query(&lit.value(), lit.span());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for ConnascenceAnalyzer {
+    fn visit_item_mod(&mut self, node: &'ast ItemMod) {
+        if !is_test_code(&node.attrs) {
+            syn::visit::visit_item_mod(self, node);
+        }
+    }
+
+    fn visit_item_fn(&mut self, node: &'ast ItemFn) {
+        if is_test_code(&node.attrs) {
+            return;
+        }
+        self.record_signature(&node.sig, &node.vis, None);
+        self.record_ordering_hints(&node.attrs, &node.sig.ident);
+        self.record_body(&node.sig.ident, None, &node.block);
+        self.functions.push(node.sig.ident.to_string());
+        syn::visit::visit_item_fn(self, node);
+        self.functions.pop();
+    }
+
+    fn visit_item_impl(&mut self, node: &'ast ItemImpl) {
+        let previous = self.impl_owner.take();
+        let previous_trait_impl = self.in_trait_impl;
+        if let syn::Type::Path(type_path) = &*node.self_ty {
+            self.impl_owner = type_path.path.segments.last().map(|s| s.ident.to_string());
+        }
+        self.in_trait_impl = node.trait_.is_some();
+        syn::visit::visit_item_impl(self, node);
+        self.impl_owner = previous;
+        self.in_trait_impl = previous_trait_impl;
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast ImplItemFn) {
+        if is_test_code(&node.attrs) {
+            return;
+        }
+        self.record_signature(&node.sig, &node.vis, self.impl_owner.clone());
+        self.record_ordering_hints(&node.attrs, &node.sig.ident);
+        self.record_body(&node.sig.ident, self.impl_owner.clone(), &node.block);
+        self.functions.push(node.sig.ident.to_string());
+        syn::visit::visit_impl_item_fn(self, node);
+        self.functions.pop();
+    }
+
+    fn visit_expr_call(&mut self, node: &'ast ExprCall) {
+        if let Expr::Path(path) = &*node.func {
+            let segments = &path.path.segments;
+            if let Some(last) = segments.last() {
+                let qualifier = segments
+                    .len()
+                    .checked_sub(2)
+                    .map(|i| segments[i].ident.to_string());
+                self.record_call(
+                    last.ident.to_string(),
+                    qualifier,
+                    false,
+                    literal_positions(node.args.iter()),
+                    node.args.len(),
+                    last.ident.span(),
+                );
+                self.record_algorithm(
+                    &last.ident.to_string(),
+                    path_scheme(segments.iter().take(segments.len() - 1)),
+                    last.ident.span(),
+                );
+            }
+        }
+        syn::visit::visit_expr_call(self, node);
+    }
+
+    // Named constants and discriminants give a literal its meaning; they are
+    // the fix for magic numbers, not an instance of them. A constant query
+    // still names its tables, though.
+    fn visit_item_const(&mut self, node: &'ast ItemConst) {
+        if let Expr::Lit(ExprLit {
+            lit: Lit::Str(lit), ..
+        }) = &*node.expr
+        {
+            self.record_query(&lit.value(), lit.span());
+        }
+    }
+
+    fn visit_item_static(&mut self, node: &'ast ItemStatic) {
+        if matches!(node.mutability, syn::StaticMutability::Mut(_)) {
+            self.record_shared_state(&node.ident, SharedStateKind::StaticMut);
+        } else if is_state_type(&node.ty) {
+            self.record_shared_state(&node.ident, SharedStateKind::Static);
+        }
+    }
+
+    fn visit_item_type(&mut self, node: &'ast ItemType) {
+        if is_shared_pointer(&node.ty) {
+            self.record_shared_state(&node.ident, SharedStateKind::SharedPointer);
+        }
+        syn::visit::visit_item_type(self, node);
+    }
+
+    fn visit_item_macro(&mut self, node: &'ast ItemMacro) {
+        if node
+            .mac
+            .path
+            .segments
+            .last()
+            .is_some_and(|s| s.ident == "lazy_static")
+        {
+            for ident in lazy_static_names(node.mac.tokens.clone()) {
+                self.record_shared_state(&ident, SharedStateKind::LazyStatic);
+            }
+        }
+        syn::visit::visit_item_macro(self, node);
+    }
+
+    fn visit_path(&mut self, node: &'ast syn::Path) {
+        if let Some(last) = node.segments.last() {
+            let name = last.ident.to_string();
+            if name.starts_with(char::is_uppercase) {
+                self.metrics.referenced_names.insert(name);
+            }
+        }
+        syn::visit::visit_path(self, node);
+    }
+
+    fn visit_use_name(&mut self, node: &'ast UseName) {
+        let name = node.ident.to_string();
+        if name.starts_with(char::is_uppercase) {
+            self.metrics.referenced_names.insert(name);
+        }
+    }
+
+    fn visit_use_rename(&mut self, node: &'ast UseRename) {
+        let name = node.ident.to_string();
+        if name.starts_with(char::is_uppercase) {
+            self.metrics.referenced_names.insert(name);
+        }
+    }
+
+    fn visit_impl_item_const(&mut self, node: &'ast ImplItemConst) {
+        if let Expr::Lit(ExprLit {
+            lit: Lit::Str(lit), ..
+        }) = &node.expr
+        {
+            self.record_query(&lit.value(), lit.span());
+        }
+    }
+
+    fn visit_trait_item_const(&mut self, _node: &'ast TraitItemConst) {}
+
+    fn visit_variant(&mut self, node: &'ast Variant) {
+        self.visit_fields(&node.fields);
+    }
+
+    fn visit_expr_index(&mut self, node: &'ast ExprIndex) {
+        self.visit_expr(&node.expr);
+        if !is_literal(&node.index) {
+            self.visit_expr(&node.index);
+        }
+    }
+
+    fn visit_expr_lit(&mut self, node: &'ast ExprLit) {
+        match &node.lit {
+            Lit::Int(lit) => self.record_magic_number(lit.base10_digits().to_string(), lit.span()),
+            Lit::Float(lit) => {
+                self.record_magic_number(lit.base10_digits().to_string(), lit.span())
+            }
+            Lit::Str(lit) => self.record_query(&lit.value(), lit.span()),
+            _ => {}
+        }
+    }
+
+    fn visit_macro(&mut self, node: &'ast syn::Macro) {
+        self.record_macro_queries(node.tokens.clone());
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast ExprMethodCall) {
+        self.record_call(
+            node.method.to_string(),
+            None,
+            true,
+            literal_positions(node.args.iter()),
+            node.args.len(),
+            node.method.span(),
+        );
+        // `STANDARD.encode(..)`; a lowercase single name is a local variable
+        if let Expr::Path(receiver) = &*node.receiver {
+            let scheme = path_scheme(receiver.path.segments.iter())
+                .filter(|s| s.contains("::") || !s.starts_with(char::is_lowercase));
+            self.record_algorithm(&node.method.to_string(), scheme, node.method.span());
+        }
+        syn::visit::visit_expr_method_call(self, node);
+    }
+}
+
+/// Function bodies shorter than this many tokens are not compared for
+/// clones
+pub const MIN_CLONE_TOKENS: usize = 50;
+
+/// Similarity from which two function bodies are reported as clones
+pub const MIN_CLONE_SIMILARITY: f64 = 0.8;
+
+/// Consecutive tokens hashed together when comparing bodies
+const SHINGLE_SIZE: usize = 5;
+
+/// Keywords that can appear in a function body
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "false",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use",
+    "where", "while",
+];
+
+/// Hash the tokens of a body, replacing literals and local names
+///
+/// Keywords, capitalized names (types, variants, constants) and names of
+/// called functions, methods and macros are kept, so `a.len() + b.len()`
+/// and `x.len() + y.len()` match but `a.len()` and `a.clear()` do not.
+fn normalize_tokens(stream: proc_macro2::TokenStream, out: &mut Vec<u64>) {
+    use proc_macro2::{Delimiter, TokenTree};
+
+    let hash = |token: &str| {
+        let mut hasher = std::hash::DefaultHasher::new();
+        std::hash::Hash::hash(token, &mut hasher);
+        std::hash::Hasher::finish(&hasher)
+    };
+    let tokens: Vec<TokenTree> = stream.into_iter().collect();
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::None => ("", ""),
+                };
+                out.push(hash(open));
+                normalize_tokens(group.stream(), out);
+                out.push(hash(close));
+            }
+            TokenTree::Ident(ident) => {
+                let name = ident.to_string();
+                let called = match tokens.get(i + 1) {
+                    Some(TokenTree::Group(group)) => group.delimiter() == Delimiter::Parenthesis,
+                    Some(TokenTree::Punct(punct)) => matches!(punct.as_char(), '!' | ':'),
+                    _ => false,
+                };
+                let kept = called
+                    || name.starts_with(char::is_uppercase)
+                    || KEYWORDS.contains(&name.as_str());
+                out.push(hash(if kept { &name } else { "$name" }));
+            }
+            TokenTree::Literal(_) => out.push(hash("$literal")),
+            TokenTree::Punct(punct) => out.push(hash(&punct.as_char().to_string())),
+        }
+    }
+}
+
+/// Sorted, deduplicated hashes of every [`SHINGLE_SIZE`] consecutive tokens
+fn shingles(tokens: &[u64]) -> Vec<u64> {
+    let mut shingles: Vec<u64> = tokens
+        .windows(SHINGLE_SIZE)
+        .map(|window| {
+            window.iter().fold(0u64, |acc, t| {
+                acc.rotate_left(7) ^ t.wrapping_mul(0x9E37_79B9_7F4A_7C15)
+            })
+        })
+        .collect();
+    shingles.sort_unstable();
+    shingles.dedup();
+    shingles
+}
+
+/// Jaccard similarity of two sorted shingle sets
+fn jaccard(a: &[u64], b: &[u64]) -> f64 {
+    let (mut i, mut j, mut shared) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                shared += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    let union = a.len() + b.len() - shared;
+    if union == 0 {
+        0.0
+    } else {
+        shared as f64 / union as f64
+    }
+}
+
+/// Resolve the signature a call refers to. Returns `None` if the callee is
+/// unknown or ambiguous (several candidates with different parameters).
+fn resolve_callee<'a>(
+    call: &CallSite,
+    caller_module: &str,
+    signatures: &HashMap<&str, Vec<(&'a str, &'a FnSignature)>>,
+) -> Option<(&'a str, &'a FnSignature)> {
+    let candidates: Vec<_> = signatures
+        .get(call.callee.as_str())?
+        .iter()
+        .filter(|(_, sig)| sig.is_method == call.is_method && sig.params.len() == call.arg_count)
+        .filter(|(_, sig)| match (&call.qualifier, &sig.owner) {
+            (Some(q), Some(owner)) => q == owner || q == "Self",
+            // A lowercase qualifier is a module path (`geom::resize`)
+            (Some(q), None) => q.starts_with(char::is_lowercase),
+            (None, Some(_)) => call.is_method,
+            (None, None) => true,
+        })
+        .copied()
+        .collect();
+
+    if let Some(local) = candidates.iter().find(|(m, _)| *m == caller_module) {
+        return Some(*local);
+    }
+    let first = candidates.first()?;
+    candidates
+        .iter()
+        .all(|(_, sig)| sig.params == first.1.params)
+        .then_some(*first)
+}
+
+/// Find caller/callee pairs where arguments of the same type are passed
+/// positionally, sorted by descending risk
+pub fn find_positional_risks(metrics: &ProjectMetrics) -> Vec<PositionalRisk> {
+    let mut signatures: HashMap<&str, Vec<(&str, &FnSignature)>> = HashMap::new();
+    for module in metrics.modules.values() {
+        for sig in &module.connascence.signatures {
+            if sig.swappable_pairs() > 0 {
+                signatures
+                    .entry(sig.name.as_str())
+                    .or_default()
+                    .push((module.name.as_str(), sig));
+            }
+        }
+    }
+
+    let mut risks: HashMap<(String, Option<String>, String, String), PositionalRisk> =
+        HashMap::new();
+    for module in metrics.modules.values() {
+        for call in &module.connascence.call_sites {
+            let Some((callee_module, sig)) = resolve_callee(call, &module.name, &signatures) else {
+                continue;
+            };
+
+            let repeated = sig.repeated_types();
+            let literal_args = call
+                .literal_args
+                .iter()
+                .filter(|i| repeated.iter().any(|(_, p)| p.contains(i)))
+                .count();
+
+            let key = (
+                module.name.clone(),
+                call.caller.clone(),
+                callee_module.to_string(),
+                sig.name.clone(),
+            );
+            let risk = risks.entry(key).or_insert_with(|| PositionalRisk {
+                module: module.name.clone(),
+                caller: call.caller.clone(),
+                callee_module: callee_module.to_string(),
+                callee: sig.name.clone(),
+                shared_types: repeated.iter().map(|(t, _)| t.to_string()).collect(),
+                swappable_pairs: sig.swappable_pairs(),
+                literal_args,
+                call_sites: 0,
+                location: CouplingLocation::new(module.path.clone(), call.line, call.column),
+            });
+            risk.call_sites += 1;
+            if literal_args > risk.literal_args {
+                risk.literal_args = literal_args;
+                risk.location = CouplingLocation::new(module.path.clone(), call.line, call.column);
+            }
+        }
+    }
+
+    let mut risks: Vec<PositionalRisk> = risks.into_values().collect();
+    risks.sort_by(|a, b| {
+        b.risk()
+            .cmp(&a.risk())
+            .then_with(|| a.module.cmp(&b.module))
+            .then_with(|| a.caller.cmp(&b.caller))
+            .then_with(|| a.callee.cmp(&b.callee))
+    });
+    risks
+}
+
+/// Magic numbers used in two or more modules, most widespread first
+pub fn find_shared_literals(metrics: &ProjectMetrics) -> Vec<SharedLiteral> {
+    let mut uses: BTreeMap<&str, Vec<(&str, CouplingLocation)>> = BTreeMap::new();
+    for module in metrics.modules.values() {
+        for magic in &module.connascence.magic_numbers {
+            uses.entry(magic.value.as_str()).or_default().push((
+                module.name.as_str(),
+                CouplingLocation::new(module.path.clone(), magic.line, magic.column),
+            ));
+        }
+    }
+
+    let mut shared: Vec<SharedLiteral> = uses
+        .into_iter()
+        .filter_map(|(value, mut occurrences)| {
+            let modules: BTreeSet<&str> = occurrences.iter().map(|(m, _)| *m).collect();
+            if modules.len() < 2 {
+                return None;
+            }
+            occurrences
+                .sort_by(|a, b| (a.0, a.1.line, a.1.column).cmp(&(b.0, b.1.line, b.1.column)));
+            Some(SharedLiteral {
+                value: value.to_string(),
+                modules: modules.into_iter().map(String::from).collect(),
+                occurrences: occurrences.len(),
+                location: occurrences.swap_remove(0).1,
+            })
+        })
+        .collect();
+    shared.sort_by(|a, b| {
+        b.modules
+            .len()
+            .cmp(&a.modules.len())
+            .then_with(|| a.value.cmp(&b.value))
+    });
+    shared
+}
+
+/// Tables queried by SQL in at least two modules, most widespread first
+pub fn find_shared_schema(metrics: &ProjectMetrics) -> Vec<SharedSchema> {
+    // Table -> (module, query) pairs
+    let mut uses: BTreeMap<&str, Vec<(&str, &Path, &QueryReference)>> = BTreeMap::new();
+    for module in metrics.modules.values() {
+        for query in &module.connascence.query_references {
+            uses.entry(query.table.as_str()).or_default().push((
+                module.name.as_str(),
+                module.path.as_path(),
+                query,
+            ));
+        }
+    }
+
+    let mut shared: Vec<SharedSchema> = uses
+        .into_iter()
+        .filter_map(|(table, mut queries)| {
+            let modules: BTreeSet<&str> = queries.iter().map(|(m, _, _)| *m).collect();
+            if modules.len() < 2 {
+                return None;
+            }
+            let mut column_modules: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+            for (module, _, query) in &queries {
+                for column in &query.columns {
+                    column_modules.entry(column).or_default().insert(module);
+                }
+            }
+            queries.sort_by(|a, b| (a.0, a.2.line, a.2.column).cmp(&(b.0, b.2.line, b.2.column)));
+            let (_, path, first) = queries[0];
+            Some(SharedSchema {
+                table: table.to_string(),
+                modules: modules.into_iter().map(String::from).collect(),
+                columns: column_modules
+                    .into_iter()
+                    .filter(|(_, modules)| modules.len() >= 2)
+                    .map(|(column, _)| column.to_string())
+                    .collect(),
+                occurrences: queries.len(),
+                location: CouplingLocation::new(path.to_path_buf(), first.line, first.column),
+            })
+        })
+        .collect();
+    shared.sort_by(|a, b| {
+        b.modules
+            .len()
+            .cmp(&a.modules.len())
+            .then_with(|| a.table.cmp(&b.table))
+    });
+    shared
+}
+
+/// Other modules whose public signatures must mention a type before it is
+/// reported as Connascence of Type
+pub const MIN_SIGNATURE_TYPE_USERS: usize = 3;
+
+/// Concrete types in the public signatures of at least
+/// [`MIN_SIGNATURE_TYPE_USERS`] other modules, most widespread first
+pub fn find_shared_signature_types(metrics: &ProjectMetrics) -> Vec<SharedSignatureType> {
+    // Concrete type name -> defining modules
+    let mut definitions: BTreeMap<&str, Vec<&ModuleMetrics>> = BTreeMap::new();
+    for module in metrics.modules.values() {
+        for def in module.type_definitions.values() {
+            if !def.is_trait {
+                definitions
+                    .entry(def.name.as_str())
+                    .or_default()
+                    .push(module);
+            }
+        }
+    }
+
+    // Type name -> modules with a public signature mentioning it
+    let mut users: BTreeMap<&str, BTreeMap<&str, &ModuleMetrics>> = BTreeMap::new();
+    for module in metrics.modules.values() {
+        for signature in module.connascence.signatures.iter().filter(|s| s.is_public) {
+            for name in &signature.named_types {
+                users
+                    .entry(name.as_str())
+                    .or_default()
+                    .insert(module.name.as_str(), module);
+            }
+        }
+    }
+
+    let mut shared: Vec<SharedSignatureType> = definitions
+        .into_iter()
+        .filter_map(|(name, defined_in)| {
+            let [definer] = defined_in[..] else {
+                return None;
+            };
+            let others: Vec<&ModuleMetrics> = users
+                .get(name)?
+                .values()
+                .copied()
+                .filter(|m| m.name != definer.name)
+                .collect();
+            if others.len() < MIN_SIGNATURE_TYPE_USERS {
+                return None;
+            }
+            let user_crates: BTreeSet<&str> = others
+                .iter()
+                .filter_map(|m| m.crate_name.as_deref())
+                .filter(|krate| Some(*krate) != definer.crate_name.as_deref())
+                .collect();
+            let def = &definer.type_definitions[name];
+            Some(SharedSignatureType {
+                module: definer.name.clone(),
+                krate: definer.crate_name.clone(),
+                name: name.to_string(),
+                users: others.iter().map(|m| m.name.clone()).collect(),
+                user_crates: user_crates.into_iter().map(String::from).collect(),
+                location: CouplingLocation::new(definer.path.clone(), def.line, def.column),
+            })
+        })
+        .collect();
+    shared.sort_by(|a, b| {
+        b.users
+            .len()
+            .cmp(&a.users.len())
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    shared
+}
+
+/// Module pairs sharing an algorithm across a module or crate boundary
+pub fn find_algorithm_couplings(metrics: &ProjectMetrics) -> Vec<AlgorithmCoupling> {
+    // (family, scheme) -> role -> modules using it, with the first call site
+    type Uses<'a> = BTreeMap<AlgorithmRole, BTreeMap<&'a str, CouplingLocation>>;
+    let mut schemes: BTreeMap<(&str, &str), Uses> = BTreeMap::new();
+    for module in metrics.modules.values() {
+        for algo in &module.connascence.algorithm_uses {
+            schemes
+                .entry((algo.family, algo.scheme.as_str()))
+                .or_default()
+                .entry(algo.role)
+                .or_default()
+                .entry(module.name.as_str())
+                .or_insert_with(|| {
+                    CouplingLocation::new(module.path.clone(), algo.line, algo.column)
+                });
+        }
+    }
+
+    let crate_of = |module: &str| {
+        metrics
+            .modules
+            .get(module)
+            .and_then(|m| m.crate_name.clone())
+    };
+    let depends = |a: &str, b: &str| {
+        metrics
+            .crate_dependencies
+            .get(a)
+            .is_some_and(|deps| deps.iter().any(|d| d == b))
+    };
+
+    let mut couplings = Vec::new();
+    for ((family, scheme), roles) in &schemes {
+        let empty = BTreeMap::new();
+        let encoders = roles.get(&AlgorithmRole::Encode).unwrap_or(&empty);
+        let decoders = roles.get(&AlgorithmRole::Decode).unwrap_or(&empty);
+        let hashers = roles.get(&AlgorithmRole::Hash).unwrap_or(&empty);
+
+        let mut pairs: Vec<(&str, &str, &CouplingLocation)> = Vec::new();
+        for producer in encoders.keys() {
+            for (consumer, location) in decoders {
+                pairs.push((producer, consumer, location));
+            }
+        }
+        for (i, first) in hashers.keys().enumerate() {
+            for (second, location) in hashers.iter().skip(i + 1) {
+                pairs.push((first, second, location));
+            }
+        }
+
+        for (producer, consumer, location) in pairs {
+            if producer == consumer {
+                continue;
+            }
+            let producer_crate = crate_of(producer);
+            let consumer_crate = crate_of(consumer);
+            let crates_linked = match (&producer_crate, &consumer_crate) {
+                (Some(a), Some(b)) => a == b || depends(a, b) || depends(b, a),
+                _ => true,
+            };
+            couplings.push(AlgorithmCoupling {
+                scheme: scheme.to_string(),
+                family,
+                producer: producer.to_string(),
+                consumer: consumer.to_string(),
+                producer_crate,
+                consumer_crate,
+                crates_linked,
+                location: location.clone(),
+            });
+        }
+    }
+    couplings
+}
+
+/// Pairs of near-identical function bodies in different modules, most
+/// similar first
+///
+/// Bodies are compared by the shingles (runs of [`SHINGLE_SIZE`] tokens)
+/// of their normalized tokens; pairs reaching [`MIN_CLONE_SIMILARITY`] are
+/// reported.
+pub fn find_code_clones(metrics: &ProjectMetrics) -> Vec<CodeClone> {
+    let mut bodies: Vec<(&ModuleMetrics, &FunctionBody, Vec<u64>)> = metrics
+        .modules
+        .values()
+        .flat_map(|module| {
+            module
+                .connascence
+                .function_bodies
+                .iter()
+                .map(move |body| (module, body, shingles(&body.tokens)))
+        })
+        .collect();
+    bodies.sort_by_key(|(module, body, shingles)| (shingles.len(), &module.name, body.line));
+
+    let site = |module: &ModuleMetrics, body: &FunctionBody| CloneSite {
+        module: module.name.clone(),
+        function: body.qualified_name(),
+        location: CouplingLocation::new(module.path.clone(), body.line, body.column),
+    };
+    let mut clones = Vec::new();
+    for (i, (module_a, body_a, shingles_a)) in bodies.iter().enumerate() {
+        for (module_b, body_b, shingles_b) in &bodies[i + 1..] {
+            // Sorted by size: the similarity can only drop from here
+            if (shingles_a.len() as f64) < MIN_CLONE_SIMILARITY * shingles_b.len() as f64 {
+                break;
+            }
+            if module_a.path == module_b.path {
+                continue;
+            }
+            let similarity = jaccard(shingles_a, shingles_b);
+            if similarity < MIN_CLONE_SIMILARITY {
+                continue;
+            }
+            let (first, second) = if (&module_a.name, body_a.line) <= (&module_b.name, body_b.line)
+            {
+                ((module_a, body_a), (module_b, body_b))
+            } else {
+                ((module_b, body_b), (module_a, body_a))
+            };
+            clones.push(CodeClone {
+                first: site(first.0, first.1),
+                second: site(second.0, second.1),
+                similarity,
+                tokens: body_a.tokens.len().min(body_b.tokens.len()),
+                extraction_target: extraction_target(first.0, second.0),
+            });
+        }
+    }
+    clones.sort_by(|a, b| {
+        b.similarity
+            .total_cmp(&a.similarity)
+            .then_with(|| b.tokens.cmp(&a.tokens))
+            .then_with(|| a.first.function.cmp(&b.first.function))
+    });
+    clones
+}
+
+/// Closest module containing both modules, `None` across crates
+fn extraction_target(a: &ModuleMetrics, b: &ModuleMetrics) -> Option<String> {
+    if let (Some(crate_a), Some(crate_b)) = (&a.crate_name, &b.crate_name)
+        && crate_a != crate_b
+    {
+        return None;
+    }
+    let path_a = module_path_from_file(&a.path);
+    let path_b = module_path_from_file(&b.path);
+    let common: Vec<&str> = path_a
+        .split("::")
+        .zip(path_b.split("::"))
+        .take_while(|(x, y)| x == y && !x.is_empty())
+        .map(|(x, _)| x)
+        .collect();
+    let root = a.crate_name.as_deref().unwrap_or("crate");
+    Some(
+        std::iter::once(root)
+            .chain(common)
+            .collect::<Vec<_>>()
+            .join("::"),
+    )
+}
+
+/// Global state items referenced from modules other than their own
+///
+/// References are matched by name. Names defined in more than one module
+/// are skipped, since their users cannot be told apart.
+pub fn find_shared_state(metrics: &ProjectMetrics) -> Vec<SharedStateUse> {
+    let mut definitions: HashMap<&str, usize> = HashMap::new();
+    for module in metrics.modules.values() {
+        for item in &module.connascence.shared_state {
+            *definitions.entry(item.name.as_str()).or_default() += 1;
+        }
+    }
+
+    let mut shared = Vec::new();
+    for module in metrics.modules.values() {
+        for item in &module.connascence.shared_state {
+            if definitions[item.name.as_str()] > 1 {
+                continue;
+            }
+            let mut users: Vec<String> = metrics
+                .modules
+                .values()
+                .filter(|other| other.name != module.name)
+                .filter(|other| other.connascence.referenced_names.contains(&item.name))
+                .map(|other| other.name.clone())
+                .collect();
+            if users.is_empty() {
+                continue;
+            }
+            users.sort();
+            shared.push(SharedStateUse {
+                module: module.name.clone(),
+                name: item.name.clone(),
+                kind: item.kind,
+                users,
+                location: CouplingLocation::new(module.path.clone(), item.line, item.column),
+            });
+        }
+    }
+    shared.sort_by(|a, b| {
+        b.users
+            .len()
+            .cmp(&a.users.len())
+            .then_with(|| a.module.cmp(&b.module))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    shared
+}
+
+/// Functions each function calls, keyed by `(module, function)`
+fn call_graph(metrics: &ProjectMetrics) -> HashMap<(&str, &str), HashSet<&str>> {
+    let mut graph: HashMap<(&str, &str), HashSet<&str>> = HashMap::new();
+    for module in metrics.modules.values() {
+        for (caller, callee) in &module.connascence.calls {
+            graph
+                .entry((module.name.as_str(), caller.as_str()))
+                .or_default()
+                .insert(callee.as_str());
+        }
+    }
+    graph
+}
+
+/// Functions that must run after others, with their callers in the crate
+pub fn find_execution_orders(metrics: &ProjectMetrics) -> Vec<ExecutionOrder> {
+    let mut orders: Vec<ExecutionOrder> = Vec::new();
+    for module in metrics.modules.values() {
+        let location = |line, column| CouplingLocation::new(module.path.clone(), line, column);
+
+        // State checks: `send` checks `self.connected`, `connect` sets it
+        let mut seen: HashSet<(&str, &str)> = HashSet::new();
+        for check in &module.temporal.state_checks {
+            if !seen.insert((check.function.as_str(), check.field.as_str())) {
+                continue;
+            }
+            let mut prerequisites: Vec<String> = module
+                .temporal
+                .state_writes
+                .iter()
+                .filter(|w| {
+                    w.owner == check.owner && w.field == check.field && w.function != check.function
+                })
+                .map(|w| w.function.clone())
+                .collect();
+            prerequisites.sort();
+            prerequisites.dedup();
+            if prerequisites.is_empty() {
+                continue;
+            }
+            orders.push(ExecutionOrder {
+                module: module.name.clone(),
+                owner: check.owner.clone(),
+                prerequisites,
+                function: check.function.clone(),
+                evidence: ExecutionEvidence::StateCheck {
+                    field: check.field.clone(),
+                },
+                callers: Vec::new(),
+                unguarded_callers: Vec::new(),
+                location: location(check.line, check.column),
+            });
+        }
+
+        // Doc comments: "must be called after `connect`"
+        for hint in &module.connascence.ordering_hints {
+            let (first, then) = if hint.before {
+                (&hint.function, &hint.other)
+            } else {
+                (&hint.other, &hint.function)
+            };
+            if orders.iter().any(|o| {
+                o.module == module.name && &o.function == then && o.prerequisites.contains(first)
+            }) {
+                continue;
+            }
+            orders.push(ExecutionOrder {
+                module: module.name.clone(),
+                owner: hint.owner.clone(),
+                prerequisites: vec![first.clone()],
+                function: then.clone(),
+                evidence: ExecutionEvidence::Documented,
+                callers: Vec::new(),
+                unguarded_callers: Vec::new(),
+                location: location(hint.line, hint.column),
+            });
+        }
+    }
+
+    // Callers in the same crate, and whether they establish the order
+    let graph = call_graph(metrics);
+    let crate_of = |module: &str| {
+        metrics
+            .modules
+            .get(module)
+            .and_then(|m| m.crate_name.as_deref())
+    };
+    for order in &mut orders {
+        let home = crate_of(&order.module);
+        let mut callers: Vec<(&str, &str, bool)> = graph
+            .iter()
+            .filter(|((module, caller), callees)| {
+                callees.contains(order.function.as_str())
+                    && crate_of(module) == home
+                    && !(*module == order.module && order.prerequisites.iter().any(|p| p == caller))
+                    && *caller != order.function
+            })
+            .map(|((module, caller), callees)| {
+                let guarded = order
+                    .prerequisites
+                    .iter()
+                    .any(|p| callees.contains(p.as_str()));
+                (*module, *caller, guarded)
+            })
+            .collect();
+        callers.sort();
+        order.callers = callers
+            .iter()
+            .map(|(m, c, _)| format!("{}::{}", m, c))
+            .collect();
+        order.unguarded_callers = callers
+            .iter()
+            .filter(|(_, _, guarded)| !guarded)
+            .map(|(m, c, _)| format!("{}::{}", m, c))
+            .collect();
+    }
+
+    orders.sort_by(|a, b| {
+        b.foreign_unguarded_callers()
+            .cmp(&a.foreign_unguarded_callers())
+            .then_with(|| a.module.cmp(&b.module))
+            .then_with(|| a.function.cmp(&b.function))
+    });
+    orders
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::ModuleMetrics;
+    use crate::temporal::TemporalAnalyzer;
+    use std::path::PathBuf;
+
+    fn module(name: &str, code: &str) -> ModuleMetrics {
+        let mut module = ModuleMetrics::new(PathBuf::from(format!("src/{}.rs", name)), name.into());
+        module.connascence = ConnascenceAnalyzer::analyze_source(code).unwrap();
+        module.temporal = TemporalAnalyzer::analyze_source(code).unwrap();
+        module
+    }
+
+    #[test]
+    fn test_swappable_pairs() {
+        let metrics = ConnascenceAnalyzer::analyze_source(
+            "fn f(a: u32, b: u32, c: u32, d: &str) {} fn g(a: &str, b: String) {}",
+        )
+        .unwrap();
+        assert_eq!(metrics.signatures[0].swappable_pairs(), 3);
+        assert_eq!(metrics.signatures[1].swappable_pairs(), 0);
+    }
+
+    #[test]
+    fn test_positional_risk_across_modules() {
+        let mut project = ProjectMetrics::new();
+        project.add_module(module(
+            "geometry",
+            r#"
+            pub struct Rect;
+            impl Rect {
+                pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self { Rect }
+                pub fn scale(&self, fx: f64, fy: f64) {}
+            }
+            pub fn label(text: &str, color: u8) {}
+            pub fn offset(dx: i32, dy: i32) {}
+            "#,
+        ));
+        project.add_module(module(
+            "ui",
+            r#"
+            fn draw(w: u32, h: u32) {
+                let r = Rect::new(0, 0, w, h);
+                r.scale(2.0, 1.5);
+                label("hi", 3);
+                geometry::offset(w as i32, 1);
+            }
+            "#,
+        ));
+
+        let risks = find_positional_risks(&project);
+        assert_eq!(risks.len(), 3);
+
+        let new = &risks[0];
+        assert_eq!((new.module.as_str(), new.callee.as_str()), ("ui", "new"));
+        assert_eq!(new.caller.as_deref(), Some("draw"));
+        assert_eq!(new.callee_module, "geometry");
+        assert_eq!(new.shared_types, vec!["u32"]);
+        assert_eq!(new.swappable_pairs, 6);
+        assert_eq!(new.literal_args, 2);
+        assert_eq!(new.location.line, 3);
+
+        assert_eq!(risks[1].callee, "scale");
+        assert_eq!(risks[1].risk(), 3);
+        assert_eq!(risks[2].callee, "offset");
+        assert_eq!(risks[2].risk(), 2);
+    }
+
+    #[test]
+    fn test_ambiguous_callee_is_skipped() {
+        let mut project = ProjectMetrics::new();
+        project.add_module(module("a", "pub fn open(x: u8, y: u8) {}"));
+        project.add_module(module("b", "pub fn open(x: i64, y: i64) {}"));
+        project.add_module(module("c", "fn main() { open(1, 2); }"));
+        assert!(find_positional_risks(&project).is_empty());
+    }
+
+    #[test]
+    fn test_magic_numbers_skip_constants_indices_and_tests() {
+        let metrics = ConnascenceAnalyzer::analyze_source(
+            r#"
+            const LIMIT: usize = 4096;
+            enum Code { A = 7 }
+            fn run(buf: &[u8]) -> usize {
+                let first = buf[3];
+                let retries = 0x1F;
+                if buf.len() > 4096 { 1 } else { 0 }
+            }
+            #[cfg(test)]
+            mod tests {
+                fn helper() -> u32 { 42 }
+            }
+            "#,
+        )
+        .unwrap();
+
+        let values: Vec<(&str, Option<&str>)> = metrics
+            .magic_numbers
+            .iter()
+            .map(|m| (m.value.as_str(), m.function.as_deref()))
+            .collect();
+        assert_eq!(values, vec![("31", Some("run")), ("4096", Some("run"))]);
+        assert_eq!(metrics.magic_numbers[1].line, 7);
+    }
+
+    #[test]
+    fn test_shared_literals_span_modules() {
+        let mut project = ProjectMetrics::new();
+        project.add_module(module("server", "fn listen() { bind(8080); wait(30); }"));
+        project.add_module(module("client", "fn connect() { dial(8080); }"));
+        project.add_module(module("retry", "fn backoff() { sleep(30); sleep(30); }"));
+        project.add_module(module("local", "fn f() { g(99); g(99); }"));
+
+        let shared = find_shared_literals(&project);
+        assert_eq!(shared.len(), 2);
+        assert_eq!(shared[0].value, "30");
+        assert_eq!(shared[0].modules, vec!["retry", "server"]);
+        assert_eq!(shared[0].occurrences, 3);
+        assert_eq!(shared[1].value, "8080");
+        assert_eq!(
+            shared[1].location.file_path,
+            Some(PathBuf::from("src/client.rs"))
+        );
+    }
+
+    #[test]
+    fn test_shared_schema_spans_modules() {
+        let mut project = ProjectMetrics::new();
+        project.add_module(module(
+            "users",
+            r#"
+            const FIND: &str = "SELECT id, email FROM users WHERE id = $1";
+            fn rename(db: &Db) { db.execute("UPDATE users SET name = ? WHERE id = ?"); }
+            "#,
+        ));
+        project.add_module(module(
+            "billing",
+            r#"
+            fn owner(db: &Db, id: i64) {
+                sqlx::query!("SELECT u.email FROM invoices i JOIN users u ON u.id = i.user_id");
+                log("Delete from cache failed");
+            }
+            #[cfg(test)]
+            mod tests {
+                fn seed() { exec("INSERT INTO invoices (id) VALUES (1)"); }
+            }
+            "#,
+        ));
+        project.add_module(module(
+            "audit",
+            r#"fn log(db: &Db) { db.execute("insert into events (kind) values ('login')"); }"#,
+        ));
+
+        let queries = &project.modules["users"].connascence.query_references;
+        assert_eq!(queries.len(), 2);
+        assert_eq!(queries[1].function.as_deref(), Some("rename"));
+
+        // `invoices` is only queried outside tests in one module, `events` in one
+        let shared = find_shared_schema(&project);
+        assert_eq!(shared.len(), 1);
+        assert_eq!(shared[0].table, "users");
+        assert_eq!(shared[0].modules, vec!["billing", "users"]);
+        assert_eq!(shared[0].columns, vec!["email", "id"]);
+        assert_eq!(shared[0].occurrences, 3);
+    }
+
+    #[test]
+    fn test_code_clones_across_modules() {
+        let body = |name: &str, total: &str, item: &str| {
+            format!(
+                r#"
+                fn {name}(items: &[Item]) -> u64 {{
+                    let mut {total} = 0;
+                    for {item} in items {{
+                        if {item}.price > 100 && !{item}.discounted {{
+                            {total} += {item}.price * 9 / 10;
+                        }} else {{
+                            {total} += {item}.price;
+                        }}
+                    }}
+                    {total}
+                }}
+                "#
+            )
+        };
+        let mut project = ProjectMetrics::new();
+        project.add_module(module("shop/cart", &body("cart_total", "sum", "i")));
+        project.add_module(module(
+            "shop/invoice",
+            &body("invoice_total", "total", "line"),
+        ));
+        project.add_module(module(
+            "report",
+            r#"
+            fn summary(items: &[Item]) -> String {
+                let names: Vec<String> = items.iter().map(|i| i.name.clone()).collect();
+                let count = names.len();
+                format!("{} items: {}", count, names.join(", "))
+            }
+            "#,
+        ));
+        project.add_module(module("tiny", "fn f() { g(); }"));
+
+        // Short bodies are not recorded
+        assert!(
+            project.modules["tiny"]
+                .connascence
+                .function_bodies
+                .is_empty()
+        );
+
+        let clones = find_code_clones(&project);
+        assert_eq!(clones.len(), 1);
+        assert_eq!(clones[0].first.function, "cart_total");
+        assert_eq!(clones[0].second.function, "invoice_total");
+        assert_eq!(clones[0].similarity, 1.0);
+        assert_eq!(clones[0].extraction_target.as_deref(), Some("crate::shop"));
+    }
+
+    #[test]
+    fn test_normalized_tokens_keep_calls() {
+        let tokens = |code: &str| {
+            let mut out = Vec::new();
+            normalize_tokens(code.parse().unwrap(), &mut out);
+            out
+        };
+        assert_eq!(tokens("a.len() + 1"), tokens("b.len() + 2"));
+        assert_ne!(tokens("a.len()"), tokens("a.clear()"));
+        assert_ne!(tokens("Some(a)"), tokens("Ok(a)"));
+    }
+
+    #[test]
+    fn test_algorithm_uses_need_a_scheme() {
+        let metrics = ConnascenceAnalyzer::analyze_source(
+            r#"
+            fn save(data: &[u8], engine: Engine) {
+                let a = base64::encode(data);
+                let b = STANDARD.decode(a);
+                let c = engine.encode(data);
+                let d = encode(data);
+                let e = crate::util::Sha256::digest(data);
+            }
+            "#,
+        )
+        .unwrap();
+
+        let uses: Vec<(&str, AlgorithmRole)> = metrics
+            .algorithm_uses
+            .iter()
+            .map(|u| (u.scheme.as_str(), u.role))
+            .collect();
+        assert_eq!(
+            uses,
+            vec![
+                ("base64", AlgorithmRole::Encode),
+                ("STANDARD", AlgorithmRole::Decode),
+                ("util::Sha256", AlgorithmRole::Hash),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_algorithm_couplings_across_crates() {
+        let mut project = ProjectMetrics::new();
+        let mut writer = module("writer", "fn put(x: &[u8]) { bincode::serialize(x); }");
+        writer.crate_name = Some("store".into());
+        let mut reader = module("reader", "fn get(x: &[u8]) { bincode::deserialize(x); }");
+        reader.crate_name = Some("api".into());
+        let mut cache = module("cache", "fn load(x: &[u8]) { bincode::deserialize(x); }");
+        cache.crate_name = Some("store".into());
+        project.add_module(writer);
+        project.add_module(reader);
+        project.add_module(cache);
+        project
+            .crate_dependencies
+            .insert("api".into(), vec!["serde".into()]);
+
+        let couplings = find_algorithm_couplings(&project);
+        assert_eq!(couplings.len(), 2);
+
+        let cache = &couplings[0];
+        assert_eq!(
+            (cache.producer.as_str(), cache.consumer.as_str()),
+            ("writer", "cache")
+        );
+        assert!(!cache.crosses_crate());
+        assert!(cache.crates_linked);
+
+        let reader = &couplings[1];
+        assert_eq!(reader.family, "serialization");
+        assert!(reader.crosses_crate());
+        assert!(!reader.crates_linked);
+    }
+
+    #[test]
+    fn test_doc_ordering() {
+        let metrics = ConnascenceAnalyzer::analyze_source(
+            r#"
+            impl Conn {
+                /// Sends a frame. Must be called after `Conn::handshake()`.
+                pub fn send(&self) {}
+                /// Returns the value before `apply` was run.
+                pub fn previous(&self) {}
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(metrics.ordering_hints.len(), 1);
+        let hint = &metrics.ordering_hints[0];
+        assert_eq!(
+            (hint.function.as_str(), hint.other.as_str()),
+            ("send", "handshake")
+        );
+        assert!(!hint.before);
+        assert_eq!(hint.owner.as_deref(), Some("Conn"));
+    }
+
+    #[test]
+    fn test_execution_order_from_state_checks() {
+        let client = module(
+            "client",
+            r#"
+            impl Client {
+                pub fn connect(&mut self) { self.connected = true; }
+                pub fn send(&mut self) {
+                    assert!(self.connected);
+                }
+                pub fn reconnect(&mut self) { self.connect(); self.send(); }
+            }
+            "#,
+        );
+        let mut project = ProjectMetrics::new();
+        project.add_module(client);
+        project.add_module(module("app", "fn run(c: &mut Client) { c.send(); }"));
+
+        let orders = find_execution_orders(&project);
+        assert_eq!(orders.len(), 1);
+        let order = &orders[0];
+        assert_eq!(order.function, "send");
+        assert_eq!(order.prerequisites, vec!["connect"]);
+        assert_eq!(
+            order.evidence,
+            ExecutionEvidence::StateCheck {
+                field: "connected".into()
+            }
+        );
+        assert_eq!(order.callers, vec!["app::run", "client::reconnect"]);
+        assert_eq!(order.unguarded_callers, vec!["app::run"]);
+        assert_eq!(order.foreign_unguarded_callers(), 1);
+        assert!(ConnascenceType::Execution.is_dynamic());
+        assert!(!ConnascenceType::Algorithm.is_dynamic());
+    }
+
+    #[test]
+    fn test_shared_state_items() {
+        let metrics = ConnascenceAnalyzer::analyze_source(
+            r#"
+            static mut COUNTER: u32 = 0;
+            static NAME: &str = "app";
+            static CACHE: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+            static HITS: AtomicUsize = AtomicUsize::new(0);
+            type Registry = Arc<RwLock<HashMap<String, u32>>>;
+            type Name = Arc<str>;
+            lazy_static! {
+                static ref CONFIG: Config = Config::load();
+            }
+            "#,
+        )
+        .unwrap();
+
+        let items: Vec<(&str, SharedStateKind)> = metrics
+            .shared_state
+            .iter()
+            .map(|i| (i.name.as_str(), i.kind))
+            .collect();
+        assert_eq!(
+            items,
+            vec![
+                ("COUNTER", SharedStateKind::StaticMut),
+                ("CACHE", SharedStateKind::Static),
+                ("HITS", SharedStateKind::Static),
+                ("Registry", SharedStateKind::SharedPointer),
+                ("CONFIG", SharedStateKind::LazyStatic),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shared_state_users() {
+        let mut project = ProjectMetrics::new();
+        project.add_module(module("state", "pub static mut COUNTER: u32 = 0;"));
+        project.add_module(module(
+            "worker",
+            "use crate::state::COUNTER; fn tick() { unsafe { COUNTER += 1; } }",
+        ));
+        project.add_module(module(
+            "report",
+            "fn show() -> u32 { unsafe { state::COUNTER } }",
+        ));
+        project.add_module(module("a", "static mut LIMIT: u32 = 1;"));
+        project.add_module(module("b", "static LIMIT: AtomicU32 = AtomicU32::new(1);"));
+        project.add_module(module("c", "fn f() -> u32 { unsafe { LIMIT } }"));
+        project.add_module(module(
+            "tests_only",
+            "#[cfg(test)] mod tests { fn t() { COUNTER; } }",
+        ));
+
+        let shared = find_shared_state(&project);
+        assert_eq!(shared.len(), 1);
+        assert_eq!(shared[0].module, "state");
+        assert_eq!(shared[0].users, vec!["report", "worker"]);
+    }
+
+    #[test]
+    fn test_shared_signature_types() {
+        use crate::metrics::Visibility;
+
+        let mut project = ProjectMetrics::new();
+        let mut model = module("model", "pub fn order_count(order: &Order) -> usize { 0 }");
+        model.add_type_definition("Order".into(), Visibility::Public, false);
+        model.add_type_definition("Store".into(), Visibility::Public, true);
+        project.add_module(model);
+        project.add_module(module(
+            "billing",
+            "pub fn invoice(order: &Order) -> Invoice { todo!() }",
+        ));
+        project.add_module(module(
+            "shipping",
+            "pub struct Shipper; impl Shipper { pub fn ship(&self, orders: Vec<Order>) {} }",
+        ));
+        project.add_module(module(
+            "reports",
+            "pub fn totals(store: &dyn Store) -> Option<Order> { None }",
+        ));
+        // Private functions, trait impls and generic parameters do not count
+        project.add_module(module(
+            "audit",
+            "fn log(order: &Order) {} impl Display for Order {} pub fn any<Order>(o: Order) {}",
+        ));
+
+        let shared = find_shared_signature_types(&project);
+        assert_eq!(shared.len(), 1);
+        assert_eq!(shared[0].name, "Order");
+        assert_eq!(shared[0].module, "model");
+        assert_eq!(shared[0].users, vec!["billing", "reports", "shipping"]);
+        assert!(shared[0].user_crates.is_empty());
+
+        project.modules.remove("reports");
+        assert!(find_shared_signature_types(&project).is_empty());
+    }
+}
@@ -0,0 +1,321 @@
+//! Typed, serializable result of an analysis
+//!
+//! [`CouplingReport`] gathers what the individual analyzers found into one
+//! plain-data value: balance findings, temporal and connascence summaries,
+//! module depths (APOSD), the workspace layout and git volatility. Every
+//! part derives `Serialize` and `Deserialize`, so tools embedding the
+//! library can store, send or diff a report without calling the analyzers
+//! themselves.
+//!
+//! ```no_run
+//! use cargo_coupling::{CouplingReport, IssueThresholds, analyze_workspace};
+//!
+//! let metrics = analyze_workspace(std::path::Path::new("./src"))?;
+//! let report = CouplingReport::from_metrics(&metrics, &IssueThresholds::default());
+//! println!("{}", serde_json::to_string_pretty(&report)?);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::aposd::{
+    ModuleDepth, OverriddenDefault, TemporalDecomposition, TraitSurface,
+    analyze_module_depths_with, analyze_trait_surfaces, average_depth_ratio,
+    find_overridden_defaults, find_temporal_decompositions,
+};
+use crate::balance::{
+    CouplingIssue, IssueThresholds, Severity, analyze_project_balance_with_thresholds,
+};
+use crate::build_coupling::PhasedDependency;
+use crate::connascence::ConnascenceType;
+use crate::error_surface::{ErrorExposure, find_error_exposures};
+use crate::fingerprint::fingerprint;
+use crate::functions::{
+    COMPLEX_FUNCTION_LIMIT, ComplexFunction, FunctionDistribution, most_complex_functions,
+};
+use crate::metrics::{ProjectMetrics, SkippedFile, TestCodeMetrics};
+use crate::temporal::TemporalIssueKind;
+use crate::volatility::VolatilityStats;
+use crate::workspace::TargetDependency;
+
+/// Everything an analysis found, as plain data
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CouplingReport {
+    /// Version of cargo-coupling that produced the report
+    pub tool_version: String,
+    pub workspace: WorkspaceSummary,
+    pub balance: BalanceSummary,
+    /// Findings, most severe first
+    pub issues: Vec<IssueRecord>,
+    pub temporal: TemporalSummary,
+    pub connascence: ConnascenceSummary,
+    pub aposd: AposdSummary,
+    /// Change counts per file from git history (empty without git)
+    pub volatility: VolatilityStats,
+}
+
+/// Crates and files that were analyzed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceSummary {
+    /// Workspace name (if available from cargo metadata)
+    pub name: Option<String>,
+    /// Workspace member crate names
+    pub members: Vec<String>,
+    /// Crate name -> its dependencies
+    pub crate_dependencies: BTreeMap<String, Vec<String>>,
+    /// Crate name -> its dependencies that only apply on some platforms
+    pub target_dependencies: BTreeMap<String, Vec<TargetDependency>>,
+    /// Manifest dependencies by when they are used (runtime, build script,
+    /// proc macro, dev)
+    pub dependency_phases: Vec<PhasedDependency>,
+    pub files: usize,
+    pub modules: usize,
+    pub couplings: usize,
+    /// Test code kept out of the analysis
+    pub test_code: TestCodeMetrics,
+    /// Files that could not be parsed, with the parse error
+    pub skipped_files: Vec<SkippedFile>,
+}
+
+/// Overall coupling balance
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BalanceSummary {
+    pub average_score: f64,
+    /// Health grade letter
+    pub grade: String,
+    pub balanced: usize,
+    pub needs_review: usize,
+    pub needs_refactoring: usize,
+    pub issues_by_severity: BTreeMap<Severity, usize>,
+}
+
+/// A single finding
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IssueRecord {
+    /// Rule id (`category::name`)
+    pub rule: String,
+    /// Content-based id (see [`crate::fingerprint`])
+    #[serde(default)]
+    pub fingerprint: String,
+    pub severity: Severity,
+    pub source: String,
+    pub target: String,
+    pub description: String,
+    /// Suggested refactoring
+    pub refactoring: String,
+    /// File relative to the workspace root
+    pub file: Option<PathBuf>,
+    /// Rust module path of the file (`my_crate::net::client`)
+    #[serde(default)]
+    pub module_path: Option<String>,
+    /// 1-based line, 0 if unknown
+    pub line: usize,
+}
+
+impl IssueRecord {
+    pub(crate) fn from_issue(issue: &CouplingIssue, metrics: &ProjectMetrics) -> Self {
+        Self {
+            rule: issue.issue_type.rule_id().to_string(),
+            fingerprint: fingerprint(issue, metrics),
+            severity: issue.severity,
+            source: issue.source.clone(),
+            target: issue.target.clone(),
+            description: issue.description.clone(),
+            refactoring: issue.refactoring.to_string(),
+            file: issue
+                .file(metrics)
+                .map(|path| PathBuf::from(metrics.relative_path(path))),
+            module_path: issue.module(metrics).map(|m| m.rust_path()),
+            line: issue.location.line,
+        }
+    }
+}
+
+/// What the temporal analyzer found across all modules
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TemporalSummary {
+    pub unpaired_operations: usize,
+    pub dropped_guards: usize,
+    pub panicking_builders: usize,
+    /// Panics on state another method initializes
+    #[serde(default)]
+    pub panic_paths: usize,
+    /// `select!` branches that are not cancellation safe
+    #[serde(default)]
+    pub cancellation_unsafe_selects: usize,
+    /// Spawned tasks whose handle is dropped
+    #[serde(default)]
+    pub detached_spawns: usize,
+    /// Lock pairs acquired in opposite orders
+    #[serde(default)]
+    pub lock_order_inversions: usize,
+    /// Types tearing their fields down in a hand-written order
+    #[serde(default)]
+    pub drop_orders: usize,
+    /// Types with a `Drop` implementation
+    pub drop_impls: usize,
+    pub guard_bindings: usize,
+    pub unsafe_blocks: usize,
+    pub builders: usize,
+}
+
+/// Connascence findings by kind
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConnascenceSummary {
+    pub findings: BTreeMap<ConnascenceType, usize>,
+    /// Findings weighted by their Page-Jones rank (Name = 1 ... Identity = 9)
+    pub strength: usize,
+    /// Magic numbers in non-test code
+    pub magic_numbers: usize,
+    /// Global state items
+    pub shared_state: usize,
+}
+
+/// Module depth (APOSD)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AposdSummary {
+    pub average_depth_ratio: Option<f64>,
+    /// Modules with a public interface, by name
+    pub modules: Vec<ModuleDepth>,
+    /// Public traits with methods, costliest first
+    #[serde(default)]
+    pub traits: Vec<TraitSurface>,
+    /// False abstractions: trait default methods most implementors
+    /// override, most overridden first
+    #[serde(default)]
+    pub false_abstractions: Vec<OverriddenDefault>,
+    /// Sibling modules split by execution phase, most shared types first
+    #[serde(default)]
+    pub temporal_decompositions: Vec<TemporalDecomposition>,
+    /// Modules with fallible public functions, most error cases first
+    #[serde(default)]
+    pub errors: Vec<ErrorExposure>,
+    /// Length, nesting, branching and complexity of all functions
+    #[serde(default)]
+    pub function_shape: FunctionDistribution,
+    /// Functions with the highest cognitive complexity, most complex first
+    #[serde(default)]
+    pub complex_functions: Vec<ComplexFunction>,
+}
+
+impl CouplingReport {
+    /// Summarize an analysis; findings follow `thresholds` (strict mode,
+    /// rule selection, suppressions and baseline included)
+    pub fn from_metrics(metrics: &ProjectMetrics, thresholds: &IssueThresholds) -> Self {
+        let report = analyze_project_balance_with_thresholds(metrics, thresholds);
+        let depths = analyze_module_depths_with(metrics, &thresholds.aposd);
+
+        let mut temporal = TemporalSummary::default();
+        let mut connascence = ConnascenceSummary::default();
+        for module in metrics.modules.values() {
+            for issue in &module.temporal.issues {
+                match issue.kind {
+                    TemporalIssueKind::UnpairedOperation => temporal.unpaired_operations += 1,
+                    TemporalIssueKind::DroppedGuard => temporal.dropped_guards += 1,
+                    TemporalIssueKind::PanickingBuilder => temporal.panicking_builders += 1,
+                    TemporalIssueKind::PanicPath => temporal.panic_paths += 1,
+                    TemporalIssueKind::CancellationUnsafeSelect => {
+                        temporal.cancellation_unsafe_selects += 1
+                    }
+                    TemporalIssueKind::DetachedSpawn => temporal.detached_spawns += 1,
+                    TemporalIssueKind::LockOrderInversion => temporal.lock_order_inversions += 1,
+                    TemporalIssueKind::DropOrder => temporal.drop_orders += 1,
+                }
+            }
+            temporal.drop_impls += module.temporal.drop_impls.len();
+            temporal.guard_bindings += module.temporal.guard_bindings.len();
+            temporal.unsafe_blocks += module.temporal.unsafe_blocks.len();
+            temporal.builders += module.temporal.builders.len();
+            connascence.magic_numbers += module.connascence.magic_numbers.len();
+            connascence.shared_state += module.connascence.shared_state.len();
+        }
+        for kind in report
+            .issues
+            .iter()
+            .filter_map(|i| i.issue_type.connascence_type())
+        {
+            *connascence.findings.entry(kind).or_default() += 1;
+            connascence.strength += kind as usize + 1;
+        }
+
+        Self {
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            workspace: WorkspaceSummary {
+                name: metrics.workspace_name.clone(),
+                members: metrics.workspace_members.clone(),
+                crate_dependencies: metrics
+                    .crate_dependencies
+                    .iter()
+                    .map(|(name, deps)| (name.clone(), deps.clone()))
+                    .collect(),
+                target_dependencies: metrics
+                    .target_dependencies
+                    .iter()
+                    .map(|(name, deps)| (name.clone(), deps.clone()))
+                    .collect(),
+                dependency_phases: metrics.dependency_phases.clone(),
+                files: metrics.total_files,
+                modules: metrics.module_count(),
+                couplings: metrics.couplings.len(),
+                test_code: metrics.test_code(),
+                skipped_files: metrics.skipped_files.clone(),
+            },
+            balance: BalanceSummary {
+                average_score: report.average_score,
+                grade: format!("{:?}", report.health_grade),
+                balanced: report.balanced_count,
+                needs_review: report.needs_review,
+                needs_refactoring: report.needs_refactoring,
+                issues_by_severity: report.issues_by_severity.into_iter().collect(),
+            },
+            issues: report
+                .issues
+                .iter()
+                .map(|issue| IssueRecord::from_issue(issue, metrics))
+                .collect(),
+            temporal,
+            connascence,
+            aposd: AposdSummary {
+                average_depth_ratio: average_depth_ratio(&depths),
+                modules: depths,
+                traits: analyze_trait_surfaces(metrics, &thresholds.aposd),
+                false_abstractions: find_overridden_defaults(metrics),
+                temporal_decompositions: find_temporal_decompositions(metrics),
+                errors: find_error_exposures(metrics),
+                function_shape: FunctionDistribution::of_project(metrics),
+                complex_functions: most_complex_functions(metrics, COMPLEX_FUNCTION_LIMIT),
+            },
+            volatility: VolatilityStats::from_counts(metrics.file_changes.values().copied()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connascence::ConnascenceAnalyzer;
+    use crate::metrics::ModuleMetrics;
+
+    #[test]
+    fn test_report_round_trip() {
+        let mut metrics = ProjectMetrics::new();
+        let mut module = ModuleMetrics::new("src/calc.rs".into(), "calc".into());
+        module.connascence = ConnascenceAnalyzer::analyze_source(
+            "pub fn area(w: u32, h: u32) -> u32 { w * h }\nfn f() { area(3, 4); }",
+        )
+        .unwrap();
+        metrics.add_module(module);
+        metrics.file_changes.insert("src/calc.rs".to_string(), 4);
+
+        let report = CouplingReport::from_metrics(&metrics, &IssueThresholds::default());
+        assert_eq!(report.workspace.modules, 1);
+        assert_eq!(report.volatility.medium_volatility_count, 1);
+
+        let json = serde_json::to_string(&report).unwrap();
+        let parsed: CouplingReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, report);
+    }
+}
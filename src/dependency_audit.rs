@@ -0,0 +1,277 @@
+//! Unused and version-skewed dependencies
+//!
+//! Two kinds of manifest coupling that the code does not justify:
+//!
+//! - **Unused dependencies**: a normal or proc macro dependency of a
+//!   workspace member that none of its analyzed modules names, whether
+//!   through `use`, a qualified path, a macro or an attribute. It still
+//!   costs build time and ties the crate to the dependency's releases.
+//! - **Version skew**: workspace members depending on semver-incompatible
+//!   versions of the same crate (`1.x` and `2.x`, or `0.3` and `0.4`). Both
+//!   versions are compiled, their types do not mix across the members, and
+//!   upgrading one member drags the others along.
+//!
+//! Dependencies are matched by the name code uses for them, so renamed
+//! dependencies are handled. Build-dependencies (used by `build.rs`, which
+//! is not analyzed) and dev-dependencies (used by skipped test code) are
+//! never reported as unused, and neither are dependencies only named by
+//! `extern crate`. Version skew needs cargo metadata with a resolve graph.
+
+use std::collections::{BTreeMap, HashSet};
+use std::io::{self, Write};
+
+use crate::build_coupling::DependencyPhase;
+use crate::metrics::ProjectMetrics;
+
+/// A declared dependency no analyzed code refers to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnusedDependency {
+    /// Depending workspace crate
+    pub krate: String,
+    /// Dependency crate
+    pub dependency: String,
+    pub phase: DependencyPhase,
+}
+
+/// A crate the workspace depends on in incompatible versions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionSkew {
+    pub dependency: String,
+    /// Compatible version series (`1`, `0.4`) -> members and the exact
+    /// version they get
+    pub versions: BTreeMap<String, Vec<(String, String)>>,
+}
+
+/// Unused and version-skewed dependencies of the analyzed crates
+#[derive(Debug, Clone, Default)]
+pub struct DependencyAudit {
+    /// Sorted by crate and dependency
+    pub unused: Vec<UnusedDependency>,
+    /// Sorted by dependency
+    pub skew: Vec<VersionSkew>,
+}
+
+impl DependencyAudit {
+    /// Cross-reference the manifest dependencies with the analyzed code
+    pub fn build(metrics: &ProjectMetrics) -> Self {
+        // Crates with analyzed code -> first segments of every path they name
+        let mut named: BTreeMap<&str, HashSet<&str>> = BTreeMap::new();
+        for module in metrics.modules.values() {
+            let Some(krate) = module.crate_name.as_deref() else {
+                continue;
+            };
+            let segments = named.entry(krate).or_default();
+            segments.extend(
+                module
+                    .referenced_paths()
+                    .filter_map(|path| path.split("::").next()),
+            );
+            segments.extend(module.external_deps.iter().map(String::as_str));
+        }
+        for coupling in &metrics.couplings {
+            if let (Some(source), Some(target)) = (&coupling.source_crate, &coupling.target_crate)
+                && let Some(segments) = named.get_mut(source.as_str())
+            {
+                segments.insert(target);
+            }
+        }
+
+        let unused = metrics
+            .dependency_phases
+            .iter()
+            .filter(|d| {
+                matches!(
+                    d.phase,
+                    DependencyPhase::Runtime | DependencyPhase::ProcMacro
+                )
+            })
+            .filter(|d| {
+                named.get(d.source.as_str()).is_some_and(|segments| {
+                    !segments.contains(d.library.as_str()) && !segments.contains(d.target.as_str())
+                })
+            })
+            .map(|d| UnusedDependency {
+                krate: d.source.clone(),
+                dependency: d.target.clone(),
+                phase: d.phase,
+            })
+            .collect();
+
+        // Phases are sorted by source, so members come out sorted too
+        let mut series: BTreeMap<&str, VersionSkew> = BTreeMap::new();
+        for dependency in &metrics.dependency_phases {
+            let Some(version) = &dependency.version else {
+                continue;
+            };
+            if dependency.workspace {
+                continue;
+            }
+            let users = series
+                .entry(&dependency.target)
+                .or_insert_with(|| VersionSkew {
+                    dependency: dependency.target.clone(),
+                    versions: BTreeMap::new(),
+                })
+                .versions
+                .entry(compatible_series(version))
+                .or_default();
+            let user = (dependency.source.clone(), version.clone());
+            if !users.contains(&user) {
+                users.push(user);
+            }
+        }
+        let skew = series
+            .into_values()
+            .filter(|skew| skew.versions.len() > 1)
+            .collect();
+
+        Self { unused, skew }
+    }
+}
+
+/// Semver-compatible series of a version: the major version, or the first
+/// non-zero component and everything before it for `0.x` versions
+/// (`1.4.2` -> `1`, `0.4.1` -> `0.4`, `0.0.3` -> `0.0.3`)
+pub fn compatible_series(version: &str) -> String {
+    let core = version.split(['-', '+']).next().unwrap_or(version);
+    let parts: Vec<&str> = core.split('.').collect();
+    let significant = parts
+        .iter()
+        .position(|part| *part != "0")
+        .unwrap_or(parts.len() - 1);
+    parts[..=significant].join(".")
+}
+
+/// Generate the unused and duplicate dependency report to writer
+pub fn generate_dependency_audit_report<W: Write>(
+    audit: &DependencyAudit,
+    writer: &mut W,
+) -> io::Result<()> {
+    writeln!(writer, "Dependency Audit")?;
+    writeln!(writer, "================")?;
+    writeln!(writer)?;
+
+    if audit.unused.is_empty() && audit.skew.is_empty() {
+        writeln!(writer, "No unused or version-skewed dependencies found.")?;
+        return Ok(());
+    }
+
+    if !audit.unused.is_empty() {
+        writeln!(writer, "Declared but unused")?;
+        for unused in &audit.unused {
+            let kind = match unused.phase {
+                DependencyPhase::ProcMacro => " (proc macro)",
+                _ => "",
+            };
+            writeln!(
+                writer,
+                "  {} -> {}{}",
+                unused.krate, unused.dependency, kind
+            )?;
+        }
+        writeln!(writer)?;
+    }
+
+    if !audit.skew.is_empty() {
+        writeln!(writer, "Version skew")?;
+        for skew in &audit.skew {
+            writeln!(writer, "  {}", skew.dependency)?;
+            for users in skew.versions.values() {
+                let users: Vec<String> = users
+                    .iter()
+                    .map(|(krate, version)| format!("{} ({})", krate, version))
+                    .collect();
+                writeln!(writer, "    {}", users.join(", "))?;
+            }
+        }
+        writeln!(writer)?;
+    }
+
+    writeln!(
+        writer,
+        "{} unused dependencies, {} crates in several incompatible versions",
+        audit.unused.len(),
+        audit.skew.len()
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::build_coupling::PhasedDependency;
+    use crate::metrics::ModuleMetrics;
+    use std::path::PathBuf;
+
+    fn dependency(source: &str, target: &str, library: &str, version: &str) -> PhasedDependency {
+        PhasedDependency {
+            source: source.into(),
+            target: target.into(),
+            phase: DependencyPhase::Runtime,
+            workspace: false,
+            library: library.into(),
+            version: Some(version.into()),
+        }
+    }
+
+    #[test]
+    fn test_compatible_series() {
+        assert_eq!(compatible_series("1.4.2"), "1");
+        assert_eq!(compatible_series("0.4.1"), "0.4");
+        assert_eq!(compatible_series("0.0.3"), "0.0.3");
+        assert_eq!(compatible_series("2.0.0-rc.1"), "2");
+    }
+
+    #[test]
+    fn test_unused_and_skewed_dependencies() {
+        let mut metrics = ProjectMetrics::new();
+        metrics.dependency_phases = vec![
+            dependency("app", "serde-json", "serde_json", "1.0.100"),
+            dependency("app", "regex", "regex", "1.10.0"),
+            dependency("app", "tracing", "tracing", "0.1.40"),
+            // Renamed in Cargo.toml: `yaml = { package = "serde_yaml" }`
+            dependency("app", "serde_yaml", "yaml", "0.9.0"),
+            dependency("cli", "regex", "regex", "1.5.0"),
+            dependency("cli", "tracing", "tracing", "0.2.0"),
+            // Not analyzed: no modules
+            dependency("tools", "glob", "glob", "0.3.0"),
+        ];
+        metrics.dependency_phases.push(PhasedDependency {
+            phase: DependencyPhase::Dev,
+            ..dependency("app", "tempfile", "tempfile", "3.0.0")
+        });
+
+        let mut main = ModuleMetrics::new(PathBuf::from("src/main.rs"), "main".into());
+        main.crate_name = Some("app".into());
+        main.use_paths = vec!["serde_json::Value".into(), "yaml::from_str".into()];
+        main.macro_paths = vec!["tracing::info".into()];
+        let mut cli = ModuleMetrics::new(PathBuf::from("cli/src/lib.rs"), "cli".into());
+        cli.crate_name = Some("cli".into());
+        cli.use_paths = vec!["regex::Regex".into(), "tracing::Level".into()];
+        metrics.add_module(main);
+        metrics.add_module(cli);
+
+        let audit = DependencyAudit::build(&metrics);
+        assert_eq!(
+            audit.unused,
+            vec![UnusedDependency {
+                krate: "app".into(),
+                dependency: "regex".into(),
+                phase: DependencyPhase::Runtime,
+            }]
+        );
+        // regex 1.10 and 1.5 are compatible
+        assert_eq!(audit.skew.len(), 1);
+        assert_eq!(audit.skew[0].dependency, "tracing");
+        assert_eq!(
+            audit.skew[0].versions.keys().collect::<Vec<_>>(),
+            vec!["0.1", "0.2"]
+        );
+
+        let mut out = Vec::new();
+        generate_dependency_audit_report(&audit, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("  app -> regex\n"));
+        assert!(text.contains("    app (0.1.40)\n    cli (0.2.0)\n"));
+    }
+}
@@ -0,0 +1,267 @@
+//! How much of each external dependency a crate uses
+//!
+//! A dependency pulled in for a single function costs a compile-time,
+//! audit and semver burden out of proportion to what it provides; it is a
+//! candidate for removal or for vendoring the function. A dependency whose
+//! items are used all over a crate is deep coupling: replacing or upgrading
+//! it touches every one of those places.
+//!
+//! For every runtime dependency of a workspace member this module counts
+//! the distinct items the crate names: `use` imports (`serde_json::Value`,
+//! `rayon::prelude::*`), fully qualified calls (`serde_json::to_string(..)`),
+//! types in public signatures and qualified macros, attributes and derives
+//! (`tracing::debug!`, `#[tokio::main]`). Items are compared by path inside the
+//! dependency, so importing `Value` and later calling `serde_json::from_str`
+//! counts as two items. Dependencies the code never names are left out.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, Write};
+
+use crate::build_coupling::DependencyPhase;
+use crate::metrics::{ModuleMetrics, ProjectMetrics};
+
+/// Distinct items from which a dependency counts as heavily used
+pub const HEAVY_DEPENDENCY_ITEMS: usize = 10;
+
+/// Crates that make up the standard library
+const STD_CRATES: &[&str] = &["std", "core", "alloc"];
+
+/// How much a workspace crate uses one external dependency
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DependencyWeight {
+    /// Depending workspace crate
+    pub krate: String,
+    /// Dependency, as named in code (`serde_json`)
+    pub dependency: String,
+    /// Distinct items used, as paths inside the dependency (`Value`,
+    /// `prelude::*`)
+    pub items: BTreeSet<String>,
+    /// Modules of the crate naming the dependency
+    pub modules: BTreeSet<String>,
+}
+
+impl DependencyWeight {
+    /// Used for a single item: a candidate for removal or vendoring
+    pub fn is_single_use(&self) -> bool {
+        self.items.len() == 1
+    }
+
+    /// Used for many items: deep coupling to the dependency
+    pub fn is_heavy(&self) -> bool {
+        self.items.len() >= HEAVY_DEPENDENCY_ITEMS
+    }
+}
+
+/// Usage of every external dependency of the analyzed crates
+#[derive(Debug, Clone, Default)]
+pub struct DependencyWeightReport {
+    /// Sorted by crate, then most used dependency first
+    pub weights: Vec<DependencyWeight>,
+}
+
+impl DependencyWeightReport {
+    /// Count the items of each dependency named in the crates' code
+    pub fn build(metrics: &ProjectMetrics) -> Self {
+        let mut weights: BTreeMap<(String, String), DependencyWeight> = BTreeMap::new();
+        for module in metrics.modules.values() {
+            let Some(krate) = module.crate_name.as_deref() else {
+                continue;
+            };
+            let dependencies = runtime_dependencies(metrics, krate, module);
+            for path in module.referenced_paths() {
+                let Some((first, item)) = path.split_once("::") else {
+                    continue;
+                };
+                if !dependencies.contains(first) {
+                    continue;
+                }
+                let weight = weights
+                    .entry((krate.to_string(), first.to_string()))
+                    .or_insert_with(|| DependencyWeight {
+                        krate: krate.to_string(),
+                        dependency: first.to_string(),
+                        ..DependencyWeight::default()
+                    });
+                weight.items.insert(item.to_string());
+                weight.modules.insert(module.name.clone());
+            }
+        }
+
+        let mut weights: Vec<DependencyWeight> = weights.into_values().collect();
+        weights.sort_by(|a, b| {
+            a.krate
+                .cmp(&b.krate)
+                .then_with(|| b.items.len().cmp(&a.items.len()))
+                .then_with(|| a.dependency.cmp(&b.dependency))
+        });
+        Self { weights }
+    }
+
+    /// Dependencies used for a single item
+    pub fn single_use(&self) -> impl Iterator<Item = &DependencyWeight> {
+        self.weights.iter().filter(|w| w.is_single_use())
+    }
+
+    /// Dependencies used for many items
+    pub fn heavy(&self) -> impl Iterator<Item = &DependencyWeight> {
+        self.weights.iter().filter(|w| w.is_heavy())
+    }
+}
+
+/// External runtime dependencies of `krate`, as named in code
+///
+/// Without cargo metadata every crate the module imports counts.
+fn runtime_dependencies(
+    metrics: &ProjectMetrics,
+    krate: &str,
+    module: &ModuleMetrics,
+) -> BTreeSet<String> {
+    let declared: BTreeSet<String> = metrics
+        .dependency_phases
+        .iter()
+        .filter(|d| d.source == krate && !d.workspace && d.phase == DependencyPhase::Runtime)
+        .map(|d| d.library.clone())
+        .collect();
+    if !declared.is_empty() || metrics.dependency_phases.iter().any(|d| d.source == krate) {
+        return declared;
+    }
+    let is_member = |name: &str| {
+        metrics
+            .workspace_members
+            .iter()
+            .any(|m| m.replace('-', "_") == name)
+    };
+    module
+        .external_deps
+        .iter()
+        .filter(|d| !STD_CRATES.contains(&d.as_str()) && !is_member(d))
+        .cloned()
+        .collect()
+}
+
+/// Generate the dependency weight report to writer
+pub fn generate_dependency_weight_report<W: Write>(
+    report: &DependencyWeightReport,
+    writer: &mut W,
+) -> io::Result<()> {
+    writeln!(writer, "Dependency Weight")?;
+    writeln!(writer, "=================")?;
+    writeln!(writer)?;
+
+    if report.weights.is_empty() {
+        writeln!(writer, "No external dependencies referenced from code.")?;
+        return Ok(());
+    }
+
+    let labels: Vec<String> = report
+        .weights
+        .iter()
+        .map(|w| format!("{} -> {}", w.krate, w.dependency))
+        .collect();
+    let width = labels
+        .iter()
+        .map(String::len)
+        .max()
+        .unwrap_or(0)
+        .max("Dependency".len());
+    writeln!(
+        writer,
+        "{:<width$}  {:>5}  {:>7}  Note",
+        "Dependency", "Items", "Modules"
+    )?;
+    writeln!(writer, "{}", "-".repeat(width + 32))?;
+    for (label, weight) in labels.iter().zip(&report.weights) {
+        let note = if weight.is_single_use() {
+            format!(
+                "single use ({}): remove or vendor?",
+                weight.items.first().map_or("", String::as_str)
+            )
+        } else if weight.is_heavy() {
+            "deep coupling".to_string()
+        } else {
+            String::new()
+        };
+        let line = format!(
+            "{:<width$}  {:>5}  {:>7}  {}",
+            label,
+            weight.items.len(),
+            weight.modules.len(),
+            note
+        );
+        writeln!(writer, "{}", line.trim_end())?;
+    }
+
+    writeln!(writer)?;
+    writeln!(
+        writer,
+        "{} dependencies, {} used for a single item, {} used for {}+ items",
+        report.weights.len(),
+        report.single_use().count(),
+        report.heavy().count(),
+        HEAVY_DEPENDENCY_ITEMS
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::build_coupling::PhasedDependency;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_dependency_weights() {
+        let mut metrics = ProjectMetrics::new();
+        metrics.workspace_members = vec!["app".into(), "my-core".into()];
+        for (target, phase, workspace) in [
+            ("serde-json", DependencyPhase::Runtime, false),
+            ("syn", DependencyPhase::Runtime, false),
+            ("my-core", DependencyPhase::Runtime, true),
+            ("tempfile", DependencyPhase::Dev, false),
+        ] {
+            metrics.dependency_phases.push(PhasedDependency {
+                source: "app".into(),
+                target: target.into(),
+                phase,
+                workspace,
+                library: target.replace('-', "_"),
+                version: None,
+            });
+        }
+
+        let mut main = ModuleMetrics::new(PathBuf::from("src/main.rs"), "main".into());
+        main.crate_name = Some("app".into());
+        main.use_paths = vec![
+            "serde_json::Value".into(),
+            "my_core::Engine".into(),
+            "tempfile::tempdir".into(),
+        ];
+        main.use_paths
+            .extend((0..HEAVY_DEPENDENCY_ITEMS).map(|i| format!("syn::Item{}", i)));
+        let mut cli = ModuleMetrics::new(PathBuf::from("src/cli.rs"), "cli".into());
+        cli.crate_name = Some("app".into());
+        cli.use_paths = vec!["serde_json::Value".into()];
+        metrics.add_module(main);
+        metrics.add_module(cli);
+
+        let report = DependencyWeightReport::build(&metrics);
+        let summary: Vec<(&str, usize, usize)> = report
+            .weights
+            .iter()
+            .map(|w| (w.dependency.as_str(), w.items.len(), w.modules.len()))
+            .collect();
+        // Workspace members and dev-dependencies are not weighed
+        assert_eq!(
+            summary,
+            vec![("syn", HEAVY_DEPENDENCY_ITEMS, 1), ("serde_json", 1, 2)]
+        );
+        assert!(report.weights[0].is_heavy());
+        assert!(report.weights[1].is_single_use());
+
+        let mut out = Vec::new();
+        generate_dependency_weight_report(&report, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("single use (Value): remove or vendor?"));
+        assert!(text.contains("2 dependencies, 1 used for a single item, 1 used for 10+ items"));
+    }
+}
@@ -0,0 +1,386 @@
+//! Diff mode: compare coupling between two git revisions
+//!
+//! Each revision is exported with `git archive` into a temporary directory
+//! and analyzed on its own, so the working tree is never touched. The two
+//! results are then compared:
+//!
+//! - modules that became deeper or shallower (depth ratio, see `aposd`)
+//! - coupling issues that appeared or disappeared (matched like baseline
+//!   entries: by rule, source and target, ignoring line numbers)
+//! - the overall balance score trend
+
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use thiserror::Error;
+
+use crate::analyzer::{AnalysisScope, AnalyzerError, analyze_workspace_scoped};
+use crate::aposd::analyze_module_depths_with;
+use crate::balance::{
+    CouplingIssue, HealthGrade, IssueThresholds, analyze_project_balance_with_thresholds,
+};
+use crate::baseline::Baseline;
+use crate::metrics::ProjectMetrics;
+use crate::temporal::{TemporalRules, reanalyze_project};
+
+/// Smallest depth ratio change reported as deeper/shallower
+pub const MIN_DEPTH_CHANGE: f64 = 0.5;
+
+/// Errors that can occur while comparing revisions
+#[derive(Error, Debug)]
+pub enum DiffError {
+    #[error("Failed to execute git command: {0}")]
+    GitCommand(#[from] std::io::Error),
+
+    #[error("git {0} failed: {1}")]
+    GitFailed(String, String),
+
+    #[error("Failed to analyze revision {0}: {1}")]
+    Analysis(String, AnalyzerError),
+}
+
+/// Depth ratio of a module before and after (`None` if absent)
+#[derive(Debug, Clone)]
+pub struct DepthChange {
+    pub module: String,
+    pub before: Option<f64>,
+    pub after: Option<f64>,
+}
+
+impl DepthChange {
+    /// Change in depth ratio (positive = deeper)
+    pub fn delta(&self) -> f64 {
+        self.after.unwrap_or(0.0) - self.before.unwrap_or(0.0)
+    }
+}
+
+/// Result of comparing two revisions
+#[derive(Debug, Clone)]
+pub struct RevisionDiff {
+    pub from: String,
+    pub to: String,
+    pub score_before: f64,
+    pub score_after: f64,
+    pub grade_before: HealthGrade,
+    pub grade_after: HealthGrade,
+    /// Modules whose depth ratio changed noticeably, most changed first
+    pub depth_changes: Vec<DepthChange>,
+    /// Issues present in `to` but not in `from`
+    pub new_issues: Vec<CouplingIssue>,
+    /// Issues present in `from` but not in `to`
+    pub resolved_issues: Vec<CouplingIssue>,
+}
+
+impl RevisionDiff {
+    /// Change in average balance score (positive = better)
+    pub fn score_delta(&self) -> f64 {
+        self.score_after - self.score_before
+    }
+
+    /// One-word summary of the overall direction: the balance score decides,
+    /// issue counts break ties
+    pub fn trend(&self) -> &'static str {
+        let delta = self.score_delta();
+        let issue_delta = self.resolved_issues.len() as isize - self.new_issues.len() as isize;
+        if delta > 0.005 || (delta >= -0.005 && issue_delta > 0) {
+            "improved"
+        } else if delta < -0.005 || issue_delta < 0 {
+            "regressed"
+        } else {
+            "unchanged"
+        }
+    }
+}
+
+/// Run git in `dir` and return its trimmed stdout
+pub(crate) fn git(dir: &Path, args: &[&str]) -> Result<String, DiffError> {
+    let _span = tracing::debug_span!("git", args = %args.join(" ")).entered();
+    let output = Command::new("git").args(args).current_dir(dir).output()?;
+    if !output.status.success() {
+        return Err(DiffError::GitFailed(
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Export a revision of the repository into `dest` with `git archive`
+pub fn export_revision(repo_root: &Path, rev: &str, dest: &Path) -> Result<(), DiffError> {
+    let _span = tracing::debug_span!("git", args = %format!("archive {}", rev)).entered();
+    let mut archive = Command::new("git")
+        .args(["archive", "--format=tar", rev])
+        .current_dir(repo_root)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let tar_input = archive
+        .stdout
+        .take()
+        .map(Stdio::from)
+        .unwrap_or(Stdio::null());
+    let tar = Command::new("tar")
+        .args(["-x", "-C"])
+        .arg(dest)
+        .stdin(tar_input)
+        .output()?;
+    let archive = archive.wait_with_output()?;
+
+    if !archive.status.success() {
+        return Err(DiffError::GitFailed(
+            format!("archive {}", rev),
+            String::from_utf8_lossy(&archive.stderr).trim().to_string(),
+        ));
+    }
+    if !tar.status.success() {
+        return Err(DiffError::GitFailed(
+            format!("archive {} | tar", rev),
+            String::from_utf8_lossy(&tar.stderr).trim().to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Analyze `path` (inside a git repository) as it was at `rev`
+pub fn analyze_revision(path: &Path, rev: &str) -> Result<ProjectMetrics, DiffError> {
+    analyze_revision_with(path, rev, &AnalysisScope::default(), None)
+}
+
+/// Analyze the files in `scope` of `path` at `rev`, re-running the temporal
+/// analysis with custom rules while the exported files still exist
+pub fn analyze_revision_with(
+    path: &Path,
+    rev: &str,
+    scope: &AnalysisScope,
+    rules: Option<&TemporalRules>,
+) -> Result<ProjectMetrics, DiffError> {
+    let dir = if path.is_dir() {
+        path
+    } else {
+        path.parent().unwrap_or(Path::new("."))
+    };
+    let root = PathBuf::from(git(dir, &["rev-parse", "--show-toplevel"])?);
+    let prefix = git(dir, &["rev-parse", "--show-prefix"])?;
+    let relative = match path.file_name() {
+        Some(name) if !path.is_dir() => Path::new(&prefix).join(name),
+        _ => PathBuf::from(&prefix),
+    };
+
+    let checkout = tempfile::tempdir()?;
+    export_revision(&root, rev, checkout.path())?;
+    let mut metrics = analyze_workspace_scoped(&checkout.path().join(relative), scope)
+        .map_err(|e| DiffError::Analysis(rev.to_string(), e))?;
+    if let Some(rules) = rules {
+        reanalyze_project(&mut metrics, rules, scope.include_tests);
+    }
+    Ok(metrics)
+}
+
+/// Compare the analysis results of two revisions
+pub fn compare_metrics(
+    from: &str,
+    before: &ProjectMetrics,
+    to: &str,
+    after: &ProjectMetrics,
+    thresholds: &IssueThresholds,
+) -> RevisionDiff {
+    let report_before = analyze_project_balance_with_thresholds(before, thresholds);
+    let report_after = analyze_project_balance_with_thresholds(after, thresholds);
+
+    let mut new_issues = report_after.issues.clone();
+    Baseline::from_issues(&report_before.issues, before).retain_new(&mut new_issues, after);
+    let mut resolved_issues = report_before.issues.clone();
+    Baseline::from_issues(&report_after.issues, after).retain_new(&mut resolved_issues, before);
+
+    let depths_before = analyze_module_depths_with(before, &thresholds.aposd);
+    let depths_after = analyze_module_depths_with(after, &thresholds.aposd);
+    let mut modules: Vec<&str> = depths_before
+        .iter()
+        .chain(&depths_after)
+        .map(|d| d.module.as_str())
+        .collect();
+    modules.sort();
+    modules.dedup();
+
+    let ratio = |depths: &[crate::aposd::ModuleDepth], module: &str| {
+        depths
+            .iter()
+            .find(|d| d.module == module)
+            .map(|d| d.depth_ratio)
+    };
+    let mut depth_changes: Vec<DepthChange> = modules
+        .into_iter()
+        .map(|module| DepthChange {
+            module: module.to_string(),
+            before: ratio(&depths_before, module),
+            after: ratio(&depths_after, module),
+        })
+        .filter(|c| match (c.before, c.after) {
+            (Some(_), Some(_)) => c.delta().abs() >= MIN_DEPTH_CHANGE,
+            _ => true,
+        })
+        .collect();
+    depth_changes.sort_by(|a, b| {
+        b.delta()
+            .abs()
+            .partial_cmp(&a.delta().abs())
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.module.cmp(&b.module))
+    });
+
+    RevisionDiff {
+        from: from.to_string(),
+        to: to.to_string(),
+        score_before: report_before.average_score,
+        score_after: report_after.average_score,
+        grade_before: report_before.health_grade,
+        grade_after: report_after.health_grade,
+        depth_changes,
+        new_issues,
+        resolved_issues,
+    }
+}
+
+fn format_ratio(ratio: Option<f64>) -> String {
+    ratio.map_or_else(|| "-".to_string(), |r| format!("{:.1}", r))
+}
+
+/// Generate the diff report to writer
+pub fn generate_diff_report<W: Write>(diff: &RevisionDiff, writer: &mut W) -> io::Result<()> {
+    writeln!(writer, "Coupling Diff: {} → {}", diff.from, diff.to)?;
+    writeln!(
+        writer,
+        "==============={}",
+        "=".repeat(diff.from.len() + diff.to.len() + 3)
+    )?;
+    writeln!(writer)?;
+    writeln!(
+        writer,
+        "Trend: {} (balance score {:.2} → {:.2}, {:+.2})",
+        diff.trend(),
+        diff.score_before,
+        diff.score_after,
+        diff.score_delta()
+    )?;
+    writeln!(
+        writer,
+        "Grade: {} → {}",
+        diff.grade_before, diff.grade_after
+    )?;
+    writeln!(
+        writer,
+        "Issues: {} new, {} resolved",
+        diff.new_issues.len(),
+        diff.resolved_issues.len()
+    )?;
+
+    if !diff.depth_changes.is_empty() {
+        writeln!(writer)?;
+        writeln!(writer, "Module Depth Changes")?;
+        writeln!(writer, "--------------------")?;
+        for change in &diff.depth_changes {
+            let label = match (change.before, change.after) {
+                (None, Some(_)) => "added",
+                (Some(_), None) => "removed",
+                _ if change.delta() > 0.0 => "deeper",
+                _ => "shallower",
+            };
+            writeln!(
+                writer,
+                "  {:<10} {} ({} → {})",
+                label,
+                change.module,
+                format_ratio(change.before),
+                format_ratio(change.after)
+            )?;
+        }
+    }
+
+    for (title, issues) in [
+        ("New Issues", &diff.new_issues),
+        ("Resolved Issues", &diff.resolved_issues),
+    ] {
+        if issues.is_empty() {
+            continue;
+        }
+        writeln!(writer)?;
+        writeln!(writer, "{}", title)?;
+        writeln!(writer, "{}", "-".repeat(title.len()))?;
+        for issue in issues {
+            writeln!(
+                writer,
+                "  [{}] {}: {} → {}",
+                issue.severity, issue.issue_type, issue.source, issue.target
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connascence::ConnascenceAnalyzer;
+    use crate::metrics::{ModuleMetrics, Visibility};
+
+    fn module(name: &str, loc: usize) -> ModuleMetrics {
+        let mut module = ModuleMetrics::new(PathBuf::from(format!("src/{}.rs", name)), name.into());
+        module.add_function_definition_full(
+            "run".to_string(),
+            Visibility::Public,
+            1,
+            0,
+            Vec::new(),
+        );
+        module.lines_of_code = loc;
+        module
+    }
+
+    #[test]
+    fn test_compare_metrics_depth_and_issues() {
+        let mut before = ProjectMetrics::new();
+        before.add_module(module("core", 4));
+        before.add_module(module("old", 10));
+
+        let mut after = ProjectMetrics::new();
+        // `new` introduces global state that `core` reaches into
+        let mut core = module("core", 40);
+        core.connascence = ConnascenceAnalyzer::analyze_source("fn f() { STATE; }").unwrap();
+        let mut new = module("new", 10);
+        new.connascence = ConnascenceAnalyzer::analyze_source("static mut STATE: u8 = 3;").unwrap();
+        after.add_module(core);
+        after.add_module(new);
+
+        let diff = compare_metrics("main", &before, "HEAD", &after, &IssueThresholds::default());
+
+        let changes: Vec<(&str, f64)> = diff
+            .depth_changes
+            .iter()
+            .map(|c| (c.module.as_str(), c.delta()))
+            .collect();
+        assert_eq!(changes, vec![("core", 18.0), ("new", 5.0), ("old", -5.0)]);
+        assert!(diff.resolved_issues.is_empty());
+        assert_eq!(diff.new_issues.len(), 1);
+        assert_eq!(diff.new_issues[0].source, "new::STATE");
+        assert_eq!(diff.trend(), "regressed");
+    }
+
+    #[test]
+    fn test_report_lists_changes() {
+        let mut before = ProjectMetrics::new();
+        before.add_module(module("core", 4));
+        let mut after = ProjectMetrics::new();
+        after.add_module(module("core", 40));
+
+        let diff = compare_metrics("v1", &before, "v2", &after, &IssueThresholds::default());
+        let mut out = Vec::new();
+        generate_diff_report(&diff, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+
+        assert!(text.starts_with("Coupling Diff: v1 → v2"));
+        assert!(text.contains("deeper     core (2.0 → 20.0)"));
+        assert!(text.contains("Issues: 0 new, 0 resolved"));
+    }
+}
@@ -0,0 +1,205 @@
+//! Documentation coverage of public items
+//!
+//! Ousterhout lists a *non-obvious* interface among the red flags of
+//! "A Philosophy of Software Design": when a module exposes a lot, callers
+//! need comments to use it without reading its implementation. We count,
+//! per file, the items visible outside their module (any `pub`
+//! visibility): functions, structs, enums, unions, traits, type aliases,
+//! constants and statics, `pub` methods of inherent impls and the methods
+//! of visible traits. An item is documented when it has a doc comment
+//! (`///` or `#[doc = ".."]`); `#[doc(hidden)]` items are not interface and
+//! left out. Items inside function bodies are not counted.
+//!
+//! [`crate::aposd`] combines the coverage with interface complexity: a
+//! large interface with few doc comments is reported as a non-obvious
+//! interface.
+
+use syn::visit::Visit;
+use syn::{
+    Attribute, ImplItem, ItemConst, ItemEnum, ItemFn, ItemImpl, ItemStatic, ItemStruct, ItemTrait,
+    ItemType, ItemUnion, Meta, TraitItem, Type, Visibility,
+};
+
+/// Documented and undocumented public items of one file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DocCoverage {
+    pub public_items: usize,
+    pub documented: usize,
+    /// Names of the undocumented items (`Type::method` for methods), in
+    /// source order
+    pub undocumented: Vec<String>,
+}
+
+impl DocCoverage {
+    /// Share of documented public items; `None` without public items
+    pub fn ratio(&self) -> Option<f64> {
+        (self.public_items > 0).then(|| self.documented as f64 / self.public_items as f64)
+    }
+}
+
+/// Collects [`DocCoverage`] per file
+#[derive(Debug, Default)]
+pub struct DocAnalyzer {
+    coverage: DocCoverage,
+}
+
+impl DocAnalyzer {
+    /// Analyze a parsed file
+    pub fn analyze(file: &syn::File) -> DocCoverage {
+        let mut analyzer = Self::default();
+        analyzer.visit_file(file);
+        analyzer.coverage
+    }
+
+    fn item(&mut self, name: impl ToString, vis: &Visibility, attrs: &[Attribute]) {
+        if matches!(vis, Visibility::Inherited) {
+            return;
+        }
+        self.member(name, attrs);
+    }
+
+    /// Count an item whose visibility was checked by the caller
+    fn member(&mut self, name: impl ToString, attrs: &[Attribute]) {
+        if is_doc_hidden(attrs) {
+            return;
+        }
+        self.coverage.public_items += 1;
+        if has_doc(attrs) {
+            self.coverage.documented += 1;
+        } else {
+            self.coverage.undocumented.push(name.to_string());
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for DocAnalyzer {
+    // Items in function bodies are not interface, so bodies are not visited
+    fn visit_item_fn(&mut self, node: &'ast ItemFn) {
+        self.item(&node.sig.ident, &node.vis, &node.attrs);
+    }
+
+    fn visit_item_struct(&mut self, node: &'ast ItemStruct) {
+        self.item(&node.ident, &node.vis, &node.attrs);
+    }
+
+    fn visit_item_enum(&mut self, node: &'ast ItemEnum) {
+        self.item(&node.ident, &node.vis, &node.attrs);
+    }
+
+    fn visit_item_union(&mut self, node: &'ast ItemUnion) {
+        self.item(&node.ident, &node.vis, &node.attrs);
+    }
+
+    fn visit_item_type(&mut self, node: &'ast ItemType) {
+        self.item(&node.ident, &node.vis, &node.attrs);
+    }
+
+    fn visit_item_const(&mut self, node: &'ast ItemConst) {
+        self.item(&node.ident, &node.vis, &node.attrs);
+    }
+
+    fn visit_item_static(&mut self, node: &'ast ItemStatic) {
+        self.item(&node.ident, &node.vis, &node.attrs);
+    }
+
+    fn visit_item_trait(&mut self, node: &'ast ItemTrait) {
+        if matches!(node.vis, Visibility::Inherited) || is_doc_hidden(&node.attrs) {
+            return;
+        }
+        self.member(&node.ident, &node.attrs);
+        for item in &node.items {
+            if let TraitItem::Fn(method) = item {
+                self.member(
+                    format!("{}::{}", node.ident, method.sig.ident),
+                    &method.attrs,
+                );
+            }
+        }
+    }
+
+    fn visit_item_impl(&mut self, node: &'ast ItemImpl) {
+        // Trait impl methods are documented on the trait
+        if node.trait_.is_some() {
+            return;
+        }
+        let Type::Path(type_path) = &*node.self_ty else {
+            return;
+        };
+        let Some(owner) = type_path.path.segments.last().map(|s| &s.ident) else {
+            return;
+        };
+        for item in &node.items {
+            if let ImplItem::Fn(method) = item {
+                self.item(
+                    format!("{}::{}", owner, method.sig.ident),
+                    &method.vis,
+                    &method.attrs,
+                );
+            }
+        }
+    }
+}
+
+fn has_doc(attrs: &[Attribute]) -> bool {
+    attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("doc") && matches!(attr.meta, Meta::NameValue(_)))
+}
+
+fn is_doc_hidden(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path().is_ident("doc")
+            && matches!(&attr.meta, Meta::List(list) if list.tokens.to_string() == "hidden")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_doc_coverage() {
+        let code = r#"
+/// Parse the input
+pub fn parse() {
+    pub fn nested() {}
+}
+pub fn undocumented() {}
+fn private() {}
+
+/// A parser
+pub struct Parser;
+
+impl Parser {
+    /// Create a parser
+    pub fn new() -> Self { Parser }
+    pub fn run(&self) {}
+    fn helper(&self) {}
+}
+
+impl Default for Parser {
+    fn default() -> Self { Parser }
+}
+
+pub(crate) trait Visitor {
+    /// Visit a node
+    fn visit(&self);
+    fn leave(&self) {}
+}
+
+#[doc(hidden)]
+pub const INTERNAL: u8 = 1;
+#[doc = "Maximum depth"]
+pub const MAX_DEPTH: usize = 8;
+"#;
+        let coverage = DocAnalyzer::analyze(&syn::parse_file(code).unwrap());
+        assert_eq!(coverage.public_items, 9);
+        assert_eq!(coverage.documented, 5);
+        assert_eq!(
+            coverage.undocumented,
+            vec!["undocumented", "Parser::run", "Visitor", "Visitor::leave"]
+        );
+        assert_eq!(coverage.ratio(), Some(5.0 / 9.0));
+        assert_eq!(DocCoverage::default().ratio(), None);
+    }
+}
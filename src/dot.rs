@@ -0,0 +1,251 @@
+//! Graphviz DOT export
+//!
+//! Writes the workspace crate dependency graph as a DOT digraph that can be
+//! rendered with `dot -Tsvg`. Edges are annotated with the number of
+//! code-level couplings between the two crates and colored by the strongest
+//! integration strength; nodes are filled by the highest volatility of the
+//! couplings that target them.
+//!
+//! Single-crate projects have no interesting crate graph, so the internal
+//! module graph is written instead.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, Write};
+
+use crate::metrics::{Distance, IntegrationStrength, ProjectMetrics, Volatility};
+
+/// Aggregated couplings between two nodes
+#[derive(Debug, Default)]
+struct EdgeStats {
+    count: usize,
+    strength: Option<IntegrationStrength>,
+}
+
+impl EdgeStats {
+    fn add(&mut self, strength: IntegrationStrength) {
+        self.count += 1;
+        if self.strength.is_none_or(|s| strength.value() > s.value()) {
+            self.strength = Some(strength);
+        }
+    }
+}
+
+/// Edge color for an integration strength
+fn strength_color(strength: Option<IntegrationStrength>) -> &'static str {
+    match strength {
+        Some(IntegrationStrength::Intrusive) => "#d73a49",
+        Some(IntegrationStrength::Functional) => "#f66a0a",
+        Some(IntegrationStrength::Model) => "#dbab09",
+        Some(IntegrationStrength::Contract) => "#28a745",
+        None => "#959da5",
+    }
+}
+
+/// Node fill color for a volatility level
+fn volatility_color(volatility: Option<Volatility>) -> &'static str {
+    match volatility {
+        Some(Volatility::High) => "#f9d0c4",
+        Some(Volatility::Medium) => "#fef2c0",
+        Some(Volatility::Low) | None => "#ffffff",
+    }
+}
+
+/// Quote a string as a DOT identifier
+fn quote(id: &str) -> String {
+    format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Generate a Graphviz DOT graph to writer
+pub fn generate_dot_output<W: Write>(metrics: &ProjectMetrics, writer: &mut W) -> io::Result<()> {
+    let members: BTreeSet<&str> = metrics
+        .workspace_members
+        .iter()
+        .map(String::as_str)
+        .collect();
+
+    let mut nodes: BTreeSet<String> = BTreeSet::new();
+    let mut edges: BTreeMap<(String, String), EdgeStats> = BTreeMap::new();
+    let mut volatility: BTreeMap<String, Volatility> = BTreeMap::new();
+
+    let crate_level = members.len() > 1;
+    if crate_level {
+        nodes.extend(members.iter().map(|m| m.to_string()));
+
+        // Manifest dependencies between workspace members
+        for (krate, deps) in &metrics.crate_dependencies {
+            for dep in deps {
+                if members.contains(dep.as_str()) && dep != krate {
+                    edges.entry((krate.clone(), dep.clone())).or_default();
+                }
+            }
+        }
+    }
+
+    for coupling in &metrics.couplings {
+        let (source, target) = if crate_level {
+            match (&coupling.source_crate, &coupling.target_crate) {
+                (Some(s), Some(t))
+                    if s != t && members.contains(s.as_str()) && members.contains(t.as_str()) =>
+                {
+                    (s.clone(), t.clone())
+                }
+                _ => continue,
+            }
+        } else {
+            // Glob imports (`module::*`) do not name a real module
+            if coupling.distance == Distance::DifferentCrate
+                || coupling.source == coupling.target
+                || coupling.target.ends_with('*')
+            {
+                continue;
+            }
+            nodes.insert(coupling.source.clone());
+            nodes.insert(coupling.target.clone());
+            (coupling.source.clone(), coupling.target.clone())
+        };
+
+        let level = volatility.entry(target.clone()).or_insert(Volatility::Low);
+        if coupling.volatility.value() > level.value() {
+            *level = coupling.volatility;
+        }
+        edges
+            .entry((source, target))
+            .or_default()
+            .add(coupling.strength);
+    }
+
+    let name = if crate_level { "crates" } else { "modules" };
+    writeln!(writer, "digraph {} {{", name)?;
+    writeln!(writer, "    rankdir=LR;")?;
+    writeln!(
+        writer,
+        "    node [shape=box, style=\"rounded,filled\", fontname=\"Helvetica\"];"
+    )?;
+    writeln!(writer, "    edge [fontname=\"Helvetica\", fontsize=10];")?;
+    writeln!(writer)?;
+
+    for node in &nodes {
+        writeln!(
+            writer,
+            "    {} [fillcolor=\"{}\"];",
+            quote(node),
+            volatility_color(volatility.get(node).copied())
+        )?;
+    }
+    writeln!(writer)?;
+
+    for ((source, target), stats) in &edges {
+        let width = 1.0 + (stats.count as f64).ln_1p();
+        if stats.count == 0 {
+            writeln!(
+                writer,
+                "    {} -> {} [color=\"{}\", style=dashed];",
+                quote(source),
+                quote(target),
+                strength_color(None)
+            )?;
+        } else {
+            writeln!(
+                writer,
+                "    {} -> {} [color=\"{}\", penwidth={:.1}, label=\"{}\"];",
+                quote(source),
+                quote(target),
+                strength_color(stats.strength),
+                width,
+                stats.count
+            )?;
+        }
+    }
+
+    writeln!(writer, "}}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::CouplingMetrics;
+
+    fn coupling(
+        source_crate: &str,
+        target_crate: &str,
+        strength: IntegrationStrength,
+        volatility: Volatility,
+    ) -> CouplingMetrics {
+        let mut c = CouplingMetrics::new(
+            format!("{}::a", source_crate),
+            format!("{}::b", target_crate),
+            strength,
+            Distance::DifferentCrate,
+            volatility,
+        );
+        c.source_crate = Some(source_crate.to_string());
+        c.target_crate = Some(target_crate.to_string());
+        c
+    }
+
+    #[test]
+    fn test_crate_graph() {
+        let mut metrics = ProjectMetrics::new();
+        metrics.workspace_members = vec!["app".into(), "core".into(), "cli".into()];
+        metrics
+            .crate_dependencies
+            .insert("app".into(), vec!["core".into(), "serde".into()]);
+        metrics
+            .crate_dependencies
+            .insert("cli".into(), vec!["core".into()]);
+        metrics.add_coupling(coupling(
+            "app",
+            "core",
+            IntegrationStrength::Model,
+            Volatility::Low,
+        ));
+        metrics.add_coupling(coupling(
+            "app",
+            "core",
+            IntegrationStrength::Intrusive,
+            Volatility::High,
+        ));
+
+        let mut out = Vec::new();
+        generate_dot_output(&metrics, &mut out).unwrap();
+        let dot = String::from_utf8(out).unwrap();
+
+        assert!(dot.starts_with("digraph crates {"));
+        assert!(dot.contains("\"app\" -> \"core\" [color=\"#d73a49\""));
+        assert!(dot.contains("label=\"2\""));
+        assert!(dot.contains("\"cli\" -> \"core\" [color=\"#959da5\", style=dashed]"));
+        assert!(dot.contains("\"core\" [fillcolor=\"#f9d0c4\"]"));
+        assert!(!dot.contains("serde"));
+    }
+
+    #[test]
+    fn test_module_graph_for_single_crate() {
+        let mut metrics = ProjectMetrics::new();
+        metrics.add_coupling(CouplingMetrics::new(
+            "my\"mod".into(),
+            "other".into(),
+            IntegrationStrength::Contract,
+            Distance::DifferentModule,
+            Volatility::Low,
+        ));
+
+        let mut out = Vec::new();
+        generate_dot_output(&metrics, &mut out).unwrap();
+        let dot = String::from_utf8(out).unwrap();
+
+        assert!(dot.starts_with("digraph modules {"));
+        assert!(dot.contains("\"my\\\"mod\" -> \"other\""));
+    }
+
+    #[test]
+    fn test_crate_graph_from_analyzed_workspace() {
+        let dir = crate::test_fixtures::two_lib_workspace();
+        let metrics = crate::analyzer::analyze_workspace(dir.path()).unwrap();
+
+        let mut out = Vec::new();
+        generate_dot_output(&metrics, &mut out).unwrap();
+        let dot = String::from_utf8(out).unwrap();
+        assert!(dot.contains("\"app\" -> \"corelib\" [color="));
+        assert!(!dot.contains("style=dashed"));
+    }
+}
@@ -0,0 +1,149 @@
+//! Volatility-weighted coupling per dependency edge
+//!
+//! Strength alone does not say what a dependency costs. A strong coupling to
+//! a stable component is cheap because it rarely forces a change; the same
+//! coupling to a component that changes every week keeps pulling its
+//! dependents along (Khononov: *pain = strength × volatility*).
+//!
+//! Couplings are grouped into edges between a source module and a target
+//! module (or external crate). Each coupling contributes its integration
+//! strength (0.25 - 1.0), multiplied by `1 + volatility` (×1 for stable,
+//! ×1.5 for medium and ×2 for highly volatile targets); the edge risk is the
+//! sum.
+
+use std::collections::HashMap;
+
+use crate::metrics::{CouplingMetrics, Distance, ProjectMetrics, Volatility};
+
+/// Number of edges listed in the main report
+pub const EDGE_REPORT_LIMIT: usize = 10;
+
+/// A dependency edge with its volatility-weighted coupling
+#[derive(Debug, Clone, PartialEq)]
+pub struct EdgeRisk {
+    /// Source module
+    pub source: String,
+    /// Target module, or crate for external dependencies
+    pub target: String,
+    /// Number of couplings along the edge
+    pub couplings: usize,
+    /// Sum of the couplings' integration strength
+    pub strength: f64,
+    /// Highest volatility among the targets
+    pub volatility: Volatility,
+    /// Sum of strength × (1 + volatility)
+    pub risk: f64,
+}
+
+/// Risk of one coupling
+pub fn coupling_risk(coupling: &CouplingMetrics) -> f64 {
+    coupling.strength.value() * (1.0 + coupling.volatility.value())
+}
+
+/// Every dependency edge between different modules, riskiest first
+pub fn compute_edge_risks(metrics: &ProjectMetrics) -> Vec<EdgeRisk> {
+    let mut edges: HashMap<(String, String), EdgeRisk> = HashMap::new();
+    for coupling in &metrics.couplings {
+        let source = metrics
+            .find_module(&coupling.source)
+            .map_or_else(|| coupling.source.clone(), |m| m.name.clone());
+        let target = if coupling.distance == Distance::DifferentCrate {
+            coupling.target_crate.clone().unwrap_or_else(|| {
+                coupling
+                    .target
+                    .split("::")
+                    .next()
+                    .unwrap_or(&coupling.target)
+                    .to_string()
+            })
+        } else {
+            metrics
+                .find_module(&coupling.target)
+                .map_or_else(|| coupling.target.clone(), |m| m.name.clone())
+        };
+        if source == target {
+            continue;
+        }
+
+        let edge = edges
+            .entry((source.clone(), target.clone()))
+            .or_insert_with(|| EdgeRisk {
+                source,
+                target,
+                couplings: 0,
+                strength: 0.0,
+                volatility: Volatility::Low,
+                risk: 0.0,
+            });
+        edge.couplings += 1;
+        edge.strength += coupling.strength.value();
+        if coupling.volatility.value() > edge.volatility.value() {
+            edge.volatility = coupling.volatility;
+        }
+        edge.risk += coupling_risk(coupling);
+    }
+
+    let mut edges: Vec<EdgeRisk> = edges.into_values().collect();
+    edges.sort_by(|a, b| {
+        b.risk
+            .total_cmp(&a.risk)
+            .then_with(|| (&a.source, &a.target).cmp(&(&b.source, &b.target)))
+    });
+    edges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{IntegrationStrength, ModuleMetrics};
+
+    #[test]
+    fn test_volatility_weighted_edges() {
+        let mut metrics = ProjectMetrics::new();
+        for name in ["app", "engine", "config"] {
+            metrics.add_module(ModuleMetrics::new(
+                format!("src/{name}.rs").into(),
+                name.into(),
+            ));
+        }
+        for (target, strength, volatility) in [
+            (
+                "crate::engine::Engine",
+                IntegrationStrength::Functional,
+                Volatility::High,
+            ),
+            (
+                "crate::engine::run",
+                IntegrationStrength::Contract,
+                Volatility::High,
+            ),
+            (
+                "crate::config::Config",
+                IntegrationStrength::Intrusive,
+                Volatility::Low,
+            ),
+            (
+                "crate::app::main",
+                IntegrationStrength::Functional,
+                Volatility::High,
+            ),
+        ] {
+            metrics.add_coupling(CouplingMetrics::new(
+                "crate::app".to_string(),
+                target.to_string(),
+                strength,
+                Distance::DifferentModule,
+                volatility,
+            ));
+        }
+
+        let edges = compute_edge_risks(&metrics);
+        let summary: Vec<(&str, usize, f64)> = edges
+            .iter()
+            .map(|e| (e.target.as_str(), e.couplings, e.risk))
+            .collect();
+        // engine: (0.75 + 0.25) × 2; config: 1.0 × 1; app -> app is skipped
+        assert_eq!(summary, vec![("engine", 2, 2.0), ("config", 1, 1.0)]);
+        assert_eq!(edges[0].volatility, Volatility::High);
+    }
+}
@@ -0,0 +1,211 @@
+//! Edition-aware parsing
+//!
+//! `syn` parses a single superset of Rust syntax, whatever the edition.
+//! That covers let-chains, async closures, `unsafe extern` blocks and most
+//! other newer syntax, but two edition differences make it reject valid
+//! code:
+//!
+//! - Rust 2024 `gen` blocks (`gen { yield x }`, `gen move`, `async gen`),
+//!   which `syn` does not know yet
+//! - Rust 2015 code using `async`, `await` or `try` as plain identifiers
+//!
+//! [`parse_file`] parses the source as is first. Only when that fails does
+//! it rewrite those tokens for the crate's edition and try again: `gen`
+//! blocks become `async` blocks (the closest construct `syn` understands)
+//! and 2015 keywords-to-be become raw identifiers. Rewrites never add or
+//! remove lines, so line numbers stay correct.
+
+use std::fmt;
+
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use serde::{Deserialize, Serialize};
+
+/// Rust edition of a crate
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum Edition {
+    #[serde(rename = "2015")]
+    E2015,
+    #[serde(rename = "2018")]
+    E2018,
+    #[serde(rename = "2021")]
+    E2021,
+    /// Also used when the edition is unknown (no cargo metadata)
+    #[default]
+    #[serde(rename = "2024")]
+    E2024,
+}
+
+impl From<cargo_metadata::Edition> for Edition {
+    fn from(edition: cargo_metadata::Edition) -> Self {
+        match edition {
+            cargo_metadata::Edition::E2015 => Edition::E2015,
+            cargo_metadata::Edition::E2018 => Edition::E2018,
+            cargo_metadata::Edition::E2021 => Edition::E2021,
+            // 2024 and future editions
+            _ => Edition::E2024,
+        }
+    }
+}
+
+impl fmt::Display for Edition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let year = match self {
+            Edition::E2015 => "2015",
+            Edition::E2018 => "2018",
+            Edition::E2021 => "2021",
+            Edition::E2024 => "2024",
+        };
+        f.write_str(year)
+    }
+}
+
+/// Parse a source file written for `edition`
+///
+/// On failure the error refers to the original source, not a rewrite.
+pub fn parse_file(content: &str, edition: Edition) -> syn::Result<syn::File> {
+    let error = match syn::parse_file(content) {
+        Ok(file) => return Ok(file),
+        Err(e) => e,
+    };
+    let Some(rewritten) = rewrite_for_edition(content, edition) else {
+        return Err(error);
+    };
+    let file = syn::parse_file(&rewritten).map_err(|_| error)?;
+    tracing::debug!(%edition, "parsed after rewriting edition-specific syntax");
+    Ok(file)
+}
+
+/// Replacement for a byte range of the source
+struct Edit {
+    start: usize,
+    end: usize,
+    text: &'static str,
+}
+
+/// Source rewritten into syntax `syn` accepts, or `None` if nothing applies
+fn rewrite_for_edition(content: &str, edition: Edition) -> Option<String> {
+    let tokens: TokenStream = content.parse().ok()?;
+    let lines = LineIndex::new(content);
+    let mut edits = Vec::new();
+    collect_edits(tokens, edition, &lines, &mut edits);
+    if edits.is_empty() {
+        return None;
+    }
+
+    edits.sort_by_key(|edit| edit.start);
+    let mut rewritten = String::with_capacity(content.len() + edits.len() * 2);
+    let mut copied = 0;
+    for edit in edits {
+        rewritten.push_str(&content[copied..edit.start]);
+        rewritten.push_str(edit.text);
+        copied = edit.end;
+    }
+    rewritten.push_str(&content[copied..]);
+    Some(rewritten)
+}
+
+fn collect_edits(tokens: TokenStream, edition: Edition, lines: &LineIndex, edits: &mut Vec<Edit>) {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    for (i, token) in tokens.iter().enumerate() {
+        let ident = match token {
+            TokenTree::Group(group) => {
+                collect_edits(group.stream(), edition, lines, edits);
+                continue;
+            }
+            TokenTree::Ident(ident) => ident,
+            _ => continue,
+        };
+        let text = if edition >= Edition::E2024 && *ident == "gen" {
+            let starts_block = match tokens.get(i + 1) {
+                Some(TokenTree::Group(group)) => group.delimiter() == Delimiter::Brace,
+                Some(TokenTree::Ident(next)) => *next == "move",
+                _ => false,
+            };
+            if !starts_block {
+                continue;
+            }
+            // `async gen { .. }` is an async block once `gen` is gone
+            match i.checked_sub(1).and_then(|j| tokens.get(j)) {
+                Some(TokenTree::Ident(previous)) if *previous == "async" => "   ",
+                _ => "async",
+            }
+        } else if edition == Edition::E2015 {
+            // Keywords since 2018
+            match ident.to_string().as_str() {
+                "async" => "r#async",
+                "await" => "r#await",
+                "try" => "r#try",
+                _ => continue,
+            }
+        } else {
+            continue;
+        };
+
+        let span = ident.span();
+        if let (Some(start), Some(end)) = (lines.offset(span.start()), lines.offset(span.end())) {
+            edits.push(Edit { start, end, text });
+        }
+    }
+}
+
+/// Maps token positions (line, character column) to byte offsets
+struct LineIndex<'a> {
+    content: &'a str,
+    /// Byte offset of the start of each line
+    starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    fn new(content: &'a str) -> Self {
+        let starts = std::iter::once(0)
+            .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self { content, starts }
+    }
+
+    fn offset(&self, position: proc_macro2::LineColumn) -> Option<usize> {
+        let start = *self.starts.get(position.line.checked_sub(1)?)?;
+        let line = &self.content[start..];
+        line.char_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(line.len()))
+            .nth(position.column)
+            .map(|i| start + i)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gen_blocks_parse_in_2024() {
+        let source = "fn numbers() -> impl Iterator<Item = u32> {\n    gen { yield 1; }.into_iter()\n}\n\
+                      fn both() { let a = gen move { yield 2; }; let b = async gen { yield 3; }; }\n\
+                      fn ident() -> u32 { let gen = 1; gen }\n";
+        assert!(syn::parse_file(source).is_err());
+        let file = parse_file(source, Edition::E2024).unwrap();
+        assert_eq!(file.items.len(), 3);
+
+        // Only 2024 reserves `gen`
+        assert!(parse_file(source, Edition::E2021).is_err());
+
+        let Err(error) = parse_file("fn f() {\n    gen { let = ; }\n}\n", Edition::E2024) else {
+            panic!("invalid source parsed");
+        };
+        assert_eq!(error.span().start().line, 2);
+    }
+
+    #[test]
+    fn test_2015_keywords_as_identifiers() {
+        let source = "fn async(try: u8) -> u8 { try }\nfn main() { async(1); }\n";
+        assert!(parse_file(source, Edition::E2015).is_ok());
+        assert!(parse_file(source, Edition::E2018).is_err());
+        assert_eq!(
+            rewrite_for_edition("fn async() {}", Edition::E2015).unwrap(),
+            "fn r#async() {}"
+        );
+    }
+}
@@ -0,0 +1,395 @@
+//! Error cases exposed by public functions
+//!
+//! Ousterhout's advice on exceptions is to *define errors out of
+//! existence*: every error a function can return is one more case each
+//! caller has to think about. We record, per file, the public functions
+//! and methods (and the methods of visible traits) returning a `Result`,
+//! with the error type in their signature. `Result<T>` aliases are resolved
+//! through a `type Result<T> = Result<T, E>` in the same file or crate;
+//! `io::Result<T>` and `fmt::Result` name `io::Error` and `fmt::Error`.
+//!
+//! Across the project an error enum of the same crate counts one case per
+//! variant, any other error type (a struct, `Box<dyn Error>`, a foreign
+//! error) counts one. A module exposing many cases through its public
+//! functions makes callers match on fine-grained errors they can rarely
+//! handle differently.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use syn::visit::Visit;
+use syn::{
+    GenericArgument, ImplItem, ItemFn, ItemImpl, ItemTrait, ItemType, PathArguments, ReturnType,
+    Signature, TraitItem, Type, Visibility,
+};
+
+use crate::analyzer::span_position;
+use crate::metrics::{CouplingLocation, ProjectMetrics};
+
+/// Default number of error cases from which a module's public functions
+/// expose too many
+pub const MAX_ERROR_CASES: usize = 10;
+
+/// A public function returning `Result`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FallibleFunction {
+    /// Function name (`Type::method` for methods)
+    pub name: String,
+    /// Error type as written (`ParseError`, `io::Error`); `None` for a
+    /// `Result<T>` alias that could not be resolved
+    pub error: Option<String>,
+    /// Error cases of the error type (filled in by [`find_error_exposures`])
+    #[serde(default)]
+    pub cases: usize,
+    pub line: usize,
+}
+
+/// Fallible public functions of one file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorSurface {
+    pub functions: Vec<FallibleFunction>,
+    /// Error type of a `type Result<T> = Result<T, E>` alias in the file
+    pub result_alias: Option<String>,
+}
+
+/// Collects the [`ErrorSurface`] of a file
+#[derive(Debug, Default)]
+pub struct ErrorAnalyzer {
+    surface: ErrorSurface,
+}
+
+impl ErrorAnalyzer {
+    /// Analyze a parsed file
+    pub fn analyze(file: &syn::File) -> ErrorSurface {
+        let mut analyzer = Self::default();
+        analyzer.visit_file(file);
+        let alias = analyzer.surface.result_alias.clone();
+        for function in &mut analyzer.surface.functions {
+            if function.error.is_none() {
+                function.error = alias.clone();
+            }
+        }
+        analyzer.surface
+    }
+
+    fn function(&mut self, name: String, sig: &Signature) {
+        let ReturnType::Type(_, ty) = &sig.output else {
+            return;
+        };
+        let Some(error) = result_error(ty) else {
+            return;
+        };
+        self.surface.functions.push(FallibleFunction {
+            name,
+            error,
+            cases: 0,
+            line: span_position(sig.ident.span()).0,
+        });
+    }
+}
+
+impl<'ast> Visit<'ast> for ErrorAnalyzer {
+    // Functions in bodies are not interface, so bodies are not visited
+    fn visit_item_fn(&mut self, node: &'ast ItemFn) {
+        if !matches!(node.vis, Visibility::Inherited) {
+            self.function(node.sig.ident.to_string(), &node.sig);
+        }
+    }
+
+    fn visit_item_trait(&mut self, node: &'ast ItemTrait) {
+        if matches!(node.vis, Visibility::Inherited) {
+            return;
+        }
+        for item in &node.items {
+            if let TraitItem::Fn(method) = item {
+                self.function(format!("{}::{}", node.ident, method.sig.ident), &method.sig);
+            }
+        }
+    }
+
+    fn visit_item_impl(&mut self, node: &'ast ItemImpl) {
+        // Trait impl methods are counted on the trait
+        if node.trait_.is_some() {
+            return;
+        }
+        let Type::Path(type_path) = &*node.self_ty else {
+            return;
+        };
+        let Some(owner) = type_path.path.segments.last().map(|s| &s.ident) else {
+            return;
+        };
+        for item in &node.items {
+            if let ImplItem::Fn(method) = item
+                && !matches!(method.vis, Visibility::Inherited)
+            {
+                self.function(format!("{}::{}", owner, method.sig.ident), &method.sig);
+            }
+        }
+    }
+
+    fn visit_item_type(&mut self, node: &'ast ItemType) {
+        if node.ident == "Result"
+            && let Some(Some(error)) = result_error(&node.ty)
+        {
+            self.surface.result_alias = Some(error);
+        }
+    }
+}
+
+/// Error type of a `Result` type: `Some(None)` for an unresolved
+/// `Result<T>` alias, `None` if `ty` is not a `Result`
+fn result_error(ty: &Type) -> Option<Option<String>> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segments = &path.path.segments;
+    let last = segments.last()?;
+    if last.ident != "Result" {
+        return None;
+    }
+    let qualifier = segments
+        .iter()
+        .rev()
+        .nth(1)
+        .map(|s| s.ident.to_string())
+        .filter(|q| {
+            !matches!(
+                q.as_str(),
+                "std" | "core" | "result" | "crate" | "self" | "super"
+            )
+        });
+    let args: Vec<&Type> = match &last.arguments {
+        PathArguments::AngleBracketed(args) => args
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    Some(match (args.get(1), qualifier) {
+        (Some(error), _) => Some(type_name(error)),
+        (None, Some(qualifier)) => Some(format!("{}::Error", qualifier)),
+        (None, None) => None,
+    })
+}
+
+/// A type as written without extra spaces (`Box<dyn Error>`); paths into
+/// the crate (`crate::error::ParseError`) are shortened to the type name
+fn type_name(ty: &Type) -> String {
+    let name = ty
+        .to_token_stream()
+        .to_string()
+        .replace(" :: ", "::")
+        .replace(" <", "<")
+        .replace("< ", "<")
+        .replace(" >", ">");
+    if ["crate::", "self::", "super::"]
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+    {
+        let base = name.split('<').next().unwrap_or(&name);
+        return base.rsplit("::").next().unwrap_or(base).to_string();
+    }
+    name
+}
+
+/// An error type exposed by a module, with its error cases
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExposedError {
+    pub name: String,
+    /// Variants of an error enum of the same crate, 1 for other types
+    pub cases: usize,
+}
+
+/// The errors a module's public functions return
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorExposure {
+    pub module: String,
+    /// Workspace crate of the module (workspace analysis only)
+    pub krate: Option<String>,
+    /// Public functions returning `Result`, in source order
+    pub functions: Vec<FallibleFunction>,
+    /// Distinct error types of those functions, by name
+    pub errors: Vec<ExposedError>,
+    #[serde(skip)]
+    pub location: CouplingLocation,
+}
+
+impl ErrorExposure {
+    /// Error cases callers of the module may have to handle
+    pub fn error_cases(&self) -> usize {
+        self.errors.iter().map(|e| e.cases).sum()
+    }
+}
+
+/// Modules with fallible public functions, most error cases first
+///
+/// Error enums are looked up by name in the crate of the module; qualified
+/// names (`io::Error`) and names defined in several modules of a crate
+/// count one case.
+pub fn find_error_exposures(metrics: &ProjectMetrics) -> Vec<ErrorExposure> {
+    // Variants of the enums of each crate; `None` for ambiguous names
+    let mut enums: HashMap<(Option<&str>, &str), Option<usize>> = HashMap::new();
+    // `Result` alias errors of each crate
+    let mut aliases: HashMap<Option<&str>, BTreeSet<&str>> = HashMap::new();
+    for module in metrics.modules.values() {
+        let krate = module.crate_name.as_deref();
+        for def in module.type_definitions.values().filter(|d| d.variants > 0) {
+            enums
+                .entry((krate, def.name.as_str()))
+                .and_modify(|variants| *variants = None)
+                .or_insert(Some(def.variants));
+        }
+        if let Some(alias) = &module.error_surface.result_alias {
+            aliases.entry(krate).or_default().insert(alias);
+        }
+    }
+
+    let mut found = Vec::new();
+    for module in metrics.modules.values() {
+        if module.error_surface.functions.is_empty() {
+            continue;
+        }
+        let krate = module.crate_name.as_deref();
+        // A crate-wide `Result<T>` alias, if there is only one
+        let crate_alias = aliases
+            .get(&krate)
+            .filter(|a| a.len() == 1)
+            .and_then(|a| a.first().copied());
+
+        let mut errors: BTreeMap<String, usize> = BTreeMap::new();
+        let functions = module
+            .error_surface
+            .functions
+            .iter()
+            .map(|function| {
+                let error = function
+                    .error
+                    .clone()
+                    .or_else(|| crate_alias.map(str::to_string));
+                let cases = error.as_deref().map_or(0, |name| {
+                    enums.get(&(krate, name)).copied().flatten().unwrap_or(1)
+                });
+                if let Some(name) = &error {
+                    errors.insert(name.clone(), cases);
+                }
+                FallibleFunction {
+                    name: function.name.clone(),
+                    error,
+                    cases,
+                    line: function.line,
+                }
+            })
+            .collect();
+        found.push(ErrorExposure {
+            module: module.name.clone(),
+            krate: krate.map(str::to_string),
+            functions,
+            errors: errors
+                .into_iter()
+                .map(|(name, cases)| ExposedError { name, cases })
+                .collect(),
+            location: CouplingLocation::file(module.path.clone()),
+        });
+    }
+    found.sort_by(|a, b| {
+        b.error_cases()
+            .cmp(&a.error_cases())
+            .then_with(|| a.module.cmp(&b.module))
+    });
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::CouplingAnalyzer;
+    use crate::metrics::ModuleMetrics;
+
+    fn module(name: &str, code: &str) -> ModuleMetrics {
+        let mut analyzer = CouplingAnalyzer::new(name.to_string(), format!("src/{name}.rs").into());
+        analyzer.analyze_file(code).unwrap();
+        analyzer.metrics
+    }
+
+    #[test]
+    fn test_error_surface() {
+        let surface = ErrorAnalyzer::analyze(
+            &syn::parse_file(
+                r#"
+                pub type Result<T> = std::result::Result<T, Error>;
+                pub fn load() -> Result<Config> { todo!() }
+                pub fn read() -> io::Result<Vec<u8>> { todo!() }
+                pub fn boxed() -> Result<(), Box<dyn std::error::Error>> { todo!() }
+                fn private() -> Result<()> { todo!() }
+                pub fn infallible() -> Config { todo!() }
+                impl Parser {
+                    pub fn parse(&self) -> Result<Ast, crate::ParseError> {
+                        fn nested() -> Result<()> { Ok(()) }
+                        todo!()
+                    }
+                }
+                impl fmt::Display for Parser {
+                    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { Ok(()) }
+                }
+                "#,
+            )
+            .unwrap(),
+        );
+        let errors: Vec<(&str, Option<&str>)> = surface
+            .functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.error.as_deref()))
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                ("load", Some("Error")),
+                ("read", Some("io::Error")),
+                ("boxed", Some("Box<dyn std::error::Error>")),
+                ("Parser::parse", Some("ParseError")),
+            ]
+        );
+        assert_eq!(surface.result_alias.as_deref(), Some("Error"));
+    }
+
+    #[test]
+    fn test_find_error_exposures() {
+        let mut metrics = ProjectMetrics::new();
+        metrics.add_module(module(
+            "error",
+            "pub enum Error { Io, Parse, Eof, Syntax }
+             pub type Result<T> = std::result::Result<T, Error>;",
+        ));
+        metrics.add_module(module(
+            "store",
+            "use crate::error::Result;
+             pub fn open() -> Result<()> { Ok(()) }
+             pub fn sync() -> Result<(), std::io::Error> { Ok(()) }
+             pub fn close() {}",
+        ));
+
+        let found = find_error_exposures(&metrics);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].module, "store");
+        assert_eq!(found[0].functions[0].cases, 4);
+        assert_eq!(
+            found[0].errors,
+            vec![
+                ExposedError {
+                    name: "Error".into(),
+                    cases: 4
+                },
+                ExposedError {
+                    name: "std::io::Error".into(),
+                    cases: 1
+                },
+            ]
+        );
+        assert_eq!(found[0].error_cases(), 5);
+    }
+}
@@ -0,0 +1,344 @@
+//! Exhaustive matches on public enums
+//!
+//! A `match` without a wildcard arm lists every variant of the enum it
+//! matches. When the enum is public and not `#[non_exhaustive]`, each such
+//! match outside the defining module agrees with the definition on the full
+//! set of variants: adding one breaks all of them, and in other crates it is
+//! a semver-breaking change (connascence of meaning between the enum and its
+//! matchers).
+//!
+//! Matches are recorded per file with the enum their arm patterns name
+//! (`Shape::Circle { .. }`, `Self::Empty`); patterns inside `Some(..)` or
+//! tuples are not followed, and a binding arm (`other =>`) counts as a
+//! wildcard. Across the project the enum is looked up by name like structs
+//! are for stamp coupling: names defined in several modules are skipped, and
+//! matches in the defining module itself are not counted.
+
+use std::collections::{BTreeMap, HashMap};
+
+use syn::visit::Visit;
+use syn::{Arm, ExprMatch, ImplItemFn, ItemFn, ItemImpl, Pat, Type};
+
+use crate::analyzer::span_position;
+use crate::metrics::{CouplingLocation, ModuleMetrics, ProjectMetrics, Visibility};
+
+/// Enums matched in at least this many places outside their module are
+/// Medium severity; fewer matches are Low unless one is in another crate
+pub const MANY_ENUM_MATCHES: usize = 3;
+
+/// A `match` over the variants of one enum without a wildcard arm
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumMatch {
+    /// Enum named by the arm patterns (last path segment)
+    pub enum_name: String,
+    /// Function containing the match (`Type::method` for associated
+    /// functions, empty outside functions)
+    pub function: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Collects [`EnumMatch`]es per file
+#[derive(Debug, Default)]
+pub struct MatchAnalyzer {
+    matches: Vec<EnumMatch>,
+    /// Self type of the enclosing impl block
+    impl_owner: Option<String>,
+    /// Enclosing function
+    function: Option<String>,
+}
+
+impl MatchAnalyzer {
+    /// Analyze a parsed file
+    pub fn analyze(file: &syn::File) -> Vec<EnumMatch> {
+        let mut analyzer = Self::default();
+        analyzer.visit_file(file);
+        analyzer.matches
+    }
+
+    /// Enum every arm names a variant of, if the match has no wildcard arm
+    fn matched_enum(&self, arms: &[Arm]) -> Option<String> {
+        let mut matched: Option<String> = None;
+        for arm in arms {
+            if arm.guard.is_none() && is_catch_all(&arm.pat) {
+                return None;
+            }
+            for owner in self.variant_owners(&arm.pat)? {
+                match &matched {
+                    Some(name) if *name != owner => return None,
+                    Some(_) => {}
+                    None => matched = Some(owner),
+                }
+            }
+        }
+        matched
+    }
+
+    /// Enums named by a pattern's variant paths; `None` if it matches
+    /// anything else (literals, tuples, single-segment paths)
+    fn variant_owners(&self, pat: &Pat) -> Option<Vec<String>> {
+        let path = match pat {
+            Pat::Or(or) => {
+                let mut owners = Vec::new();
+                for case in &or.cases {
+                    owners.extend(self.variant_owners(case)?);
+                }
+                return Some(owners);
+            }
+            Pat::Reference(reference) => return self.variant_owners(&reference.pat),
+            Pat::Paren(paren) => return self.variant_owners(&paren.pat),
+            Pat::Ident(ident) => return self.variant_owners(&ident.subpat.as_ref()?.1),
+            Pat::Path(path) if path.qself.is_none() => &path.path,
+            Pat::TupleStruct(tuple) if tuple.qself.is_none() => &tuple.path,
+            Pat::Struct(strukt) if strukt.qself.is_none() => &strukt.path,
+            _ => return None,
+        };
+        let segments = &path.segments;
+        if segments.len() < 2 {
+            return None;
+        }
+        let owner = segments[segments.len() - 2].ident.to_string();
+        let owner = if owner == "Self" {
+            self.impl_owner.clone()?
+        } else {
+            owner
+        };
+        owner.starts_with(char::is_uppercase).then(|| vec![owner])
+    }
+
+    fn visit_function(&mut self, name: &syn::Ident, visit: impl FnOnce(&mut Self)) {
+        let function = match &self.impl_owner {
+            Some(owner) => format!("{}::{}", owner, name),
+            None => name.to_string(),
+        };
+        let previous = self.function.replace(function);
+        visit(self);
+        self.function = previous;
+    }
+}
+
+impl<'ast> Visit<'ast> for MatchAnalyzer {
+    fn visit_item_fn(&mut self, node: &'ast ItemFn) {
+        self.visit_function(&node.sig.ident, |this| {
+            syn::visit::visit_item_fn(this, node)
+        });
+    }
+
+    fn visit_item_impl(&mut self, node: &'ast ItemImpl) {
+        let previous = self.impl_owner.take();
+        if let Type::Path(type_path) = &*node.self_ty {
+            self.impl_owner = type_path.path.segments.last().map(|s| s.ident.to_string());
+        }
+        syn::visit::visit_item_impl(self, node);
+        self.impl_owner = previous;
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast ImplItemFn) {
+        self.visit_function(&node.sig.ident, |this| {
+            syn::visit::visit_impl_item_fn(this, node)
+        });
+    }
+
+    fn visit_expr_match(&mut self, node: &'ast ExprMatch) {
+        if let Some(enum_name) = self.matched_enum(&node.arms) {
+            let (line, column) = span_position(node.match_token.span);
+            self.matches.push(EnumMatch {
+                enum_name,
+                function: self.function.clone().unwrap_or_default(),
+                line,
+                column,
+            });
+        }
+        syn::visit::visit_expr_match(self, node);
+    }
+}
+
+/// Whether an arm pattern matches every value (`_`, a binding, or an
+/// or-pattern containing one)
+fn is_catch_all(pat: &Pat) -> bool {
+    match pat {
+        Pat::Wild(_) => true,
+        Pat::Ident(ident) => ident.subpat.is_none(),
+        Pat::Or(or) => or.cases.iter().any(is_catch_all),
+        Pat::Reference(reference) => is_catch_all(&reference.pat),
+        Pat::Paren(paren) => is_catch_all(&paren.pat),
+        _ => false,
+    }
+}
+
+/// One exhaustive match on a [`FragileEnum`]
+#[derive(Debug, Clone)]
+pub struct EnumMatcher {
+    pub module: String,
+    /// Workspace crate containing the match (workspace analysis only)
+    pub crate_name: Option<String>,
+    pub function: String,
+    pub location: CouplingLocation,
+}
+
+/// A public enum matched without a wildcard arm outside its module
+#[derive(Debug, Clone)]
+pub struct FragileEnum {
+    /// Module defining the enum
+    pub module: String,
+    /// Workspace crate defining the enum (workspace analysis only)
+    pub crate_name: Option<String>,
+    pub enum_name: String,
+    pub variants: usize,
+    /// Exhaustive matches outside the defining module
+    pub matches: Vec<EnumMatcher>,
+    /// Where the enum is defined
+    pub location: CouplingLocation,
+}
+
+impl FragileEnum {
+    /// Whether another workspace crate matches the enum exhaustively
+    pub fn matched_in_other_crates(&self) -> bool {
+        self.matches.iter().any(|m| m.crate_name != self.crate_name)
+    }
+
+    /// Modules containing the matches, sorted and without duplicates
+    pub fn matching_modules(&self) -> Vec<&str> {
+        let mut modules: Vec<&str> = self.matches.iter().map(|m| m.module.as_str()).collect();
+        modules.sort_unstable();
+        modules.dedup();
+        modules
+    }
+}
+
+/// Find public, exhaustive enums matched without a wildcard arm in other
+/// modules, most matched first
+pub fn find_fragile_enums(metrics: &ProjectMetrics) -> Vec<FragileEnum> {
+    let mut enums: HashMap<&str, Vec<&ModuleMetrics>> = HashMap::new();
+    for module in metrics.modules.values() {
+        for def in module.type_definitions.values() {
+            if !def.is_trait && def.variants > 0 {
+                enums.entry(def.name.as_str()).or_default().push(module);
+            }
+        }
+    }
+
+    let mut found: BTreeMap<(&str, &str), FragileEnum> = BTreeMap::new();
+    for module in metrics.modules.values() {
+        for enum_match in &module.enum_matches {
+            let name = enum_match.enum_name.as_str();
+            if module.type_definitions.contains_key(name) {
+                continue;
+            }
+            let Some([definer]) = enums.get(name).map(Vec::as_slice) else {
+                continue;
+            };
+            let def = &definer.type_definitions[name];
+            if def.visibility != Visibility::Public || def.non_exhaustive {
+                continue;
+            }
+            found
+                .entry((definer.name.as_str(), name))
+                .or_insert_with(|| FragileEnum {
+                    module: definer.name.clone(),
+                    crate_name: definer.crate_name.clone(),
+                    enum_name: def.name.clone(),
+                    variants: def.variants,
+                    matches: Vec::new(),
+                    location: CouplingLocation::new(definer.path.clone(), def.line, def.column),
+                })
+                .matches
+                .push(EnumMatcher {
+                    module: module.name.clone(),
+                    crate_name: module.crate_name.clone(),
+                    function: enum_match.function.clone(),
+                    location: CouplingLocation::new(
+                        module.path.clone(),
+                        enum_match.line,
+                        enum_match.column,
+                    ),
+                });
+        }
+    }
+
+    let mut found: Vec<FragileEnum> = found.into_values().collect();
+    found.sort_by_key(|f| std::cmp::Reverse(f.matches.len()));
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::CouplingAnalyzer;
+
+    fn matches(code: &str) -> Vec<(String, String)> {
+        MatchAnalyzer::analyze(&syn::parse_file(code).unwrap())
+            .into_iter()
+            .map(|m| (m.enum_name, m.function))
+            .collect()
+    }
+
+    fn module(name: &str, code: &str) -> ModuleMetrics {
+        let mut analyzer = CouplingAnalyzer::new(name.to_string(), format!("src/{name}.rs").into());
+        analyzer.analyze_file(code).unwrap();
+        analyzer.metrics
+    }
+
+    #[test]
+    fn test_exhaustive_matches() {
+        let found = matches(
+            r#"
+            fn area(shape: &Shape) -> f64 {
+                match shape {
+                    Shape::Circle { r } => 3.14 * r * r,
+                    Shape::Square(s) | &Shape::Rect(s, _) => s * s,
+                    Shape::Empty => 0.0,
+                }
+            }
+            fn wildcard(shape: Shape) -> bool {
+                match shape { Shape::Empty => true, _ => false }
+            }
+            fn binding(shape: Shape) -> bool {
+                match shape { Shape::Empty => true, other => other.is_big() }
+            }
+            fn nested(shape: Option<Shape>) {
+                match shape { Some(Shape::Empty) => {}, Some(_) | None => {} }
+            }
+            impl Kind {
+                fn name(&self) -> &str {
+                    match self { Self::A => "a", Self::B if true => "b", Self::B => "c" }
+                }
+            }
+            "#,
+        );
+        assert_eq!(
+            found,
+            vec![
+                ("Shape".to_string(), "area".to_string()),
+                ("Kind".to_string(), "Kind::name".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_fragile_enums() {
+        let mut metrics = ProjectMetrics::new();
+        metrics.add_module(module(
+            "shape",
+            "pub enum Shape { Circle, Square }
+             #[non_exhaustive]
+             pub enum Open { A, B }
+             fn own(s: Shape) -> u8 { match s { Shape::Circle => 1, Shape::Square => 2 } }",
+        ));
+        metrics.add_module(module(
+            "draw",
+            "use crate::shape::{Open, Shape};
+             fn draw(s: Shape) -> u8 { match s { Shape::Circle => 1, Shape::Square => 2 } }
+             fn open(o: Open) -> u8 { match o { Open::A => 1, Open::B => 2 } }",
+        ));
+
+        let found = find_fragile_enums(&metrics);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].enum_name, "Shape");
+        assert_eq!(found[0].variants, 2);
+        assert_eq!(found[0].location.line, 1);
+        assert_eq!(found[0].matching_modules(), vec!["draw"]);
+        assert_eq!(found[0].matches[0].function, "draw");
+        assert!(!found[0].matched_in_other_crates());
+    }
+}
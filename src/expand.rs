@@ -0,0 +1,329 @@
+//! Macro expansion via `cargo expand`
+//!
+//! syn only sees macro invocations, so code generated by derives,
+//! `#[tokio::main]` or `macro_rules!` is invisible to the analysis. With
+//! `--expand` every selected crate is expanded with `cargo expand` (which has
+//! to be installed), the single expanded file is split back into one source
+//! per module file, and those sources are analyzed instead of the files on
+//! disk.
+//!
+//! Modules analyzed from expanded code are marked `expanded`. Their line
+//! numbers refer to the `cargo expand` output of the crate, and comments
+//! (including `coupling:ignore` directives) are gone after expansion.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use proc_macro2::LineColumn;
+use quote::ToTokens;
+use syn::spanned::Spanned;
+use syn::{Item, ItemMod};
+use thiserror::Error;
+
+use crate::analyzer::AnalysisScope;
+use crate::module_graph::module_path_from_file;
+use crate::paths::rs_files;
+use crate::workspace::{WorkspaceError, WorkspaceInfo};
+
+/// Expanded source per original file
+pub type ExpandedSources = HashMap<PathBuf, String>;
+
+/// Errors that can occur while expanding macros
+#[derive(Error, Debug)]
+pub enum ExpandError {
+    #[error("cargo expand is not installed (install it with `cargo install cargo-expand`)")]
+    NotInstalled,
+
+    #[error("Failed to run cargo expand: {0}")]
+    IoError(#[from] io::Error),
+
+    #[error("cargo expand failed for '{0}': {1}")]
+    Failed(String, String),
+
+    #[error("Failed to parse expanded source of '{0}': {1}")]
+    ParseError(String, String),
+
+    #[error("Workspace error: {0}")]
+    WorkspaceError(#[from] WorkspaceError),
+}
+
+/// Which target of a package to expand
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpandTarget {
+    /// `src/lib.rs`
+    Lib,
+    /// `src/main.rs`
+    Bin,
+}
+
+/// Run `cargo expand` for one package and return the expanded source
+pub fn run_cargo_expand(
+    manifest_path: &Path,
+    package: &str,
+    target: ExpandTarget,
+) -> Result<String, ExpandError> {
+    let _span = tracing::debug_span!("cargo_expand", package).entered();
+    let mut command = Command::new("cargo");
+    command
+        .arg("expand")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .args(["--package", package]);
+    match target {
+        ExpandTarget::Lib => command.arg("--lib"),
+        ExpandTarget::Bin => command.args(["--bin", package]),
+    };
+
+    let output = command.output()?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        if stderr.contains("no such command") {
+            return Err(ExpandError::NotInstalled);
+        }
+        return Err(ExpandError::Failed(
+            package.to_string(),
+            stderr.trim().to_string(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Module paths of the files under `src_dir` (`web::server` -> file)
+///
+/// The crate root (empty path) is `root_file`, so a package with both
+/// `lib.rs` and `main.rs` maps the root to the expanded target only.
+pub fn module_files(src_dir: &Path, root_file: &Path) -> BTreeMap<String, PathBuf> {
+    let mut files: BTreeMap<String, PathBuf> = rs_files(src_dir)
+        .map(|file| (module_path_from_file(&file), file))
+        .filter(|(module_path, _)| !module_path.is_empty())
+        .collect();
+    files.insert(String::new(), root_file.to_path_buf());
+    files
+}
+
+/// Split the expanded source of a crate into one source per module file
+///
+/// Inline modules that correspond to a file in `files` are cut out of their
+/// parent and replaced by a `mod name;` declaration; inline modules written
+/// inline in the original source stay where they are. Blank lines keep every
+/// item on the same line number as in `expanded`.
+pub fn split_expanded(
+    expanded: &str,
+    files: &BTreeMap<String, PathBuf>,
+) -> Result<ExpandedSources, syn::Error> {
+    let file = syn::parse_file(expanded)?;
+    let splitter = Splitter {
+        text: expanded,
+        line_starts: std::iter::once(0)
+            .chain(expanded.match_indices('\n').map(|(i, _)| i + 1))
+            .collect(),
+        files,
+    };
+    let mut sources = ExpandedSources::new();
+    splitter.split(&file.items, "", 0, expanded.len(), &mut sources);
+    Ok(sources)
+}
+
+struct Splitter<'a> {
+    text: &'a str,
+    line_starts: Vec<usize>,
+    files: &'a BTreeMap<String, PathBuf>,
+}
+
+impl Splitter<'_> {
+    /// Byte offset of a span position (lines are 1-based, columns count chars)
+    fn offset(&self, position: LineColumn) -> usize {
+        let start = self.line_starts[position.line.saturating_sub(1)];
+        self.text[start..]
+            .char_indices()
+            .nth(position.column)
+            .map_or(self.text.len(), |(i, _)| start + i)
+    }
+
+    fn split(
+        &self,
+        items: &[Item],
+        module_path: &str,
+        start: usize,
+        end: usize,
+        sources: &mut ExpandedSources,
+    ) {
+        let Some(file) = self.files.get(module_path) else {
+            return;
+        };
+
+        // Keep line numbers: everything before the module body becomes blank lines
+        let mut source = "\n".repeat(self.text[..start].matches('\n').count());
+        let mut cursor = start;
+        for item in items {
+            let Item::Mod(ItemMod {
+                vis,
+                ident,
+                content: Some((brace, children)),
+                ..
+            }) = item
+            else {
+                continue;
+            };
+            let child_path = if module_path.is_empty() {
+                ident.to_string()
+            } else {
+                format!("{}::{}", module_path, ident)
+            };
+            if !self.files.contains_key(&child_path) {
+                continue;
+            }
+
+            let item_start = self.offset(item.span().start());
+            let item_end = self.offset(item.span().end());
+            let vis = vis.to_token_stream().to_string();
+            source.push_str(&self.text[cursor..item_start]);
+            if !vis.is_empty() {
+                source.push_str(&vis);
+                source.push(' ');
+            }
+            source.push_str(&format!("mod {};", ident));
+            source.push_str(&"\n".repeat(self.text[item_start..item_end].matches('\n').count()));
+            cursor = item_end;
+
+            let body_start = self.offset(brace.span.open().end());
+            let body_end = self.offset(brace.span.close().start());
+            self.split(children, &child_path, body_start, body_end, sources);
+        }
+        source.push_str(&self.text[cursor..end]);
+        sources.insert(file.clone(), source);
+    }
+}
+
+/// Expand every crate in `scope` and split the results per file
+///
+/// Crates that fail to expand (for example because they do not compile)
+/// are reported and analyzed from the files on disk.
+pub fn expand_sources(path: &Path, scope: &AnalysisScope) -> Result<ExpandedSources, ExpandError> {
+    let workspace = WorkspaceInfo::from_path_with(path, &scope.features)?;
+    let mut sources = ExpandedSources::new();
+
+    for member in scope.select_members(&workspace)? {
+        let Some(crate_info) = workspace.get_crate(&member) else {
+            continue;
+        };
+        let lib = crate_info.src_path.join("lib.rs");
+        let (target, root_file) = if lib.exists() {
+            (ExpandTarget::Lib, lib)
+        } else {
+            (ExpandTarget::Bin, crate_info.src_path.join("main.rs"))
+        };
+
+        let expanded = match run_cargo_expand(&crate_info.manifest_path, &member, target) {
+            Ok(expanded) => expanded,
+            Err(ExpandError::Failed(krate, stderr)) => {
+                eprintln!(
+                    "Warning: cargo expand failed for '{}', analyzing unexpanded source",
+                    krate
+                );
+                if let Some(line) = stderr.lines().rfind(|l| l.starts_with("error")) {
+                    eprintln!("  {}", line);
+                }
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+
+        let files = module_files(&crate_info.src_path, &root_file);
+        match split_expanded(&expanded, &files) {
+            Ok(expanded) => sources.extend(expanded),
+            Err(e) => eprintln!(
+                "Warning: {}",
+                ExpandError::ParseError(member.clone(), e.to_string())
+            ),
+        }
+    }
+
+    Ok(sources)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPANDED: &str = r#"#![feature(prelude_import)]
+#[prelude_import]
+use std::prelude::rust_2024::*;
+#[macro_use]
+extern crate std;
+pub mod model {
+    pub struct User {
+        pub name: String,
+    }
+    #[automatically_derived]
+    impl ::core::clone::Clone for User {
+        fn clone(&self) -> User {
+            User { name: ::core::clone::Clone::clone(&self.name) }
+        }
+    }
+    mod inline {
+        pub fn helper() {}
+    }
+}
+pub(crate) mod web {
+    pub mod server {
+        pub fn run(user: &crate::model::User) {}
+    }
+}
+pub fn entry() {}
+"#;
+
+    fn files() -> BTreeMap<String, PathBuf> {
+        [
+            ("", "src/lib.rs"),
+            ("model", "src/model.rs"),
+            ("web", "src/web/mod.rs"),
+            ("web::server", "src/web/server.rs"),
+        ]
+        .into_iter()
+        .map(|(m, f)| (m.to_string(), PathBuf::from(f)))
+        .collect()
+    }
+
+    #[test]
+    fn test_split_expanded() {
+        let sources = split_expanded(EXPANDED, &files()).unwrap();
+        assert_eq!(sources.len(), 4);
+
+        let root = &sources[Path::new("src/lib.rs")];
+        assert!(root.contains("pub mod model;"));
+        assert!(root.contains(" mod web;"));
+        assert!(root.contains("pub fn entry() {}"));
+        assert!(!root.contains("struct User"));
+
+        let model = &sources[Path::new("src/model.rs")];
+        assert!(model.contains("impl ::core::clone::Clone for User"));
+        assert!(model.contains("mod inline {"));
+
+        assert!(sources[Path::new("src/web/mod.rs")].contains("pub mod server;"));
+        assert!(sources[Path::new("src/web/server.rs")].contains("pub fn run"));
+    }
+
+    #[test]
+    fn test_split_keeps_line_numbers() {
+        let sources = split_expanded(EXPANDED, &files()).unwrap();
+        let line_of = |source: &str, needle: &str| {
+            source.lines().position(|l| l.contains(needle)).unwrap() + 1
+        };
+
+        let model = &sources[Path::new("src/model.rs")];
+        assert_eq!(
+            line_of(model, "impl ::core::clone::Clone"),
+            line_of(EXPANDED, "impl ::core::clone::Clone")
+        );
+        let root = &sources[Path::new("src/lib.rs")];
+        assert_eq!(
+            line_of(root, "pub fn entry"),
+            line_of(EXPANDED, "pub fn entry")
+        );
+        assert!(syn::parse_file(root).is_ok());
+        assert!(syn::parse_file(model).is_ok());
+    }
+}
@@ -0,0 +1,856 @@
+//! Detailed explanations of finding types
+//!
+//! Every finding carries a stable rule id (`temporal::unpaired-operation`,
+//! see [`IssueType::rule_id`]) that is shown next to it in the reports.
+//! `cargo coupling --explain <rule-id>` prints what the pattern is, why it is
+//! a coupling risk, a small before/after refactoring in Rust and where the
+//! idea comes from.
+
+use std::io::{self, Write};
+
+use crate::balance::IssueType;
+
+/// Khononov, *Balancing Coupling in Software Design*
+const KHONONOV: &str = "Vlad Khononov, Balancing Coupling in Software Design (2024)";
+/// Ousterhout, *A Philosophy of Software Design*
+const OUSTERHOUT: &str = "John Ousterhout, A Philosophy of Software Design (2nd ed., 2021)";
+/// Page-Jones, *What Every Programmer Should Know About Object-Oriented Design*
+const PAGE_JONES: &str =
+    "Meilir Page-Jones, What Every Programmer Should Know About Object-Oriented Design (1995)";
+/// Martin, *Agile Software Development*
+const MARTIN: &str =
+    "Robert C. Martin, Agile Software Development: Principles, Patterns, and Practices (2002)";
+/// Fowler, *Refactoring*
+const FOWLER: &str = "Martin Fowler, Refactoring (2nd ed., 2018)";
+/// Tornhill, *Your Code as a Crime Scene*
+const TORNHILL: &str = "Adam Tornhill, Your Code as a Crime Scene (2nd ed., 2024)";
+/// The Rust API guidelines
+const API_GUIDELINES: &str = "Rust API Guidelines, https://rust-lang.github.io/api-guidelines/";
+
+/// Teaching material for one finding type
+#[derive(Debug, Clone)]
+pub struct Explanation {
+    pub issue_type: IssueType,
+    /// Why the pattern is a coupling risk
+    pub why: &'static [&'static str],
+    /// Code showing the pattern
+    pub before: &'static str,
+    /// The same code after refactoring
+    pub after: &'static str,
+    pub references: &'static [&'static str],
+}
+
+/// Get the explanation of an issue type
+pub fn explain(issue_type: IssueType) -> Explanation {
+    let (why, before, after, references): (&[&str], &str, &str, &[&str]) = match issue_type {
+        IssueType::GlobalComplexity => (
+            &[
+                "Knowledge of the other component's internals travels across a long distance",
+                "Changes on either side need coordination between teams or crates",
+                "The code can no longer be understood locally",
+            ],
+            r#"// crate `billing`
+fn invoice(order: &shop::orders::Order) -> Money {
+    order.lines.iter().map(|l| l.price * l.quantity).sum()
+}"#,
+            r#"// crate `shop` exposes what billing needs
+pub trait Billable {
+    fn total(&self) -> Money;
+}
+
+// crate `billing`
+fn invoice(order: &impl Billable) -> Money {
+    order.total()
+}"#,
+            &[KHONONOV],
+        ),
+        IssueType::CascadingChangeRisk => (
+            &[
+                "Every change of the volatile component ripples into this one",
+                "Strong coupling means the ripple is a code change, not a recompile",
+            ],
+            r#"fn price(rules: &pricing::RuleEngine, cart: &Cart) -> Money {
+    rules.rules.iter().fold(cart.total(), |p, r| r.apply(p))
+}"#,
+            r#"pub trait PriceCalculator {
+    fn price(&self, cart: &Cart) -> Money;
+}
+
+fn price(calc: &dyn PriceCalculator, cart: &Cart) -> Money {
+    calc.price(cart)
+}"#,
+            &[KHONONOV],
+        ),
+        IssueType::InappropriateIntimacy => (
+            &[
+                "Field access couples callers to the representation, not the behavior",
+                "The owner can no longer change its internals without breaking others",
+            ],
+            r#"if account.balance - amount >= account.overdraft_limit {
+    account.balance -= amount;
+}"#,
+            r#"impl Account {
+    pub fn withdraw(&mut self, amount: Money) -> Result<(), Overdrawn> {
+        // the invariant lives next to the data it protects
+    }
+}
+
+account.withdraw(amount)?;"#,
+            &[KHONONOV, FOWLER],
+        ),
+        IssueType::HighEfferentCoupling => (
+            &[
+                "The module breaks whenever any of its dependencies change",
+                "Tests need many collaborators or mocks",
+                "It usually does several jobs at once",
+            ],
+            r#"// main.rs uses config, db, http, cache, mailer, metrics, ...
+fn main() { /* parses args, connects, serves, reports */ }"#,
+            r#"// main.rs only wires focused modules together
+fn main() -> Result<()> {
+    let config = cli::parse()?;
+    let app = app::build(&config)?;
+    server::run(app)
+}"#,
+            &[MARTIN, KHONONOV],
+        ),
+        IssueType::HighAfferentCoupling => (
+            &[
+                "Every change risks breaking many dependents",
+                "Fear of breakage freezes the module and accumulates workarounds",
+            ],
+            r#"// used directly by 40 modules
+pub struct Database { pub pool: PgPool }"#,
+            r#"// dependents see a small, stable contract
+pub trait UserStore {
+    fn find(&self, id: UserId) -> Result<User>;
+}"#,
+            &[MARTIN, KHONONOV],
+        ),
+        IssueType::UnnecessaryAbstraction => (
+            &[
+                "Indirection without a second implementation adds code to read",
+                "Close, stable components gain nothing from loose coupling",
+            ],
+            r#"trait Formatter { fn format(&self, v: &Value) -> String; }
+struct DefaultFormatter;
+impl Formatter for DefaultFormatter { /* the only impl */ }"#,
+            r#"fn format(v: &Value) -> String {
+    // a plain function until a second format is needed
+}"#,
+            &[KHONONOV, OUSTERHOUT],
+        ),
+        IssueType::CircularDependency => (
+            &[
+                "No member of the cycle can be understood, tested or reused alone",
+                "A change anywhere in the cycle can affect every member",
+            ],
+            r#"// user.rs
+use crate::order::Order;
+pub struct User { pub orders: Vec<Order> }
+
+// order.rs
+use crate::user::User;
+pub struct Order { pub owner: User }"#,
+            r#"// ids.rs: shared, dependency-free
+pub struct UserId(u64);
+
+// order.rs depends on ids only
+pub struct Order { pub owner: UserId }"#,
+            &[MARTIN, KHONONOV],
+        ),
+        IssueType::LayerViolation => (
+            &[
+                "Lower layers that know higher ones can't change or be tested on their own",
+                "The declared architecture erodes one import at a time",
+            ],
+            r#"// domain/user.rs
+use crate::db::PgUserRow;"#,
+            r#"// domain/user.rs declares what it needs
+pub trait UserRepository { fn save(&self, user: &User) -> Result<()>; }
+
+// db/user.rs implements it
+impl UserRepository for PgUsers { /* ... */ }"#,
+            &[MARTIN],
+        ),
+        IssueType::StampCoupling => (
+            &[
+                "Callers have to build the whole struct to call the function",
+                "The signature hides which fields the function actually needs",
+            ],
+            r#"fn connect_timeout(config: &Config) -> Duration {
+    config.timeout * config.retries
+}"#,
+            r#"fn connect_timeout(timeout: Duration, retries: u32) -> Duration {
+    timeout * retries
+}"#,
+            &[KHONONOV],
+        ),
+        IssueType::ShallowModule => (
+            &[
+                "The interface costs nearly as much to learn as the implementation",
+                "Callers carry complexity the module should have hidden",
+            ],
+            r#"pub fn open_file(path: &Path) -> io::Result<File> { File::open(path) }
+pub fn read_bytes(f: &mut File, buf: &mut Vec<u8>) -> io::Result<usize> { f.read_to_end(buf) }
+pub fn parse_bytes(buf: &[u8]) -> Result<Config> { toml::from_slice(buf) }"#,
+            r#"/// Load and validate the configuration at `path`
+pub fn load(path: &Path) -> Result<Config> {
+    // open, read, parse, validate and fill in defaults
+}"#,
+            &[OUSTERHOUT],
+        ),
+        IssueType::UnusedPublicSurface => (
+            &[
+                "Every public item is interface a reader has to learn, and a semver promise",
+                "Items nobody calls make the module look shallower than it is",
+            ],
+            r#"// core/src/parse.rs: only `parse` is used by the other crates
+pub fn parse(input: &str) -> Result<Ast> { .. }
+pub fn tokenize(input: &str) -> Vec<Token> { .. }
+pub fn parse_legacy(input: &str, strict: bool, depth: usize) -> Result<Ast> { .. }"#,
+            r#"pub fn parse(input: &str) -> Result<Ast> { .. }
+pub(crate) fn tokenize(input: &str) -> Vec<Token> { .. }
+// parse_legacy removed"#,
+            &[OUSTERHOUT, API_GUIDELINES],
+        ),
+        IssueType::WideTrait => (
+            &[
+                "Every implementor writes every required method again",
+                "Adding a required method or changing a signature breaks all implementors",
+            ],
+            r#"pub trait Store {
+    fn get(&self, key: &str) -> Option<Vec<u8>>;
+    fn put(&mut self, key: &str, value: Vec<u8>);
+    fn contains(&self, key: &str) -> bool;
+    fn get_or(&self, key: &str, fallback: Vec<u8>) -> Vec<u8>;
+    fn put_all(&mut self, entries: Vec<(String, Vec<u8>)>);
+}"#,
+            r#"pub trait Store {
+    fn get(&self, key: &str) -> Option<Vec<u8>>;
+    fn put(&mut self, key: &str, value: Vec<u8>);
+
+    fn contains(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+    // get_or and put_all as default methods too
+}"#,
+            &[OUSTERHOUT, API_GUIDELINES],
+        ),
+        IssueType::SpecialCaseFlag => (
+            &[
+                "Callers choose the behavior with an argument instead of a function name",
+                "Each mode's code is read, tested and changed together with the others",
+                "Adding a mode changes the shared function and its signature's meaning",
+            ],
+            r#"pub fn export(data: &Data, csv: bool) -> String {
+    if csv {
+        let header = csv_header(data);
+        csv_rows(data, header)
+    } else {
+        let value = to_value(data);
+        serde_json::to_string(&value).unwrap()
+    }
+}"#,
+            r#"pub fn export_csv(data: &Data) -> String {
+    let header = csv_header(data);
+    csv_rows(data, header)
+}
+
+pub fn export_json(data: &Data) -> String {
+    serde_json::to_string(&to_value(data)).unwrap()
+}"#,
+            &[OUSTERHOUT],
+        ),
+        IssueType::FineGrainedErrors => (
+            &[
+                "Every caller writes a match arm, or a conversion, for each error case",
+                "Most callers handle the cases alike, so the distinction only adds code",
+                "A new variant is a change to every caller that matches exhaustively",
+            ],
+            r#"pub enum ReadError { NotFound, PermissionDenied, Interrupted, WouldBlock,
+    UnexpectedEof, InvalidUtf8, TooLarge, Empty, Closed, TimedOut }
+
+pub fn read_all(path: &Path) -> Result<String, ReadError> { .. }"#,
+            r#"// Retry Interrupted/WouldBlock inside; treat a missing or empty file as ""
+pub fn read_all(path: &Path) -> Result<String, ReadError> { .. }
+
+pub struct ReadError { kind: ReadErrorKind, source: io::Error }
+impl ReadError { pub fn kind(&self) -> ReadErrorKind { self.kind } }"#,
+            &[OUSTERHOUT],
+        ),
+        IssueType::TemporalDecomposition => (
+            &[
+                "Every phase module knows the format or model it reads, changes or writes",
+                "A change to the shared types is a change to each phase",
+                "The module structure tells readers the order of execution, not where knowledge lives",
+            ],
+            r#"// config/read.rs
+pub fn read(path: &Path) -> RawConfig { /* knows the file format */ }
+// config/validate.rs
+pub fn validate(raw: &RawConfig) -> Config { /* knows the fields */ }
+// config/write.rs
+pub fn write(config: &Config, path: &Path) { /* knows the file format again */ }"#,
+            r#"// config/format.rs: the only module that knows the file format
+pub fn load(path: &Path) -> Result<Config, ConfigError> { .. }
+pub fn store(config: &Config, path: &Path) -> io::Result<()> { .. }
+// config/mod.rs: the only module that knows the fields
+impl Config { pub fn validate(&self) -> Result<(), ConfigError> { .. } }"#,
+            &[OUSTERHOUT],
+        ),
+        IssueType::NonObviousInterface => (
+            &[
+                "Callers must read the implementation to learn what undocumented items do",
+                "Assumptions that are not written down get broken by the next change",
+            ],
+            r#"pub fn open(path: &Path, mode: u8, retries: usize) -> Result<Store> { .. }
+pub fn compact(&mut self, level: u8) { .. }"#,
+            r#"/// Open the store at `path`, creating it if missing. `mode` is 0 for
+/// read-only and 1 for read-write; locked files are retried `retries` times.
+pub fn open(path: &Path, mode: u8, retries: usize) -> Result<Store> { .. }
+/// Merge segments until at most `level` remain; blocks writers meanwhile.
+pub fn compact(&mut self, level: u8) { .. }"#,
+            &[OUSTERHOUT],
+        ),
+        IssueType::OverriddenDefault => (
+            &[
+                "The default body fits few implementors, so most still write their own",
+                "Readers assume the default describes the behavior, but most types do something else",
+            ],
+            r#"pub trait Encoder {
+    fn encode(&self, value: &Value) -> Vec<u8>;
+    fn content_type(&self) -> &str {
+        "application/json"
+    }
+}
+// Json keeps the default; Cbor, Yaml and Toml override content_type"#,
+            r#"pub trait Encoder {
+    fn encode(&self, value: &Value) -> Vec<u8>;
+    fn content_type(&self) -> &str;
+}
+// Every encoder states its own content type"#,
+            &[OUSTERHOUT, FOWLER],
+        ),
+        IssueType::PassThroughMethod => (
+            &[
+                "Each layer adds an interface without adding behavior",
+                "Responsibilities between the layers are unclear",
+            ],
+            r#"impl Service {
+    pub fn get_user(&self, id: UserId) -> Result<User> {
+        self.repo.get_user(id)
+    }
+}"#,
+            r#"// callers use the repository directly, or the service adds value:
+impl Service {
+    pub fn active_user(&self, id: UserId) -> Result<User> {
+        let user = self.repo.get_user(id)?;
+        user.ensure_active()?;
+        Ok(user)
+    }
+}"#,
+            &[OUSTERHOUT],
+        ),
+        IssueType::HighCognitiveLoad => (
+            &[
+                "Using the module requires knowing many items and their parameters",
+                "Every dependency adds something a reader must keep in mind",
+            ],
+            r#"pub fn send(to: &str, cc: &[&str], subject: &str, body: &str,
+            html: bool, retries: u32, timeout: Duration) -> Result<()>"#,
+            r#"pub struct Email { /* to, cc, subject, body, ... */ }
+
+pub fn send(email: &Email, options: &SendOptions) -> Result<()>"#,
+            &[OUSTERHOUT],
+        ),
+        IssueType::GodModule => (
+            &[
+                "Unrelated changes collide in the same file",
+                "Everything can reach everything else, so boundaries disappear",
+            ],
+            r#"// utils.rs: 80 functions, 25 types
+pub fn parse_date(..) { .. }
+pub fn send_email(..) { .. }
+pub fn hash_password(..) { .. }"#,
+            r#"// dates.rs, email.rs, auth.rs: one responsibility each
+pub mod dates;
+pub mod email;
+pub mod auth;"#,
+            &[MARTIN, KHONONOV],
+        ),
+        IssueType::PublicFieldExposure => (
+            &[
+                "The field layout becomes part of the public contract",
+                "Invariants can't be enforced when anyone can write the fields",
+            ],
+            r#"pub struct Temperature { pub kelvin: f64 }"#,
+            r#"pub struct Temperature { kelvin: f64 }
+
+impl Temperature {
+    pub fn from_kelvin(kelvin: f64) -> Option<Self> {
+        (kelvin >= 0.0).then_some(Self { kelvin })
+    }
+    pub fn kelvin(&self) -> f64 { self.kelvin }
+}"#,
+            &[API_GUIDELINES, KHONONOV],
+        ),
+        IssueType::PrimitiveObsession => (
+            &[
+                "Arguments of the same primitive type can be swapped silently",
+                "The meaning of each value lives only in parameter names",
+            ],
+            r#"fn transfer(from: u64, to: u64, amount: u64) { .. }"#,
+            r#"struct AccountId(u64);
+struct Cents(u64);
+
+fn transfer(from: AccountId, to: AccountId, amount: Cents) { .. }"#,
+            &[FOWLER, API_GUIDELINES],
+        ),
+        IssueType::OrphanRuleWorkaround => (
+            &[
+                "The impl breaks when either the trait's or the type's crate changes",
+                "Every caller wraps and unwraps values to reach the impl",
+            ],
+            r#"struct Json(serde_json::Value);
+
+impl sqlx::Type<Postgres> for Json { .. }
+impl sqlx::Encode<'_, Postgres> for Json { .. }"#,
+            r#"# Cargo.toml: sqlx ships the impl behind a feature
+sqlx = { version = "0.8", features = ["json"] }
+
+// use sqlx::types::Json<serde_json::Value> directly"#,
+            &[API_GUIDELINES, KHONONOV],
+        ),
+        IssueType::InteriorMutabilityLeak => (
+            &[
+                "Every caller locks or borrows on its own and picks the critical section",
+                "Switching from RefCell to Mutex, or to finer-grained locks, breaks them all",
+            ],
+            r#"pub struct Cache {
+    pub entries: Mutex<HashMap<Key, Entry>>,
+}
+impl Cache {
+    pub fn stats(&self) -> MutexGuard<'_, Stats> { self.stats.lock().unwrap() }
+}"#,
+            r#"pub struct Cache {
+    entries: Mutex<HashMap<Key, Entry>>,
+}
+impl Cache {
+    pub fn get(&self, key: &Key) -> Option<Entry> { self.entries.lock().unwrap().get(key).cloned() }
+    pub fn stats(&self) -> Stats { self.stats.lock().unwrap().clone() }
+}"#,
+            &[API_GUIDELINES, KHONONOV],
+        ),
+        IssueType::ExhaustiveEnumMatch => (
+            &[
+                "Every match without a wildcard arm repeats the full list of variants",
+                "Adding a variant breaks all of them, and other crates only find out on upgrade",
+            ],
+            r#"// shapes/src/lib.rs
+pub enum Shape { Circle(f64), Square(f64) }
+// render/src/lib.rs, export/src/lib.rs, ...
+match shape {
+    Shape::Circle(r) => draw_circle(r),
+    Shape::Square(s) => draw_square(s),
+}"#,
+            r#"#[non_exhaustive]
+pub enum Shape { Circle(f64), Square(f64) }
+
+impl Shape {
+    pub fn area(&self) -> f64 {
+        match self {
+            Shape::Circle(r) => PI * r * r,
+            Shape::Square(s) => s * s,
+        }
+    }
+}
+// other crates call shape.area() or keep a `_ =>` arm"#,
+            &[PAGE_JONES, API_GUIDELINES],
+        ),
+        IssueType::FieldVisibilityLeak => (
+            &[
+                "Every struct literal names all fields, so adding one breaks each of them",
+                "Other crates cannot be updated together with the struct; a new field is a breaking release",
+            ],
+            r#"// net/src/lib.rs
+pub struct Config { pub host: String, pub port: u16 }
+// app/src/main.rs, cli/src/main.rs, ...
+let config = Config { host: "localhost".into(), port: 8080 };"#,
+            r#"#[non_exhaustive]
+pub struct Config { pub host: String, pub port: u16 }
+
+impl Config {
+    pub fn new(host: impl Into<String>) -> Self {
+        Self { host: host.into(), port: 8080 }
+    }
+}
+// other crates call Config::new("localhost")"#,
+            &[PAGE_JONES, API_GUIDELINES],
+        ),
+        IssueType::ReExportChain => (
+            &[
+                "Renaming or changing the type breaks every crate in the chain",
+                "Users of the outer crate depend on the inner crate without declaring it",
+            ],
+            r#"// core/src/lib.rs
+pub struct User { .. }
+// service/src/lib.rs
+pub use core::User;
+// app/src/lib.rs
+pub use service::User;"#,
+            r#"// app/src/lib.rs: expose what app's users need
+pub struct Account {
+    user: service::User,
+}
+
+impl Account {
+    pub fn name(&self) -> &str { self.user.name() }
+}"#,
+            &[PAGE_JONES, API_GUIDELINES],
+        ),
+        IssueType::UnpairedOperation => (
+            &[
+                "Callers must remember to close what they opened, in the right order",
+                "Early returns and `?` skip the closing call",
+            ],
+            r#"fn save(tx: &mut Tx) -> Result<()> {
+    tx.begin();
+    write(tx)?; // returns without commit or rollback
+    tx.commit();
+    Ok(())
+}"#,
+            r#"fn save(db: &mut Db) -> Result<()> {
+    let tx = db.transaction()?; // rolls back on Drop
+    write(&tx)?;
+    tx.commit()
+}"#,
+            &[PAGE_JONES, API_GUIDELINES],
+        ),
+        IssueType::DroppedGuard => (
+            &[
+                "`let _ = ...` drops the guard immediately, so nothing is protected",
+                "The code looks synchronized but is not",
+            ],
+            r#"let _ = state.lock().unwrap(); // guard dropped here
+update_shared_state();"#,
+            r#"let _guard = state.lock().unwrap(); // held until end of scope
+update_shared_state();"#,
+            &[PAGE_JONES],
+        ),
+        IssueType::PanickingBuilder => (
+            &[
+                "Which setters must run before `build()` is a protocol only the docs know",
+                "Forgetting one compiles fine and panics at runtime",
+            ],
+            r#"impl ClientBuilder {
+    pub fn host(mut self, host: &str) -> Self { self.host = Some(host.into()); self }
+    pub fn build(self) -> Client {
+        Client { host: self.host.unwrap() } // panics if host() was not called
+    }
+}"#,
+            r#"impl ClientBuilder {
+    pub fn new(host: &str) -> Self { Self { host: host.into(), ..Default::default() } }
+    pub fn build(self) -> Client { Client { host: self.host } }
+}"#,
+            &[PAGE_JONES, API_GUIDELINES],
+        ),
+        IssueType::PanicPath => (
+            &[
+                "The order `connect()` before `send()` is a protocol only the docs know",
+                "Getting it wrong compiles fine and panics at runtime",
+            ],
+            r#"impl Client {
+    pub fn connect(&mut self) { self.conn = Some(Conn::open()); }
+    pub fn send(&self, data: &[u8]) {
+        self.conn.as_ref().unwrap().write(data); // panics before connect()
+    }
+}"#,
+            r#"impl Client {
+    pub fn connect(self) -> Result<Connection> { Ok(Connection { conn: Conn::open()? }) }
+}
+impl Connection {
+    pub fn send(&self, data: &[u8]) { self.conn.write(data); }
+}"#,
+            &[PAGE_JONES],
+        ),
+        IssueType::CancellationUnsafeSelect => (
+            &[
+                "Only the winning branch runs to completion; the other futures are dropped",
+                "Bytes a dropped `read_exact` already consumed are gone",
+            ],
+            r#"loop {
+    tokio::select! {
+        res = sock.read_exact(&mut header) => handle(res?),
+        _ = shutdown.recv() => break,
+    }
+}"#,
+            r#"let reader = tokio::spawn(read_frames(sock, tx)); // owns its buffer
+loop {
+    tokio::select! {
+        Some(frame) = rx.recv() => handle(frame),
+        _ = shutdown.recv() => { reader.abort(); break }
+    }
+}"#,
+            &[PAGE_JONES],
+        ),
+        IssueType::DetachedSpawn => (
+            &[
+                "Nothing can await, abort or observe the task once its handle is dropped",
+                "Shutdown order depends on tasks nobody tracks",
+            ],
+            r#"tokio::spawn(async move { sync_forever(db).await });"#,
+            r#"let mut tasks = JoinSet::new();
+tasks.spawn(async move { sync_forever(db).await });
+// on shutdown
+tasks.abort_all();"#,
+            &[PAGE_JONES],
+        ),
+        IssueType::LockOrderInversion => (
+            &[
+                "Each function is correct alone; together they can deadlock",
+                "Every new function touching both locks must know the order the others use",
+            ],
+            r#"fn transfer(&self) {
+    let accounts = self.accounts.lock().unwrap();
+    let audit = self.audit.lock().unwrap();
+}
+fn report(&self) {
+    let audit = self.audit.lock().unwrap();
+    let accounts = self.accounts.lock().unwrap(); // waits on transfer
+}"#,
+            r#"// Always `accounts` before `audit`, or one lock for both:
+struct Ledger { accounts: Vec<u64>, audit: Log }
+fn transfer(&self) {
+    let mut ledger = self.ledger.lock().unwrap();
+}"#,
+            &[PAGE_JONES],
+        ),
+        IssueType::DropOrderCoupling => (
+            &[
+                "Teardown order lives in statement and field order, not in types",
+                "A method releasing a field early must run before `drop` and never twice",
+            ],
+            r#"struct Conn { stream: ManuallyDrop<Stream>, pool: Arc<Pool> }
+impl Conn {
+    fn close(&mut self) { unsafe { ManuallyDrop::drop(&mut self.stream) } }
+}
+impl Drop for Conn {
+    fn drop(&mut self) { self.pool.release(&self.stream) } // after close()?
+}"#,
+            r#"// The stream returns itself to the pool when dropped
+struct PooledStream { stream: Stream, pool: Arc<Pool> }
+impl Drop for PooledStream {
+    fn drop(&mut self) { self.pool.release(&self.stream) }
+}
+struct Conn { stream: PooledStream }"#,
+            &[PAGE_JONES],
+        ),
+        IssueType::HiddenCoupling => (
+            &[
+                "The files share knowledge the code does not show",
+                "Changing one without the other causes bugs nobody can trace",
+            ],
+            r#"// encoder.rs and decoder.rs both hard-code the wire format
+const HEADER_LEN: usize = 12;"#,
+            r#"// wire.rs: the single place that knows the format
+pub const HEADER_LEN: usize = 12;"#,
+            &[TORNHILL, KHONONOV],
+        ),
+        IssueType::OrganizationalCoupling => (
+            &[
+                "Team boundaries and module boundaries disagree (Conway's law)",
+                "Coordination cost grows with every author and every dependent",
+            ],
+            r#"// handlers.rs: changed by the billing, accounts and search teams
+pub fn create_invoice(..) { .. }
+pub fn update_account(..) { .. }
+pub fn reindex(..) { .. }"#,
+            r#"// one module per owning team
+// billing/handlers.rs, accounts/handlers.rs, search/handlers.rs"#,
+            &[TORNHILL, KHONONOV],
+        ),
+        IssueType::ConnascenceOfPosition => (
+            &[
+                "Caller and callee must agree on argument order",
+                "Swapped arguments of the same type still compile",
+            ],
+            r#"fn resize(width: u32, height: u32) { .. }
+resize(768, 1024); // which one is which?"#,
+            r#"struct Size { width: u32, height: u32 }
+
+fn resize(size: Size) { .. }
+resize(Size { width: 1024, height: 768 });"#,
+            &[PAGE_JONES],
+        ),
+        IssueType::ConnascenceOfMeaning => (
+            &[
+                "Every module using the literal must agree on what it means",
+                "Changing the value means finding every copy",
+                "Renaming a table or column breaks every module whose SQL names it",
+            ],
+            r#"// server.rs
+if retries > 3 { .. }
+// client.rs
+for _ in 0..3 { .. }"#,
+            r#"// config.rs
+pub const MAX_RETRIES: u32 = 3;"#,
+            &[PAGE_JONES],
+        ),
+        IssueType::ConnascenceOfAlgorithm => (
+            &[
+                "Both sides must use the same encoding or hash to interoperate",
+                "Changing the algorithm on one side breaks the other at runtime",
+                "A bug fixed in one copy of a cloned function stays in the others",
+            ],
+            r#"// writer.rs
+let sum = crc32(&payload);
+// reader.rs
+if crc32(&payload) != sum { .. }"#,
+            r#"// checksum.rs owns the algorithm
+pub fn checksum(payload: &[u8]) -> u32 { crc32(payload) }
+pub fn verify(payload: &[u8], sum: u32) -> bool { checksum(payload) == sum }"#,
+            &[PAGE_JONES],
+        ),
+        IssueType::ConnascenceOfExecution => (
+            &[
+                "Callers must call methods in a particular order",
+                "The compiler can't check the order, so mistakes show up at runtime",
+            ],
+            r#"let mut conn = Connection::new(addr);
+conn.connect()?;
+conn.send(msg)?; // panics if connect() was skipped"#,
+            r#"// typestate: only a connected connection can send
+let conn = Connection::new(addr).connect()?; // -> Connected
+conn.send(msg)?;"#,
+            &[PAGE_JONES],
+        ),
+        IssueType::ConnascenceOfType => (
+            &[
+                "Every module naming the concrete type changes when it does",
+                "No module can be given another implementation, not even in tests",
+            ],
+            r#"// orders.rs, billing.rs, reports.rs, ...
+pub fn load(db: &PgPool, id: OrderId) -> Order { .. }"#,
+            r#"pub trait OrderStore {
+    fn order(&self, id: OrderId) -> Option<Order>;
+}
+
+pub fn load(store: &impl OrderStore, id: OrderId) -> Option<Order> { .. }"#,
+            &[PAGE_JONES],
+        ),
+        IssueType::ConnascenceOfIdentity => (
+            &[
+                "Every module must refer to the very same global instance",
+                "Hidden shared state makes behavior depend on who ran first",
+            ],
+            r#"static CACHE: Mutex<Option<Cache>> = Mutex::new(None);
+
+fn lookup(key: &str) -> Option<Value> {
+    CACHE.lock().unwrap().as_ref()?.get(key)
+}"#,
+            r#"fn lookup(cache: &Cache, key: &str) -> Option<Value> {
+    cache.get(key)
+}"#,
+            &[PAGE_JONES],
+        ),
+    };
+
+    Explanation {
+        issue_type,
+        why,
+        before,
+        after,
+        references,
+    }
+}
+
+/// Write the explanation of an issue type to writer
+pub fn generate_explanation<W: Write>(issue_type: IssueType, writer: &mut W) -> io::Result<()> {
+    let explanation = explain(issue_type);
+
+    let title = format!("{} ({})", issue_type, issue_type.rule_id());
+    writeln!(writer, "{}", title)?;
+    writeln!(writer, "{}", "=".repeat(title.chars().count()))?;
+    writeln!(writer)?;
+    writeln!(writer, "{}", issue_type.description())?;
+    writeln!(writer)?;
+
+    writeln!(writer, "Why it is a coupling risk:")?;
+    for reason in explanation.why {
+        writeln!(writer, "  - {}", reason)?;
+    }
+    writeln!(writer)?;
+
+    writeln!(writer, "Before:")?;
+    writeln!(writer)?;
+    for line in explanation.before.lines() {
+        writeln!(writer, "    {}", line)?;
+    }
+    writeln!(writer)?;
+    writeln!(writer, "After:")?;
+    writeln!(writer)?;
+    for line in explanation.after.lines() {
+        writeln!(writer, "    {}", line)?;
+    }
+    writeln!(writer)?;
+
+    writeln!(writer, "References:")?;
+    for reference in explanation.references {
+        writeln!(writer, "  - {}", reference)?;
+    }
+    Ok(())
+}
+
+/// Write every rule id with its name to writer
+pub fn generate_rule_list<W: Write>(writer: &mut W) -> io::Result<()> {
+    let width = IssueType::ALL
+        .iter()
+        .map(|t| t.rule_id().len())
+        .max()
+        .unwrap_or(0);
+    for issue_type in IssueType::ALL {
+        writeln!(writer, "{:<width$}  {}", issue_type.rule_id(), issue_type)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_rule_id() {
+        assert_eq!(
+            IssueType::from_rule_id("temporal::dropped-guard"),
+            Some(IssueType::DroppedGuard)
+        );
+        assert_eq!(
+            IssueType::from_rule_id("GodModule"),
+            Some(IssueType::GodModule)
+        );
+        assert_eq!(
+            IssueType::from_rule_id("connascence of position"),
+            Some(IssueType::ConnascenceOfPosition)
+        );
+        assert_eq!(IssueType::from_rule_id("temporal"), None);
+        // Every type round-trips through its rule id
+        for issue_type in IssueType::ALL {
+            assert_eq!(
+                IssueType::from_rule_id(issue_type.rule_id()),
+                Some(*issue_type)
+            );
+        }
+    }
+
+    #[test]
+    fn test_generate_explanation() {
+        let mut out = Vec::new();
+        generate_explanation(IssueType::UnpairedOperation, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+
+        assert!(text.starts_with("Unpaired Operation (temporal::unpaired-operation)\n"));
+        assert!(text.contains("Why it is a coupling risk:"));
+        assert!(text.contains("    let tx = db.transaction()?;"));
+        assert!(text.contains("Page-Jones"));
+    }
+}
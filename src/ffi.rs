@@ -0,0 +1,507 @@
+//! Coupling to the C ABI
+//!
+//! Code on either side of an FFI boundary shares knowledge the compiler
+//! cannot check: the layout of `#[repr(C)]` types, the symbols of
+//! `#[no_mangle]` and `extern "C" fn` exports, and the signatures declared
+//! in `extern "C" { ... }` blocks all have to match the other language by
+//! hand. This report lists the modules holding any of them.
+//!
+//! Foreign functions usually come in allocate/free pairs (`foo_new` and
+//! `foo_free`, `malloc` and `free`), and the memory must go back through
+//! the matching function. Pairs are found by name among the declared
+//! foreign functions, and the modules calling each side (in `unsafe`
+//! code) are compared: memory allocated in one module and freed in
+//! another, or never freed, couples the modules through the resource.
+//! Within a file, the pairs also feed the temporal analysis, so a
+//! function allocating without freeing (and no `Drop` impl freeing) is
+//! reported as an unpaired operation.
+
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use syn::visit::Visit;
+
+use crate::metrics::ProjectMetrics;
+
+/// Name parts of foreign functions that allocate a resource
+pub const ALLOC_WORDS: &[&str] = &["new", "create", "alloc", "open", "init", "acquire"];
+
+/// Name parts of foreign functions that free a resource
+pub const FREE_WORDS: &[&str] = &[
+    "free", "destroy", "delete", "release", "close", "fini", "dispose",
+];
+
+/// libc allocators, freed with `free`
+const C_ALLOCATORS: &[&str] = &["malloc", "calloc", "realloc", "strdup"];
+
+/// An `extern "ABI" { ... }` block
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ForeignBlock {
+    /// ABI string (`C` when omitted)
+    pub abi: String,
+    /// Declared functions
+    pub functions: Vec<String>,
+    pub line: usize,
+}
+
+/// FFI items of one file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FfiUsage {
+    pub foreign_blocks: Vec<ForeignBlock>,
+    /// `#[no_mangle]` items (name, line)
+    pub no_mangle: Vec<(String, usize)>,
+    /// Functions defined with a foreign ABI (`extern "C" fn cb()`)
+    pub exported_fns: Vec<(String, usize)>,
+    /// `#[repr(C)]` types (name, line)
+    pub repr_c: Vec<(String, usize)>,
+    /// Functions called inside `unsafe` code, by last path segment
+    pub unsafe_calls: BTreeSet<String>,
+}
+
+impl FfiUsage {
+    /// Collect the FFI items of a parsed file
+    pub fn analyze(file: &syn::File) -> Self {
+        let mut collector = FfiCollector::default();
+        collector.visit_file(file);
+        collector.usage
+    }
+
+    /// Whether the file has any FFI item (calls aside)
+    pub fn is_empty(&self) -> bool {
+        self.foreign_blocks.is_empty()
+            && self.no_mangle.is_empty()
+            && self.exported_fns.is_empty()
+            && self.repr_c.is_empty()
+    }
+
+    /// Foreign functions declared in the file
+    pub fn foreign_functions(&self) -> impl Iterator<Item = &String> {
+        self.foreign_blocks.iter().flat_map(|b| &b.functions)
+    }
+}
+
+#[derive(Default)]
+struct FfiCollector {
+    usage: FfiUsage,
+    /// Depth of enclosing `unsafe` blocks and functions
+    unsafe_depth: usize,
+}
+
+impl FfiCollector {
+    fn record_item(&mut self, attrs: &[syn::Attribute], ident: &syn::Ident) {
+        let line = ident.span().start().line;
+        if attrs.iter().any(is_no_mangle) {
+            self.usage.no_mangle.push((ident.to_string(), line));
+        }
+        if attrs.iter().any(is_repr_c) {
+            self.usage.repr_c.push((ident.to_string(), line));
+        }
+    }
+
+    fn visit_function(&mut self, sig: &syn::Signature, block: &syn::Block) {
+        if sig.abi.is_some() {
+            self.usage
+                .exported_fns
+                .push((sig.ident.to_string(), sig.ident.span().start().line));
+        }
+        let is_unsafe = sig.unsafety.is_some();
+        self.unsafe_depth += usize::from(is_unsafe);
+        self.visit_block(block);
+        self.unsafe_depth -= usize::from(is_unsafe);
+    }
+}
+
+impl<'ast> Visit<'ast> for FfiCollector {
+    fn visit_item_foreign_mod(&mut self, node: &'ast syn::ItemForeignMod) {
+        let abi = node
+            .abi
+            .name
+            .as_ref()
+            .map_or_else(|| "C".to_string(), |name| name.value());
+        let functions = node
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                syn::ForeignItem::Fn(f) => Some(f.sig.ident.to_string()),
+                _ => None,
+            })
+            .collect();
+        self.usage.foreign_blocks.push(ForeignBlock {
+            abi,
+            functions,
+            line: node.abi.extern_token.span.start().line,
+        });
+    }
+
+    fn visit_item_fn(&mut self, node: &'ast syn::ItemFn) {
+        self.record_item(&node.attrs, &node.sig.ident);
+        self.visit_function(&node.sig, &node.block);
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast syn::ImplItemFn) {
+        self.record_item(&node.attrs, &node.sig.ident);
+        self.visit_function(&node.sig, &node.block);
+    }
+
+    fn visit_item_static(&mut self, node: &'ast syn::ItemStatic) {
+        self.record_item(&node.attrs, &node.ident);
+        syn::visit::visit_item_static(self, node);
+    }
+
+    fn visit_item_struct(&mut self, node: &'ast syn::ItemStruct) {
+        self.record_item(&node.attrs, &node.ident);
+    }
+
+    fn visit_item_enum(&mut self, node: &'ast syn::ItemEnum) {
+        self.record_item(&node.attrs, &node.ident);
+    }
+
+    fn visit_item_union(&mut self, node: &'ast syn::ItemUnion) {
+        self.record_item(&node.attrs, &node.ident);
+    }
+
+    fn visit_expr_unsafe(&mut self, node: &'ast syn::ExprUnsafe) {
+        self.unsafe_depth += 1;
+        syn::visit::visit_expr_unsafe(self, node);
+        self.unsafe_depth -= 1;
+    }
+
+    fn visit_expr_call(&mut self, node: &'ast syn::ExprCall) {
+        if self.unsafe_depth > 0
+            && let syn::Expr::Path(func) = &*node.func
+            && let Some(last) = func.path.segments.last()
+        {
+            self.usage.unsafe_calls.insert(last.ident.to_string());
+        }
+        syn::visit::visit_expr_call(self, node);
+    }
+}
+
+/// `#[no_mangle]`, or `#[unsafe(no_mangle)]` in edition 2024
+fn is_no_mangle(attr: &syn::Attribute) -> bool {
+    if attr.path().is_ident("no_mangle") {
+        return true;
+    }
+    attr.path().is_ident("unsafe")
+        && attr
+            .parse_args::<syn::Ident>()
+            .is_ok_and(|ident| ident == "no_mangle")
+}
+
+/// `#[repr(C)]`, also combined (`#[repr(C, packed)]`)
+fn is_repr_c(attr: &syn::Attribute) -> bool {
+    let mut is_c = false;
+    if attr.path().is_ident("repr") {
+        let _ = attr.parse_nested_meta(|meta| {
+            is_c |= meta.path.is_ident("C");
+            Ok(())
+        });
+    }
+    is_c
+}
+
+/// Allocate/free pairs among foreign function names
+///
+/// `foo_new` pairs with `foo_free` (any [`ALLOC_WORDS`] with any
+/// [`FREE_WORDS`] on the same stem, as prefix or suffix), and the libc
+/// allocators with `free`.
+pub fn alloc_free_pairs<'a>(names: impl IntoIterator<Item = &'a str>) -> Vec<(String, String)> {
+    let names: BTreeSet<&str> = names.into_iter().collect();
+    let mut pairs = Vec::new();
+    for &alloc in &names {
+        if C_ALLOCATORS.contains(&alloc) {
+            if names.contains("free") {
+                pairs.push((alloc.to_string(), "free".to_string()));
+            }
+            continue;
+        }
+        let Some((stem, suffix)) = split_word(alloc, ALLOC_WORDS) else {
+            continue;
+        };
+        let free = FREE_WORDS.iter().find_map(|free_word| {
+            let name = if suffix {
+                format!("{}_{}", stem, free_word)
+            } else {
+                format!("{}_{}", free_word, stem)
+            };
+            names.contains(name.as_str()).then_some(name)
+        });
+        if let Some(free) = free {
+            pairs.push((alloc.to_string(), free));
+        }
+    }
+    pairs
+}
+
+/// Stem of a name ending or starting with one of `words`, and whether the
+/// word is a suffix (`foo_new` -> `foo`, `new_foo` -> `foo`)
+fn split_word<'a>(name: &'a str, words: &[&str]) -> Option<(&'a str, bool)> {
+    if let Some((stem, word)) = name.rsplit_once('_')
+        && words.contains(&word)
+        && !stem.is_empty()
+    {
+        return Some((stem, true));
+    }
+    let (word, stem) = name.split_once('_')?;
+    (words.contains(&word) && !stem.is_empty()).then_some((stem, false))
+}
+
+/// A module coupled to the C ABI
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FfiModule {
+    pub module: String,
+    pub file: PathBuf,
+    pub usage: FfiUsage,
+}
+
+/// Who allocates and who frees one foreign resource
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FfiResource {
+    pub alloc: String,
+    pub free: String,
+    /// Modules calling the allocating function
+    pub allocated_in: BTreeSet<String>,
+    /// Modules calling the freeing function
+    pub freed_in: BTreeSet<String>,
+}
+
+impl FfiResource {
+    /// Allocated somewhere but never freed through the matching function
+    pub fn is_leaked(&self) -> bool {
+        !self.allocated_in.is_empty() && self.freed_in.is_empty()
+    }
+
+    /// Freed in modules other than the allocating ones
+    pub fn is_split(&self) -> bool {
+        !self.freed_in.is_empty() && !self.freed_in.is_subset(&self.allocated_in)
+    }
+}
+
+/// Modules coupled to the C ABI and the foreign resources they share
+#[derive(Debug, Clone, Default)]
+pub struct FfiReport {
+    /// Modules with FFI items, by module name
+    pub modules: Vec<FfiModule>,
+    /// Allocate/free pairs of the declared foreign functions
+    pub resources: Vec<FfiResource>,
+}
+
+impl FfiReport {
+    /// Collect the FFI items of every module and pair their resources
+    pub fn build(metrics: &ProjectMetrics) -> Self {
+        let mut modules: Vec<FfiModule> = metrics
+            .modules
+            .values()
+            .filter(|m| !m.ffi.is_empty())
+            .map(|m| FfiModule {
+                module: m.name.clone(),
+                file: m.path.clone(),
+                usage: m.ffi.clone(),
+            })
+            .collect();
+        modules.sort_by(|a, b| a.module.cmp(&b.module).then_with(|| a.file.cmp(&b.file)));
+
+        let declared: BTreeSet<&str> = modules
+            .iter()
+            .flat_map(|m| m.usage.foreign_functions())
+            .map(String::as_str)
+            .collect();
+        let callers = |function: &str| -> BTreeSet<String> {
+            metrics
+                .modules
+                .values()
+                .filter(|m| m.ffi.unsafe_calls.contains(function))
+                .map(|m| m.name.clone())
+                .collect()
+        };
+        let resources = alloc_free_pairs(declared)
+            .into_iter()
+            .map(|(alloc, free)| FfiResource {
+                allocated_in: callers(&alloc),
+                freed_in: callers(&free),
+                alloc,
+                free,
+            })
+            .collect();
+        Self { modules, resources }
+    }
+}
+
+/// Generate the FFI coupling report to writer
+pub fn generate_ffi_report<W: Write>(report: &FfiReport, writer: &mut W) -> io::Result<()> {
+    writeln!(writer, "FFI Coupling (C ABI)")?;
+    writeln!(writer, "====================")?;
+    writeln!(writer)?;
+
+    if report.modules.is_empty() {
+        writeln!(
+            writer,
+            "No extern blocks, #[no_mangle] items or #[repr(C)] types found."
+        )?;
+        return Ok(());
+    }
+
+    let names = |items: &[(String, usize)]| -> String {
+        items
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    for module in &report.modules {
+        let usage = &module.usage;
+        writeln!(writer, "{} ({})", module.module, module.file.display())?;
+        for block in &usage.foreign_blocks {
+            writeln!(
+                writer,
+                "  extern \"{}\" (line {}): {} functions",
+                block.abi,
+                block.line,
+                block.functions.len()
+            )?;
+        }
+        if !usage.no_mangle.is_empty() {
+            writeln!(writer, "  #[no_mangle]: {}", names(&usage.no_mangle))?;
+        }
+        if !usage.exported_fns.is_empty() {
+            writeln!(writer, "  extern fn: {}", names(&usage.exported_fns))?;
+        }
+        if !usage.repr_c.is_empty() {
+            writeln!(writer, "  #[repr(C)]: {}", names(&usage.repr_c))?;
+        }
+    }
+
+    if !report.resources.is_empty() {
+        writeln!(writer)?;
+        writeln!(writer, "Foreign resources")?;
+        let list = |modules: &BTreeSet<String>| -> String {
+            if modules.is_empty() {
+                "-".to_string()
+            } else {
+                modules.iter().cloned().collect::<Vec<_>>().join(", ")
+            }
+        };
+        for resource in &report.resources {
+            let note = if resource.is_leaked() {
+                "  (never freed)"
+            } else if resource.is_split() {
+                "  (freed in another module)"
+            } else {
+                ""
+            };
+            writeln!(
+                writer,
+                "  {} / {}: allocated in {}, freed in {}{}",
+                resource.alloc,
+                resource.free,
+                list(&resource.allocated_in),
+                list(&resource.freed_in),
+                note
+            )?;
+        }
+    }
+
+    writeln!(writer)?;
+    writeln!(
+        writer,
+        "{} modules coupled to the C ABI, {} foreign resources ({} never freed, {} freed in another module)",
+        report.modules.len(),
+        report.resources.len(),
+        report.resources.iter().filter(|r| r.is_leaked()).count(),
+        report.resources.iter().filter(|r| r.is_split()).count()
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::ModuleMetrics;
+
+    fn usage(code: &str) -> FfiUsage {
+        FfiUsage::analyze(&syn::parse_file(code).unwrap())
+    }
+
+    #[test]
+    fn test_alloc_free_pairs() {
+        let pairs = alloc_free_pairs([
+            "png_create",
+            "png_destroy",
+            "ctx_new",
+            "ctx_free",
+            "free_list",
+            "new_list",
+            "malloc",
+            "free",
+            "open",
+        ]);
+        assert_eq!(
+            pairs,
+            vec![
+                ("ctx_new".to_string(), "ctx_free".to_string()),
+                ("malloc".to_string(), "free".to_string()),
+                ("new_list".to_string(), "free_list".to_string()),
+                ("png_create".to_string(), "png_destroy".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ffi_report() {
+        let bindings = usage(
+            r#"
+            #[repr(C)]
+            pub struct Point { x: f64, y: f64 }
+            #[repr(u8)]
+            enum Tag { A }
+            unsafe extern "C" {
+                pub fn ctx_new() -> *mut Ctx;
+                pub fn ctx_free(ctx: *mut Ctx);
+                pub fn buf_create() -> *mut Buf;
+                pub fn buf_destroy(buf: *mut Buf);
+            }
+            #[unsafe(no_mangle)]
+            pub extern "C" fn on_event(code: i32) {}
+            "#,
+        );
+        assert_eq!(bindings.foreign_blocks[0].functions.len(), 4);
+        assert_eq!(bindings.repr_c, vec![("Point".to_string(), 3)]);
+        assert_eq!(bindings.no_mangle, vec![("on_event".to_string(), 13)]);
+        assert_eq!(bindings.exported_fns, vec![("on_event".to_string(), 13)]);
+
+        let mut metrics = ProjectMetrics::new();
+        for (name, usage) in [
+            ("bindings", bindings),
+            (
+                "engine",
+                usage("fn run() { let c = unsafe { ctx_new() }; buf_create(); }"),
+            ),
+            (
+                "cleanup",
+                usage("unsafe fn stop(c: *mut Ctx) { ffi::ctx_free(c) }"),
+            ),
+        ] {
+            let mut module =
+                ModuleMetrics::new(PathBuf::from(format!("src/{}.rs", name)), name.into());
+            module.ffi = usage;
+            metrics.add_module(module);
+        }
+
+        let report = FfiReport::build(&metrics);
+        assert_eq!(report.modules.len(), 1);
+        let ctx = &report.resources[1];
+        assert_eq!(ctx.alloc, "ctx_new");
+        assert!(ctx.is_split());
+        // Called outside unsafe code: not an FFI call
+        assert!(report.resources[0].allocated_in.is_empty());
+
+        let mut out = Vec::new();
+        generate_ffi_report(&report, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("  extern \"C\" (line 6): 4 functions\n"));
+        assert!(text.contains(
+            "  ctx_new / ctx_free: allocated in engine, freed in cleanup  (freed in another module)\n"
+        ));
+    }
+}
@@ -0,0 +1,207 @@
+//! Per-file metrics for downstream tools
+//!
+//! [`ProjectMetrics`] is organized by module; review bots and editors think
+//! in files. [`FileMetrics`] gives the numbers of one source file: size,
+//! item counts, interface complexity, cognitive load and doc coverage (see
+//! [`crate::aposd`]), fan-in/fan-out, function length and nesting (see
+//! [`crate::functions`]) and the findings located in it.
+//!
+//! ```no_run
+//! use cargo_coupling::{FileMetrics, IssueThresholds, analyze_workspace};
+//!
+//! let metrics = analyze_workspace(std::path::Path::new("./"))?;
+//! // The files changed in a pull request, relative to the repository
+//! let changed = ["src/lib.rs", "src/parser.rs"];
+//! for file in FileMetrics::for_files(&metrics, &IssueThresholds::default(), &changed) {
+//!     println!("{}: {} findings", file.path.display(), file.findings.len());
+//! }
+//! # Ok::<(), cargo_coupling::AnalyzerError>(())
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::aposd::{cognitive_load, interface_complexity, module_depth_with};
+use crate::balance::{IssueThresholds, analyze_project_balance_with_thresholds};
+use crate::coupling_report::IssueRecord;
+use crate::functions::FunctionDistribution;
+use crate::metrics::{ModuleMetrics, ProjectMetrics};
+
+/// Metrics of one analyzed source file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileMetrics {
+    /// Path relative to the workspace root
+    pub path: PathBuf,
+    /// Module the file defines
+    pub module: String,
+    /// Rust module path of the file (`my_crate::net::client`)
+    pub module_path: String,
+    /// Workspace crate containing the file (workspace analysis only)
+    pub crate_name: Option<String>,
+    /// Non-blank, non-comment lines of code (without test code)
+    pub lines_of_code: usize,
+    pub functions: usize,
+    /// Structs, enums, traits and type aliases
+    pub types: usize,
+    /// Trait and inherent impl blocks
+    pub impls: usize,
+    pub interface_complexity: usize,
+    pub cognitive_load: usize,
+    /// Generic complexity of the public signatures
+    pub signature_complexity: usize,
+    /// Lines of code per unit of interface; `None` without a public
+    /// interface
+    pub depth_ratio: Option<f64>,
+    /// Share of documented public items; `None` without public items
+    #[serde(default)]
+    pub doc_coverage: Option<f64>,
+    /// Analyzed modules depending on this one
+    pub fan_in: usize,
+    /// Analyzed modules this one depends on
+    pub fan_out: usize,
+    /// Length, nesting and branching of the file's functions
+    #[serde(default)]
+    pub function_shape: FunctionDistribution,
+    /// Findings in this file, most severe first
+    pub findings: Vec<IssueRecord>,
+}
+
+impl FileMetrics {
+    /// Metrics of every analyzed file, sorted by path; findings follow
+    /// `thresholds` (strict mode, rules, suppressions and baseline)
+    pub fn collect(metrics: &ProjectMetrics, thresholds: &IssueThresholds) -> Vec<Self> {
+        Self::select(metrics, thresholds, |_| true)
+    }
+
+    /// Metrics of the analyzed files among `files`, sorted by path
+    ///
+    /// A file matches when its path ends with one of `files`, so paths
+    /// relative to the repository (as in a diff) find files analyzed by
+    /// absolute path. Files that were not analyzed are left out.
+    pub fn for_files<P: AsRef<Path>>(
+        metrics: &ProjectMetrics,
+        thresholds: &IssueThresholds,
+        files: &[P],
+    ) -> Vec<Self> {
+        Self::select(metrics, thresholds, |path| {
+            files.iter().any(|f| path.ends_with(f.as_ref()))
+        })
+    }
+
+    fn select(
+        metrics: &ProjectMetrics,
+        thresholds: &IssueThresholds,
+        wanted: impl Fn(&Path) -> bool,
+    ) -> Vec<Self> {
+        let modules: Vec<&ModuleMetrics> = metrics
+            .modules
+            .values()
+            .filter(|m| wanted(&m.path))
+            .collect();
+        if modules.is_empty() {
+            return Vec::new();
+        }
+
+        // Findings by file
+        let report = analyze_project_balance_with_thresholds(metrics, thresholds);
+        let mut findings: BTreeMap<&Path, Vec<IssueRecord>> = BTreeMap::new();
+        for issue in &report.issues {
+            if let Some(path) = issue.file(metrics) {
+                findings
+                    .entry(path)
+                    .or_default()
+                    .push(IssueRecord::from_issue(issue, metrics));
+            }
+        }
+
+        let fan_in = metrics.module_fan_in();
+        let fan_out = metrics.module_fan_out();
+        let mut files: Vec<Self> = modules
+            .into_iter()
+            .map(|module| Self {
+                path: PathBuf::from(metrics.relative_path(&module.path)),
+                module: module.name.clone(),
+                module_path: module.rust_path(),
+                crate_name: module.crate_name.clone(),
+                lines_of_code: module.lines_of_code,
+                functions: module.function_count(),
+                types: module.type_definitions.len(),
+                impls: module.trait_impl_count + module.inherent_impl_count,
+                interface_complexity: interface_complexity(module),
+                cognitive_load: cognitive_load(module),
+                signature_complexity: module.signature_complexity,
+                depth_ratio: module_depth_with(module, &thresholds.aposd).map(|d| d.depth_ratio),
+                doc_coverage: module.doc_coverage.ratio(),
+                fan_in: fan_in.get(&module.name).copied().unwrap_or(0),
+                fan_out: fan_out.get(&module.name).copied().unwrap_or(0),
+                function_shape: FunctionDistribution::of(&module.function_stats),
+                findings: findings.remove(module.path.as_path()).unwrap_or_default(),
+            })
+            .collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        files
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{CouplingMetrics, Distance, IntegrationStrength, Visibility, Volatility};
+
+    fn project() -> ProjectMetrics {
+        let mut metrics = ProjectMetrics::new();
+        metrics.workspace_root = Some(PathBuf::from("/work"));
+        let mut big = ModuleMetrics::new(PathBuf::from("/work/src/big.rs"), "big".into());
+        for i in 0..40 {
+            big.add_function_definition(format!("f{}", i), Visibility::Public);
+        }
+        big.lines_of_code = 400;
+        metrics.add_module(big);
+        metrics.add_module(ModuleMetrics::new(
+            PathBuf::from("/work/src/small.rs"),
+            "small".into(),
+        ));
+        metrics.add_coupling(CouplingMetrics::new(
+            "small".into(),
+            "big".into(),
+            IntegrationStrength::Functional,
+            Distance::DifferentModule,
+            Volatility::Low,
+        ));
+        metrics
+    }
+
+    #[test]
+    fn test_collect_file_metrics() {
+        let files = FileMetrics::collect(&project(), &IssueThresholds::default());
+        assert_eq!(files.len(), 2);
+
+        let big = &files[0];
+        assert_eq!(big.path, PathBuf::from("src/big.rs"));
+        assert_eq!(big.module_path, "crate::big");
+        assert_eq!(big.functions, 40);
+        assert_eq!(big.interface_complexity, 40);
+        assert_eq!(big.depth_ratio, Some(10.0));
+        assert_eq!(big.fan_in, 1);
+        assert_eq!(big.findings[0].rule, "rust::god-module");
+
+        let small = &files[1];
+        assert_eq!(small.fan_out, 1);
+        assert!(small.findings.is_empty());
+        assert_eq!(small.depth_ratio, None);
+    }
+
+    #[test]
+    fn test_for_files_matches_relative_paths() {
+        let metrics = project();
+        let thresholds = IssueThresholds::default();
+        let files = FileMetrics::for_files(&metrics, &thresholds, &["src/small.rs", "src/gone.rs"]);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].module, "small");
+
+        let none: [&str; 0] = [];
+        assert!(FileMetrics::for_files(&metrics, &thresholds, &none).is_empty());
+    }
+}
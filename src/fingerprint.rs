@@ -0,0 +1,124 @@
+//! Content-based finding fingerprints
+//!
+//! A fingerprint identifies a finding across runs, for baselines,
+//! `[suppress] fingerprints` and SARIF `partialFingerprints`. It is a
+//! 64-bit FNV-1a hash, written as 16 hex digits, of:
+//!
+//! - the rule id (`coupling::god-module`)
+//! - the source and target, with standalone numbers replaced by `#`, so
+//!   `102 dependents` and `103 dependents` are the same finding
+//! - the file the finding is in relative to the workspace root, if known,
+//!   so the `src/lib.rs` of two crates are told apart
+//!
+//! Line and column numbers, descriptions and the checkout directory are left
+//! out, so fingerprints survive edits that shift code, changed counts in
+//! messages and different checkout locations. FNV is used instead of the standard
+//! library hasher, whose output may change between Rust releases.
+
+use crate::balance::CouplingIssue;
+use crate::metrics::ProjectMetrics;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Fingerprint of a finding (see module docs)
+pub fn fingerprint(issue: &CouplingIssue, metrics: &ProjectMetrics) -> String {
+    let file = issue
+        .file(metrics)
+        .map(|path| metrics.relative_path(path))
+        .unwrap_or_default();
+    let parts = [
+        issue.issue_type.rule_id().to_string(),
+        normalize(&issue.source),
+        normalize(&issue.target),
+        file,
+    ];
+
+    let mut hash = FNV_OFFSET;
+    for part in &parts {
+        // A separator keeps ("ab", "c") and ("a", "bc") apart
+        for byte in part.bytes().chain([0]) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+    format!("{:016x}", hash)
+}
+
+/// Replace standalone numbers (`102 dependents`) with `#`; digits inside
+/// names (`http2`, `v1::api`) are kept
+fn normalize(text: &str) -> String {
+    text.split(' ')
+        .map(|word| {
+            if !word.is_empty() && word.bytes().all(|b| b.is_ascii_digit()) {
+                "#"
+            } else {
+                word
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::balance::{IssueType, RefactoringAction, Severity};
+    use crate::metrics::CouplingLocation;
+
+    fn issue(source: &str, path: &str, line: usize) -> CouplingIssue {
+        CouplingIssue {
+            issue_type: IssueType::HighAfferentCoupling,
+            severity: Severity::High,
+            source: source.to_string(),
+            target: "app::metrics".to_string(),
+            description: format!("depended on at line {}", line),
+            refactoring: RefactoringAction::General {
+                action: String::new(),
+            },
+            balance_score: 0.0,
+            location: CouplingLocation::new(path.into(), line, 1),
+        }
+    }
+
+    fn checkout(root: &str) -> ProjectMetrics {
+        ProjectMetrics {
+            workspace_root: Some(root.into()),
+            ..ProjectMetrics::new()
+        }
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_lines_counts_and_checkout_location() {
+        let ci = checkout("/ci/work");
+        let original = fingerprint(&issue("102 dependents", "/ci/work/src/metrics.rs", 10), &ci);
+        assert_eq!(original.len(), 16);
+        assert_eq!(
+            fingerprint(
+                &issue("87 dependents", "/home/dev/app/src/metrics.rs", 42),
+                &checkout("/home/dev/app")
+            ),
+            original
+        );
+        assert_ne!(
+            fingerprint(&issue("102 dependents", "/ci/work/src/other.rs", 10), &ci),
+            original
+        );
+    }
+
+    #[test]
+    fn test_same_issue_in_different_crates_differs() {
+        let ws = checkout("/ws");
+        assert_ne!(
+            fingerprint(&issue("lib", "/ws/app/src/lib.rs", 1), &ws),
+            fingerprint(&issue("lib", "/ws/corelib/src/lib.rs", 1), &ws)
+        );
+    }
+
+    #[test]
+    fn test_normalize_keeps_digits_in_names() {
+        assert_eq!(normalize("5 authors"), "# authors");
+        assert_eq!(normalize("http2::v1"), "http2::v1");
+        assert_eq!(normalize(""), "");
+    }
+}
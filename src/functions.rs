@@ -0,0 +1,503 @@
+//! Function length, nesting, branching and complexity
+//!
+//! Module-level APOSD metrics describe interfaces; these describe the code
+//! behind them. For every function with a body (free functions, methods
+//! and default trait methods) we record:
+//!
+//! - **Lines**: lines spanned from the signature to the closing brace
+//! - **Nesting**: the deepest nesting of `if`, `match`, loops and closures;
+//!   an `else if` continues its chain instead of nesting deeper
+//! - **Branches**: decision points, one per `if`/`else if`, loop and
+//!   `match` arm
+//! - **Cyclomatic complexity** (McCabe): independent paths through the
+//!   body, 1 plus one per `if`/`else if`, `for`, `while`, `match` arm
+//!   after the first, `&&`/`||` operator and `?`
+//! - **Cognitive complexity** (G. Ann Campbell, SonarSource): how hard the
+//!   body is to read. `if`, `match` and loops add 1 plus their nesting
+//!   depth, `else if` and `else` add 1, each run of like logical operators
+//!   (`a && b && c` is one, `a && b || c` two) adds 1, and so does a
+//!   `break` or `continue` to a label. Closures deepen the nesting without
+//!   adding anything themselves. Unlike cyclomatic complexity, a flat
+//!   `match` of twenty arms costs 1, while three nested loops cost 6.
+//!
+//! Functions nested in another function's body are measured on their own
+//! and not counted in the enclosing one. Per module and across the project
+//! the values are summarized as p50/p95/max ([`FunctionDistribution`]), so
+//! a few long functions stand out without being averaged away.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use syn::spanned::Spanned;
+use syn::visit::Visit;
+use syn::{
+    BinOp, Block, Expr, ExprBinary, ExprBreak, ExprClosure, ExprContinue, ExprForLoop, ExprIf,
+    ExprLoop, ExprMatch, ExprTry, ExprWhile, ImplItemFn, ItemFn, ItemImpl, Signature, TraitItemFn,
+    Type,
+};
+
+use crate::analyzer::span_position;
+use crate::metrics::ProjectMetrics;
+
+/// Functions listed by [`most_complex_functions`] in reports
+pub const COMPLEX_FUNCTION_LIMIT: usize = 10;
+
+/// Shape of one function body
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionStats {
+    /// Function name (`Type::method` for associated functions)
+    pub name: String,
+    /// Line of the function name (1-based)
+    pub line: usize,
+    /// Lines from the signature to the closing brace
+    pub lines: usize,
+    /// Deepest nesting of control flow
+    pub max_nesting: usize,
+    /// Decision points
+    pub branches: usize,
+    /// McCabe cyclomatic complexity (see module docs)
+    pub cyclomatic: usize,
+    /// Sonar-style cognitive complexity (see module docs)
+    pub cognitive: usize,
+}
+
+/// Function being measured
+#[derive(Debug)]
+struct Frame {
+    stats: FunctionStats,
+    nesting: usize,
+}
+
+/// Collects [`FunctionStats`] per file
+#[derive(Debug, Default)]
+pub struct FunctionAnalyzer {
+    functions: Vec<FunctionStats>,
+    /// Enclosing functions, innermost last
+    frames: Vec<Frame>,
+    /// Self type of the enclosing impl block
+    impl_owner: Option<String>,
+}
+
+impl FunctionAnalyzer {
+    /// Analyze a parsed file
+    pub fn analyze(file: &syn::File) -> Vec<FunctionStats> {
+        let mut analyzer = Self::default();
+        analyzer.visit_file(file);
+        analyzer.functions
+    }
+
+    fn measure(&mut self, sig: &Signature, block: &Block, visit: impl FnOnce(&mut Self)) {
+        let name = match (&self.impl_owner, self.frames.is_empty()) {
+            (Some(owner), true) => format!("{}::{}", owner, sig.ident),
+            _ => sig.ident.to_string(),
+        };
+        let (line, _) = span_position(sig.ident.span());
+        let start = sig.span().start().line;
+        let end = block.brace_token.span.close().end().line;
+        self.frames.push(Frame {
+            stats: FunctionStats {
+                name,
+                line,
+                lines: end.saturating_sub(start) + 1,
+                max_nesting: 0,
+                branches: 0,
+                cyclomatic: 1,
+                cognitive: 0,
+            },
+            nesting: 0,
+        });
+        visit(self);
+        if let Some(frame) = self.frames.pop() {
+            self.functions.push(frame.stats);
+        }
+    }
+
+    fn branch(&mut self, count: usize) {
+        if let Some(frame) = self.frames.last_mut() {
+            frame.stats.branches += count;
+        }
+    }
+
+    /// Add to the complexities; `nested` structures also pay their depth
+    /// in cognitive complexity
+    fn complexity(&mut self, cyclomatic: usize, cognitive: usize, nested: bool) {
+        if let Some(frame) = self.frames.last_mut() {
+            frame.stats.cyclomatic += cyclomatic;
+            frame.stats.cognitive += cognitive + if nested { frame.nesting } else { 0 };
+        }
+    }
+
+    fn nested(&mut self, visit: impl FnOnce(&mut Self)) {
+        if let Some(frame) = self.frames.last_mut() {
+            frame.nesting += 1;
+            frame.stats.max_nesting = frame.stats.max_nesting.max(frame.nesting);
+        }
+        visit(self);
+        if let Some(frame) = self.frames.last_mut() {
+            frame.nesting -= 1;
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for FunctionAnalyzer {
+    fn visit_item_fn(&mut self, node: &'ast ItemFn) {
+        self.measure(&node.sig, &node.block, |this| {
+            syn::visit::visit_item_fn(this, node)
+        });
+    }
+
+    fn visit_item_impl(&mut self, node: &'ast ItemImpl) {
+        let previous = self.impl_owner.take();
+        if let Type::Path(type_path) = &*node.self_ty {
+            self.impl_owner = type_path.path.segments.last().map(|s| s.ident.to_string());
+        }
+        syn::visit::visit_item_impl(self, node);
+        self.impl_owner = previous;
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast ImplItemFn) {
+        self.measure(&node.sig, &node.block, |this| {
+            syn::visit::visit_impl_item_fn(this, node)
+        });
+    }
+
+    fn visit_trait_item_fn(&mut self, node: &'ast TraitItemFn) {
+        match &node.default {
+            Some(block) => self.measure(&node.sig, block, |this| {
+                syn::visit::visit_trait_item_fn(this, node)
+            }),
+            None => syn::visit::visit_trait_item_fn(self, node),
+        }
+    }
+
+    fn visit_expr_if(&mut self, node: &'ast ExprIf) {
+        self.complexity(1, 1, true);
+        self.visit_if_chain(node);
+    }
+
+    fn visit_expr_match(&mut self, node: &'ast ExprMatch) {
+        self.branch(node.arms.len());
+        self.complexity(node.arms.len().saturating_sub(1), 1, true);
+        self.visit_expr(&node.expr);
+        self.nested(|this| {
+            for arm in &node.arms {
+                this.visit_arm(arm);
+            }
+        });
+    }
+
+    fn visit_expr_for_loop(&mut self, node: &'ast ExprForLoop) {
+        self.branch(1);
+        self.complexity(1, 1, true);
+        self.visit_expr(&node.expr);
+        self.nested(|this| this.visit_block(&node.body));
+    }
+
+    fn visit_expr_while(&mut self, node: &'ast ExprWhile) {
+        self.branch(1);
+        self.complexity(1, 1, true);
+        self.visit_expr(&node.cond);
+        self.nested(|this| this.visit_block(&node.body));
+    }
+
+    fn visit_expr_loop(&mut self, node: &'ast ExprLoop) {
+        self.branch(1);
+        self.complexity(0, 1, true);
+        self.nested(|this| this.visit_block(&node.body));
+    }
+
+    fn visit_expr_closure(&mut self, node: &'ast ExprClosure) {
+        self.nested(|this| this.visit_expr(&node.body));
+    }
+
+    fn visit_expr_binary(&mut self, node: &'ast ExprBinary) {
+        if !is_logical(&node.op) {
+            return syn::visit::visit_expr_binary(self, node);
+        }
+        let mut operators = Vec::new();
+        let mut operands = Vec::new();
+        flatten_logical(node, &mut operators, &mut operands);
+        let runs = 1 + operators.windows(2).filter(|w| w[0] != w[1]).count();
+        self.complexity(operators.len(), runs, false);
+        for operand in operands {
+            self.visit_expr(operand);
+        }
+    }
+
+    fn visit_expr_try(&mut self, node: &'ast ExprTry) {
+        self.complexity(1, 0, false);
+        syn::visit::visit_expr_try(self, node);
+    }
+
+    fn visit_expr_break(&mut self, node: &'ast ExprBreak) {
+        if node.label.is_some() {
+            self.complexity(0, 1, false);
+        }
+        syn::visit::visit_expr_break(self, node);
+    }
+
+    fn visit_expr_continue(&mut self, node: &'ast ExprContinue) {
+        if node.label.is_some() {
+            self.complexity(0, 1, false);
+        }
+        syn::visit::visit_expr_continue(self, node);
+    }
+}
+
+impl FunctionAnalyzer {
+    /// Visit an `if` and its `else if`/`else` continuation; the `if`
+    /// itself has been counted
+    fn visit_if_chain(&mut self, node: &ExprIf) {
+        self.branch(1);
+        self.visit_expr(&node.cond);
+        self.nested(|this| this.visit_block(&node.then_branch));
+        if let Some((_, else_branch)) = &node.else_branch {
+            self.complexity(0, 1, false);
+            match &**else_branch {
+                // `else if` continues the chain at the same depth
+                Expr::If(else_if) => {
+                    self.complexity(1, 0, false);
+                    self.visit_if_chain(else_if);
+                }
+                other => self.nested(|this| this.visit_expr(other)),
+            }
+        }
+    }
+}
+
+fn is_logical(op: &BinOp) -> bool {
+    matches!(op, BinOp::And(_) | BinOp::Or(_))
+}
+
+/// Operators (`true` for `&&`) and operands of a chain of `&&`/`||`,
+/// left to right
+fn flatten_logical<'a>(
+    node: &'a ExprBinary,
+    operators: &mut Vec<bool>,
+    operands: &mut Vec<&'a Expr>,
+) {
+    for (i, side) in [&*node.left, &*node.right].into_iter().enumerate() {
+        if i == 1 {
+            operators.push(matches!(node.op, BinOp::And(_)));
+        }
+        match side {
+            Expr::Binary(binary) if is_logical(&binary.op) => {
+                flatten_logical(binary, operators, operands)
+            }
+            _ => operands.push(side),
+        }
+    }
+}
+
+/// Median, 95th percentile and maximum of a set of values
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Percentiles {
+    pub p50: usize,
+    pub p95: usize,
+    pub max: usize,
+}
+
+impl Percentiles {
+    /// Nearest-rank percentiles; all zero for no values
+    pub fn of(values: impl IntoIterator<Item = usize>) -> Self {
+        let mut values: Vec<usize> = values.into_iter().collect();
+        if values.is_empty() {
+            return Self::default();
+        }
+        values.sort_unstable();
+        let rank = |p: usize| values[(values.len() * p).div_ceil(100).max(1) - 1];
+        Self {
+            p50: rank(50),
+            p95: rank(95),
+            max: values[values.len() - 1],
+        }
+    }
+}
+
+/// Length, nesting, branching and complexity across a set of functions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionDistribution {
+    pub functions: usize,
+    pub lines: Percentiles,
+    pub nesting: Percentiles,
+    pub branches: Percentiles,
+    #[serde(default)]
+    pub cyclomatic: Percentiles,
+    #[serde(default)]
+    pub cognitive: Percentiles,
+}
+
+impl FunctionDistribution {
+    /// Summarize function stats
+    pub fn of<'a>(stats: impl IntoIterator<Item = &'a FunctionStats>) -> Self {
+        let stats: Vec<&FunctionStats> = stats.into_iter().collect();
+        Self {
+            functions: stats.len(),
+            lines: Percentiles::of(stats.iter().map(|s| s.lines)),
+            nesting: Percentiles::of(stats.iter().map(|s| s.max_nesting)),
+            branches: Percentiles::of(stats.iter().map(|s| s.branches)),
+            cyclomatic: Percentiles::of(stats.iter().map(|s| s.cyclomatic)),
+            cognitive: Percentiles::of(stats.iter().map(|s| s.cognitive)),
+        }
+    }
+
+    /// Summarize every function of the project
+    pub fn of_project(metrics: &ProjectMetrics) -> Self {
+        Self::of(metrics.modules.values().flat_map(|m| &m.function_stats))
+    }
+}
+
+/// One of the most complex functions of the project
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComplexFunction {
+    pub module: String,
+    /// Function name (`Type::method` for associated functions)
+    pub function: String,
+    /// File relative to the workspace root
+    pub file: PathBuf,
+    pub line: usize,
+    pub lines: usize,
+    pub cyclomatic: usize,
+    pub cognitive: usize,
+}
+
+/// The `limit` functions with the highest cognitive complexity, ties
+/// broken by cyclomatic complexity
+pub fn most_complex_functions(metrics: &ProjectMetrics, limit: usize) -> Vec<ComplexFunction> {
+    let mut functions: Vec<ComplexFunction> = metrics
+        .modules
+        .values()
+        .flat_map(|module| {
+            module.function_stats.iter().map(|stats| ComplexFunction {
+                module: module.name.clone(),
+                function: stats.name.clone(),
+                file: PathBuf::from(metrics.relative_path(&module.path)),
+                line: stats.line,
+                lines: stats.lines,
+                cyclomatic: stats.cyclomatic,
+                cognitive: stats.cognitive,
+            })
+        })
+        .collect();
+    functions.sort_by(|a, b| {
+        b.cognitive
+            .cmp(&a.cognitive)
+            .then_with(|| b.cyclomatic.cmp(&a.cyclomatic))
+            .then_with(|| a.module.cmp(&b.module))
+            .then_with(|| a.line.cmp(&b.line))
+    });
+    functions.truncate(limit);
+    functions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_function_stats() {
+        let code = r#"
+fn flat() -> u8 {
+    1
+}
+
+impl Parser {
+    fn parse(&mut self, input: &str) -> usize {
+        let mut count = 0;
+        for c in input.chars() {
+            if c == 'a' {
+                count += 1;
+            } else if c == 'b' {
+                match c {
+                    'b' => count += 2,
+                    _ => {}
+                }
+            } else {
+                input.chars().for_each(|x| {
+                    if x == c {}
+                });
+            }
+        }
+        fn helper() {
+            loop {}
+        }
+        count
+    }
+}
+"#;
+        let stats = FunctionAnalyzer::analyze(&syn::parse_file(code).unwrap());
+        let summary: Vec<(&str, usize, usize, usize)> = stats
+            .iter()
+            .map(|s| (s.name.as_str(), s.lines, s.max_nesting, s.branches))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("flat", 3, 0, 0),
+                ("helper", 3, 1, 1),
+                // for > else > closure > if; for, if, else if, 2 arms, if
+                ("Parser::parse", 21, 4, 6),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_complexity() {
+        let code = r#"
+fn sum_of_primes(max: u32) -> u32 {
+    let mut total = 0;
+    'out: for i in 1..=max {
+        for j in 2..i {
+            if i % j == 0 {
+                continue 'out;
+            }
+        }
+        total += i;
+    }
+    total
+}
+
+fn words(n: u32) -> &'static str {
+    match n {
+        1 => "one",
+        2 => "a couple",
+        _ => "lots",
+    }
+}
+
+fn check(a: bool, b: bool, c: bool, d: bool) -> Result<(), Error> {
+    if a && b && c || d {
+        load()?;
+    } else if !a {
+        return Err(Error);
+    } else {
+        retry()?;
+    }
+    Ok(())
+}
+"#;
+        let stats = FunctionAnalyzer::analyze(&syn::parse_file(code).unwrap());
+        let scores: Vec<(&str, usize, usize)> = stats
+            .iter()
+            .map(|s| (s.name.as_str(), s.cyclomatic, s.cognitive))
+            .collect();
+        assert_eq!(
+            scores,
+            vec![
+                // for +1, for +2, if +3, continue 'out +1
+                ("sum_of_primes", 4, 7),
+                ("words", 3, 1),
+                // if +1, two operator runs +2, else if +1, else +1
+                ("check", 8, 5),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_percentiles() {
+        assert_eq!(Percentiles::of([]), Percentiles::default());
+        let values = Percentiles::of((1..=20).rev());
+        assert_eq!((values.p50, values.p95, values.max), (10, 19, 20));
+        assert_eq!(Percentiles::of([7]).p95, 7);
+    }
+}
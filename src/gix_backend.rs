@@ -0,0 +1,279 @@
+//! In-process git history backend (`--features gix`)
+//!
+//! Reads the same commits as [`GitCommandBackend`] without spawning `git`:
+//! the history is walked newest first with gitoxide and each commit's tree is
+//! diffed against its first parent.
+//!
+//! - Only additions and modifications of `.rs` files count; a rename or copy
+//!   shows up as the addition of the new path, like `--diff-filter=AMRC`
+//! - Merge commits list no files, as with `git log --name-only`
+//! - Authors are resolved through the repository's `.mailmap`
+//! - Files outside the analyzed directory are left out, like the `*.rs`
+//!   pathspec `git log` is run with from there
+//!
+//! A date the backend cannot parse falls back to [`GitCommandBackend`].
+//!
+//! [`GitCommandBackend`]: crate::volatility::GitCommandBackend
+
+use std::ops::ControlFlow;
+use std::path::Path;
+
+use gix::bstr::ByteSlice;
+use gix::object::tree::diff::Change;
+use gix::revision::walk::Sorting;
+use gix::traverse::commit::simple::CommitTimeOrder;
+
+use crate::volatility::{CommitRecord, GitWindow, HistoryBackend, VolatilityError};
+
+/// Reads history with gitoxide, without a `git` binary
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GixBackend;
+
+impl HistoryBackend for GixBackend {
+    fn name(&self) -> &'static str {
+        "gix"
+    }
+
+    fn read_commits(
+        &self,
+        repo_path: &Path,
+        window: &GitWindow,
+        record: &mut dyn FnMut(CommitRecord),
+    ) -> Result<(), VolatilityError> {
+        let _span = tracing::debug_span!("gix", window = %window).entered();
+
+        let repo = gix::discover(repo_path).map_err(|_| VolatilityError::NotGitRepo)?;
+        let prefix = path_prefix(&repo, repo_path);
+        let (since, until) = time_bounds(window)?;
+
+        let head = repo.head_id().map_err(backend_error)?;
+        let sorting = match since {
+            Some(seconds) => Sorting::ByCommitTimeCutoff {
+                order: CommitTimeOrder::NewestFirst,
+                seconds,
+            },
+            None => Sorting::ByCommitTime(CommitTimeOrder::NewestFirst),
+        };
+        let mut walk = repo.rev_walk([head.detach()]).sorting(sorting);
+        if let GitWindow::SinceTag(tag) = window {
+            let base = repo
+                .rev_parse_single(format!("{}^{{commit}}", tag).as_str())
+                .map_err(|_| VolatilityError::UnknownRevision(tag.clone()))?;
+            walk = walk.with_hidden([base.detach()]);
+        }
+        let limit = match window {
+            GitWindow::LastCommits(count) => *count,
+            _ => usize::MAX,
+        };
+
+        let mailmap = repo.open_mailmap();
+        let mut recorded = 0;
+        for info in walk.all().map_err(backend_error)? {
+            if recorded == limit {
+                break;
+            }
+            let info = info.map_err(backend_error)?;
+            let commit = info.object().map_err(backend_error)?;
+            let time = commit.time().map_err(backend_error)?.seconds;
+            if until.is_some_and(|until| time > until) {
+                continue;
+            }
+
+            let files = changed_files(&repo, &commit, &prefix)?;
+            if files.is_empty() {
+                continue;
+            }
+            let author = commit.author().map_err(backend_error)?;
+            let author = match mailmap.try_resolve(author) {
+                Some(resolved) => resolved.name.to_string(),
+                None => author.name.to_string(),
+            };
+            let subject = commit
+                .message()
+                .map_err(backend_error)?
+                .summary()
+                .to_string();
+            record(CommitRecord {
+                author: author.trim().to_string(),
+                subject: subject.trim().to_string(),
+                files,
+            });
+            recorded += 1;
+        }
+        Ok(())
+    }
+}
+
+/// `.rs` files a commit adds or modifies under `prefix`, relative to the
+/// repository root
+fn changed_files(
+    repo: &gix::Repository,
+    commit: &gix::Commit<'_>,
+    prefix: &str,
+) -> Result<Vec<String>, VolatilityError> {
+    let mut parents = commit.parent_ids();
+    let parent = parents.next();
+    if parents.next().is_some() {
+        return Ok(Vec::new());
+    }
+    let parent_tree = match parent {
+        Some(id) => id
+            .object()
+            .map_err(backend_error)?
+            .peel_to_tree()
+            .map_err(backend_error)?,
+        None => repo.empty_tree(),
+    };
+    let tree = commit.tree().map_err(backend_error)?;
+
+    let mut files = Vec::new();
+    parent_tree
+        .changes()
+        .map_err(backend_error)?
+        .options(|options| {
+            options.track_path().track_rewrites(None);
+        })
+        .for_each_to_obtain_tree(&tree, |change| {
+            if let Change::Addition {
+                location,
+                entry_mode,
+                ..
+            }
+            | Change::Modification {
+                location,
+                entry_mode,
+                ..
+            } = change
+                && entry_mode.is_blob()
+                && let Ok(path) = location.to_str()
+                && path.ends_with(".rs")
+                && path.starts_with(prefix)
+            {
+                files.push(path.to_string());
+            }
+            Ok(ControlFlow::Continue(()))
+        })
+        .map_err(backend_error)?;
+    Ok(files)
+}
+
+/// Directory of `repo_path` inside the work tree, with a trailing `/`
+/// (empty at the root)
+fn path_prefix(repo: &gix::Repository, repo_path: &Path) -> String {
+    let (Some(workdir), Ok(path)) = (repo.workdir(), repo_path.canonicalize()) else {
+        return String::new();
+    };
+    let Ok(workdir) = workdir.canonicalize() else {
+        return String::new();
+    };
+    match path.strip_prefix(&workdir) {
+        Ok(relative) if !relative.as_os_str().is_empty() => {
+            format!("{}/", relative.to_string_lossy().replace('\\', "/"))
+        }
+        _ => String::new(),
+    }
+}
+
+/// Oldest and newest commit time (seconds since the epoch) in `window`
+fn time_bounds(window: &GitWindow) -> Result<(Option<i64>, Option<i64>), VolatilityError> {
+    let parse = |date: &str| {
+        gix::date::parse(date, Some(gix::date::Zoned::now()))
+            .map(|time| time.seconds)
+            .map_err(|e| VolatilityError::Backend(format!("date '{}': {}", date, e)))
+    };
+    Ok(match window {
+        GitWindow::Months(months) => (Some(parse(&format!("{} months ago", months))?), None),
+        GitWindow::Dates { since, until } => (
+            since.as_deref().map(parse).transpose()?,
+            until.as_deref().map(parse).transpose()?,
+        ),
+        GitWindow::LastCommits(_) | GitWindow::SinceTag(_) => (None, None),
+    })
+}
+
+fn backend_error(error: impl std::fmt::Display) -> VolatilityError {
+    VolatilityError::Backend(error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::volatility::GitCommandBackend;
+    use std::process::Command;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args([
+                "-c",
+                "user.name=Alice",
+                "-c",
+                "user.email=alice@example.com",
+            ])
+            .args(args)
+            .current_dir(dir)
+            .env("GIT_AUTHOR_NAME", "alice")
+            .env("GIT_AUTHOR_EMAIL", "alice@example.com")
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {:?}", args);
+    }
+
+    fn commits(backend: &dyn HistoryBackend, dir: &Path, window: &GitWindow) -> Vec<CommitRecord> {
+        let mut commits = Vec::new();
+        backend
+            .read_commits(dir, window, &mut |mut commit| {
+                commit.files.sort();
+                commits.push(commit);
+            })
+            .unwrap();
+        // `git log` starts with an empty record before the first header
+        commits.retain(|c| !c.files.is_empty());
+        commits
+    }
+
+    #[test]
+    fn test_matches_git_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let write = |path: &str, content: &str| {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        git(root, &["init", "-q", "-b", "main"]);
+        write(".mailmap", "Alice Smith <alice@example.com>\n");
+        write("src/a.rs", "fn a() {}\n");
+        write("src/b.rs", "fn b() {}\n");
+        write("README.md", "readme\n");
+        git(root, &["add", "-A"]);
+        git(root, &["commit", "-q", "-m", "Add a and b"]);
+        git(root, &["tag", "v1"]);
+        write("src/a.rs", "fn a() { b() }\n");
+        write("README.md", "more\n");
+        git(root, &["commit", "-qam", "Call b from a"]);
+        git(root, &["mv", "src/b.rs", "src/c.rs"]);
+        git(root, &["commit", "-qm", "Rename b"]);
+        git(root, &["rm", "-q", "src/a.rs"]);
+        git(root, &["commit", "-qm", "Remove a"]);
+
+        for window in [
+            GitWindow::Months(6),
+            GitWindow::LastCommits(2),
+            GitWindow::SinceTag("v1".into()),
+        ] {
+            let expected = commits(&GitCommandBackend, root, &window);
+            assert_eq!(commits(&GixBackend, root, &window), expected, "{}", window);
+        }
+
+        let all = commits(&GixBackend, root, &GitWindow::Months(6));
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].files, vec!["src/c.rs"]);
+        assert_eq!(all[0].author, "Alice Smith");
+        assert_eq!(all[2].subject, "Add a and b");
+
+        assert!(matches!(
+            GixBackend.read_commits(root, &GitWindow::SinceTag("v9".into()), &mut |_| {}),
+            Err(VolatilityError::UnknownRevision(_))
+        ));
+    }
+}
@@ -0,0 +1,193 @@
+//! Dependency cycle detection
+//!
+//! Cycles are found with Tarjan's strongly connected components algorithm:
+//! every component with more than one node is a group of crates or modules
+//! that (transitively) depend on each other. Unlike enumerating individual
+//! cycles, this reports each tangle once, however many cycles it contains.
+//!
+//! Edges carry a weight (the number of references, or how costly the
+//! dependency is to remove). For each component the weakest edge is
+//! suggested as the first one to break.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Directed graph with weighted edges: source -> (target -> weight)
+pub type WeightedGraph = BTreeMap<String, BTreeMap<String, usize>>;
+
+/// An edge inside a cycle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleEdge {
+    pub from: String,
+    pub to: String,
+    pub weight: usize,
+}
+
+/// A group of nodes that depend on each other
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyCycle {
+    /// Members of the strongly connected component, sorted
+    pub members: Vec<String>,
+    /// Lowest-weight edge inside the component (ties broken by name)
+    pub weakest_edge: CycleEdge,
+}
+
+/// Tarjan's algorithm state
+struct Tarjan<'a> {
+    graph: &'a WeightedGraph,
+    index: usize,
+    indices: BTreeMap<&'a str, usize>,
+    low_links: BTreeMap<&'a str, usize>,
+    stack: Vec<&'a str>,
+    on_stack: BTreeSet<&'a str>,
+    components: Vec<Vec<String>>,
+}
+
+impl<'a> Tarjan<'a> {
+    fn connect(&mut self, node: &'a str) {
+        self.indices.insert(node, self.index);
+        self.low_links.insert(node, self.index);
+        self.index += 1;
+        self.stack.push(node);
+        self.on_stack.insert(node);
+
+        for target in self.graph.get(node).into_iter().flat_map(|t| t.keys()) {
+            if !self.indices.contains_key(target.as_str()) {
+                self.connect(target);
+                let low = self.low_links[node].min(self.low_links[target.as_str()]);
+                self.low_links.insert(node, low);
+            } else if self.on_stack.contains(target.as_str()) {
+                let low = self.low_links[node].min(self.indices[target.as_str()]);
+                self.low_links.insert(node, low);
+            }
+        }
+
+        if self.low_links[node] == self.indices[node] {
+            let mut component = Vec::new();
+            while let Some(member) = self.stack.pop() {
+                self.on_stack.remove(member);
+                component.push(member.to_string());
+                if member == node {
+                    break;
+                }
+            }
+            component.sort();
+            self.components.push(component);
+        }
+    }
+}
+
+/// Strongly connected components with more than one node, sorted by their
+/// first member
+pub fn strongly_connected_components(graph: &WeightedGraph) -> Vec<Vec<String>> {
+    let mut tarjan = Tarjan {
+        graph,
+        index: 0,
+        indices: BTreeMap::new(),
+        low_links: BTreeMap::new(),
+        stack: Vec::new(),
+        on_stack: BTreeSet::new(),
+        components: Vec::new(),
+    };
+    for node in graph.keys() {
+        if !tarjan.indices.contains_key(node.as_str()) {
+            tarjan.connect(node);
+        }
+    }
+
+    let mut components: Vec<Vec<String>> = tarjan
+        .components
+        .into_iter()
+        .filter(|c| c.len() > 1)
+        .collect();
+    components.sort();
+    components
+}
+
+/// Find every dependency cycle and its weakest edge
+pub fn find_cycles(graph: &WeightedGraph) -> Vec<DependencyCycle> {
+    strongly_connected_components(graph)
+        .into_iter()
+        .filter_map(|members| {
+            let in_cycle: BTreeSet<&str> = members.iter().map(String::as_str).collect();
+            let weakest_edge = members
+                .iter()
+                .flat_map(|from| {
+                    graph
+                        .get(from)
+                        .into_iter()
+                        .flatten()
+                        .filter(|(to, _)| in_cycle.contains(to.as_str()))
+                        .map(move |(to, &weight)| CycleEdge {
+                            from: from.clone(),
+                            to: to.clone(),
+                            weight,
+                        })
+                })
+                .min_by(|a, b| {
+                    a.weight
+                        .cmp(&b.weight)
+                        .then_with(|| a.from.cmp(&b.from))
+                        .then_with(|| a.to.cmp(&b.to))
+                })?;
+            Some(DependencyCycle {
+                members,
+                weakest_edge,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weighted(edges: &[(&str, &str, usize)]) -> WeightedGraph {
+        let mut graph = WeightedGraph::new();
+        for &(from, to, weight) in edges {
+            graph
+                .entry(from.to_string())
+                .or_default()
+                .insert(to.to_string(), weight);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        let graph = weighted(&[
+            ("a", "b", 1),
+            ("b", "c", 1),
+            ("c", "a", 1),
+            ("c", "d", 1),
+            ("d", "e", 1),
+            ("e", "d", 1),
+            ("e", "f", 1),
+        ]);
+        assert_eq!(
+            strongly_connected_components(&graph),
+            vec![vec!["a", "b", "c"], vec!["d", "e"]]
+        );
+    }
+
+    #[test]
+    fn test_weakest_edge() {
+        let graph = weighted(&[
+            ("api", "db", 5),
+            ("db", "model", 3),
+            ("model", "api", 1),
+            ("model", "util", 1),
+        ]);
+        let cycles = find_cycles(&graph);
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].members, vec!["api", "db", "model"]);
+        assert_eq!(
+            cycles[0].weakest_edge,
+            CycleEdge {
+                from: "model".into(),
+                to: "api".into(),
+                weight: 1
+            }
+        );
+        assert!(find_cycles(&weighted(&[("a", "b", 1)])).is_empty());
+    }
+}
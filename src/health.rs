@@ -0,0 +1,267 @@
+//! Project health score
+//!
+//! Combines the analyzers into one number for dashboards and badges. Each
+//! component is normalized to 0-100 (higher is healthier):
+//!
+//! - **balance**: average balance score of internal couplings
+//! - **issues**: 100 minus the severity-weighted issues per internal
+//!   coupling (Critical 1.0, High 0.5, Medium 0.2, Low 0.05), floored at 0
+//! - **depth**: share of modules with a public interface that are not
+//!   shallow (see [`crate::aposd`])
+//! - **cycles**: share of modules outside module dependency cycles
+//!
+//! The project score is the weighted mean of the components (weights from
+//! the `[health]` config section), and the grade follows from the score:
+//! A from 90, B from 80, C from 70, D from 60, F below. Other scores can be
+//! combined the same way with [`HealthScore::combine`].
+
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::aposd::{AposdConfig, DepthClass, analyze_module_depths_with};
+use crate::balance::{
+    HealthGrade, IssueThresholds, Severity, analyze_project_balance_with_thresholds,
+};
+use crate::metrics::{Distance, ProjectMetrics};
+
+/// Component weights (the `[health]` config section)
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct HealthWeights {
+    pub balance: f64,
+    pub issues: f64,
+    pub depth: f64,
+    pub cycles: f64,
+}
+
+impl Default for HealthWeights {
+    fn default() -> Self {
+        Self {
+            balance: 0.4,
+            issues: 0.3,
+            depth: 0.15,
+            cycles: 0.15,
+        }
+    }
+}
+
+/// One normalized input of the health score
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScoreComponent {
+    pub name: String,
+    /// Score from 0 (worst) to 100 (best)
+    pub score: f64,
+    pub weight: f64,
+    /// What the score was computed from
+    pub detail: String,
+}
+
+impl ScoreComponent {
+    pub fn new(name: impl Into<String>, score: f64, weight: f64, detail: String) -> Self {
+        Self {
+            name: name.into(),
+            score: score.clamp(0.0, 100.0),
+            weight,
+            detail,
+        }
+    }
+}
+
+/// Weighted project score with its breakdown
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HealthScore {
+    /// Weighted mean of the components, 0-100
+    pub score: f64,
+    #[serde(serialize_with = "serialize_grade")]
+    pub grade: HealthGrade,
+    pub components: Vec<ScoreComponent>,
+}
+
+fn serialize_grade<S: serde::Serializer>(grade: &HealthGrade, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(&format!("{:?}", grade))
+}
+
+impl HealthScore {
+    /// Score a project with the built-in components
+    pub fn calculate(
+        metrics: &ProjectMetrics,
+        thresholds: &IssueThresholds,
+        aposd: &AposdConfig,
+        weights: &HealthWeights,
+    ) -> Self {
+        let report = analyze_project_balance_with_thresholds(metrics, thresholds);
+        let internal = metrics
+            .couplings
+            .iter()
+            .filter(|c| c.distance != Distance::DifferentCrate)
+            .count();
+
+        let severity_count = |severity| *report.issues_by_severity.get(&severity).unwrap_or(&0);
+        let weighted_issues = severity_count(Severity::Critical) as f64
+            + severity_count(Severity::High) as f64 * 0.5
+            + severity_count(Severity::Medium) as f64 * 0.2
+            + severity_count(Severity::Low) as f64 * 0.05;
+        let issue_density = if internal == 0 {
+            0.0
+        } else {
+            weighted_issues / internal as f64
+        };
+
+        let depths = analyze_module_depths_with(metrics, aposd);
+        let shallow = depths
+            .iter()
+            .filter(|d| d.class == DepthClass::Shallow)
+            .count();
+
+        let in_cycles: BTreeSet<String> = metrics
+            .module_cycles()
+            .into_iter()
+            .flat_map(|c| c.members)
+            .collect();
+        let modules = metrics.modules.len();
+
+        Self::combine(vec![
+            ScoreComponent::new(
+                "balance",
+                report.average_score * 100.0,
+                weights.balance,
+                format!(
+                    "average balance {:.2} over {} internal couplings",
+                    report.average_score, internal
+                ),
+            ),
+            ScoreComponent::new(
+                "issues",
+                100.0 * (1.0 - issue_density),
+                weights.issues,
+                format!(
+                    "{} issues, {:.2} weighted per coupling",
+                    report.issues.len(),
+                    issue_density
+                ),
+            ),
+            ScoreComponent::new(
+                "depth",
+                100.0 * share(depths.len() - shallow, depths.len()),
+                weights.depth,
+                format!("{} of {} modules shallow", shallow, depths.len()),
+            ),
+            ScoreComponent::new(
+                "cycles",
+                100.0 * share(modules.saturating_sub(in_cycles.len()), modules),
+                weights.cycles,
+                format!("{} of {} modules in cycles", in_cycles.len(), modules),
+            ),
+        ])
+    }
+
+    /// Weighted mean of `components`; components without weight are shown
+    /// but do not count
+    pub fn combine(components: Vec<ScoreComponent>) -> Self {
+        let total_weight: f64 = components.iter().map(|c| c.weight.max(0.0)).sum();
+        let score = if total_weight > 0.0 {
+            components
+                .iter()
+                .map(|c| c.score * c.weight.max(0.0))
+                .sum::<f64>()
+                / total_weight
+        } else {
+            100.0
+        };
+        Self {
+            score,
+            grade: grade_for(score),
+            components,
+        }
+    }
+}
+
+/// `part / whole`, or 1.0 when there is nothing to measure
+fn share(part: usize, whole: usize) -> f64 {
+    if whole == 0 {
+        1.0
+    } else {
+        part as f64 / whole as f64
+    }
+}
+
+/// Grade for a 0-100 score
+pub fn grade_for(score: f64) -> HealthGrade {
+    match score {
+        s if s >= 90.0 => HealthGrade::A,
+        s if s >= 80.0 => HealthGrade::B,
+        s if s >= 70.0 => HealthGrade::C,
+        s if s >= 60.0 => HealthGrade::D,
+        _ => HealthGrade::F,
+    }
+}
+
+/// Generate the health score breakdown to writer
+pub fn generate_health_report<W: Write>(health: &HealthScore, writer: &mut W) -> io::Result<()> {
+    writeln!(writer, "Project Health")?;
+    writeln!(writer, "==============")?;
+    writeln!(writer)?;
+    writeln!(
+        writer,
+        "Score: {:.0}/100 | Grade: {}",
+        health.score, health.grade
+    )?;
+    writeln!(writer)?;
+    writeln!(writer, "| Component | Score | Weight | Based on |")?;
+    writeln!(writer, "|-----------|------:|-------:|----------|")?;
+    for component in &health.components {
+        writeln!(
+            writer,
+            "| {} | {:.0} | {:.2} | {} |",
+            component.name, component.score, component.weight, component.detail
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::ModuleMetrics;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_combine_weights_components() {
+        let health = HealthScore::combine(vec![
+            ScoreComponent::new("a", 100.0, 3.0, String::new()),
+            ScoreComponent::new("b", 140.0, 0.0, String::new()),
+            ScoreComponent::new("c", 60.0, 1.0, String::new()),
+        ]);
+        assert_eq!(health.score, 90.0);
+        assert_eq!(health.grade, HealthGrade::A);
+        assert_eq!(health.components[1].score, 100.0);
+        assert_eq!(grade_for(59.9), HealthGrade::F);
+        assert_eq!(HealthScore::combine(Vec::new()).score, 100.0);
+    }
+
+    #[test]
+    fn test_empty_project_is_healthy() {
+        let mut metrics = ProjectMetrics::new();
+        metrics.add_module(ModuleMetrics::new(
+            PathBuf::from("src/lib.rs"),
+            "lib".into(),
+        ));
+        let health = HealthScore::calculate(
+            &metrics,
+            &IssueThresholds::default(),
+            &AposdConfig::default(),
+            &HealthWeights::default(),
+        );
+        assert_eq!(health.score, 100.0);
+        let names: Vec<&str> = health.components.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["balance", "issues", "depth", "cycles"]);
+
+        let mut out = Vec::new();
+        generate_health_report(&health, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("Score: 100/100 | Grade: A (Well-balanced)"));
+        assert!(text.contains("| cycles | 100 | 0.15 | 0 of 1 modules in cycles |"));
+    }
+}
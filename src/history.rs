@@ -0,0 +1,372 @@
+//! Trend tracking across runs
+//!
+//! `--record-history` appends one JSON line per run to a history file
+//! (`.coupling-history.jsonl` by default) with the run's summary metrics,
+//! a timestamp and the current git commit. `--trend` reads the file back and
+//! prints the metrics over time as a table with sparklines.
+//!
+//! JSONL keeps the file append-only and easy to commit or diff; lines that
+//! cannot be parsed are reported with their line number.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::aposd::{analyze_module_depths_with, average_depth_ratio};
+use crate::balance::{
+    HealthGrade, IssueThresholds, IssueType, analyze_project_balance_with_thresholds,
+};
+use crate::metrics::ProjectMetrics;
+
+/// Default history file name
+pub const DEFAULT_HISTORY_FILE: &str = ".coupling-history.jsonl";
+
+/// Number of most recent runs shown by the trend report
+pub const TREND_ROWS: usize = 20;
+
+/// Errors that can occur while reading or writing the history
+#[derive(Error, Debug)]
+pub enum HistoryError {
+    #[error("Failed to access history file: {0}")]
+    IoError(#[from] io::Error),
+
+    #[error("Invalid history entry on line {0}: {1}")]
+    ParseError(usize, serde_json::Error),
+
+    #[error("Failed to serialize history entry: {0}")]
+    SerializeError(#[from] serde_json::Error),
+}
+
+/// Summary metrics of one run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    /// Commit the run analyzed (`None` outside a git repository)
+    pub git_sha: Option<String>,
+    pub modules: usize,
+    pub couplings: usize,
+    pub average_score: f64,
+    pub grade: HealthGrade,
+    /// Findings of every severity
+    pub issues: usize,
+    pub average_depth_ratio: Option<f64>,
+    /// Connascence findings weighted by their Page-Jones rank
+    /// (Name = 1 ... Identity = 9)
+    pub connascence_strength: usize,
+    pub temporal_issues: usize,
+    /// Average volatility of all couplings (0.0 - 1.0)
+    pub average_volatility: Option<f64>,
+}
+
+impl HistoryEntry {
+    /// Summarize an analysis
+    pub fn capture(
+        metrics: &ProjectMetrics,
+        thresholds: &IssueThresholds,
+        timestamp: u64,
+        git_sha: Option<String>,
+    ) -> Self {
+        let all_issues = IssueThresholds {
+            strict_mode: false,
+            baseline: None,
+            ..thresholds.clone()
+        };
+        let report = analyze_project_balance_with_thresholds(metrics, &all_issues);
+        let depths = analyze_module_depths_with(metrics, &thresholds.aposd);
+
+        let connascence_strength = report
+            .issues
+            .iter()
+            .filter_map(|i| i.issue_type.connascence_type())
+            .map(|c| c as usize + 1)
+            .sum();
+        let temporal_issues = report
+            .issues
+            .iter()
+            .filter(|i| {
+                matches!(
+                    i.issue_type,
+                    IssueType::UnpairedOperation
+                        | IssueType::DroppedGuard
+                        | IssueType::PanickingBuilder
+                        | IssueType::PanicPath
+                        | IssueType::CancellationUnsafeSelect
+                        | IssueType::DetachedSpawn
+                        | IssueType::LockOrderInversion
+                        | IssueType::DropOrderCoupling
+                )
+            })
+            .count();
+        let average_volatility = (!metrics.couplings.is_empty()).then(|| {
+            metrics
+                .couplings
+                .iter()
+                .map(|c| c.volatility_value())
+                .sum::<f64>()
+                / metrics.couplings.len() as f64
+        });
+
+        Self {
+            timestamp,
+            git_sha,
+            modules: metrics.module_count(),
+            couplings: metrics.couplings.len(),
+            average_score: report.average_score,
+            grade: report.health_grade,
+            issues: report.issues.len(),
+            average_depth_ratio: average_depth_ratio(&depths),
+            connascence_strength,
+            temporal_issues,
+            average_volatility,
+        }
+    }
+}
+
+/// Current time in seconds since the Unix epoch
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Commit checked out at `path`, if it is inside a git repository
+pub fn current_git_sha(path: &Path) -> Option<String> {
+    let dir = if path.is_dir() {
+        path
+    } else {
+        path.parent().unwrap_or(Path::new("."))
+    };
+    let _span = tracing::debug_span!("git", args = "rev-parse HEAD").entered();
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Append an entry to the history file, creating it if needed
+pub fn append_entry(path: &Path, entry: &HistoryEntry) -> Result<(), HistoryError> {
+    let line = serde_json::to_string(entry)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)?;
+    Ok(())
+}
+
+/// Load all entries of a history file (empty if it does not exist yet)
+pub fn load_history(path: &Path) -> Result<Vec<HistoryEntry>, HistoryError> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| serde_json::from_str(line).map_err(|e| HistoryError::ParseError(i + 1, e)))
+        .collect()
+}
+
+/// Render values as a sparkline scaled between their minimum and maximum
+pub fn sparkline(values: &[f64]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    values
+        .iter()
+        .map(|&v| {
+            if (max - min).abs() < f64::EPSILON {
+                BARS[0]
+            } else {
+                BARS[(((v - min) / (max - min)) * 7.0).round() as usize]
+            }
+        })
+        .collect()
+}
+
+/// Format a Unix timestamp as `YYYY-MM-DD` (UTC)
+fn format_date(timestamp: u64) -> String {
+    // Civil-from-days (Howard Hinnant's algorithm)
+    let days = (timestamp / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+fn format_optional(value: Option<f64>) -> String {
+    value.map_or("-".to_string(), |v| format!("{:.2}", v))
+}
+
+/// Generate the trend report to writer
+pub fn generate_trend_report<W: Write>(entries: &[HistoryEntry], writer: &mut W) -> io::Result<()> {
+    writeln!(writer, "Coupling Trend")?;
+    writeln!(writer, "==============")?;
+    writeln!(writer)?;
+
+    if entries.is_empty() {
+        writeln!(
+            writer,
+            "No history recorded yet. Run with --record-history to start tracking."
+        )?;
+        return Ok(());
+    }
+
+    let recent = &entries[entries.len().saturating_sub(TREND_ROWS)..];
+    writeln!(
+        writer,
+        "{:<10}  {:<8}  {:>5}  {:>5}  {:>6}  {:>5}  {:>11}  {:>8}  {:>10}",
+        "Date",
+        "Commit",
+        "Grade",
+        "Score",
+        "Issues",
+        "Depth",
+        "Connascence",
+        "Temporal",
+        "Volatility"
+    )?;
+    writeln!(writer, "{}", "-".repeat(88))?;
+    for entry in recent {
+        let sha = entry.git_sha.as_deref().unwrap_or("-");
+        writeln!(
+            writer,
+            "{:<10}  {:<8}  {:>5}  {:>5.2}  {:>6}  {:>5}  {:>11}  {:>8}  {:>10}",
+            format_date(entry.timestamp),
+            &sha[..sha.len().min(8)],
+            entry.grade.letter(),
+            entry.average_score,
+            entry.issues,
+            format_optional(entry.average_depth_ratio),
+            entry.connascence_strength,
+            entry.temporal_issues,
+            format_optional(entry.average_volatility),
+        )?;
+    }
+
+    if recent.len() > 1 {
+        writeln!(writer)?;
+        let series: [(&str, Vec<f64>); 6] = [
+            ("Score", recent.iter().map(|e| e.average_score).collect()),
+            ("Issues", recent.iter().map(|e| e.issues as f64).collect()),
+            (
+                "Depth",
+                recent
+                    .iter()
+                    .map(|e| e.average_depth_ratio.unwrap_or(0.0))
+                    .collect(),
+            ),
+            (
+                "Connascence",
+                recent
+                    .iter()
+                    .map(|e| e.connascence_strength as f64)
+                    .collect(),
+            ),
+            (
+                "Temporal",
+                recent.iter().map(|e| e.temporal_issues as f64).collect(),
+            ),
+            (
+                "Volatility",
+                recent
+                    .iter()
+                    .map(|e| e.average_volatility.unwrap_or(0.0))
+                    .collect(),
+            ),
+        ];
+        for (name, values) in &series {
+            writeln!(writer, "{:<12} {}", name, sparkline(values))?;
+        }
+    }
+
+    writeln!(writer)?;
+    writeln!(
+        writer,
+        "{} run(s) recorded, showing the last {}",
+        entries.len(),
+        recent.len()
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(timestamp: u64, issues: usize) -> HistoryEntry {
+        HistoryEntry {
+            timestamp,
+            git_sha: Some("0123456789abcdef".into()),
+            modules: 10,
+            couplings: 50,
+            average_score: 0.8,
+            grade: HealthGrade::B,
+            issues,
+            average_depth_ratio: Some(4.5),
+            connascence_strength: 7,
+            temporal_issues: 1,
+            average_volatility: None,
+        }
+    }
+
+    #[test]
+    fn test_append_and_load_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(DEFAULT_HISTORY_FILE);
+        assert!(load_history(&path).unwrap().is_empty());
+
+        append_entry(&path, &entry(1_700_000_000, 3)).unwrap();
+        append_entry(&path, &entry(1_700_086_400, 5)).unwrap();
+        let entries = load_history(&path).unwrap();
+        assert_eq!(
+            entries,
+            vec![entry(1_700_000_000, 3), entry(1_700_086_400, 5)]
+        );
+        assert!(
+            fs::read_to_string(&path)
+                .unwrap()
+                .contains("\"grade\":\"B\"")
+        );
+
+        fs::write(&path, "{\"timestamp\": 1}\n").unwrap();
+        assert!(matches!(
+            load_history(&path),
+            Err(HistoryError::ParseError(1, _))
+        ));
+    }
+
+    #[test]
+    fn test_trend_report() {
+        assert_eq!(sparkline(&[1.0, 8.0, 4.5]), "▁█▅");
+        assert_eq!(sparkline(&[2.0, 2.0]), "▁▁");
+        assert_eq!(format_date(1_700_000_000), "2023-11-14");
+
+        let mut out = Vec::new();
+        generate_trend_report(
+            &[entry(1_700_000_000, 3), entry(1_700_086_400, 5)],
+            &mut out,
+        )
+        .unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("2023-11-15  01234567      B   0.80       5   4.50"));
+        assert!(text.contains("Issues       ▁█"));
+        assert!(text.contains("2 run(s) recorded"));
+    }
+}
@@ -0,0 +1,506 @@
+//! Self-contained HTML report
+//!
+//! Renders a single HTML file (inline CSS and JavaScript, no external
+//! assets) with the issue list, coupling matrix, module table, temporal
+//! findings and volatility hotspots. Every table can be sorted by clicking
+//! a column header and filtered with the search box above it, which makes
+//! the file convenient to attach to CI runs as a build artifact.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::balance::{IssueThresholds, analyze_project_balance_with_thresholds};
+use crate::metrics::{Distance, IntegrationStrength, ProjectMetrics, Volatility};
+use crate::temporal::TemporalIssueKind;
+
+/// Maximum number of rows in the volatility hotspot table
+const MAX_VOLATILITY_HOTSPOTS: usize = 25;
+
+/// Escape text for safe inclusion in HTML element content and attributes
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Depth of a module file below its crate's `src` directory
+/// (`src/lib.rs` → 0, `src/web/graph.rs` → 1)
+fn module_depth(path: &Path) -> usize {
+    let components: Vec<_> = path.components().collect();
+    let after_src = components
+        .iter()
+        .rposition(|c| c.as_os_str() == "src")
+        .map(|i| components.len() - i - 1)
+        .unwrap_or(components.len());
+    after_src.saturating_sub(1)
+}
+
+/// Short label for an integration strength
+fn strength_label(strength: IntegrationStrength) -> &'static str {
+    match strength {
+        IntegrationStrength::Intrusive => "Intrusive",
+        IntegrationStrength::Functional => "Functional",
+        IntegrationStrength::Model => "Model",
+        IntegrationStrength::Contract => "Contract",
+    }
+}
+
+/// Write a table header row with sortable columns
+fn write_table_start<W: Write>(writer: &mut W, id: &str, headers: &[&str]) -> io::Result<()> {
+    writeln!(
+        writer,
+        r#"<input class="filter" type="search" placeholder="Filter…" data-table="{id}">"#
+    )?;
+    writeln!(writer, r#"<table id="{id}" class="sortable"><thead><tr>"#)?;
+    for header in headers {
+        writeln!(writer, "<th>{}</th>", escape_html(header))?;
+    }
+    writeln!(writer, "</tr></thead><tbody>")
+}
+
+/// Write a single table row; every cell is escaped
+fn write_row<W: Write>(writer: &mut W, class: &str, cells: &[String]) -> io::Result<()> {
+    if class.is_empty() {
+        write!(writer, "<tr>")?;
+    } else {
+        write!(writer, r#"<tr class="{class}">"#)?;
+    }
+    for cell in cells {
+        write!(writer, "<td>{}</td>", escape_html(cell))?;
+    }
+    writeln!(writer, "</tr>")
+}
+
+/// Generate a self-contained HTML report to writer
+pub fn generate_html_output<W: Write>(
+    metrics: &ProjectMetrics,
+    thresholds: &IssueThresholds,
+    writer: &mut W,
+) -> io::Result<()> {
+    let report = analyze_project_balance_with_thresholds(metrics, thresholds);
+    let title = metrics
+        .workspace_name
+        .as_deref()
+        .map(|name| format!("Coupling Report: {}", name))
+        .unwrap_or_else(|| "Coupling Report".to_string());
+
+    writeln!(writer, "<!DOCTYPE html>")?;
+    writeln!(writer, r#"<html lang="en"><head><meta charset="utf-8">"#)?;
+    writeln!(writer, "<title>{}</title>", escape_html(&title))?;
+    writeln!(writer, "<style>{}</style>", STYLE)?;
+    writeln!(writer, "</head><body>")?;
+    writeln!(writer, "<h1>{}</h1>", escape_html(&title))?;
+
+    // Summary
+    writeln!(writer, r#"<section class="summary">"#)?;
+    for (label, value) in [
+        ("Health Grade", report.health_grade.to_string()),
+        ("Balance Score", format!("{:.2}", report.average_score)),
+        ("Files", metrics.total_files.to_string()),
+        ("Modules", metrics.module_count().to_string()),
+        ("Couplings", metrics.coupling_count().to_string()),
+        ("Issues", report.issues.len().to_string()),
+    ] {
+        writeln!(
+            writer,
+            r#"<div class="card"><div class="value">{}</div><div class="label">{}</div></div>"#,
+            escape_html(&value),
+            label
+        )?;
+    }
+    writeln!(writer, "</section>")?;
+
+    // Issues
+    writeln!(writer, "<h2>Issues</h2>")?;
+    write_table_start(
+        writer,
+        "issues",
+        &[
+            "Severity",
+            "Type",
+            "Source",
+            "Target",
+            "Location",
+            "Description",
+        ],
+    )?;
+    for issue in &report.issues {
+        let location = if issue.location.file_path.is_some() {
+            metrics.relative_location(&issue.location)
+        } else {
+            String::new()
+        };
+        write_row(
+            writer,
+            &format!("sev-{}", issue.severity.to_string().to_lowercase()),
+            &[
+                issue.severity.to_string(),
+                issue.issue_type.to_string(),
+                issue.source.clone(),
+                issue.target.clone(),
+                location,
+                issue.description.clone(),
+            ],
+        )?;
+    }
+    writeln!(writer, "</tbody></table>")?;
+
+    write_coupling_matrix(metrics, writer)?;
+    write_module_table(metrics, writer)?;
+    write_temporal_table(metrics, writer)?;
+    write_volatility_hotspots(metrics, writer)?;
+
+    writeln!(writer, "<script>{}</script>", SCRIPT)?;
+    writeln!(writer, "</body></html>")?;
+    Ok(())
+}
+
+/// Internal module-to-module matrix; each cell shows the number of
+/// couplings and is colored by the strongest one
+fn write_coupling_matrix<W: Write>(metrics: &ProjectMetrics, writer: &mut W) -> io::Result<()> {
+    let mut cells: BTreeMap<(&str, &str), (usize, IntegrationStrength)> = BTreeMap::new();
+    let mut modules: BTreeSet<&str> = BTreeSet::new();
+    for coupling in &metrics.couplings {
+        if coupling.distance == Distance::DifferentCrate {
+            continue;
+        }
+        modules.insert(&coupling.source);
+        modules.insert(&coupling.target);
+        let cell = cells
+            .entry((&coupling.source, &coupling.target))
+            .or_insert((0, coupling.strength));
+        cell.0 += 1;
+        if coupling.strength.value() > cell.1.value() {
+            cell.1 = coupling.strength;
+        }
+    }
+
+    writeln!(writer, "<h2>Coupling Matrix</h2>")?;
+    if modules.is_empty() {
+        return writeln!(writer, "<p>No internal couplings.</p>");
+    }
+    writeln!(
+        writer,
+        "<p>Rows depend on columns. Cells show the number of couplings, colored by the strongest integration strength.</p>"
+    )?;
+    writeln!(
+        writer,
+        r#"<div class="matrix-wrap"><table class="matrix"><thead><tr><th></th>"#
+    )?;
+    for target in &modules {
+        writeln!(
+            writer,
+            r#"<th class="col"><span>{}</span></th>"#,
+            escape_html(target)
+        )?;
+    }
+    writeln!(writer, "</tr></thead><tbody>")?;
+    for source in &modules {
+        write!(writer, "<tr><th>{}</th>", escape_html(source))?;
+        for target in &modules {
+            match cells.get(&(*source, *target)) {
+                Some((count, strength)) => write!(
+                    writer,
+                    r#"<td class="str-{}" title="{} → {}: {} ({})">{}</td>"#,
+                    strength_label(*strength).to_lowercase(),
+                    escape_html(source),
+                    escape_html(target),
+                    count,
+                    strength_label(*strength),
+                    count
+                )?,
+                None => write!(writer, "<td></td>")?,
+            }
+        }
+        writeln!(writer, "</tr>")?;
+    }
+    writeln!(writer, "</tbody></table></div>")
+}
+
+/// Per-module size, depth and coupling counts
+fn write_module_table<W: Write>(metrics: &ProjectMetrics, writer: &mut W) -> io::Result<()> {
+    let mut efferent: HashMap<&str, usize> = HashMap::new();
+    let mut afferent: HashMap<&str, usize> = HashMap::new();
+    for coupling in &metrics.couplings {
+        if coupling.distance == Distance::DifferentCrate {
+            continue;
+        }
+        let source = coupling
+            .source
+            .rsplit("::")
+            .next()
+            .unwrap_or(&coupling.source);
+        let target = coupling
+            .target
+            .rsplit("::")
+            .next()
+            .unwrap_or(&coupling.target);
+        *efferent.entry(source).or_default() += 1;
+        *afferent.entry(target).or_default() += 1;
+    }
+
+    writeln!(writer, "<h2>Modules</h2>")?;
+    write_table_start(
+        writer,
+        "modules",
+        &[
+            "Module",
+            "File",
+            "Depth",
+            "Functions",
+            "Types",
+            "Impls",
+            "Efferent (Ce)",
+            "Afferent (Ca)",
+        ],
+    )?;
+    let mut modules: Vec<_> = metrics.modules.values().collect();
+    modules.sort_by(|a, b| a.name.cmp(&b.name));
+    for module in modules {
+        write_row(
+            writer,
+            "",
+            &[
+                module.name.clone(),
+                metrics.relative_path(&module.path),
+                module_depth(&module.path).to_string(),
+                module.function_count().to_string(),
+                module.type_definitions.len().to_string(),
+                (module.trait_impl_count + module.inherent_impl_count).to_string(),
+                efferent
+                    .get(module.name.as_str())
+                    .copied()
+                    .unwrap_or(0)
+                    .to_string(),
+                afferent
+                    .get(module.name.as_str())
+                    .copied()
+                    .unwrap_or(0)
+                    .to_string(),
+            ],
+        )?;
+    }
+    writeln!(writer, "</tbody></table>")
+}
+
+/// Temporal coupling findings from every module
+fn write_temporal_table<W: Write>(metrics: &ProjectMetrics, writer: &mut W) -> io::Result<()> {
+    writeln!(writer, "<h2>Temporal Issues</h2>")?;
+    write_table_start(
+        writer,
+        "temporal",
+        &["Kind", "Operation", "Location", "Function", "Message"],
+    )?;
+    let mut modules: Vec<_> = metrics.modules.values().collect();
+    modules.sort_by(|a, b| a.path.cmp(&b.path));
+    for module in modules {
+        for issue in &module.temporal.issues {
+            let kind = match issue.kind {
+                TemporalIssueKind::UnpairedOperation => "Unpaired Operation",
+                TemporalIssueKind::DroppedGuard => "Dropped Guard",
+                TemporalIssueKind::PanickingBuilder => "Panicking Builder",
+                TemporalIssueKind::PanicPath => "Panic Path",
+                TemporalIssueKind::CancellationUnsafeSelect => "Cancellation-Unsafe Select",
+                TemporalIssueKind::DetachedSpawn => "Detached Spawn",
+                TemporalIssueKind::LockOrderInversion => "Lock Order Inversion",
+                TemporalIssueKind::DropOrder => "Drop Order",
+            };
+            write_row(
+                writer,
+                "",
+                &[
+                    kind.to_string(),
+                    issue.operation.clone(),
+                    format!("{}:{}", metrics.relative_path(&module.path), issue.line),
+                    issue.function.clone().unwrap_or_default(),
+                    issue.message.clone(),
+                ],
+            )?;
+        }
+    }
+    writeln!(writer, "</tbody></table>")
+}
+
+/// Most frequently changed files, with how many modules depend on them
+fn write_volatility_hotspots<W: Write>(metrics: &ProjectMetrics, writer: &mut W) -> io::Result<()> {
+    writeln!(writer, "<h2>Volatility Hotspots</h2>")?;
+    if metrics.file_changes.is_empty() {
+        return writeln!(
+            writer,
+            "<p>No git history available (run without <code>--no-git</code> inside a git repository).</p>"
+        );
+    }
+
+    let mut dependents: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+    for coupling in &metrics.couplings {
+        if coupling.distance == Distance::DifferentCrate {
+            continue;
+        }
+        let target = coupling
+            .target
+            .rsplit("::")
+            .next()
+            .unwrap_or(&coupling.target);
+        dependents
+            .entry(target)
+            .or_default()
+            .insert(coupling.source.as_str());
+    }
+
+    let mut files: Vec<(&String, &usize)> = metrics.file_changes.iter().collect();
+    files.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+
+    write_table_start(
+        writer,
+        "volatility",
+        &["File", "Changes", "Volatility", "Dependents", "Risk"],
+    )?;
+    for (file, &changes) in files.into_iter().take(MAX_VOLATILITY_HOTSPOTS) {
+        let stem = Path::new(file)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or(file);
+        let dependent_count = dependents.get(stem).map_or(0, |d| d.len());
+        let volatility = Volatility::from_count(changes);
+        write_row(
+            writer,
+            if volatility == Volatility::High {
+                "sev-high"
+            } else {
+                ""
+            },
+            &[
+                file.clone(),
+                changes.to_string(),
+                format!("{:?}", volatility),
+                dependent_count.to_string(),
+                (changes * dependent_count).to_string(),
+            ],
+        )?;
+    }
+    writeln!(writer, "</tbody></table>")
+}
+
+const STYLE: &str = r#"
+body { font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; margin: 2rem; color: #222; }
+h1 { margin-bottom: 0.5rem; }
+h2 { margin-top: 2.5rem; border-bottom: 1px solid #ddd; padding-bottom: 0.25rem; }
+.summary { display: flex; gap: 1rem; flex-wrap: wrap; }
+.card { border: 1px solid #ddd; border-radius: 6px; padding: 0.75rem 1.25rem; min-width: 7rem; }
+.card .value { font-size: 1.5rem; font-weight: bold; }
+.card .label { color: #666; font-size: 0.85rem; }
+table { border-collapse: collapse; font-size: 0.9rem; }
+th, td { border: 1px solid #ddd; padding: 0.3rem 0.6rem; text-align: left; }
+table.sortable th { cursor: pointer; background: #f6f8fa; user-select: none; }
+table.sortable th.asc::after { content: " ▲"; }
+table.sortable th.desc::after { content: " ▼"; }
+.filter { margin: 0.5rem 0; padding: 0.3rem; width: 20rem; }
+tr.sev-critical td:first-child { background: #d73a49; color: #fff; }
+tr.sev-high td:first-child { background: #f66a0a; color: #fff; }
+tr.sev-medium td:first-child { background: #ffd33d; }
+tr.sev-low td:first-child { background: #e1e4e8; }
+.matrix-wrap { overflow: auto; max-height: 80vh; }
+table.matrix td { text-align: center; min-width: 1.5rem; }
+table.matrix th.col { vertical-align: bottom; }
+table.matrix th.col span { writing-mode: vertical-rl; transform: rotate(180deg); }
+td.str-intrusive { background: #d73a49; color: #fff; }
+td.str-functional { background: #f9826c; }
+td.str-model { background: #ffdf5d; }
+td.str-contract { background: #85e89d; }
+"#;
+
+const SCRIPT: &str = r#"
+document.querySelectorAll("table.sortable").forEach(function (table) {
+  table.querySelectorAll("th").forEach(function (th, index) {
+    th.addEventListener("click", function () {
+      var asc = !th.classList.contains("asc");
+      table.querySelectorAll("th").forEach(function (h) { h.classList.remove("asc", "desc"); });
+      th.classList.add(asc ? "asc" : "desc");
+      var body = table.tBodies[0];
+      var rows = Array.from(body.rows);
+      rows.sort(function (a, b) {
+        var x = a.cells[index].textContent, y = b.cells[index].textContent;
+        var nx = parseFloat(x), ny = parseFloat(y);
+        var cmp = (!isNaN(nx) && !isNaN(ny)) ? nx - ny : x.localeCompare(y);
+        return asc ? cmp : -cmp;
+      });
+      rows.forEach(function (row) { body.appendChild(row); });
+    });
+  });
+});
+document.querySelectorAll("input.filter").forEach(function (input) {
+  input.addEventListener("input", function () {
+    var needle = input.value.toLowerCase();
+    var table = document.getElementById(input.dataset.table);
+    Array.from(table.tBodies[0].rows).forEach(function (row) {
+      row.style.display = row.textContent.toLowerCase().includes(needle) ? "" : "none";
+    });
+  });
+});
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{CouplingMetrics, ModuleMetrics};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(
+            escape_html(r#"<a href="x">Vec<T> & 'b'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Vec&lt;T&gt; &amp; &#39;b&#39;&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn test_module_depth() {
+        assert_eq!(module_depth(Path::new("./src/lib.rs")), 0);
+        assert_eq!(module_depth(Path::new("crates/web/src/routes/api.rs")), 1);
+    }
+
+    #[test]
+    fn test_html_report_sections() {
+        let mut project = ProjectMetrics::new();
+        project.add_module(ModuleMetrics::new(
+            PathBuf::from("src/a.rs"),
+            "a".to_string(),
+        ));
+        project.add_coupling(CouplingMetrics::new(
+            "a".to_string(),
+            "<b>".to_string(),
+            IntegrationStrength::Intrusive,
+            Distance::DifferentModule,
+            Volatility::Low,
+        ));
+        project.file_changes.insert("src/a.rs".to_string(), 12);
+
+        let mut output = Vec::new();
+        generate_html_output(&project, &IssueThresholds::default(), &mut output).unwrap();
+        let html = String::from_utf8(output).unwrap();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        for section in [
+            "Coupling Matrix",
+            "Modules",
+            "Temporal Issues",
+            "Volatility Hotspots",
+        ] {
+            assert!(html.contains(&format!("<h2>{}</h2>", section)));
+        }
+        assert!(html.contains(r#"class="str-intrusive""#));
+        assert!(html.contains("&lt;b&gt;"));
+        assert!(!html.contains("<b>"));
+        assert!(html.contains("<td>src/a.rs</td><td>12</td>"));
+    }
+}
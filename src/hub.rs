@@ -0,0 +1,151 @@
+//! Hub risk: god modules that are also depended on and changed often
+//!
+//! A large module is only a problem when it is also central and busy. The
+//! hub risk of a module combines four signals:
+//!
+//! - **Fan-in**: number of other modules that depend on it
+//! - **Size**: non-blank, non-comment lines of code
+//! - **Cognitive load**: see [`crate::aposd::cognitive_load`]
+//! - **Churn**: commits that changed its file (only with git history)
+//!
+//! Each signal is turned into a percentile rank among all modules (0.0 for
+//! the lowest value, 1.0 for the highest), and the score is their mean, so a
+//! module only scores high when it is near the top on every signal. Signals
+//! that are zero for every module (no git history, a single module) are
+//! left out of the mean.
+
+use std::path::PathBuf;
+
+use crate::aposd::cognitive_load;
+use crate::metrics::{ModuleMetrics, ProjectMetrics};
+
+/// Number of modules listed in the main report
+pub const HUB_REPORT_LIMIT: usize = 5;
+
+/// Composite hub risk of a module
+#[derive(Debug, Clone, PartialEq)]
+pub struct HubRisk {
+    pub module: String,
+    pub file: PathBuf,
+    /// Modules depending on this one
+    pub fan_in: usize,
+    pub lines_of_code: usize,
+    pub cognitive_load: usize,
+    /// Commits changing the module's file
+    pub changes: usize,
+    /// Mean percentile rank of the signals (0.0 - 1.0)
+    pub score: f64,
+}
+
+/// Hub risk of every module, highest first
+pub fn compute_hub_risks(metrics: &ProjectMetrics) -> Vec<HubRisk> {
+    let fan_in = metrics.module_fan_in();
+    let mut modules: Vec<&ModuleMetrics> = metrics.modules.values().collect();
+    modules.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let signals: Vec<[usize; 4]> = modules
+        .iter()
+        .map(|module| {
+            [
+                fan_in.get(&module.name).copied().unwrap_or(0),
+                module.lines_of_code,
+                cognitive_load(module),
+                metrics.module_changes(module),
+            ]
+        })
+        .collect();
+
+    let ranks: Vec<Option<Vec<f64>>> = (0..4)
+        .map(|i| {
+            let values: Vec<usize> = signals.iter().map(|s| s[i]).collect();
+            percentile_ranks(&values)
+        })
+        .collect();
+    let used = ranks.iter().flatten().count();
+
+    let mut risks: Vec<HubRisk> = modules
+        .iter()
+        .enumerate()
+        .map(|(m, module)| {
+            let [fan_in, lines_of_code, cognitive_load, changes] = signals[m];
+            let sum: f64 = ranks.iter().flatten().map(|r| r[m]).sum();
+            HubRisk {
+                module: module.name.clone(),
+                file: module.path.clone(),
+                fan_in,
+                lines_of_code,
+                cognitive_load,
+                changes,
+                score: if used == 0 { 0.0 } else { sum / used as f64 },
+            }
+        })
+        .collect();
+    risks.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| b.lines_of_code.cmp(&a.lines_of_code))
+            .then_with(|| a.module.cmp(&b.module))
+    });
+    risks
+}
+
+/// Fraction of the other values below each value, or `None` when every
+/// value is zero
+fn percentile_ranks(values: &[usize]) -> Option<Vec<f64>> {
+    if values.iter().all(|&v| v == 0) {
+        return None;
+    }
+    let others = values.len().saturating_sub(1).max(1) as f64;
+    Some(
+        values
+            .iter()
+            .map(|&v| values.iter().filter(|&&o| o < v).count() as f64 / others)
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{CouplingMetrics, Distance, IntegrationStrength, Volatility};
+
+    #[test]
+    fn test_compute_hub_risks() {
+        let mut metrics = ProjectMetrics::new();
+        for (name, lines) in [("core", 800), ("api", 300), ("cli", 900), ("util", 50)] {
+            let mut module = ModuleMetrics::new(format!("src/{name}.rs").into(), name.into());
+            module.lines_of_code = lines;
+            metrics.add_module(module);
+        }
+        for (source, target) in [
+            ("api", "core"),
+            ("cli", "core"),
+            ("cli", "api"),
+            ("util", "core"),
+        ] {
+            metrics.add_coupling(CouplingMetrics::new(
+                format!("crate::{source}"),
+                format!("crate::{target}"),
+                IntegrationStrength::Functional,
+                Distance::DifferentModule,
+                Volatility::Low,
+            ));
+        }
+        metrics.file_changes.insert("src/core.rs".into(), 12);
+        metrics.file_changes.insert("src/cli.rs".into(), 3);
+
+        let risks = compute_hub_risks(&metrics);
+        let order: Vec<&str> = risks.iter().map(|r| r.module.as_str()).collect();
+        assert_eq!(order, vec!["core", "cli", "api", "util"]);
+        assert_eq!((risks[0].fan_in, risks[0].changes), (3, 12));
+    }
+
+    #[test]
+    fn test_percentile_ranks() {
+        assert_eq!(
+            percentile_ranks(&[0, 10, 5, 10, 1]),
+            Some(vec![0.0, 0.75, 0.5, 0.75, 0.25])
+        );
+        assert_eq!(percentile_ranks(&[0, 0]), None);
+    }
+}
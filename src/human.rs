@@ -0,0 +1,288 @@
+//! Human-readable terminal output
+//!
+//! The default report is Markdown, which reads poorly in a terminal. When
+//! the report goes to a terminal, findings are printed the way cargo and
+//! clippy print diagnostics instead:
+//!
+//! - grouped by file, with paths relative to the working directory and
+//!   findings ordered by line
+//! - a colored `error`/`warning` label: findings of rules set to `error` in
+//!   `[rules.severity]` and Critical findings are errors (both fail
+//!   `--check`), everything else is a warning
+//! - a footer with the totals (`14 warnings (3 new), 2 errors`); with
+//!   `--compare-baseline`, only new findings are listed and the totals
+//!   include the known ones
+//!
+//! `--min-severity` and `--top` shorten the list; the footer says how many
+//! findings were left out.
+//!
+//! Colors follow `--color` (`auto` colors terminals unless `NO_COLOR` is
+//! set).
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::balance::{
+    CouplingIssue, IssueThresholds, Severity, analyze_project_balance_with_thresholds,
+};
+use crate::metrics::ProjectMetrics;
+use crate::rules::RuleLevel;
+
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const CYAN: &str = "\x1b[1;36m";
+const GREEN: &str = "\x1b[32m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// When to color output (`--color`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorChoice {
+    /// Color terminals unless `NO_COLOR` is set
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Parse `auto`, `always` or `never`
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "auto" => Some(ColorChoice::Auto),
+            "always" => Some(ColorChoice::Always),
+            "never" => Some(ColorChoice::Never),
+            _ => None,
+        }
+    }
+
+    /// Whether to color output going to a terminal (`is_terminal`) or not
+    pub fn enabled(self, is_terminal: bool) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => is_terminal && std::env::var_os("NO_COLOR").is_none(),
+        }
+    }
+}
+
+/// Wraps text in ANSI codes when enabled
+#[derive(Debug, Clone, Copy)]
+struct Painter {
+    color: bool,
+}
+
+impl Painter {
+    fn paint(self, code: &str, text: &str) -> String {
+        if self.color {
+            format!("{}{}{}", code, text, RESET)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// Whether a finding is an error (fails `--check`) rather than a warning
+pub fn is_error(issue: &CouplingIssue, thresholds: &IssueThresholds) -> bool {
+    issue.severity == Severity::Critical
+        || thresholds.rules.level(issue.issue_type) == Some(RuleLevel::Error)
+}
+
+/// `path` relative to `base` when inside it
+fn relative<'a>(path: &'a Path, base: &Path) -> &'a Path {
+    path.strip_prefix(base).unwrap_or(path)
+}
+
+fn plural(count: usize, word: &str) -> String {
+    if count == 1 {
+        format!("1 {}", word)
+    } else {
+        format!("{} {}s", count, word)
+    }
+}
+
+/// Generate the terminal report to writer; paths are shown relative to
+/// `base` (usually the working directory)
+pub fn generate_human_output<W: Write>(
+    metrics: &ProjectMetrics,
+    thresholds: &IssueThresholds,
+    base: &Path,
+    color: bool,
+    writer: &mut W,
+) -> io::Result<()> {
+    let painter = Painter { color };
+
+    // Analyze without the baseline so the totals include known findings
+    let mut all_thresholds = thresholds.clone();
+    let baseline = all_thresholds.baseline.take();
+    let report = analyze_project_balance_with_thresholds(metrics, &all_thresholds);
+    let new = match &baseline {
+        Some(baseline) => baseline.new_flags(&report.issues, metrics),
+        None => vec![true; report.issues.len()],
+    };
+
+    let mut by_file: BTreeMap<Option<PathBuf>, Vec<&CouplingIssue>> = BTreeMap::new();
+    let (mut errors, mut warnings, mut new_errors, mut new_warnings) = (0, 0, 0, 0);
+    for (issue, is_new) in report.issues.iter().zip(&new) {
+        let error = is_error(issue, thresholds);
+        match (error, *is_new) {
+            (true, true) => new_errors += 1,
+            (false, true) => new_warnings += 1,
+            _ => {}
+        }
+        if error {
+            errors += 1;
+        } else {
+            warnings += 1;
+        }
+        if *is_new {
+            let file = issue
+                .location
+                .file_path
+                .as_deref()
+                .map(|p| relative(p, base).to_path_buf());
+            by_file.entry(file).or_default().push(issue);
+        }
+    }
+
+    // Files in order, findings without a file last
+    let unlocated = by_file.remove(&None);
+    let groups = by_file
+        .into_iter()
+        .chain(unlocated.map(|issues| (None, issues)));
+
+    for (file, mut issues) in groups {
+        issues.sort_by_key(|i| (i.location.line, i.location.column));
+        let heading = file.map_or("(project)".to_string(), |f| f.display().to_string());
+        writeln!(writer, "{}", painter.paint(BOLD, &heading))?;
+        for issue in issues {
+            let label = if is_error(issue, thresholds) {
+                painter.paint(RED, "error")
+            } else {
+                painter.paint(YELLOW, "warning")
+            };
+            let position = if issue.location.line > 0 {
+                format!("{}:{}", issue.location.line, issue.location.column)
+            } else {
+                "-".to_string()
+            };
+            writeln!(
+                writer,
+                "  {:>7}  {}[{}]: {}",
+                position,
+                label,
+                issue.issue_type.rule_id(),
+                issue.description
+            )?;
+            writeln!(
+                writer,
+                "  {:>7}  {} {}",
+                "",
+                painter.paint(CYAN, "= help:"),
+                issue.refactoring
+            )?;
+        }
+        writeln!(writer)?;
+    }
+
+    writeln!(
+        writer,
+        "Grade {} | Score {:.2}/1.00 | {} modules, {} couplings",
+        report.health_grade,
+        report.average_score,
+        metrics.module_count(),
+        report.total_couplings
+    )?;
+
+    let with_new = |count: usize, new: usize, word: &str, code: &str| {
+        let mut text = painter.paint(code, &plural(count, word));
+        if baseline.is_some() {
+            text.push_str(&format!(
+                " ({})",
+                painter.paint(GREEN, &format!("{} new", new))
+            ));
+        }
+        text
+    };
+    if errors + warnings == 0 {
+        writeln!(writer, "{}", painter.paint(GREEN, "No findings"))?;
+    } else {
+        writeln!(
+            writer,
+            "{}, {}",
+            with_new(warnings, new_warnings, "warning", YELLOW),
+            with_new(errors, new_errors, "error", RED)
+        )?;
+    }
+
+    let counted: usize = report.issues_by_severity.values().sum();
+    if counted > report.issues.len() {
+        writeln!(
+            writer,
+            "{} not listed (--min-severity, --top)",
+            plural(counted - report.issues.len(), "finding")
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::baseline::Baseline;
+    use crate::metrics::{
+        CouplingMetrics, Distance, IntegrationStrength, ModuleMetrics, Volatility,
+    };
+
+    fn project() -> ProjectMetrics {
+        let mut metrics = ProjectMetrics::new();
+        let mut module = ModuleMetrics::new(PathBuf::from("/work/src/big.rs"), "big".into());
+        for i in 0..40 {
+            module.add_function_definition(format!("f{}", i), Default::default());
+        }
+        metrics.add_module(module);
+        metrics.add_coupling(CouplingMetrics::new(
+            "big".into(),
+            "other::Thing".into(),
+            IntegrationStrength::Functional,
+            Distance::DifferentModule,
+            Volatility::Low,
+        ));
+        metrics
+    }
+
+    fn render(metrics: &ProjectMetrics, thresholds: &IssueThresholds, color: bool) -> String {
+        let mut out = Vec::new();
+        generate_human_output(metrics, thresholds, Path::new("/work"), color, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_grouped_by_relative_file() {
+        let text = render(&project(), &IssueThresholds::default(), false);
+        assert!(text.starts_with("src/big.rs\n"));
+        assert!(text.contains("warning[rust::god-module]: Module big has too many"));
+        assert!(text.contains("= help: Split into modules"));
+        assert!(text.ends_with("1 warning, 0 errors\n"));
+        assert!(!text.contains('\x1b'));
+
+        let colored = render(&project(), &IssueThresholds::default(), true);
+        assert!(colored.contains("\x1b[1;33mwarning\x1b[0m"));
+    }
+
+    #[test]
+    fn test_baseline_counts_new_findings() {
+        let metrics = project();
+        let mut thresholds = IssueThresholds::default();
+        let known = analyze_project_balance_with_thresholds(&metrics, &thresholds).issues;
+        thresholds.baseline = Some(Baseline::from_issues(&known, &metrics));
+        let text = render(&metrics, &thresholds, false);
+        assert!(!text.contains("god-module"));
+        assert!(text.ends_with("1 warning (0 new), 0 errors (0 new)\n"));
+
+        assert_eq!(ColorChoice::parse("NEVER"), Some(ColorChoice::Never));
+        assert!(ColorChoice::Always.enabled(false));
+        assert!(!ColorChoice::Never.enabled(true));
+    }
+}
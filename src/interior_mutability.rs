@@ -0,0 +1,216 @@
+//! Interior mutability leaked through public APIs
+//!
+//! A public field of type `RefCell<T>`, `Cell<T>`, `Mutex<T>` or `RwLock<T>`
+//! hands the synchronization decision to every caller: they lock or borrow
+//! the field themselves, choose how long to hold it, and break when the
+//! type switches from `RefCell` to `Mutex` or from one lock to a finer
+//! grained one. Public methods returning a guard (`MutexGuard`, `Ref`,
+//! `RwLockWriteGuard`, ...) or the lock itself do the same: the caller
+//! decides the length of the critical section, and with it which deadlocks
+//! and `BorrowMutError` panics are possible.
+//!
+//! Types are read from the public fields, signatures and type aliases of
+//! each module (`ModuleMetrics::exposed_types`), so `Arc<Mutex<State>>` is
+//! found inside other types too. `Ref` and `RefMut` only count when they
+//! resolve to `std::cell` (or `core::cell`), since the names are common.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::metrics::ProjectMetrics;
+
+/// Types providing interior mutability
+pub const CELL_TYPES: &[&str] = &["Cell", "RefCell", "UnsafeCell", "Mutex", "RwLock"];
+
+/// Guards of the cell types
+pub const GUARD_TYPES: &[&str] = &[
+    "MutexGuard",
+    "RwLockReadGuard",
+    "RwLockWriteGuard",
+    "MappedMutexGuard",
+    "MappedRwLockReadGuard",
+    "MappedRwLockWriteGuard",
+    "Ref",
+    "RefMut",
+];
+
+/// A public field or signature naming a cell or guard type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeakedMutability {
+    /// Public item (`Cache.entries`, `Store::lock_state`)
+    pub item: String,
+    /// Cell or guard type (`Mutex`, `MutexGuard`)
+    pub type_name: String,
+    pub line: usize,
+}
+
+/// Interior mutability a module's public API exposes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InteriorMutabilityExposure {
+    pub module: String,
+    pub file: PathBuf,
+    /// Public fields (and enum variant fields)
+    pub fields: Vec<LeakedMutability>,
+    /// Public functions, methods and type aliases
+    pub signatures: Vec<LeakedMutability>,
+}
+
+impl InteriorMutabilityExposure {
+    /// Line of the first exposure
+    pub fn line(&self) -> usize {
+        self.fields
+            .iter()
+            .chain(&self.signatures)
+            .map(|l| l.line)
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// How to keep the synchronization inside the module
+    pub fn suggestion(&self) -> String {
+        let mut steps = Vec::new();
+        if !self.fields.is_empty() {
+            steps.push(format!(
+                "make {} private and add methods that lock or borrow internally",
+                item_list(&self.fields)
+            ));
+        }
+        if !self.signatures.is_empty() {
+            steps.push(format!(
+                "let {} return owned values or take a closure run under the lock (`fn with_state<R>(&self, f: impl FnOnce(&mut State) -> R) -> R`)",
+                item_list(&self.signatures)
+            ));
+        }
+        capitalize(&steps.join("; "))
+    }
+}
+
+/// Modules whose public API exposes cell or guard types, by module name
+pub fn find_interior_mutability(metrics: &ProjectMetrics) -> Vec<InteriorMutabilityExposure> {
+    let mut found: BTreeMap<&str, InteriorMutabilityExposure> = BTreeMap::new();
+    for module in metrics.modules.values() {
+        for exposed in &module.exposed_types {
+            let Some(type_name) = mutability_type(&exposed.type_path) else {
+                continue;
+            };
+            let exposure =
+                found
+                    .entry(module.name.as_str())
+                    .or_insert_with(|| InteriorMutabilityExposure {
+                        module: module.name.clone(),
+                        file: module.path.clone(),
+                        fields: Vec::new(),
+                        signatures: Vec::new(),
+                    });
+            let leaked = LeakedMutability {
+                item: exposed.item.clone(),
+                type_name: type_name.to_string(),
+                line: exposed.line,
+            };
+            if exposed.item.contains('.') {
+                exposure.fields.push(leaked);
+            } else {
+                exposure.signatures.push(leaked);
+            }
+        }
+    }
+    found.into_values().collect()
+}
+
+/// Cell or guard type a resolved type path names
+fn mutability_type(path: &str) -> Option<&str> {
+    let name = path.rsplit("::").next()?;
+    if matches!(name, "Ref" | "RefMut") {
+        let in_cell = path.starts_with("std::cell::") || path.starts_with("core::cell::");
+        return in_cell.then_some(name);
+    }
+    (CELL_TYPES.contains(&name) || GUARD_TYPES.contains(&name)).then_some(name)
+}
+
+/// `` `a` ``, `` `a` and `b` ``, `` `a`, `b` and 2 more ``
+fn item_list(leaked: &[LeakedMutability]) -> String {
+    let mut items: Vec<&str> = leaked.iter().map(|l| l.item.as_str()).collect();
+    items.dedup();
+    let named: Vec<String> = items.iter().take(2).map(|i| format!("`{}`", i)).collect();
+    match items.len() {
+        1 | 2 => named.join(" and "),
+        n => format!("{} and {} more", named.join(", "), n - 2),
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::CouplingAnalyzer;
+
+    #[test]
+    fn test_mutability_type() {
+        assert_eq!(mutability_type("std::sync::Mutex"), Some("Mutex"));
+        assert_eq!(
+            mutability_type("parking_lot::RwLockWriteGuard"),
+            Some("RwLockWriteGuard")
+        );
+        assert_eq!(mutability_type("std::cell::Ref"), Some("Ref"));
+        // A local type named `Ref`
+        assert_eq!(mutability_type("Ref"), None);
+    }
+
+    #[test]
+    fn test_find_interior_mutability() {
+        let mut analyzer = CouplingAnalyzer::new("cache".into(), PathBuf::from("src/cache.rs"));
+        analyzer
+            .analyze_file(
+                r#"
+                use std::cell::{Ref, RefCell};
+                use std::sync::{Arc, Mutex, MutexGuard};
+
+                pub struct Cache {
+                    pub entries: RefCell<Vec<u8>>,
+                    pub shared: Arc<Mutex<u32>>,
+                    hits: Mutex<u64>,
+                }
+                impl Cache {
+                    pub fn entries(&self) -> Ref<'_, Vec<u8>> { self.entries.borrow() }
+                    pub fn hits(&self) -> MutexGuard<'_, u64> { self.hits.lock().unwrap() }
+                    pub fn count(&self) -> u64 { *self.hits.lock().unwrap() }
+                    fn raw(&self) -> &Mutex<u64> { &self.hits }
+                }
+                "#,
+            )
+            .unwrap();
+        let mut metrics = ProjectMetrics::new();
+        metrics.add_module(analyzer.metrics);
+
+        let found = find_interior_mutability(&metrics);
+        assert_eq!(found.len(), 1);
+        let fields: Vec<(&str, &str)> = found[0]
+            .fields
+            .iter()
+            .map(|l| (l.item.as_str(), l.type_name.as_str()))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![("Cache.entries", "RefCell"), ("Cache.shared", "Mutex")]
+        );
+        let signatures: Vec<&str> = found[0]
+            .signatures
+            .iter()
+            .map(|l| l.item.as_str())
+            .collect();
+        // Private methods do not count
+        assert_eq!(signatures, vec!["Cache::entries", "Cache::hits"]);
+        assert!(
+            found[0]
+                .suggestion()
+                .starts_with("Make `Cache.entries` and `Cache.shared` private")
+        );
+    }
+}
@@ -0,0 +1,237 @@
+//! Architectural layering rules
+//!
+//! Users declare layers in `.coupling.toml` and which other layers each one
+//! may depend on:
+//!
+//! ```toml
+//! [layers.domain]
+//! members = ["domain", "*::model*"]
+//!
+//! [layers.application]
+//! members = ["app"]
+//! allow = ["domain"]
+//!
+//! [layers.infrastructure]
+//! members = ["db", "web"]
+//! allow = ["application", "domain"]
+//! ```
+//!
+//! Member patterns are globs matched against module paths (`app::handlers`)
+//! and crate names (`app`). A module pattern wins over a crate pattern, so a
+//! single crate can be split into layers by module. Dependencies within a
+//! layer are always allowed; any other dependency must be listed in `allow`.
+//!
+//! Both code-level references and manifest dependencies between workspace
+//! crates are checked.
+
+use std::collections::BTreeMap;
+
+use glob::Pattern;
+
+use crate::metrics::{CouplingLocation, ProjectMetrics};
+
+/// A declared architectural layer
+#[derive(Debug, Clone)]
+pub struct Layer {
+    pub name: String,
+    /// Patterns for crate names and module paths belonging to the layer
+    pub members: Vec<Pattern>,
+    /// Layers this layer may depend on
+    pub allow: Vec<String>,
+}
+
+impl Layer {
+    /// Whether this layer may depend on `other`
+    pub fn may_depend_on(&self, other: &str) -> bool {
+        self.name == other || self.allow.iter().any(|a| a == other)
+    }
+
+    fn contains(&self, name: &str) -> bool {
+        self.members.iter().any(|p| p.matches(name))
+    }
+}
+
+/// Find the layer of a component: module path first, then crate name
+pub fn layer_of<'a>(layers: &'a [Layer], krate: Option<&str>, module: &str) -> Option<&'a Layer> {
+    layers
+        .iter()
+        .find(|l| l.contains(module))
+        .or_else(|| krate.and_then(|k| layers.iter().find(|l| l.contains(k))))
+}
+
+/// A dependency that goes against the declared layering
+#[derive(Debug, Clone)]
+pub struct LayerViolation {
+    /// Depending module (or crate, for manifest dependencies)
+    pub source: String,
+    /// Module (or crate) depended on
+    pub target: String,
+    pub source_layer: String,
+    pub target_layer: String,
+    /// Number of references between source and target
+    pub references: usize,
+    /// Whether this is a `Cargo.toml` dependency between crates
+    pub manifest: bool,
+    /// First reference (no file for manifest dependencies)
+    pub location: CouplingLocation,
+}
+
+/// Find all dependencies that violate the declared layering
+///
+/// Code-level references are grouped per (source, target) pair, sorted by
+/// source and target.
+pub fn find_layer_violations(metrics: &ProjectMetrics, layers: &[Layer]) -> Vec<LayerViolation> {
+    if layers.is_empty() {
+        return Vec::new();
+    }
+
+    let mut violations: BTreeMap<(String, String), LayerViolation> = BTreeMap::new();
+
+    for coupling in &metrics.couplings {
+        let source = layer_of(layers, coupling.source_crate.as_deref(), &coupling.source);
+        let target = layer_of(layers, coupling.target_crate.as_deref(), &coupling.target);
+        let (Some(source), Some(target)) = (source, target) else {
+            continue;
+        };
+        if source.may_depend_on(&target.name) {
+            continue;
+        }
+        violations
+            .entry((coupling.source.clone(), coupling.target.clone()))
+            .and_modify(|v| v.references += 1)
+            .or_insert_with(|| LayerViolation {
+                source: coupling.source.clone(),
+                target: coupling.target.clone(),
+                source_layer: source.name.clone(),
+                target_layer: target.name.clone(),
+                references: 1,
+                manifest: false,
+                location: coupling.location.clone(),
+            });
+    }
+
+    // Manifest dependencies between workspace members
+    for (krate, deps) in &metrics.crate_dependencies {
+        let Some(source) = layer_of(layers, Some(krate), krate) else {
+            continue;
+        };
+        for dep in deps {
+            if !metrics.workspace_members.contains(dep) {
+                continue;
+            }
+            let Some(target) = layer_of(layers, Some(dep), dep) else {
+                continue;
+            };
+            if source.may_depend_on(&target.name) {
+                continue;
+            }
+            violations
+                .entry((krate.clone(), dep.clone()))
+                .or_insert_with(|| LayerViolation {
+                    source: krate.clone(),
+                    target: dep.clone(),
+                    source_layer: source.name.clone(),
+                    target_layer: target.name.clone(),
+                    references: 0,
+                    manifest: true,
+                    location: CouplingLocation::default(),
+                });
+        }
+    }
+
+    violations.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{CouplingMetrics, Distance, IntegrationStrength, Volatility};
+
+    fn layer(name: &str, members: &[&str], allow: &[&str]) -> Layer {
+        Layer {
+            name: name.to_string(),
+            members: members.iter().map(|m| Pattern::new(m).unwrap()).collect(),
+            allow: allow.iter().map(|a| a.to_string()).collect(),
+        }
+    }
+
+    fn layers() -> Vec<Layer> {
+        vec![
+            layer("domain", &["domain", "app::model*"], &[]),
+            layer("application", &["app"], &["domain"]),
+            layer("infrastructure", &["db"], &["domain", "application"]),
+        ]
+    }
+
+    fn coupling(
+        source_crate: &str,
+        source: &str,
+        target_crate: &str,
+        target: &str,
+    ) -> CouplingMetrics {
+        let mut c = CouplingMetrics::new(
+            source.to_string(),
+            target.to_string(),
+            IntegrationStrength::Model,
+            Distance::DifferentCrate,
+            Volatility::Low,
+        );
+        c.source_crate = Some(source_crate.to_string());
+        c.target_crate = Some(target_crate.to_string());
+        c
+    }
+
+    #[test]
+    fn test_layer_of_prefers_module_patterns() {
+        let layers = layers();
+        assert_eq!(
+            layer_of(&layers, Some("app"), "app::handlers")
+                .unwrap()
+                .name,
+            "application"
+        );
+        assert_eq!(
+            layer_of(&layers, Some("app"), "app::model").unwrap().name,
+            "domain"
+        );
+        assert!(layer_of(&layers, Some("serde"), "serde::Serialize").is_none());
+    }
+
+    #[test]
+    fn test_find_layer_violations() {
+        let mut metrics = ProjectMetrics::new();
+        metrics.workspace_members = vec!["app".into(), "db".into(), "domain".into()];
+        metrics
+            .crate_dependencies
+            .insert("domain".into(), vec!["db".into(), "serde".into()]);
+        // Allowed: application -> domain, infrastructure -> application
+        metrics.add_coupling(coupling("app", "app::handlers", "domain", "domain::user"));
+        metrics.add_coupling(coupling("db", "db::repo", "app", "app::service"));
+        // Violations: domain -> infrastructure (twice), domain module -> application
+        metrics.add_coupling(coupling("domain", "domain::user", "db", "db::pool"));
+        metrics.add_coupling(coupling("domain", "domain::user", "db", "db::pool"));
+        metrics.add_coupling(coupling("app", "app::model", "app", "app::service"));
+
+        let violations = find_layer_violations(&metrics, &layers());
+        let summary: Vec<(&str, &str, &str, usize, bool)> = violations
+            .iter()
+            .map(|v| {
+                (
+                    v.source.as_str(),
+                    v.target.as_str(),
+                    v.target_layer.as_str(),
+                    v.references,
+                    v.manifest,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("app::model", "app::service", "application", 1, false),
+                ("domain", "db", "infrastructure", 0, true),
+                ("domain::user", "db::pool", "infrastructure", 2, false),
+            ]
+        );
+    }
+}
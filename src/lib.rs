@@ -36,39 +36,241 @@
 //! - Strong coupling + far distance = Bad (global complexity)
 //! - High volatility + strong coupling = Bad (cascading changes)
 
+pub mod analysis;
 pub mod analyzer;
+pub mod api;
+pub mod aposd;
+pub mod badge;
 pub mod balance;
+pub mod baseline;
+pub mod blast_radius;
+pub mod build_coupling;
 pub mod cli_output;
 pub mod config;
+pub mod connascence;
+pub mod coupling_report;
+pub mod dependency_audit;
+pub mod dependency_weight;
+pub mod diff;
+pub mod doc_coverage;
+pub mod dot;
+pub mod edge_risk;
+pub mod edition;
+pub mod error_surface;
+pub mod exhaustive_match;
+pub mod expand;
+pub mod explain;
+pub mod ffi;
+pub mod file_metrics;
+pub mod fingerprint;
+pub mod functions;
+#[cfg(feature = "gix")]
+pub mod gix_backend;
+pub mod graph;
+pub mod health;
+pub mod history;
+pub mod html;
+pub mod hub;
+pub mod human;
+pub mod interior_mutability;
+pub mod layers;
+pub mod logging;
+pub mod lsp;
+pub mod markdown;
+pub mod matrix;
 pub mod metrics;
+pub mod mode_switch;
+pub mod module_graph;
+pub mod ownership;
+pub mod paths;
+pub mod platform;
+pub mod progress;
+pub mod quadrant;
 pub mod report;
+pub mod rules;
+pub mod rustdoc;
+pub mod sarif;
+pub mod split;
+pub mod sql;
+pub mod stability;
+pub mod staged;
+pub mod stamp;
+pub mod struct_literal;
+pub mod suggest;
+pub mod suppress;
+pub mod temporal;
+#[cfg(test)]
+mod test_fixtures;
+pub mod trait_coupling;
+pub mod unsafe_surface;
 pub mod volatility;
 pub mod web;
 pub mod workspace;
 
+pub use analysis::{Analysis, AnalysisBuilder, AnalysisError};
 pub use analyzer::{
-    AnalyzedFileResult, AnalyzerError, CouplingAnalyzer, Dependency, DependencyKind, ItemDepType,
-    ItemDependency, ItemKind, analyze_project, analyze_rust_file, analyze_rust_file_full,
-    analyze_workspace,
+    AnalysisScope, AnalyzedFileResult, AnalyzerError, CouplingAnalyzer, Dependency, DependencyKind,
+    ItemDepType, ItemDependency, ItemKind, analyze_project, analyze_project_filtered,
+    analyze_rust_file, analyze_rust_file_full, analyze_rust_file_with, analyze_workspace,
+    analyze_workspace_scoped, is_test_code, strip_test_code,
 };
+pub use api::{
+    ApiItem, ApiItemKind, ApiSurface, ChainHop, ExposingItem, ReExportChain, ThirdPartyExposure,
+    generate_api_report,
+};
+pub use aposd::{
+    AposdConfig, CognitiveLoadClass, DepthClass, ModuleDepth, OverriddenDefault,
+    TemporalDecomposition, TraitSurface, analyze_module_depths, analyze_module_depths_with,
+    analyze_trait_surfaces, average_depth_ratio, cognitive_load, find_overridden_defaults,
+    find_temporal_decompositions,
+};
+pub use badge::{Badge, BadgeMetric, write_badge};
 pub use balance::{
     BalanceInterpretation, BalanceScore, CouplingIssue, HealthGrade, IssueThresholds, IssueType,
     ProjectBalanceReport, RefactoringAction, Severity, analyze_project_balance,
     analyze_project_balance_with_thresholds, calculate_project_score,
 };
+pub use baseline::{Baseline, BaselineEntry, BaselineError};
+pub use blast_radius::{
+    BlastRadius, BlastRadiusError, ChangedItemKind, CoChangedFile, NameReference,
+    generate_blast_radius_report,
+};
+pub use build_coupling::{
+    BuildCouplingReport, DependencyCoupling, DependencyPhase, PhasedDependency, ProcMacroReach,
+    classify_dependencies, generate_build_coupling_report,
+};
 pub use config::{
-    CompiledConfig, ConfigError, CouplingConfig, ThresholdsConfig, VolatilityConfig,
-    load_compiled_config, load_config,
+    CompiledConfig, ConfigError, CouplingConfig, LayerConfig, OverrideConfig, PathOverride,
+    PathsConfig, ThresholdOverrides, ThresholdsConfig, VolatilityConfig, find_config_file,
+    load_compiled_config, load_compiled_config_file, load_config, load_config_file,
+};
+pub use connascence::{
+    AlgorithmCoupling, AlgorithmRole, AlgorithmUse, CallSite, CloneSite, CodeClone,
+    ConnascenceAnalyzer, ConnascenceMetrics, ConnascenceType, ExecutionEvidence, ExecutionOrder,
+    FnSignature, FunctionBody, MagicNumber, OrderingHint, PositionalRisk, QueryReference,
+    SharedLiteral, SharedSchema, SharedStateItem, SharedStateKind, SharedStateUse,
+    find_algorithm_couplings, find_code_clones, find_execution_orders, find_positional_risks,
+    find_shared_literals, find_shared_schema, find_shared_state,
+};
+pub use coupling_report::{
+    AposdSummary, BalanceSummary, ConnascenceSummary, CouplingReport, IssueRecord, TemporalSummary,
+    WorkspaceSummary,
+};
+pub use dependency_audit::{
+    DependencyAudit, UnusedDependency, VersionSkew, compatible_series,
+    generate_dependency_audit_report,
+};
+pub use dependency_weight::{
+    DependencyWeight, DependencyWeightReport, HEAVY_DEPENDENCY_ITEMS,
+    generate_dependency_weight_report,
+};
+pub use diff::{
+    DepthChange, DiffError, RevisionDiff, analyze_revision, analyze_revision_with, compare_metrics,
+    export_revision, generate_diff_report,
+};
+pub use doc_coverage::{DocAnalyzer, DocCoverage};
+pub use dot::generate_dot_output;
+pub use edge_risk::{EDGE_REPORT_LIMIT, EdgeRisk, compute_edge_risks, coupling_risk};
+pub use edition::Edition;
+pub use error_surface::{
+    ErrorAnalyzer, ErrorExposure, ErrorSurface, ExposedError, FallibleFunction, MAX_ERROR_CASES,
+    find_error_exposures,
+};
+pub use exhaustive_match::{
+    EnumMatch, EnumMatcher, FragileEnum, MANY_ENUM_MATCHES, MatchAnalyzer, find_fragile_enums,
+};
+pub use expand::{
+    ExpandError, ExpandTarget, ExpandedSources, expand_sources, module_files, run_cargo_expand,
+    split_expanded,
+};
+pub use explain::{Explanation, explain, generate_explanation, generate_rule_list};
+pub use ffi::{
+    FfiModule, FfiReport, FfiResource, FfiUsage, ForeignBlock, alloc_free_pairs,
+    generate_ffi_report,
 };
+pub use file_metrics::FileMetrics;
+pub use fingerprint::fingerprint;
+pub use functions::{
+    COMPLEX_FUNCTION_LIMIT, ComplexFunction, FunctionAnalyzer, FunctionDistribution, FunctionStats,
+    Percentiles, most_complex_functions,
+};
+#[cfg(feature = "gix")]
+pub use gix_backend::GixBackend;
+pub use graph::{
+    CycleEdge, DependencyCycle, WeightedGraph, find_cycles, strongly_connected_components,
+};
+pub use health::{HealthScore, HealthWeights, ScoreComponent, generate_health_report, grade_for};
+pub use history::{
+    DEFAULT_HISTORY_FILE, HistoryEntry, HistoryError, append_entry, generate_trend_report,
+    load_history, sparkline,
+};
+pub use html::generate_html_output;
+pub use hub::{HUB_REPORT_LIMIT, HubRisk, compute_hub_risks};
+pub use human::{ColorChoice, generate_human_output};
+pub use interior_mutability::{
+    InteriorMutabilityExposure, LeakedMutability, find_interior_mutability,
+};
+pub use layers::{Layer, LayerViolation, find_layer_violations, layer_of};
+pub use lsp::run_lsp_server;
+pub use markdown::{
+    MarkdownDocument, MarkdownSection, build_markdown_report, generate_markdown_report,
+};
+pub use matrix::{CrateMatrix, MatrixCell, MatrixFormat, generate_matrix_output};
 pub use metrics::{
     BalanceClassification, BalanceCounts, CircularDependencySummary, CouplingMetrics,
-    DimensionStats, Distance, DistanceCounts, FunctionDefinition, IntegrationStrength,
-    ModuleMetrics, ProjectMetrics, StrengthCounts, TypeDefinition, Visibility, Volatility,
-    VolatilityCounts,
+    DimensionStats, Distance, DistanceCounts, ExposedType, FunctionDefinition, IntegrationStrength,
+    ModuleMetrics, ProjectMetrics, ReExport, SkippedFile, StrengthCounts, TestCodeMetrics,
+    TraitUse, TypeDefinition, Visibility, Volatility, VolatilityCounts,
+};
+pub use mode_switch::{BranchArm, MIN_ARM_LINES, ModeAnalyzer, ModeBranch, ModeSwitch};
+pub use module_graph::{
+    ModuleFan, ModuleGraph, ModuleNode, generate_module_graph_report, module_name_from_file,
+    module_path_from_file,
 };
+pub use ownership::{MIN_OWNERSHIP_COUPLING, OwnershipHotspot, find_ownership_hotspots};
+pub use paths::{PathFilter, matches_path_suffix};
+pub use platform::{PlatformModule, PlatformReport, PlatformUsage, generate_platform_report};
+pub use progress::{AnalysisPhase, ProgressEvent, ProgressHook, terminal_progress};
+pub use quadrant::{Quadrant, QuadrantPoint, QuadrantReport, generate_quadrant_output};
 pub use report::{
-    generate_ai_output, generate_ai_output_with_thresholds, generate_report,
-    generate_report_with_thresholds, generate_summary, generate_summary_with_thresholds,
+    generate_ai_output, generate_ai_output_with_thresholds, generate_quiet_summary,
+    generate_report, generate_report_with_thresholds, generate_summary,
+    generate_summary_with_thresholds,
+};
+pub use rules::{RuleFilter, RuleLevel, UnknownRule};
+pub use rustdoc::{RustdocError, RustdocIndex, rustdoc_index};
+pub use sarif::{SarifLog, build_sarif_log, generate_sarif_output};
+pub use split::{CrateSnapshot, SplitError, SplitSimulation, generate_split_report};
+pub use stability::{
+    EXTRACT_CANDIDATE_LIMIT, MemberStability, StabilityFinding, StabilityThresholds,
+    compute_crate_stability, find_stability_issues, generate_stability_report,
+};
+pub use staged::{StagedCheck, check_staged, files_with_dependents, generate_staged_report};
+pub use stamp::{
+    MAX_STAMP_FIELDS_USED, MIN_STAMP_STRUCT_FIELDS, ParamUse, StampAnalyzer, StampCoupling,
+    find_stamp_couplings,
+};
+pub use struct_literal::{
+    LiteralAnalyzer, LiteralSite, LiteralStruct, StructLiteral, StructLiterals,
+    find_literal_structs,
+};
+pub use suggest::{Suggestion, SuggestionKind, SuggestionReport, generate_suggestion_report};
+pub use suppress::{FileSuppression, Suppression, apply_suppressions, parse_directives};
+pub use temporal::{
+    BuilderPattern, GuardBinding, PAIRED_OPS, PairRule, PairedOperation, RequiredFields,
+    StateCheck, StateWrite, TemporalAnalyzer, TemporalConfig, TemporalIssue, TemporalIssueKind,
+    TemporalMetrics, TemporalRules, reanalyze_project,
+};
+pub use trait_coupling::{
+    CrateTraitCoupling, OrphanWorkaround, SharedTrait, TraitCouplingReport,
+    find_orphan_workarounds, generate_trait_coupling_report,
+};
+pub use unsafe_surface::{UnsafeModule, UnsafeSurfaceReport, generate_unsafe_surface_report};
+pub use volatility::{
+    CoChangePair, CommitRecord, GitCommandBackend, GitWindow, HistoryBackend, VolatilityAnalyzer,
+    VolatilityError, VolatilityStats, default_backends,
+};
+pub use workspace::{
+    CrateInfo, FeatureSelection, ResolvedDependency, TargetDependency, WorkspaceError,
+    WorkspaceInfo,
 };
-pub use volatility::{VolatilityAnalyzer, VolatilityError, VolatilityStats};
-pub use workspace::{CrateInfo, WorkspaceError, WorkspaceInfo};
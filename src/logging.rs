@@ -0,0 +1,111 @@
+//! Diagnostic logging with `tracing`
+//!
+//! The analysis phases, the temporal, connascence and APOSD passes and every
+//! git or cargo subprocess run inside `tracing` spans, and skipped files are
+//! logged with the reason. [`init`] installs a `tracing-subscriber` logger on
+//! stderr:
+//!
+//! - `RUST_LOG` selects what is shown with `EnvFilter` directives: a level
+//!   (`debug`), or comma-separated `target=level` directives
+//!   (`cargo_coupling::analyzer=trace,warn`)
+//! - without `RUST_LOG`, `--verbose` shows `cargo_coupling=debug`
+//!
+//! Each closed span is logged with the time spent in it, which is usually
+//! the quickest way to find out why a run is slow.
+//!
+//! ```text
+//!    0.412s DEBUG analyze_workspace{path=./}: cargo_coupling::analyzer: skipped file file=src/gen.rs reason=excluded by path filter
+//!    1.873s DEBUG analyze_workspace{path=./}: cargo_coupling::analyzer: close time.busy=1.87s time.idle=12.1µs
+//! ```
+
+use std::io;
+
+use tracing::Subscriber;
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::fmt::time::Uptime;
+
+/// Directives used by `--verbose` when `RUST_LOG` is not set
+pub const VERBOSE_DIRECTIVES: &str = "cargo_coupling=debug";
+
+/// Install the stderr logger for `RUST_LOG`, or for `--verbose`; returns
+/// whether a logger was installed
+pub fn init(verbose: bool) -> bool {
+    let filter = match EnvFilter::try_from_default_env() {
+        Ok(filter) => filter,
+        Err(_) if verbose => EnvFilter::new(VERBOSE_DIRECTIVES),
+        Err(_) => return false,
+    };
+    if filter.max_level_hint() == Some(LevelFilter::OFF) {
+        return false;
+    }
+    tracing::subscriber::set_global_default(subscriber(filter, io::stderr)).is_ok()
+}
+
+/// One line per event and closed span, with the time since start
+fn subscriber<W>(filter: EnvFilter, writer: W) -> impl Subscriber + Send + Sync
+where
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(writer)
+        .with_timer(Uptime::default())
+        .with_span_events(FmtSpan::CLOSE)
+        .finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    /// Writer that can be read back after the logger is dropped
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_logger_spans_and_events() {
+        let output = Shared::default();
+        let writer = output.clone();
+        let logger = subscriber(
+            EnvFilter::new("warn,cargo_coupling=debug,cargo_coupling::git=off"),
+            move || writer.clone(),
+        );
+        tracing::subscriber::with_default(logger, || {
+            let span = tracing::debug_span!("analyze_workspace", path = "src").entered();
+            tracing::debug!(
+                file = "gen.rs",
+                reason = "excluded by path filter",
+                "skipped file"
+            );
+            tracing::trace!("hidden");
+            tracing::error!(target: "cargo_coupling::git", "hidden");
+            drop(span);
+        });
+
+        let text = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains(
+            "analyze_workspace{path=\"src\"}: cargo_coupling::logging::tests: skipped file file=\"gen.rs\" reason=\"excluded by path filter\""
+        ));
+        assert!(lines[1].contains(
+            "analyze_workspace{path=\"src\"}: cargo_coupling::logging::tests: close time.busy="
+        ));
+    }
+}
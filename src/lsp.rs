@@ -0,0 +1,439 @@
+//! Minimal Language Server for in-editor coupling diagnostics
+//!
+//! `cargo coupling --lsp` speaks the Language Server Protocol over
+//! stdin/stdout. It supports just enough of the protocol for design feedback
+//! while editing:
+//!
+//! - Findings are published as diagnostics (`textDocument/publishDiagnostics`)
+//!   at their source location, with the rule id as the diagnostic code
+//! - The project is analyzed again whenever a file is saved
+//! - Hovering anywhere in a file shows its module's depth ratio and
+//!   cognitive load (see [`crate::aposd`])
+//!
+//! Git history is read once at startup; saves only refresh the static
+//! analysis. Messages are JSON-RPC 2.0 with `Content-Length` framing, handled
+//! with `serde_json` directly.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use serde_json::{Value, json};
+
+use crate::aposd::{cognitive_load, module_depth_with};
+use crate::balance::{
+    CouplingIssue, IssueThresholds, Severity, analyze_project_balance_with_thresholds,
+};
+use crate::metrics::ProjectMetrics;
+use crate::rules::RuleLevel;
+
+/// JSON-RPC error code for unknown methods
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// LSP diagnostic severities
+const DIAGNOSTIC_ERROR: u8 = 1;
+const DIAGNOSTIC_WARNING: u8 = 2;
+const DIAGNOSTIC_INFORMATION: u8 = 3;
+
+/// Serve LSP requests until the client sends `exit` or closes the stream
+///
+/// `reanalyze` is called on every save and returns fresh metrics, or `None`
+/// to keep the previous ones (e.g. while a file does not parse).
+pub fn run_lsp_server<R, W, F>(
+    mut metrics: ProjectMetrics,
+    thresholds: &IssueThresholds,
+    mut reanalyze: F,
+    mut reader: R,
+    mut writer: W,
+) -> io::Result<()>
+where
+    R: BufRead,
+    W: Write,
+    F: FnMut() -> Option<ProjectMetrics>,
+{
+    let mut published: BTreeSet<String> = BTreeSet::new();
+
+    while let Some(message) = read_message(&mut reader)? {
+        let method = message["method"].as_str().unwrap_or_default();
+        let id = message.get("id").cloned();
+
+        match method {
+            "initialize" => {
+                let result = json!({
+                    "capabilities": {
+                        "textDocumentSync": { "openClose": true, "save": true },
+                        "hoverProvider": true,
+                    },
+                    "serverInfo": {
+                        "name": env!("CARGO_PKG_NAME"),
+                        "version": env!("CARGO_PKG_VERSION"),
+                    },
+                });
+                respond(&mut writer, id, result)?;
+            }
+            "initialized" => {
+                publish_diagnostics(&metrics, thresholds, &mut published, &mut writer)?;
+            }
+            "textDocument/didSave" => {
+                if let Some(fresh) = reanalyze() {
+                    metrics = fresh;
+                }
+                publish_diagnostics(&metrics, thresholds, &mut published, &mut writer)?;
+            }
+            "textDocument/hover" => {
+                let result = message["params"]["textDocument"]["uri"]
+                    .as_str()
+                    .and_then(uri_to_path)
+                    .and_then(|path| hover_text(&metrics, thresholds, &path))
+                    .map_or(
+                        Value::Null,
+                        |text| json!({ "contents": { "kind": "markdown", "value": text } }),
+                    );
+                respond(&mut writer, id, result)?;
+            }
+            "shutdown" => respond(&mut writer, id, Value::Null)?,
+            "exit" => break,
+            _ => {
+                // Notifications (no id) that are not handled are ignored
+                if let Some(id) = id {
+                    let error = json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "error": {
+                            "code": METHOD_NOT_FOUND,
+                            "message": format!("Unsupported method: {}", method),
+                        },
+                    });
+                    write_message(&mut writer, &error)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Read one `Content-Length` framed message, or `None` at end of input
+pub fn read_message<R: BufRead>(reader: &mut R) -> io::Result<Option<Value>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            if length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = value.trim().parse::<usize>().ok();
+        }
+    }
+
+    let mut body = vec![0; length.unwrap_or(0)];
+    reader.read_exact(&mut body)?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Write one `Content-Length` framed message
+pub fn write_message<W: Write>(writer: &mut W, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()
+}
+
+fn respond<W: Write>(writer: &mut W, id: Option<Value>, result: Value) -> io::Result<()> {
+    let response = json!({ "jsonrpc": "2.0", "id": id.unwrap_or(Value::Null), "result": result });
+    write_message(writer, &response)
+}
+
+/// Publish diagnostics for every file with findings, and clear files that
+/// had findings before but no longer do
+fn publish_diagnostics<W: Write>(
+    metrics: &ProjectMetrics,
+    thresholds: &IssueThresholds,
+    published: &mut BTreeSet<String>,
+    writer: &mut W,
+) -> io::Result<()> {
+    let by_uri: BTreeMap<String, Vec<Value>> = diagnostics(metrics, thresholds)
+        .into_iter()
+        .map(|(path, diagnostics)| (path_to_uri(&path), diagnostics))
+        .collect();
+
+    let stale: Vec<String> = published
+        .iter()
+        .filter(|uri| !by_uri.contains_key(*uri))
+        .cloned()
+        .collect();
+    for uri in stale.iter().chain(by_uri.keys()) {
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": {
+                "uri": uri,
+                "diagnostics": by_uri.get(uri).cloned().unwrap_or_default(),
+            },
+        });
+        write_message(writer, &notification)?;
+    }
+
+    *published = by_uri.into_keys().collect();
+    Ok(())
+}
+
+/// LSP diagnostics per absolute file path
+pub fn diagnostics(
+    metrics: &ProjectMetrics,
+    thresholds: &IssueThresholds,
+) -> BTreeMap<PathBuf, Vec<Value>> {
+    let report = analyze_project_balance_with_thresholds(metrics, thresholds);
+    let mut by_file: BTreeMap<PathBuf, Vec<Value>> = BTreeMap::new();
+
+    for issue in &report.issues {
+        let path = match &issue.location.file_path {
+            Some(path) => path.clone(),
+            None => match metrics.find_module(&issue.source) {
+                Some(module) => module.path.clone(),
+                None => continue,
+            },
+        };
+        // LSP positions are 0-based; file-level findings go on the first line
+        let line = issue.location.line.saturating_sub(1);
+        let column = issue.location.column.saturating_sub(1);
+        by_file.entry(absolute(&path)).or_default().push(json!({
+            "range": {
+                "start": { "line": line, "character": column },
+                "end": { "line": line, "character": column },
+            },
+            "severity": diagnostic_severity(thresholds, issue),
+            "code": issue.issue_type.rule_id(),
+            "source": env!("CARGO_PKG_NAME"),
+            "message": format!("{}: {}\n→ {}", issue.issue_type, issue.description, issue.refactoring),
+        }));
+    }
+    by_file
+}
+
+/// Rule level from `[rules.severity]` if set, otherwise by issue severity
+fn diagnostic_severity(thresholds: &IssueThresholds, issue: &CouplingIssue) -> u8 {
+    match thresholds.rules.level(issue.issue_type) {
+        Some(RuleLevel::Error) => DIAGNOSTIC_ERROR,
+        Some(RuleLevel::Warning) => DIAGNOSTIC_WARNING,
+        Some(RuleLevel::Info) => DIAGNOSTIC_INFORMATION,
+        None => match issue.severity {
+            Severity::Critical | Severity::High => DIAGNOSTIC_ERROR,
+            Severity::Medium => DIAGNOSTIC_WARNING,
+            Severity::Low => DIAGNOSTIC_INFORMATION,
+        },
+    }
+}
+
+/// Markdown hover for the module defined in `path`
+pub fn hover_text(
+    metrics: &ProjectMetrics,
+    thresholds: &IssueThresholds,
+    path: &Path,
+) -> Option<String> {
+    let path = absolute(path);
+    let module = metrics
+        .modules
+        .values()
+        .find(|m| absolute(&m.path) == path)?;
+
+    let mut text = format!("**{}**\n\n", module.name);
+    match module_depth_with(module, &thresholds.aposd) {
+        Some(depth) => {
+            text.push_str(&format!(
+                "- Depth ratio: {:.1} ({}; {} lines / interface {})\n- Cognitive load: {} ({})\n- Signature complexity: {}\n- Lifetime leakage: {} items\n",
+                depth.depth_ratio,
+                depth.class,
+                depth.implementation_complexity,
+                depth.interface_complexity,
+                depth.cognitive_load,
+                depth.cognitive_class,
+                depth.signature_complexity,
+                depth.lifetime_leaks
+            ));
+        }
+        None => text.push_str(&format!(
+            "- No public interface\n- Cognitive load: {}\n",
+            cognitive_load(module)
+        )),
+    }
+    text.push_str(&format!(
+        "- Dependencies: {} internal, {} external",
+        module.internal_deps.len(),
+        module.external_deps.len()
+    ));
+    Some(text)
+}
+
+/// Absolute form of a path, resolving symlinks when the file exists
+fn absolute(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| {
+        std::env::current_dir()
+            .map(|dir| dir.join(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    })
+}
+
+/// `file://` URI of an absolute path, percent-encoding reserved characters
+pub fn path_to_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    let path = path.to_string_lossy().replace('\\', "/");
+    if !path.starts_with('/') {
+        uri.push('/');
+    }
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' | b':' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
+/// Path of a `file://` URI
+pub fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?;
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(byte) = encoded
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    let path = String::from_utf8(decoded).ok()?;
+    // `file:///C:/dir` on Windows
+    let path = match path.as_bytes() {
+        [b'/', _, b':', ..] => path[1..].to_string(),
+        _ => path,
+    };
+    Some(PathBuf::from(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{
+        CouplingMetrics, Distance, IntegrationStrength, ModuleMetrics, Volatility,
+    };
+
+    fn frame(message: Value) -> String {
+        let body = message.to_string();
+        format!("Content-Length: {}\r\n\r\n{}", body.len(), body)
+    }
+
+    fn responses(output: &[u8]) -> Vec<Value> {
+        let mut reader = output;
+        std::iter::from_fn(|| read_message(&mut reader).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_uri_round_trip() {
+        let path = Path::new("/home/me/my project/src/lib.rs");
+        let uri = path_to_uri(path);
+        assert_eq!(uri, "file:///home/me/my%20project/src/lib.rs");
+        assert_eq!(uri_to_path(&uri).as_deref(), Some(path));
+        assert_eq!(uri_to_path("untitled:1"), None);
+    }
+
+    #[test]
+    fn test_server_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut metrics = ProjectMetrics::new();
+        for name in ["a", "b"] {
+            let path = dir.path().join(format!("{}.rs", name));
+            std::fs::write(&path, "").unwrap();
+            let mut module = ModuleMetrics::new(path, name.to_string());
+            module.lines_of_code = 40;
+            module.add_function_definition("run".into(), crate::metrics::Visibility::Public);
+            metrics.add_module(module);
+        }
+        for (source, target) in [("a", "b"), ("b", "a")] {
+            metrics.add_coupling(CouplingMetrics::new(
+                format!("crate::{}", source),
+                format!("crate::{}", target),
+                IntegrationStrength::Functional,
+                Distance::DifferentModule,
+                Volatility::Low,
+            ));
+        }
+        let a_uri = path_to_uri(&absolute(&dir.path().join("a.rs")));
+
+        let input: String = [
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }),
+            json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }),
+            json!({ "jsonrpc": "2.0", "id": 2, "method": "textDocument/hover", "params": {
+                "textDocument": { "uri": a_uri }, "position": { "line": 0, "character": 0 }
+            }}),
+            json!({ "jsonrpc": "2.0", "method": "textDocument/didSave", "params": {
+                "textDocument": { "uri": a_uri }
+            }}),
+            json!({ "jsonrpc": "2.0", "id": 3, "method": "workspace/symbol", "params": {} }),
+            json!({ "jsonrpc": "2.0", "id": 4, "method": "shutdown" }),
+            json!({ "jsonrpc": "2.0", "method": "exit" }),
+        ]
+        .into_iter()
+        .map(frame)
+        .collect();
+
+        // After the save the cycle is gone
+        let mut output = Vec::new();
+        run_lsp_server(
+            metrics,
+            &IssueThresholds::default(),
+            || Some(ProjectMetrics::new()),
+            input.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        let messages = responses(&output);
+
+        assert_eq!(messages[0]["result"]["capabilities"]["hoverProvider"], true);
+        let published: Vec<&Value> = messages
+            .iter()
+            .filter(|m| m["method"] == "textDocument/publishDiagnostics")
+            .collect();
+        let first = published
+            .iter()
+            .find(|m| m["params"]["uri"] == a_uri.as_str())
+            .unwrap();
+        assert!(
+            first["params"]["diagnostics"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|d| d["code"] == "coupling::circular-dependency")
+        );
+        // Cleared after the save
+        assert_eq!(
+            published.last().unwrap()["params"]["diagnostics"],
+            json!([])
+        );
+
+        let hover = messages.iter().find(|m| m["id"] == 2).unwrap();
+        let text = hover["result"]["contents"]["value"].as_str().unwrap();
+        assert!(text.starts_with("**a**"));
+        assert!(text.contains("Depth ratio: 40.0"));
+
+        let unsupported = messages.iter().find(|m| m["id"] == 3).unwrap();
+        assert_eq!(unsupported["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(messages.last().unwrap()["id"], 4);
+    }
+}
@@ -8,22 +8,39 @@
 //!   cargo-coupling [OPTIONS] [PATH]
 
 use std::fs::File;
-use std::io::{BufWriter, Write, stdout};
+use std::io::{BufWriter, IsTerminal, Write, stderr, stdout};
 use std::path::PathBuf;
-use std::process;
+use std::process::ExitCode;
 use std::time::Instant;
 
-use clap::{Parser, Subcommand};
+use clap::{ArgGroup, Parser, Subcommand};
 
 use cargo_coupling::{
-    CompiledConfig, IssueThresholds, VolatilityAnalyzer, analyze_workspace,
+    Analysis, ApiSurface, Badge, BadgeMetric, Baseline, BlastRadius, BlastRadiusError,
+    BuildCouplingReport, ColorChoice, CompiledConfig, DEFAULT_HISTORY_FILE, DependencyAudit,
+    DependencyWeightReport, FfiReport, GitWindow, HealthScore, HistoryEntry, IssueThresholds,
+    IssueType, MatrixFormat, ModuleGraph, PlatformReport, ProgressHook, ProjectMetrics,
+    RustdocError, SplitSimulation, SuggestionReport, TraitCouplingReport, UnsafeSurfaceReport,
+    analyze_module_depths_with, analyze_project_balance_with_thresholds, analyze_revision_with,
+    append_entry, average_depth_ratio, check_staged,
     cli_output::{
         CheckConfig, generate_check_output, generate_hotspots_output, generate_impact_output,
-        generate_json_output, parse_grade, parse_severity,
+        generate_json_output, limit_failures, parse_grade, parse_severity,
     },
-    generate_ai_output_with_thresholds, generate_report_with_thresholds,
-    generate_summary_with_thresholds, load_compiled_config,
+    compare_metrics, expand_sources, generate_ai_output_with_thresholds, generate_api_report,
+    generate_blast_radius_report, generate_build_coupling_report, generate_dependency_audit_report,
+    generate_dependency_weight_report, generate_diff_report, generate_dot_output,
+    generate_explanation, generate_ffi_report, generate_health_report, generate_html_output,
+    generate_human_output, generate_markdown_report, generate_matrix_output,
+    generate_module_graph_report, generate_platform_report, generate_quadrant_output,
+    generate_quiet_summary, generate_report_with_thresholds, generate_rule_list,
+    generate_sarif_output, generate_split_report, generate_stability_report,
+    generate_staged_report, generate_suggestion_report, generate_summary_with_thresholds,
+    generate_trait_coupling_report, generate_trend_report, generate_unsafe_surface_report, history,
+    load_compiled_config, load_compiled_config_file, load_history, logging, run_lsp_server,
+    rustdoc_index, terminal_progress,
     web::{ServerConfig, start_server},
+    write_badge,
 };
 
 /// cargo-coupling - Measure the "right distance" in your Rust code
@@ -43,6 +60,47 @@ enum Commands {
 }
 
 #[derive(Parser, Debug)]
+// Output modes select what is printed and cannot be combined
+#[command(group(
+    ArgGroup::new("mode")
+        .multiple(false)
+        .args([
+            "summary",
+            "quiet",
+            "ai",
+            "chapters",
+            "json",
+            "sarif",
+            "html",
+            "dot",
+            "matrix",
+            "module_graph",
+            "api",
+            "traits",
+            "build_coupling",
+            "dependency_weight",
+            "dependency_audit",
+            "unsafe_surface",
+            "platforms",
+            "ffi",
+            "simulate_split",
+            "quadrants",
+            "stability",
+            "check",
+            "hotspots",
+            "health",
+            "badge",
+            "suggest",
+            "impact",
+            "trace",
+            "lsp",
+            "web",
+            "explain",
+            "trend",
+            "baseline",
+            "diff_from",
+        ])
+))]
 struct Args {
     /// Path to the project or directory to analyze
     #[arg(default_value = "./src")]
@@ -56,23 +114,50 @@ struct Args {
     #[arg(short, long)]
     summary: bool,
 
+    /// Print the summary metrics only: grade, score and issue counts
+    #[arg(short, long)]
+    quiet: bool,
+
     /// AI-friendly output format for use with coding agents (Claude, Copilot, etc.)
     #[arg(long)]
     ai: bool,
 
+    /// Markdown report with a table of contents, a chapter per workspace
+    /// member, cross-crate sections and an appendix of all findings
+    #[arg(long)]
+    chapters: bool,
+
     /// Analyze git history for volatility (months to look back)
     #[arg(long, default_value = "6")]
     git_months: usize,
 
+    /// Analyze git history since a tag or other revision instead of --git-months
+    #[arg(long, value_name = "TAG", conflicts_with_all = ["last_commits", "since", "until"])]
+    since_tag: Option<String>,
+
+    /// Analyze the last N commits instead of --git-months
+    #[arg(long, value_name = "N", conflicts_with_all = ["since", "until"])]
+    last_commits: Option<usize>,
+
+    /// Analyze git history after this date (e.g. 2024-01-01) instead of --git-months
+    #[arg(long, value_name = "DATE")]
+    since: Option<String>,
+
+    /// Analyze git history before this date; combine with --since
+    #[arg(long, value_name = "DATE")]
+    until: Option<String>,
+
     /// Skip git history analysis
     #[arg(long)]
     no_git: bool,
 
-    /// Config file path (default: search for .coupling.toml)
+    /// Config file path (default: nearest .coupling.toml, coupling.toml or
+    /// Cargo.toml metadata up to the workspace root)
     #[arg(short, long)]
     config: Option<PathBuf>,
 
-    /// Verbose output
+    /// Verbose output, including debug logs of the analysis phases
+    /// (finer control with RUST_LOG, e.g. RUST_LOG=cargo_coupling=trace)
     #[arg(short, long)]
     verbose: bool,
 
@@ -80,6 +165,56 @@ struct Args {
     #[arg(long)]
     timing: bool,
 
+    /// Only analyze these workspace members (name or glob; repeatable)
+    #[arg(short, long, value_name = "SPEC")]
+    package: Vec<String>,
+
+    /// Analyze every workspace member, not just the default members
+    #[arg(long, conflicts_with = "package")]
+    workspace: bool,
+
+    /// Resolve dependencies with these features (comma-separated; repeatable)
+    #[arg(short = 'F', long, value_name = "FEATURES", value_delimiter = ',')]
+    features: Vec<String>,
+
+    /// Resolve dependencies with all features enabled
+    #[arg(long)]
+    all_features: bool,
+
+    /// Resolve dependencies without the default features
+    #[arg(long)]
+    no_default_features: bool,
+
+    /// Only analyze files matching this glob (repeatable)
+    #[arg(long, value_name = "GLOB")]
+    include: Vec<String>,
+
+    /// Skip files matching this glob, e.g. "**/generated/**" (repeatable)
+    #[arg(long, value_name = "GLOB")]
+    exclude: Vec<String>,
+
+    /// Only report these rules: ids like `temporal::dropped-guard` or
+    /// categories like `aposd` (comma-separated; replaces `[rules] enabled`)
+    #[arg(long, value_name = "RULES", value_delimiter = ',')]
+    only: Vec<String>,
+
+    /// Do not report these rules (comma-separated; adds to `[rules] disabled`)
+    #[arg(long, value_name = "RULES", value_delimiter = ',')]
+    skip: Vec<String>,
+
+    /// Analyze test code (tests/ files, #[cfg(test)] modules) like production code
+    #[arg(long)]
+    include_tests: bool,
+
+    /// Analyze macro-expanded source (runs `cargo expand` per crate; requires cargo-expand)
+    #[arg(long)]
+    expand: bool,
+
+    /// Resolve cross-crate paths through re-exports with rustdoc JSON (runs
+    /// `cargo +nightly doc`; requires a nightly toolchain)
+    #[arg(long)]
+    rustdoc: bool,
+
     /// Number of threads for parallel processing (default: all CPU cores)
     #[arg(long, short = 'j', value_name = "N")]
     jobs: Option<usize>,
@@ -93,6 +228,16 @@ struct Args {
     #[arg(long)]
     max_dependents: Option<usize>,
 
+    /// Show crate stability metrics (Ca, Ce, Instability) for workspace members
+    #[arg(long)]
+    stability: bool,
+
+    // === Editor integration ===
+    /// Run as a Language Server on stdin/stdout, publishing findings as
+    /// diagnostics and module depth/cognitive load on hover
+    #[arg(long, conflicts_with = "output")]
+    lsp: bool,
+
     // === Web visualization options ===
     /// Start web server for interactive visualization
     #[arg(long)]
@@ -115,8 +260,28 @@ struct Args {
     #[arg(long, value_name = "N", num_args = 0..=1, require_equals = true, default_missing_value = "5")]
     hotspots: Option<usize>,
 
-    /// Analyze change impact for a specific module
-    #[arg(long, value_name = "MODULE")]
+    /// Show the top N refactoring actions ranked by estimated coupling score
+    /// improvement (default: 10). Use --suggest or --suggest=N
+    #[arg(long, value_name = "N", num_args = 0..=1, require_equals = true, default_missing_value = "10")]
+    suggest: Option<usize>,
+
+    /// Show the project health score (0-100) and grade with the component
+    /// breakdown; weights come from the `[health]` config section
+    #[arg(long)]
+    health: bool,
+
+    /// Write a README badge to FILE: an SVG, or shields.io endpoint JSON if
+    /// FILE ends in `.json`
+    #[arg(long, value_name = "FILE")]
+    badge: Option<PathBuf>,
+
+    /// What the badge shows: the health grade or the average depth ratio
+    #[arg(long, value_name = "METRIC", default_value = "grade", value_parser = ["grade", "depth"], requires = "badge")]
+    badge_metric: String,
+
+    /// Analyze change impact for a specific module, or predict how many
+    /// files a change to an item touches (e.g. "metrics::ProjectMetrics")
+    #[arg(long, value_name = "MODULE|ITEM")]
     impact: Option<String>,
 
     /// Trace dependencies for a specific function/type (e.g., "analyze_file" or "BalanceScore")
@@ -143,30 +308,260 @@ struct Args {
     #[arg(long, value_name = "SEVERITY", requires = "check")]
     fail_on: Option<String>,
 
+    /// Fail when a source file cannot be parsed (with --check: a gate
+    /// failure; otherwise a non-zero exit after the report)
+    #[arg(long)]
+    strict: bool,
+
+    /// Check only the files staged in the git index (and their direct
+    /// dependents), failing on issues that are new since HEAD
+    #[arg(long, requires = "check")]
+    staged: bool,
+
     /// Output in JSON format (machine-readable)
     #[arg(long)]
     json: bool,
 
+    /// Output findings in SARIF 2.1.0 format (for GitHub Code Scanning)
+    #[arg(long)]
+    sarif: bool,
+
+    /// Output a self-contained HTML report with sortable tables
+    #[arg(long)]
+    html: bool,
+
+    /// Output the crate dependency graph in Graphviz DOT format
+    #[arg(long)]
+    dot: bool,
+
+    /// Output the crate-to-crate coupling matrix (markdown, csv or json; default: markdown)
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "markdown",
+        value_parser = ["markdown", "md", "csv", "json"]
+    )]
+    matrix: Option<String>,
+
+    /// Show per-module fan-in/fan-out from `use` statements and `mod` declarations
+    #[arg(long)]
+    module_graph: bool,
+
+    /// List each crate's public API and which workspace crates use each item
+    #[arg(long)]
+    api: bool,
+
+    /// Show trait impls and trait bounds between workspace crates next to type coupling
+    #[arg(long)]
+    traits: bool,
+
+    /// Split crate dependencies into runtime, build-script, proc-macro and dev
+    /// dependencies and show which crates each proc macro rebuilds
+    #[arg(long)]
+    build_coupling: bool,
+
+    /// Count how many distinct items of each external dependency every crate
+    /// uses, flagging single-use and heavily used dependencies
+    #[arg(long)]
+    dependency_weight: bool,
+
+    /// Report declared dependencies no code uses and crates the workspace
+    /// depends on in incompatible versions
+    #[arg(long)]
+    dependency_audit: bool,
+
+    /// List the modules with unsafe blocks or functions by unsafe surface
+    /// (unsafe code × (1 + fan-in))
+    #[arg(long)]
+    unsafe_surface: bool,
+
+    /// Show which modules and crates name target platforms in `cfg`s, and
+    /// which modules mix platform-specific with portable code
+    #[arg(long)]
+    platforms: bool,
+
+    /// List the modules coupled to the C ABI (extern blocks, #[no_mangle],
+    /// #[repr(C)]) and where foreign resources are allocated and freed
+    #[arg(long)]
+    ffi: bool,
+
+    /// Simulate extracting --split-modules of this crate into a new crate and
+    /// show the crate references, instability and cycles before and after
+    #[arg(long, value_name = "CRATE", requires = "split_modules")]
+    simulate_split: Option<String>,
+
+    /// Modules to extract for --simulate-split, with their submodules
+    /// (comma-separated, e.g. "net,storage::cache")
+    #[arg(
+        long,
+        value_name = "MODULES",
+        value_delimiter = ',',
+        requires = "simulate_split"
+    )]
+    split_modules: Vec<String>,
+
+    /// Name of the extracted crate for --simulate-split (default: <CRATE>-split)
+    #[arg(long, value_name = "NAME", requires = "simulate_split")]
+    split_name: Option<String>,
+
+    /// Rank modules by complexity × git churn and place them in hotspot quadrants
+    /// (markdown, csv or json scatter-plot data; default: markdown)
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "markdown",
+        value_parser = ["markdown", "md", "csv", "json"],
+        conflicts_with = "no_git"
+    )]
+    quadrants: Option<String>,
+
+    /// Explain a finding by rule id (e.g. `temporal::unpaired-operation`) and exit;
+    /// without a value, list all rule ids
+    #[arg(
+        long,
+        value_name = "RULE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "list"
+    )]
+    explain: Option<String>,
+
+    /// Append this run's summary metrics to a history file
+    /// (default: .coupling-history.jsonl)
+    #[arg(
+        long,
+        value_name = "FILE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = DEFAULT_HISTORY_FILE
+    )]
+    record_history: Option<PathBuf>,
+
+    /// Show metric trends from a history file and exit
+    /// (default: .coupling-history.jsonl)
+    #[arg(
+        long,
+        value_name = "FILE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = DEFAULT_HISTORY_FILE
+    )]
+    trend: Option<PathBuf>,
+
+    /// Record current findings to a baseline file and exit
+    #[arg(long, value_name = "FILE", conflicts_with = "compare_baseline")]
+    baseline: Option<PathBuf>,
+
+    /// Only report findings that are not in the given baseline file
+    #[arg(long, value_name = "FILE")]
+    compare_baseline: Option<PathBuf>,
+
+    /// Compare coupling against an earlier git revision (e.g. `main`) and exit
+    #[arg(long, value_name = "REF")]
+    diff_from: Option<String>,
+
+    /// Revision to compare with --diff-from
+    #[arg(
+        long,
+        value_name = "REF",
+        default_value = "HEAD",
+        requires = "diff_from"
+    )]
+    diff_to: String,
+
     /// Show all issues including Low severity (default: only Medium/High/Critical)
     #[arg(long)]
     all: bool,
 
+    /// List only findings at this severity or higher (critical, high,
+    /// medium, low); counts and the grade still include the others
+    #[arg(long, value_name = "SEVERITY", conflicts_with = "check",
+          value_parser = ["critical", "high", "medium", "low"])]
+    min_severity: Option<String>,
+
+    /// List only the N worst findings of each issue type; counts and the
+    /// grade still include the others
+    #[arg(long, value_name = "N", conflicts_with = "check")]
+    top: Option<usize>,
+
     /// Show explanations in Japanese (日本語で解説を表示)
     #[arg(long, visible_alias = "jp")]
     japanese: bool,
+
+    /// Color terminal output: auto, always or never (auto honors NO_COLOR)
+    #[arg(long, value_name = "WHEN", default_value = "auto", value_parser = ["auto", "always", "never"])]
+    color: String,
 }
 
-fn main() {
-    if let Err(e) = run() {
-        eprintln!("Error: {}", e);
-        process::exit(1);
+fn main() -> ExitCode {
+    match run() {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Writer for the report: the `--output` file, or stdout
+fn open_output(path: Option<&PathBuf>) -> std::io::Result<Box<dyn Write>> {
+    Ok(match path {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(stdout()),
+    })
+}
+
+/// Git history window from `--since-tag`, `--last-commits`, `--since`/`--until`
+/// or, by default, `--git-months`
+fn git_window(args: &Args) -> GitWindow {
+    if let Some(tag) = &args.since_tag {
+        GitWindow::SinceTag(tag.clone())
+    } else if let Some(count) = args.last_commits {
+        GitWindow::LastCommits(count)
+    } else if args.since.is_some() || args.until.is_some() {
+        GitWindow::Dates {
+            since: args.since.clone(),
+            until: args.until.clone(),
+        }
+    } else {
+        GitWindow::Months(args.git_months)
     }
 }
 
-fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// Run the command; the exit code is returned rather than exited with so
+/// that every report writer is flushed and dropped first
+fn run() -> Result<ExitCode, Box<dyn std::error::Error>> {
     let cli = Cli::parse();
 
     let Commands::Coupling(args) = cli.command;
+    let logging = logging::init(args.verbose);
+
+    // --explain: Teaching material for a finding type, no analysis needed
+    if let Some(rule) = &args.explain {
+        let mut writer = open_output(args.output.as_ref())?;
+        if rule == "list" {
+            generate_rule_list(&mut writer)?;
+            return Ok(ExitCode::SUCCESS);
+        }
+        let Some(issue_type) = IssueType::from_rule_id(rule) else {
+            generate_rule_list(&mut stderr())?;
+            return Err(format!("Unknown rule id '{}'", rule).into());
+        };
+        generate_explanation(issue_type, &mut writer)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // --trend: Metrics over time from the history file
+    if let Some(path) = &args.trend {
+        let entries = load_history(path)?;
+        let mut writer = open_output(args.output.as_ref())?;
+        generate_trend_report(&entries, &mut writer)?;
+        return Ok(ExitCode::SUCCESS);
+    }
 
     // Detect available CPU cores
     let available_cores = std::thread::available_parallelism()
@@ -191,80 +586,208 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
 
     let total_start = Instant::now();
 
-    // Load configuration file
-    let config_path = args.config.as_ref().unwrap_or(&args.path);
-    let mut config = match load_compiled_config(config_path) {
+    // Load configuration file: --config, or the nearest one up to the workspace root
+    let loaded = match &args.config {
+        Some(path) => load_compiled_config_file(path),
+        None => load_compiled_config(&args.path),
+    };
+    let config = match loaded {
         Ok(config) => {
-            if args.verbose && config.has_volatility_overrides() {
-                eprintln!("Loaded configuration from .coupling.toml");
+            if args.verbose
+                && let Some(source) = &config.source
+            {
+                eprintln!("Loaded configuration from {}", source.display());
             }
             config
         }
+        Err(e) if args.config.is_some() => {
+            return Err(format!("Invalid config file: {}", e).into());
+        }
         Err(e) => {
-            if args.verbose {
-                eprintln!("Note: No config file loaded: {}", e);
-            }
+            eprintln!("Warning: Ignoring config file: {}", e);
             CompiledConfig::empty()
         }
     };
 
-    // Print analysis header
-    eprintln!("Analyzing project at '{}'...", args.path.display());
+    // The analysis: --package and features, [paths] globs extended by
+    // --include/--exclude, --only replacing the configured rule list and
+    // --skip adding to it, and the git history window
+    let mut builder = Analysis::builder()
+        .path(&args.path)
+        .config(config.clone())
+        .workspace(args.workspace)
+        .all_features(args.all_features)
+        .no_default_features(args.no_default_features)
+        .include_tests(args.include_tests)
+        .git_window(git_window(&args))
+        .all_issues(args.all);
+    for package in &args.package {
+        builder = builder.package(package);
+    }
+    for feature in &args.features {
+        builder = builder.feature(feature);
+    }
+    for pattern in &args.include {
+        builder = builder.include(pattern);
+    }
+    for pattern in &args.exclude {
+        builder = builder.exclude(pattern);
+    }
+    for selector in &args.only {
+        builder = builder.only(selector);
+    }
+    for selector in &args.skip {
+        builder = builder.skip(selector);
+    }
+    if args.no_git {
+        builder = builder.no_git();
+    }
+    let mut analysis = builder.build()?;
+
+    // CLI thresholds apply to every crate and path
+    let mut crate_thresholds = config.crate_thresholds.clone();
+    let mut path_thresholds = config.path_thresholds.clone();
+    for overrides in crate_thresholds
+        .values_mut()
+        .chain(path_thresholds.iter_mut().map(|o| &mut o.thresholds))
+    {
+        if args.max_deps.is_some() {
+            overrides.max_dependencies = None;
+        }
+        if args.max_dependents.is_some() {
+            overrides.max_dependents = None;
+        }
+    }
 
-    // Analyze the project (uses cargo metadata for better accuracy)
-    let analysis_start = Instant::now();
-    let mut metrics = analyze_workspace(&args.path)?;
-    let analysis_time = analysis_start.elapsed();
+    // Create custom thresholds - CLI args override config, which overrides defaults
+    let mut thresholds = IssueThresholds {
+        max_dependencies: args.max_deps.unwrap_or(config.thresholds.max_dependencies),
+        max_dependents: args
+            .max_dependents
+            .unwrap_or(config.thresholds.max_dependents),
+        crate_thresholds,
+        path_thresholds,
+        show_info: args.verbose,
+        min_severity: args.min_severity.as_deref().and_then(parse_severity),
+        top_per_type: args.top,
+        japanese: args.japanese,
+        ..analysis.thresholds().clone()
+    };
 
-    // Analyze git history for volatility (if not disabled)
-    if !args.no_git {
-        if args.verbose {
-            eprintln!("Analyzing git history ({} months)...", args.git_months);
-        }
+    if args.verbose {
+        eprintln!(
+            "Thresholds: max_deps={}, max_dependents={}",
+            thresholds.max_dependencies, thresholds.max_dependents
+        );
+    }
 
-        let mut volatility = VolatilityAnalyzer::new(args.git_months);
-        match volatility.analyze(&args.path) {
-            Ok(()) => {
-                if args.verbose {
-                    let stats = volatility.statistics();
-                    eprintln!(
-                        "Git analysis: {} files, {} total changes",
-                        stats.total_files, stats.total_changes
-                    );
-                }
+    // Custom paired operations need the files analyzed again
+    let temporal_rules = config
+        .temporal
+        .customizes_analysis()
+        .then(|| config.temporal.rules());
 
-                // Copy file changes to project metrics (must be after statistics())
-                metrics.file_changes = volatility.file_changes;
+    // --diff-from: Compare two git revisions and exit
+    if let Some(from) = &args.diff_from {
+        eprintln!(
+            "Comparing '{}' between {} and {}...",
+            args.path.display(),
+            from,
+            args.diff_to
+        );
+        let scope = analysis.scope();
+        let before = analyze_revision_with(&args.path, from, scope, temporal_rules.as_ref())?;
+        let after =
+            analyze_revision_with(&args.path, &args.diff_to, scope, temporal_rules.as_ref())?;
+        let diff = compare_metrics(from, &before, &args.diff_to, &after, &thresholds);
+        let mut writer = open_output(args.output.as_ref())?;
+        generate_diff_report(&diff, &mut writer)?;
+        return Ok(ExitCode::SUCCESS);
+    }
 
-                // Update volatility for all couplings based on git history
-                metrics.update_volatility_from_git();
-            }
-            Err(e) => {
-                if args.verbose {
-                    eprintln!("Warning: Git analysis failed: {}", e);
+    // --check --staged: Pre-commit check of the git index against HEAD
+    if args.staged {
+        let check = check_staged(
+            &args.path,
+            analysis.scope(),
+            temporal_rules.as_ref(),
+            &thresholds,
+        )?;
+        let mut writer = open_output(args.output.as_ref())?;
+        generate_staged_report(&check, &mut writer)?;
+        writer.flush()?;
+        return Ok(exit_status(check.exit_code()));
+    }
+
+    // --expand: Analyze the cargo expand output instead of the files on disk
+    if args.expand {
+        eprintln!("Expanding macros with cargo expand...");
+        let expanded = expand_sources(&args.path, analysis.scope())?;
+        analysis.scope_mut().expanded = expanded;
+    }
+
+    // --rustdoc: Resolve re-exported items to their defining crate
+    if args.rustdoc {
+        eprintln!("Documenting workspace libraries with rustdoc JSON...");
+        match rustdoc_index(&args.path, analysis.scope()) {
+            Ok(index) => analysis.scope_mut().rustdoc = index,
+            Err(RustdocError::Failed(stderr)) => {
+                eprintln!("Warning: cargo doc failed, resolving paths statically");
+                if let Some(line) = stderr.lines().rfind(|l| l.starts_with("error")) {
+                    eprintln!("  {}", line);
                 }
             }
+            Err(e) => return Err(e.into()),
         }
     }
 
-    // Apply volatility overrides from config
-    if config.has_volatility_overrides() {
-        let mut override_count = 0;
-        for coupling in &mut metrics.couplings {
-            // Use the target path for volatility lookup
-            if let Some(override_vol) = config.get_volatility_override(&coupling.target) {
-                coupling.volatility = override_vol;
-                override_count += 1;
-            }
-        }
-        if args.verbose && override_count > 0 {
-            eprintln!(
-                "Applied {} volatility overrides from config",
-                override_count
-            );
-        }
+    // Print analysis header
+    eprintln!("Analyzing project at '{}'...", args.path.display());
+
+    // Progress bar on interactive terminals; verbose output and logs would
+    // garble it
+    let progress = if args.verbose || logging {
+        None
+    } else {
+        terminal_progress()
+    }
+    .unwrap_or_default();
+    analysis.scope_mut().progress = progress;
+
+    // Analyze the project (uses cargo metadata for better accuracy)
+    let analysis_start = Instant::now();
+    let metrics = analysis.metrics()?;
+    let analysis_time = analysis_start.elapsed();
+    analysis.scope_mut().progress = ProgressHook::default();
+    if args.package.is_empty() && metrics.analyzed_members.len() < metrics.workspace_members.len() {
+        eprintln!(
+            "Note: Analyzing the default members ({}); use --workspace for all {} members",
+            metrics.analyzed_members.join(", "),
+            metrics.workspace_members.len()
+        );
     }
 
+    for skipped in &metrics.skipped_files {
+        eprintln!(
+            "Warning: Skipped {}: {}",
+            skipped.path.display(),
+            skipped.error
+        );
+    }
+    let tests = metrics.test_code();
+    if !tests.is_empty() && args.verbose {
+        eprintln!(
+            "Test code skipped: {} files, {} test modules, {} tests, {} lines (use --include-tests to analyze it)",
+            tests.files, tests.modules, tests.functions, tests.lines_of_code
+        );
+    }
+    if args.expand {
+        eprintln!(
+            "Macro-expanded: {} of {} modules (line numbers refer to cargo expand output)",
+            metrics.expanded_module_count(),
+            metrics.module_count()
+        );
+    }
     if args.timing {
         eprintln!(
             "Analysis complete: {} files, {} modules (took {:.2?})\n",
@@ -280,22 +803,66 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
         );
     }
 
-    // Create custom thresholds - CLI args override config, which overrides defaults
-    let thresholds = IssueThresholds {
-        max_dependencies: args.max_deps.unwrap_or(config.thresholds.max_dependencies),
-        max_dependents: args
-            .max_dependents
-            .unwrap_or(config.thresholds.max_dependents),
-        strict_mode: !args.all, // Default is strict (hide Low), --all shows everything
-        japanese: args.japanese,
-        ..IssueThresholds::default()
-    };
+    // --record-history: Append this run to the trend history
+    if let Some(path) = &args.record_history {
+        let entry = HistoryEntry::capture(
+            &metrics,
+            &thresholds,
+            history::now(),
+            history::current_git_sha(&args.path),
+        );
+        append_entry(path, &entry)?;
+        if args.verbose {
+            eprintln!("Recorded run in {}", path.display());
+        }
+    }
 
-    if args.verbose {
+    // --baseline: Record all current findings (every severity) and exit
+    if let Some(path) = &args.baseline {
+        let all_issues = IssueThresholds {
+            strict_mode: false,
+            ..thresholds.clone()
+        };
+        let report = analyze_project_balance_with_thresholds(&metrics, &all_issues);
+        Baseline::from_issues(&report.issues, &metrics).save(path)?;
         eprintln!(
-            "Thresholds: max_deps={}, max_dependents={}",
-            thresholds.max_dependencies, thresholds.max_dependents
+            "Baseline with {} findings written to: {}",
+            report.issues.len(),
+            path.display()
         );
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // --compare-baseline: Suppress findings recorded in the baseline
+    if let Some(path) = &args.compare_baseline {
+        let baseline = Baseline::load(path)?;
+        if args.verbose {
+            eprintln!(
+                "Loaded baseline with {} findings from {}",
+                baseline.findings.len(),
+                path.display()
+            );
+        }
+        thresholds.baseline = Some(baseline);
+    }
+
+    // --lsp: Serve diagnostics to an editor; saves trigger a fresh static analysis
+    if args.lsp {
+        let reanalyze = || match analysis.refresh() {
+            Ok(fresh) => Some(fresh),
+            Err(e) => {
+                eprintln!("Warning: Re-analysis failed: {}", e);
+                None
+            }
+        };
+        run_lsp_server(
+            metrics,
+            &thresholds,
+            reanalyze,
+            std::io::stdin().lock(),
+            stdout().lock(),
+        )?;
+        return Ok(ExitCode::SUCCESS);
     }
 
     // Web visualization mode
@@ -311,26 +878,158 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
         rt.block_on(start_server(metrics, thresholds, server_config))
             .map_err(|e| -> Box<dyn std::error::Error> { e })?;
 
-        return Ok(());
+        return Ok(ExitCode::SUCCESS);
     }
 
     // Generate output
-    let output: Box<dyn Write> = match &args.output {
-        Some(path) => {
-            let file = File::create(path)?;
-            Box::new(BufWriter::new(file))
-        }
-        None => Box::new(stdout()),
-    };
+    let mut writer = open_output(args.output.as_ref())?;
+    let status = write_report(&args, &metrics, &thresholds, &config, &mut writer)?;
+    writer.flush()?;
+    drop(writer);
 
-    let mut writer = output;
+    // Show total timing
+    if args.timing {
+        let total_time = total_start.elapsed();
+        let files_per_sec = metrics.total_files as f64 / total_time.as_secs_f64();
+        eprintln!(
+            "Total time: {:.2?} ({:.1} files/sec)",
+            total_time, files_per_sec
+        );
+    }
 
+    // --check applies the limits itself; failed modes keep their status
+    if args.check || status != ExitCode::SUCCESS {
+        return Ok(status);
+    }
+    Ok(enforce_limits(&metrics, &thresholds, &config, args.strict))
+}
+
+/// Write the report of the selected output mode, returning its exit status
+/// before `[thresholds]` limits are applied
+fn write_report(
+    args: &Args,
+    metrics: &ProjectMetrics,
+    thresholds: &IssueThresholds,
+    config: &CompiledConfig,
+    writer: &mut impl Write,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
     // Job-focused CLI modes (mutually exclusive with other modes)
 
     // --json: Machine-readable JSON output
     if args.json {
-        generate_json_output(&metrics, &thresholds, &mut writer)?;
-        return Ok(());
+        generate_json_output(metrics, thresholds, writer)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // --sarif: SARIF output for code scanning tools
+    if args.sarif {
+        generate_sarif_output(metrics, thresholds, writer)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // --html: Self-contained HTML report
+    if args.html {
+        generate_html_output(metrics, thresholds, writer)?;
+        if let Some(path) = &args.output {
+            eprintln!("Report written to: {}", path.display());
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // --dot: Graphviz dependency graph
+    if args.dot {
+        generate_dot_output(metrics, writer)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // --matrix: Crate-to-crate coupling matrix
+    if let Some(format) = &args.matrix {
+        let format = MatrixFormat::parse(format).unwrap_or_default();
+        generate_matrix_output(metrics, format, writer)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // --module-graph: Module fan-in/fan-out inside each crate
+    if args.module_graph {
+        generate_module_graph_report(&ModuleGraph::build(metrics), writer)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // --api: Public items and their downstream users
+    if args.api {
+        generate_api_report(&ApiSurface::build(metrics), writer)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // --traits: Trait impls and bounds across crates
+    if args.traits {
+        generate_trait_coupling_report(&TraitCouplingReport::build(metrics), writer)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // --build-coupling: Build-time vs runtime crate dependencies
+    if args.build_coupling {
+        generate_build_coupling_report(&BuildCouplingReport::build(metrics), writer)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // --dependency-weight: Items used per external dependency
+    if args.dependency_weight {
+        generate_dependency_weight_report(&DependencyWeightReport::build(metrics), writer)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // --dependency-audit: Unused and version-skewed dependencies
+    if args.dependency_audit {
+        generate_dependency_audit_report(&DependencyAudit::build(metrics), writer)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // --unsafe-surface: Unsafe code weighted by fan-in
+    if args.unsafe_surface {
+        generate_unsafe_surface_report(&UnsafeSurfaceReport::build(metrics), writer)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // --platforms: Conditional-compilation coupling to target platforms
+    if args.platforms {
+        generate_platform_report(&PlatformReport::build(metrics), writer)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // --ffi: Coupling to the C ABI
+    if args.ffi {
+        generate_ffi_report(&FfiReport::build(metrics), writer)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // --simulate-split: Crate coupling as if modules were extracted
+    if let Some(krate) = &args.simulate_split {
+        let new_crate = args
+            .split_name
+            .clone()
+            .unwrap_or_else(|| format!("{}-split", krate));
+        match SplitSimulation::simulate(metrics, krate, &args.split_modules, &new_crate) {
+            Ok(simulation) => generate_split_report(&simulation, writer)?,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // --quadrants: Complexity × churn hotspot quadrants
+    if let Some(format) = &args.quadrants {
+        let format = MatrixFormat::parse(format).unwrap_or_default();
+        generate_quadrant_output(metrics, format, writer)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // --stability: Crate-level Ca/Ce/Instability
+    if args.stability {
+        generate_stability_report(&metrics.crate_stability, &config.stability, writer)?;
+        return Ok(ExitCode::SUCCESS);
     }
 
     // --check: Quality gate check (returns exit code)
@@ -340,43 +1039,107 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
             max_critical: args.max_critical,
             max_circular: args.max_circular,
             fail_on: args.fail_on.as_ref().and_then(|s| parse_severity(s)),
+            limits: config.thresholds.clone(),
+            aposd: config.aposd.clone(),
+            strict: args.strict,
         };
-        let exit_code = generate_check_output(&metrics, &thresholds, &check_config, &mut writer)?;
-        process::exit(exit_code);
+        let exit_code = generate_check_output(metrics, thresholds, &check_config, writer)?;
+        return Ok(exit_status(exit_code));
     }
 
     // --hotspots: Show top refactoring targets
     if let Some(limit) = args.hotspots {
-        generate_hotspots_output(&metrics, &thresholds, limit, args.verbose, &mut writer)?;
-        return Ok(());
+        generate_hotspots_output(metrics, thresholds, limit, args.verbose, writer)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // --health: Weighted project health score
+    if args.health {
+        let health = HealthScore::calculate(metrics, thresholds, &config.aposd, &config.health);
+        generate_health_report(&health, writer)?;
+        return Ok(ExitCode::SUCCESS);
     }
 
-    // --impact: Analyze impact of a specific module
+    // --badge: Health grade or depth ratio badge for READMEs
+    if let Some(path) = &args.badge {
+        let badge = match BadgeMetric::parse(&args.badge_metric).unwrap_or_default() {
+            BadgeMetric::Grade => Badge::health(&HealthScore::calculate(
+                metrics,
+                thresholds,
+                &config.aposd,
+                &config.health,
+            )),
+            BadgeMetric::Depth => Badge::depth_ratio(
+                average_depth_ratio(&analyze_module_depths_with(metrics, &config.aposd)),
+                &config.aposd,
+            ),
+        };
+        let mut file = BufWriter::new(File::create(path)?);
+        write_badge(&badge, path, &mut file)?;
+        file.flush()?;
+        eprintln!("Badge written to: {}", path.display());
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // --suggest: Refactoring actions ranked by payoff
+    if let Some(limit) = args.suggest {
+        let report = SuggestionReport::build(metrics, thresholds, &config.aposd);
+        generate_suggestion_report(&report, limit, writer)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // --impact: Analyze impact of a specific module, or the blast radius of
+    // an item when no module has that name
     if let Some(module_name) = &args.impact {
-        let found = generate_impact_output(&metrics, module_name, &mut writer)?;
-        if !found {
-            process::exit(1);
+        if !metrics.modules.contains_key(module_name) {
+            match BlastRadius::analyze(metrics, module_name) {
+                Ok(radius) => {
+                    generate_blast_radius_report(&radius, writer)?;
+                    return Ok(ExitCode::SUCCESS);
+                }
+                Err(e @ BlastRadiusError::Ambiguous { .. }) => {
+                    eprintln!("Error: {}", e);
+                    return Ok(ExitCode::FAILURE);
+                }
+                Err(BlastRadiusError::NotFound(_)) => {}
+            }
         }
-        return Ok(());
+        let found = generate_impact_output(metrics, module_name, writer)?;
+        return Ok(if found {
+            ExitCode::SUCCESS
+        } else {
+            ExitCode::FAILURE
+        });
     }
 
     // --trace: Trace dependencies for a specific function/type
     if let Some(item_name) = &args.trace {
-        let found =
-            cargo_coupling::cli_output::generate_trace_output(&metrics, item_name, &mut writer)?;
-        if !found {
-            process::exit(1);
-        }
-        return Ok(());
+        let found = cargo_coupling::cli_output::generate_trace_output(metrics, item_name, writer)?;
+        return Ok(if found {
+            ExitCode::SUCCESS
+        } else {
+            ExitCode::FAILURE
+        });
     }
 
     // Default modes
     if args.ai {
-        generate_ai_output_with_thresholds(&metrics, &thresholds, &mut writer)?;
+        generate_ai_output_with_thresholds(metrics, thresholds, writer)?;
+    } else if args.quiet {
+        generate_quiet_summary(metrics, thresholds, writer)?;
     } else if args.summary {
-        generate_summary_with_thresholds(&metrics, &thresholds, &mut writer)?;
+        generate_summary_with_thresholds(metrics, thresholds, writer)?;
+    } else if args.chapters {
+        generate_markdown_report(metrics, thresholds, writer)?;
+    } else if args.output.is_none() && stdout().is_terminal() && !args.japanese {
+        // Terminals get cargo-style diagnostics instead of Markdown
+        let color = ColorChoice::parse(&args.color)
+            .unwrap_or_default()
+            .enabled(true);
+        let base = std::env::current_dir().unwrap_or_default();
+        generate_human_output(metrics, thresholds, &base, color, writer)?;
     } else {
-        generate_report_with_thresholds(&metrics, &thresholds, &mut writer)?;
+        generate_report_with_thresholds(metrics, thresholds, writer)?;
     }
 
     // Notify about output file
@@ -384,15 +1147,37 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
         eprintln!("Report written to: {}", path.display());
     }
 
-    // Show total timing
-    if args.timing {
-        let total_time = total_start.elapsed();
-        let files_per_sec = metrics.total_files as f64 / total_time.as_secs_f64();
-        eprintln!(
-            "Total time: {:.2?} ({:.1} files/sec)",
-            total_time, files_per_sec
-        );
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Exit non-zero on findings of rules set to `error`, when a `[thresholds]`
+/// limit from the config is exceeded, or under `--strict` when files could
+/// not be parsed
+fn enforce_limits(
+    metrics: &ProjectMetrics,
+    thresholds: &IssueThresholds,
+    config: &CompiledConfig,
+    strict: bool,
+) -> ExitCode {
+    let failures = limit_failures(
+        metrics,
+        thresholds,
+        &config.thresholds,
+        &config.aposd,
+        strict,
+    );
+    if failures.is_empty() {
+        return ExitCode::SUCCESS;
     }
 
-    Ok(())
+    eprintln!("Threshold check failed:");
+    for failure in &failures {
+        eprintln!("  - {}", failure);
+    }
+    ExitCode::FAILURE
+}
+
+/// Exit status for a numeric exit code (0 success, anything else failure)
+fn exit_status(code: i32) -> ExitCode {
+    u8::try_from(code).map_or(ExitCode::FAILURE, ExitCode::from)
 }
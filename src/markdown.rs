@@ -0,0 +1,477 @@
+//! Structured Markdown report
+//!
+//! A multi-section document instead of the flat report: an overview, one
+//! chapter per workspace member (its modules and the findings in them), a
+//! cross-crate chapter (crate stability, references between members, crate
+//! cycles) and an appendix with every finding. Sections get anchors and
+//! are listed in a table of contents; chapters link their findings to the
+//! appendix entries (`#finding-N`).
+//!
+//! [`MarkdownDocument`] handles the layout and can be used for other
+//! documents as well.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+use crate::balance::{
+    CouplingIssue, IssueThresholds, Severity, analyze_project_balance_with_thresholds,
+};
+use crate::metrics::{ModuleMetrics, ProjectMetrics};
+
+/// Findings listed per crate chapter before pointing to the appendix
+const CHAPTER_FINDINGS: usize = 10;
+
+/// A section with a Markdown body and subsections
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MarkdownSection {
+    pub title: String,
+    pub body: String,
+    pub children: Vec<MarkdownSection>,
+}
+
+impl MarkdownSection {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            ..Self::default()
+        }
+    }
+}
+
+/// A Markdown document with a table of contents
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MarkdownDocument {
+    pub title: String,
+    /// Text between the title and the table of contents
+    pub intro: String,
+    pub sections: Vec<MarkdownSection>,
+}
+
+impl MarkdownDocument {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Render the document; every section gets a unique anchor from its
+    /// title (`Crate: core` -> `crate-core`, then `crate-core-1`, ...)
+    pub fn render<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let mut used = HashSet::new();
+        let mut anchored = Vec::new();
+        for section in &self.sections {
+            assign_anchors(section, 0, &mut used, &mut anchored);
+        }
+
+        writeln!(writer, "# {}\n", self.title)?;
+        if !self.intro.is_empty() {
+            writeln!(writer, "{}", self.intro.trim_end())?;
+            writeln!(writer)?;
+        }
+        writeln!(writer, "## Contents\n")?;
+        for (section, depth, anchor) in &anchored {
+            writeln!(
+                writer,
+                "{}- [{}](#{})",
+                "  ".repeat(*depth),
+                section.title,
+                anchor
+            )?;
+        }
+        writeln!(writer)?;
+
+        for (section, depth, anchor) in &anchored {
+            writeln!(writer, "<a id=\"{}\"></a>\n", anchor)?;
+            writeln!(writer, "{} {}\n", "#".repeat(depth + 2), section.title)?;
+            if !section.body.is_empty() {
+                writeln!(writer, "{}", section.body.trim_end())?;
+                writeln!(writer)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Flatten sections in document order with their depth and anchor
+fn assign_anchors<'a>(
+    section: &'a MarkdownSection,
+    depth: usize,
+    used: &mut HashSet<String>,
+    out: &mut Vec<(&'a MarkdownSection, usize, String)>,
+) {
+    let slug = slug(&section.title);
+    let mut anchor = slug.clone();
+    let mut n = 0;
+    while !used.insert(anchor.clone()) {
+        n += 1;
+        anchor = format!("{}-{}", slug, n);
+    }
+    out.push((section, depth, anchor));
+    for child in &section.children {
+        assign_anchors(child, depth + 1, used, out);
+    }
+}
+
+/// GitHub-style heading slug
+pub fn slug(title: &str) -> String {
+    let slug: String = title
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            c if c.is_alphanumeric() || c == '_' || c == '-' => Some(c),
+            ' ' => Some('-'),
+            _ => None,
+        })
+        .collect();
+    if slug.is_empty() {
+        "section".into()
+    } else {
+        slug
+    }
+}
+
+/// Escape text for a table cell
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+fn severity_icon(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "🔴",
+        Severity::High => "🟠",
+        Severity::Medium => "🟡",
+        Severity::Low => "⚪",
+    }
+}
+
+/// Crate (chapter) an issue belongs to, from its file or source module
+///
+/// The file is exact; module names are only unique within a crate.
+fn issue_crate<'a>(metrics: &'a ProjectMetrics, issue: &CouplingIssue) -> Option<&'a str> {
+    let by_file = issue
+        .location
+        .file_path
+        .as_ref()
+        .and_then(|file| metrics.modules.values().find(|m| &m.path == file));
+    by_file
+        .or_else(|| metrics.find_module(&issue.source))
+        .map(|m| chapter_name(metrics, m))
+}
+
+fn chapter_name<'a>(metrics: &'a ProjectMetrics, module: &'a ModuleMetrics) -> &'a str {
+    module
+        .crate_name
+        .as_deref()
+        .or(metrics.workspace_name.as_deref())
+        .unwrap_or("crate")
+}
+
+/// Build the structured report
+pub fn build_markdown_report(
+    metrics: &ProjectMetrics,
+    thresholds: &IssueThresholds,
+) -> MarkdownDocument {
+    let report = analyze_project_balance_with_thresholds(metrics, thresholds);
+    let project = metrics.workspace_name.as_deref().unwrap_or("project");
+    let mut doc = MarkdownDocument::new(format!("Coupling Analysis: {}", project));
+
+    let count = |severity| *report.issues_by_severity.get(&severity).unwrap_or(&0);
+    let _ = writeln!(
+        doc.intro,
+        "**Health Grade**: {} | **Balance Score**: {:.2}/1.00",
+        report.health_grade, report.average_score
+    );
+
+    // Overview
+    let mut overview = MarkdownSection::new("Overview");
+    let body = &mut overview.body;
+    let _ = writeln!(body, "| Metric | Value |");
+    let _ = writeln!(body, "|--------|-------|");
+    let _ = writeln!(body, "| Files Analyzed | {} |", metrics.total_files);
+    let _ = writeln!(body, "| Modules | {} |", metrics.module_count());
+    let _ = writeln!(body, "| Couplings | {} |", report.total_couplings);
+    for severity in [
+        Severity::Critical,
+        Severity::High,
+        Severity::Medium,
+        Severity::Low,
+    ] {
+        let _ = writeln!(body, "| {} Findings | {} |", severity, count(severity));
+    }
+    doc.sections.push(overview);
+
+    // Findings are numbered in appendix order (severity, then balance)
+    let mut by_crate: BTreeMap<&str, Vec<(usize, &CouplingIssue)>> = BTreeMap::new();
+    for (i, issue) in report.issues.iter().enumerate() {
+        if let Some(krate) = issue_crate(metrics, issue) {
+            by_crate.entry(krate).or_default().push((i + 1, issue));
+        }
+    }
+
+    // One chapter per crate
+    let mut modules_by_crate: BTreeMap<&str, Vec<&ModuleMetrics>> = BTreeMap::new();
+    for module in metrics.modules.values() {
+        modules_by_crate
+            .entry(chapter_name(metrics, module))
+            .or_default()
+            .push(module);
+    }
+    let fan_in = metrics.module_fan_in();
+    let fan_out = metrics.module_fan_out();
+    let mut crates = MarkdownSection::new("Crates");
+    crates.body = format!(
+        "{} crate(s); each chapter lists its modules and the findings in them.\n",
+        modules_by_crate.len()
+    );
+    for (krate, mut modules) in modules_by_crate {
+        modules.sort_by(|a, b| a.path.cmp(&b.path));
+        let mut chapter = MarkdownSection::new(format!("Crate: {}", krate));
+        let lines: usize = modules.iter().map(|m| m.lines_of_code).sum();
+        let findings = by_crate.remove(krate).unwrap_or_default();
+        chapter.body = format!(
+            "{} module(s), {} lines of code, {} finding(s).\n",
+            modules.len(),
+            lines,
+            findings.len()
+        );
+
+        let mut module_table = MarkdownSection::new(format!("{}: Modules", krate));
+        let body = &mut module_table.body;
+        let _ = writeln!(body, "| Module | File | Lines | Fan-in | Fan-out |");
+        let _ = writeln!(body, "|--------|------|------:|-------:|--------:|");
+        for module in &modules {
+            let _ = writeln!(
+                body,
+                "| `{}` | `{}` | {} | {} | {} |",
+                module.name,
+                metrics.relative_path(&module.path),
+                module.lines_of_code,
+                fan_in.get(&module.name).copied().unwrap_or(0),
+                fan_out.get(&module.name).copied().unwrap_or(0)
+            );
+        }
+        chapter.children.push(module_table);
+
+        let mut chapter_findings = MarkdownSection::new(format!("{}: Findings", krate));
+        let body = &mut chapter_findings.body;
+        if findings.is_empty() {
+            let _ = writeln!(body, "No findings.");
+        }
+        for (number, issue) in findings.iter().take(CHAPTER_FINDINGS) {
+            let _ = writeln!(
+                body,
+                "- {} [#{}](#finding-{}) **{}** `{}` → `{}`: {}",
+                severity_icon(issue.severity),
+                number,
+                number,
+                issue.issue_type,
+                issue.source,
+                issue.target,
+                issue.description
+            );
+        }
+        if findings.len() > CHAPTER_FINDINGS {
+            let _ = writeln!(
+                body,
+                "\n{} more in the [appendix](#appendix-all-findings).",
+                findings.len() - CHAPTER_FINDINGS
+            );
+        }
+        chapter.children.push(chapter_findings);
+        crates.children.push(chapter);
+    }
+    doc.sections.push(crates);
+
+    doc.sections.push(cross_crate_section(metrics));
+    doc.sections.push(appendix(metrics, &report.issues));
+    doc
+}
+
+/// Crate stability, references between workspace members and crate cycles
+fn cross_crate_section(metrics: &ProjectMetrics) -> MarkdownSection {
+    let mut section = MarkdownSection::new("Cross-Crate");
+
+    let mut stability = MarkdownSection::new("Crate Stability");
+    let body = &mut stability.body;
+    if metrics.crate_stability.is_empty() {
+        let _ = writeln!(body, "No crate dependencies (single crate analysis).");
+    } else {
+        let _ = writeln!(body, "| Crate | Ca | Ce | Instability |");
+        let _ = writeln!(body, "|-------|---:|---:|------------:|");
+        for krate in &metrics.crate_stability {
+            let _ = writeln!(
+                body,
+                "| `{}` | {} | {} | {:.2} |",
+                krate.name,
+                krate.afferent(),
+                krate.efferent(),
+                krate.instability()
+            );
+        }
+    }
+    section.children.push(stability);
+
+    let members: HashSet<String> = metrics
+        .workspace_members
+        .iter()
+        .map(|m| m.replace('-', "_"))
+        .collect();
+    let mut references: BTreeMap<(&str, &str), usize> = BTreeMap::new();
+    for coupling in &metrics.couplings {
+        if let (Some(from), Some(to)) = (&coupling.source_crate, &coupling.target_crate)
+            && from != to
+            && members.contains(&from.replace('-', "_"))
+            && members.contains(&to.replace('-', "_"))
+        {
+            *references.entry((from, to)).or_default() += 1;
+        }
+    }
+    let mut between = MarkdownSection::new("Couplings Between Members");
+    let body = &mut between.body;
+    if references.is_empty() {
+        let _ = writeln!(body, "No couplings between workspace members.");
+    } else {
+        let _ = writeln!(body, "| From | To | Couplings |");
+        let _ = writeln!(body, "|------|----|----------:|");
+        for ((from, to), count) in &references {
+            let _ = writeln!(body, "| `{}` | `{}` | {} |", from, to, count);
+        }
+    }
+    section.children.push(between);
+
+    let mut cycles = MarkdownSection::new("Crate Cycles");
+    let body = &mut cycles.body;
+    if metrics.crate_cycles.is_empty() {
+        let _ = writeln!(body, "No crate cycles.");
+    }
+    for cycle in &metrics.crate_cycles {
+        let _ = writeln!(
+            body,
+            "- {} (weakest edge: `{}` → `{}`)",
+            cycle.members.join(" ↔ "),
+            cycle.weakest_edge.from,
+            cycle.weakest_edge.to
+        );
+    }
+    section.children.push(cycles);
+    section
+}
+
+/// Every finding, numbered, with an anchor each
+fn appendix(metrics: &ProjectMetrics, issues: &[CouplingIssue]) -> MarkdownSection {
+    let mut section = MarkdownSection::new("Appendix: All Findings");
+    let body = &mut section.body;
+    if issues.is_empty() {
+        let _ = writeln!(body, "No findings.");
+        return section;
+    }
+    let _ = writeln!(
+        body,
+        "| # | Severity | Rule | Source | Target | Location | Description | Action |"
+    );
+    let _ = writeln!(
+        body,
+        "|--:|----------|------|--------|--------|----------|-------------|--------|"
+    );
+    for (i, issue) in issues.iter().enumerate() {
+        let _ = writeln!(
+            body,
+            "| <a id=\"finding-{n}\"></a>{n} | {} {} | `{}` | `{}` | `{}` | {} | {} | {} |",
+            severity_icon(issue.severity),
+            issue.severity,
+            issue.issue_type.rule_id(),
+            cell(&issue.source),
+            cell(&issue.target),
+            cell(&metrics.relative_location(&issue.location)),
+            cell(&issue.description),
+            cell(&issue.refactoring.to_string()),
+            n = i + 1,
+        );
+    }
+    section
+}
+
+/// Generate the structured Markdown report to writer
+pub fn generate_markdown_report<W: Write>(
+    metrics: &ProjectMetrics,
+    thresholds: &IssueThresholds,
+    writer: &mut W,
+) -> io::Result<()> {
+    build_markdown_report(metrics, thresholds).render(writer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{CouplingMetrics, Distance, IntegrationStrength, Volatility};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_document_anchors_and_toc() {
+        let mut doc = MarkdownDocument::new("Report");
+        let mut first = MarkdownSection::new("Crate: core");
+        first.children.push(MarkdownSection::new("Findings"));
+        let mut second = MarkdownSection::new("Crate: app");
+        second.children.push(MarkdownSection::new("Findings"));
+        doc.sections = vec![first, second];
+
+        let mut out = Vec::new();
+        doc.render(&mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("- [Crate: core](#crate-core)\n  - [Findings](#findings)\n"));
+        assert!(text.contains("  - [Findings](#findings-1)\n"));
+        assert!(text.contains("<a id=\"findings-1\"></a>\n\n### Findings"));
+        assert_eq!(slug("Appendix: All Findings"), "appendix-all-findings");
+    }
+
+    #[test]
+    fn test_markdown_report_chapters() {
+        let mut metrics = ProjectMetrics::new();
+        for (krate, name) in [("core", "store"), ("app", "main")] {
+            let mut module = ModuleMetrics::new(
+                PathBuf::from(format!("{}/src/{}.rs", krate, name)),
+                name.into(),
+            );
+            module.crate_name = Some(krate.into());
+            metrics.add_module(module);
+        }
+        metrics.workspace_members = vec!["app".into(), "core".into()];
+        let mut coupling = CouplingMetrics::new(
+            "main".into(),
+            "store::Db".into(),
+            IntegrationStrength::Intrusive,
+            Distance::DifferentCrate,
+            Volatility::Low,
+        );
+        coupling.source_crate = Some("app".into());
+        coupling.target_crate = Some("core".into());
+        metrics.add_coupling(coupling);
+
+        let mut out = Vec::new();
+        generate_markdown_report(&metrics, &IssueThresholds::default(), &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("- [Crate: app](#crate-app)"));
+        assert!(text.contains("- [Crate: core](#crate-core)"));
+        assert!(text.contains("| `store` | `core/src/store.rs` | 0 | 0 | 0 |"));
+        assert!(text.contains("| `app` | `core` | 1 |"));
+        assert!(text.contains("## Appendix: All Findings"));
+    }
+
+    #[test]
+    fn test_chapters_for_members_with_lib_roots() {
+        let dir = crate::test_fixtures::two_lib_workspace();
+        let metrics = crate::analyzer::analyze_workspace(dir.path()).unwrap();
+
+        let mut out = Vec::new();
+        generate_markdown_report(&metrics, &IssueThresholds::default(), &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("2 crate(s); each chapter"));
+        assert!(text.contains("- [Crate: app](#crate-app)"));
+        assert!(text.contains("- [Crate: corelib](#crate-corelib)"));
+        assert!(text.contains("| `app::lib` |"));
+        assert!(text.contains("| `corelib::lib` |"));
+    }
+}
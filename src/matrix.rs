@@ -0,0 +1,277 @@
+//! Crate-level coupling matrix
+//!
+//! An N×N matrix of workspace members where each cell counts the code-level
+//! references (imports, type usage, calls) from the row crate into the
+//! column crate, and sums their integration strength as a weight. Reading a
+//! row shows what a crate reaches into; reading a column shows who reaches
+//! into it. Unexpected non-zero cells are the point of the report.
+//!
+//! The matrix can be written as a Markdown table, CSV or JSON.
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+use crate::metrics::ProjectMetrics;
+
+/// Output format of the coupling matrix
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MatrixFormat {
+    #[default]
+    Markdown,
+    Csv,
+    Json,
+}
+
+impl MatrixFormat {
+    /// Parse a format name (`markdown`/`md`, `csv`, `json`)
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "markdown" | "md" => Some(MatrixFormat::Markdown),
+            "csv" => Some(MatrixFormat::Csv),
+            "json" => Some(MatrixFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+/// References from one crate into another
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct MatrixCell {
+    /// Number of code-level references
+    pub count: usize,
+    /// Sum of the references' integration strength (0.25 - 1.0 each)
+    pub weighted_strength: f64,
+}
+
+/// Coupling between every pair of workspace members
+#[derive(Debug, Clone, Serialize)]
+pub struct CrateMatrix {
+    /// Workspace members, sorted by name (row and column order)
+    pub crates: Vec<String>,
+    /// `cells[row][column]`: references from `crates[row]` into `crates[column]`
+    pub cells: Vec<Vec<MatrixCell>>,
+}
+
+impl CrateMatrix {
+    /// Build the matrix from workspace couplings
+    pub fn build(metrics: &ProjectMetrics) -> Self {
+        let mut crates = metrics.workspace_members.clone();
+        crates.sort();
+        crates.dedup();
+        let index: BTreeMap<&str, usize> = crates
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (c.as_str(), i))
+            .collect();
+
+        let mut cells = vec![vec![MatrixCell::default(); crates.len()]; crates.len()];
+        for coupling in &metrics.couplings {
+            let (Some(source), Some(target)) = (&coupling.source_crate, &coupling.target_crate)
+            else {
+                continue;
+            };
+            if source == target {
+                continue;
+            }
+            if let (Some(&row), Some(&column)) =
+                (index.get(source.as_str()), index.get(target.as_str()))
+            {
+                let cell = &mut cells[row][column];
+                cell.count += 1;
+                cell.weighted_strength += coupling.strength.value();
+            }
+        }
+
+        Self { crates, cells }
+    }
+
+    /// Get the cell for a pair of crates
+    pub fn get(&self, from: &str, to: &str) -> Option<&MatrixCell> {
+        let row = self.crates.iter().position(|c| c == from)?;
+        let column = self.crates.iter().position(|c| c == to)?;
+        Some(&self.cells[row][column])
+    }
+}
+
+/// Format a cell as `count (weight)`, or `-` when empty
+fn format_cell(cell: &MatrixCell) -> String {
+    if cell.count == 0 {
+        "-".to_string()
+    } else {
+        format!("{} ({:.2})", cell.count, cell.weighted_strength)
+    }
+}
+
+/// Quote a CSV field if needed
+pub(crate) fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Write the matrix as a Markdown table
+fn write_markdown<W: Write>(matrix: &CrateMatrix, writer: &mut W) -> io::Result<()> {
+    writeln!(writer, "# Crate Coupling Matrix")?;
+    writeln!(writer)?;
+    writeln!(
+        writer,
+        "Rows depend on columns. Cells show `references (weighted strength)`."
+    )?;
+    writeln!(writer)?;
+
+    write!(writer, "| from \\ to |")?;
+    for krate in &matrix.crates {
+        write!(writer, " {} |", krate)?;
+    }
+    writeln!(writer)?;
+    write!(writer, "|---|")?;
+    for _ in &matrix.crates {
+        write!(writer, "---:|")?;
+    }
+    writeln!(writer)?;
+
+    for (krate, row) in matrix.crates.iter().zip(&matrix.cells) {
+        write!(writer, "| **{}** |", krate)?;
+        for cell in row {
+            write!(writer, " {} |", format_cell(cell))?;
+        }
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+/// Write the matrix as CSV, one row per (from, to) pair with references
+fn write_csv<W: Write>(matrix: &CrateMatrix, writer: &mut W) -> io::Result<()> {
+    writeln!(writer, "from,to,references,weighted_strength")?;
+    for (from, row) in matrix.crates.iter().zip(&matrix.cells) {
+        for (to, cell) in matrix.crates.iter().zip(row) {
+            if cell.count > 0 {
+                writeln!(
+                    writer,
+                    "{},{},{},{:.2}",
+                    csv_field(from),
+                    csv_field(to),
+                    cell.count,
+                    cell.weighted_strength
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Generate the crate coupling matrix to writer
+pub fn generate_matrix_output<W: Write>(
+    metrics: &ProjectMetrics,
+    format: MatrixFormat,
+    writer: &mut W,
+) -> io::Result<()> {
+    let matrix = CrateMatrix::build(metrics);
+    match format {
+        MatrixFormat::Markdown => write_markdown(&matrix, writer),
+        MatrixFormat::Csv => write_csv(&matrix, writer),
+        MatrixFormat::Json => {
+            serde_json::to_writer_pretty(&mut *writer, &matrix)?;
+            writeln!(writer)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{CouplingMetrics, Distance, IntegrationStrength, Volatility};
+
+    fn project() -> ProjectMetrics {
+        let mut metrics = ProjectMetrics::new();
+        metrics.workspace_members = vec!["core".into(), "app".into(), "cli".into()];
+        for (source, target, strength) in [
+            ("app", "core", IntegrationStrength::Model),
+            ("app", "core", IntegrationStrength::Intrusive),
+            ("cli", "app", IntegrationStrength::Contract),
+            ("core", "core", IntegrationStrength::Functional),
+            ("app", "serde", IntegrationStrength::Model),
+        ] {
+            let mut c = CouplingMetrics::new(
+                format!("{}::a", source),
+                format!("{}::b", target),
+                strength,
+                Distance::DifferentCrate,
+                Volatility::Low,
+            );
+            c.source_crate = Some(source.to_string());
+            c.target_crate = Some(target.to_string());
+            metrics.add_coupling(c);
+        }
+        metrics
+    }
+
+    #[test]
+    fn test_build_matrix() {
+        let matrix = CrateMatrix::build(&project());
+
+        assert_eq!(matrix.crates, vec!["app", "cli", "core"]);
+        assert_eq!(
+            matrix.get("app", "core"),
+            Some(&MatrixCell {
+                count: 2,
+                weighted_strength: 1.5
+            })
+        );
+        assert_eq!(matrix.get("cli", "app").unwrap().count, 1);
+        // Intra-crate and external references are not part of the matrix
+        assert_eq!(matrix.get("core", "core").unwrap().count, 0);
+        assert!(matrix.get("app", "serde").is_none());
+    }
+
+    #[test]
+    fn test_matrix_formats() {
+        let metrics = project();
+        let render = |format| {
+            let mut out = Vec::new();
+            generate_matrix_output(&metrics, format, &mut out).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        let markdown = render(MatrixFormat::Markdown);
+        assert!(markdown.contains("| from \\ to | app | cli | core |"));
+        assert!(markdown.contains("| **app** | - | - | 2 (1.50) |"));
+
+        let csv = render(MatrixFormat::Csv);
+        assert_eq!(
+            csv,
+            "from,to,references,weighted_strength\napp,core,2,1.50\ncli,app,1,0.25\n"
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&render(MatrixFormat::Json)).unwrap();
+        assert_eq!(json["crates"][2], "core");
+        assert_eq!(json["cells"][0][2]["count"], 2);
+        assert_eq!(MatrixFormat::parse("MD"), Some(MatrixFormat::Markdown));
+    }
+
+    #[test]
+    fn test_matrix_from_analyzed_workspace() {
+        let dir = crate::test_fixtures::two_lib_workspace();
+        let metrics = crate::analyzer::analyze_workspace(dir.path()).unwrap();
+        let matrix = CrateMatrix::build(&metrics);
+
+        assert_eq!(matrix.crates, vec!["app", "corelib"]);
+        // `use corelib::helper`, `use corelib::model::User` and the `User`
+        // parameter
+        assert_eq!(matrix.get("app", "corelib").unwrap().count, 3);
+        assert_eq!(matrix.get("corelib", "app").unwrap().count, 0);
+
+        let targets: Vec<&str> = metrics
+            .couplings
+            .iter()
+            .filter(|c| c.target_crate.as_deref() == Some("corelib"))
+            .map(|c| c.target.as_str())
+            .collect();
+        assert!(targets.contains(&"corelib::lib"));
+        assert!(targets.contains(&"corelib::model"));
+    }
+}
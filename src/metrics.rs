@@ -3,11 +3,31 @@
 //! This module defines the core data structures for measuring coupling
 //! based on Vlad Khononov's "Balancing Coupling in Software Design".
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
 
 use crate::analyzer::ItemDependency;
+use crate::build_coupling::PhasedDependency;
+use crate::connascence::ConnascenceMetrics;
+use crate::doc_coverage::DocCoverage;
+use crate::edition::Edition;
+use crate::error_surface::ErrorSurface;
+use crate::exhaustive_match::EnumMatch;
+use crate::ffi::FfiUsage;
+use crate::functions::FunctionStats;
+use crate::graph::{DependencyCycle, WeightedGraph, find_cycles};
+use crate::mode_switch::ModeSwitch;
+use crate::platform::PlatformUsage;
+use crate::stability::MemberStability;
+use crate::stamp::ParamUse;
+use crate::struct_literal::StructLiterals;
+use crate::suppress::Suppression;
+use crate::temporal::TemporalMetrics;
+use crate::volatility::CoChangePair;
+use crate::workspace::TargetDependency;
 
 /// Visibility level of a Rust item
 ///
@@ -160,12 +180,46 @@ impl Volatility {
 }
 
 /// Location information for a coupling
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct CouplingLocation {
     /// File path where the coupling originates
     pub file_path: Option<PathBuf>,
-    /// Line number in the source file
+    /// Line number in the source file (1-based, 0 if unknown)
     pub line: usize,
+    /// Column number in the source file (1-based, 0 if unknown)
+    pub column: usize,
+}
+
+impl CouplingLocation {
+    /// Create a location in a file
+    pub fn new(file_path: PathBuf, line: usize, column: usize) -> Self {
+        Self {
+            file_path: Some(file_path),
+            line,
+            column,
+        }
+    }
+
+    /// Create a location that only knows the file
+    pub fn file(file_path: PathBuf) -> Self {
+        Self::new(file_path, 0, 0)
+    }
+}
+
+impl fmt::Display for CouplingLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(path) = &self.file_path else {
+            return write!(f, "<unknown>");
+        };
+        write!(f, "{}", path.display())?;
+        if self.line > 0 {
+            write!(f, ":{}", self.line)?;
+            if self.column > 0 {
+                write!(f, ":{}", self.column)?;
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Metrics for a single coupling relationship
@@ -256,10 +310,7 @@ impl CouplingMetrics {
             source_crate: None,
             target_crate: None,
             target_visibility: visibility,
-            location: CouplingLocation {
-                file_path: Some(file_path),
-                line,
-            },
+            location: CouplingLocation::new(file_path, line, 0),
         }
     }
 
@@ -325,7 +376,8 @@ pub struct TypeDefinition {
     pub is_trait: bool,
     /// Whether this is a newtype pattern (tuple struct with single field)
     pub is_newtype: bool,
-    /// Inner type for newtypes (e.g., "u64" for `struct UserId(u64)`)
+    /// Inner type for newtypes (e.g., "u64" for `struct UserId(u64)`), with
+    /// imported names resolved to their full path
     pub inner_type: Option<String>,
     /// Whether this type has #[derive(Serialize)] or #[derive(Deserialize)]
     pub has_serde_derive: bool,
@@ -333,6 +385,20 @@ pub struct TypeDefinition {
     pub public_field_count: usize,
     /// Total number of fields
     pub total_field_count: usize,
+    /// Trait methods implementors must provide (traits only)
+    pub required_methods: usize,
+    /// Trait methods with a default body (traits only)
+    pub default_methods: usize,
+    /// Names of the methods with a default body (traits only)
+    pub default_method_names: Vec<String>,
+    /// Number of variants (enums only)
+    pub variants: usize,
+    /// Marked `#[non_exhaustive]`
+    pub non_exhaustive: bool,
+    /// Line of the definition (1-based, 0 if unknown)
+    pub line: usize,
+    /// Column of the definition (1-based, 0 if unknown)
+    pub column: usize,
 }
 
 /// Information about a function definition in a module
@@ -348,6 +414,45 @@ pub struct FunctionDefinition {
     pub primitive_param_count: usize,
     /// Parameter types (for primitive obsession detection)
     pub param_types: Vec<String>,
+    /// Line of the definition (1-based, 0 if unknown)
+    pub line: usize,
+    /// Column of the definition (1-based, 0 if unknown)
+    pub column: usize,
+}
+
+/// A trait a module implements or requires through a bound
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraitUse {
+    /// Trait path, resolved through the module's imports (`my_core::Repository`)
+    pub trait_path: String,
+    /// Implementing type for impls; enclosing function (or module) for bounds
+    pub item: String,
+    /// Line of the trait name (1-based)
+    pub line: usize,
+    /// Methods the impl block defines (impls only)
+    pub methods: Vec<String>,
+}
+
+/// A type named in a public signature or public field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExposedType {
+    /// Public item naming the type (`parse`, `Config.value`, `Store::get`)
+    pub item: String,
+    /// Type path, resolved through the module's imports (`serde_json::Value`)
+    pub type_path: String,
+    /// Line of the type (1-based)
+    pub line: usize,
+}
+
+/// A `pub use` re-export
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReExport {
+    /// Path as written (`crate::model::User`, `self::web::*`)
+    pub source: String,
+    /// Name the item is exported under (`*` for glob re-exports)
+    pub name: String,
+    /// Line of the `pub use` (1-based)
+    pub line: usize,
 }
 
 /// Khononov's balance classification for couplings
@@ -545,6 +650,42 @@ pub struct BalanceCounts {
     pub local_complexity: usize,
 }
 
+/// Test code kept out of the coupling analysis
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestCodeMetrics {
+    /// Files under a `tests/` directory
+    pub files: usize,
+    /// `#[cfg(test)]` modules
+    pub modules: usize,
+    /// `#[test]` functions
+    pub functions: usize,
+    /// Non-blank, non-comment lines of test code
+    pub lines_of_code: usize,
+}
+
+impl TestCodeMetrics {
+    /// Whether no test code was found
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Add another module's test code to this one
+    pub fn add(&mut self, other: &TestCodeMetrics) {
+        self.files += other.files;
+        self.modules += other.modules;
+        self.functions += other.functions;
+        self.lines_of_code += other.lines_of_code;
+    }
+}
+
+/// A source file left out of the analysis because it could not be read or parsed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkippedFile {
+    pub path: PathBuf,
+    /// Read or parse error, with its location for parse errors
+    pub error: String,
+}
+
 /// Aggregated metrics for a module
 #[derive(Debug, Clone, Default)]
 pub struct ModuleMetrics {
@@ -552,6 +693,8 @@ pub struct ModuleMetrics {
     pub path: PathBuf,
     /// Module name
     pub name: String,
+    /// Workspace crate containing the module (workspace analysis only)
+    pub crate_name: Option<String>,
     /// Number of trait implementations (contract coupling)
     pub trait_impl_count: usize,
     /// Number of inherent implementations (intrusive coupling)
@@ -564,12 +707,71 @@ pub struct ModuleMetrics {
     pub external_deps: Vec<String>,
     /// Internal module dependencies
     pub internal_deps: Vec<String>,
+    /// Every path imported with `use` (e.g. `crate::metrics::Distance`)
+    pub use_paths: Vec<String>,
+    /// Child modules declared with `mod name;`
+    pub declared_modules: Vec<String>,
+    /// Child modules declared `pub` (`pub mod name;` or inline)
+    pub public_modules: Vec<String>,
+    /// `pub use` re-exports
+    pub reexports: Vec<ReExport>,
+    /// Types named in public function signatures, public fields, public
+    /// enum variants and public type aliases
+    pub exposed_types: Vec<ExposedType>,
+    /// Qualified paths of macro invocations, attributes and derives
+    /// (`tracing::debug`, `tokio::main`, `serde::Serialize`)
+    pub macro_paths: Vec<String>,
+    /// `impl Trait for Type` blocks
+    pub trait_impls: Vec<TraitUse>,
+    /// Trait bounds in generics, `impl Trait` and `dyn Trait`
+    pub trait_bounds: Vec<TraitUse>,
     /// Type definitions in this module with visibility info
-    pub type_definitions: HashMap<String, TypeDefinition>,
+    pub type_definitions: BTreeMap<String, TypeDefinition>,
     /// Function definitions in this module with visibility info
-    pub function_definitions: HashMap<String, FunctionDefinition>,
+    pub function_definitions: BTreeMap<String, FunctionDefinition>,
     /// Item-level dependencies (function → function, function → type, etc.)
     pub item_dependencies: Vec<ItemDependency>,
+    /// Temporal coupling patterns (paired operations, guards, Drop impls)
+    pub temporal: TemporalMetrics,
+    /// Type aliases to trait objects (e.g. `type Handler = Box<dyn Fn()>`)
+    pub trait_object_aliases: usize,
+    /// Generic complexity of the public signatures: generic parameters,
+    /// bounds, where-clauses, higher-ranked bounds and nested generic types
+    pub signature_complexity: usize,
+    /// Public items leaking lifetimes: signatures or types with several
+    /// named lifetimes, and methods returning data borrowed from `&self`
+    pub lifetime_leaks: Vec<String>,
+    /// Platforms named by `cfg` attributes and `cfg!`
+    pub platforms: PlatformUsage,
+    /// Foreign blocks, `#[no_mangle]`, `extern fn` and `#[repr(C)]` items
+    pub ffi: FfiUsage,
+    /// Non-blank, non-comment lines of code (without test code)
+    pub lines_of_code: usize,
+    /// Test code removed before analysis
+    pub tests: TestCodeMetrics,
+    /// Edition the module was parsed with
+    pub edition: Edition,
+    /// Analyzed from macro-expanded source (`--expand`); line numbers refer
+    /// to the `cargo expand` output
+    pub expanded: bool,
+    /// Signatures and call sites for connascence analysis
+    pub connascence: ConnascenceMetrics,
+    /// Field accesses on struct-typed parameters (stamp coupling)
+    pub param_uses: Vec<ParamUse>,
+    /// `match` expressions over enum variants without a wildcard arm
+    pub enum_matches: Vec<EnumMatch>,
+    /// Struct literals and public constructors
+    pub struct_literals: StructLiterals,
+    /// Length, nesting and branching of every function body
+    pub function_stats: Vec<FunctionStats>,
+    /// Doc comments on public items
+    pub doc_coverage: DocCoverage,
+    /// Public functions returning `Result`, with their error types
+    pub error_surface: ErrorSurface,
+    /// Functions splitting into separate paths on a mode parameter
+    pub mode_switches: Vec<ModeSwitch>,
+    /// `coupling:ignore` directives in the source
+    pub suppressions: Vec<Suppression>,
 }
 
 impl ModuleMetrics {
@@ -581,6 +783,57 @@ impl ModuleMetrics {
         }
     }
 
+    /// Rust module path of the file (`my_crate::net::client`), derived from
+    /// its location under `src/`
+    ///
+    /// `lib.rs`, `main.rs` and `mod.rs` name their directory's module. The
+    /// path starts with the crate name when known and `crate` otherwise.
+    pub fn rust_path(&self) -> String {
+        let krate = self
+            .crate_name
+            .as_deref()
+            .map_or("crate".to_string(), |name| name.replace('-', "_"));
+        let components: Vec<_> = self
+            .path
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(part) => Some(part.to_string_lossy()),
+                _ => None,
+            })
+            .collect();
+        let under_src = components
+            .iter()
+            .rposition(|c| c == "src")
+            .map_or(&components[components.len().saturating_sub(1)..], |i| {
+                &components[i + 1..]
+            });
+
+        let mut segments = vec![krate];
+        for (i, part) in under_src.iter().enumerate() {
+            let last = i + 1 == under_src.len();
+            let part = if last {
+                part.strip_suffix(".rs").unwrap_or(part)
+            } else {
+                part
+            };
+            if !(last && matches!(part, "lib" | "main" | "mod")) {
+                segments.push(part.to_string());
+            }
+        }
+        segments.join("::")
+    }
+
+    /// Every qualified path the module names: imports, item-level
+    /// dependencies (calls, type uses), exposed types and macro paths
+    pub fn referenced_paths(&self) -> impl Iterator<Item = &str> {
+        self.use_paths
+            .iter()
+            .map(String::as_str)
+            .chain(self.item_dependencies.iter().map(|d| d.target.as_str()))
+            .chain(self.exposed_types.iter().map(|e| e.type_path.as_str()))
+            .chain(self.macro_paths.iter().map(String::as_str))
+    }
+
     /// Add a type definition to this module (simple version for backward compatibility)
     pub fn add_type_definition(&mut self, name: String, visibility: Visibility, is_trait: bool) {
         self.type_definitions.insert(
@@ -594,6 +847,13 @@ impl ModuleMetrics {
                 has_serde_derive: false,
                 public_field_count: 0,
                 total_field_count: 0,
+                required_methods: 0,
+                default_methods: 0,
+                default_method_names: Vec::new(),
+                variants: 0,
+                non_exhaustive: false,
+                line: 0,
+                column: 0,
             },
         );
     }
@@ -622,6 +882,13 @@ impl ModuleMetrics {
                 has_serde_derive,
                 public_field_count,
                 total_field_count,
+                required_methods: 0,
+                default_methods: 0,
+                default_method_names: Vec::new(),
+                variants: 0,
+                non_exhaustive: false,
+                line: 0,
+                column: 0,
             },
         );
     }
@@ -636,6 +903,8 @@ impl ModuleMetrics {
                 param_count: 0,
                 primitive_param_count: 0,
                 param_types: Vec::new(),
+                line: 0,
+                column: 0,
             },
         );
     }
@@ -657,6 +926,8 @@ impl ModuleMetrics {
                 param_count,
                 primitive_param_count,
                 param_types,
+                line: 0,
+                column: 0,
             },
         );
     }
@@ -674,6 +945,23 @@ impl ModuleMetrics {
             .count()
     }
 
+    /// Count abstract types (traits and trait object aliases)
+    pub fn abstract_type_count(&self) -> usize {
+        self.type_definitions
+            .values()
+            .filter(|t| t.is_trait)
+            .count()
+            + self.trait_object_aliases
+    }
+
+    /// Count concrete types (structs and enums)
+    pub fn concrete_type_count(&self) -> usize {
+        self.type_definitions
+            .values()
+            .filter(|t| !t.is_trait)
+            .count()
+    }
+
     /// Count non-public types
     pub fn private_type_count(&self) -> usize {
         self.type_definitions
@@ -762,21 +1050,45 @@ impl ModuleMetrics {
 #[derive(Debug, Default)]
 pub struct ProjectMetrics {
     /// All module metrics
-    pub modules: HashMap<String, ModuleMetrics>,
+    pub modules: BTreeMap<String, ModuleMetrics>,
     /// All detected couplings
     pub couplings: Vec<CouplingMetrics>,
     /// File change counts (for volatility)
-    pub file_changes: HashMap<String, usize>,
+    pub file_changes: BTreeMap<String, usize>,
+    /// Commits per author for each file (from git history)
+    pub file_authors: BTreeMap<String, BTreeMap<String, usize>>,
     /// Total files analyzed
     pub total_files: usize,
+    /// Files under `tests/` directories that were skipped
+    pub test_files: TestCodeMetrics,
+    /// Files that could not be read or parsed, sorted by path
+    pub skipped_files: Vec<SkippedFile>,
     /// Workspace name (if available from cargo metadata)
     pub workspace_name: Option<String>,
     /// Workspace member crate names
     pub workspace_members: Vec<String>,
+    /// Members whose files were analyzed: the default members unless
+    /// packages or `--workspace` were selected
+    pub analyzed_members: Vec<String>,
+    /// Directory that file paths in structured output are relative to: the
+    /// workspace root, or the analyzed directory without cargo metadata
+    pub workspace_root: Option<PathBuf>,
     /// Crate-level dependencies (crate name -> list of dependencies)
-    pub crate_dependencies: HashMap<String, Vec<String>>,
+    pub crate_dependencies: BTreeMap<String, Vec<String>>,
+    /// Dependencies only built on some platforms, per crate (a subset of
+    /// `crate_dependencies`)
+    pub target_dependencies: BTreeMap<String, Vec<TargetDependency>>,
+    /// Manifest dependencies of the analyzed crates by when they are used
+    /// (requires cargo metadata)
+    pub dependency_phases: Vec<PhasedDependency>,
     /// Global type registry: type name -> (module name, visibility)
     pub type_registry: HashMap<String, (String, Visibility)>,
+    /// File pairs that frequently change together (from git history)
+    pub co_changes: Vec<CoChangePair>,
+    /// Ca/Ce/Instability per workspace member (requires cargo metadata)
+    pub crate_stability: Vec<MemberStability>,
+    /// Dependency cycles between workspace members (requires cargo metadata)
+    pub crate_cycles: Vec<DependencyCycle>,
 }
 
 impl ProjectMetrics {
@@ -789,6 +1101,47 @@ impl ProjectMetrics {
         self.modules.insert(metrics.name.clone(), metrics);
     }
 
+    /// Find a module by name, also accepting `crate::module` or
+    /// `module::Item` style names
+    ///
+    /// Longer sub-paths win, so `crate::net::client::Conn` finds
+    /// `net::client` before `net`.
+    pub fn find_module(&self, name: &str) -> Option<&ModuleMetrics> {
+        if let Some(module) = self.modules.get(name) {
+            return Some(module);
+        }
+        let segments: Vec<&str> = name.split("::").collect();
+        (1..segments.len()).rev().find_map(|len| {
+            (0..=segments.len() - len)
+                .rev()
+                .find_map(|start| self.modules.get(&segments[start..start + len].join("::")))
+        })
+    }
+
+    /// `path` relative to the workspace root, with `/` separators
+    ///
+    /// Paths outside the root (or any path when the root is unknown) are
+    /// only stripped of a leading `./`.
+    pub fn relative_path(&self, path: &Path) -> String {
+        let relative = self
+            .workspace_root
+            .as_deref()
+            .and_then(|root| path.strip_prefix(root).ok())
+            .unwrap_or(path);
+        let relative = relative.strip_prefix(".").unwrap_or(relative);
+        relative.to_string_lossy().replace('\\', "/")
+    }
+
+    /// `location` as `file:line:column`, with the file relative to the
+    /// workspace root
+    pub fn relative_location(&self, location: &CouplingLocation) -> String {
+        let mut location = location.clone();
+        location.file_path = location
+            .file_path
+            .map(|path| PathBuf::from(self.relative_path(&path)));
+        location.to_string()
+    }
+
     /// Add coupling
     pub fn add_coupling(&mut self, coupling: CouplingMetrics) {
         self.couplings.push(coupling);
@@ -850,6 +1203,20 @@ impl ProjectMetrics {
         self.modules.len()
     }
 
+    /// Test code skipped across the project (`tests/` files and inline tests)
+    pub fn test_code(&self) -> TestCodeMetrics {
+        let mut total = self.test_files;
+        for module in self.modules.values() {
+            total.add(&module.tests);
+        }
+        total
+    }
+
+    /// Number of modules analyzed from macro-expanded source
+    pub fn expanded_module_count(&self) -> usize {
+        self.modules.values().filter(|m| m.expanded).count()
+    }
+
     /// Get total coupling count
     pub fn coupling_count(&self) -> usize {
         self.couplings.len()
@@ -965,9 +1332,43 @@ impl ProjectMetrics {
         }
     }
 
+    /// Check whether two files are connected by a static dependency
+    ///
+    /// The files (paths relative to the repository, as git reports them) are
+    /// matched to analyzed modules by path, and coupling sources and targets
+    /// to modules like [`Self::find_module`]. A dependency in either
+    /// direction counts.
+    pub fn has_static_dependency(&self, file_a: &str, file_b: &str) -> bool {
+        let module_of = |file: &str| self.modules.values().find(|m| m.path.ends_with(file));
+        let (Some(a), Some(b)) = (module_of(file_a), module_of(file_b)) else {
+            return false;
+        };
+        let last = |name: &str| name.rsplit("::").next().unwrap_or(name).to_string();
+        let (last_a, last_b) = (last(&a.name), last(&b.name));
+
+        let is = |path: &str, module: &ModuleMetrics, last: &str| {
+            path.split("::").any(|part| part == last)
+                && self
+                    .find_module(path)
+                    .is_some_and(|m| m.path == module.path)
+        };
+        self.couplings.iter().any(|c| {
+            (is(&c.source, a, &last_a) && is(&c.target, b, &last_b))
+                || (is(&c.source, b, &last_b) && is(&c.target, a, &last_a))
+        })
+    }
+
+    /// Get co-changing file pairs without a static dependency (hidden coupling)
+    pub fn hidden_couplings(&self) -> Vec<&CoChangePair> {
+        self.co_changes
+            .iter()
+            .filter(|pair| !self.has_static_dependency(&pair.file_a, &pair.file_b))
+            .collect()
+    }
+
     /// Build a dependency graph from couplings
-    fn build_dependency_graph(&self) -> HashMap<String, HashSet<String>> {
-        let mut graph: HashMap<String, HashSet<String>> = HashMap::new();
+    fn build_dependency_graph(&self) -> BTreeMap<String, BTreeSet<String>> {
+        let mut graph: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
 
         for coupling in &self.couplings {
             // Only consider internal couplings (not external crates)
@@ -985,6 +1386,81 @@ impl ProjectMetrics {
         graph
     }
 
+    /// Module graph weighted by the number of couplings between modules
+    ///
+    /// Coupling sources and targets (`crate::module`, `module::Item`) are
+    /// resolved to analyzed modules; anything else is left out.
+    fn weighted_module_graph(&self) -> WeightedGraph {
+        let mut graph = WeightedGraph::new();
+        for coupling in &self.couplings {
+            if coupling.distance == Distance::DifferentCrate {
+                continue;
+            }
+            let (Some(source), Some(target)) = (
+                self.find_module(&coupling.source),
+                self.find_module(&coupling.target),
+            ) else {
+                continue;
+            };
+            if source.name == target.name {
+                continue;
+            }
+            *graph
+                .entry(source.name.clone())
+                .or_default()
+                .entry(target.name.clone())
+                .or_default() += 1;
+        }
+        graph
+    }
+
+    /// Number of other modules that depend on each module (fan-in), keyed
+    /// by module name; modules nothing depends on are left out
+    pub fn module_fan_in(&self) -> HashMap<String, usize> {
+        let mut fan_in: HashMap<String, usize> = HashMap::new();
+        for targets in self.weighted_module_graph().values() {
+            for target in targets.keys() {
+                *fan_in.entry(target.clone()).or_default() += 1;
+            }
+        }
+        fan_in
+    }
+
+    /// Number of other modules each module depends on (fan-out), keyed by
+    /// module name; modules without dependencies are left out
+    pub fn module_fan_out(&self) -> HashMap<String, usize> {
+        self.weighted_module_graph()
+            .into_iter()
+            .map(|(source, targets)| (source, targets.len()))
+            .collect()
+    }
+
+    /// Commits per author for a module's file (see [`Self::module_changes`])
+    pub fn module_authors(&self, module: &ModuleMetrics) -> Option<&BTreeMap<String, usize>> {
+        self.file_authors
+            .iter()
+            .filter(|(file, _)| module.path.ends_with(file.as_str()))
+            .max_by_key(|(file, _)| file.len())
+            .map(|(_, authors)| authors)
+    }
+
+    /// Commits that changed a module's file, matching git paths (relative
+    /// to the repository root) against the end of the module path
+    pub fn module_changes(&self, module: &ModuleMetrics) -> usize {
+        self.file_changes
+            .iter()
+            .filter(|(file, _)| module.path.ends_with(file.as_str()))
+            .map(|(_, &count)| count)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Groups of modules that depend on each other (strongly connected
+    /// components), each with the weakest edge to break
+    pub fn module_cycles(&self) -> Vec<DependencyCycle> {
+        find_cycles(&self.weighted_module_graph())
+    }
+
     /// Detect circular dependencies in the project
     ///
     /// Returns a list of cycles, where each cycle is a list of module names
@@ -1028,7 +1504,7 @@ impl ProjectMetrics {
     fn dfs_find_cycles(
         &self,
         node: &str,
-        graph: &HashMap<String, HashSet<String>>,
+        graph: &BTreeMap<String, BTreeSet<String>>,
         visited: &mut HashSet<String>,
         rec_stack: &mut HashSet<String>,
         path: &mut Vec<String>,
@@ -1330,6 +1806,63 @@ mod tests {
         assert!(cycles.is_empty());
     }
 
+    #[test]
+    fn test_module_cycles_with_weakest_edge() {
+        let mut project = ProjectMetrics::new();
+        for name in ["api", "db"] {
+            project.add_module(ModuleMetrics::new(
+                PathBuf::from(format!("src/{}.rs", name)),
+                name.to_string(),
+            ));
+        }
+
+        // api uses db twice, db refers back to one api type
+        for (source, target) in [
+            ("app::api", "db::query"),
+            ("app::api", "db::Pool"),
+            ("app::db", "api::Request"),
+        ] {
+            project.add_coupling(CouplingMetrics::new(
+                source.to_string(),
+                target.to_string(),
+                IntegrationStrength::Model,
+                Distance::DifferentModule,
+                Volatility::Low,
+            ));
+        }
+
+        let cycles = project.module_cycles();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].members, vec!["api", "db"]);
+        assert_eq!(
+            (
+                cycles[0].weakest_edge.from.as_str(),
+                cycles[0].weakest_edge.to.as_str()
+            ),
+            ("db", "api")
+        );
+    }
+
+    #[test]
+    fn test_module_cycle_through_mod_rs_modules() {
+        let dir = crate::test_fixtures::mod_rs_crate();
+        let project = crate::analyzer::analyze_project_filtered(
+            dir.path(),
+            &crate::analyzer::AnalysisScope::default(),
+        )
+        .unwrap();
+
+        let cycles = project.module_cycles();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].members, vec!["net", "net::client", "store"]);
+
+        // Workspace couplings name the crate (`netstore::net::client`)
+        let project = crate::analyzer::analyze_workspace(dir.path()).unwrap();
+        let cycles = project.module_cycles();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].members, vec!["net", "net::client", "store"]);
+    }
+
     #[test]
     fn test_external_crates_excluded_from_cycles() {
         let mut project = ProjectMetrics::new();
@@ -1672,4 +2205,126 @@ mod tests {
             "cli_output should be Medium (3 changes)"
         );
     }
+
+    #[test]
+    fn test_hidden_couplings() {
+        let mut project = ProjectMetrics::new();
+        for name in ["lexer", "parser", "config", "report"] {
+            project.add_module(ModuleMetrics::new(
+                format!("src/{}.rs", name).into(),
+                name.to_string(),
+            ));
+        }
+        project.add_coupling(CouplingMetrics::new(
+            "app::parser".to_string(),
+            "app::lexer::Token".to_string(),
+            IntegrationStrength::Model,
+            Distance::DifferentModule,
+            Volatility::Low,
+        ));
+        let pair = |a: &str, b: &str| CoChangePair {
+            file_a: a.to_string(),
+            file_b: b.to_string(),
+            co_changes: 5,
+            ratio: 0.8,
+            examples: Vec::new(),
+        };
+        project.co_changes = vec![
+            pair("src/lexer.rs", "src/parser.rs"),
+            pair("src/config.rs", "src/report.rs"),
+        ];
+
+        assert!(project.has_static_dependency("src/lexer.rs", "src/parser.rs"));
+        let hidden = project.hidden_couplings();
+        assert_eq!(hidden.len(), 1);
+        assert_eq!(hidden[0].file_a, "src/config.rs");
+    }
+
+    #[test]
+    fn test_static_dependency_between_mod_rs_files() {
+        let dir = crate::test_fixtures::mod_rs_crate();
+        let project = crate::analyzer::analyze_workspace(dir.path()).unwrap();
+
+        // Both files have the stem `mod`
+        assert!(project.has_static_dependency("src/net/mod.rs", "src/store/mod.rs"));
+        assert!(project.has_static_dependency("src/store/mod.rs", "src/net/client.rs"));
+        assert!(!project.has_static_dependency("src/net/mod.rs", "src/util.rs"));
+        assert!(!project.has_static_dependency("src/util.rs", "src/store/mod.rs"));
+    }
+
+    #[test]
+    fn test_reports_do_not_contain_the_absolute_root() {
+        use crate::balance::IssueThresholds;
+
+        let thresholds = IssueThresholds::default();
+        for dir in [
+            crate::test_fixtures::two_lib_workspace(),
+            crate::test_fixtures::mod_rs_crate(),
+        ] {
+            let project = crate::analyzer::analyze_workspace(dir.path()).unwrap();
+            let root = dir.path().to_string_lossy().to_string();
+
+            let mut reports = Vec::new();
+            let mut out = Vec::new();
+            crate::html::generate_html_output(&project, &thresholds, &mut out).unwrap();
+            reports.push(out);
+            let mut out = Vec::new();
+            let surface = crate::api::ApiSurface::build(&project);
+            crate::api::generate_api_report(&surface, &mut out).unwrap();
+            reports.push(out);
+            let mut out = Vec::new();
+            crate::markdown::generate_markdown_report(&project, &thresholds, &mut out).unwrap();
+            reports.push(out);
+            for item in ["corelib::model::User", "netstore::net::Config"] {
+                if let Ok(radius) = crate::blast_radius::BlastRadius::analyze(&project, item) {
+                    let mut out = Vec::new();
+                    crate::blast_radius::generate_blast_radius_report(&radius, &mut out).unwrap();
+                    reports.push(out);
+                }
+            }
+            for item in ["User", "Config"] {
+                let mut out = Vec::new();
+                crate::cli_output::generate_trace_output(&project, item, &mut out).unwrap();
+                reports.push(out);
+            }
+
+            assert_eq!(reports.len(), 6);
+            for report in reports {
+                let text = String::from_utf8(report).unwrap();
+                assert!(!text.contains(&root), "{}", text);
+            }
+        }
+    }
+
+    #[test]
+    fn test_relative_paths_and_rust_paths() {
+        let project = ProjectMetrics {
+            workspace_root: Some(PathBuf::from("/ci/work")),
+            ..ProjectMetrics::default()
+        };
+        assert_eq!(
+            project.relative_path(Path::new("/ci/work/crates/net/src/client.rs")),
+            "crates/net/src/client.rs"
+        );
+        assert_eq!(
+            project.relative_path(Path::new("./src/lib.rs")),
+            "src/lib.rs"
+        );
+        assert_eq!(
+            project.relative_path(Path::new("/elsewhere/a.rs")),
+            "/elsewhere/a.rs"
+        );
+
+        let module = |path: &str, krate: Option<&str>| ModuleMetrics {
+            crate_name: krate.map(String::from),
+            ..ModuleMetrics::new(PathBuf::from(path), String::new())
+        };
+        assert_eq!(
+            module("/ci/work/crates/net/src/http/client.rs", Some("my-net")).rust_path(),
+            "my_net::http::client"
+        );
+        assert_eq!(module("./src/http/mod.rs", None).rust_path(), "crate::http");
+        assert_eq!(module("src/main.rs", None).rust_path(), "crate");
+        assert_eq!(module("calc.rs", None).rust_path(), "crate::calc");
+    }
 }
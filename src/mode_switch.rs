@@ -0,0 +1,432 @@
+//! Functions switching on a caller-supplied mode
+//!
+//! A general-purpose function that takes a flag or mode parameter and
+//! branches on it into mostly separate code is really several special-case
+//! functions sharing a signature ("A Philosophy of Software Design",
+//! general-purpose vs special-purpose code). Every caller passes the mode,
+//! every mode's reader wades through the others, and adding a mode changes
+//! the shared function.
+//!
+//! A parameter is a mode when it is a `bool`, its type name ends in
+//! `Mode`, `Kind`, `Strategy`, `Style` or `Flavor`, or its name is (or
+//! ends in) `mode`, `kind` or `flag`. Each outermost `if`/`else if` chain
+//! or `match` whose condition names the parameter is a branch; its arms are
+//! compared by the functions and methods they call. A function is reported
+//! when the branches cover at least half of its lines, at least two arms
+//! span [`MIN_ARM_LINES`] lines, and at most a third of the called names
+//! appear in more than one arm of the same branch. Trait impls are skipped:
+//! their signature belongs to the trait.
+
+use std::collections::BTreeSet;
+
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use syn::spanned::Spanned;
+use syn::visit::Visit;
+use syn::{
+    Block, Expr, ExprCall, ExprIf, ExprMatch, ExprMethodCall, ExprPath, FnArg, ImplItem, Item,
+    ItemFn, ItemImpl, Pat, Signature, Type, Visibility,
+};
+
+use crate::analyzer::span_position;
+
+/// Lines from which a branch arm counts as its own code path
+pub const MIN_ARM_LINES: usize = 3;
+
+/// Type name suffixes of mode parameters
+const MODE_TYPE_SUFFIXES: &[&str] = &["Mode", "Kind", "Strategy", "Style", "Flavor"];
+/// Name suffixes of mode parameters
+const MODE_NAME_SUFFIXES: &[&str] = &["mode", "kind", "flag"];
+/// Longest arm label kept in reports
+const MAX_LABEL_LEN: usize = 40;
+
+/// One arm of a branch on a mode parameter
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BranchArm {
+    /// Pattern of a `match` arm, condition of an `if`, or `else`
+    pub label: String,
+    pub lines: usize,
+}
+
+/// An `if` chain or `match` on a mode parameter
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModeBranch {
+    /// `if` or `match`
+    pub keyword: String,
+    pub line: usize,
+    pub arms: Vec<BranchArm>,
+    /// Distinct functions and methods called in the arms
+    pub calls: usize,
+    /// Of those, the ones called in more than one arm
+    pub shared_calls: usize,
+}
+
+/// A function splitting into mostly separate paths on a mode parameter
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModeSwitch {
+    /// Function name (`Type::method` for methods)
+    pub function: String,
+    pub parameter: String,
+    /// Type of the parameter as written
+    pub parameter_type: String,
+    /// Whether the function is visible outside its module
+    pub is_public: bool,
+    pub line: usize,
+    pub column: usize,
+    /// Lines from the signature to the closing brace
+    pub lines: usize,
+    pub branches: Vec<ModeBranch>,
+}
+
+impl ModeSwitch {
+    /// Lines of the arms of all branches
+    pub fn switched_lines(&self) -> usize {
+        self.branches
+            .iter()
+            .flat_map(|b| &b.arms)
+            .map(|arm| arm.lines)
+            .sum()
+    }
+
+    /// Arms spanning at least [`MIN_ARM_LINES`] lines
+    pub fn paths(&self) -> usize {
+        self.branches
+            .iter()
+            .flat_map(|b| &b.arms)
+            .filter(|arm| arm.lines >= MIN_ARM_LINES)
+            .count()
+    }
+}
+
+/// Collects [`ModeSwitch`]es per file
+#[derive(Debug, Default)]
+pub struct ModeAnalyzer {
+    switches: Vec<ModeSwitch>,
+    /// Self type of the enclosing inherent impl block
+    impl_owner: Option<String>,
+}
+
+impl ModeAnalyzer {
+    /// Analyze a parsed file
+    pub fn analyze(file: &syn::File) -> Vec<ModeSwitch> {
+        let mut analyzer = Self::default();
+        analyzer.visit_file(file);
+        analyzer.switches
+    }
+
+    fn function(&mut self, vis: &Visibility, sig: &Signature, block: &Block) {
+        let name = match &self.impl_owner {
+            Some(owner) => format!("{}::{}", owner, sig.ident),
+            None => sig.ident.to_string(),
+        };
+        let (line, column) = span_position(sig.ident.span());
+        let lines = block.brace_token.span.close().end().line + 1 - sig.fn_token.span.start().line;
+        for input in &sig.inputs {
+            let FnArg::Typed(arg) = input else {
+                continue;
+            };
+            let Pat::Ident(ident) = &*arg.pat else {
+                continue;
+            };
+            let parameter = ident.ident.to_string();
+            if !is_mode(&parameter, &arg.ty) {
+                continue;
+            }
+            let mut finder = BranchFinder {
+                parameter: &parameter,
+                branches: Vec::new(),
+            };
+            finder.visit_block(block);
+            let branches = finder.branches;
+            let switch = ModeSwitch {
+                function: name.clone(),
+                parameter_type: snippet(&arg.ty),
+                parameter,
+                is_public: !matches!(vis, Visibility::Inherited),
+                line,
+                column,
+                lines,
+                branches,
+            };
+            if is_split_candidate(&switch) {
+                self.switches.push(switch);
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for ModeAnalyzer {
+    fn visit_item_fn(&mut self, node: &'ast ItemFn) {
+        let previous = self.impl_owner.take();
+        self.function(&node.vis, &node.sig, &node.block);
+        syn::visit::visit_item_fn(self, node);
+        self.impl_owner = previous;
+    }
+
+    fn visit_item_impl(&mut self, node: &'ast ItemImpl) {
+        if node.trait_.is_some() {
+            return;
+        }
+        let previous = self.impl_owner.take();
+        if let Type::Path(type_path) = &*node.self_ty {
+            self.impl_owner = type_path.path.segments.last().map(|s| s.ident.to_string());
+        }
+        for item in &node.items {
+            if let ImplItem::Fn(method) = item {
+                self.function(&method.vis, &method.sig, &method.block);
+                let owner = self.impl_owner.take();
+                self.visit_block(&method.block);
+                self.impl_owner = owner;
+            }
+        }
+        self.impl_owner = previous;
+    }
+}
+
+/// Whether a parameter selects a mode (see module docs)
+fn is_mode(name: &str, ty: &Type) -> bool {
+    let ty = match ty {
+        Type::Reference(reference) => &*reference.elem,
+        ty => ty,
+    };
+    let type_name = match ty {
+        Type::Path(path) => path.path.segments.last().map(|s| s.ident.to_string()),
+        _ => None,
+    };
+    type_name.as_deref() == Some("bool")
+        || type_name.is_some_and(|t| MODE_TYPE_SUFFIXES.iter().any(|s| t.ends_with(s)))
+        || MODE_NAME_SUFFIXES.iter().any(|s| name.ends_with(s))
+}
+
+/// Whether the branches split the function into mostly separate paths
+fn is_split_candidate(switch: &ModeSwitch) -> bool {
+    let calls: usize = switch.branches.iter().map(|b| b.calls).sum();
+    let shared: usize = switch.branches.iter().map(|b| b.shared_calls).sum();
+    switch.paths() >= 2
+        && switch.switched_lines() * 2 >= switch.lines
+        && calls > 0
+        && shared * 3 <= calls
+}
+
+/// Finds the outermost branches on one parameter
+struct BranchFinder<'a> {
+    parameter: &'a str,
+    branches: Vec<ModeBranch>,
+}
+
+impl BranchFinder<'_> {
+    fn branch(&mut self, keyword: &str, line: usize, arms: Vec<(String, usize, BTreeSet<String>)>) {
+        let mut calls = BTreeSet::new();
+        let mut shared = BTreeSet::new();
+        for (_, _, arm_calls) in &arms {
+            for call in arm_calls {
+                if !calls.insert(call) {
+                    shared.insert(call);
+                }
+            }
+        }
+        self.branches.push(ModeBranch {
+            keyword: keyword.to_string(),
+            line,
+            calls: calls.len(),
+            shared_calls: shared.len(),
+            arms: arms
+                .into_iter()
+                .map(|(label, lines, _)| BranchArm { label, lines })
+                .collect(),
+        });
+    }
+
+    /// Arms of an `if` chain, following `else if`s on the parameter
+    fn if_arms(&self, node: &ExprIf, arms: &mut Vec<(String, usize, BTreeSet<String>)>) {
+        arms.push((
+            snippet(&node.cond),
+            lines(node.then_branch.span()),
+            called(|c| c.visit_block(&node.then_branch)),
+        ));
+        match node.else_branch.as_ref().map(|(_, e)| &**e) {
+            Some(Expr::If(next)) if names(&next.cond, self.parameter) => self.if_arms(next, arms),
+            Some(other) => arms.push((
+                "else".to_string(),
+                lines(other.span()),
+                called(|c| c.visit_expr(other)),
+            )),
+            None => {}
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for BranchFinder<'_> {
+    // Nested items have their own parameters
+    fn visit_item(&mut self, _: &'ast Item) {}
+
+    fn visit_expr_if(&mut self, node: &'ast ExprIf) {
+        if !names(&node.cond, self.parameter) {
+            return syn::visit::visit_expr_if(self, node);
+        }
+        let mut arms = Vec::new();
+        self.if_arms(node, &mut arms);
+        self.branch("if", span_position(node.if_token.span).0, arms);
+    }
+
+    fn visit_expr_match(&mut self, node: &'ast ExprMatch) {
+        if !names(&node.expr, self.parameter) {
+            return syn::visit::visit_expr_match(self, node);
+        }
+        let arms = node
+            .arms
+            .iter()
+            .map(|arm| {
+                (
+                    snippet(&arm.pat),
+                    lines(arm.body.span()),
+                    called(|c| c.visit_expr(&arm.body)),
+                )
+            })
+            .collect();
+        self.branch("match", span_position(node.match_token.span).0, arms);
+    }
+}
+
+/// Whether an expression names the parameter
+fn names(expr: &Expr, parameter: &str) -> bool {
+    struct Finder<'a> {
+        parameter: &'a str,
+        found: bool,
+    }
+    impl<'ast> Visit<'ast> for Finder<'_> {
+        fn visit_expr_path(&mut self, node: &'ast ExprPath) {
+            self.found |= node.path.is_ident(self.parameter);
+        }
+    }
+    let mut finder = Finder {
+        parameter,
+        found: false,
+    };
+    finder.visit_expr(expr);
+    finder.found
+}
+
+/// Names of the functions and methods called by `visit`
+fn called(visit: impl FnOnce(&mut CallCollector)) -> BTreeSet<String> {
+    let mut collector = CallCollector::default();
+    visit(&mut collector);
+    collector.calls
+}
+
+#[derive(Default)]
+struct CallCollector {
+    calls: BTreeSet<String>,
+}
+
+impl<'ast> Visit<'ast> for CallCollector {
+    fn visit_expr_call(&mut self, node: &'ast ExprCall) {
+        if let Expr::Path(path) = &*node.func
+            && let Some(last) = path.path.segments.last()
+        {
+            self.calls.insert(last.ident.to_string());
+        }
+        syn::visit::visit_expr_call(self, node);
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast ExprMethodCall) {
+        self.calls.insert(node.method.to_string());
+        syn::visit::visit_expr_method_call(self, node);
+    }
+}
+
+fn lines(span: proc_macro2::Span) -> usize {
+    span.end().line + 1 - span.start().line
+}
+
+/// Source text of a syntax node on one line, shortened for reports
+fn snippet(node: &impl ToTokens) -> String {
+    let text = node
+        .to_token_stream()
+        .to_string()
+        .replace(" :: ", "::")
+        .replace(" (", "(")
+        .replace("( ", "(")
+        .replace(" )", ")")
+        .replace(" ,", ",");
+    if text.chars().count() > MAX_LABEL_LEN {
+        let short: String = text.chars().take(MAX_LABEL_LEN - 3).collect();
+        format!("{}...", short)
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mode_switches() {
+        let code = r#"
+pub fn export(data: &Data, kind: ExportKind) -> String {
+    match kind {
+        ExportKind::Csv => {
+            let header = csv_header(data);
+            let rows = csv_rows(data);
+            join(header, rows)
+        }
+        ExportKind::Json => {
+            let value = to_value(data);
+            let text = serde_json::to_string(&value);
+            text.unwrap()
+        }
+    }
+}
+
+fn render(doc: &Doc, verbose: bool) -> String {
+    let mut out = title(doc);
+    if verbose {
+        out.push_str(&details(doc));
+    }
+    out
+}
+
+impl Store {
+    pub fn save(&self, fast: bool) {
+        if fast {
+            self.write_raw();
+            self.flush();
+            self.touch();
+        } else {
+            self.compress();
+            self.checksum();
+            self.flush();
+            self.write_raw();
+        }
+    }
+}
+"#;
+        let switches = ModeAnalyzer::analyze(&syn::parse_file(code).unwrap());
+        // render has one short arm; save shares two of five calls
+        assert_eq!(switches.len(), 1);
+        let export = &switches[0];
+        assert_eq!(export.function, "export");
+        assert_eq!(export.parameter, "kind");
+        assert_eq!(export.parameter_type, "ExportKind");
+        assert!(export.is_public);
+        assert_eq!(export.lines, 14);
+        assert_eq!(export.branches[0].keyword, "match");
+        assert_eq!(export.branches[0].line, 3);
+        assert_eq!(
+            export.branches[0].arms,
+            vec![
+                BranchArm {
+                    label: "ExportKind::Csv".into(),
+                    lines: 5
+                },
+                BranchArm {
+                    label: "ExportKind::Json".into(),
+                    lines: 5
+                },
+            ]
+        );
+        assert_eq!(export.branches[0].shared_calls, 0);
+        assert_eq!(export.paths(), 2);
+    }
+}
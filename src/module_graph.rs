@@ -0,0 +1,455 @@
+//! Intra-crate module dependency graph
+//!
+//! Builds a graph of the modules inside each crate from `use` statements and
+//! `mod` declarations, so refactoring targets can be found inside a single
+//! large crate where the crate-level view shows nothing.
+//!
+//! - Every analyzed file is a module; its path is derived from the file
+//!   location under `src/` (`src/web/server.rs` and `src/web/server/mod.rs`
+//!   are both `web::server`, `src/lib.rs` is the crate root)
+//! - `use crate::a::b::Item` depends on the longest known module prefix
+//!   (`a::b`, else `a`); `super::`, `self::` and declared child modules are
+//!   resolved relative to the importing module
+//! - In a workspace, `use other_crate::a::Item` resolves into that member
+//! - `mod child;` is recorded as a declaration, not as a dependency
+//!
+//! Per module, **fan-out** is the number of modules it imports from and
+//! **fan-in** the number of modules importing from it.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, Write};
+use std::path::{Component, Path, PathBuf};
+
+use crate::graph::WeightedGraph;
+use crate::metrics::{ModuleMetrics, ProjectMetrics};
+
+/// Name of the root module when the crate name is unknown
+const ROOT: &str = "crate";
+
+/// Module path of a file relative to its crate's `src` directory
+///
+/// Returns an empty string for the crate root (`lib.rs`, `main.rs`).
+pub fn module_path_from_file(file: &Path) -> String {
+    let components: Vec<String> = file
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(s) => s.to_str().map(str::to_string),
+            _ => None,
+        })
+        .collect();
+    let start = components
+        .iter()
+        .rposition(|c| c == "src")
+        .map_or(components.len().saturating_sub(1), |i| i + 1);
+
+    let mut segments: Vec<String> = components[start..].to_vec();
+    if let Some(last) = segments.pop() {
+        let stem = last.strip_suffix(".rs").unwrap_or(&last).to_string();
+        let is_root = segments.is_empty() && (stem == "lib" || stem == "main");
+        if stem != "mod" && !is_root {
+            segments.push(stem);
+        }
+    }
+    segments.join("::")
+}
+
+/// Unique name of a file's module within its crate
+///
+/// The module path (`web::server`), or the file stem for the crate root
+/// (`lib`, `main`) so binary and library roots stay apart.
+pub fn module_name_from_file(file: &Path) -> String {
+    let module_path = module_path_from_file(file);
+    if !module_path.is_empty() {
+        return module_path;
+    }
+    file.file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+/// Qualified node name: crate name (or `crate`) plus module path
+fn node_name(krate: Option<&str>, module_path: &str) -> String {
+    let root = krate.unwrap_or(ROOT);
+    if module_path.is_empty() {
+        root.to_string()
+    } else {
+        format!("{}::{}", root, module_path)
+    }
+}
+
+/// A module in the graph
+#[derive(Debug, Clone)]
+pub struct ModuleNode {
+    /// Qualified name (`crate::web::server` or `my_crate::web::server`)
+    pub name: String,
+    pub krate: Option<String>,
+    /// Path inside the crate (empty for the root)
+    pub module_path: String,
+    pub file: PathBuf,
+}
+
+/// Fan-in/fan-out of a module
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModuleFan {
+    pub module: String,
+    /// Modules importing from this module
+    pub fan_in: usize,
+    /// Modules this module imports from
+    pub fan_out: usize,
+}
+
+impl ModuleFan {
+    /// Instability `fan_out / (fan_in + fan_out)`; isolated modules are stable
+    pub fn instability(&self) -> f64 {
+        let total = self.fan_in + self.fan_out;
+        if total == 0 {
+            0.0
+        } else {
+            self.fan_out as f64 / total as f64
+        }
+    }
+}
+
+/// Module graph of every analyzed crate
+#[derive(Debug, Clone, Default)]
+pub struct ModuleGraph {
+    /// Modules by qualified name
+    pub nodes: BTreeMap<String, ModuleNode>,
+    /// Import edges, weighted by the number of imported paths
+    pub edges: WeightedGraph,
+    /// `mod child;` declarations as (parent, child)
+    pub declarations: BTreeSet<(String, String)>,
+}
+
+impl ModuleGraph {
+    /// Build the graph from analyzed modules
+    pub fn build(metrics: &ProjectMetrics) -> Self {
+        let mut graph = ModuleGraph::default();
+        let mut modules: Vec<&ModuleMetrics> = metrics.modules.values().collect();
+        modules.sort_by(|a, b| a.path.cmp(&b.path));
+
+        for module in &modules {
+            let module_path = module_path_from_file(&module.path);
+            let name = node_name(module.crate_name.as_deref(), &module_path);
+            graph.nodes.insert(
+                name.clone(),
+                ModuleNode {
+                    name,
+                    krate: module.crate_name.clone(),
+                    module_path,
+                    file: module.path.clone(),
+                },
+            );
+        }
+
+        let crates: BTreeMap<String, String> = metrics
+            .workspace_members
+            .iter()
+            .map(|m| (m.replace('-', "_"), m.clone()))
+            .collect();
+
+        for module in &modules {
+            let module_path = module_path_from_file(&module.path);
+            let krate = module.crate_name.as_deref();
+            let source = node_name(krate, &module_path);
+
+            for child in &module.declared_modules {
+                let child_path = join(&module_path, child);
+                let child_name = node_name(krate, &child_path);
+                if graph.nodes.contains_key(&child_name) {
+                    graph.declarations.insert((source.clone(), child_name));
+                }
+            }
+
+            for use_path in &module.use_paths {
+                let Some(target) = graph.resolve(krate, &module_path, module, use_path, &crates)
+                else {
+                    continue;
+                };
+                if target != source {
+                    *graph
+                        .edges
+                        .entry(source.clone())
+                        .or_default()
+                        .entry(target)
+                        .or_default() += 1;
+                }
+            }
+        }
+
+        graph
+    }
+
+    /// Resolve an imported path to the module that defines it
+    fn resolve(
+        &self,
+        krate: Option<&str>,
+        module_path: &str,
+        module: &ModuleMetrics,
+        use_path: &str,
+        crates: &BTreeMap<String, String>,
+    ) -> Option<String> {
+        let mut segments: Vec<&str> = use_path.split("::").filter(|s| *s != "*").collect();
+        let first = *segments.first()?;
+
+        let (target_crate, mut base): (Option<&str>, Vec<&str>) = match first {
+            "crate" => {
+                segments.remove(0);
+                (krate, Vec::new())
+            }
+            "self" => {
+                segments.remove(0);
+                (krate, split_path(module_path))
+            }
+            "super" => {
+                let mut base = split_path(module_path);
+                while segments.first() == Some(&"super") {
+                    segments.remove(0);
+                    base.pop()?;
+                }
+                (krate, base)
+            }
+            child if module.declared_modules.iter().any(|m| m == child) => {
+                (krate, split_path(module_path))
+            }
+            other => {
+                let member = crates.get(other)?;
+                segments.remove(0);
+                (Some(member.as_str()), Vec::new())
+            }
+        };
+
+        base.extend(segments);
+        // Longest prefix that is a known module
+        while !base.is_empty() {
+            let name = node_name(target_crate, &base.join("::"));
+            if self.nodes.contains_key(&name) {
+                return Some(name);
+            }
+            base.pop();
+        }
+        let root = node_name(target_crate, "");
+        self.nodes.contains_key(&root).then_some(root)
+    }
+
+    /// Number of distinct modules importing from `module`
+    pub fn fan_in(&self, module: &str) -> usize {
+        self.edges
+            .values()
+            .filter(|targets| targets.contains_key(module))
+            .count()
+    }
+
+    /// Number of distinct modules `module` imports from
+    pub fn fan_out(&self, module: &str) -> usize {
+        self.edges.get(module).map_or(0, |targets| targets.len())
+    }
+
+    /// Fan-in/fan-out of every module, highest total first
+    pub fn fan_stats(&self) -> Vec<ModuleFan> {
+        let mut stats: Vec<ModuleFan> = self
+            .nodes
+            .keys()
+            .map(|module| ModuleFan {
+                module: module.clone(),
+                fan_in: self.fan_in(module),
+                fan_out: self.fan_out(module),
+            })
+            .collect();
+        stats.sort_by(|a, b| {
+            (b.fan_in + b.fan_out)
+                .cmp(&(a.fan_in + a.fan_out))
+                .then_with(|| a.module.cmp(&b.module))
+        });
+        stats
+    }
+}
+
+fn split_path(module_path: &str) -> Vec<&str> {
+    if module_path.is_empty() {
+        Vec::new()
+    } else {
+        module_path.split("::").collect()
+    }
+}
+
+fn join(module_path: &str, child: &str) -> String {
+    if module_path.is_empty() {
+        child.to_string()
+    } else {
+        format!("{}::{}", module_path, child)
+    }
+}
+
+/// Generate the module fan-in/fan-out report to writer
+pub fn generate_module_graph_report<W: Write>(
+    graph: &ModuleGraph,
+    writer: &mut W,
+) -> io::Result<()> {
+    writeln!(writer, "Module Dependencies (from use statements)")?;
+    writeln!(writer, "=========================================")?;
+    writeln!(writer)?;
+
+    if graph.nodes.is_empty() {
+        writeln!(writer, "No modules found.")?;
+        return Ok(());
+    }
+
+    let stats = graph.fan_stats();
+    let width = stats
+        .iter()
+        .map(|s| s.module.len())
+        .max()
+        .unwrap_or(0)
+        .max("Module".len());
+    writeln!(
+        writer,
+        "{:<width$}  {:>6}  {:>7}  {:>5}",
+        "Module", "Fan-in", "Fan-out", "I"
+    )?;
+    writeln!(writer, "{}", "-".repeat(width + 24))?;
+    for stat in &stats {
+        writeln!(
+            writer,
+            "{:<width$}  {:>6}  {:>7}  {:>5.2}",
+            stat.module,
+            stat.fan_in,
+            stat.fan_out,
+            stat.instability()
+        )?;
+    }
+
+    writeln!(writer)?;
+    writeln!(
+        writer,
+        "{} modules, {} import edges, {} mod declarations",
+        graph.nodes.len(),
+        graph.edges.values().map(|t| t.len()).sum::<usize>(),
+        graph.declarations.len()
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn module(path: &str, uses: &[&str], declared: &[&str]) -> ModuleMetrics {
+        let mut module =
+            ModuleMetrics::new(PathBuf::from(path), module_name_from_file(Path::new(path)));
+        module.use_paths = uses.iter().map(|u| u.to_string()).collect();
+        module.declared_modules = declared.iter().map(|d| d.to_string()).collect();
+        module
+    }
+
+    #[test]
+    fn test_module_path_from_file() {
+        assert_eq!(module_path_from_file(Path::new("/p/src/lib.rs")), "");
+        assert_eq!(
+            module_path_from_file(Path::new("src/metrics.rs")),
+            "metrics"
+        );
+        assert_eq!(
+            module_path_from_file(Path::new("/p/crates/a/src/web/mod.rs")),
+            "web"
+        );
+        assert_eq!(
+            module_path_from_file(Path::new("src/web/server.rs")),
+            "web::server"
+        );
+    }
+
+    #[test]
+    fn test_module_name_from_file() {
+        assert_eq!(module_name_from_file(Path::new("/p/src/lib.rs")), "lib");
+        assert_eq!(module_name_from_file(Path::new("src/main.rs")), "main");
+        assert_eq!(module_name_from_file(Path::new("src/net/mod.rs")), "net");
+        assert_eq!(
+            module_name_from_file(Path::new("src/store/mod.rs")),
+            "store"
+        );
+        assert_eq!(
+            module_name_from_file(Path::new("src/net/client.rs")),
+            "net::client"
+        );
+    }
+
+    #[test]
+    fn test_build_graph_and_fan() {
+        let mut metrics = ProjectMetrics::new();
+        metrics.add_module(module(
+            "src/lib.rs",
+            &["crate::web::Server"],
+            &["web", "metrics"],
+        ));
+        metrics.add_module(module(
+            "src/web/mod.rs",
+            &["self::server::run", "server::Config", "serde::Serialize"],
+            &["server"],
+        ));
+        metrics.add_module(module(
+            "src/web/server.rs",
+            &["super::super::metrics::Distance", "crate::metrics::*"],
+            &[],
+        ));
+        metrics.add_module(module("src/metrics.rs", &["crate::web::server"], &[]));
+
+        let graph = ModuleGraph::build(&metrics);
+
+        assert_eq!(graph.edges["crate"]["crate::web"], 1);
+        assert_eq!(graph.edges["crate::web"]["crate::web::server"], 2);
+        assert_eq!(graph.edges["crate::web::server"]["crate::metrics"], 2);
+        assert!(
+            graph
+                .declarations
+                .contains(&("crate::web".into(), "crate::web::server".into()))
+        );
+
+        assert_eq!(graph.fan_in("crate::web::server"), 2);
+        assert_eq!(graph.fan_out("crate::web::server"), 1);
+        let top = &graph.fan_stats()[0];
+        assert_eq!(top.module, "crate::web::server");
+        assert!((top.instability() - 1.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_workspace_crate_imports() {
+        let mut metrics = ProjectMetrics::new();
+        metrics.workspace_members = vec!["my-core".into(), "app".into()];
+        let mut core = module("/w/core/src/model.rs", &[], &[]);
+        core.crate_name = Some("my-core".into());
+        let mut app = module("/w/app/src/main.rs", &["my_core::model::User"], &[]);
+        app.crate_name = Some("app".into());
+        metrics.add_module(core);
+        metrics.add_module(app);
+
+        let graph = ModuleGraph::build(&metrics);
+        assert_eq!(graph.fan_out("app"), 1);
+        assert_eq!(graph.fan_in("my-core::model"), 1);
+    }
+
+    #[test]
+    fn test_mod_rs_modules_are_separate_nodes() {
+        let dir = crate::test_fixtures::mod_rs_crate();
+        let metrics =
+            crate::analyzer::analyze_project_filtered(dir.path(), &Default::default()).unwrap();
+        assert_eq!(metrics.total_files, 5);
+
+        let graph = ModuleGraph::build(&metrics);
+        let nodes: Vec<&str> = graph.nodes.keys().map(String::as_str).collect();
+        assert_eq!(
+            nodes,
+            vec![
+                "crate",
+                "crate::net",
+                "crate::net::client",
+                "crate::store",
+                "crate::util"
+            ]
+        );
+        assert_eq!(graph.edges["crate::net"]["crate::store"], 1);
+        assert_eq!(graph.edges["crate::store"]["crate::net::client"], 1);
+        assert_eq!(graph.edges["crate::net::client"]["crate::net"], 1);
+    }
+}
@@ -0,0 +1,133 @@
+//! Organizational coupling from git authorship
+//!
+//! Conway's law: a system's structure mirrors the communication structure
+//! of the people building it. A module that many different people change
+//! and that many other modules are coupled to is where coordination costs
+//! concentrate: every change needs agreement between its authors, and every
+//! change ripples into its neighbours.
+//!
+//! Authors are counted per file over the git analysis window (mailmap
+//! names from `git log`). Coupling is the module's fan-in plus fan-out.
+
+use std::path::PathBuf;
+
+use crate::metrics::ProjectMetrics;
+
+/// Modules coupled to fewer other modules are not reported
+pub const MIN_OWNERSHIP_COUPLING: usize = 5;
+
+/// A module changed by many authors and coupled to many modules
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnershipHotspot {
+    pub module: String,
+    pub file: PathBuf,
+    /// Distinct authors in the git window
+    pub authors: usize,
+    /// Author with the most commits (ties broken by name)
+    pub top_author: String,
+    /// Share of the module's commits made by the top author (0.0 - 1.0)
+    pub top_share: f64,
+    /// Fan-in plus fan-out
+    pub coupled_modules: usize,
+}
+
+/// Modules with more than `max_authors` authors and at least
+/// [`MIN_OWNERSHIP_COUPLING`] coupled modules, most authors first
+pub fn find_ownership_hotspots(
+    metrics: &ProjectMetrics,
+    max_authors: usize,
+) -> Vec<OwnershipHotspot> {
+    let fan_in = metrics.module_fan_in();
+    let fan_out = metrics.module_fan_out();
+
+    let mut found: Vec<OwnershipHotspot> = metrics
+        .modules
+        .values()
+        .filter_map(|module| {
+            let authors = metrics.module_authors(module)?;
+            if authors.len() <= max_authors {
+                return None;
+            }
+            let coupled_modules = fan_in.get(&module.name).copied().unwrap_or(0)
+                + fan_out.get(&module.name).copied().unwrap_or(0);
+            if coupled_modules < MIN_OWNERSHIP_COUPLING {
+                return None;
+            }
+            let (top_author, top_commits) = authors
+                .iter()
+                .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))?;
+            let total: usize = authors.values().sum();
+            Some(OwnershipHotspot {
+                module: module.name.clone(),
+                file: module.path.clone(),
+                authors: authors.len(),
+                top_author: top_author.clone(),
+                top_share: *top_commits as f64 / total.max(1) as f64,
+                coupled_modules,
+            })
+        })
+        .collect();
+    found.sort_by(|a, b| {
+        b.authors
+            .cmp(&a.authors)
+            .then_with(|| b.coupled_modules.cmp(&a.coupled_modules))
+            .then_with(|| a.module.cmp(&b.module))
+    });
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{
+        CouplingMetrics, Distance, IntegrationStrength, ModuleMetrics, Volatility,
+    };
+
+    #[test]
+    fn test_find_ownership_hotspots() {
+        let mut metrics = ProjectMetrics::new();
+        let names = ["core", "a", "b", "c", "d", "e", "f"];
+        for name in names {
+            metrics.add_module(ModuleMetrics::new(
+                format!("src/{name}.rs").into(),
+                name.into(),
+            ));
+        }
+        for name in &names[1..] {
+            metrics.add_coupling(CouplingMetrics::new(
+                format!("crate::{name}"),
+                "crate::core".to_string(),
+                IntegrationStrength::Functional,
+                Distance::DifferentModule,
+                Volatility::Low,
+            ));
+        }
+        let authors = |list: &[(&str, usize)]| {
+            list.iter()
+                .map(|(name, commits)| (name.to_string(), *commits))
+                .collect()
+        };
+        metrics.file_authors.insert(
+            "src/core.rs".into(),
+            authors(&[("ann", 6), ("bo", 1), ("cy", 1), ("di", 1), ("ed", 1)]),
+        );
+        metrics.file_authors.insert(
+            "src/a.rs".into(),
+            authors(&[("ann", 1), ("bo", 1), ("cy", 1)]),
+        );
+
+        let found = find_ownership_hotspots(&metrics, 4);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].module, "core");
+        assert_eq!((found[0].authors, found[0].coupled_modules), (5, 6));
+        assert_eq!(found[0].top_author, "ann");
+        assert!((found[0].top_share - 0.6).abs() < f64::EPSILON);
+
+        // `a` has few authors and a single coupling either way
+        assert!(
+            find_ownership_hotspots(&metrics, 2)
+                .iter()
+                .all(|h| h.module == "core")
+        );
+    }
+}
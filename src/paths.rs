@@ -0,0 +1,156 @@
+//! Source path selection
+//!
+//! Include/exclude globs decide which `.rs` files are analyzed, so generated
+//! code, vendored code and test fixtures do not distort depth ratios and
+//! connascence counts. They come from the `[paths]` section of
+//! `.coupling.toml` and from `--include`/`--exclude` on the command line.
+//!
+//! Discovery itself honors `.gitignore`, `.ignore` and git's exclude files,
+//! like ripgrep, and always skips `target/` and hidden directories, so build
+//! script output (`OUT_DIR`) and ignored copies of sources are never picked
+//! up.
+
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use glob::{Pattern, PatternError};
+use ignore::WalkBuilder;
+
+/// Every `.rs` file below `dir` that is not ignored (see module docs)
+///
+/// Ignore files apply outside git repositories too, so an unpacked source
+/// archive is analyzed like its checkout.
+pub(crate) fn rs_files(dir: &Path) -> impl Iterator<Item = PathBuf> {
+    WalkBuilder::new(dir)
+        .follow_links(true)
+        .require_git(false)
+        // `dir` itself may be inside a `target` directory
+        .filter_entry(|entry| entry.depth() == 0 || entry.file_name() != "target")
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            entry.file_type().is_some_and(|t| t.is_file())
+                && entry.path().extension() == Some(OsStr::new("rs"))
+        })
+        .map(ignore::DirEntry::into_path)
+}
+
+/// Whether `pattern` matches any trailing part of `path`
+///
+/// `src/legacy/*` matches both `./src/legacy/a.rs` and
+/// `crates/app/src/legacy/a.rs`.
+pub fn matches_path_suffix(pattern: &Pattern, path: &Path) -> bool {
+    let components: Vec<_> = path.components().collect();
+    (0..components.len()).any(|start| {
+        let suffix: PathBuf = components[start..].iter().collect();
+        pattern.matches_path(&suffix)
+    })
+}
+
+/// Include/exclude globs for source files
+#[derive(Debug, Clone, Default)]
+pub struct PathFilter {
+    /// When non-empty, only files matching one of these are analyzed
+    pub include: Vec<Pattern>,
+    /// Files matching any of these are skipped
+    pub exclude: Vec<Pattern>,
+}
+
+impl PathFilter {
+    /// Compile include and exclude globs
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self, PatternError> {
+        let compile = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|p| Pattern::new(p))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok(Self {
+            include: compile(include)?,
+            exclude: compile(exclude)?,
+        })
+    }
+
+    /// Add more globs (e.g. from the command line) to this filter
+    pub fn extend(&mut self, include: &[String], exclude: &[String]) -> Result<(), PatternError> {
+        let other = Self::new(include, exclude)?;
+        self.include.extend(other.include);
+        self.exclude.extend(other.exclude);
+        Ok(())
+    }
+
+    /// Whether the filter lets every file through
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Whether `path` should be analyzed
+    ///
+    /// Patterns are matched against the path relative to `root`, so the
+    /// directories above the project never match by accident.
+    pub fn allows(&self, path: &Path, root: &Path) -> bool {
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        let included = self.include.is_empty()
+            || self
+                .include
+                .iter()
+                .any(|p| matches_path_suffix(p, relative));
+        included
+            && !self
+                .exclude
+                .iter()
+                .any(|p| matches_path_suffix(p, relative))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_filter() {
+        let filter = PathFilter::new(&[], &["**/generated/**".into(), "tests/*".into()]).unwrap();
+        let root = Path::new("/home/tests/project");
+
+        assert!(filter.allows(Path::new("/home/tests/project/src/lib.rs"), root));
+        assert!(!filter.allows(Path::new("/home/tests/project/src/generated/api.rs"), root));
+        assert!(!filter.allows(Path::new("/home/tests/project/tests/it.rs"), root));
+        assert!(!filter.allows(
+            Path::new("/home/tests/project/crates/app/tests/it.rs"),
+            root
+        ));
+    }
+
+    #[test]
+    fn test_include_patterns() {
+        let mut filter = PathFilter::new(&["src/core/*".into()], &[]).unwrap();
+        filter.extend(&[], &["*_test.rs".into()]).unwrap();
+        let root = Path::new(".");
+
+        assert!(filter.allows(Path::new("./src/core/model.rs"), root));
+        assert!(!filter.allows(Path::new("./src/core/model_test.rs"), root));
+        assert!(!filter.allows(Path::new("./src/web/server.rs"), root));
+        assert!(PathFilter::new(&["[".into()], &[]).is_err());
+    }
+
+    #[test]
+    fn test_rs_files_skip_ignored_and_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("target").join("project");
+        for file in [
+            "src/lib.rs",
+            "src/gen/out.rs",
+            "src/notes.txt",
+            "target/debug/build/out.rs",
+            ".cache/copy.rs",
+        ] {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "").unwrap();
+        }
+        std::fs::write(root.join(".gitignore"), "/src/gen/\n").unwrap();
+
+        let files: Vec<PathBuf> = rs_files(&root).collect();
+        assert_eq!(files, vec![root.join("src/lib.rs")]);
+    }
+}
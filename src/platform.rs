@@ -0,0 +1,367 @@
+//! Conditional-compilation coupling to target platforms
+//!
+//! `#[cfg(windows)]`, `#[cfg(target_os = "linux")]` and `cfg!(unix)` couple
+//! a module to the platforms they name: changing how one platform works
+//! means editing every module that names it, and each platform build sees
+//! a different module. This report lists the modules naming platforms in
+//! `cfg` attributes, `cfg_attr` and `cfg!`, and the platforms each
+//! workspace member is coupled to through them.
+//!
+//! A module is **mixed** when some of its items are platform-specific and
+//! others are not: the portable logic cannot be read, tested or changed
+//! without the platform code around it. Platform code kept in its own
+//! modules (`#[cfg(windows)] mod windows;`, a file gated by
+//! `#![cfg(unix)]`) is not mixed; `mod` and `use` items never count as
+//! either kind.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use proc_macro2::{TokenStream, TokenTree};
+use syn::visit::Visit;
+
+use crate::metrics::ProjectMetrics;
+
+/// `cfg` keys whose value names a platform
+pub const PLATFORM_KEYS: &[&str] = &[
+    "target_os",
+    "target_family",
+    "target_arch",
+    "target_env",
+    "target_vendor",
+];
+
+/// `cfg` names that are platforms on their own
+pub const PLATFORM_NAMES: &[&str] = &["windows", "unix"];
+
+/// Platforms named by the conditional compilation of one file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlatformUsage {
+    /// Platform -> lines of the `cfg`s naming it
+    pub platforms: BTreeMap<String, Vec<usize>>,
+    /// The whole file is gated (`#![cfg(windows)]`)
+    pub file_gated: bool,
+    /// Items gated on a platform or containing platform-specific code
+    pub specific_items: usize,
+    /// Items compiled the same way on every platform
+    pub portable_items: usize,
+}
+
+impl PlatformUsage {
+    /// Collect the platform `cfg`s of a parsed file
+    pub fn analyze(file: &syn::File) -> Self {
+        let mut usage = Self::default();
+        let mut file_cfgs = PlatformCfgs::default();
+        for attr in &file.attrs {
+            file_cfgs.visit_attribute(attr);
+        }
+        usage.file_gated = !file_cfgs.0.is_empty();
+        usage.add(file_cfgs);
+
+        for item in &file.items {
+            match item {
+                syn::Item::Mod(_) | syn::Item::Use(_) | syn::Item::ExternCrate(_) => {
+                    let mut cfgs = PlatformCfgs::default();
+                    cfgs.visit_item(item);
+                    usage.add(cfgs);
+                }
+                // Methods of an ungated impl count one by one
+                syn::Item::Impl(item_impl) if !gated(&item_impl.attrs) => {
+                    for impl_item in &item_impl.items {
+                        let mut cfgs = PlatformCfgs::default();
+                        cfgs.visit_impl_item(impl_item);
+                        usage.classify(cfgs);
+                    }
+                }
+                _ => {
+                    let mut cfgs = PlatformCfgs::default();
+                    cfgs.visit_item(item);
+                    usage.classify(cfgs);
+                }
+            }
+        }
+        usage
+    }
+
+    /// Whether platform-specific and portable items share the module
+    pub fn is_mixed(&self) -> bool {
+        !self.file_gated && self.specific_items > 0 && self.portable_items > 0
+    }
+
+    fn classify(&mut self, cfgs: PlatformCfgs) {
+        if cfgs.0.is_empty() && !self.file_gated {
+            self.portable_items += 1;
+        } else {
+            self.specific_items += 1;
+        }
+        self.add(cfgs);
+    }
+
+    fn add(&mut self, cfgs: PlatformCfgs) {
+        for (platform, line) in cfgs.0 {
+            self.platforms.entry(platform).or_default().push(line);
+        }
+    }
+}
+
+/// Platforms named by `cfg`s, with their lines
+#[derive(Default)]
+struct PlatformCfgs(Vec<(String, usize)>);
+
+impl<'ast> Visit<'ast> for PlatformCfgs {
+    fn visit_attribute(&mut self, node: &'ast syn::Attribute) {
+        if (node.path().is_ident("cfg") || node.path().is_ident("cfg_attr"))
+            && let syn::Meta::List(list) = &node.meta
+        {
+            let line = list.path.segments[0].ident.span().start().line;
+            for platform in platform_names(list.tokens.clone()) {
+                self.0.push((platform, line));
+            }
+        }
+    }
+
+    fn visit_macro(&mut self, node: &'ast syn::Macro) {
+        if node.path.is_ident("cfg") {
+            let line = node.path.segments[0].ident.span().start().line;
+            for platform in platform_names(node.tokens.clone()) {
+                self.0.push((platform, line));
+            }
+        }
+        syn::visit::visit_macro(self, node);
+    }
+}
+
+/// Whether attributes gate an item on a platform
+fn gated(attrs: &[syn::Attribute]) -> bool {
+    let mut cfgs = PlatformCfgs::default();
+    for attr in attrs {
+        cfgs.visit_attribute(attr);
+    }
+    !cfgs.0.is_empty()
+}
+
+/// Platforms a `cfg` predicate names: `windows`, `unix` and the values of
+/// the [`PLATFORM_KEYS`], inside `all`, `any` and `not` too
+fn platform_names(tokens: TokenStream) -> Vec<String> {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    let mut names = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        match &tokens[i] {
+            TokenTree::Group(group) => names.extend(platform_names(group.stream())),
+            TokenTree::Ident(ident) => {
+                let key = ident.to_string();
+                let value = match (tokens.get(i + 1), tokens.get(i + 2)) {
+                    (Some(TokenTree::Punct(eq)), Some(TokenTree::Literal(value)))
+                        if eq.as_char() == '=' =>
+                    {
+                        i += 2;
+                        Some(value.to_string().trim_matches('"').to_string())
+                    }
+                    _ => None,
+                };
+                match value {
+                    Some(value) if PLATFORM_KEYS.contains(&key.as_str()) => names.push(value),
+                    None if PLATFORM_NAMES.contains(&key.as_str()) => names.push(key),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    names
+}
+
+/// A module naming platforms in its conditional compilation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlatformModule {
+    pub module: String,
+    pub file: PathBuf,
+    pub krate: Option<String>,
+    pub usage: PlatformUsage,
+}
+
+/// Modules and workspace members coupled to target platforms
+#[derive(Debug, Clone, Default)]
+pub struct PlatformReport {
+    /// Modules naming at least one platform, by module name
+    pub modules: Vec<PlatformModule>,
+    /// Workspace member -> platforms its modules name
+    pub crates: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl PlatformReport {
+    /// Collect the platform `cfg`s of every analyzed module
+    pub fn build(metrics: &ProjectMetrics) -> Self {
+        let mut modules: Vec<PlatformModule> = metrics
+            .modules
+            .values()
+            .filter(|m| !m.platforms.platforms.is_empty())
+            .map(|m| PlatformModule {
+                module: m.name.clone(),
+                file: m.path.clone(),
+                krate: m.crate_name.clone(),
+                usage: m.platforms.clone(),
+            })
+            .collect();
+        modules.sort_by(|a, b| a.module.cmp(&b.module).then_with(|| a.file.cmp(&b.file)));
+
+        let mut crates: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        for module in &modules {
+            if let Some(krate) = &module.krate {
+                crates
+                    .entry(krate.clone())
+                    .or_default()
+                    .extend(module.usage.platforms.keys().cloned());
+            }
+        }
+        Self { modules, crates }
+    }
+
+    /// Modules mixing platform-specific and portable items
+    pub fn mixed(&self) -> impl Iterator<Item = &PlatformModule> {
+        self.modules.iter().filter(|m| m.usage.is_mixed())
+    }
+}
+
+/// Generate the platform coupling report to writer
+pub fn generate_platform_report<W: Write>(
+    report: &PlatformReport,
+    writer: &mut W,
+) -> io::Result<()> {
+    writeln!(writer, "Platform Coupling (conditional compilation)")?;
+    writeln!(writer, "===========================================")?;
+    writeln!(writer)?;
+
+    if report.modules.is_empty() {
+        writeln!(writer, "No platform-specific cfgs found.")?;
+        return Ok(());
+    }
+
+    if !report.crates.is_empty() {
+        writeln!(writer, "Crates")?;
+        for (krate, platforms) in &report.crates {
+            let platforms: Vec<&str> = platforms.iter().map(String::as_str).collect();
+            writeln!(writer, "  {}: {}", krate, platforms.join(", "))?;
+        }
+        writeln!(writer)?;
+    }
+
+    writeln!(writer, "Modules")?;
+    for module in &report.modules {
+        let platforms: Vec<&str> = module.usage.platforms.keys().map(String::as_str).collect();
+        let note = if module.usage.file_gated {
+            " (whole file)".to_string()
+        } else if module.usage.is_mixed() {
+            format!(
+                " (mixed: {} platform-specific, {} portable items)",
+                module.usage.specific_items, module.usage.portable_items
+            )
+        } else {
+            String::new()
+        };
+        writeln!(
+            writer,
+            "  {} [{}]{}",
+            module.module,
+            platforms.join(", "),
+            note
+        )?;
+    }
+
+    writeln!(writer)?;
+    writeln!(
+        writer,
+        "{} modules name platforms, {} mix platform-specific and portable code",
+        report.modules.len(),
+        report.mixed().count()
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::ModuleMetrics;
+
+    fn usage(code: &str) -> PlatformUsage {
+        PlatformUsage::analyze(&syn::parse_file(code).unwrap())
+    }
+
+    #[test]
+    fn test_platform_usage() {
+        let mixed = usage(
+            r#"
+            #[cfg(windows)]
+            mod windows;
+            use std::path::Path;
+
+            pub fn parse(input: &str) -> u32 { 0 }
+
+            #[cfg(all(unix, not(target_os = "macos")))]
+            fn open() {}
+
+            pub fn home() -> String {
+                if cfg!(target_family = "wasm") { return String::new(); }
+                String::new()
+            }
+
+            impl Config {
+                #[cfg_attr(target_arch = "x86_64", inline)]
+                fn fast(&self) {}
+                fn slow(&self) {}
+            }
+            "#,
+        );
+        assert_eq!(
+            mixed.platforms.keys().collect::<Vec<_>>(),
+            vec!["macos", "unix", "wasm", "windows", "x86_64"]
+        );
+        assert_eq!(mixed.platforms["windows"], vec![2]);
+        // open, home, fast vs parse, slow
+        assert_eq!((mixed.specific_items, mixed.portable_items), (3, 2));
+        assert!(mixed.is_mixed());
+
+        let gated = usage("#![cfg(unix)]\nfn open() {}\nfn close() {}");
+        assert!(gated.file_gated);
+        assert!(!gated.is_mixed());
+
+        // Feature flags and dispatch modules are no platform code
+        let dispatch =
+            usage("#[cfg(feature = \"windows\")] mod win;\n#[cfg(unix)] mod unix;\nfn run() {}");
+        assert_eq!(dispatch.platforms.keys().collect::<Vec<_>>(), vec!["unix"]);
+        assert!(!dispatch.is_mixed());
+    }
+
+    #[test]
+    fn test_platform_report() {
+        let mut metrics = ProjectMetrics::new();
+        for (path, code) in [
+            ("src/fs.rs", "#[cfg(windows)] fn a() {}\nfn b() {}"),
+            ("src/sys.rs", "#![cfg(target_os = \"linux\")]\nfn c() {}"),
+            ("src/core.rs", "fn d() {}"),
+        ] {
+            let name = path.trim_start_matches("src/").trim_end_matches(".rs");
+            let mut module = ModuleMetrics::new(PathBuf::from(path), name.into());
+            module.crate_name = Some("app".into());
+            module.platforms = usage(code);
+            metrics.add_module(module);
+        }
+
+        let report = PlatformReport::build(&metrics);
+        assert_eq!(report.modules.len(), 2);
+        assert_eq!(
+            report.crates["app"].iter().collect::<Vec<_>>(),
+            vec!["linux", "windows"]
+        );
+
+        let mut out = Vec::new();
+        generate_platform_report(&report, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("  app: linux, windows\n"));
+        assert!(text.contains("  fs [windows] (mixed: 1 platform-specific, 1 portable items)\n"));
+        assert!(text.contains("  sys [linux] (whole file)\n"));
+    }
+}
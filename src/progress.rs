@@ -0,0 +1,158 @@
+//! Progress reporting for long analyses
+//!
+//! Large workspaces take a while to parse. The analysis reports what it is
+//! doing through a [`ProgressHook`] set on
+//! [`AnalysisScope::progress`](crate::AnalysisScope) (or
+//! [`AnalysisBuilder::progress`](crate::AnalysisBuilder::progress)):
+//! the phase it entered, how many files it found and each file it finished.
+//! Files are parsed in parallel, so the hook is called from several threads.
+//!
+//! The command line draws these events as an `indicatif` progress bar on
+//! stderr when it is a terminal ([`terminal_progress`]).
+
+use std::fmt;
+use std::io::{IsTerminal, stderr};
+use std::sync::Arc;
+
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+
+/// Step of the analysis pipeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnalysisPhase {
+    /// Reading cargo metadata and collecting source files
+    Discovering,
+    /// Parsing files and running the per-file analyzers
+    Parsing,
+    /// Turning dependencies into couplings between modules and crates
+    Resolving,
+    /// Re-running the temporal analyzer with custom paired operations
+    Temporal,
+    /// Reading the git history for volatility, co-changes and authors
+    GitHistory,
+}
+
+impl fmt::Display for AnalysisPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnalysisPhase::Discovering => write!(f, "Discovering files"),
+            AnalysisPhase::Parsing => write!(f, "Parsing"),
+            AnalysisPhase::Resolving => write!(f, "Resolving couplings"),
+            AnalysisPhase::Temporal => write!(f, "Applying temporal rules"),
+            AnalysisPhase::GitHistory => write!(f, "Reading git history"),
+        }
+    }
+}
+
+/// Something the analysis reports while it runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressEvent {
+    /// A new phase started
+    Phase(AnalysisPhase),
+    /// Number of source files that will be parsed
+    FilesDiscovered(usize),
+    /// A file was parsed (successfully or not)
+    FileAnalyzed { done: usize, total: usize },
+    /// The analysis is complete
+    Finished,
+}
+
+/// Callback receiving [`ProgressEvent`]s; does nothing by default
+#[derive(Clone, Default)]
+pub struct ProgressHook(Option<Arc<dyn Fn(ProgressEvent) + Send + Sync>>);
+
+impl ProgressHook {
+    /// Call `callback` for every event
+    pub fn new(callback: impl Fn(ProgressEvent) + Send + Sync + 'static) -> Self {
+        Self(Some(Arc::new(callback)))
+    }
+
+    /// Report an event
+    pub fn emit(&self, event: ProgressEvent) {
+        if let Some(callback) = &self.0 {
+            callback(event);
+        }
+    }
+}
+
+impl fmt::Debug for ProgressHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(if self.0.is_some() {
+            "ProgressHook(Some(..))"
+        } else {
+            "ProgressHook(None)"
+        })
+    }
+}
+
+/// Phase name while the file count is unknown: `Discovering files...`
+const PHASE_TEMPLATE: &str = "{msg}...";
+/// Phase name with a bar: `Parsing [#####.....] 12/40 files`
+const BAR_TEMPLATE: &str = "{msg} [{bar:30}] {pos}/{len} files";
+
+/// A progress bar on stderr, or `None` when stderr is not a terminal
+pub fn terminal_progress() -> Option<ProgressHook> {
+    if !stderr().is_terminal() {
+        return None;
+    }
+    Some(bar_progress(ProgressBar::with_draw_target(
+        None,
+        ProgressDrawTarget::stderr(),
+    )))
+}
+
+/// Draw the events on `bar`: the phase name until files are counted, then
+/// the files parsed so far
+fn bar_progress(bar: ProgressBar) -> ProgressHook {
+    let phase_style = ProgressStyle::with_template(PHASE_TEMPLATE).expect("valid template");
+    let bar_style = ProgressStyle::with_template(BAR_TEMPLATE)
+        .expect("valid template")
+        .progress_chars("#.");
+    bar.set_style(phase_style.clone());
+    ProgressHook::new(move |event| match event {
+        ProgressEvent::Phase(phase) => {
+            bar.set_style(phase_style.clone());
+            bar.set_message(phase.to_string());
+        }
+        ProgressEvent::FilesDiscovered(total) => {
+            bar.set_length(total as u64);
+            bar.set_position(0);
+        }
+        // Files finish in any order, so no single event is reliably the
+        // first; setting the style each time is cheap next to parsing
+        ProgressEvent::FileAnalyzed { done, total } => {
+            bar.set_style(bar_style.clone());
+            bar.set_length(total as u64);
+            bar.set_position(done as u64);
+        }
+        ProgressEvent::Finished => bar.finish_and_clear(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_progress_hook_and_bar() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&events);
+        let hook = ProgressHook::new(move |event| seen.lock().unwrap().push(event));
+        hook.clone().emit(ProgressEvent::FilesDiscovered(3));
+        ProgressHook::default().emit(ProgressEvent::Finished);
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![ProgressEvent::FilesDiscovered(3)]
+        );
+
+        let bar = ProgressBar::hidden();
+        let hook = bar_progress(bar.clone());
+        hook.emit(ProgressEvent::Phase(AnalysisPhase::Parsing));
+        hook.emit(ProgressEvent::FilesDiscovered(10));
+        hook.emit(ProgressEvent::FileAnalyzed { done: 3, total: 10 });
+        assert_eq!(bar.message(), "Parsing");
+        assert_eq!((bar.position(), bar.length()), (3, Some(10)));
+        hook.emit(ProgressEvent::Finished);
+        assert!(bar.is_finished());
+    }
+}
@@ -0,0 +1,334 @@
+//! Hotspot quadrants: complexity × churn
+//!
+//! Complex code is only expensive when it has to be changed, and code that
+//! changes often is only risky when it is hard to understand (Tornhill,
+//! *Your Code as a Crime Scene*). Plotting every module by change frequency
+//! and complexity gives four quadrants:
+//!
+//! - **Hotspot**: complex and changed often; the best refactoring targets
+//! - **Complex, stable**: complex but rarely touched; leave it alone
+//! - **Simple, churning**: changed often but easy to change
+//! - **Healthy**: simple and stable
+//!
+//! Churn is the number of commits that changed a module's file in the
+//! analyzed git window; complexity is the APOSD cognitive load estimate
+//! (see [`crate::aposd`]). A module is "high" on an axis when it is above
+//! zero and at or above the project median. Modules are ranked by
+//! `churn / max churn × complexity / max complexity`.
+//!
+//! The report can be written as a Markdown table, or as CSV or JSON
+//! scatter-plot data with one point per module.
+
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::aposd::cognitive_load;
+use crate::matrix::{MatrixFormat, csv_field};
+use crate::metrics::ProjectMetrics;
+
+/// Number of modules shown in the Markdown table
+const MARKDOWN_LIMIT: usize = 20;
+
+/// Quadrant of the complexity × churn plot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Quadrant {
+    Hotspot,
+    ComplexStable,
+    SimpleChurning,
+    Healthy,
+}
+
+impl std::fmt::Display for Quadrant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Quadrant::Hotspot => write!(f, "hotspot"),
+            Quadrant::ComplexStable => write!(f, "complex-stable"),
+            Quadrant::SimpleChurning => write!(f, "simple-churning"),
+            Quadrant::Healthy => write!(f, "healthy"),
+        }
+    }
+}
+
+/// A module plotted by churn and complexity
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QuadrantPoint {
+    pub module: String,
+    pub file: PathBuf,
+    /// Commits that changed the module's file
+    pub churn: usize,
+    /// APOSD cognitive load
+    pub complexity: usize,
+    pub lines_of_code: usize,
+    /// Normalized churn × normalized complexity (0.0 - 1.0)
+    pub score: f64,
+    pub quadrant: Quadrant,
+}
+
+/// Every module placed in a quadrant, highest score first
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct QuadrantReport {
+    /// Whether git history was available
+    pub has_history: bool,
+    pub median_churn: usize,
+    pub median_complexity: usize,
+    pub modules: Vec<QuadrantPoint>,
+}
+
+impl QuadrantReport {
+    /// Place every analyzed module in a quadrant
+    pub fn build(metrics: &ProjectMetrics) -> Self {
+        let points: Vec<(String, PathBuf, usize, usize, usize)> = metrics
+            .modules
+            .values()
+            .map(|module| {
+                (
+                    module.name.clone(),
+                    module.path.clone(),
+                    metrics.module_changes(module),
+                    cognitive_load(module),
+                    module.lines_of_code,
+                )
+            })
+            .collect();
+
+        let median_churn = median(points.iter().map(|p| p.2).collect());
+        let median_complexity = median(points.iter().map(|p| p.3).collect());
+        let max_churn = points.iter().map(|p| p.2).max().unwrap_or(0).max(1) as f64;
+        let max_complexity = points.iter().map(|p| p.3).max().unwrap_or(0).max(1) as f64;
+
+        let mut modules: Vec<QuadrantPoint> = points
+            .into_iter()
+            .map(|(module, file, churn, complexity, lines_of_code)| {
+                let churning = churn > 0 && churn >= median_churn;
+                let complex = complexity > 0 && complexity >= median_complexity;
+                let quadrant = match (complex, churning) {
+                    (true, true) => Quadrant::Hotspot,
+                    (true, false) => Quadrant::ComplexStable,
+                    (false, true) => Quadrant::SimpleChurning,
+                    (false, false) => Quadrant::Healthy,
+                };
+                QuadrantPoint {
+                    module,
+                    file,
+                    churn,
+                    complexity,
+                    lines_of_code,
+                    score: churn as f64 / max_churn * complexity as f64 / max_complexity,
+                    quadrant,
+                }
+            })
+            .collect();
+        modules.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| b.complexity.cmp(&a.complexity))
+                .then_with(|| a.module.cmp(&b.module))
+        });
+
+        Self {
+            has_history: !metrics.file_changes.is_empty(),
+            median_churn,
+            median_complexity,
+            modules,
+        }
+    }
+
+    /// Modules in the given quadrant, highest score first
+    pub fn in_quadrant(&self, quadrant: Quadrant) -> impl Iterator<Item = &QuadrantPoint> {
+        self.modules.iter().filter(move |m| m.quadrant == quadrant)
+    }
+}
+
+/// Median of the values (upper median for even counts, 0 when empty)
+fn median(mut values: Vec<usize>) -> usize {
+    if values.is_empty() {
+        return 0;
+    }
+    values.sort_unstable();
+    values[values.len() / 2]
+}
+
+/// Write the ranked table as Markdown
+fn write_markdown<W: Write>(report: &QuadrantReport, writer: &mut W) -> io::Result<()> {
+    writeln!(writer, "# Hotspot Quadrants")?;
+    writeln!(writer)?;
+    if !report.has_history {
+        writeln!(
+            writer,
+            "*Git history not available; run in a git repository without `--no-git` to measure churn.*"
+        )?;
+        writeln!(writer)?;
+    }
+    writeln!(
+        writer,
+        "Complexity is APOSD cognitive load, churn the number of commits changing the file. \
+         High means at or above the median (complexity {}, churn {}).",
+        report.median_complexity, report.median_churn
+    )?;
+    writeln!(writer)?;
+
+    for (quadrant, label) in [
+        (Quadrant::Hotspot, "Hotspots (complex, changed often)"),
+        (Quadrant::ComplexStable, "Complex, stable"),
+        (Quadrant::SimpleChurning, "Simple, changed often"),
+        (Quadrant::Healthy, "Healthy"),
+    ] {
+        writeln!(
+            writer,
+            "- {}: {}",
+            label,
+            report.in_quadrant(quadrant).count()
+        )?;
+    }
+    writeln!(writer)?;
+
+    writeln!(
+        writer,
+        "| # | Module | Churn | Complexity | Lines | Score | Quadrant |"
+    )?;
+    writeln!(
+        writer,
+        "|---|--------|------:|-----------:|------:|------:|----------|"
+    )?;
+    for (i, point) in report.modules.iter().take(MARKDOWN_LIMIT).enumerate() {
+        writeln!(
+            writer,
+            "| {} | `{}` | {} | {} | {} | {:.2} | {} |",
+            i + 1,
+            point.module,
+            point.churn,
+            point.complexity,
+            point.lines_of_code,
+            point.score,
+            point.quadrant
+        )?;
+    }
+    if report.modules.len() > MARKDOWN_LIMIT {
+        writeln!(
+            writer,
+            "\n*Showing top {} of {} modules; use `--quadrants=csv` for all*",
+            MARKDOWN_LIMIT,
+            report.modules.len()
+        )?;
+    }
+    Ok(())
+}
+
+/// Write one scatter-plot point per module as CSV
+fn write_csv<W: Write>(report: &QuadrantReport, writer: &mut W) -> io::Result<()> {
+    writeln!(
+        writer,
+        "module,file,churn,complexity,lines_of_code,score,quadrant"
+    )?;
+    for point in &report.modules {
+        writeln!(
+            writer,
+            "{},{},{},{},{},{:.4},{}",
+            csv_field(&point.module),
+            csv_field(&point.file.display().to_string()),
+            point.churn,
+            point.complexity,
+            point.lines_of_code,
+            point.score,
+            point.quadrant
+        )?;
+    }
+    Ok(())
+}
+
+/// Generate the hotspot quadrant report to writer
+pub fn generate_quadrant_output<W: Write>(
+    metrics: &ProjectMetrics,
+    format: MatrixFormat,
+    writer: &mut W,
+) -> io::Result<()> {
+    let report = QuadrantReport::build(metrics);
+    match format {
+        MatrixFormat::Markdown => write_markdown(&report, writer),
+        MatrixFormat::Csv => write_csv(&report, writer),
+        MatrixFormat::Json => {
+            serde_json::to_writer_pretty(&mut *writer, &report)?;
+            writeln!(writer)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{FunctionDefinition, ModuleMetrics, Visibility};
+
+    fn project() -> ProjectMetrics {
+        let mut metrics = ProjectMetrics::new();
+        for (name, functions, changes) in [
+            ("engine", 12, 20),
+            ("legacy", 10, 1),
+            ("config", 1, 15),
+            ("util", 1, 0),
+        ] {
+            let mut module = ModuleMetrics::new(format!("src/{name}.rs").into(), name.into());
+            for i in 0..functions {
+                module.function_definitions.insert(
+                    format!("f{i}"),
+                    FunctionDefinition {
+                        name: format!("f{i}"),
+                        visibility: Visibility::Public,
+                        param_count: 0,
+                        primitive_param_count: 0,
+                        param_types: Vec::new(),
+                        line: 0,
+                        column: 0,
+                    },
+                );
+            }
+            metrics.add_module(module);
+            if changes > 0 {
+                metrics
+                    .file_changes
+                    .insert(format!("src/{name}.rs"), changes);
+            }
+        }
+        metrics
+    }
+
+    #[test]
+    fn test_quadrants() {
+        let report = QuadrantReport::build(&project());
+        let summary: Vec<(&str, Quadrant)> = report
+            .modules
+            .iter()
+            .map(|m| (m.module.as_str(), m.quadrant))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("engine", Quadrant::Hotspot),
+                ("config", Quadrant::SimpleChurning),
+                ("legacy", Quadrant::ComplexStable),
+                ("util", Quadrant::Healthy),
+            ]
+        );
+        assert_eq!(report.modules[0].score, 1.0);
+        assert!(report.has_history);
+    }
+
+    #[test]
+    fn test_quadrant_csv() {
+        let mut out = Vec::new();
+        generate_quadrant_output(&project(), MatrixFormat::Csv, &mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("module,file,churn,complexity,lines_of_code,score,quadrant")
+        );
+        assert_eq!(
+            lines.next(),
+            Some("engine,src/engine.rs,20,12,0,1.0000,hotspot")
+        );
+        assert_eq!(csv.lines().count(), 5);
+    }
+}
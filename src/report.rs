@@ -8,7 +8,10 @@ use crate::balance::{
     BalanceScore, IssueThresholds, ProjectBalanceReport, Severity,
     analyze_project_balance_with_thresholds,
 };
-use crate::metrics::{Distance, IntegrationStrength, ProjectMetrics};
+use crate::edge_risk::{EDGE_REPORT_LIMIT, EdgeRisk, compute_edge_risks};
+use crate::functions::{COMPLEX_FUNCTION_LIMIT, FunctionDistribution, most_complex_functions};
+use crate::hub::{HUB_REPORT_LIMIT, HubRisk, compute_hub_risks};
+use crate::metrics::{Distance, IntegrationStrength, ProjectMetrics, Volatility};
 
 /// Generate a summary report to the given writer
 pub fn generate_summary<W: Write>(metrics: &ProjectMetrics, writer: &mut W) -> io::Result<()> {
@@ -52,6 +55,18 @@ pub fn generate_summary_with_thresholds<W: Write>(
     }
     writeln!(writer)?;
 
+    if !metrics.skipped_files.is_empty() {
+        if jp {
+            writeln!(writer, "解析できなかったファイル:")?;
+        } else {
+            writeln!(writer, "Skipped Files (could not be parsed):")?;
+        }
+        for skipped in &metrics.skipped_files {
+            writeln!(writer, "  {}: {}", skipped.path.display(), skipped.error)?;
+        }
+        writeln!(writer)?;
+    }
+
     // 3-Dimensional Analysis
     if !metrics.couplings.is_empty() {
         // Strength distribution
@@ -347,6 +362,60 @@ pub fn generate_summary_with_thresholds<W: Write>(
     Ok(())
 }
 
+/// Generate the summary metrics only (`--quiet`): grade, score, size,
+/// issue counts by severity and dependency cycles
+pub fn generate_quiet_summary<W: Write>(
+    metrics: &ProjectMetrics,
+    thresholds: &IssueThresholds,
+    writer: &mut W,
+) -> io::Result<()> {
+    let report = analyze_project_balance_with_thresholds(metrics, thresholds);
+    let count = |severity| *report.issues_by_severity.get(&severity).unwrap_or(&0);
+    let project_name = metrics.workspace_name.as_deref().unwrap_or("project");
+    let cycles = metrics.circular_dependency_summary().total_cycles;
+
+    if thresholds.japanese {
+        writeln!(
+            writer,
+            "{}: 評価 {} | スコア {:.2}/1.00 | モジュール {} | 結合 {}",
+            project_name,
+            report.health_grade,
+            report.average_score,
+            metrics.module_count(),
+            report.total_couplings
+        )?;
+        writeln!(
+            writer,
+            "問題: 緊急 {} / 高 {} / 中 {} / 低 {} | 循環依存 {}",
+            count(Severity::Critical),
+            count(Severity::High),
+            count(Severity::Medium),
+            count(Severity::Low),
+            cycles
+        )?;
+    } else {
+        writeln!(
+            writer,
+            "{}: Grade {} | Score {:.2}/1.00 | {} modules, {} couplings",
+            project_name,
+            report.health_grade,
+            report.average_score,
+            metrics.module_count(),
+            report.total_couplings
+        )?;
+        writeln!(
+            writer,
+            "Issues: {} critical, {} high, {} medium, {} low | {} cycles",
+            count(Severity::Critical),
+            count(Severity::High),
+            count(Severity::Medium),
+            count(Severity::Low),
+            cycles
+        )?;
+    }
+    Ok(())
+}
+
 /// Get Japanese translation for issue type
 fn issue_type_japanese(issue_type: crate::balance::IssueType) -> &'static str {
     use crate::balance::IssueType;
@@ -358,12 +427,58 @@ fn issue_type_japanese(issue_type: crate::balance::IssueType) -> &'static str {
         IssueType::HighAfferentCoupling => "入力依存過多 (多くのモジュールから依存される)",
         IssueType::UnnecessaryAbstraction => "過剰な抽象化",
         IssueType::CircularDependency => "循環依存",
+        IssueType::LayerViolation => "レイヤー違反 (許可されていない層への依存)",
+        IssueType::StampCoupling => "スタンプ結合 (大きな構造体の一部のフィールドだけを使用)",
         IssueType::ShallowModule => "浅いモジュール",
         IssueType::PassThroughMethod => "パススルーメソッド",
         IssueType::HighCognitiveLoad => "高認知負荷",
+        IssueType::UnusedPublicSurface => {
+            "未使用の公開API (ワークスペース内で使われていない pub 項目)"
+        }
+        IssueType::WideTrait => "幅広いトレイト (多数のメソッドと実装者)",
+        IssueType::OverriddenDefault => "上書きされるデフォルトメソッド (大半の実装者が独自に実装)",
+        IssueType::NonObviousInterface => {
+            "自明でないインターフェース (大きな公開APIにドキュメントが少ない)"
+        }
+        IssueType::TemporalDecomposition => "時間的分解 (実行フェーズごとにモジュールを分割)",
+        IssueType::FineGrainedErrors => "細かすぎるエラー (呼び出し側が多数のエラーケースを処理)",
+        IssueType::SpecialCaseFlag => "特殊ケースフラグ (モード引数で別々の処理に分岐)",
         IssueType::GodModule => "神モジュール (責務が多すぎる)",
         IssueType::PublicFieldExposure => "公開フィールド (getterを検討)",
         IssueType::PrimitiveObsession => "プリミティブ過多 (newtypeを検討)",
+        IssueType::OrphanRuleWorkaround => "孤児ルール回避 (外部型のnewtypeに外部トレイトを実装)",
+        IssueType::InteriorMutabilityLeak => "内部可変性の漏洩 (公開APIにRefCell/Mutex/ガード)",
+        IssueType::ExhaustiveEnumMatch => {
+            "網羅的なmatch (他モジュールが公開enumの全バリアントに依存)"
+        }
+        IssueType::FieldVisibilityLeak => "フィールド公開の漏洩 (他クレートが構造体リテラルで構築)",
+        IssueType::ReExportChain => "再エクスポートの連鎖 (複数クレートを越える pub use)",
+        IssueType::UnpairedOperation => "対になる操作の欠落 (時間的結合)",
+        IssueType::DroppedGuard => "即時破棄されるガード (時間的結合)",
+        IssueType::PanickingBuilder => "パニックするビルダー (時間的結合)",
+        IssueType::PanicPath => "初期化順序に依存するパニック (時間的結合)",
+        IssueType::CancellationUnsafeSelect => "キャンセル安全でない select! 分岐 (時間的結合)",
+        IssueType::DetachedSpawn => "JoinHandle を破棄したタスク生成 (時間的結合)",
+        IssueType::LockOrderInversion => "ロック取得順序の逆転 (デッドロックの恐れ)",
+        IssueType::DropOrderCoupling => "手書きの破棄順序 (時間的結合)",
+        IssueType::HiddenCoupling => "隠れた結合 (同時に変更されるファイル)",
+        IssueType::OrganizationalCoupling => {
+            "組織的結合 (多くの作者が変更する結合度の高いモジュール)"
+        }
+        IssueType::ConnascenceOfPosition => "位置のコナーセンス (同じ型の引数の順序依存)",
+        IssueType::ConnascenceOfAlgorithm => {
+            "アルゴリズムのコナーセンス (境界をまたぐエンコード/デコード・ハッシュ・重複コード)"
+        }
+        IssueType::ConnascenceOfExecution => "実行順序のコナーセンス (呼び出し順序への暗黙の依存)",
+        IssueType::ConnascenceOfType => {
+            "型のコナーセンス (多くのモジュールの公開シグネチャに現れる具象型)"
+        }
+        IssueType::ConnascenceOfIdentity => {
+            "同一性のコナーセンス (モジュール間で共有されるグローバル状態)"
+        }
+        IssueType::ConnascenceOfMeaning => {
+            "意味のコナーセンス (複数モジュールで共有されるマジックナンバー・SQLテーブル)"
+        }
     }
 }
 
@@ -424,11 +539,20 @@ pub fn generate_report_with_thresholds<W: Write>(
     // Executive Summary
     write_executive_summary(metrics, &report, writer)?;
 
+    // Files left out of the analysis
+    write_skipped_files_section(metrics, writer)?;
+
     // Refactoring Priorities (if any issues)
     if !report.issues.is_empty() {
         write_refactoring_priorities(&report, writer)?;
     }
 
+    // Central, large and busy modules
+    write_hub_risk_section(metrics, writer)?;
+
+    // Strong dependencies on volatile modules
+    write_edge_risk_section(metrics, writer)?;
+
     // Detailed Issues by Type
     write_issues_by_type(&report, writer)?;
 
@@ -437,6 +561,7 @@ pub fn generate_report_with_thresholds<W: Write>(
 
     // Module analysis
     write_module_section(metrics, writer)?;
+    write_function_section(metrics, writer)?;
 
     // Volatility section
     write_volatility_section(metrics, writer)?;
@@ -477,6 +602,29 @@ fn write_executive_summary<W: Write>(
     writeln!(writer, "|--------|-------|")?;
     writeln!(writer, "| Files Analyzed | {} |", metrics.total_files)?;
     writeln!(writer, "| Total Modules | {} |", metrics.module_count())?;
+    let expanded = metrics.expanded_module_count();
+    if expanded > 0 {
+        writeln!(
+            writer,
+            "| Macro-Expanded Modules | {} (line numbers refer to `cargo expand` output) |",
+            expanded
+        )?;
+    }
+    let tests = metrics.test_code();
+    if !tests.is_empty() {
+        writeln!(
+            writer,
+            "| Test Code (not analyzed) | {} tests, {} lines |",
+            tests.functions, tests.lines_of_code
+        )?;
+    }
+    if !metrics.skipped_files.is_empty() {
+        writeln!(
+            writer,
+            "| Skipped Files (could not be parsed) | {} |",
+            metrics.skipped_files.len()
+        )?;
+    }
     writeln!(writer, "| Total Couplings | {} |", report.total_couplings)?;
     writeln!(
         writer,
@@ -591,8 +739,10 @@ fn write_refactoring_priorities<W: Write>(
 
         writeln!(
             writer,
-            "- **Issue**: {} - {}",
-            issue.issue_type, issue.description
+            "- **Issue**: {} (`{}`) - {}",
+            issue.issue_type,
+            issue.issue_type.rule_id(),
+            issue.description
         )?;
         writeln!(writer, "- **Why**: {}", issue.issue_type.description())?;
         writeln!(writer, "- **Action**: {}", issue.refactoring)?;
@@ -602,6 +752,96 @@ fn write_refactoring_priorities<W: Write>(
     Ok(())
 }
 
+fn write_hub_risk_section<W: Write>(metrics: &ProjectMetrics, writer: &mut W) -> io::Result<()> {
+    let risks: Vec<HubRisk> = compute_hub_risks(metrics)
+        .into_iter()
+        .filter(|r| r.score > 0.0)
+        .take(HUB_REPORT_LIMIT)
+        .collect();
+    if risks.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(writer, "## 🎯 God Module Candidates\n")?;
+    writeln!(
+        writer,
+        "Modules that many others depend on and that are large, hard to understand and often changed. Splitting these pays off most.\n"
+    )?;
+    writeln!(
+        writer,
+        "| Module | Hub Risk | Fan-in | Lines | Cognitive Load | Changes |"
+    )?;
+    writeln!(
+        writer,
+        "|--------|----------|--------|-------|----------------|---------|"
+    )?;
+    for risk in &risks {
+        let changes = if metrics.file_changes.is_empty() {
+            "-".to_string()
+        } else {
+            risk.changes.to_string()
+        };
+        writeln!(
+            writer,
+            "| `{}` | {:.2} | {} | {} | {} | {} |",
+            truncate_path(&risk.module, 30),
+            risk.score,
+            risk.fan_in,
+            risk.lines_of_code,
+            risk.cognitive_load,
+            changes
+        )?;
+    }
+    writeln!(writer)?;
+
+    Ok(())
+}
+
+fn write_edge_risk_section<W: Write>(metrics: &ProjectMetrics, writer: &mut W) -> io::Result<()> {
+    let edges: Vec<EdgeRisk> = compute_edge_risks(metrics)
+        .into_iter()
+        .filter(|e| e.volatility != Volatility::Low)
+        .take(EDGE_REPORT_LIMIT)
+        .collect();
+    if edges.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(writer, "## ⚡ Risky Edges\n")?;
+    writeln!(
+        writer,
+        "Dependencies weighted by strength × volatility: strong coupling to code that changes often costs the most.\n"
+    )?;
+    writeln!(
+        writer,
+        "| Source | Target | Couplings | Strength | Volatility | Risk |"
+    )?;
+    writeln!(
+        writer,
+        "|--------|--------|-----------|----------|------------|------|"
+    )?;
+    for edge in &edges {
+        let volatility = match edge.volatility {
+            Volatility::Low => "Low",
+            Volatility::Medium => "Med",
+            Volatility::High => "High",
+        };
+        writeln!(
+            writer,
+            "| `{}` | `{}` | {} | {:.2} | {} | {:.2} |",
+            truncate_path(&edge.source, 30),
+            truncate_path(&edge.target, 30),
+            edge.couplings,
+            edge.strength,
+            volatility,
+            edge.risk
+        )?;
+    }
+    writeln!(writer)?;
+
+    Ok(())
+}
+
 fn write_issues_by_type<W: Write>(report: &ProjectBalanceReport, writer: &mut W) -> io::Result<()> {
     if report.issues.is_empty() {
         return Ok(());
@@ -884,6 +1124,89 @@ fn write_module_section<W: Write>(metrics: &ProjectMetrics, writer: &mut W) -> i
     Ok(())
 }
 
+fn write_function_section<W: Write>(metrics: &ProjectMetrics, writer: &mut W) -> io::Result<()> {
+    let overall = FunctionDistribution::of_project(metrics);
+    if overall.functions == 0 {
+        return Ok(());
+    }
+
+    writeln!(writer, "## Function Shape\n")?;
+    writeln!(writer, "{} functions\n", overall.functions)?;
+    writeln!(writer, "| Metric | p50 | p95 | Max |")?;
+    writeln!(writer, "|--------|-----|-----|-----|")?;
+    for (label, values) in [
+        ("Lines", overall.lines),
+        ("Nesting depth", overall.nesting),
+        ("Branches", overall.branches),
+        ("Cyclomatic complexity", overall.cyclomatic),
+        ("Cognitive complexity", overall.cognitive),
+    ] {
+        writeln!(
+            writer,
+            "| {} | {} | {} | {} |",
+            label, values.p50, values.p95, values.max
+        )?;
+    }
+    writeln!(writer)?;
+
+    let mut modules: Vec<(&String, FunctionDistribution)> = metrics
+        .modules
+        .iter()
+        .map(|(name, module)| (name, FunctionDistribution::of(&module.function_stats)))
+        .filter(|(_, shape)| shape.functions > 0)
+        .collect();
+    modules.sort_by(|a, b| b.1.lines.p95.cmp(&a.1.lines.p95).then_with(|| a.0.cmp(b.0)));
+
+    writeln!(writer, "### Longest Functions by Module (p95)\n")?;
+    writeln!(
+        writer,
+        "| Module | Functions | Lines p50 / p95 | Nesting p95 | Branches p95 |"
+    )?;
+    writeln!(
+        writer,
+        "|--------|-----------|-----------------|-------------|--------------|"
+    )?;
+    for (name, shape) in modules.iter().take(10) {
+        writeln!(
+            writer,
+            "| `{}` | {} | {} / {} | {} | {} |",
+            truncate_path(name, 30),
+            shape.functions,
+            shape.lines.p50,
+            shape.lines.p95,
+            shape.nesting.p95,
+            shape.branches.p95
+        )?;
+    }
+    writeln!(writer)?;
+
+    writeln!(writer, "### Most Complex Functions\n")?;
+    writeln!(
+        writer,
+        "| Function | Location | Lines | Cyclomatic | Cognitive |"
+    )?;
+    writeln!(
+        writer,
+        "|----------|----------|-------|------------|-----------|"
+    )?;
+    for function in most_complex_functions(metrics, COMPLEX_FUNCTION_LIMIT) {
+        writeln!(
+            writer,
+            "| `{}::{}` | {}:{} | {} | {} | {} |",
+            function.module,
+            function.function,
+            function.file.display(),
+            function.line,
+            function.lines,
+            function.cyclomatic,
+            function.cognitive
+        )?;
+    }
+    writeln!(writer)?;
+
+    Ok(())
+}
+
 fn write_volatility_section<W: Write>(metrics: &ProjectMetrics, writer: &mut W) -> io::Result<()> {
     writeln!(writer, "## Volatility Analysis\n")?;
 
@@ -925,6 +1248,39 @@ fn write_volatility_section<W: Write>(metrics: &ProjectMetrics, writer: &mut W)
     Ok(())
 }
 
+fn write_skipped_files_section<W: Write>(
+    metrics: &ProjectMetrics,
+    writer: &mut W,
+) -> io::Result<()> {
+    if metrics.skipped_files.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(
+        writer,
+        "## ⚠️ Skipped Files
+"
+    )?;
+    writeln!(
+        writer,
+        "These files could not be parsed and are missing from every metric below \
+         (`--strict` turns this into a failure).\n"
+    )?;
+    writeln!(writer, "| File | Error |")?;
+    writeln!(writer, "|------|-------|")?;
+    for skipped in &metrics.skipped_files {
+        writeln!(
+            writer,
+            "| `{}` | {} |",
+            skipped.path.display(),
+            skipped.error.replace('|', "\\|")
+        )?;
+    }
+    writeln!(writer)?;
+
+    Ok(())
+}
+
 fn write_circular_dependencies_section<W: Write>(
     metrics: &ProjectMetrics,
     writer: &mut W,
@@ -1091,7 +1447,12 @@ pub fn generate_ai_output_with_thresholds<W: Write>(
                 issue.source,
                 issue.target
             )?;
-            writeln!(writer, "   Type: {}", issue.issue_type)?;
+            writeln!(
+                writer,
+                "   Type: {} ({})",
+                issue.issue_type,
+                issue.issue_type.rule_id()
+            )?;
             writeln!(writer, "   Problem: {}", issue.description)?;
             writeln!(writer, "   Fix: {}", issue.refactoring)?;
             writeln!(writer)?;
@@ -1170,6 +1531,18 @@ mod tests {
         assert!(output_str.contains("Grade:"));
     }
 
+    #[test]
+    fn test_generate_quiet_summary() {
+        let metrics = ProjectMetrics::new();
+        let mut output = Vec::new();
+        generate_quiet_summary(&metrics, &IssueThresholds::default(), &mut output).unwrap();
+
+        let output_str = String::from_utf8(output).unwrap();
+        assert_eq!(output_str.lines().count(), 2);
+        assert!(output_str.starts_with("project: Grade "));
+        assert!(output_str.ends_with("Issues: 0 critical, 0 high, 0 medium, 0 low | 0 cycles\n"));
+    }
+
     #[test]
     fn test_generate_report() {
         let metrics = ProjectMetrics::new();
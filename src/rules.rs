@@ -0,0 +1,205 @@
+//! Rule selection: which detectors report findings
+//!
+//! Every issue type has a rule id (`category::name`, see
+//! [`IssueType::rule_id`]). Rules are turned on and off with selectors, in
+//! the config file or on the command line:
+//!
+//! ```toml
+//! [rules]
+//! # Only report these rules (everything when empty)
+//! enabled = ["temporal", "connascence::position"]
+//! # Never report these
+//! disabled = ["temporal::panicking-builder"]
+//! ```
+//!
+//! `--only` and `--skip` take the same comma-separated selectors; `--only`
+//! replaces `enabled` and `--skip` adds to `disabled`.
+//!
+//! A selector is `all`, a category (`temporal`) or a full id
+//! (`temporal::dropped-guard`, also written `temporal/dropped-guard`). When
+//! both lists match a rule, the more specific selector decides, so
+//! `disabled = ["temporal"]` with `enabled = ["temporal::dropped-guard"]`
+//! keeps the dropped-guard findings. On a tie, `disabled` wins.
+//!
+//! Reported rules can be given a level, again by selector with the most
+//! specific one winning:
+//!
+//! ```toml
+//! [rules.severity]
+//! "temporal::dropped-guard" = "error"   # fails --check
+//! "aposd" = "info"                      # only shown with --verbose
+//! "git::hidden-coupling" = "warning"    # reported, never fails the build
+//! ```
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::balance::IssueType;
+
+/// A selector that matches no rule
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Unknown rule '{0}' (see --explain list)")]
+pub struct UnknownRule(pub String);
+
+/// Level a rule's findings are reported at (`[rules.severity]`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleLevel {
+    /// Only shown in verbose reports
+    Info,
+    /// Reported, but never fails `--check`
+    Warning,
+    /// Fails `--check`
+    Error,
+}
+
+impl std::fmt::Display for RuleLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuleLevel::Info => write!(f, "info"),
+            RuleLevel::Warning => write!(f, "warning"),
+            RuleLevel::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Enabled and disabled rule selectors (`[rules]` section)
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+pub struct RuleFilter {
+    /// Only these rules are reported; all when empty
+    #[serde(default)]
+    pub enabled: Vec<String>,
+
+    /// These rules are never reported
+    #[serde(default)]
+    pub disabled: Vec<String>,
+
+    /// Levels by selector (`[rules.severity]`)
+    #[serde(default)]
+    pub severity: BTreeMap<String, RuleLevel>,
+}
+
+impl RuleFilter {
+    /// Whether findings of an issue type are reported
+    pub fn allows(&self, issue_type: IssueType) -> bool {
+        let enabled = best_match(&self.enabled, issue_type);
+        let disabled = best_match(&self.disabled, issue_type);
+        match (enabled, disabled) {
+            (Some(on), Some(off)) => on > off,
+            (Some(_), None) => true,
+            (None, Some(_)) => false,
+            (None, None) => self.enabled.is_empty(),
+        }
+    }
+
+    /// Level set for an issue type by its most specific selector
+    pub fn level(&self, issue_type: IssueType) -> Option<RuleLevel> {
+        self.severity
+            .iter()
+            .filter(|(selector, _)| issue_type.matches_rule(selector))
+            .max_by_key(|(selector, _)| specificity(selector))
+            .map(|(_, level)| *level)
+    }
+
+    /// Check that every selector matches at least one rule
+    pub fn validate(&self) -> Result<(), UnknownRule> {
+        match self
+            .enabled
+            .iter()
+            .chain(&self.disabled)
+            .chain(self.severity.keys())
+            .find(|s| !IssueType::ALL.iter().any(|t| t.matches_rule(s)))
+        {
+            Some(selector) => Err(UnknownRule(selector.clone())),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Specificity of the most specific selector matching an issue type
+fn best_match(selectors: &[String], issue_type: IssueType) -> Option<usize> {
+    selectors
+        .iter()
+        .filter(|s| issue_type.matches_rule(s))
+        .map(|s| specificity(s))
+        .max()
+}
+
+/// 0 for `all`, 1 for a category, 2 for a full rule id
+fn specificity(selector: &str) -> usize {
+    match selector {
+        "all" => 0,
+        s if s.contains("::") || s.contains('/') => 2,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(enabled: &[&str], disabled: &[&str]) -> RuleFilter {
+        RuleFilter {
+            enabled: enabled.iter().map(|s| s.to_string()).collect(),
+            disabled: disabled.iter().map(|s| s.to_string()).collect(),
+            ..RuleFilter::default()
+        }
+    }
+
+    #[test]
+    fn test_rule_filter() {
+        assert!(RuleFilter::default().allows(IssueType::GodModule));
+
+        let only_temporal = filter(&["temporal"], &[]);
+        assert!(only_temporal.allows(IssueType::DroppedGuard));
+        assert!(!only_temporal.allows(IssueType::GodModule));
+
+        // The more specific selector wins; ties go to `disabled`
+        let mixed = filter(&["temporal/dropped-guard"], &["temporal"]);
+        assert!(mixed.allows(IssueType::DroppedGuard));
+        assert!(!mixed.allows(IssueType::UnpairedOperation));
+        assert!(!mixed.allows(IssueType::GodModule));
+        assert!(!filter(&["aposd"], &["aposd"]).allows(IssueType::ShallowModule));
+
+        let skip = filter(&[], &["connascence::position", "git"]);
+        assert!(!skip.allows(IssueType::ConnascenceOfPosition));
+        assert!(!skip.allows(IssueType::HiddenCoupling));
+        assert!(skip.allows(IssueType::ConnascenceOfMeaning));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(
+            filter(&["all", "aposd::shallow-module"], &["temporal"])
+                .validate()
+                .is_ok()
+        );
+        assert_eq!(
+            filter(&[], &["temporal::paired-imbalance"]).validate(),
+            Err(UnknownRule("temporal::paired-imbalance".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_rule_levels() {
+        let rules: RuleFilter = toml::from_str(
+            r#"
+            [severity]
+            all = "warning"
+            temporal = "error"
+            "temporal/panicking-builder" = "info"
+        "#,
+        )
+        .unwrap();
+        assert_eq!(rules.level(IssueType::DroppedGuard), Some(RuleLevel::Error));
+        assert_eq!(
+            rules.level(IssueType::PanickingBuilder),
+            Some(RuleLevel::Info)
+        );
+        assert_eq!(rules.level(IssueType::GodModule), Some(RuleLevel::Warning));
+        assert_eq!(RuleFilter::default().level(IssueType::GodModule), None);
+        assert!(rules.validate().is_ok());
+    }
+}
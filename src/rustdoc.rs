@@ -0,0 +1,329 @@
+//! Path resolution via rustdoc JSON
+//!
+//! The static analysis attributes a path to the crate named by its first
+//! segment, so `use facade::Engine` couples to `facade` even when `facade`
+//! only re-exports `my_core::engine::Engine`, and a type re-exported under
+//! another name is counted under the wrong name. With `--rustdoc` the
+//! workspace libraries are documented with rustdoc's JSON output (which
+//! needs a nightly toolchain), and every path that crosses a crate boundary
+//! is resolved through the re-exports to where the item is defined before
+//! couplings are built.
+//!
+//! Only paths into libraries are resolved: rustdoc does not document
+//! function bodies or private re-exports, and binaries cannot be named by
+//! other crates. Paths rustdoc does not know keep the static resolution.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+use serde::Deserialize;
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::analyzer::AnalysisScope;
+use crate::workspace::{WorkspaceError, WorkspaceInfo};
+
+/// Glob re-exports followed when resolving one path
+const MAX_GLOB_DEPTH: usize = 4;
+
+/// Errors that can occur while generating or reading rustdoc JSON
+#[derive(Error, Debug)]
+pub enum RustdocError {
+    #[error(
+        "rustdoc JSON needs a nightly toolchain (install it with `rustup toolchain install nightly`)"
+    )]
+    NightlyMissing,
+
+    #[error("Failed to run cargo doc: {0}")]
+    IoError(#[from] io::Error),
+
+    #[error("cargo doc failed: {0}")]
+    Failed(String),
+
+    #[error("Failed to parse rustdoc JSON of '{0}': {1}")]
+    ParseError(String, String),
+
+    #[error("Workspace error: {0}")]
+    WorkspaceError(#[from] WorkspaceError),
+}
+
+/// The parts of a rustdoc JSON file the resolution needs
+#[derive(Debug, Deserialize)]
+struct RustdocCrate {
+    root: Value,
+    index: HashMap<String, RustdocItem>,
+    paths: HashMap<String, ItemSummary>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RustdocItem {
+    name: Option<String>,
+    inner: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct ItemSummary {
+    path: Vec<String>,
+}
+
+/// Where the items of the documented crates are defined
+#[derive(Debug, Clone, Default)]
+pub struct RustdocIndex {
+    /// Path an item can be named by -> path of its definition
+    items: HashMap<String, String>,
+    /// Module path -> definition paths of the modules it glob re-exports
+    globs: HashMap<String, Vec<String>>,
+}
+
+impl RustdocIndex {
+    /// Add the items and re-exports of one crate's rustdoc JSON
+    pub fn add_crate(&mut self, json: &str) -> Result<(), serde_json::Error> {
+        let krate: RustdocCrate = serde_json::from_str(json)?;
+        for summary in krate.paths.values() {
+            let path = summary.path.join("::");
+            self.items.entry(path.clone()).or_insert(path);
+        }
+        if let Some(root) = krate.index.get(&id_key(&krate.root)) {
+            let name = root.name.clone().unwrap_or_default();
+            self.add_module(&krate, root, &name);
+        }
+        Ok(())
+    }
+
+    /// Record the re-exports of `module` (named `path`) and its submodules
+    fn add_module(&mut self, krate: &RustdocCrate, module: &RustdocItem, path: &str) {
+        let Some(children) = module.inner["module"]["items"].as_array() else {
+            return;
+        };
+        for child in children
+            .iter()
+            .filter_map(|id| krate.index.get(&id_key(id)))
+        {
+            if let Some(name) = &child.name
+                && child.inner.get("module").is_some()
+            {
+                self.add_module(krate, child, &format!("{}::{}", path, name));
+                continue;
+            }
+            let reexport = &child.inner["use"];
+            let Some(target) = krate.paths.get(&id_key(&reexport["id"])) else {
+                continue;
+            };
+            let target = target.path.join("::");
+            if reexport["is_glob"].as_bool() == Some(true) {
+                self.globs.entry(path.to_string()).or_default().push(target);
+            } else if let Some(name) = reexport["name"].as_str() {
+                self.items.insert(format!("{}::{}", path, name), target);
+            }
+        }
+    }
+
+    /// No crate has been added
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Definition path of `path` (`facade::Engine::new` ->
+    /// `my_core::engine::Engine::new`), if rustdoc knows a prefix of it
+    pub fn resolve(&self, path: &str) -> Option<String> {
+        let segments: Vec<&str> = path.split("::").collect();
+        self.resolve_segments(&segments, 0).map(|(path, _)| path)
+    }
+
+    /// Resolved path and the number of its segments rustdoc knows
+    fn resolve_segments(&self, segments: &[&str], depth: usize) -> Option<(String, usize)> {
+        for len in (1..=segments.len()).rev() {
+            let prefix = segments[..len].join("::");
+            let rest = &segments[len..];
+            // Items a glob brings into this module, if rustdoc knows them
+            if depth < MAX_GLOB_DEPTH
+                && !rest.is_empty()
+                && let Some(modules) = self.globs.get(&prefix)
+            {
+                for module in modules {
+                    let mut imported: Vec<&str> = module.split("::").collect();
+                    let module_len = imported.len();
+                    imported.extend(rest);
+                    if let Some((resolved, known)) = self.resolve_segments(&imported, depth + 1)
+                        && known > module_len
+                    {
+                        return Some((resolved, known));
+                    }
+                }
+            }
+            if let Some(defined) = self.items.get(&prefix) {
+                let known = defined.split("::").count();
+                let mut resolved = vec![defined.as_str()];
+                resolved.extend(rest);
+                return Some((resolved.join("::"), known));
+            }
+        }
+        None
+    }
+
+    /// Definition path of a path used in `krate`, if it is defined in
+    /// another crate than the path names
+    ///
+    /// `crate::` paths are resolved from `krate`'s root; `self::` and
+    /// `super::` paths are left to the static analysis.
+    pub fn resolve_cross_crate(&self, path: &str, krate: &str) -> Option<String> {
+        let lib = krate.replace('-', "_");
+        let path = match path.split_once("::") {
+            Some(("crate", rest)) => format!("{}::{}", lib, rest),
+            Some(("self" | "super", _)) => return None,
+            _ => path.to_string(),
+        };
+        let first = path.split("::").next()?;
+        let resolved = self.resolve(&path)?;
+        (resolved.split("::").next() != Some(first)).then_some(resolved)
+    }
+}
+
+/// Key of a rustdoc item id (numbers in current formats, strings in old
+/// ones)
+fn id_key(id: &Value) -> String {
+    match id {
+        Value::String(id) => id.clone(),
+        id => id.to_string(),
+    }
+}
+
+/// Document the workspace libraries with rustdoc's JSON output and index
+/// every documented crate
+///
+/// Documenting needs a nightly toolchain; `cargo +nightly doc` is run with
+/// `--no-deps`, so dependencies outside the workspace are only known by the
+/// items the workspace refers to.
+pub fn rustdoc_index(path: &Path, scope: &AnalysisScope) -> Result<RustdocIndex, RustdocError> {
+    let workspace = WorkspaceInfo::from_path_with(path, &scope.features)?;
+    let _span = tracing::debug_span!("cargo_doc_json").entered();
+
+    let mut command = Command::new("cargo");
+    command
+        .args(["+nightly", "doc", "--workspace", "--no-deps", "--lib"])
+        .arg("--manifest-path")
+        .arg(workspace.root.join("Cargo.toml"))
+        .env("RUSTDOCFLAGS", "-Z unstable-options --output-format json");
+    if scope.features.all_features {
+        command.arg("--all-features");
+    }
+    if scope.features.no_default_features {
+        command.arg("--no-default-features");
+    }
+    if !scope.features.features.is_empty() {
+        command.args(["--features", &scope.features.features.join(",")]);
+    }
+
+    let output = command.output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("no such command: `+nightly`") || stderr.contains("is not installed") {
+            return Err(RustdocError::NightlyMissing);
+        }
+        return Err(RustdocError::Failed(stderr.trim().to_string()));
+    }
+
+    let mut index = RustdocIndex::default();
+    let doc_dir = workspace.target_directory.join("doc");
+    for member in &workspace.members {
+        let lib = member.replace('-', "_");
+        let file = doc_dir.join(format!("{}.json", lib));
+        // Members without a library target
+        let Ok(json) = std::fs::read_to_string(&file) else {
+            continue;
+        };
+        index
+            .add_crate(&json)
+            .map_err(|e| RustdocError::ParseError(member.clone(), e.to_string()))?;
+    }
+    Ok(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Trimmed rustdoc JSON of a facade crate re-exporting `my_core`
+    const FACADE: &str = r#"{
+        "root": 6,
+        "format_version": 57,
+        "index": {
+            "6": {"id": 6, "crate_id": 0, "name": "facade",
+                  "inner": {"module": {"is_crate": true, "items": [2, 3, 4]}}},
+            "2": {"id": 2, "crate_id": 0, "name": "prelude",
+                  "inner": {"module": {"is_crate": false, "items": [0]}}},
+            "0": {"id": 0, "crate_id": 0, "name": null,
+                  "inner": {"use": {"source": "my_core::engine::Engine", "name": "Motor",
+                                    "id": 1, "is_glob": false}}},
+            "3": {"id": 3, "crate_id": 0, "name": null,
+                  "inner": {"use": {"source": "my_core::engine::Engine", "name": "Engine",
+                                    "id": 1, "is_glob": false}}},
+            "4": {"id": 4, "crate_id": 0, "name": null,
+                  "inner": {"use": {"source": "my_core::util", "name": "util",
+                                    "id": 5, "is_glob": true}}}
+        },
+        "paths": {
+            "6": {"crate_id": 0, "path": ["facade"], "kind": "module"},
+            "2": {"crate_id": 0, "path": ["facade", "prelude"], "kind": "module"},
+            "1": {"crate_id": 20, "path": ["my_core", "engine", "Engine"], "kind": "struct"},
+            "5": {"crate_id": 20, "path": ["my_core", "util"], "kind": "module"}
+        }
+    }"#;
+
+    /// Trimmed rustdoc JSON of `my_core`
+    const CORE: &str = r#"{
+        "root": "0:0:1",
+        "index": {
+            "0:0:1": {"name": "my_core", "inner": {"module": {"items": []}}}
+        },
+        "paths": {
+            "0:1:2": {"crate_id": 0, "path": ["my_core", "util", "helper"], "kind": "function"}
+        }
+    }"#;
+
+    #[test]
+    fn test_resolve_reexports() {
+        let mut index = RustdocIndex::default();
+        index.add_crate(FACADE).unwrap();
+
+        assert_eq!(
+            index.resolve("facade::Engine::new").as_deref(),
+            Some("my_core::engine::Engine::new")
+        );
+        assert_eq!(
+            index.resolve("facade::prelude::Motor").as_deref(),
+            Some("my_core::engine::Engine")
+        );
+        // Defined in the facade itself
+        assert_eq!(index.resolve_cross_crate("facade::prelude", "app"), None);
+        assert_eq!(
+            index
+                .resolve_cross_crate("crate::Engine", "facade")
+                .as_deref(),
+            Some("my_core::engine::Engine")
+        );
+        assert_eq!(index.resolve("serde::Serialize"), None);
+
+        // Glob re-exports resolve once the globbed crate is documented
+        assert_eq!(
+            index.resolve("facade::helper").as_deref(),
+            Some("facade::helper")
+        );
+        index.add_crate(CORE).unwrap();
+        assert_eq!(
+            index
+                .resolve_cross_crate("facade::helper", "app")
+                .as_deref(),
+            Some("my_core::util::helper")
+        );
+    }
+
+    #[test]
+    fn test_invalid_json() {
+        let mut index = RustdocIndex::default();
+        assert!(index.add_crate("{\"root\": 0}").is_err());
+        assert!(index.is_empty());
+    }
+}
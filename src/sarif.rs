@@ -0,0 +1,398 @@
+//! SARIF 2.1.0 export for coupling findings
+//!
+//! Converts the issues found by the balance analysis into the
+//! [SARIF](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html)
+//! format so that GitHub Code Scanning and other SARIF viewers can display
+//! them inline on pull requests.
+//!
+//! Severity mapping:
+//! - Critical / High → `error`
+//! - Medium → `warning`
+//! - Low → `note`
+//!
+//! Each result carries the finding's fingerprint (see
+//! [`crate::fingerprint`]) in `partialFingerprints`, so code scanning
+//! tracks findings across commits that move code around.
+//!
+//! Artifact URIs are relative to the workspace root (the working directory
+//! when it is unknown), and each location names the Rust module path as a
+//! logical location, so logs from different checkouts line up.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::balance::{
+    CouplingIssue, IssueThresholds, IssueType, Severity, analyze_project_balance_with_thresholds,
+};
+use crate::fingerprint::fingerprint;
+use crate::metrics::ProjectMetrics;
+use crate::rules::RuleLevel;
+
+/// SARIF schema URI
+pub const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// SARIF specification version
+pub const SARIF_VERSION: &str = "2.1.0";
+
+/// `partialFingerprints` key of the finding fingerprint
+pub const FINGERPRINT_KEY: &str = "couplingFingerprint/v1";
+
+/// Top-level SARIF log
+#[derive(Debug, Clone, Serialize)]
+pub struct SarifLog {
+    #[serde(rename = "$schema")]
+    pub schema: &'static str,
+    pub version: &'static str,
+    pub runs: Vec<SarifRun>,
+}
+
+/// A single analysis run
+#[derive(Debug, Clone, Serialize)]
+pub struct SarifRun {
+    pub tool: SarifTool,
+    pub results: Vec<SarifResult>,
+}
+
+/// Tool information
+#[derive(Debug, Clone, Serialize)]
+pub struct SarifTool {
+    pub driver: SarifDriver,
+}
+
+/// Tool driver (name, version and rule metadata)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifDriver {
+    pub name: &'static str,
+    pub version: &'static str,
+    pub information_uri: &'static str,
+    pub rules: Vec<SarifRule>,
+}
+
+/// Rule metadata (one per issue type)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifRule {
+    pub id: String,
+    pub name: String,
+    pub short_description: SarifMessage,
+    pub default_configuration: SarifConfiguration,
+}
+
+/// Default configuration of a rule
+#[derive(Debug, Clone, Serialize)]
+pub struct SarifConfiguration {
+    pub level: &'static str,
+}
+
+/// A plain text message
+#[derive(Debug, Clone, Serialize)]
+pub struct SarifMessage {
+    pub text: String,
+}
+
+/// A single finding
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifResult {
+    pub rule_id: String,
+    pub rule_index: usize,
+    pub level: &'static str,
+    pub message: SarifMessage,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub locations: Vec<SarifLocation>,
+    pub partial_fingerprints: BTreeMap<&'static str, String>,
+}
+
+/// Location of a finding
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifLocation {
+    pub physical_location: SarifPhysicalLocation,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub logical_locations: Vec<SarifLogicalLocation>,
+}
+
+/// Logical (module) location
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifLogicalLocation {
+    pub fully_qualified_name: String,
+    pub kind: &'static str,
+}
+
+/// Physical (file-based) location
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifPhysicalLocation {
+    pub artifact_location: SarifArtifactLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<SarifRegion>,
+}
+
+/// File reference
+#[derive(Debug, Clone, Serialize)]
+pub struct SarifArtifactLocation {
+    pub uri: String,
+}
+
+/// Region inside a file
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifRegion {
+    pub start_line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_column: Option<usize>,
+}
+
+/// Map a severity to a SARIF result level
+pub fn severity_to_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical | Severity::High => "error",
+        Severity::Medium => "warning",
+        Severity::Low => "note",
+    }
+}
+
+/// SARIF level of an issue: its `[rules.severity]` level, if set, or its
+/// severity
+fn issue_level(
+    thresholds: &IssueThresholds,
+    issue_type: IssueType,
+    severity: Severity,
+) -> &'static str {
+    match thresholds.rules.level(issue_type) {
+        Some(RuleLevel::Error) => "error",
+        Some(RuleLevel::Warning) => "warning",
+        Some(RuleLevel::Info) => "note",
+        None => severity_to_level(severity),
+    }
+}
+
+/// Build a SARIF log from project metrics
+pub fn build_sarif_log(metrics: &ProjectMetrics, thresholds: &IssueThresholds) -> SarifLog {
+    let report = analyze_project_balance_with_thresholds(metrics, thresholds);
+    let base_dir = metrics
+        .workspace_root
+        .clone()
+        .or_else(|| std::env::current_dir().ok());
+
+    // Rules are keyed by id so that rule indices are stable across runs
+    let mut rules: BTreeMap<String, (IssueType, Severity)> = BTreeMap::new();
+    for issue in &report.issues {
+        let entry = rules
+            .entry(issue.issue_type.rule_id().to_string())
+            .or_insert((issue.issue_type, issue.severity));
+        entry.1 = entry.1.max(issue.severity);
+    }
+    let rule_index: HashMap<String, usize> = rules
+        .keys()
+        .enumerate()
+        .map(|(i, id)| (id.clone(), i))
+        .collect();
+
+    let results = report
+        .issues
+        .iter()
+        .map(|issue| {
+            let id = issue.issue_type.rule_id().to_string();
+            SarifResult {
+                rule_index: rule_index[&id],
+                rule_id: id,
+                level: issue_level(thresholds, issue.issue_type, issue.severity),
+                message: SarifMessage {
+                    text: format!("{} → {}", issue.description, issue.refactoring),
+                },
+                locations: issue_location(metrics, issue, base_dir.as_deref())
+                    .into_iter()
+                    .collect(),
+                partial_fingerprints: BTreeMap::from([(
+                    FINGERPRINT_KEY,
+                    fingerprint(issue, metrics),
+                )]),
+            }
+        })
+        .collect();
+
+    SarifLog {
+        schema: SARIF_SCHEMA,
+        version: SARIF_VERSION,
+        runs: vec![SarifRun {
+            tool: SarifTool {
+                driver: SarifDriver {
+                    name: env!("CARGO_PKG_NAME"),
+                    version: env!("CARGO_PKG_VERSION"),
+                    information_uri: env!("CARGO_PKG_REPOSITORY"),
+                    rules: rules
+                        .into_iter()
+                        .map(|(id, (issue_type, severity))| SarifRule {
+                            name: issue_type.to_string(),
+                            short_description: SarifMessage {
+                                text: issue_type.description().to_string(),
+                            },
+                            default_configuration: SarifConfiguration {
+                                level: issue_level(thresholds, issue_type, severity),
+                            },
+                            id,
+                        })
+                        .collect(),
+                },
+            },
+            results,
+        }],
+    }
+}
+
+/// Resolve the file (and line/column, if known) an issue belongs to
+fn issue_location(
+    metrics: &ProjectMetrics,
+    issue: &CouplingIssue,
+    base_dir: Option<&Path>,
+) -> Option<SarifLocation> {
+    let path = issue.file(metrics)?;
+    let location = &issue.location;
+
+    Some(SarifLocation {
+        physical_location: SarifPhysicalLocation {
+            artifact_location: SarifArtifactLocation {
+                uri: to_uri(path, base_dir),
+            },
+            region: (location.line > 0).then_some(SarifRegion {
+                start_line: location.line,
+                start_column: (location.column > 0).then_some(location.column),
+            }),
+        },
+        logical_locations: issue
+            .module(metrics)
+            .map(|module| SarifLogicalLocation {
+                fully_qualified_name: module.rust_path(),
+                kind: "module",
+            })
+            .into_iter()
+            .collect(),
+    })
+}
+
+/// Convert a path into a SARIF artifact URI relative to `base_dir`
+fn to_uri(path: &Path, base_dir: Option<&Path>) -> String {
+    let relative = base_dir
+        .and_then(|base| path.strip_prefix(base).ok())
+        .unwrap_or(path);
+    let uri = relative.to_string_lossy().replace('\\', "/");
+    uri.strip_prefix("./").map(String::from).unwrap_or(uri)
+}
+
+/// Generate SARIF output to writer
+pub fn generate_sarif_output<W: Write>(
+    metrics: &ProjectMetrics,
+    thresholds: &IssueThresholds,
+    writer: &mut W,
+) -> io::Result<()> {
+    let log = build_sarif_log(metrics, thresholds);
+    let json = serde_json::to_string_pretty(&log).map_err(io::Error::other)?;
+    writeln!(writer, "{}", json)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{
+        CouplingMetrics, Distance, IntegrationStrength, ModuleMetrics, Volatility,
+    };
+    use std::path::PathBuf;
+
+    fn project_with_god_module() -> ProjectMetrics {
+        let mut project = ProjectMetrics::new();
+        let mut module = ModuleMetrics::new(PathBuf::from("./src/big.rs"), "big".to_string());
+        for i in 0..40 {
+            module.add_function_definition(format!("f{}", i), crate::metrics::Visibility::Public);
+        }
+        project.add_module(module);
+        project.add_coupling(CouplingMetrics::with_location(
+            "big".to_string(),
+            "other".to_string(),
+            IntegrationStrength::Intrusive,
+            Distance::DifferentModule,
+            Volatility::High,
+            crate::metrics::Visibility::Public,
+            PathBuf::from("./src/big.rs"),
+            12,
+        ));
+        project
+    }
+
+    #[test]
+    fn test_severity_to_level() {
+        assert_eq!(severity_to_level(Severity::Critical), "error");
+        assert_eq!(severity_to_level(Severity::High), "error");
+        assert_eq!(severity_to_level(Severity::Medium), "warning");
+        assert_eq!(severity_to_level(Severity::Low), "note");
+    }
+
+    #[test]
+    fn test_to_uri_strips_base_and_dot() {
+        assert_eq!(to_uri(Path::new("./src/lib.rs"), None), "src/lib.rs");
+        assert_eq!(
+            to_uri(Path::new("/repo/src/lib.rs"), Some(Path::new("/repo"))),
+            "src/lib.rs"
+        );
+    }
+
+    #[test]
+    fn test_sarif_log_structure() {
+        let project = project_with_god_module();
+        let thresholds = IssueThresholds {
+            strict_mode: false,
+            ..Default::default()
+        };
+        let log = build_sarif_log(&project, &thresholds);
+
+        assert_eq!(log.version, "2.1.0");
+        assert_eq!(log.runs.len(), 1);
+        let run = &log.runs[0];
+        assert!(!run.results.is_empty());
+
+        for result in &run.results {
+            assert_eq!(run.tool.driver.rules[result.rule_index].id, result.rule_id);
+            let location = &result.locations[0].physical_location;
+            assert_eq!(location.artifact_location.uri, "src/big.rs");
+            // Module-level findings point at the file, coupling findings at the use site
+            let module_level = matches!(
+                result.rule_id.as_str(),
+                "rust::god-module" | "aposd::high-cognitive-load"
+            );
+            let expected_line = (!module_level).then_some(12);
+            assert_eq!(
+                location.region.as_ref().map(|r| r.start_line),
+                expected_line
+            );
+        }
+
+        let json = serde_json::to_value(&log).unwrap();
+        assert!(json["$schema"].as_str().unwrap().contains("sarif"));
+        assert!(json["runs"][0]["results"][0]["ruleId"].is_string());
+        let partial = &json["runs"][0]["results"][0]["partialFingerprints"][FINGERPRINT_KEY];
+        assert_eq!(partial.as_str().unwrap().len(), 16);
+        let logical = &json["runs"][0]["results"][0]["locations"][0]["logicalLocations"][0];
+        assert_eq!(logical["fullyQualifiedName"], "crate::big");
+    }
+
+    #[test]
+    fn test_uris_are_relative_to_workspace_root() {
+        let mut project = ProjectMetrics::new();
+        let mut module = ModuleMetrics::new(PathBuf::from("/ci/work/src/big.rs"), "big".into());
+        for i in 0..40 {
+            module.add_function_definition(format!("f{}", i), crate::metrics::Visibility::Public);
+        }
+        project.add_module(module);
+        project.workspace_root = Some(PathBuf::from("/ci/work"));
+
+        let log = build_sarif_log(&project, &IssueThresholds::default());
+        let location = &log.runs[0].results[0].locations[0].physical_location;
+        assert_eq!(location.artifact_location.uri, "src/big.rs");
+    }
+}
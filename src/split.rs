@@ -0,0 +1,458 @@
+//! What-if simulation of splitting a crate
+//!
+//! Extracting modules into a new crate is expensive to try and easy to get
+//! wrong: Cargo rejects a split whose two halves import from each other,
+//! and a split can leave the remaining crate more unstable than before.
+//! This simulation moves the listed modules (with their submodules) into a
+//! new crate and recomputes, before and after:
+//!
+//! - the crate references: imported paths from one crate into another,
+//!   resolved through the [`ModuleGraph`]
+//! - Ca, Ce and instability of every crate (see [`MemberStability`]),
+//!   counting a crate as a dependency when any of its items is imported
+//! - crate dependency cycles, with the module imports that would create
+//!   a cycle between the remaining and the extracted crate
+//!
+//! Both sides are computed from the code, not from the manifests, so the
+//! before and after numbers can be compared.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, Write};
+
+use thiserror::Error;
+
+use crate::graph::{DependencyCycle, WeightedGraph, find_cycles};
+use crate::metrics::ProjectMetrics;
+use crate::module_graph::{ModuleGraph, ModuleNode};
+use crate::stability::MemberStability;
+
+/// Crate name of modules outside a workspace, as in the module graph
+const ROOT: &str = "crate";
+
+/// Errors setting up a split simulation
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SplitError {
+    #[error("crate `{krate}` not found (analyzed crates: {})", .available.join(", "))]
+    UnknownCrate {
+        krate: String,
+        available: Vec<String>,
+    },
+    #[error("none of the modules {} found in crate `{krate}`", .modules.join(", "))]
+    NoModules { krate: String, modules: Vec<String> },
+}
+
+/// Crate-level coupling at one point of the simulation
+#[derive(Debug, Clone, Default)]
+pub struct CrateSnapshot {
+    /// Crate -> crate -> imported paths
+    pub references: WeightedGraph,
+    /// Every analyzed crate, sorted by name
+    pub stability: Vec<MemberStability>,
+    pub cycles: Vec<DependencyCycle>,
+}
+
+impl CrateSnapshot {
+    fn build<'a>(
+        nodes: impl Iterator<Item = (&'a ModuleNode, String)>,
+        metrics: &ProjectMetrics,
+        graph: &ModuleGraph,
+        crate_of: impl Fn(&str) -> Option<String>,
+    ) -> Self {
+        let mut references = WeightedGraph::new();
+        for (source, targets) in &graph.edges {
+            let Some(from) = crate_of(source) else {
+                continue;
+            };
+            for (target, weight) in targets {
+                match crate_of(target) {
+                    Some(to) if to != from => {
+                        *references
+                            .entry(from.clone())
+                            .or_default()
+                            .entry(to)
+                            .or_default() += weight
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let mut stability: BTreeMap<String, MemberStability> = BTreeMap::new();
+        for (node, krate) in nodes {
+            let entry = stability
+                .entry(krate.clone())
+                .or_insert_with(|| MemberStability {
+                    name: krate,
+                    dependents: Vec::new(),
+                    dependencies: Vec::new(),
+                    abstract_types: 0,
+                    concrete_types: 0,
+                    type_uses: BTreeMap::new(),
+                });
+            if let Some(module) = metrics.modules.values().find(|m| m.path == node.file) {
+                for def in module.type_definitions.values() {
+                    if def.is_trait {
+                        entry.abstract_types += 1;
+                    } else {
+                        entry.concrete_types += 1;
+                    }
+                }
+            }
+        }
+        for (from, targets) in &references {
+            for to in targets.keys() {
+                if let Some(krate) = stability.get_mut(from) {
+                    krate.dependencies.push(to.clone());
+                }
+                if let Some(krate) = stability.get_mut(to) {
+                    krate.dependents.push(from.clone());
+                }
+            }
+        }
+        for krate in stability.values_mut() {
+            krate.dependencies.sort();
+            krate.dependents.sort();
+        }
+
+        Self {
+            cycles: find_cycles(&references),
+            references,
+            stability: stability.into_values().collect(),
+        }
+    }
+
+    /// Stability of one crate
+    pub fn get(&self, krate: &str) -> Option<&MemberStability> {
+        self.stability.iter().find(|s| s.name == krate)
+    }
+
+    /// Imported paths from one crate into another
+    pub fn references(&self, from: &str, to: &str) -> usize {
+        self.references
+            .get(from)
+            .and_then(|targets| targets.get(to))
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+/// Crate coupling before and after extracting modules into a new crate
+#[derive(Debug, Clone)]
+pub struct SplitSimulation {
+    /// Crate the modules are extracted from
+    pub krate: String,
+    /// Name of the extracted crate
+    pub new_crate: String,
+    /// Module paths moved, sorted
+    pub moved: Vec<String>,
+    /// Listed modules not found in the crate
+    pub unknown: Vec<String>,
+    pub before: CrateSnapshot,
+    pub after: CrateSnapshot,
+    /// Module imports from the extracted crate back into the remaining
+    /// one, as (from, to, imported paths); with imports in the other
+    /// direction too, they make the split a cycle
+    pub back_references: Vec<(String, String, usize)>,
+}
+
+impl SplitSimulation {
+    /// Simulate moving `modules` (module paths inside `krate`, such as
+    /// `net` or `net::http`) and their submodules into `new_crate`
+    pub fn simulate(
+        metrics: &ProjectMetrics,
+        krate: &str,
+        modules: &[String],
+        new_crate: &str,
+    ) -> Result<Self, SplitError> {
+        let graph = ModuleGraph::build(metrics);
+        let crate_name = |node: &ModuleNode| node.krate.clone().unwrap_or_else(|| ROOT.into());
+
+        let crates: BTreeSet<String> = graph.nodes.values().map(crate_name).collect();
+        if !crates.contains(krate) {
+            return Err(SplitError::UnknownCrate {
+                krate: krate.to_string(),
+                available: crates.into_iter().collect(),
+            });
+        }
+
+        let prefix = format!("{}::", krate);
+        let listed: Vec<&str> = modules
+            .iter()
+            .map(|m| m.strip_prefix(&prefix).unwrap_or(m))
+            .collect();
+        let is_moved = |node: &ModuleNode| {
+            crate_name(node) == krate
+                && listed.iter().any(|m| {
+                    node.module_path == *m || node.module_path.starts_with(&format!("{}::", m))
+                })
+        };
+        let moved: Vec<String> = graph
+            .nodes
+            .values()
+            .filter(|node| is_moved(node))
+            .map(|node| node.module_path.clone())
+            .collect();
+        if moved.is_empty() {
+            return Err(SplitError::NoModules {
+                krate: krate.to_string(),
+                modules: modules.to_vec(),
+            });
+        }
+        let unknown = listed
+            .iter()
+            .filter(|m| {
+                !moved
+                    .iter()
+                    .any(|p| p == *m || p.starts_with(&format!("{}::", m)))
+            })
+            .map(|m| m.to_string())
+            .collect();
+
+        let before_crate = |name: &str| graph.nodes.get(name).map(crate_name);
+        let after_crate = |name: &str| {
+            graph.nodes.get(name).map(|node| {
+                if is_moved(node) {
+                    new_crate.to_string()
+                } else {
+                    crate_name(node)
+                }
+            })
+        };
+        let before = CrateSnapshot::build(
+            graph.nodes.values().map(|n| (n, crate_name(n))),
+            metrics,
+            &graph,
+            before_crate,
+        );
+        let after = CrateSnapshot::build(
+            graph
+                .nodes
+                .values()
+                .map(|n| (n, after_crate(&n.name).unwrap_or_default())),
+            metrics,
+            &graph,
+            after_crate,
+        );
+
+        let mut back_references = Vec::new();
+        for (source, targets) in &graph.edges {
+            if !graph.nodes.get(source).is_some_and(is_moved) {
+                continue;
+            }
+            for (target, weight) in targets {
+                if graph
+                    .nodes
+                    .get(target)
+                    .is_some_and(|node| crate_name(node) == krate && !is_moved(node))
+                {
+                    back_references.push((source.clone(), target.clone(), *weight));
+                }
+            }
+        }
+        back_references.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+
+        Ok(Self {
+            krate: krate.to_string(),
+            new_crate: new_crate.to_string(),
+            moved,
+            unknown,
+            before,
+            after,
+            back_references,
+        })
+    }
+
+    /// Crate cycles the split would add
+    pub fn new_cycles(&self) -> Vec<&DependencyCycle> {
+        self.after
+            .cycles
+            .iter()
+            .filter(|cycle| cycle.members.contains(&self.new_crate))
+            .collect()
+    }
+}
+
+/// `Ca/Ce/I` of a crate, or `-` when it does not exist
+fn format_stability(stability: Option<&MemberStability>) -> String {
+    match stability {
+        Some(s) => format!("{}/{}/{:.2}", s.afferent(), s.efferent(), s.instability()),
+        None => "-".to_string(),
+    }
+}
+
+/// Generate the split simulation report to writer
+pub fn generate_split_report<W: Write>(
+    simulation: &SplitSimulation,
+    writer: &mut W,
+) -> io::Result<()> {
+    let title = format!(
+        "Split Simulation: {} -> {} + {}",
+        simulation.krate, simulation.krate, simulation.new_crate
+    );
+    writeln!(writer, "{}", title)?;
+    writeln!(writer, "{}", "=".repeat(title.chars().count()))?;
+    writeln!(writer)?;
+
+    writeln!(
+        writer,
+        "Moved modules ({}): {}",
+        simulation.moved.len(),
+        simulation.moved.join(", ")
+    )?;
+    if !simulation.unknown.is_empty() {
+        writeln!(writer, "Not found: {}", simulation.unknown.join(", "))?;
+    }
+    writeln!(writer)?;
+
+    writeln!(writer, "Crate references (imported paths, before -> after)")?;
+    let mut pairs: BTreeSet<(&str, &str)> = BTreeSet::new();
+    for snapshot in [&simulation.before, &simulation.after] {
+        for (from, targets) in &snapshot.references {
+            for to in targets.keys() {
+                pairs.insert((from, to));
+            }
+        }
+    }
+    if pairs.is_empty() {
+        writeln!(writer, "  (none)")?;
+    }
+    for (from, to) in pairs {
+        let (before, after) = (
+            simulation.before.references(from, to),
+            simulation.after.references(from, to),
+        );
+        if before != after {
+            writeln!(writer, "  {} -> {}: {} -> {}", from, to, before, after)?;
+        } else {
+            writeln!(writer, "  {} -> {}: {}", from, to, before)?;
+        }
+    }
+    writeln!(writer)?;
+
+    writeln!(writer, "Stability (Ca/Ce/I, before -> after)")?;
+    for after in &simulation.after.stability {
+        let before = simulation.before.get(&after.name);
+        let (before_text, after_text) = (format_stability(before), format_stability(Some(after)));
+        if before_text == after_text {
+            writeln!(writer, "  {}: {}", after.name, after_text)?;
+        } else {
+            writeln!(
+                writer,
+                "  {}: {} -> {}",
+                after.name, before_text, after_text
+            )?;
+        }
+    }
+    writeln!(writer)?;
+
+    let new_cycles = simulation.new_cycles();
+    if new_cycles.is_empty() {
+        writeln!(
+            writer,
+            "No crate cycles: {} can be extracted as is ({} cycles before, {} after)",
+            simulation.new_crate,
+            simulation.before.cycles.len(),
+            simulation.after.cycles.len()
+        )?;
+    } else {
+        for cycle in &new_cycles {
+            writeln!(
+                writer,
+                "Cycle: {} (weakest edge {} -> {}, {} imported paths)",
+                cycle.members.join(" <-> "),
+                cycle.weakest_edge.from,
+                cycle.weakest_edge.to,
+                cycle.weakest_edge.weight
+            )?;
+        }
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "Imports from the extracted modules back into {}:",
+            simulation.krate
+        )?;
+        for (from, to, weight) in &simulation.back_references {
+            writeln!(writer, "  {} -> {} ({})", from, to, weight)?;
+        }
+        writeln!(writer)?;
+        writeln!(
+            writer,
+            "Cargo rejects cyclic crate dependencies; move or invert these imports before splitting"
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::ModuleMetrics;
+    use std::path::PathBuf;
+
+    fn project(modules: &[(&str, &str, &[&str])]) -> ProjectMetrics {
+        let mut metrics = ProjectMetrics::new();
+        for (krate, path, uses) in modules {
+            let name = path.rsplit('/').next().unwrap().trim_end_matches(".rs");
+            let mut module = ModuleMetrics::new(PathBuf::from(path), name.into());
+            module.crate_name = Some(krate.to_string());
+            module.use_paths = uses.iter().map(|u| u.to_string()).collect();
+            metrics.add_module(module);
+        }
+        metrics.workspace_members = vec!["app".into(), "core".into()];
+        metrics
+    }
+
+    #[test]
+    fn test_split_without_cycle() {
+        let metrics = project(&[
+            ("app", "app/src/main.rs", &["core::net::Client"]),
+            ("core", "core/src/lib.rs", &[]),
+            ("core", "core/src/net.rs", &["crate::util::retry"]),
+            ("core", "core/src/net/http.rs", &[]),
+            ("core", "core/src/util.rs", &[]),
+        ]);
+        let simulation =
+            SplitSimulation::simulate(&metrics, "core", &["net".into(), "db".into()], "core-net")
+                .unwrap();
+        assert_eq!(simulation.moved, vec!["net", "net::http"]);
+        assert_eq!(simulation.unknown, vec!["db"]);
+        assert_eq!(simulation.before.references("app", "core"), 1);
+        assert_eq!(simulation.after.references("app", "core"), 0);
+        assert_eq!(simulation.after.references("app", "core-net"), 1);
+        assert_eq!(simulation.after.references("core-net", "core"), 1);
+        let core = simulation.after.get("core").unwrap();
+        assert_eq!((core.afferent(), core.efferent()), (1, 0));
+        assert!(simulation.new_cycles().is_empty());
+    }
+
+    #[test]
+    fn test_split_creating_cycle() {
+        let metrics = project(&[
+            ("core", "core/src/net.rs", &["crate::util::retry"]),
+            ("core", "core/src/util.rs", &["crate::net::Client"]),
+        ]);
+        let simulation =
+            SplitSimulation::simulate(&metrics, "core", &["net".into()], "core-net").unwrap();
+        assert!(simulation.before.cycles.is_empty());
+        assert_eq!(simulation.new_cycles().len(), 1);
+        assert_eq!(
+            simulation.back_references,
+            vec![("core::net".to_string(), "core::util".to_string(), 1)]
+        );
+
+        let mut out = Vec::new();
+        generate_split_report(&simulation, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("Cycle: core <-> core-net"));
+        assert!(text.contains("  core::net -> core::util (1)\n"));
+
+        assert!(matches!(
+            SplitSimulation::simulate(&metrics, "web", &["net".into()], "x"),
+            Err(SplitError::UnknownCrate { .. })
+        ));
+        assert!(matches!(
+            SplitSimulation::simulate(&metrics, "core", &["db".into()], "x"),
+            Err(SplitError::NoModules { .. })
+        ));
+    }
+}
@@ -0,0 +1,396 @@
+//! Table and column names in embedded SQL
+//!
+//! A light scanner, not a parser: it recognizes `SELECT`, `INSERT`,
+//! `UPDATE`, `DELETE`, `WITH`, `CREATE TABLE` and `ALTER TABLE`
+//! statements and reads the names a schema change would have to find:
+//!
+//! - Tables after `FROM`, `JOIN`, `INTO`, `UPDATE` and `TABLE`, with their
+//!   aliases (`FROM users u`)
+//! - Columns of the `SELECT` list, the `INSERT` column list, the `CREATE
+//!   TABLE` definitions, `ADD COLUMN`, and every name compared or assigned
+//!   (`SET name = ?`, `WHERE u.id = $1`, `email LIKE ?`)
+//!
+//! Qualified columns go to the table their qualifier names, the others to
+//! the statement's first table. The leading keyword must be all upper or
+//! all lower case, so messages such as "Delete from cache failed" are not
+//! taken for SQL.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Words that are never table or column names
+const KEYWORDS: &[&str] = &[
+    "ADD",
+    "ALL",
+    "ALTER",
+    "AND",
+    "AS",
+    "ASC",
+    "BETWEEN",
+    "BY",
+    "CASE",
+    "CHECK",
+    "COLUMN",
+    "CONFLICT",
+    "CONSTRAINT",
+    "COUNT",
+    "CREATE",
+    "CROSS",
+    "DEFAULT",
+    "DELETE",
+    "DESC",
+    "DISTINCT",
+    "DO",
+    "DROP",
+    "ELSE",
+    "END",
+    "EXISTS",
+    "FOREIGN",
+    "FROM",
+    "FULL",
+    "GROUP",
+    "HAVING",
+    "IF",
+    "IN",
+    "INDEX",
+    "INNER",
+    "INSERT",
+    "INTO",
+    "IS",
+    "JOIN",
+    "KEY",
+    "LEFT",
+    "LIKE",
+    "LIMIT",
+    "NOT",
+    "NOTHING",
+    "NULL",
+    "OFFSET",
+    "ON",
+    "ONLY",
+    "OR",
+    "ORDER",
+    "OUTER",
+    "PRIMARY",
+    "REFERENCES",
+    "RETURNING",
+    "RIGHT",
+    "SELECT",
+    "SET",
+    "TABLE",
+    "THEN",
+    "UNION",
+    "UNIQUE",
+    "UPDATE",
+    "USING",
+    "VALUES",
+    "WHEN",
+    "WHERE",
+    "WITH",
+];
+
+/// Statements the scanner reads
+const STATEMENTS: &[&str] = &[
+    "SELECT", "INSERT", "UPDATE", "DELETE", "WITH", "CREATE", "ALTER",
+];
+
+/// Keywords followed by a table name
+const TABLE_KEYWORDS: &[&str] = &["FROM", "JOIN", "INTO", "UPDATE", "TABLE"];
+
+/// Keywords comparing a column with a value
+const COMPARISONS: &[&str] = &["IN", "IS", "LIKE", "BETWEEN"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    /// Name or keyword, possibly qualified (`u.email`)
+    Word(String),
+    /// Punctuation and operators
+    Punct(char),
+}
+
+impl Token {
+    fn keyword(&self) -> Option<String> {
+        match self {
+            Token::Word(word) => {
+                let upper = word.to_ascii_uppercase();
+                KEYWORDS.contains(&upper.as_str()).then_some(upper)
+            }
+            Token::Punct(_) => None,
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        self.keyword().as_deref() == Some(keyword)
+    }
+
+    /// A table or column name
+    fn name(&self) -> Option<&str> {
+        match self {
+            Token::Word(word) if self.keyword().is_none() => Some(word),
+            _ => None,
+        }
+    }
+}
+
+/// Tables an SQL string references, with the columns it names on each
+///
+/// Returns nothing when the text is not a recognized statement.
+pub fn sql_tables(text: &str) -> BTreeMap<String, BTreeSet<String>> {
+    let mut tables: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    // Most string literals are not SQL; check before tokenizing them
+    let first = text
+        .trim_start()
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .next()
+        .unwrap_or_default();
+    let uniform_case = first == first.to_ascii_uppercase() || first == first.to_ascii_lowercase();
+    if !uniform_case || !STATEMENTS.contains(&first.to_ascii_uppercase().as_str()) {
+        return tables;
+    }
+    let tokens = tokenize(text);
+
+    // Tables and aliases
+    let mut aliases: BTreeMap<String, String> = BTreeMap::new();
+    let mut main_table = None;
+    for (i, token) in tokens.iter().enumerate() {
+        if !TABLE_KEYWORDS.iter().any(|k| token.is_keyword(k)) {
+            continue;
+        }
+        let mut j = i + 1;
+        while tokens.get(j).is_some_and(|t| {
+            t.is_keyword("IF")
+                || t.is_keyword("NOT")
+                || t.is_keyword("EXISTS")
+                || t.is_keyword("ONLY")
+        }) {
+            j += 1;
+        }
+        let Some(table) = tokens.get(j).and_then(Token::name) else {
+            continue;
+        };
+        let table = unqualified(table).to_string();
+        tables.entry(table.clone()).or_default();
+        main_table.get_or_insert_with(|| table.clone());
+        let alias_at = if tokens.get(j + 1).is_some_and(|t| t.is_keyword("AS")) {
+            j + 2
+        } else {
+            j + 1
+        };
+        if let Some(alias) = tokens.get(alias_at).and_then(Token::name) {
+            aliases.insert(alias.to_string(), table.clone());
+        }
+        aliases.insert(table.clone(), table);
+    }
+    let Some(main_table) = main_table else {
+        return tables;
+    };
+
+    let mut columns: Vec<&str> = Vec::new();
+    columns.extend(select_list(&tokens));
+    columns.extend(parenthesized_columns(&tokens));
+    for (i, token) in tokens.iter().enumerate() {
+        let Some(name) = token.name() else {
+            continue;
+        };
+        let is_comparison =
+            |t: Option<&Token>| t.is_some_and(|t| COMPARISONS.iter().any(|k| t.is_keyword(k)));
+        let compared = match tokens.get(i + 1) {
+            Some(Token::Punct(c)) => matches!(c, '=' | '<' | '>' | '!'),
+            // `email NOT LIKE ?`, but not `email TEXT NOT NULL`
+            Some(next) if next.is_keyword("NOT") => is_comparison(tokens.get(i + 2)),
+            next => is_comparison(next),
+        };
+        let added = i > 0 && tokens[i - 1].is_keyword("COLUMN");
+        if compared || added {
+            columns.push(name);
+        }
+    }
+
+    for column in columns {
+        let (table, column) = match column.rsplit_once('.') {
+            Some((qualifier, column)) => match aliases.get(unqualified(qualifier)) {
+                Some(table) => (table.clone(), column),
+                None => continue,
+            },
+            None => (main_table.clone(), column),
+        };
+        // Table names compared (`FROM a WHERE a = ..`) are no columns
+        if !aliases.contains_key(column) {
+            tables.entry(table).or_default().insert(column.to_string());
+        }
+    }
+    tables
+}
+
+/// Last segment of a qualified name (`public.users` -> `users`)
+fn unqualified(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+/// Plain columns of a `SELECT` list (`id, u.email AS mail`); expressions
+/// and `*` are skipped
+fn select_list(tokens: &[Token]) -> Vec<&str> {
+    let Some(start) = tokens.iter().position(|t| t.is_keyword("SELECT")) else {
+        return Vec::new();
+    };
+    let mut columns = Vec::new();
+    let mut item: Vec<&Token> = Vec::new();
+    for token in tokens[start + 1..].iter().chain([&Token::Punct(',')]) {
+        if token.is_keyword("FROM") || *token == Token::Punct(',') {
+            let item = std::mem::take(&mut item);
+            let name = match item.as_slice() {
+                [column] | [column, _] => column.name(),
+                [column, alias_keyword, _] if alias_keyword.is_keyword("AS") => column.name(),
+                _ => None,
+            };
+            columns.extend(name);
+            if token.is_keyword("FROM") {
+                break;
+            }
+        } else if !token.is_keyword("DISTINCT") {
+            item.push(token);
+        }
+    }
+    columns
+}
+
+/// Columns listed in parentheses right after a table name: the `INSERT`
+/// column list and `CREATE TABLE` definitions (first word of each)
+fn parenthesized_columns(tokens: &[Token]) -> Vec<&str> {
+    let mut columns = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        if !(token.is_keyword("INTO") || token.is_keyword("TABLE")) {
+            continue;
+        }
+        let Some(open) = tokens[i + 1..]
+            .iter()
+            .take(5)
+            .position(|t| *t == Token::Punct('('))
+        else {
+            continue;
+        };
+        let mut depth = 0;
+        let mut item_start = true;
+        for token in &tokens[i + 1 + open..] {
+            match token {
+                Token::Punct('(') => depth += 1,
+                Token::Punct(')') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                Token::Punct(',') if depth == 1 => {
+                    item_start = true;
+                    continue;
+                }
+                _ => {}
+            }
+            if item_start && depth == 1 {
+                columns.extend(token.name());
+                item_start = matches!(token, Token::Punct('('));
+            }
+        }
+    }
+    columns
+}
+
+/// Words and punctuation; string literals, numbers and placeholders (`?`,
+/// `$1`, `:name`) are dropped, quoted identifiers kept
+fn tokenize(text: &str) -> Vec<Token> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || matches!(chars[i], '_' | '.')) {
+                i += 1;
+            }
+            tokens.push(Token::Word(chars[start..i].iter().collect()));
+            continue;
+        }
+        match c {
+            '\'' => {
+                i += 1;
+                while i < chars.len() && chars[i] != '\'' {
+                    i += 1;
+                }
+            }
+            '"' | '`' => {
+                let start = i + 1;
+                i += 1;
+                while i < chars.len() && chars[i] != c {
+                    i += 1;
+                }
+                tokens.push(Token::Word(
+                    chars[start..i.min(chars.len())].iter().collect(),
+                ));
+            }
+            '$' | ':' | '?' | '@' => {
+                while i + 1 < chars.len() && (chars[i + 1].is_alphanumeric() || chars[i + 1] == '_')
+                {
+                    i += 1;
+                }
+            }
+            c if c.is_ascii_digit() || c.is_whitespace() => {}
+            c => tokens.push(Token::Punct(c)),
+        }
+        i += 1;
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tables(text: &str) -> Vec<(String, Vec<String>)> {
+        sql_tables(text)
+            .into_iter()
+            .map(|(table, columns)| (table, columns.into_iter().collect()))
+            .collect()
+    }
+
+    fn owned(expected: &[(&str, &[&str])]) -> Vec<(String, Vec<String>)> {
+        expected
+            .iter()
+            .map(|(t, cols)| (t.to_string(), cols.iter().map(|c| c.to_string()).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn test_sql_tables() {
+        assert_eq!(
+            tables(
+                "SELECT u.id, u.email AS mail, count(*) FROM users u \
+                 JOIN orders o ON o.user_id = u.id WHERE o.status = 'paid' AND deleted IS NULL"
+            ),
+            owned(&[
+                ("orders", &["status", "user_id"]),
+                ("users", &["deleted", "email", "id"])
+            ])
+        );
+        assert_eq!(
+            tables("insert into users (id, email) values ($1, $2)"),
+            owned(&[("users", &["email", "id"])])
+        );
+        assert_eq!(
+            tables("UPDATE users SET email = ?, name = :name WHERE id = ?"),
+            owned(&[("users", &["email", "id", "name"])])
+        );
+        assert_eq!(
+            tables(
+                "CREATE TABLE IF NOT EXISTS \"users\" (id INTEGER PRIMARY KEY, email TEXT NOT NULL)"
+            ),
+            owned(&[("users", &["email", "id"])])
+        );
+        assert_eq!(
+            tables("ALTER TABLE users ADD COLUMN age INT"),
+            owned(&[("users", &["age"])])
+        );
+        // Prose, not SQL
+        assert!(tables("Delete from cache failed").is_empty());
+        assert!(tables("select a widget").is_empty());
+    }
+}
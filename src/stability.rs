@@ -0,0 +1,667 @@
+//! Crate-level stability metrics
+//!
+//! Computes Robert C. Martin's package metrics for every workspace member:
+//!
+//! - **Ca** (afferent coupling): number of crates that depend on this crate
+//! - **Ce** (efferent coupling): number of crates this crate depends on
+//! - **I** (instability): `Ce / (Ce + Ca)`, from 0.0 (stable) to 1.0 (unstable)
+//!
+//! - **A** (abstractness): abstract types (traits, trait object aliases)
+//!   divided by all types defined in the crate
+//! - **D** (distance from the main sequence): `|A + I - 1|`
+//!
+//! A crate that many others depend on should be stable; the
+//! *Stable Dependencies Principle* says dependencies should point in the
+//! direction of stability. Dev-dependencies are ignored because they do not
+//! affect downstream crates.
+//!
+//! The *Stable Abstractions Principle* adds that a crate should be as
+//! abstract as it is stable. A crate that several crates depend on, that
+//! depends on little itself and that defines almost no traits forces its
+//! dependents onto concrete types; the concrete types its dependents use
+//! most are the ones to put behind traits first.
+//!
+//! Crates far from the main sequence (`A + I = 1`) fall into one of two zones:
+//! the *zone of pain* (stable and concrete, so hard to change) or the
+//! *zone of uselessness* (abstract but with nobody depending on it).
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{self, Write};
+
+use serde::Deserialize;
+
+use crate::workspace::WorkspaceInfo;
+
+/// Number of concrete types suggested for extraction into traits
+pub const EXTRACT_CANDIDATE_LIMIT: usize = 3;
+
+/// Stability metrics for a single workspace member
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemberStability {
+    /// Crate name
+    pub name: String,
+    /// Crates depending on this crate (sorted)
+    pub dependents: Vec<String>,
+    /// Crates this crate depends on (sorted)
+    pub dependencies: Vec<String>,
+    /// Number of traits and trait object aliases defined in the crate
+    pub abstract_types: usize,
+    /// Number of structs and enums defined in the crate
+    pub concrete_types: usize,
+    /// Structs and enums of this crate referenced from other workspace
+    /// crates, with the number of references
+    pub type_uses: BTreeMap<String, usize>,
+}
+
+impl MemberStability {
+    /// Afferent coupling (Ca)
+    pub fn afferent(&self) -> usize {
+        self.dependents.len()
+    }
+
+    /// Efferent coupling (Ce)
+    pub fn efferent(&self) -> usize {
+        self.dependencies.len()
+    }
+
+    /// Instability I = Ce / (Ce + Ca); an isolated crate is treated as stable
+    pub fn instability(&self) -> f64 {
+        let total = self.afferent() + self.efferent();
+        if total == 0 {
+            0.0
+        } else {
+            self.efferent() as f64 / total as f64
+        }
+    }
+
+    /// Abstractness A = abstract types / all types
+    pub fn abstractness(&self) -> f64 {
+        let total = self.abstract_types + self.concrete_types;
+        if total == 0 {
+            0.0
+        } else {
+            self.abstract_types as f64 / total as f64
+        }
+    }
+
+    /// Distance from the main sequence D = |A + I - 1|
+    pub fn distance(&self) -> f64 {
+        (self.abstractness() + self.instability() - 1.0).abs()
+    }
+
+    /// Concrete types most referenced by other crates, most used first
+    pub fn extract_candidates(&self) -> Vec<String> {
+        let mut uses: Vec<(&String, &usize)> = self.type_uses.iter().collect();
+        uses.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        uses.into_iter()
+            .take(EXTRACT_CANDIDATE_LIMIT)
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// Zone the crate falls into, if its distance exceeds `max_distance`.
+    /// Crates that define no types are never classified.
+    pub fn zone(&self, max_distance: f64) -> Option<Zone> {
+        if self.abstract_types + self.concrete_types == 0 || self.distance() <= max_distance {
+            return None;
+        }
+        if self.abstractness() + self.instability() < 1.0 {
+            Some(Zone::Pain)
+        } else {
+            Some(Zone::Uselessness)
+        }
+    }
+}
+
+/// Region far from the main sequence
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Zone {
+    /// Stable and concrete: many dependents, hard to extend
+    Pain,
+    /// Abstract and unstable: abstractions nobody uses
+    Uselessness,
+}
+
+impl std::fmt::Display for Zone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Zone::Pain => write!(f, "Zone of Pain"),
+            Zone::Uselessness => write!(f, "Zone of Uselessness"),
+        }
+    }
+}
+
+/// Thresholds for stability findings (the `[stability]` config section)
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct StabilityThresholds {
+    /// Maximum instability for a crate that other crates depend on
+    pub max_instability: f64,
+    /// Minimum number of dependents before `max_instability` applies
+    pub min_dependents: usize,
+    /// Maximum distance from the main sequence before a crate is flagged
+    pub max_distance: f64,
+    /// Minimum abstractness for a stable crate with `min_dependents`
+    /// dependents
+    pub min_abstractness: f64,
+    /// Instability below which a crate counts as stable for
+    /// `min_abstractness`
+    pub max_stable_instability: f64,
+}
+
+impl Default for StabilityThresholds {
+    fn default() -> Self {
+        Self {
+            max_instability: 0.8,
+            min_dependents: 2,
+            max_distance: 0.7,
+            min_abstractness: 0.2,
+            max_stable_instability: 0.5,
+        }
+    }
+}
+
+/// A problem found in the crate dependency structure
+#[derive(Debug, Clone, PartialEq)]
+pub enum StabilityFinding {
+    /// A crate with many dependents is itself highly unstable
+    UnstableDependency {
+        name: String,
+        instability: f64,
+        dependents: usize,
+    },
+    /// A crate depends on a workspace crate that is less stable than itself
+    /// (violates the Stable Dependencies Principle)
+    SdpViolation {
+        from: String,
+        to: String,
+        from_instability: f64,
+        to_instability: f64,
+    },
+    /// A stable crate with many dependents defines almost no abstractions
+    /// (violates the Stable Abstractions Principle)
+    SapViolation {
+        name: String,
+        instability: f64,
+        abstractness: f64,
+        dependents: usize,
+        /// Concrete types to extract into traits first
+        extract: Vec<String>,
+    },
+    /// A crate is far from the main sequence
+    OffMainSequence {
+        name: String,
+        zone: Zone,
+        abstractness: f64,
+        instability: f64,
+        distance: f64,
+    },
+}
+
+impl std::fmt::Display for StabilityFinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StabilityFinding::UnstableDependency {
+                name,
+                instability,
+                dependents,
+            } => write!(
+                f,
+                "{} has {} dependents but instability {:.2}",
+                name, dependents, instability
+            ),
+            StabilityFinding::SdpViolation {
+                from,
+                to,
+                from_instability,
+                to_instability,
+            } => write!(
+                f,
+                "{} (I={:.2}) depends on less stable {} (I={:.2})",
+                from, from_instability, to, to_instability
+            ),
+            StabilityFinding::SapViolation {
+                name,
+                instability,
+                abstractness,
+                dependents,
+                extract,
+            } => {
+                write!(
+                    f,
+                    "{} is stable (I={:.2}) with {} dependents but concrete (A={:.2})",
+                    name, instability, dependents, abstractness
+                )?;
+                if extract.is_empty() {
+                    write!(f, "; introduce traits for its public API")
+                } else {
+                    write!(f, "; extract traits for {}", extract.join(", "))
+                }
+            }
+            StabilityFinding::OffMainSequence {
+                name,
+                zone,
+                abstractness,
+                instability,
+                distance,
+            } => write!(
+                f,
+                "{} is in the {} (A={:.2}, I={:.2}, D={:.2})",
+                name, zone, abstractness, instability, distance
+            ),
+        }
+    }
+}
+
+/// Compute stability metrics for every workspace member, sorted by name.
+/// Type counts are left at zero; the analyzer fills them in per crate.
+pub fn compute_crate_stability(workspace: &WorkspaceInfo) -> Vec<MemberStability> {
+    // Only normal/build dependencies propagate to dependents
+    let runtime_deps = |name: &str| -> HashSet<&str> {
+        workspace
+            .get_crate(name)
+            .map(|info| info.dependencies.iter().map(String::as_str).collect())
+            .unwrap_or_default()
+    };
+
+    let mut result: Vec<MemberStability> = workspace
+        .members
+        .iter()
+        .map(|name| {
+            let own_deps = runtime_deps(name);
+            let mut dependencies: Vec<String> = workspace
+                .get_dependencies(name)
+                .into_iter()
+                .flatten()
+                .filter(|dep| own_deps.contains(dep.as_str()))
+                .cloned()
+                .collect();
+            dependencies.sort();
+
+            let mut dependents: Vec<String> = workspace
+                .get_dependents(name)
+                .into_iter()
+                .flatten()
+                .filter(|dependent| runtime_deps(dependent).contains(name.as_str()))
+                .cloned()
+                .collect();
+            dependents.sort();
+
+            MemberStability {
+                name: name.clone(),
+                dependents,
+                dependencies,
+                abstract_types: 0,
+                concrete_types: 0,
+                type_uses: BTreeMap::new(),
+            }
+        })
+        .collect();
+
+    result.sort_by(|a, b| a.name.cmp(&b.name));
+    result
+}
+
+/// Find stability problems among workspace crates
+pub fn find_stability_issues(
+    crates: &[MemberStability],
+    thresholds: &StabilityThresholds,
+) -> Vec<StabilityFinding> {
+    let instability: HashMap<&str, f64> = crates
+        .iter()
+        .map(|c| (c.name.as_str(), c.instability()))
+        .collect();
+
+    let mut findings = Vec::new();
+    for krate in crates {
+        let i = krate.instability();
+        if krate.afferent() >= thresholds.min_dependents && i > thresholds.max_instability {
+            findings.push(StabilityFinding::UnstableDependency {
+                name: krate.name.clone(),
+                instability: i,
+                dependents: krate.afferent(),
+            });
+        }
+
+        if krate.afferent() >= thresholds.min_dependents
+            && i < thresholds.max_stable_instability
+            && krate.concrete_types > 0
+            && krate.abstractness() < thresholds.min_abstractness
+        {
+            findings.push(StabilityFinding::SapViolation {
+                name: krate.name.clone(),
+                instability: i,
+                abstractness: krate.abstractness(),
+                dependents: krate.afferent(),
+                extract: krate.extract_candidates(),
+            });
+        }
+
+        if let Some(zone) = krate.zone(thresholds.max_distance) {
+            findings.push(StabilityFinding::OffMainSequence {
+                name: krate.name.clone(),
+                zone,
+                abstractness: krate.abstractness(),
+                instability: i,
+                distance: krate.distance(),
+            });
+        }
+
+        for dep in &krate.dependencies {
+            if let Some(&dep_i) = instability.get(dep.as_str())
+                && dep_i > i
+            {
+                findings.push(StabilityFinding::SdpViolation {
+                    from: krate.name.clone(),
+                    to: dep.clone(),
+                    from_instability: i,
+                    to_instability: dep_i,
+                });
+            }
+        }
+    }
+    findings
+}
+
+/// Generate the crate stability report to writer
+pub fn generate_stability_report<W: Write>(
+    crates: &[MemberStability],
+    thresholds: &StabilityThresholds,
+    writer: &mut W,
+) -> io::Result<()> {
+    writeln!(writer, "Crate Stability (Martin metrics)")?;
+    writeln!(writer, "================================")?;
+    writeln!(writer)?;
+
+    if crates.is_empty() {
+        writeln!(
+            writer,
+            "No workspace information available (cargo metadata required)."
+        )?;
+        return Ok(());
+    }
+
+    let width = crates
+        .iter()
+        .map(|c| c.name.len())
+        .max()
+        .unwrap_or(0)
+        .max("Crate".len());
+    writeln!(
+        writer,
+        "{:<width$}  {:>4}  {:>4}  {:>5}  {:>5}  {:>5}",
+        "Crate", "Ca", "Ce", "I", "A", "D"
+    )?;
+    writeln!(writer, "{}", "-".repeat(width + 33))?;
+    for krate in crates {
+        writeln!(
+            writer,
+            "{:<width$}  {:>4}  {:>4}  {:>5.2}  {:>5.2}  {:>5.2}",
+            krate.name,
+            krate.afferent(),
+            krate.efferent(),
+            krate.instability(),
+            krate.abstractness(),
+            krate.distance()
+        )?;
+    }
+    writeln!(writer)?;
+
+    // Main sequence section
+    writeln!(writer, "Main Sequence (D = |A + I - 1|)")?;
+    writeln!(writer, "-------------------------------")?;
+    let mut off_sequence = false;
+    for krate in crates {
+        if let Some(zone) = krate.zone(thresholds.max_distance) {
+            off_sequence = true;
+            let hint = match zone {
+                Zone::Pain => "stable and concrete; extract traits or reduce dependents",
+                Zone::Uselessness => "abstract with few dependents; inline or remove unused traits",
+            };
+            writeln!(
+                writer,
+                "  {} {}: D={:.2} ({})",
+                krate.name,
+                zone,
+                krate.distance(),
+                hint
+            )?;
+        }
+    }
+    if !off_sequence {
+        writeln!(
+            writer,
+            "  All crates within D <= {:.2} of the main sequence.",
+            thresholds.max_distance
+        )?;
+    }
+    writeln!(writer)?;
+
+    let findings = find_stability_issues(crates, thresholds);
+    if findings.is_empty() {
+        writeln!(writer, "No stability issues found.")?;
+    } else {
+        writeln!(writer, "Issues ({}):", findings.len())?;
+        for finding in &findings {
+            writeln!(writer, "  - {}", finding)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workspace::CrateInfo;
+    use std::collections::BTreeSet;
+    use std::path::PathBuf;
+
+    fn workspace(edges: &[(&str, &str)], dev_edges: &[(&str, &str)]) -> WorkspaceInfo {
+        let mut crates: BTreeMap<String, CrateInfo> = BTreeMap::new();
+        let mut dependency_graph: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        let mut reverse_deps: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        let mut members = Vec::new();
+
+        for (from, to) in edges.iter().chain(dev_edges) {
+            for name in [from, to] {
+                if !members.contains(&name.to_string()) {
+                    members.push(name.to_string());
+                }
+                crates.entry(name.to_string()).or_insert_with(|| CrateInfo {
+                    name: name.to_string(),
+                    id: cargo_metadata::PackageId {
+                        repr: name.to_string(),
+                    },
+                    src_path: PathBuf::new(),
+                    manifest_path: PathBuf::new(),
+                    dependencies: Vec::new(),
+                    dev_dependencies: Vec::new(),
+                    build_dependencies: Vec::new(),
+                    target_dependencies: Vec::new(),
+                    inactive_dependencies: Vec::new(),
+                    resolved_dependencies: HashMap::new(),
+                    is_workspace_member: true,
+                    edition: Default::default(),
+                    is_proc_macro: false,
+                });
+            }
+            dependency_graph
+                .entry(from.to_string())
+                .or_default()
+                .insert(to.to_string());
+            reverse_deps
+                .entry(to.to_string())
+                .or_default()
+                .insert(from.to_string());
+        }
+        for (from, to) in edges {
+            crates
+                .get_mut(*from)
+                .unwrap()
+                .dependencies
+                .push(to.to_string());
+        }
+        for (from, to) in dev_edges {
+            crates
+                .get_mut(*from)
+                .unwrap()
+                .dev_dependencies
+                .push(to.to_string());
+        }
+
+        WorkspaceInfo {
+            root: PathBuf::new(),
+            target_directory: PathBuf::new(),
+            crates,
+            default_members: members.clone(),
+            members,
+            dependency_graph,
+            reverse_deps,
+        }
+    }
+
+    #[test]
+    fn test_instability() {
+        let ws = workspace(&[("app", "core"), ("cli", "core"), ("core", "util")], &[]);
+        let crates = compute_crate_stability(&ws);
+        let core = crates.iter().find(|c| c.name == "core").unwrap();
+        assert_eq!((core.afferent(), core.efferent()), (2, 1));
+        assert!((core.instability() - 1.0 / 3.0).abs() < 1e-9);
+
+        let app = crates.iter().find(|c| c.name == "app").unwrap();
+        assert_eq!(app.instability(), 1.0);
+        let util = crates.iter().find(|c| c.name == "util").unwrap();
+        assert_eq!(util.instability(), 0.0);
+    }
+
+    #[test]
+    fn test_dev_dependencies_are_ignored() {
+        let ws = workspace(&[("app", "core")], &[("core", "testkit")]);
+        let crates = compute_crate_stability(&ws);
+        let core = crates.iter().find(|c| c.name == "core").unwrap();
+        assert_eq!(core.efferent(), 0);
+        let testkit = crates.iter().find(|c| c.name == "testkit").unwrap();
+        assert_eq!(testkit.afferent(), 0);
+    }
+
+    #[test]
+    fn test_abstractness_and_zones() {
+        let mut krate = MemberStability {
+            name: "core".to_string(),
+            dependents: vec!["a".into(), "b".into(), "c".into()],
+            dependencies: Vec::new(),
+            abstract_types: 0,
+            concrete_types: 4,
+            type_uses: BTreeMap::new(),
+        };
+        // Stable (I=0) and concrete (A=0): D=1, zone of pain
+        assert_eq!(krate.distance(), 1.0);
+        assert_eq!(krate.zone(0.7), Some(Zone::Pain));
+
+        // Abstract (A=1) and unstable (I=1): zone of uselessness
+        krate.dependents.clear();
+        krate.dependencies = vec!["x".into()];
+        krate.abstract_types = 4;
+        krate.concrete_types = 0;
+        assert_eq!(krate.zone(0.7), Some(Zone::Uselessness));
+
+        // On the main sequence (A=0.5, I=0.5)
+        krate.dependents = vec!["a".into()];
+        krate.abstract_types = 2;
+        krate.concrete_types = 2;
+        assert_eq!(krate.distance(), 0.0);
+        assert_eq!(krate.zone(0.7), None);
+
+        // No types: never classified
+        krate.abstract_types = 0;
+        krate.concrete_types = 0;
+        krate.dependencies.clear();
+        assert_eq!(krate.zone(0.0), None);
+    }
+
+    #[test]
+    fn test_stability_findings() {
+        // core is depended on by two crates but itself depends on four (I=0.67)
+        let ws = workspace(
+            &[
+                ("app", "core"),
+                ("cli", "core"),
+                ("core", "a"),
+                ("core", "b"),
+                ("core", "c"),
+                ("core", "d"),
+                ("util", "app"),
+            ],
+            &[],
+        );
+        let crates = compute_crate_stability(&ws);
+        let thresholds = StabilityThresholds {
+            max_instability: 0.5,
+            ..Default::default()
+        };
+        let findings = find_stability_issues(&crates, &thresholds);
+
+        assert!(findings.iter().any(|f| matches!(
+            f,
+            StabilityFinding::UnstableDependency { name, dependents: 2, .. } if name == "core"
+        )));
+        // util (I=1.0) -> app (I=0.5) is fine; app (I=0.5) -> core (I=0.67) is not
+        assert!(findings.iter().any(|f| matches!(
+            f,
+            StabilityFinding::SdpViolation { from, to, .. } if from == "app" && to == "core"
+        )));
+        assert!(!findings.iter().any(|f| matches!(
+            f,
+            StabilityFinding::SdpViolation { from, .. } if from == "util"
+        )));
+    }
+
+    #[test]
+    fn test_sap_violation() {
+        let mut krate = MemberStability {
+            name: "core".to_string(),
+            dependents: vec!["app".into(), "cli".into()],
+            dependencies: vec!["util".into()],
+            abstract_types: 0,
+            concrete_types: 6,
+            type_uses: [("Engine", 7), ("Config", 3), ("Error", 3), ("Id", 1)]
+                .into_iter()
+                .map(|(name, uses)| (name.to_string(), uses))
+                .collect(),
+        };
+        let findings = find_stability_issues(
+            std::slice::from_ref(&krate),
+            &StabilityThresholds::default(),
+        );
+        let sap = findings.iter().find_map(|f| match f {
+            StabilityFinding::SapViolation { extract, .. } => Some(extract.clone()),
+            _ => None,
+        });
+        assert_eq!(
+            sap,
+            Some(vec!["Engine".into(), "Config".into(), "Error".into()])
+        );
+
+        // With I = 0.33, core is not stable enough under a stricter cutoff
+        let strict = StabilityThresholds {
+            max_stable_instability: 0.3,
+            ..Default::default()
+        };
+        let findings = find_stability_issues(std::slice::from_ref(&krate), &strict);
+        assert!(
+            !findings
+                .iter()
+                .any(|f| matches!(f, StabilityFinding::SapViolation { .. }))
+        );
+
+        // Two traits for six concrete types is abstract enough
+        krate.abstract_types = 2;
+        let findings = find_stability_issues(&[krate], &StabilityThresholds::default());
+        assert!(
+            !findings
+                .iter()
+                .any(|f| matches!(f, StabilityFinding::SapViolation { .. }))
+        );
+    }
+}
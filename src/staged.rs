@@ -0,0 +1,434 @@
+//! Pre-commit mode: check only what is about to be committed
+//!
+//! `--check --staged` looks at the files in the git index instead of the
+//! working tree. The index is written to a temporary directory with
+//! `git checkout-index`, so unstaged edits never leak into the result.
+//!
+//! Only the staged Rust files and the files that may depend on them are
+//! parsed: a file that never mentions the name of a staged module (or lies
+//! below its directory) is emptied in the export first. Findings located in
+//! the staged files, or in modules that directly depend on one of them, are
+//! reported if they are new compared to `HEAD` (matched like baseline
+//! entries: by rule, source and target). `HEAD` is not exported again: the
+//! staged and deleted files are put back to their `HEAD` contents in the
+//! same export and the same files analyzed a second time.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::analyzer::{AnalysisScope, analyze_workspace_scoped};
+use crate::balance::{CouplingIssue, IssueThresholds, analyze_project_balance_with_thresholds};
+use crate::baseline::Baseline;
+use crate::diff::{DiffError, git};
+use crate::metrics::{Distance, ProjectMetrics};
+use crate::paths::rs_files;
+use crate::rules::RuleLevel;
+use crate::temporal::{TemporalRules, reanalyze_project};
+
+/// Result of checking the staged changes
+#[derive(Debug, Clone, Default)]
+pub struct StagedCheck {
+    /// Staged Rust files, relative to the repository root
+    pub staged: Vec<PathBuf>,
+    /// Staged files plus their direct dependents, relative to the repository root
+    pub checked: Vec<PathBuf>,
+    /// Findings in the checked files that are not present at `HEAD`
+    pub new_issues: Vec<CouplingIssue>,
+    /// New findings of rules set to `error` in `[rules.severity]`
+    pub failing: usize,
+}
+
+impl StagedCheck {
+    /// Exit code for a pre-commit hook (0 = commit may proceed)
+    pub fn exit_code(&self) -> i32 {
+        i32::from(self.failing > 0)
+    }
+}
+
+/// Files of the modules in `changed` and of every module directly depending
+/// on one of them through a coupling inside the workspace
+pub fn files_with_dependents(
+    metrics: &ProjectMetrics,
+    changed: &BTreeSet<PathBuf>,
+) -> BTreeSet<PathBuf> {
+    let mut files = changed.clone();
+    for coupling in &metrics.couplings {
+        if coupling.distance == Distance::DifferentCrate {
+            continue;
+        }
+        let (Some(source), Some(target)) = (
+            metrics.find_module(&coupling.source),
+            metrics.find_module(&coupling.target),
+        ) else {
+            continue;
+        };
+        if changed.contains(&target.path) {
+            files.insert(source.path.clone());
+        }
+    }
+    files
+}
+
+/// Check the staged version of `path` (inside a git repository) against `HEAD`
+pub fn check_staged(
+    path: &Path,
+    scope: &AnalysisScope,
+    rules: Option<&TemporalRules>,
+    thresholds: &IssueThresholds,
+) -> Result<StagedCheck, DiffError> {
+    let dir = if path.is_dir() {
+        path
+    } else {
+        path.parent().unwrap_or(Path::new("."))
+    };
+    let root = PathBuf::from(git(dir, &["rev-parse", "--show-toplevel"])?);
+    let prefix = git(dir, &["rev-parse", "--show-prefix"])?;
+
+    let staged = staged_files(&root, "ACM")?;
+    if staged.is_empty() {
+        return Ok(StagedCheck::default());
+    }
+    let deleted = staged_files(&root, "D")?;
+
+    // The trailing slash makes checkout-index write into the directory
+    let checkout = tempfile::tempdir()?;
+    let snapshot = checkout.path().canonicalize()?;
+    let target = format!("--prefix={}/", snapshot.display());
+    git(&root, &["checkout-index", "--all", &target])?;
+    keep_possible_dependents(&snapshot, &staged)?;
+
+    let analyze = |revision: &str| {
+        let mut metrics = analyze_workspace_scoped(&snapshot.join(&prefix), scope)
+            .map_err(|e| DiffError::Analysis(revision.to_string(), e))?;
+        if let Some(rules) = rules {
+            reanalyze_project(&mut metrics, rules, scope.include_tests);
+        }
+        Ok::<_, DiffError>(metrics)
+    };
+    let metrics = analyze("index")?;
+
+    let relative = |file: &Path| {
+        file.strip_prefix(&snapshot)
+            .map_or_else(|_| file.to_path_buf(), Path::to_path_buf)
+    };
+    let changed: BTreeSet<PathBuf> = metrics
+        .modules
+        .values()
+        .map(|m| m.path.clone())
+        .filter(|file| staged.contains(&relative(file)))
+        .collect();
+    let checked = files_with_dependents(&metrics, &changed);
+
+    let mut new_issues = issues_in(&metrics, thresholds, &checked);
+
+    // Without a first commit everything staged is new
+    if !new_issues.is_empty() && git(&root, &["rev-parse", "--verify", "--quiet", "HEAD"]).is_ok() {
+        for file in staged.iter().chain(&deleted) {
+            let dest = snapshot.join(file);
+            match head_blob(&root, file)? {
+                Some(content) => {
+                    if let Some(parent) = dest.parent() {
+                        fs::create_dir_all(parent)?;
+                    }
+                    fs::write(&dest, content)?;
+                }
+                // Added in the index
+                None => fs::remove_file(&dest)?,
+            }
+        }
+        let head = analyze("HEAD")?;
+        Baseline::from_issues(&issues_in(&head, thresholds, &checked), &head)
+            .retain_new(&mut new_issues, &metrics);
+    }
+    for issue in &mut new_issues {
+        if let Some(file) = issue.location.file_path.take() {
+            issue.location.file_path = Some(relative(&file));
+        }
+    }
+
+    let failing = new_issues
+        .iter()
+        .filter(|issue| match thresholds.rules.level(issue.issue_type) {
+            Some(RuleLevel::Error) => true,
+            Some(RuleLevel::Warning | RuleLevel::Info) | None => false,
+        })
+        .count();
+    Ok(StagedCheck {
+        staged,
+        checked: checked.iter().map(|file| relative(file)).collect(),
+        new_issues,
+        failing,
+    })
+}
+
+/// Staged Rust files with a `--diff-filter` status, relative to the
+/// repository root (renames count as a deletion and an addition)
+fn staged_files(root: &Path, filter: &str) -> Result<Vec<PathBuf>, DiffError> {
+    let filter = format!("--diff-filter={}", filter);
+    Ok(git(
+        root,
+        &["diff", "--cached", "--name-only", "--no-renames", &filter],
+    )?
+    .lines()
+    .filter(|line| line.ends_with(".rs"))
+    .map(PathBuf::from)
+    .collect())
+}
+
+/// Findings located in one of `files`
+fn issues_in(
+    metrics: &ProjectMetrics,
+    thresholds: &IssueThresholds,
+    files: &BTreeSet<PathBuf>,
+) -> Vec<CouplingIssue> {
+    analyze_project_balance_with_thresholds(metrics, thresholds)
+        .issues
+        .into_iter()
+        .filter(|issue| issue.file(metrics).is_some_and(|file| files.contains(file)))
+        .collect()
+}
+
+/// Contents of `file` at `HEAD`, or `None` when it does not exist there
+fn head_blob(root: &Path, file: &Path) -> Result<Option<Vec<u8>>, DiffError> {
+    let output = Command::new("git")
+        .args(["cat-file", "blob"])
+        .arg(format!("HEAD:{}", file.display()))
+        .current_dir(root)
+        .output()?;
+    Ok(output.status.success().then_some(output.stdout))
+}
+
+/// Empty every Rust file in `snapshot` that cannot depend on a staged file,
+/// so that only the staged files and their possible dependents are parsed
+///
+/// A possible dependent mentions the name of a staged module or lies below
+/// its directory. A staged crate root is reached through `crate::` or the
+/// crate name from anywhere, so then every file is kept.
+fn keep_possible_dependents(snapshot: &Path, staged: &[PathBuf]) -> io::Result<()> {
+    let mut names = BTreeSet::new();
+    let mut dirs = Vec::new();
+    for file in staged {
+        // The directory holding the module's submodules
+        let dir = match file.file_name().and_then(|name| name.to_str()) {
+            Some("lib.rs" | "main.rs") => return Ok(()),
+            Some("mod.rs") => file.parent().map(Path::to_path_buf),
+            _ => Some(file.with_extension("")),
+        };
+        let Some(dir) = dir else {
+            continue;
+        };
+        if let Some(name) = dir.file_name().and_then(|name| name.to_str()) {
+            names.insert(name.to_string());
+        }
+        dirs.push(snapshot.join(dir));
+    }
+
+    for file in rs_files(snapshot) {
+        let below = dirs.iter().any(|dir| file.starts_with(dir));
+        if below || staged.iter().any(|s| snapshot.join(s) == file) {
+            continue;
+        }
+        let source = fs::read_to_string(&file).unwrap_or_default();
+        if !names.iter().any(|name| mentions(&source, name)) {
+            fs::write(&file, "")?;
+        }
+    }
+    Ok(())
+}
+
+/// Whether `source` contains `word` as a whole identifier
+fn mentions(source: &str, word: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    source.match_indices(word).any(|(i, _)| {
+        !source[..i].chars().next_back().is_some_and(is_ident)
+            && !source[i + word.len()..]
+                .chars()
+                .next()
+                .is_some_and(is_ident)
+    })
+}
+
+/// Generate the staged check report to writer
+pub fn generate_staged_report<W: Write>(check: &StagedCheck, writer: &mut W) -> io::Result<()> {
+    writeln!(writer, "Coupling Check (staged changes)")?;
+    writeln!(writer, "===============================")?;
+    writeln!(writer)?;
+    if check.staged.is_empty() {
+        writeln!(writer, "No staged Rust files.")?;
+        return Ok(());
+    }
+    writeln!(
+        writer,
+        "Files: {} staged, {} checked with direct dependents",
+        check.staged.len(),
+        check.checked.len()
+    )?;
+    writeln!(writer, "New issues: {}", check.new_issues.len())?;
+
+    if !check.new_issues.is_empty() {
+        writeln!(writer)?;
+        for issue in &check.new_issues {
+            let location = match &issue.location.file_path {
+                Some(file) if issue.location.line > 0 => {
+                    format!("{}:{}", file.display(), issue.location.line)
+                }
+                Some(file) => file.display().to_string(),
+                None => issue.source.clone(),
+            };
+            writeln!(
+                writer,
+                "  {} [{}] {}: {}",
+                location, issue.severity, issue.issue_type, issue.description
+            )?;
+        }
+    }
+
+    writeln!(writer)?;
+    writeln!(
+        writer,
+        "Result: {}",
+        if check.exit_code() == 0 {
+            "PASSED"
+        } else {
+            "FAILED"
+        }
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{CouplingMetrics, IntegrationStrength, ModuleMetrics, Volatility};
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args([
+                "-c",
+                "user.name=Alice",
+                "-c",
+                "user.email=alice@example.com",
+            ])
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {:?}", args);
+    }
+
+    #[test]
+    fn test_check_staged_against_head() {
+        use crate::test_fixtures::write;
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "Cargo.toml",
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+        );
+        write(
+            root,
+            "src/lib.rs",
+            "pub mod a;\npub mod b;\npub mod other;\n",
+        );
+        write(root, "src/a.rs", "pub struct A;\n");
+        write(
+            root,
+            "src/b.rs",
+            "use crate::a::A;\n\npub fn make() -> A {\n    A\n}\n",
+        );
+        write(root, "src/other.rs", "pub fn lonely() {}\n");
+        git(root, &["init", "-q"]);
+        git(root, &["add", "-A"]);
+        git(root, &["commit", "-qm", "Initial"]);
+
+        let mut thresholds = IssueThresholds::default();
+        thresholds.rules.severity.insert(
+            "coupling::circular-dependency".to_string(),
+            RuleLevel::Error,
+        );
+        let scope = AnalysisScope::default();
+        let check = check_staged(root, &scope, None, &thresholds).unwrap();
+        assert!(check.staged.is_empty());
+        assert_eq!(check.exit_code(), 0);
+
+        // a -> b closes a cycle; the unstaged edit of other.rs is ignored
+        write(
+            root,
+            "src/a.rs",
+            "use crate::b::make;\n\npub struct A;\n\npub fn again() -> A {\n    make()\n}\n",
+        );
+        git(root, &["add", "src/a.rs"]);
+        write(
+            root,
+            "src/other.rs",
+            "use crate::a::A;\npub fn lonely(_: A) {}\n",
+        );
+
+        let check = check_staged(root, &scope, None, &thresholds).unwrap();
+        assert_eq!(check.staged, vec![PathBuf::from("src/a.rs")]);
+        assert_eq!(
+            check.checked,
+            vec![PathBuf::from("src/a.rs"), PathBuf::from("src/b.rs")]
+        );
+        assert!(
+            check
+                .new_issues
+                .iter()
+                .all(|issue| issue.issue_type.rule_id() == "coupling::circular-dependency"),
+            "{:?}",
+            check.new_issues
+        );
+        assert_eq!(check.failing, check.new_issues.len());
+        assert!(check.failing > 0);
+
+        // Only `error` rules fail the check
+        thresholds
+            .rules
+            .severity
+            .insert("coupling::circular-dependency".to_string(), RuleLevel::Info);
+        thresholds.show_info = true;
+        let check = check_staged(root, &scope, None, &thresholds).unwrap();
+        assert!(!check.new_issues.is_empty());
+        assert_eq!(check.exit_code(), 0);
+    }
+
+    #[test]
+    fn test_files_with_dependents() {
+        let mut metrics = ProjectMetrics::new();
+        for name in ["core", "api", "cli", "util"] {
+            metrics.add_module(ModuleMetrics::new(
+                format!("src/{name}.rs").into(),
+                name.into(),
+            ));
+        }
+        // api -> core, cli -> api, core -> util
+        for (source, target) in [
+            ("crate::api", "crate::core::Engine"),
+            ("crate::cli", "crate::api::serve"),
+            ("crate::core", "crate::util::helper"),
+        ] {
+            metrics.add_coupling(CouplingMetrics::new(
+                source.to_string(),
+                target.to_string(),
+                IntegrationStrength::Functional,
+                Distance::DifferentModule,
+                Volatility::Low,
+            ));
+        }
+
+        let changed = BTreeSet::from([PathBuf::from("src/core.rs")]);
+        let files: Vec<PathBuf> = files_with_dependents(&metrics, &changed)
+            .into_iter()
+            .collect();
+        // Only direct dependents: cli depends on api, not on core
+        assert_eq!(
+            files,
+            vec![PathBuf::from("src/api.rs"), PathBuf::from("src/core.rs")]
+        );
+    }
+}
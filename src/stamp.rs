@@ -0,0 +1,366 @@
+//! Stamp coupling detection
+//!
+//! Stamp coupling (Page-Jones, Yourdon & Constantine) exists when a function
+//! takes a whole data structure but only needs a part of it. The caller has
+//! to build the entire struct, and the function changes whenever the struct
+//! does, even when the fields it reads stay the same.
+//!
+//! For every parameter whose type is a plain path (`Config`, `&Config`,
+//! `&mut Config`), the body is searched for field accesses on it
+//! (`config.timeout`). A parameter that is used in any other way (passed on,
+//! method receiver, returned, mentioned inside a macro) needs the whole
+//! value and is not reported. Across the project, parameters that use at
+//! most [`MAX_STAMP_FIELDS_USED`] fields of a struct with at least
+//! [`MIN_STAMP_STRUCT_FIELDS`] fields are reported with the fields they
+//! actually read, so the signature can be narrowed to them.
+
+use std::collections::{BTreeSet, HashMap};
+
+use proc_macro2::{TokenStream, TokenTree};
+use syn::visit::Visit;
+use syn::{
+    Expr, ExprField, ExprPath, FnArg, ImplItemFn, ItemFn, ItemImpl, Macro, Member, Pat, Signature,
+    Type,
+};
+
+use crate::analyzer::span_position;
+use crate::metrics::{CouplingLocation, ModuleMetrics, ProjectMetrics};
+
+/// Structs with fewer fields are not reported
+pub const MIN_STAMP_STRUCT_FIELDS: usize = 5;
+
+/// Parameters reading more fields than this are not reported
+pub const MAX_STAMP_FIELDS_USED: usize = 2;
+
+/// How a function uses one of its struct-typed parameters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamUse {
+    /// Function name (`Type::method` for associated functions)
+    pub function: String,
+    pub param: String,
+    /// Last segment of the parameter type (`Config` for `&crate::Config`)
+    pub type_name: String,
+    /// Fields read through the parameter
+    pub fields: BTreeSet<String>,
+    /// Used other than through field access
+    pub whole_value: bool,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Collects [`ParamUse`]s per file
+#[derive(Debug, Default)]
+pub struct StampAnalyzer {
+    uses: Vec<ParamUse>,
+    /// Self type of the enclosing impl block
+    impl_owner: Option<String>,
+}
+
+impl StampAnalyzer {
+    /// Analyze a parsed file
+    pub fn analyze(file: &syn::File) -> Vec<ParamUse> {
+        let mut analyzer = Self::default();
+        analyzer.visit_file(file);
+        analyzer.uses
+    }
+
+    fn record_function(&mut self, sig: &Signature, body: &syn::Block) {
+        let function = match &self.impl_owner {
+            Some(owner) => format!("{}::{}", owner, sig.ident),
+            None => sig.ident.to_string(),
+        };
+        let params: Vec<(String, String, proc_macro2::Span)> = sig
+            .inputs
+            .iter()
+            .filter_map(|input| match input {
+                FnArg::Typed(pat_type) => match (&*pat_type.pat, plain_type_name(&pat_type.ty)) {
+                    (Pat::Ident(ident), Some(type_name)) => {
+                        Some((ident.ident.to_string(), type_name, ident.ident.span()))
+                    }
+                    _ => None,
+                },
+                FnArg::Receiver(_) => None,
+            })
+            .collect();
+        if params.is_empty() {
+            return;
+        }
+
+        let mut finder = FieldFinder {
+            params: params
+                .iter()
+                .map(|(name, _, _)| (name.clone(), (BTreeSet::new(), false)))
+                .collect(),
+        };
+        finder.visit_block(body);
+
+        for (param, type_name, span) in params {
+            let (fields, whole_value) = finder.params.remove(&param).unwrap_or_default();
+            let (line, column) = span_position(span);
+            self.uses.push(ParamUse {
+                function: function.clone(),
+                param,
+                type_name,
+                fields,
+                whole_value,
+                line,
+                column,
+            });
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for StampAnalyzer {
+    fn visit_item_fn(&mut self, node: &'ast ItemFn) {
+        self.record_function(&node.sig, &node.block);
+        syn::visit::visit_item_fn(self, node);
+    }
+
+    fn visit_item_impl(&mut self, node: &'ast ItemImpl) {
+        let previous = self.impl_owner.take();
+        if let Type::Path(type_path) = &*node.self_ty {
+            self.impl_owner = type_path.path.segments.last().map(|s| s.ident.to_string());
+        }
+        syn::visit::visit_item_impl(self, node);
+        self.impl_owner = previous;
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast ImplItemFn) {
+        self.record_function(&node.sig, &node.block);
+        syn::visit::visit_impl_item_fn(self, node);
+    }
+}
+
+/// Type name of `T`, `&T` or `&mut T` where `T` is a path without generics
+fn plain_type_name(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Reference(reference) => plain_type_name(&reference.elem),
+        Type::Path(type_path) if type_path.qself.is_none() => {
+            let last = type_path.path.segments.last()?;
+            last.arguments
+                .is_none()
+                .then(|| last.ident.to_string())
+                .filter(|name| name.starts_with(char::is_uppercase) && name != "Self")
+        }
+        _ => None,
+    }
+}
+
+/// Field accesses and other uses of parameters in a function body
+struct FieldFinder {
+    /// Parameter -> (fields read, used as a whole value)
+    params: HashMap<String, (BTreeSet<String>, bool)>,
+}
+
+impl FieldFinder {
+    fn param_of(&self, expr: &Expr) -> Option<String> {
+        match expr {
+            Expr::Path(ExprPath {
+                path, qself: None, ..
+            }) => path
+                .get_ident()
+                .map(ToString::to_string)
+                .filter(|name| self.params.contains_key(name)),
+            Expr::Paren(paren) => self.param_of(&paren.expr),
+            _ => None,
+        }
+    }
+
+    fn mark_tokens(&mut self, tokens: TokenStream) {
+        for token in tokens {
+            match token {
+                TokenTree::Ident(ident) => {
+                    if let Some((_, whole)) = self.params.get_mut(&ident.to_string()) {
+                        *whole = true;
+                    }
+                }
+                TokenTree::Group(group) => self.mark_tokens(group.stream()),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for FieldFinder {
+    fn visit_expr_field(&mut self, node: &'ast ExprField) {
+        if let (Some(param), Member::Named(field)) = (self.param_of(&node.base), &node.member)
+            && let Some((fields, _)) = self.params.get_mut(&param)
+        {
+            fields.insert(field.to_string());
+            return;
+        }
+        syn::visit::visit_expr_field(self, node);
+    }
+
+    fn visit_expr_path(&mut self, node: &'ast ExprPath) {
+        if let Some(ident) = node.path.get_ident()
+            && let Some((_, whole)) = self.params.get_mut(&ident.to_string())
+        {
+            *whole = true;
+        }
+        syn::visit::visit_expr_path(self, node);
+    }
+
+    fn visit_macro(&mut self, node: &'ast Macro) {
+        // Macro arguments are not parsed; any mention counts as a whole-value use
+        self.mark_tokens(node.tokens.clone());
+    }
+
+    fn visit_item_fn(&mut self, _: &'ast ItemFn) {
+        // Nested functions cannot see the parameters
+    }
+}
+
+/// A function taking a large struct but reading only a few of its fields
+#[derive(Debug, Clone)]
+pub struct StampCoupling {
+    /// Module containing the function
+    pub module: String,
+    pub function: String,
+    pub param: String,
+    pub struct_name: String,
+    /// Module defining the struct
+    pub struct_module: String,
+    pub struct_fields: usize,
+    /// Fields the function reads, sorted
+    pub used_fields: Vec<String>,
+    pub location: CouplingLocation,
+}
+
+/// Find parameters that read only a few fields of a large struct
+///
+/// The struct is looked up in the function's own module first, then among
+/// all modules; names defined in several other modules are skipped.
+pub fn find_stamp_couplings(metrics: &ProjectMetrics) -> Vec<StampCoupling> {
+    let mut structs: HashMap<&str, Vec<&ModuleMetrics>> = HashMap::new();
+    for module in metrics.modules.values() {
+        for def in module.type_definitions.values() {
+            if !def.is_trait && def.total_field_count > 0 {
+                structs.entry(def.name.as_str()).or_default().push(module);
+            }
+        }
+    }
+
+    let mut found = Vec::new();
+    for module in metrics.modules.values() {
+        for param_use in &module.param_uses {
+            if param_use.whole_value
+                || param_use.fields.is_empty()
+                || param_use.fields.len() > MAX_STAMP_FIELDS_USED
+            {
+                continue;
+            }
+            let definer = if module.type_definitions.contains_key(&param_use.type_name) {
+                Some(module)
+            } else {
+                match structs.get(param_use.type_name.as_str()).map(Vec::as_slice) {
+                    Some([definer]) => Some(*definer),
+                    _ => None,
+                }
+            };
+            let Some(definer) = definer else {
+                continue;
+            };
+            let def = &definer.type_definitions[&param_use.type_name];
+            if def.total_field_count < MIN_STAMP_STRUCT_FIELDS {
+                continue;
+            }
+            found.push(StampCoupling {
+                module: module.name.clone(),
+                function: param_use.function.clone(),
+                param: param_use.param.clone(),
+                struct_name: param_use.type_name.clone(),
+                struct_module: definer.name.clone(),
+                struct_fields: def.total_field_count,
+                used_fields: param_use.fields.iter().cloned().collect(),
+                location: CouplingLocation::new(
+                    module.path.clone(),
+                    param_use.line,
+                    param_use.column,
+                ),
+            });
+        }
+    }
+    found.sort_by(|a, b| {
+        (a.used_fields.len() * b.struct_fields)
+            .cmp(&(b.used_fields.len() * a.struct_fields))
+            .then_with(|| (&a.module, &a.function).cmp(&(&b.module, &b.function)))
+    });
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::CouplingAnalyzer;
+
+    fn uses(code: &str) -> Vec<ParamUse> {
+        StampAnalyzer::analyze(&syn::parse_file(code).unwrap())
+    }
+
+    fn module(name: &str, code: &str) -> ModuleMetrics {
+        let mut analyzer = CouplingAnalyzer::new(name.to_string(), format!("src/{name}.rs").into());
+        analyzer.analyze_file(code).unwrap();
+        analyzer.metrics
+    }
+
+    #[test]
+    fn test_param_field_uses() {
+        let found = uses(
+            r#"
+            fn timeout(config: &Config, other: &Config) -> u64 {
+                config.timeout * (config).retries + helper(other)
+            }
+            fn logged(config: Config) { println!("{:?}", config); }
+            impl Server {
+                fn start(&self, config: &mut Config, n: u32) { config.port.clone(); }
+            }
+            "#,
+        );
+        let summary: Vec<(&str, &str, Vec<&str>, bool)> = found
+            .iter()
+            .map(|u| {
+                (
+                    u.function.as_str(),
+                    u.param.as_str(),
+                    u.fields.iter().map(String::as_str).collect(),
+                    u.whole_value,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("timeout", "config", vec!["retries", "timeout"], false),
+                ("timeout", "other", vec![], true),
+                ("logged", "config", vec![], true),
+                ("Server::start", "config", vec!["port"], false),
+            ]
+        );
+        assert_eq!(found[0].line, 2);
+    }
+
+    #[test]
+    fn test_find_stamp_couplings() {
+        let mut metrics = ProjectMetrics::new();
+        metrics.add_module(module(
+            "config",
+            "pub struct Config { pub a: u8, pub b: u8, pub c: u8, pub d: u8, pub e: u8 }
+             pub struct Small { pub a: u8, pub b: u8 }",
+        ));
+        metrics.add_module(module(
+            "client",
+            "use crate::config::{Config, Small};
+             fn narrow(config: &Config) -> u8 { config.a + config.b }
+             fn wide(config: &Config) -> u8 { config.a + config.b + config.c }
+             fn small(small: &Small) -> u8 { small.a }",
+        ));
+
+        let found = find_stamp_couplings(&metrics);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].function, "narrow");
+        assert_eq!(found[0].struct_module, "config");
+        assert_eq!(found[0].struct_fields, 5);
+        assert_eq!(found[0].used_fields, vec!["a", "b"]);
+    }
+}
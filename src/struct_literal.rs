@@ -0,0 +1,407 @@
+//! Struct literals of public structs in other crates
+//!
+//! A public struct whose fields are all public can be built with a struct
+//! literal (`Config { host, port }`) or, for tuple structs, a positional
+//! call (`Point(x, y)`). Every such literal names the complete set of
+//! fields: adding one breaks all of them, and across crates it is a
+//! semver-breaking change. Tuple struct literals also depend on the field
+//! order (connascence of position).
+//!
+//! Literals are recorded per file with the struct their path names; paths
+//! whose second-to-last segment is capitalized (`Shape::Circle { .. }`) are
+//! enum variants and skipped, as are `Self { .. }` and literals completed
+//! with `..base` (new fields come from the base). Across the project the
+//! struct is looked up by name like enums are for exhaustive matches: names
+//! defined in several modules are skipped. Only literals in other workspace
+//! crates count; `#[non_exhaustive]` structs cannot be built there.
+//!
+//! A struct offers a constructor when an inherent impl has a `pub fn`
+//! without receiver returning `Self` (or the struct, also inside `Result`
+//! or `Option`), when it implements `Default`, or when a `{Name}Builder`
+//! type is defined in the workspace. Callers building it by literal anyway
+//! could switch; structs without one leave them no choice.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use quote::ToTokens;
+use syn::visit::Visit;
+use syn::{Expr, ExprCall, ExprStruct, ImplItem, ImplItemFn, ItemFn, ItemImpl, Path, ReturnType};
+
+use crate::analyzer::span_position;
+use crate::metrics::{CouplingLocation, ModuleMetrics, ProjectMetrics, Visibility};
+
+/// A struct built with a literal instead of a constructor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructLiteral {
+    /// Struct named by the literal (last path segment)
+    pub struct_name: String,
+    /// Tuple struct call (`Point(x, y)`) rather than named fields
+    pub positional: bool,
+    /// Function containing the literal (`Type::method` for associated
+    /// functions, empty outside functions)
+    pub function: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Struct literals and constructors of one file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StructLiterals {
+    pub literals: Vec<StructLiteral>,
+    /// Types with a public constructor in an inherent impl of this file
+    pub constructors: BTreeSet<String>,
+}
+
+/// Collects [`StructLiterals`] per file
+#[derive(Debug, Default)]
+pub struct LiteralAnalyzer {
+    found: StructLiterals,
+    /// Self type of the enclosing inherent impl block
+    impl_owner: Option<String>,
+    /// Enclosing function
+    function: Option<String>,
+}
+
+impl LiteralAnalyzer {
+    /// Analyze a parsed file
+    pub fn analyze(file: &syn::File) -> StructLiterals {
+        let mut analyzer = Self::default();
+        analyzer.visit_file(file);
+        analyzer.found
+    }
+
+    fn record(&mut self, path: &Path, positional: bool) {
+        let Some(struct_name) = struct_name(path) else {
+            return;
+        };
+        let (line, column) = span_position(path.segments[0].ident.span());
+        self.found.literals.push(StructLiteral {
+            struct_name,
+            positional,
+            function: self.function.clone().unwrap_or_default(),
+            line,
+            column,
+        });
+    }
+
+    fn visit_function(&mut self, name: &syn::Ident, visit: impl FnOnce(&mut Self)) {
+        let function = match &self.impl_owner {
+            Some(owner) => format!("{}::{}", owner, name),
+            None => name.to_string(),
+        };
+        let previous = self.function.replace(function);
+        visit(self);
+        self.function = previous;
+    }
+}
+
+impl<'ast> Visit<'ast> for LiteralAnalyzer {
+    fn visit_item_fn(&mut self, node: &'ast ItemFn) {
+        self.visit_function(&node.sig.ident, |this| {
+            syn::visit::visit_item_fn(this, node)
+        });
+    }
+
+    fn visit_item_impl(&mut self, node: &'ast ItemImpl) {
+        let previous = self.impl_owner.take();
+        if let syn::Type::Path(type_path) = &*node.self_ty {
+            self.impl_owner = type_path.path.segments.last().map(|s| s.ident.to_string());
+        }
+        if node.trait_.is_none()
+            && let Some(owner) = &self.impl_owner
+            && node.items.iter().any(|item| is_constructor(item, owner))
+        {
+            self.found.constructors.insert(owner.clone());
+        }
+        syn::visit::visit_item_impl(self, node);
+        self.impl_owner = previous;
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast ImplItemFn) {
+        self.visit_function(&node.sig.ident, |this| {
+            syn::visit::visit_impl_item_fn(this, node)
+        });
+    }
+
+    fn visit_expr_struct(&mut self, node: &'ast ExprStruct) {
+        if node.rest.is_none() && node.qself.is_none() {
+            self.record(&node.path, false);
+        }
+        syn::visit::visit_expr_struct(self, node);
+    }
+
+    fn visit_expr_call(&mut self, node: &'ast ExprCall) {
+        if let Expr::Path(path) = &*node.func
+            && path.qself.is_none()
+        {
+            self.record(&path.path, true);
+        }
+        syn::visit::visit_expr_call(self, node);
+    }
+}
+
+/// Struct a literal path names; `None` for `Self`, enum variants
+/// (including `Some`, `Ok` and `Err`) and lowercase (function) paths
+fn struct_name(path: &Path) -> Option<String> {
+    let mut segments = path.segments.iter().rev();
+    let name = segments.next()?.ident.to_string();
+    if matches!(name.as_str(), "Self" | "Some" | "Ok" | "Err")
+        || !name.starts_with(char::is_uppercase)
+    {
+        return None;
+    }
+    match segments.next() {
+        Some(parent) if parent.ident.to_string().starts_with(char::is_uppercase) => None,
+        _ => Some(name),
+    }
+}
+
+/// Whether an impl item is a `pub fn` without receiver returning the owner
+fn is_constructor(item: &ImplItem, owner: &str) -> bool {
+    let ImplItem::Fn(method) = item else {
+        return false;
+    };
+    if !matches!(method.vis, syn::Visibility::Public(_)) || method.sig.receiver().is_some() {
+        return false;
+    }
+    let ReturnType::Type(_, ty) = &method.sig.output else {
+        return false;
+    };
+    ty.to_token_stream()
+        .to_string()
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .any(|word| word == "Self" || word == owner)
+}
+
+/// One literal of a [`LiteralStruct`] in another crate
+#[derive(Debug, Clone)]
+pub struct LiteralSite {
+    pub module: String,
+    /// Workspace crate containing the literal
+    pub crate_name: Option<String>,
+    pub function: String,
+    pub location: CouplingLocation,
+}
+
+/// A public struct with only public fields built by literal in other crates
+#[derive(Debug, Clone)]
+pub struct LiteralStruct {
+    /// Module defining the struct
+    pub module: String,
+    /// Workspace crate defining the struct
+    pub crate_name: Option<String>,
+    pub struct_name: String,
+    pub fields: usize,
+    /// Tuple struct, built by position
+    pub positional: bool,
+    /// Offers a constructor, `Default` or a builder (see module docs)
+    pub has_constructor: bool,
+    /// Literals in other workspace crates
+    pub literals: Vec<LiteralSite>,
+    /// Where the struct is defined
+    pub location: CouplingLocation,
+}
+
+impl LiteralStruct {
+    /// Crates containing the literals, sorted and without duplicates
+    pub fn literal_crates(&self) -> Vec<&str> {
+        let mut crates: Vec<&str> = self
+            .literals
+            .iter()
+            .filter_map(|l| l.crate_name.as_deref())
+            .collect();
+        crates.sort_unstable();
+        crates.dedup();
+        crates
+    }
+}
+
+/// Find public structs with only public fields that other workspace crates
+/// build with literals, most literals first
+pub fn find_literal_structs(metrics: &ProjectMetrics) -> Vec<LiteralStruct> {
+    let mut structs: HashMap<&str, Vec<&ModuleMetrics>> = HashMap::new();
+    let mut types: BTreeSet<&str> = BTreeSet::new();
+    let mut constructed: BTreeSet<(Option<&str>, &str)> = BTreeSet::new();
+    for module in metrics.modules.values() {
+        for def in module.type_definitions.values() {
+            types.insert(def.name.as_str());
+            if !def.is_trait && def.variants == 0 && def.total_field_count > 0 {
+                structs.entry(def.name.as_str()).or_default().push(module);
+            }
+        }
+        let krate = module.crate_name.as_deref();
+        constructed.extend(
+            module
+                .struct_literals
+                .constructors
+                .iter()
+                .map(|name| (krate, name.as_str())),
+        );
+        constructed.extend(
+            module
+                .trait_impls
+                .iter()
+                .filter(|i| i.trait_path.rsplit("::").next() == Some("Default"))
+                .map(|i| (krate, i.item.as_str())),
+        );
+    }
+
+    let mut found: BTreeMap<(&str, &str), LiteralStruct> = BTreeMap::new();
+    for module in metrics.modules.values() {
+        for literal in &module.struct_literals.literals {
+            let name = literal.struct_name.as_str();
+            let Some([definer]) = structs.get(name).map(Vec::as_slice) else {
+                continue;
+            };
+            let def = &definer.type_definitions[name];
+            if module.crate_name.is_none()
+                || module.crate_name == definer.crate_name
+                || def.visibility != Visibility::Public
+                || def.non_exhaustive
+                || def.public_field_count < def.total_field_count
+            {
+                continue;
+            }
+            let krate = definer.crate_name.as_deref();
+            found
+                .entry((definer.name.as_str(), name))
+                .or_insert_with(|| LiteralStruct {
+                    module: definer.name.clone(),
+                    crate_name: definer.crate_name.clone(),
+                    struct_name: def.name.clone(),
+                    fields: def.total_field_count,
+                    positional: literal.positional,
+                    has_constructor: constructed.contains(&(krate, name))
+                        || types.contains(format!("{}Builder", name).as_str()),
+                    literals: Vec::new(),
+                    location: CouplingLocation::new(definer.path.clone(), def.line, def.column),
+                })
+                .literals
+                .push(LiteralSite {
+                    module: module.name.clone(),
+                    crate_name: module.crate_name.clone(),
+                    function: literal.function.clone(),
+                    location: CouplingLocation::new(
+                        module.path.clone(),
+                        literal.line,
+                        literal.column,
+                    ),
+                });
+        }
+    }
+
+    let mut found: Vec<LiteralStruct> = found.into_values().collect();
+    found.sort_by_key(|s| std::cmp::Reverse(s.literals.len()));
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::CouplingAnalyzer;
+
+    fn module(krate: &str, name: &str, code: &str) -> ModuleMetrics {
+        let mut analyzer =
+            CouplingAnalyzer::new(name.to_string(), format!("{krate}/src/{name}.rs").into());
+        analyzer.analyze_file(code).unwrap();
+        let mut metrics = analyzer.metrics;
+        metrics.crate_name = Some(krate.to_string());
+        metrics
+    }
+
+    #[test]
+    fn test_struct_literals() {
+        let found = LiteralAnalyzer::analyze(
+            &syn::parse_file(
+                r#"
+                impl Config {
+                    pub fn new(port: u16) -> Result<Self, Error> { todo!() }
+                    pub fn port(&self) -> u16 { self.port }
+                }
+                impl Point { fn origin() -> Self { Self(0, 0) } }
+                fn build() {
+                    let c = net::Config { host, port: 80 };
+                    let p = Point(1, 2);
+                    let d = Config { port: 1, ..Config::default() };
+                    let s = Shape::Circle { r: 1.0 };
+                    let v = Some(Wrapper(3));
+                    let n = parse(1);
+                }
+                "#,
+            )
+            .unwrap(),
+        );
+        let literals: Vec<(&str, bool, &str)> = found
+            .literals
+            .iter()
+            .map(|l| (l.struct_name.as_str(), l.positional, l.function.as_str()))
+            .collect();
+        assert_eq!(
+            literals,
+            vec![
+                ("Config", false, "build"),
+                ("Point", true, "build"),
+                ("Wrapper", true, "build"),
+            ]
+        );
+        assert_eq!(found.constructors, BTreeSet::from(["Config".to_string()]));
+    }
+
+    #[test]
+    fn test_find_literal_structs() {
+        let mut metrics = ProjectMetrics::new();
+        metrics.add_module(module(
+            "core",
+            "config",
+            "pub struct Config { pub host: String, pub port: u16 }
+             pub struct Point(pub i32, pub i32);
+             pub struct Hidden { pub a: u8, b: u8 }
+             #[non_exhaustive]
+             pub struct Open { pub a: u8 }
+             impl Point { pub fn new(x: i32, y: i32) -> Self { Self(x, y) } }
+             fn local() -> Config { Config { host: String::new(), port: 1 } }",
+        ));
+        metrics.add_module(module(
+            "app",
+            "main",
+            "use core::config::{Config, Hidden, Open, Point};
+             fn main() {
+                 let c = Config { host: String::new(), port: 80 };
+                 let d = Config { host: String::new(), port: 81 };
+                 let p = Point(1, 2);
+                 let h = Hidden { a: 1, b: 2 };
+                 let o = Open { a: 1 };
+             }",
+        ));
+
+        let found = find_literal_structs(&metrics);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].struct_name, "Config");
+        assert_eq!(found[0].literals.len(), 2);
+        assert_eq!(found[0].location.line, 1);
+        assert!(!found[0].has_constructor && !found[0].positional);
+        assert_eq!(found[0].literal_crates(), vec!["app"]);
+        assert_eq!(found[1].struct_name, "Point");
+        assert!(found[1].has_constructor && found[1].positional);
+
+        let depths = crate::aposd::analyze_module_depths(&metrics);
+        let config = depths.iter().find(|d| d.module == "config").unwrap();
+        assert_eq!(config.literal_interface, 4);
+
+        let report = crate::balance::analyze_project_balance_with_thresholds(
+            &metrics,
+            &crate::balance::IssueThresholds {
+                strict_mode: false,
+                ..crate::balance::IssueThresholds::default()
+            },
+        );
+        let rules: Vec<&str> = report
+            .issues
+            .iter()
+            .filter(|i| i.source.ends_with("Point") || i.target.ends_with("Point"))
+            .map(|i| i.issue_type.rule_id())
+            .collect();
+        assert!(rules.contains(&"rust::field-visibility-leak"));
+        assert!(rules.contains(&"connascence::position"));
+    }
+}
@@ -0,0 +1,482 @@
+//! Refactoring suggestions ranked by payoff
+//!
+//! The other reports list findings; this one proposes concrete actions and
+//! estimates how much each would raise the coupling score (the average
+//! balance score of internal couplings, see [`calculate_project_score`]).
+//! Each action is applied to the couplings it affects and the score is
+//! recomputed:
+//!
+//! - **Break a cycle** at its weakest edge: the couplings along that edge
+//!   go away (the used items move, or a trait inverts the dependency)
+//! - **Introduce a trait** for an item that several modules couple to with
+//!   functional or intrusive strength: those couplings become contracts
+//! - **Merge two shallow modules** of a crate that use each other: the
+//!   couplings between them become same-module couplings
+//! - **Split a god module**: each part changes less often than the whole,
+//!   so couplings into it drop one volatility level
+//!
+//! Estimates are not additive, since actions can touch the same couplings;
+//! apply the top suggestion and run the report again.
+//!
+//! [`calculate_project_score`]: crate::balance::calculate_project_score
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, Write};
+
+use crate::aposd::{AposdConfig, DepthClass, analyze_module_depths_with};
+use crate::balance::{BalanceScore, IssueThresholds};
+use crate::metrics::{
+    CouplingMetrics, Distance, IntegrationStrength, ProjectMetrics, Visibility, Volatility,
+};
+
+/// Minimum number of modules coupling strongly to an item before a trait
+/// is suggested for it
+const MIN_TRAIT_USERS: usize = 2;
+
+/// Smallest estimated gain worth suggesting
+const MIN_GAIN: f64 = 0.0001;
+
+/// Kind of refactoring action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SuggestionKind {
+    BreakCycle,
+    IntroduceTrait,
+    MergeModules,
+    SplitModule,
+}
+
+impl std::fmt::Display for SuggestionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SuggestionKind::BreakCycle => write!(f, "Break cycle"),
+            SuggestionKind::IntroduceTrait => write!(f, "Introduce trait"),
+            SuggestionKind::MergeModules => write!(f, "Merge modules"),
+            SuggestionKind::SplitModule => write!(f, "Split module"),
+        }
+    }
+}
+
+/// A refactoring action with its estimated payoff
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    pub kind: SuggestionKind,
+    /// Modules the action touches
+    pub modules: Vec<String>,
+    /// What to do
+    pub action: String,
+    /// Internal couplings the action changes
+    pub couplings: usize,
+    /// Estimated coupling score improvement (on the 0.0 - 1.0 scale)
+    pub gain: f64,
+}
+
+/// Refactoring suggestions for a project
+#[derive(Debug, Clone, PartialEq)]
+pub struct SuggestionReport {
+    /// Current coupling score
+    pub score: f64,
+    /// Internal couplings the score is computed from
+    pub internal_couplings: usize,
+    /// Suggestions with an estimated gain of at least [`MIN_GAIN`], highest
+    /// first
+    pub suggestions: Vec<Suggestion>,
+}
+
+/// An internal coupling with its score and resolved modules
+struct Scored<'a> {
+    coupling: &'a CouplingMetrics,
+    score: f64,
+    source: Option<&'a str>,
+    target: Option<&'a str>,
+}
+
+/// Recomputes the coupling score with some couplings changed
+struct Estimator<'a> {
+    couplings: Vec<Scored<'a>>,
+    total: f64,
+}
+
+impl<'a> Estimator<'a> {
+    fn new(metrics: &'a ProjectMetrics) -> Self {
+        let couplings: Vec<Scored> = metrics
+            .couplings
+            .iter()
+            .filter(|c| c.distance != Distance::DifferentCrate)
+            .map(|coupling| Scored {
+                coupling,
+                score: BalanceScore::calculate(coupling).score,
+                source: metrics
+                    .find_module(&coupling.source)
+                    .map(|m| m.name.as_str()),
+                target: metrics
+                    .find_module(&coupling.target)
+                    .map(|m| m.name.as_str()),
+            })
+            .collect();
+        let total = couplings.iter().map(|c| c.score).sum();
+        Self { couplings, total }
+    }
+
+    fn score(&self) -> f64 {
+        average(self.total, self.couplings.len())
+    }
+
+    /// Couplings from module `from` to module `to`
+    fn between(&self, from: &str, to: &str) -> Vec<usize> {
+        self.indices(|c| c.source == Some(from) && c.target == Some(to))
+    }
+
+    fn indices(&self, filter: impl Fn(&Scored) -> bool) -> Vec<usize> {
+        (0..self.couplings.len())
+            .filter(|&i| filter(&self.couplings[i]))
+            .collect()
+    }
+
+    /// Score change when the couplings at `indices` are replaced by
+    /// `apply` (`None` removes them)
+    fn gain(
+        &self,
+        indices: &[usize],
+        apply: impl Fn(&CouplingMetrics) -> Option<CouplingMetrics>,
+    ) -> f64 {
+        let mut total = self.total;
+        let mut count = self.couplings.len();
+        for &i in indices {
+            let scored = &self.couplings[i];
+            total -= scored.score;
+            match apply(scored.coupling) {
+                Some(changed) => total += BalanceScore::calculate(&changed).score,
+                None => count -= 1,
+            }
+        }
+        average(total, count) - self.score()
+    }
+}
+
+fn average(total: f64, count: usize) -> f64 {
+    if count == 0 {
+        1.0 // No internal couplings = perfect score
+    } else {
+        total / count as f64
+    }
+}
+
+impl SuggestionReport {
+    /// Collect and rank suggestions for a project
+    pub fn build(
+        metrics: &ProjectMetrics,
+        thresholds: &IssueThresholds,
+        aposd: &AposdConfig,
+    ) -> Self {
+        let estimator = Estimator::new(metrics);
+        let mut suggestions = Vec::new();
+        suggestions.extend(break_cycles(metrics, &estimator));
+        suggestions.extend(introduce_traits(&estimator));
+        suggestions.extend(merge_modules(metrics, aposd, &estimator));
+        suggestions.extend(split_modules(metrics, thresholds, &estimator));
+
+        suggestions.retain(|s| s.gain >= MIN_GAIN);
+        suggestions.sort_by(|a, b| {
+            b.gain
+                .total_cmp(&a.gain)
+                .then_with(|| a.kind.cmp(&b.kind))
+                .then_with(|| a.modules.cmp(&b.modules))
+        });
+
+        Self {
+            score: estimator.score(),
+            internal_couplings: estimator.couplings.len(),
+            suggestions,
+        }
+    }
+}
+
+/// Remove the weakest edge of every module cycle
+fn break_cycles(metrics: &ProjectMetrics, estimator: &Estimator) -> Vec<Suggestion> {
+    metrics
+        .module_cycles()
+        .into_iter()
+        .map(|cycle| {
+            let edge = &cycle.weakest_edge;
+            let indices = estimator.between(&edge.from, &edge.to);
+            Suggestion {
+                kind: SuggestionKind::BreakCycle,
+                action: format!(
+                    "Remove the dependency {} -> {} (move what it uses, or invert it with a trait)",
+                    edge.from, edge.to
+                ),
+                modules: cycle.members,
+                couplings: indices.len(),
+                gain: estimator.gain(&indices, |_| None),
+            }
+        })
+        .collect()
+}
+
+/// Put a trait in front of items several modules couple to strongly
+fn introduce_traits(estimator: &Estimator) -> Vec<Suggestion> {
+    let mut by_target: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for (i, scored) in estimator.couplings.iter().enumerate() {
+        let strong = matches!(
+            scored.coupling.strength,
+            IntegrationStrength::Intrusive | IntegrationStrength::Functional
+        );
+        if strong
+            && scored.source.is_some()
+            && scored.target.is_some()
+            && scored.source != scored.target
+        {
+            by_target
+                .entry(&scored.coupling.target)
+                .or_default()
+                .push(i);
+        }
+    }
+
+    by_target
+        .into_iter()
+        .filter_map(|(target, indices)| {
+            let users: BTreeSet<&str> = indices
+                .iter()
+                .filter_map(|&i| estimator.couplings[i].source)
+                .collect();
+            if users.len() < MIN_TRAIT_USERS {
+                return None;
+            }
+            let owner = estimator.couplings[indices[0]].target?;
+            let mut modules = vec![owner.to_string()];
+            modules.extend(users.iter().map(|m| m.to_string()));
+            Some(Suggestion {
+                kind: SuggestionKind::IntroduceTrait,
+                action: format!(
+                    "Introduce a trait for {} and depend on it from {} modules",
+                    target,
+                    users.len()
+                ),
+                modules,
+                couplings: indices.len(),
+                gain: estimator.gain(&indices, |coupling| {
+                    let mut changed = coupling.clone();
+                    changed.strength = IntegrationStrength::Contract;
+                    changed.target_visibility = Visibility::Public;
+                    Some(changed)
+                }),
+            })
+        })
+        .collect()
+}
+
+/// Merge pairs of shallow modules of the same crate that use each other
+fn merge_modules(
+    metrics: &ProjectMetrics,
+    aposd: &AposdConfig,
+    estimator: &Estimator,
+) -> Vec<Suggestion> {
+    let shallow: Vec<String> = analyze_module_depths_with(metrics, aposd)
+        .into_iter()
+        .filter(|depth| depth.class == DepthClass::Shallow)
+        .map(|depth| depth.module)
+        .collect();
+
+    let mut suggestions = Vec::new();
+    for (i, a) in shallow.iter().enumerate() {
+        for b in &shallow[i + 1..] {
+            let crate_of = |name: &str| metrics.modules.get(name).map(|m| &m.crate_name);
+            if crate_of(a) != crate_of(b) {
+                continue;
+            }
+            let mut indices = estimator.between(a, b);
+            indices.extend(estimator.between(b, a));
+            if indices.is_empty() {
+                continue;
+            }
+            suggestions.push(Suggestion {
+                kind: SuggestionKind::MergeModules,
+                action: format!("Merge the shallow modules {} and {}", a, b),
+                modules: vec![a.clone(), b.clone()],
+                couplings: indices.len(),
+                gain: estimator.gain(&indices, |coupling| {
+                    let mut changed = coupling.clone();
+                    changed.distance = Distance::SameModule;
+                    Some(changed)
+                }),
+            });
+        }
+    }
+    suggestions
+}
+
+/// Split god modules so couplings into them see fewer changes
+fn split_modules(
+    metrics: &ProjectMetrics,
+    thresholds: &IssueThresholds,
+    estimator: &Estimator,
+) -> Vec<Suggestion> {
+    metrics
+        .modules
+        .values()
+        .filter(|module| {
+            module.is_god_module(
+                thresholds.max_functions,
+                thresholds.max_types,
+                thresholds.max_impls,
+            )
+        })
+        .map(|module| {
+            let name = module.name.as_str();
+            let indices = estimator.indices(|c| c.target == Some(name) && c.source != Some(name));
+            Suggestion {
+                kind: SuggestionKind::SplitModule,
+                action: format!(
+                    "Split the god module {} ({} functions, {} types) by responsibility",
+                    name,
+                    module.function_count(),
+                    module.type_definitions.len()
+                ),
+                modules: vec![name.to_string()],
+                couplings: indices.len(),
+                gain: estimator.gain(&indices, |coupling| {
+                    let mut changed = coupling.clone();
+                    changed.volatility = match coupling.volatility {
+                        Volatility::High => Volatility::Medium,
+                        Volatility::Medium | Volatility::Low => Volatility::Low,
+                    };
+                    Some(changed)
+                }),
+            }
+        })
+        .collect()
+}
+
+/// Generate the refactoring suggestion report (top `limit` suggestions) to
+/// writer
+pub fn generate_suggestion_report<W: Write>(
+    report: &SuggestionReport,
+    limit: usize,
+    writer: &mut W,
+) -> io::Result<()> {
+    writeln!(writer, "Refactoring Suggestions")?;
+    writeln!(writer, "=======================")?;
+    writeln!(writer)?;
+    writeln!(
+        writer,
+        "Coupling score: {:.2}/1.00 ({} internal couplings)",
+        report.score, report.internal_couplings
+    )?;
+    writeln!(writer)?;
+
+    if report.suggestions.is_empty() {
+        writeln!(writer, "No action is estimated to improve the score.")?;
+        return Ok(());
+    }
+
+    for (rank, suggestion) in report.suggestions.iter().take(limit).enumerate() {
+        writeln!(
+            writer,
+            "{}. [+{:.4}] {}: {}",
+            rank + 1,
+            suggestion.gain,
+            suggestion.kind,
+            suggestion.action
+        )?;
+        writeln!(
+            writer,
+            "   {} coupling(s) in {}",
+            suggestion.couplings,
+            suggestion.modules.join(", ")
+        )?;
+    }
+
+    writeln!(writer)?;
+    writeln!(
+        writer,
+        "Showing {} of {} suggestions. Gains are estimated one at a time and do not add up;",
+        report.suggestions.len().min(limit),
+        report.suggestions.len()
+    )?;
+    writeln!(writer, "apply the top one and run again.")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::ModuleMetrics;
+    use std::path::PathBuf;
+
+    fn coupling(source: &str, target: &str, strength: IntegrationStrength) -> CouplingMetrics {
+        CouplingMetrics::new(
+            source.into(),
+            target.into(),
+            strength,
+            Distance::DifferentModule,
+            Volatility::High,
+        )
+    }
+
+    fn project(modules: &[&str], couplings: Vec<CouplingMetrics>) -> ProjectMetrics {
+        let mut metrics = ProjectMetrics::new();
+        for name in modules {
+            metrics.add_module(ModuleMetrics::new(
+                PathBuf::from(format!("src/{}.rs", name)),
+                name.to_string(),
+            ));
+        }
+        for c in couplings {
+            metrics.add_coupling(c);
+        }
+        metrics
+    }
+
+    #[test]
+    fn test_suggestions_ranked_by_gain() {
+        let metrics = project(
+            &["a", "b", "c"],
+            vec![
+                coupling("a", "b::Store", IntegrationStrength::Intrusive),
+                coupling("c", "b::Store", IntegrationStrength::Functional),
+                coupling("b", "a::Config", IntegrationStrength::Model),
+            ],
+        );
+        let report =
+            SuggestionReport::build(&metrics, &IssueThresholds::default(), &Default::default());
+
+        let kinds: Vec<SuggestionKind> = report.suggestions.iter().map(|s| s.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![SuggestionKind::IntroduceTrait, SuggestionKind::BreakCycle]
+        );
+        let trait_suggestion = &report.suggestions[0];
+        assert_eq!(trait_suggestion.modules, vec!["b", "a", "c"]);
+        assert_eq!(trait_suggestion.couplings, 2);
+        assert!(
+            report
+                .suggestions
+                .windows(2)
+                .all(|w| w[0].gain >= w[1].gain)
+        );
+
+        let mut out = Vec::new();
+        generate_suggestion_report(&report, 1, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("1. [+"));
+        assert!(text.contains("Introduce trait: Introduce a trait for b::Store"));
+        assert!(text.contains("Showing 1 of 2 suggestions"));
+    }
+
+    #[test]
+    fn test_gain_recomputes_average() {
+        let metrics = project(
+            &["a", "b"],
+            vec![
+                coupling("a", "b::Store", IntegrationStrength::Intrusive),
+                coupling("b", "a::Config", IntegrationStrength::Model),
+            ],
+        );
+        let estimator = Estimator::new(&metrics);
+        let scores: Vec<f64> = estimator.couplings.iter().map(|c| c.score).collect();
+        let removed = estimator.gain(&[0], |_| None);
+        assert!((removed - (scores[1] - estimator.score())).abs() < 1e-9);
+        assert_eq!(estimator.gain(&[], |_| None), 0.0);
+    }
+}
@@ -0,0 +1,241 @@
+//! Suppression of acknowledged findings
+//!
+//! Findings can be silenced where they are intentional:
+//!
+//! - In source, with comment directives. `coupling:ignore` applies to the
+//!   line it is on, `coupling:ignore-next-line` to the following line:
+//!
+//!   ```text
+//!   tx.begin(); // coupling:ignore temporal
+//!   // coupling:ignore-next-line connascence::meaning
+//!   let port = 8080;
+//!   ```
+//!
+//! - Per file, in `.coupling.toml`:
+//!
+//!   ```toml
+//!   [suppress]
+//!   "src/legacy/*" = ["temporal", "connascence::meaning"]
+//!   "src/generated/*" = ["all"]
+//!   ```
+//!
+//! Rules are selected by category (`temporal`), full rule id
+//! (`connascence::meaning`, see [`IssueType::rule_id`]) or `all`. A
+//! directive without rules suppresses everything on its line.
+//!
+//! - One finding, by its fingerprint (see [`crate::fingerprint`]):
+//!
+//!   ```toml
+//!   [suppress]
+//!   fingerprints = ["5c0f3a9d2b7e8146"]
+//!   ```
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use glob::Pattern;
+
+use crate::balance::{CouplingIssue, IssueType};
+use crate::fingerprint::fingerprint;
+use crate::metrics::ProjectMetrics;
+use crate::paths::matches_path_suffix;
+
+const IGNORE: &str = "coupling:ignore";
+const IGNORE_NEXT_LINE: &str = "coupling:ignore-next-line";
+
+/// An in-source suppression directive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suppression {
+    /// Line the directive applies to (1-based)
+    pub line: usize,
+    /// Rule selectors; empty means all rules
+    pub rules: Vec<String>,
+}
+
+impl Suppression {
+    /// Whether the directive covers an issue type
+    pub fn covers(&self, issue_type: IssueType) -> bool {
+        self.rules.is_empty() || self.rules.iter().any(|r| issue_type.matches_rule(r))
+    }
+}
+
+/// A file-level suppression from the config file
+#[derive(Debug, Clone)]
+pub struct FileSuppression {
+    pub pattern: Pattern,
+    pub rules: Vec<String>,
+}
+
+impl FileSuppression {
+    /// Whether the suppression covers an issue type in the given file
+    ///
+    /// The pattern is matched against every trailing part of the path, so
+    /// `src/legacy/*` matches both `./src/legacy/a.rs` and
+    /// `/home/me/project/src/legacy/a.rs`.
+    pub fn covers(&self, path: &Path, issue_type: IssueType) -> bool {
+        if !self.rules.iter().any(|r| issue_type.matches_rule(r)) {
+            return false;
+        }
+        matches_path_suffix(&self.pattern, path)
+    }
+}
+
+/// Parse `coupling:ignore` directives from `//` comments
+pub fn parse_directives(source: &str) -> Vec<Suppression> {
+    let mut directives = Vec::new();
+    for (index, line) in source.lines().enumerate() {
+        let Some(comment) = line.find("//").map(|i| &line[i..]) else {
+            continue;
+        };
+        let Some(start) = comment.find(IGNORE) else {
+            continue;
+        };
+        let directive = &comment[start..];
+        let (target, rest) = match directive.strip_prefix(IGNORE_NEXT_LINE) {
+            Some(rest) => (index + 2, rest),
+            None => (index + 1, &directive[IGNORE.len()..]),
+        };
+        // `coupling:ignored` or similar is not a directive
+        if rest.starts_with(|c: char| !c.is_whitespace() && c != ',') {
+            continue;
+        }
+        directives.push(Suppression {
+            line: target,
+            rules: rest
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|r| !r.is_empty())
+                .map(str::to_string)
+                .collect(),
+        });
+    }
+    directives
+}
+
+/// Remove issues covered by in-source directives, file-level suppressions
+/// or suppressed fingerprints
+pub fn apply_suppressions(
+    metrics: &ProjectMetrics,
+    files: &[FileSuppression],
+    fingerprints: &BTreeSet<String>,
+    issues: &mut Vec<CouplingIssue>,
+) {
+    issues.retain(|issue| {
+        if !fingerprints.is_empty() && fingerprints.contains(&fingerprint(issue, metrics)) {
+            return false;
+        }
+
+        let Some(path) = issue.file(metrics) else {
+            return true;
+        };
+
+        if files.iter().any(|f| f.covers(path, issue.issue_type)) {
+            return false;
+        }
+
+        let line = issue.location.line;
+        line == 0
+            || !metrics
+                .modules
+                .values()
+                .filter(|m| m.path == path)
+                .flat_map(|m| &m.suppressions)
+                .any(|s| s.line == line && s.covers(issue.issue_type))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_directives() {
+        let source = "\
+fn f() {
+    tx.begin(); // coupling:ignore temporal
+    // coupling:ignore-next-line connascence::meaning, temporal::dropped-guard
+    let port = 8080;
+    let x = 1; // coupling:ignore
+    // coupling:ignored is not a directive
+}";
+        let directives = parse_directives(source);
+        assert_eq!(
+            directives,
+            vec![
+                Suppression {
+                    line: 2,
+                    rules: vec!["temporal".into()],
+                },
+                Suppression {
+                    line: 4,
+                    rules: vec![
+                        "connascence::meaning".into(),
+                        "temporal::dropped-guard".into()
+                    ],
+                },
+                Suppression {
+                    line: 5,
+                    rules: vec![],
+                },
+            ]
+        );
+        assert!(directives[0].covers(IssueType::UnpairedOperation));
+        assert!(!directives[0].covers(IssueType::ConnascenceOfMeaning));
+        assert!(directives[2].covers(IssueType::GodModule));
+    }
+
+    #[test]
+    fn test_file_suppression_matches_path_suffix() {
+        let suppression = FileSuppression {
+            pattern: Pattern::new("src/legacy/*").unwrap(),
+            rules: vec!["connascence".into()],
+        };
+        let path = Path::new("/home/me/project/src/legacy/old.rs");
+        assert!(suppression.covers(path, IssueType::ConnascenceOfIdentity));
+        assert!(!suppression.covers(path, IssueType::GodModule));
+        assert!(!suppression.covers(
+            Path::new("src/core/new.rs"),
+            IssueType::ConnascenceOfIdentity
+        ));
+        assert!(!IssueType::ConnascenceOfMeaning.matches_rule("connascence::mean"));
+    }
+
+    #[test]
+    fn test_directive_suppresses_issue() {
+        use crate::balance::{IssueThresholds, analyze_project_balance_with_thresholds};
+        use crate::connascence::ConnascenceAnalyzer;
+        use crate::metrics::ModuleMetrics;
+        use std::path::PathBuf;
+
+        let project = |state_source: &str| {
+            let mut metrics = ProjectMetrics::new();
+            let mut core = ModuleMetrics::new(PathBuf::from("src/core.rs"), "core".into());
+            core.connascence = ConnascenceAnalyzer::analyze_source("fn f() { STATE; }").unwrap();
+            let mut state = ModuleMetrics::new(PathBuf::from("src/state.rs"), "state".into());
+            state.connascence = ConnascenceAnalyzer::analyze_source(state_source).unwrap();
+            state.suppressions = parse_directives(state_source);
+            metrics.add_module(core);
+            metrics.add_module(state);
+            metrics
+        };
+        let issues = |metrics: &ProjectMetrics| {
+            analyze_project_balance_with_thresholds(metrics, &IssueThresholds::default()).issues
+        };
+
+        assert_eq!(issues(&project("static mut STATE: u8 = 3;")).len(), 1);
+        let ignored =
+            "// coupling:ignore-next-line connascence::identity\nstatic mut STATE: u8 = 3;";
+        assert!(issues(&project(ignored)).is_empty());
+
+        let metrics = project("static mut STATE: u8 = 3;");
+        let found = issues(&metrics);
+        let thresholds = IssueThresholds {
+            suppressed_fingerprints: BTreeSet::from([fingerprint(&found[0], &metrics)]),
+            ..IssueThresholds::default()
+        };
+        assert!(
+            analyze_project_balance_with_thresholds(&metrics, &thresholds)
+                .issues
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,2280 @@
+//! Temporal coupling detection
+//!
+//! Temporal coupling exists when operations must happen in a specific order
+//! (open before close, begin before commit, acquire before release). The
+//! compiler cannot check these protocols, so they are a common source of bugs.
+//!
+//! The analysis walks the `syn` AST rather than matching text, so calls that
+//! only appear inside string literals or comments are never reported. It
+//! records:
+//!
+//! - Paired operations (`begin`/`commit`, `acquire`/`release`, ...)
+//! - `Drop` implementations (RAII cleanup)
+//! - Guard bindings (`let _guard = mutex.lock()`), including guards that are
+//!   discarded immediately with `let _ = ...`
+//! - `unsafe` blocks, which usually carry ordering invariants of their own
+//! - State checks: methods that refuse to proceed unless a `self` field is
+//!   set (`assert!(self.connected)`, `if !self.ready { return ... }`), and
+//!   the methods that assign those fields
+//! - Builder types (`FooBuilder` with chained setters and a `build()`), and
+//!   whether they enforce their required fields. A `build()` that unwraps
+//!   unset fields makes callers remember which setters must come first.
+//! - Panic paths: `unwrap()`, `expect()` or `panic!` on a `self` field that
+//!   another method of the type assigns (`self.conn.as_ref().unwrap()` with
+//!   `self.conn` set by `connect()`). Calling the methods in the wrong order
+//!   does not fail to compile or return an error, it panics.
+//! - Cancellation: a `select!` drops the futures of every branch but the
+//!   first to complete. Branches awaiting operations that are not
+//!   cancellation safe (`read_exact`, `write_all`, ...) or hand-written
+//!   futures (`impl Future` with its own `poll` state machine) lose the
+//!   progress made so far. Tasks spawned without keeping their
+//!   `JoinHandle` (`tokio::spawn(fut);`) can neither be awaited nor aborted.
+//! - Lock order: the `Mutex`/`RwLock` fields of a struct (and `static`
+//!   locks of a module) acquired while another one is held. Two functions
+//!   taking the same two locks in opposite orders can deadlock when they
+//!   run concurrently. A lock counts as held from its `let` binding to the
+//!   end of the block or an explicit `drop(guard)`.
+//! - Drop order: a `Drop::drop` that uses several fields of its type tears
+//!   them down in the order its statements run, and fields are dropped in
+//!   declaration order afterwards, so reordering either changes what is
+//!   still alive. Fields released by hand (`ManuallyDrop::drop(&mut
+//!   self.f)`, `mem::drop(self.f.take())`, `mem::forget`) move part of
+//!   the teardown into other methods, which must then run in the right
+//!   order relative to `drop`.
+//!
+//! - Foreign resources: functions declared in the file's `extern` blocks
+//!   are paired by name (`ctx_new`/`ctx_free`, `malloc`/`free`, see
+//!   [`alloc_free_pairs`]) and checked like the built-in paired operations.
+//!
+//! The built-in paired operations, guard methods and state-check macros can
+//! be extended or replaced per project with a `[temporal]` section in
+//! `.coupling.toml` (see [`TemporalConfig`]).
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Deserialize;
+
+use proc_macro2::{Delimiter, Ident, Spacing, TokenStream, TokenTree};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::visit::Visit;
+use syn::{
+    Expr, ExprAssign, ExprIf, ExprMethodCall, ExprUnsafe, ImplItem, ImplItemFn, ItemFn, ItemImpl,
+    ItemStruct, Local, Macro, Member, Pat, PathArguments, ReturnType, Signature, Stmt, Token,
+    TraitItemFn, Type,
+};
+
+use crate::analyzer::{span_position, strip_test_code};
+use crate::balance::Severity;
+use crate::ffi::alloc_free_pairs;
+use crate::metrics::ProjectMetrics;
+
+/// An operation that must be followed by a matching closing operation
+#[derive(Debug, Clone, Copy)]
+pub struct PairedOperation {
+    /// Method that starts the protocol
+    pub open: &'static str,
+    /// Methods that end the protocol (any one of them balances the open)
+    pub close: &'static [&'static str],
+}
+
+/// Known paired operations
+///
+/// Operations that Rust already cleans up through `Drop` (e.g. `File::open`,
+/// `Mutex::lock`) are intentionally not listed here; guards are tracked
+/// separately. Very generic names such as `start` or `init` are left out as
+/// well because they are mostly unrelated calls (`Span::start`, subscriber
+/// `init`).
+pub const PAIRED_OPS: &[PairedOperation] = &[
+    PairedOperation {
+        open: "begin",
+        close: &["commit", "rollback", "end"],
+    },
+    PairedOperation {
+        open: "begin_transaction",
+        close: &["commit", "rollback"],
+    },
+    PairedOperation {
+        open: "acquire",
+        close: &["release"],
+    },
+    PairedOperation {
+        open: "connect",
+        close: &["disconnect", "close"],
+    },
+    PairedOperation {
+        open: "subscribe",
+        close: &["unsubscribe"],
+    },
+    PairedOperation {
+        open: "attach",
+        close: &["detach"],
+    },
+    PairedOperation {
+        open: "mount",
+        close: &["unmount"],
+    },
+    PairedOperation {
+        open: "push_scope",
+        close: &["pop_scope"],
+    },
+];
+
+/// A paired operation declared in the configuration file
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct PairRule {
+    /// Method that starts the protocol
+    pub open: String,
+    /// Methods that end the protocol (any one of them balances the open)
+    pub close: Vec<String>,
+    /// Severity of an unpaired `open` (defaults to `unpaired_severity`)
+    #[serde(default)]
+    pub severity: Option<Severity>,
+}
+
+impl From<&PairedOperation> for PairRule {
+    fn from(op: &PairedOperation) -> Self {
+        Self {
+            open: op.open.to_string(),
+            close: op.close.iter().map(|c| c.to_string()).collect(),
+            severity: None,
+        }
+    }
+}
+
+/// Methods that return a guard whose lifetime defines a critical section
+pub const GUARD_METHODS: &[&str] = &[
+    "lock",
+    "try_lock",
+    "try_read",
+    "try_write",
+    "borrow_mut",
+    "enter",
+];
+
+/// Kind of temporal coupling problem
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TemporalIssueKind {
+    /// An opening operation without a matching close
+    UnpairedOperation,
+    /// A guard bound to `_` and therefore dropped immediately
+    DroppedGuard,
+    /// A builder whose `build()` panics when required setters were not called
+    PanickingBuilder,
+    /// A method panics on a `self` field another method initializes
+    PanicPath,
+    /// A `select!` branch awaits a future that is not cancellation safe
+    CancellationUnsafeSelect,
+    /// A spawned task whose `JoinHandle` is dropped
+    DetachedSpawn,
+    /// Two locks acquired in opposite orders by different code paths
+    LockOrderInversion,
+    /// Teardown of several fields ordered by hand
+    DropOrder,
+}
+
+/// A temporal coupling problem found in a file
+#[derive(Debug, Clone)]
+pub struct TemporalIssue {
+    /// Kind of problem
+    pub kind: TemporalIssueKind,
+    /// Method involved (e.g. "begin", "lock")
+    pub operation: String,
+    /// Function containing the call (`None` for module-level code)
+    pub function: Option<String>,
+    /// Line of the offending call (1-based, 0 if unknown)
+    pub line: usize,
+    /// Column of the offending call (1-based, 0 if unknown)
+    pub column: usize,
+    /// Human-readable description
+    pub message: String,
+}
+
+/// A guard-producing call bound with `let`
+#[derive(Debug, Clone)]
+pub struct GuardBinding {
+    /// Binding name (`_` for discarded guards)
+    pub name: String,
+    /// Guard-producing method
+    pub method: String,
+    /// Line of the binding
+    pub line: usize,
+}
+
+impl GuardBinding {
+    /// Whether the guard is dropped at the end of the statement
+    pub fn is_discarded(&self) -> bool {
+        self.name == "_"
+    }
+}
+
+/// How a builder makes sure its required fields are set
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequiredFields {
+    /// Type parameters track which fields are set (typestate)
+    Typestate,
+    /// Required values are passed to the builder's `new(..)`
+    Constructor,
+    /// `build()` returns a `Result` or `Option`
+    Checked,
+    /// `build()` unwraps or panics on unset fields
+    Panics,
+    /// `build()` cannot fail; unset fields fall back to defaults
+    Defaults,
+}
+
+/// A builder type found in a module
+#[derive(Debug, Clone)]
+pub struct BuilderPattern {
+    /// Builder type name (`FooBuilder`)
+    pub name: String,
+    /// Chained setters (`fn x(self, ..) -> Self`)
+    pub setters: Vec<String>,
+    pub required_fields: RequiredFields,
+    /// Line of `build()`
+    pub line: usize,
+    /// Column of `build()`
+    pub column: usize,
+}
+
+/// Builder-relevant parts of a type, gathered across its impl blocks
+#[derive(Debug, Default)]
+struct BuilderCandidate {
+    setters: Vec<String>,
+    /// `build()`: returns `Result`/`Option`, panics, position
+    build: Option<(bool, bool, (usize, usize))>,
+    /// `new(..)` takes arguments
+    constructor_args: bool,
+    /// Struct or impl blocks carry type parameters
+    typestate: bool,
+}
+
+/// Macros that stop a function when their condition does not hold
+pub const ASSERT_MACROS: &[&str] = &["assert", "debug_assert", "ensure"];
+
+/// Macros that never return
+const DIVERGING_MACROS: &[&str] = &["panic", "unreachable", "unimplemented", "bail"];
+
+/// Macros that panic
+const PANIC_MACROS: &[&str] = &["panic", "unreachable", "unimplemented"];
+
+/// Async operations that lose progress when their future is dropped
+pub const CANCEL_UNSAFE_METHODS: &[&str] = &[
+    "read_exact",
+    "read_to_end",
+    "read_to_string",
+    "read_line",
+    "read_until",
+    "write_all",
+    "write_all_buf",
+    "copy",
+    "copy_buf",
+];
+
+/// Methods acquiring a `Mutex` or `RwLock`
+const LOCK_METHODS: &[&str] = &["lock", "read", "write", "try_lock", "try_read", "try_write"];
+
+/// Functions releasing a value early: `drop`, `mem::forget` and the
+/// `ManuallyDrop` and `ptr` functions
+const RELEASE_FUNCTIONS: &[&str] = &["drop", "forget", "take", "into_inner", "drop_in_place"];
+
+/// Path segments of async runtimes whose `spawn` returns a `JoinHandle`
+const TASK_RUNTIMES: &[&str] = &["tokio", "task", "async_std", "smol"];
+
+/// `Option` adapters looked through when finding the field a call unwraps
+const OPTION_ADAPTERS: &[&str] = &[
+    "as_ref",
+    "as_mut",
+    "as_deref",
+    "as_deref_mut",
+    "take",
+    "clone",
+    "cloned",
+    "copied",
+];
+
+/// Operations the analysis looks for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemporalRules {
+    pub pairs: Vec<PairRule>,
+    pub guard_methods: Vec<String>,
+    /// Macros whose condition is recorded as a state check
+    pub assert_macros: Vec<String>,
+}
+
+impl Default for TemporalRules {
+    /// The built-in lists
+    fn default() -> Self {
+        Self {
+            pairs: PAIRED_OPS.iter().map(PairRule::from).collect(),
+            guard_methods: GUARD_METHODS.iter().map(|m| m.to_string()).collect(),
+            assert_macros: ASSERT_MACROS.iter().map(|m| m.to_string()).collect(),
+        }
+    }
+}
+
+/// `[temporal]` section of `.coupling.toml`
+///
+/// ```toml
+/// [temporal]
+/// unpaired_severity = "medium"
+/// guard_methods = ["checkout_conn"]
+/// state_check_macros = ["require"]
+///
+/// [[temporal.pairs]]
+/// open = "checkout"
+/// close = ["release_lease"]
+/// severity = "high"
+/// ```
+///
+/// A pair whose `open` matches a built-in pair replaces it; other pairs are
+/// added. With `replace_builtin = true` only the configured lists are used.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct TemporalConfig {
+    /// Drop the built-in pairs, guard methods and state-check macros
+    pub replace_builtin: bool,
+    /// Additional (or overriding) paired operations
+    pub pairs: Vec<PairRule>,
+    /// Additional guard-producing methods
+    pub guard_methods: Vec<String>,
+    /// Additional assert-like macros for state checks
+    pub state_check_macros: Vec<String>,
+    /// Severity of unpaired operations without a severity of their own
+    pub unpaired_severity: Severity,
+    /// Severity of guards dropped immediately
+    pub dropped_guard_severity: Severity,
+    /// Severity of builders whose `build()` panics on unset fields
+    pub panicking_builder_severity: Severity,
+    /// Severity of panics on state another method initializes
+    pub panic_path_severity: Severity,
+    /// Severity of `select!` branches that are not cancellation safe
+    pub cancellation_severity: Severity,
+    /// Severity of spawned tasks whose handle is dropped
+    pub detached_spawn_severity: Severity,
+    /// Severity of locks acquired in opposite orders
+    pub lock_order_severity: Severity,
+    /// Severity of teardowns ordered by hand
+    pub drop_order_severity: Severity,
+}
+
+impl Default for TemporalConfig {
+    fn default() -> Self {
+        Self {
+            replace_builtin: false,
+            pairs: Vec::new(),
+            guard_methods: Vec::new(),
+            state_check_macros: Vec::new(),
+            unpaired_severity: Severity::Medium,
+            dropped_guard_severity: Severity::High,
+            panicking_builder_severity: Severity::Medium,
+            panic_path_severity: Severity::High,
+            cancellation_severity: Severity::Medium,
+            detached_spawn_severity: Severity::Low,
+            lock_order_severity: Severity::High,
+            drop_order_severity: Severity::Low,
+        }
+    }
+}
+
+impl TemporalConfig {
+    /// Whether the configuration changes what the analysis detects
+    /// (as opposed to only the severities)
+    pub fn customizes_analysis(&self) -> bool {
+        self.replace_builtin
+            || !self.pairs.is_empty()
+            || !self.guard_methods.is_empty()
+            || !self.state_check_macros.is_empty()
+    }
+
+    /// Merge the configured lists with the built-in ones
+    pub fn rules(&self) -> TemporalRules {
+        let mut rules = if self.replace_builtin {
+            TemporalRules {
+                pairs: Vec::new(),
+                guard_methods: Vec::new(),
+                assert_macros: Vec::new(),
+            }
+        } else {
+            TemporalRules::default()
+        };
+
+        for pair in &self.pairs {
+            match rules.pairs.iter_mut().find(|p| p.open == pair.open) {
+                Some(existing) => *existing = pair.clone(),
+                None => rules.pairs.push(pair.clone()),
+            }
+        }
+        for (list, extra) in [
+            (&mut rules.guard_methods, &self.guard_methods),
+            (&mut rules.assert_macros, &self.state_check_macros),
+        ] {
+            for name in extra {
+                if !list.contains(name) {
+                    list.push(name.clone());
+                }
+            }
+        }
+        rules
+    }
+
+    /// Severity of a temporal finding
+    pub fn severity(&self, kind: TemporalIssueKind, operation: &str) -> Severity {
+        match kind {
+            TemporalIssueKind::UnpairedOperation => self
+                .pairs
+                .iter()
+                .find(|p| p.open == operation)
+                .and_then(|p| p.severity)
+                .unwrap_or(self.unpaired_severity),
+            TemporalIssueKind::DroppedGuard => self.dropped_guard_severity,
+            TemporalIssueKind::PanickingBuilder => self.panicking_builder_severity,
+            TemporalIssueKind::PanicPath => self.panic_path_severity,
+            TemporalIssueKind::CancellationUnsafeSelect => self.cancellation_severity,
+            TemporalIssueKind::DetachedSpawn => self.detached_spawn_severity,
+            TemporalIssueKind::LockOrderInversion => self.lock_order_severity,
+            TemporalIssueKind::DropOrder => self.drop_order_severity,
+        }
+    }
+}
+
+/// A method that refuses to proceed unless a `self` field is in some state
+#[derive(Debug, Clone)]
+pub struct StateCheck {
+    /// Self type of the enclosing impl block
+    pub owner: Option<String>,
+    /// Method performing the check
+    pub function: String,
+    /// Field the check reads
+    pub field: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// A method that assigns a `self` field (`self.connected = true`)
+#[derive(Debug, Clone)]
+pub struct StateWrite {
+    pub owner: Option<String>,
+    pub function: String,
+    pub field: String,
+}
+
+/// Temporal coupling data collected for a module
+#[derive(Debug, Clone, Default)]
+pub struct TemporalMetrics {
+    /// Types with a `Drop` implementation
+    pub drop_impls: Vec<String>,
+    /// Guard bindings found in the module
+    pub guard_bindings: Vec<GuardBinding>,
+    /// Lines of `unsafe` blocks
+    pub unsafe_blocks: Vec<usize>,
+    /// Lines of `unsafe fn` definitions
+    pub unsafe_fns: Vec<usize>,
+    /// Detected problems
+    pub issues: Vec<TemporalIssue>,
+    /// Guards on `self` fields
+    pub state_checks: Vec<StateCheck>,
+    /// Assignments to `self` fields
+    pub state_writes: Vec<StateWrite>,
+    /// Builder types
+    pub builders: Vec<BuilderPattern>,
+}
+
+impl TemporalMetrics {
+    /// Whether anything temporal was detected
+    pub fn is_empty(&self) -> bool {
+        self.drop_impls.is_empty()
+            && self.guard_bindings.is_empty()
+            && self.unsafe_blocks.is_empty()
+            && self.unsafe_fns.is_empty()
+            && self.issues.is_empty()
+            && self.state_checks.is_empty()
+            && self.builders.is_empty()
+    }
+}
+
+/// A method that panics unless a `self` field is set
+#[derive(Debug)]
+struct PanicSite {
+    owner: Option<String>,
+    function: String,
+    field: String,
+    /// `unwrap`, `expect` or the panicking macro
+    operation: String,
+    line: usize,
+    column: usize,
+}
+
+/// A name in the future of a `select!` branch
+#[derive(Debug)]
+struct SelectFuture {
+    function: Option<String>,
+    /// `tokio::select` or `select`
+    select: String,
+    name: String,
+    line: usize,
+    column: usize,
+}
+
+/// A lock acquired while another one is held
+#[derive(Debug)]
+struct LockOrder {
+    /// Self type for field locks, `None` for statics
+    owner: Option<String>,
+    held: String,
+    acquired: String,
+    function: Option<String>,
+    /// Lines of the two acquisitions
+    lines: (usize, usize),
+    column: usize,
+}
+
+/// A `self` field released by hand (`ManuallyDrop::drop(&mut self.f)`)
+#[derive(Debug)]
+struct ManualRelease {
+    owner: String,
+    function: String,
+    field: String,
+    /// Releasing function as written (`ManuallyDrop::drop`, `drop`)
+    call: String,
+    line: usize,
+    column: usize,
+}
+
+/// Paired-operation calls made inside one function
+#[derive(Debug, Default)]
+struct OperationScope {
+    /// Function name (`None` for module-level code)
+    function: Option<String>,
+    /// Positions (line, column) of opening calls, keyed by method name
+    opens: HashMap<String, Vec<(usize, usize)>>,
+    /// Number of closing calls, keyed by method name
+    closes: HashMap<String, usize>,
+    /// Whether this is `Drop::drop`, whose closes balance the whole module
+    is_drop: bool,
+}
+
+impl OperationScope {
+    fn close_count(&self, op: &PairRule) -> usize {
+        op.close
+            .iter()
+            .map(|c| self.closes.get(c).copied().unwrap_or(0))
+            .sum()
+    }
+}
+
+/// Opening calls left without a close when a function ends
+#[derive(Debug)]
+struct UnbalancedScope {
+    function: Option<String>,
+    op: PairRule,
+    open_lines: Vec<(usize, usize)>,
+    close_count: usize,
+}
+
+/// AST visitor that collects temporal coupling patterns
+///
+/// Paired operations are tracked per function: a function that opens twice
+/// and closes once is reported even if another function in the same file
+/// closes more often than it opens. Closing calls inside `Drop::drop` are
+/// the exception, since RAII cleanup balances opens anywhere in the module.
+#[derive(Debug, Default)]
+pub struct TemporalAnalyzer {
+    metrics: TemporalMetrics,
+    /// Paired operations, guard methods and assert macros to look for
+    rules: TemporalRules,
+    /// Scope for code outside any function
+    module_scope: OperationScope,
+    /// Stack of enclosing function scopes
+    scopes: Vec<OperationScope>,
+    /// Closing calls made in `Drop::drop`, keyed by method name
+    drop_closes: HashMap<String, usize>,
+    /// Functions whose paired operations did not balance
+    unbalanced: Vec<UnbalancedScope>,
+    /// Whether we are inside an `impl Drop for ...` block
+    in_drop_impl: bool,
+    /// Self type of the enclosing impl block
+    impl_owner: Option<String>,
+    /// Types that may be builders, by name
+    builders: BTreeMap<String, BuilderCandidate>,
+    /// Panics on `self` fields, resolved against the writes in `finish`
+    panic_sites: Vec<PanicSite>,
+    /// Names in `select!` branch futures, resolved in `finish`
+    select_futures: Vec<SelectFuture>,
+    /// Types implementing `Future` with their own `poll`
+    manual_futures: Vec<String>,
+    /// `Mutex`/`RwLock` fields (owner, field) and statics (`None`, name)
+    locks: Vec<(Option<String>, String)>,
+    /// Guards alive at the current point: binding, lock and its line
+    held_locks: Vec<(String, (Option<String>, String), usize)>,
+    /// Nested acquisitions, resolved against `locks` in `finish`
+    lock_orders: Vec<LockOrder>,
+    /// Fields used by each type's `Drop::drop` in order, and its position
+    drop_fields: BTreeMap<String, (Vec<String>, (usize, usize))>,
+    /// Fields released early, resolved per type in `finish`
+    manual_releases: Vec<ManualRelease>,
+    /// Functions declared in the file's `extern` blocks
+    foreign_functions: Vec<String>,
+}
+
+impl TemporalAnalyzer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an analyzer with custom rules
+    pub fn with_rules(rules: TemporalRules) -> Self {
+        Self {
+            rules,
+            ..Self::default()
+        }
+    }
+
+    /// Analyze a parsed file with the built-in rules
+    pub fn analyze(file: &syn::File) -> TemporalMetrics {
+        Self::analyze_with(file, &TemporalRules::default())
+    }
+
+    /// Analyze a parsed file with custom rules
+    pub fn analyze_with(file: &syn::File, rules: &TemporalRules) -> TemporalMetrics {
+        let mut analyzer = Self::with_rules(rules.clone());
+        analyzer.add_foreign_pairs(file);
+        analyzer.visit_file(file);
+        analyzer.finish()
+    }
+
+    /// Check the allocate/free pairs among the file's foreign functions
+    /// like the configured paired operations
+    fn add_foreign_pairs(&mut self, file: &syn::File) {
+        struct Foreign(Vec<String>);
+        impl<'ast> Visit<'ast> for Foreign {
+            fn visit_foreign_item_fn(&mut self, node: &'ast syn::ForeignItemFn) {
+                self.0.push(node.sig.ident.to_string());
+            }
+        }
+        let mut foreign = Foreign(Vec::new());
+        foreign.visit_file(file);
+        for (open, close) in alloc_free_pairs(foreign.0.iter().map(String::as_str)) {
+            if !self.rules.pairs.iter().any(|op| op.open == open) {
+                self.rules.pairs.push(PairRule {
+                    open,
+                    close: vec![close],
+                    severity: None,
+                });
+            }
+        }
+        self.foreign_functions = foreign.0;
+    }
+
+    /// Parse and analyze source code
+    pub fn analyze_source(content: &str) -> Result<TemporalMetrics, syn::Error> {
+        let file = syn::parse_file(content)?;
+        Ok(Self::analyze(&file))
+    }
+
+    /// Resolve paired operation counts into issues
+    pub fn finish(mut self) -> TemporalMetrics {
+        let module_scope = std::mem::take(&mut self.module_scope);
+        self.close_scope(module_scope);
+
+        let mut unpaired: Vec<TemporalIssue> = Vec::new();
+        for scope in &self.unbalanced {
+            // Closes performed by Drop impls can balance the remaining opens
+            let drop_count: usize = scope
+                .op
+                .close
+                .iter()
+                .map(|c| self.drop_closes.get(c).copied().unwrap_or(0))
+                .sum();
+            let balanced = scope.close_count + drop_count;
+            if scope.open_lines.len() <= balanced {
+                continue;
+            }
+
+            let location = match &scope.function {
+                Some(name) => format!("`{}` calls", name),
+                None => "Module-level code calls".to_string(),
+            };
+            // Report the opens that have no matching close
+            for &(line, column) in scope.open_lines.iter().skip(balanced) {
+                unpaired.push(TemporalIssue {
+                    kind: TemporalIssueKind::UnpairedOperation,
+                    operation: scope.op.open.clone(),
+                    function: scope.function.clone(),
+                    line,
+                    column,
+                    message: format!(
+                        "{} `{}()` {} time(s) but `{}()` only {} time(s)",
+                        location,
+                        scope.op.open,
+                        scope.open_lines.len(),
+                        scope.op.close.join("()`/`"),
+                        scope.close_count
+                    ),
+                });
+            }
+        }
+
+        self.metrics.issues.extend(unpaired);
+        self.resolve_builders();
+        self.resolve_panic_sites();
+        self.resolve_select_futures();
+        self.resolve_lock_orders();
+        self.resolve_drop_orders();
+        self.metrics.issues.sort_by_key(|i| (i.line, i.column));
+        self.metrics
+    }
+
+    /// Turn builder candidates into builders, reporting panicking `build()`s
+    fn resolve_builders(&mut self) {
+        for (name, candidate) in std::mem::take(&mut self.builders) {
+            let Some((fallible, panics, (line, column))) = candidate.build else {
+                continue;
+            };
+            let setters = candidate.setters.len();
+            if setters == 0 || (setters < 2 && !name.ends_with("Builder")) {
+                continue;
+            }
+
+            let required_fields = if candidate.typestate {
+                RequiredFields::Typestate
+            } else if candidate.constructor_args {
+                RequiredFields::Constructor
+            } else if fallible {
+                RequiredFields::Checked
+            } else if panics {
+                RequiredFields::Panics
+            } else {
+                RequiredFields::Defaults
+            };
+            if required_fields == RequiredFields::Panics {
+                self.metrics.issues.push(TemporalIssue {
+                    kind: TemporalIssueKind::PanickingBuilder,
+                    operation: "build".to_string(),
+                    function: Some(format!("{}::build", name)),
+                    line,
+                    column,
+                    message: format!(
+                        "`{}::build()` panics unless the right setters were called first",
+                        name
+                    ),
+                });
+            }
+            self.metrics.builders.push(BuilderPattern {
+                name,
+                setters: candidate.setters,
+                required_fields,
+                line,
+                column,
+            });
+        }
+    }
+
+    /// Report panics on fields that only other methods of the type assign
+    ///
+    /// Unwraps in a builder's `build()` are left to the builder analysis.
+    fn resolve_panic_sites(&mut self) {
+        for site in std::mem::take(&mut self.panic_sites) {
+            if site.function == "build"
+                && self
+                    .metrics
+                    .builders
+                    .iter()
+                    .any(|b| site.owner.as_ref() == Some(&b.name))
+            {
+                continue;
+            }
+            let writes = |function: &str| {
+                self.metrics.state_writes.iter().any(|w| {
+                    w.owner == site.owner && w.field == site.field && w.function == function
+                })
+            };
+            if writes(&site.function) {
+                continue;
+            }
+            let mut writers: Vec<&str> = self
+                .metrics
+                .state_writes
+                .iter()
+                .filter(|w| w.owner == site.owner && w.field == site.field)
+                .map(|w| w.function.as_str())
+                .collect();
+            writers.sort_unstable();
+            writers.dedup();
+            if writers.is_empty() {
+                continue;
+            }
+            let function = match &site.owner {
+                Some(owner) => format!("{}::{}", owner, site.function),
+                None => site.function.clone(),
+            };
+            let message = format!(
+                "`{}` panics ({}) unless `self.{}` was set by `{}()` first",
+                function,
+                site.operation,
+                site.field,
+                writers.join("()`/`")
+            );
+            self.metrics.issues.push(TemporalIssue {
+                kind: TemporalIssueKind::PanicPath,
+                operation: site.operation,
+                function: Some(function),
+                line: site.line,
+                column: site.column,
+                message,
+            });
+        }
+    }
+
+    /// Report `select!` branches awaiting operations or hand-written
+    /// futures that lose their progress when another branch wins
+    fn resolve_select_futures(&mut self) {
+        for future in std::mem::take(&mut self.select_futures) {
+            let what = if CANCEL_UNSAFE_METHODS.contains(&future.name.as_str()) {
+                format!("`{}()`, which is not cancellation safe", future.name)
+            } else if self.manual_futures.contains(&future.name) {
+                format!("the hand-written future `{}`", future.name)
+            } else {
+                continue;
+            };
+            self.metrics.issues.push(TemporalIssue {
+                kind: TemporalIssueKind::CancellationUnsafeSelect,
+                operation: future.name,
+                function: future.function,
+                line: future.line,
+                column: future.column,
+                message: format!(
+                    "`{}!` branch awaits {}: its progress is lost when another branch completes first",
+                    future.select, what
+                ),
+            });
+        }
+    }
+
+    /// Report pairs of locks that are taken in both orders
+    fn resolve_lock_orders(&mut self) {
+        let orders: Vec<&LockOrder> = self
+            .lock_orders
+            .iter()
+            .filter(|o| {
+                self.locks.contains(&(o.owner.clone(), o.held.clone()))
+                    && self.locks.contains(&(o.owner.clone(), o.acquired.clone()))
+            })
+            .collect();
+        let mut reported: Vec<(&Option<String>, &str, &str)> = Vec::new();
+        let mut issues = Vec::new();
+        for first in &orders {
+            let Some(second) = orders.iter().find(|o| {
+                o.owner == first.owner && o.held == first.acquired && o.acquired == first.held
+            }) else {
+                continue;
+            };
+            let pair = (&first.owner, first.held.as_str(), first.acquired.as_str());
+            let reverse = (&first.owner, first.acquired.as_str(), first.held.as_str());
+            if reported.contains(&pair) || reported.contains(&reverse) {
+                continue;
+            }
+            reported.push(pair);
+
+            let sequence = |order: &LockOrder| {
+                format!(
+                    "`{}` locks `{}` then `{}` (lines {} and {})",
+                    order.function.as_deref().unwrap_or("module-level code"),
+                    order.held,
+                    order.acquired,
+                    order.lines.0,
+                    order.lines.1
+                )
+            };
+            let owner = match &first.owner {
+                Some(owner) => format!("`{}` ", owner),
+                None => String::new(),
+            };
+            issues.push(TemporalIssue {
+                kind: TemporalIssueKind::LockOrderInversion,
+                operation: "lock".to_string(),
+                function: second.function.clone(),
+                line: second.lines.1,
+                column: second.column,
+                message: format!(
+                    "{}locks taken in opposite orders: {}, but {}; running both concurrently can deadlock",
+                    owner,
+                    sequence(first),
+                    sequence(second)
+                ),
+            });
+        }
+        self.metrics.issues.extend(issues);
+    }
+
+    /// Report types whose fields are torn down in a hand-written order:
+    /// a `Drop::drop` using several fields, or fields released by hand
+    fn resolve_drop_orders(&mut self) {
+        let mut owners: Vec<&String> = self.drop_fields.keys().collect();
+        owners.extend(self.manual_releases.iter().map(|r| &r.owner));
+        owners.sort();
+        owners.dedup();
+
+        let mut issues = Vec::new();
+        for owner in owners {
+            let mut parts = Vec::new();
+            let mut location = None;
+            if let Some((fields, position)) = self.drop_fields.get(owner)
+                && fields.len() >= 2
+            {
+                let fields: Vec<String> = fields.iter().map(|f| format!("`{}`", f)).collect();
+                parts.push(format!("`drop()` uses {}", fields.join(" then ")));
+                location = Some(("drop".to_string(), *position));
+            }
+            for release in self.manual_releases.iter().filter(|r| &r.owner == owner) {
+                parts.push(format!(
+                    "`{}()` releases `{}` with `{}`",
+                    release.function, release.field, release.call
+                ));
+                // Releases outside `drop` are the ones callers must order
+                if release.function != "drop" || location.is_none() {
+                    location = Some((release.function.clone(), (release.line, release.column)));
+                }
+            }
+            let Some((function, (line, column))) = location else {
+                continue;
+            };
+            issues.push(TemporalIssue {
+                kind: TemporalIssueKind::DropOrder,
+                operation: "drop".to_string(),
+                function: Some(function),
+                line,
+                column,
+                message: format!(
+                    "`{}` tears its fields down in a hand-written order: {}; reordering the fields or these calls changes what is still alive",
+                    owner,
+                    parts.join("; ")
+                ),
+            });
+        }
+        self.metrics.issues.extend(issues);
+    }
+
+    fn record_unsafe_fn(&mut self, sig: &Signature) {
+        if let Some(unsafety) = &sig.unsafety {
+            self.metrics.unsafe_fns.push(unsafety.span.start().line);
+        }
+    }
+
+    /// Record a `drop(self.f)`-style call releasing a `self` field early
+    fn record_release(&mut self, call: &syn::ExprCall) {
+        let Expr::Path(func) = &*call.func else {
+            return;
+        };
+        let segments: Vec<String> = func
+            .path
+            .segments
+            .iter()
+            .map(|s| s.ident.to_string())
+            .collect();
+        let Some(name) = segments.last() else {
+            return;
+        };
+        let qualifier = segments.iter().rev().nth(1).map(String::as_str);
+        let releases = RELEASE_FUNCTIONS.contains(&name.as_str())
+            && match name.as_str() {
+                "drop" => matches!(qualifier, None | Some("mem" | "ManuallyDrop")),
+                "forget" => qualifier == Some("mem"),
+                "drop_in_place" => qualifier == Some("ptr"),
+                _ => qualifier == Some("ManuallyDrop"),
+            };
+        if !releases {
+            return;
+        }
+        let (Some(owner), Some(function)) = (self.impl_owner.clone(), self.current_function())
+        else {
+            return;
+        };
+        let Some(field) = call.args.first().and_then(unwrapped_field) else {
+            return;
+        };
+        let (line, column) = span_position(func.span());
+        let call = match qualifier {
+            Some(qualifier) => format!("{}::{}", qualifier, name),
+            None => name.clone(),
+        };
+        self.manual_releases.push(ManualRelease {
+            owner,
+            function,
+            field,
+            call,
+            line,
+            column,
+        });
+    }
+
+    /// Record the acquisition of the lock `receiver` names, if it is a
+    /// `self` field or a `static`
+    fn record_lock(&mut self, receiver: &Expr, position: (usize, usize)) {
+        let Some(lock) = lock_key(receiver, &self.impl_owner) else {
+            return;
+        };
+        let function = self.current_function();
+        for (_, held, line) in &self.held_locks {
+            if held.0 == lock.0 && held.1 != lock.1 {
+                self.lock_orders.push(LockOrder {
+                    owner: lock.0.clone(),
+                    held: held.1.clone(),
+                    acquired: lock.1.clone(),
+                    function: function.clone(),
+                    lines: (*line, position.0),
+                    column: position.1,
+                });
+            }
+        }
+    }
+
+    /// Report a spawn whose `JoinHandle` is dropped
+    fn record_detached_spawn(&mut self, call: &Expr) {
+        let Expr::Call(call) = call else {
+            return;
+        };
+        let Expr::Path(func) = &*call.func else {
+            return;
+        };
+        let segments: Vec<String> = func
+            .path
+            .segments
+            .iter()
+            .map(|s| s.ident.to_string())
+            .collect();
+        let Some((last, runtime)) = segments.split_last() else {
+            return;
+        };
+        if !matches!(last.as_str(), "spawn" | "spawn_local")
+            || !runtime.iter().any(|s| TASK_RUNTIMES.contains(&s.as_str()))
+        {
+            return;
+        }
+        let path = segments.join("::");
+        let (line, column) = span_position(func.span());
+        self.metrics.issues.push(TemporalIssue {
+            kind: TemporalIssueKind::DetachedSpawn,
+            operation: last.clone(),
+            function: self.current_function(),
+            line,
+            column,
+            message: format!(
+                "Handle of `{}()` is dropped: the task can neither be awaited nor aborted, and its panics go unnoticed",
+                path
+            ),
+        });
+    }
+
+    /// Remember a panic on `field` in the current method
+    fn record_panic_site(&mut self, field: String, operation: String, position: (usize, usize)) {
+        let Some(function) = self.current_function() else {
+            return;
+        };
+        self.panic_sites.push(PanicSite {
+            owner: self.impl_owner.clone(),
+            function,
+            field,
+            operation,
+            line: position.0,
+            column: position.1,
+        });
+    }
+
+    /// Record the builder-relevant methods of an impl block
+    fn record_builder_methods(&mut self, node: &ItemImpl, owner: &str) {
+        let candidate = self.builders.entry(owner.to_string()).or_default();
+        if let Type::Path(type_path) = &*node.self_ty
+            && type_path
+                .path
+                .segments
+                .last()
+                .is_some_and(|s| !matches!(s.arguments, PathArguments::None))
+        {
+            candidate.typestate = true;
+        }
+
+        for item in &node.items {
+            let ImplItem::Fn(method) = item else {
+                continue;
+            };
+            let sig = &method.sig;
+            if sig.ident == "build" && sig.receiver().is_some() {
+                candidate.build = Some((
+                    returns_fallible(sig),
+                    panics(&method.block),
+                    span_position(sig.ident.span()),
+                ));
+            } else if sig.ident == "new" && sig.receiver().is_none() {
+                candidate.constructor_args = !sig.inputs.is_empty();
+            } else if sig
+                .receiver()
+                .is_some_and(|r| r.reference.is_none() || r.mutability.is_some())
+                && returns_self(&sig.output, owner)
+            {
+                candidate.setters.push(sig.ident.to_string());
+            }
+        }
+    }
+
+    /// Enter a function body
+    fn open_scope(&mut self, name: String) {
+        let is_drop = self.in_drop_impl && name == "drop";
+        self.scopes.push(OperationScope {
+            function: Some(name),
+            is_drop,
+            ..Default::default()
+        });
+    }
+
+    /// Leave a function body and remember any imbalance
+    fn close_scope(&mut self, scope: OperationScope) {
+        if scope.is_drop {
+            for (name, count) in scope.closes {
+                *self.drop_closes.entry(name).or_insert(0) += count;
+            }
+            return;
+        }
+
+        for op in &self.rules.pairs {
+            let Some(open_lines) = scope.opens.get(&op.open) else {
+                continue;
+            };
+            let close_count = scope.close_count(op);
+            if open_lines.len() > close_count {
+                self.unbalanced.push(UnbalancedScope {
+                    function: scope.function.clone(),
+                    op: op.clone(),
+                    open_lines: open_lines.clone(),
+                    close_count,
+                });
+            }
+        }
+    }
+
+    fn current_scope(&mut self) -> &mut OperationScope {
+        self.scopes.last_mut().unwrap_or(&mut self.module_scope)
+    }
+
+    fn current_function(&self) -> Option<String> {
+        self.scopes.last().and_then(|s| s.function.clone())
+    }
+
+    /// Record a state check on every `self` field read by `condition`
+    fn record_state_check(&mut self, condition: &Expr, position: (usize, usize)) {
+        let Some(function) = self.current_function() else {
+            return;
+        };
+        for field in self_fields(condition) {
+            self.metrics.state_checks.push(StateCheck {
+                owner: self.impl_owner.clone(),
+                function: function.clone(),
+                field,
+                line: position.0,
+                column: position.1,
+            });
+        }
+    }
+
+    fn record_method(&mut self, name: &str, position: (usize, usize)) {
+        let is_open = self.rules.pairs.iter().any(|op| op.open == name);
+        let is_close = self
+            .rules
+            .pairs
+            .iter()
+            .any(|op| op.close.iter().any(|c| c == name));
+        let scope = self.current_scope();
+        if is_open {
+            scope
+                .opens
+                .entry(name.to_string())
+                .or_default()
+                .push(position);
+        }
+        if is_close {
+            *scope.closes.entry(name.to_string()).or_insert(0) += 1;
+        }
+    }
+}
+
+/// Find the guard-producing method in an initializer, looking through
+/// `.unwrap()`, `.expect(..)`, `?` and `.await`
+fn guard_method(expr: &Expr, guard_methods: &[String]) -> Option<String> {
+    match expr {
+        Expr::MethodCall(call) => {
+            let name = call.method.to_string();
+            if guard_methods.contains(&name) {
+                Some(name)
+            } else if matches!(name.as_str(), "unwrap" | "expect") {
+                guard_method(&call.receiver, guard_methods)
+            } else {
+                None
+            }
+        }
+        Expr::Try(e) => guard_method(&e.expr, guard_methods),
+        Expr::Await(e) => guard_method(&e.base, guard_methods),
+        Expr::Paren(e) => guard_method(&e.expr, guard_methods),
+        _ => None,
+    }
+}
+
+/// Whether a method returns `Self`, `&mut Self` or the owner type
+fn returns_self(output: &ReturnType, owner: &str) -> bool {
+    fn is_self(ty: &Type, owner: &str) -> bool {
+        match ty {
+            Type::Path(path) => path
+                .path
+                .segments
+                .last()
+                .is_some_and(|s| s.ident == "Self" || s.ident == owner),
+            Type::Reference(reference) => is_self(&reference.elem, owner),
+            _ => false,
+        }
+    }
+    matches!(output, ReturnType::Type(_, ty) if is_self(ty, owner))
+}
+
+/// Whether a function returns a `Result` or `Option`
+fn returns_fallible(sig: &Signature) -> bool {
+    matches!(&sig.output, ReturnType::Type(_, ty) if matches!(&**ty, Type::Path(path)
+        if path.path.segments.last().is_some_and(|s| s.ident == "Result" || s.ident == "Option")))
+}
+
+/// Whether a block can panic (`unwrap`, `expect`, `panic!`, ...)
+fn panics(block: &syn::Block) -> bool {
+    struct Panics(bool);
+    impl<'ast> Visit<'ast> for Panics {
+        fn visit_expr_method_call(&mut self, node: &'ast ExprMethodCall) {
+            self.0 |= node.method == "unwrap" || node.method == "expect";
+            syn::visit::visit_expr_method_call(self, node);
+        }
+
+        fn visit_macro(&mut self, node: &'ast Macro) {
+            self.0 |= DIVERGING_MACROS.contains(&macro_name(node).as_str());
+            syn::visit::visit_macro(self, node);
+        }
+    }
+    let mut finder = Panics(false);
+    finder.visit_block(block);
+    finder.0
+}
+
+/// Field name of a `self.field` expression
+fn self_field(expr: &Expr) -> Option<String> {
+    if let Expr::Field(field) = expr
+        && let Expr::Path(base) = &*field.base
+        && base.path.is_ident("self")
+        && let Member::Named(name) = &field.member
+    {
+        return Some(name.to_string());
+    }
+    None
+}
+
+/// `self` field an `unwrap()` receiver reads, looking through `Option`
+/// adapters (`self.conn.as_ref()`), references and parentheses
+fn unwrapped_field(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::MethodCall(call) if OPTION_ADAPTERS.contains(&call.method.to_string().as_str()) => {
+            unwrapped_field(&call.receiver)
+        }
+        Expr::Reference(e) => unwrapped_field(&e.expr),
+        Expr::Paren(e) => unwrapped_field(&e.expr),
+        _ => self_field(expr),
+    }
+}
+
+/// `self` fields in order of first use
+struct SelfFields(Vec<String>);
+
+impl<'ast> Visit<'ast> for SelfFields {
+    fn visit_expr(&mut self, node: &'ast Expr) {
+        match self_field(node) {
+            Some(name) if !self.0.contains(&name) => self.0.push(name),
+            Some(_) => {}
+            None => syn::visit::visit_expr(self, node),
+        }
+    }
+}
+
+/// `self` fields read anywhere in an expression
+fn self_fields(expr: &Expr) -> Vec<String> {
+    let mut fields = SelfFields(Vec::new());
+    fields.visit_expr(expr);
+    fields.0
+}
+
+fn macro_name(mac: &Macro) -> String {
+    mac.path
+        .segments
+        .last()
+        .map(|s| s.ident.to_string())
+        .unwrap_or_default()
+}
+
+/// Whether a block leaves the function (`return`, `panic!`, ...)
+fn diverges(block: &syn::Block) -> bool {
+    block.stmts.iter().any(|stmt| match stmt {
+        Stmt::Expr(Expr::Return(_), _) => true,
+        Stmt::Expr(Expr::Macro(m), _) => DIVERGING_MACROS.contains(&macro_name(&m.mac).as_str()),
+        Stmt::Macro(m) => DIVERGING_MACROS.contains(&macro_name(&m.mac).as_str()),
+        _ => false,
+    })
+}
+
+/// Panicking macro a block ends the function with, if any
+fn panic_macro(block: &syn::Block) -> Option<String> {
+    block.stmts.iter().find_map(|stmt| {
+        let mac = match stmt {
+            Stmt::Expr(Expr::Macro(m), _) => &m.mac,
+            Stmt::Macro(m) => &m.mac,
+            _ => return None,
+        };
+        let name = macro_name(mac);
+        PANIC_MACROS.contains(&name.as_str()).then_some(name)
+    })
+}
+
+/// Lock a receiver names: a `self` field (keyed by the impl's type) or a
+/// `static` in upper case
+fn lock_key(receiver: &Expr, owner: &Option<String>) -> Option<(Option<String>, String)> {
+    if let Some(field) = self_field(receiver) {
+        return owner.clone().map(|owner| (Some(owner), field));
+    }
+    if let Expr::Path(path) = receiver
+        && let Some(ident) = path.path.get_ident()
+    {
+        let name = ident.to_string();
+        if name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c == '_' || c.is_ascii_digit())
+        {
+            return Some((None, name));
+        }
+    }
+    None
+}
+
+/// Lock acquired by a `let` initializer, looking through `.unwrap()`,
+/// `.expect(..)`, `?` and `.await`
+fn bound_lock(expr: &Expr, owner: &Option<String>) -> Option<(Option<String>, String)> {
+    match expr {
+        Expr::MethodCall(call) => {
+            let name = call.method.to_string();
+            if LOCK_METHODS.contains(&name.as_str()) {
+                lock_key(&call.receiver, owner)
+            } else if matches!(name.as_str(), "unwrap" | "expect") {
+                bound_lock(&call.receiver, owner)
+            } else {
+                None
+            }
+        }
+        Expr::Try(e) => bound_lock(&e.expr, owner),
+        Expr::Await(e) => bound_lock(&e.base, owner),
+        Expr::Paren(e) => bound_lock(&e.expr, owner),
+        _ => None,
+    }
+}
+
+/// Whether a type mentions `Mutex` or `RwLock`
+fn is_lock_type(ty: &Type) -> bool {
+    struct Finder(bool);
+    impl<'ast> Visit<'ast> for Finder {
+        fn visit_path_segment(&mut self, node: &'ast syn::PathSegment) {
+            self.0 |= node.ident == "Mutex" || node.ident == "RwLock";
+            syn::visit::visit_path_segment(self, node);
+        }
+    }
+    let mut finder = Finder(false);
+    finder.visit_type(ty);
+    finder.0
+}
+
+/// Names in the futures of a `select!` body (`pat = future => handler`)
+///
+/// Preconditions (`, if cond`) and handlers are skipped: handlers run to
+/// completion once their branch is chosen.
+fn select_future_names(tokens: TokenStream) -> Vec<Ident> {
+    #[derive(PartialEq)]
+    enum Part {
+        Pattern,
+        Future,
+        Precondition,
+        /// Whether the handler has started
+        Handler(bool),
+    }
+    fn idents(tree: TokenTree, out: &mut Vec<Ident>) {
+        match tree {
+            TokenTree::Ident(ident) => out.push(ident),
+            TokenTree::Group(group) => group.stream().into_iter().for_each(|t| idents(t, out)),
+            _ => {}
+        }
+    }
+
+    let mut names = Vec::new();
+    let mut part = Part::Pattern;
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(tree) = tokens.next() {
+        let arrow = matches!(&tree, TokenTree::Punct(p) if p.as_char() == '=' && p.spacing() == Spacing::Joint)
+            && matches!(tokens.peek(), Some(TokenTree::Punct(p)) if p.as_char() == '>');
+        if arrow && part != Part::Handler(true) {
+            tokens.next();
+            part = Part::Handler(false);
+            continue;
+        }
+        match (&part, &tree) {
+            (Part::Pattern, TokenTree::Punct(p)) if p.as_char() == '=' => part = Part::Future,
+            (Part::Future, TokenTree::Punct(p)) if p.as_char() == ',' => part = Part::Precondition,
+            (Part::Future, _) => idents(tree, &mut names),
+            (Part::Handler(false), TokenTree::Group(g)) if g.delimiter() == Delimiter::Brace => {
+                part = Part::Pattern
+            }
+            (Part::Handler(_), TokenTree::Punct(p)) if p.as_char() == ',' => part = Part::Pattern,
+            (Part::Handler(_), _) => part = Part::Handler(true),
+            _ => {}
+        }
+    }
+    names
+}
+
+fn binding_name(pat: &Pat) -> Option<String> {
+    match pat {
+        Pat::Wild(_) => Some("_".to_string()),
+        Pat::Ident(ident) => Some(ident.ident.to_string()),
+        Pat::Type(typed) => binding_name(&typed.pat),
+        _ => None,
+    }
+}
+
+impl<'ast> Visit<'ast> for TemporalAnalyzer {
+    fn visit_item_fn(&mut self, node: &'ast ItemFn) {
+        self.record_unsafe_fn(&node.sig);
+        self.open_scope(node.sig.ident.to_string());
+        syn::visit::visit_item_fn(self, node);
+        if let Some(scope) = self.scopes.pop() {
+            self.close_scope(scope);
+        }
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast ImplItemFn) {
+        self.record_unsafe_fn(&node.sig);
+        if self.in_drop_impl
+            && node.sig.ident == "drop"
+            && let Some(owner) = self.impl_owner.clone()
+        {
+            let mut fields = SelfFields(Vec::new());
+            fields.visit_block(&node.block);
+            let position = span_position(node.sig.ident.span());
+            self.drop_fields.insert(owner, (fields.0, position));
+        }
+        self.open_scope(node.sig.ident.to_string());
+        syn::visit::visit_impl_item_fn(self, node);
+        if let Some(scope) = self.scopes.pop() {
+            self.close_scope(scope);
+        }
+    }
+
+    fn visit_trait_item_fn(&mut self, node: &'ast TraitItemFn) {
+        self.record_unsafe_fn(&node.sig);
+        self.open_scope(node.sig.ident.to_string());
+        syn::visit::visit_trait_item_fn(self, node);
+        if let Some(scope) = self.scopes.pop() {
+            self.close_scope(scope);
+        }
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast ExprMethodCall) {
+        let position = span_position(node.method.span());
+        let method = node.method.to_string();
+        self.record_method(&method, position);
+        if LOCK_METHODS.contains(&method.as_str()) {
+            self.record_lock(&node.receiver, position);
+        }
+        if matches!(method.as_str(), "unwrap" | "expect")
+            && let Some(field) = unwrapped_field(&node.receiver)
+        {
+            self.record_panic_site(field, method, position);
+        }
+        syn::visit::visit_expr_method_call(self, node);
+    }
+
+    fn visit_item_impl(&mut self, node: &'ast ItemImpl) {
+        let previous = self.impl_owner.take();
+        if let syn::Type::Path(type_path) = &*node.self_ty {
+            self.impl_owner = type_path.path.segments.last().map(|s| s.ident.to_string());
+        }
+
+        let is_drop = node.trait_.as_ref().is_some_and(|(_, trait_path, _)| {
+            trait_path
+                .segments
+                .last()
+                .is_some_and(|s| s.ident == "Drop")
+        });
+        let is_future = node.trait_.as_ref().is_some_and(|(_, trait_path, _)| {
+            trait_path
+                .segments
+                .last()
+                .is_some_and(|s| s.ident == "Future")
+        });
+        if is_future
+            && let Some(owner) = &self.impl_owner
+            && node
+                .items
+                .iter()
+                .any(|item| matches!(item, ImplItem::Fn(f) if f.sig.ident == "poll"))
+        {
+            self.manual_futures.push(owner.clone());
+        }
+        if is_drop && let Some(owner) = &self.impl_owner {
+            self.metrics.drop_impls.push(owner.clone());
+            self.in_drop_impl = true;
+        }
+        if node.trait_.is_none()
+            && let Some(owner) = self.impl_owner.clone()
+        {
+            self.record_builder_methods(node, &owner);
+        }
+        syn::visit::visit_item_impl(self, node);
+        self.in_drop_impl = false;
+        self.impl_owner = previous;
+    }
+
+    fn visit_item_struct(&mut self, node: &'ast ItemStruct) {
+        for field in &node.fields {
+            if let Some(name) = &field.ident
+                && is_lock_type(&field.ty)
+            {
+                self.locks
+                    .push((Some(node.ident.to_string()), name.to_string()));
+            }
+        }
+        if node.generics.type_params().next().is_some() {
+            self.builders
+                .entry(node.ident.to_string())
+                .or_default()
+                .typestate = true;
+        }
+        syn::visit::visit_item_struct(self, node);
+    }
+
+    fn visit_expr_if(&mut self, node: &'ast ExprIf) {
+        // `if !self.ready { return ...; }` guards the rest of the function
+        if node.else_branch.is_none() && diverges(&node.then_branch) {
+            let position = span_position(node.if_token.span);
+            self.record_state_check(&node.cond, position);
+            if let Some(name) = panic_macro(&node.then_branch) {
+                for field in self_fields(&node.cond) {
+                    self.record_panic_site(field, format!("{}!", name), position);
+                }
+            }
+        }
+        syn::visit::visit_expr_if(self, node);
+    }
+
+    fn visit_macro(&mut self, node: &'ast Macro) {
+        let name = macro_name(node);
+        if matches!(name.as_str(), "select" | "select_biased") {
+            let select = node
+                .path
+                .segments
+                .iter()
+                .map(|s| s.ident.to_string())
+                .collect::<Vec<_>>()
+                .join("::");
+            for ident in select_future_names(node.tokens.clone()) {
+                let (line, column) = span_position(ident.span());
+                self.select_futures.push(SelectFuture {
+                    function: self.current_function(),
+                    select: select.clone(),
+                    name: ident.to_string(),
+                    line,
+                    column,
+                });
+            }
+        }
+        if self.rules.assert_macros.contains(&macro_name(node))
+            && let Ok(args) = node.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated)
+            && let Some(condition) = args.first()
+        {
+            self.record_state_check(condition, span_position(node.path.span()));
+        }
+        syn::visit::visit_macro(self, node);
+    }
+
+    fn visit_expr_assign(&mut self, node: &'ast ExprAssign) {
+        if let Some(field) = self_field(&node.left)
+            && let Some(function) = self.current_function()
+        {
+            self.metrics.state_writes.push(StateWrite {
+                owner: self.impl_owner.clone(),
+                function,
+                field,
+            });
+        }
+        syn::visit::visit_expr_assign(self, node);
+    }
+
+    fn visit_item_static(&mut self, node: &'ast syn::ItemStatic) {
+        if is_lock_type(&node.ty) {
+            self.locks.push((None, node.ident.to_string()));
+        }
+        syn::visit::visit_item_static(self, node);
+    }
+
+    fn visit_block(&mut self, node: &'ast syn::Block) {
+        // Guards bound in the block are dropped at its end
+        let held = self.held_locks.len();
+        syn::visit::visit_block(self, node);
+        self.held_locks.truncate(held);
+    }
+
+    fn visit_expr_call(&mut self, node: &'ast syn::ExprCall) {
+        self.record_release(node);
+        if let Expr::Path(func) = &*node.func
+            && let Some(last) = func.path.segments.last()
+            && self.foreign_functions.iter().any(|f| last.ident == f)
+        {
+            self.record_method(&last.ident.to_string(), span_position(last.ident.span()));
+        }
+        // `drop(guard)` releases the lock early
+        if let Expr::Path(func) = &*node.func
+            && func.path.segments.last().is_some_and(|s| s.ident == "drop")
+            && let Some(Expr::Path(arg)) = node.args.first()
+            && let Some(ident) = arg.path.get_ident()
+        {
+            self.held_locks.retain(|(name, _, _)| ident != name);
+        }
+        syn::visit::visit_expr_call(self, node);
+    }
+
+    fn visit_stmt(&mut self, node: &'ast Stmt) {
+        // `tokio::spawn(fut);`
+        if let Stmt::Expr(expr, Some(_)) = node {
+            self.record_detached_spawn(expr);
+        }
+        syn::visit::visit_stmt(self, node);
+    }
+
+    fn visit_local(&mut self, node: &'ast Local) {
+        // `let _ = tokio::spawn(fut);`
+        if let Pat::Wild(_) = &node.pat
+            && let Some(init) = &node.init
+        {
+            self.record_detached_spawn(&init.expr);
+        }
+        // `let Some(conn) = &self.conn else { panic!(..) };`
+        if let Some(init) = &node.init
+            && let Some((_, diverge)) = &init.diverge
+            && let Expr::Block(block) = &**diverge
+            && let Some(name) = panic_macro(&block.block)
+            && let Some(field) = unwrapped_field(&init.expr)
+        {
+            self.record_panic_site(field, format!("{}!", name), span_position(node.span()));
+        }
+        if let Some(init) = &node.init
+            && let Some(method) = guard_method(&init.expr, &self.rules.guard_methods)
+            && let Some(name) = binding_name(&node.pat)
+        {
+            let (line, column) = span_position(node.span());
+            let binding = GuardBinding { name, method, line };
+            if binding.is_discarded() {
+                self.metrics.issues.push(TemporalIssue {
+                    kind: TemporalIssueKind::DroppedGuard,
+                    operation: binding.method.clone(),
+                    function: self.current_function(),
+                    line,
+                    column,
+                    message: format!(
+                        "Guard from `{}()` is bound to `_` and dropped immediately",
+                        binding.method
+                    ),
+                });
+            }
+            self.metrics.guard_bindings.push(binding);
+        }
+        syn::visit::visit_local(self, node);
+        if let Some(init) = &node.init
+            && let Some(lock) = bound_lock(&init.expr, &self.impl_owner)
+            && let Some(name) = binding_name(&node.pat)
+            && name != "_"
+        {
+            self.held_locks
+                .push((name, lock, span_position(node.span()).0));
+        }
+    }
+
+    fn visit_expr_unsafe(&mut self, node: &'ast ExprUnsafe) {
+        self.metrics
+            .unsafe_blocks
+            .push(node.unsafe_token.span.start().line);
+        syn::visit::visit_expr_unsafe(self, node);
+    }
+}
+
+/// Re-run the temporal analysis of every module with custom rules
+///
+/// Returns the number of modules re-analyzed. Files that can no longer be
+/// read or parsed keep their previous results. Test code is skipped unless
+/// `include_tests` is set, as in the initial analysis.
+pub fn reanalyze_project(
+    metrics: &mut ProjectMetrics,
+    rules: &TemporalRules,
+    include_tests: bool,
+) -> usize {
+    let _span = tracing::debug_span!("temporal_rules").entered();
+    let mut count = 0;
+    for module in metrics.modules.values_mut() {
+        let Ok(content) = std::fs::read_to_string(&module.path) else {
+            tracing::debug!(file = %module.path.display(), "skipped unreadable file");
+            continue;
+        };
+        let Ok(mut file) = crate::edition::parse_file(&content, module.edition) else {
+            tracing::debug!(file = %module.path.display(), "skipped unparseable file");
+            continue;
+        };
+        if !include_tests {
+            strip_test_code(&mut file, &content);
+        }
+        module.temporal = TemporalAnalyzer::analyze_with(&file, rules);
+        count += 1;
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ignores_strings_and_comments() {
+        let code = r#"
+            // conn.begin() is mentioned here
+            fn f() {
+                let s = "tx.begin()";
+                println!("{}", s);
+            }
+        "#;
+        let metrics = TemporalAnalyzer::analyze_source(code).unwrap();
+        assert!(metrics.issues.is_empty());
+    }
+
+    #[test]
+    fn test_unpaired_operation() {
+        let code = r#"
+            fn f(tx: &mut Tx) {
+                tx.begin();
+                tx.begin();
+                tx.commit();
+            }
+        "#;
+        let metrics = TemporalAnalyzer::analyze_source(code).unwrap();
+        assert_eq!(metrics.issues.len(), 1);
+        let issue = &metrics.issues[0];
+        assert_eq!(issue.kind, TemporalIssueKind::UnpairedOperation);
+        assert_eq!(issue.operation, "begin");
+        assert_eq!(issue.function.as_deref(), Some("f"));
+        assert_eq!(issue.line, 4);
+        assert_eq!(issue.column, 20);
+    }
+
+    #[test]
+    fn test_custom_rules() {
+        let config = TemporalConfig {
+            pairs: vec![
+                PairRule {
+                    open: "checkout".into(),
+                    close: vec!["release_lease".into()],
+                    severity: Some(Severity::High),
+                },
+                // Overrides the built-in `begin` pair
+                PairRule {
+                    open: "begin".into(),
+                    close: vec!["finish".into()],
+                    severity: None,
+                },
+            ],
+            guard_methods: vec!["checkout_conn".into()],
+            state_check_macros: vec!["require".into()],
+            ..TemporalConfig::default()
+        };
+        let rules = config.rules();
+        assert_eq!(rules.pairs.len(), PAIRED_OPS.len() + 1);
+
+        let code = r#"
+            impl Pool {
+                fn f(&mut self) {
+                    require!(self.open);
+                    self.checkout();
+                    let _ = self.checkout_conn();
+                    self.begin();
+                    self.finish();
+                    self.acquire();
+                }
+            }
+        "#;
+        let file = syn::parse_file(code).unwrap();
+        let metrics = TemporalAnalyzer::analyze_with(&file, &rules);
+        let found: Vec<(TemporalIssueKind, &str)> = metrics
+            .issues
+            .iter()
+            .map(|i| (i.kind, i.operation.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (TemporalIssueKind::UnpairedOperation, "checkout"),
+                (TemporalIssueKind::DroppedGuard, "checkout_conn"),
+                (TemporalIssueKind::UnpairedOperation, "acquire"),
+            ]
+        );
+        assert_eq!(metrics.state_checks[0].field, "open");
+
+        assert_eq!(
+            config.severity(TemporalIssueKind::UnpairedOperation, "checkout"),
+            Severity::High
+        );
+        assert_eq!(
+            config.severity(TemporalIssueKind::UnpairedOperation, "acquire"),
+            Severity::Medium
+        );
+
+        // Replacing the built-ins keeps only the configured pairs
+        let replaced = TemporalConfig {
+            replace_builtin: true,
+            ..config
+        }
+        .rules();
+        assert_eq!(replaced.pairs.len(), 2);
+        assert_eq!(replaced.guard_methods, vec!["checkout_conn"]);
+        let metrics = TemporalAnalyzer::analyze_with(&file, &replaced);
+        assert!(metrics.issues.iter().all(|i| i.operation != "acquire"));
+    }
+
+    #[test]
+    fn test_imbalance_is_tracked_per_function() {
+        // Project-wide counts balance (2 begins, 2 commits), but `open_twice`
+        // leaves one transaction open.
+        let code = r#"
+            fn open_twice(tx: &mut Tx) {
+                tx.begin();
+                tx.begin();
+                tx.commit();
+            }
+            fn close_only(tx: &mut Tx) {
+                tx.commit();
+            }
+        "#;
+        let metrics = TemporalAnalyzer::analyze_source(code).unwrap();
+        assert_eq!(metrics.issues.len(), 1);
+        assert_eq!(metrics.issues[0].function.as_deref(), Some("open_twice"));
+        assert!(metrics.issues[0].message.contains("`open_twice` calls"));
+    }
+
+    #[test]
+    fn test_drop_impl_balances_module() {
+        let code = r#"
+            struct Session { pool: Pool }
+            impl Session {
+                fn new(pool: Pool) -> Self {
+                    pool.acquire();
+                    Self { pool }
+                }
+            }
+            impl Drop for Session {
+                fn drop(&mut self) {
+                    self.pool.release();
+                }
+            }
+        "#;
+        let metrics = TemporalAnalyzer::analyze_source(code).unwrap();
+        assert!(metrics.issues.is_empty(), "{:?}", metrics.issues);
+    }
+
+    #[test]
+    fn test_balanced_operations() {
+        let code = r#"
+            fn f(pool: &Pool) {
+                pool.acquire();
+                pool.release();
+            }
+        "#;
+        let metrics = TemporalAnalyzer::analyze_source(code).unwrap();
+        assert!(metrics.issues.is_empty());
+    }
+
+    #[test]
+    fn test_guard_bindings() {
+        let code = r#"
+            fn f(m: &Mutex<u32>) {
+                let _ = m.lock().unwrap();
+                let _guard = m.lock().unwrap();
+                let value = m.try_lock()?;
+            }
+        "#;
+        let metrics = TemporalAnalyzer::analyze_source(code).unwrap();
+        assert_eq!(metrics.guard_bindings.len(), 3);
+        assert_eq!(metrics.issues.len(), 1);
+        assert_eq!(metrics.issues[0].kind, TemporalIssueKind::DroppedGuard);
+        assert_eq!(metrics.issues[0].line, 3);
+    }
+
+    #[test]
+    fn test_drop_impls_and_unsafe_blocks() {
+        let code = r#"
+            struct Handle;
+            impl Drop for Handle {
+                fn drop(&mut self) {}
+            }
+            fn f() {
+                unsafe { do_it(); }
+            }
+            unsafe fn g() {}
+        "#;
+        let metrics = TemporalAnalyzer::analyze_source(code).unwrap();
+        assert_eq!(metrics.drop_impls, vec!["Handle".to_string()]);
+        assert_eq!(metrics.unsafe_blocks, vec![7]);
+        assert_eq!(metrics.unsafe_fns, vec![9]);
+    }
+
+    #[test]
+    fn test_state_checks_and_writes() {
+        let code = r#"
+            impl Client {
+                fn connect(&mut self) {
+                    self.connected = true;
+                }
+                fn send(&mut self, data: &[u8]) -> Result<(), Error> {
+                    if !self.connected {
+                        return Err(Error::NotConnected);
+                    }
+                    debug_assert!(self.buffer.is_empty(), "stale buffer");
+                    if self.verbose { log(data); }
+                    Ok(())
+                }
+            }
+        "#;
+        let metrics = TemporalAnalyzer::analyze_source(code).unwrap();
+
+        let checks: Vec<(&str, &str, usize)> = metrics
+            .state_checks
+            .iter()
+            .map(|c| (c.function.as_str(), c.field.as_str(), c.line))
+            .collect();
+        assert_eq!(
+            checks,
+            vec![("send", "connected", 7), ("send", "buffer", 10)]
+        );
+        assert_eq!(metrics.state_checks[0].owner.as_deref(), Some("Client"));
+
+        assert_eq!(metrics.state_writes.len(), 1);
+        assert_eq!(metrics.state_writes[0].function, "connect");
+        assert_eq!(metrics.state_writes[0].field, "connected");
+    }
+
+    #[test]
+    fn test_panic_paths() {
+        let metrics = TemporalAnalyzer::analyze_source(
+            r#"
+            impl Client {
+                fn connect(&mut self) { self.conn = Some(Conn::open()); }
+                fn send(&self, data: &[u8]) {
+                    self.conn.as_ref().unwrap().write(data);
+                }
+                fn peer(&self) -> Addr {
+                    let Some(conn) = &self.conn else { panic!("not connected") };
+                    conn.peer()
+                }
+                fn flush(&self) {
+                    if self.conn.is_none() { unreachable!() }
+                }
+                fn conn(&mut self) -> &Conn {
+                    if self.conn.is_none() { self.conn = Some(Conn::open()); }
+                    self.conn.as_ref().expect("just set")
+                }
+                fn name(&self) -> &str { self.name.as_deref().unwrap() }
+            }
+            "#,
+        )
+        .unwrap();
+        let panics: Vec<(&str, &str)> = metrics
+            .issues
+            .iter()
+            .filter(|i| i.kind == TemporalIssueKind::PanicPath)
+            .map(|i| (i.function.as_deref().unwrap(), i.operation.as_str()))
+            .collect();
+        // `conn` initializes lazily; `name` is never assigned
+        assert_eq!(
+            panics,
+            vec![
+                ("Client::send", "unwrap"),
+                ("Client::peer", "panic!"),
+                ("Client::flush", "unreachable!"),
+            ]
+        );
+        assert!(
+            metrics.issues[0]
+                .message
+                .contains("set by `conn()`/`connect()`")
+        );
+    }
+
+    #[test]
+    fn test_cancellation_safety() {
+        let metrics = TemporalAnalyzer::analyze_source(
+            r#"
+            struct Frames { buf: Vec<u8> }
+            impl Future for Frames {
+                type Output = Vec<u8>;
+                fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> { todo!() }
+            }
+            async fn run(mut sock: TcpStream, mut rx: Receiver<Msg>, stop: Notify) {
+                loop {
+                    tokio::select! {
+                        biased;
+                        res = sock.read_exact(&mut buf) => { sock.write_all(&buf).await; }
+                        Some(msg) = rx.recv(), if open => handle(msg),
+                        frame = Frames::new(&mut sock) => {}
+                        _ = stop.notified() => break,
+                    }
+                }
+                tokio::spawn(async move { work().await });
+                let _ = tokio::task::spawn_local(idle());
+                let handle = tokio::spawn(work());
+                std::thread::spawn(|| {});
+            }
+            "#,
+        )
+        .unwrap();
+        let found: Vec<(TemporalIssueKind, &str)> = metrics
+            .issues
+            .iter()
+            .map(|i| (i.kind, i.operation.as_str()))
+            .collect();
+        // `write_all` runs in a handler, which completes once chosen
+        assert_eq!(
+            found,
+            vec![
+                (TemporalIssueKind::CancellationUnsafeSelect, "read_exact"),
+                (TemporalIssueKind::CancellationUnsafeSelect, "Frames"),
+                (TemporalIssueKind::DetachedSpawn, "spawn"),
+                (TemporalIssueKind::DetachedSpawn, "spawn_local"),
+            ]
+        );
+        assert!(
+            metrics.issues[0]
+                .message
+                .starts_with("`tokio::select!` branch")
+        );
+    }
+
+    #[test]
+    fn test_foreign_resources() {
+        let metrics = TemporalAnalyzer::analyze_source(
+            r#"
+            unsafe extern "C" {
+                fn ctx_new() -> *mut Ctx;
+                fn ctx_free(ctx: *mut Ctx);
+                fn ctx_run(ctx: *mut Ctx);
+            }
+            fn leak() {
+                let ctx = unsafe { ctx_new() };
+                unsafe { ctx_run(ctx) };
+            }
+            fn scoped() {
+                let ctx = unsafe { ctx_new() };
+                unsafe { ctx_free(ctx) };
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(metrics.issues.len(), 1);
+        assert_eq!(metrics.issues[0].kind, TemporalIssueKind::UnpairedOperation);
+        assert_eq!(metrics.issues[0].function.as_deref(), Some("leak"));
+        assert_eq!(metrics.issues[0].operation, "ctx_new");
+    }
+
+    #[test]
+    fn test_drop_order() {
+        let metrics = TemporalAnalyzer::analyze_source(
+            r#"
+            struct Conn { stream: ManuallyDrop<Stream>, pool: Arc<Pool>, id: u32 }
+            impl Conn {
+                fn close(&mut self) {
+                    unsafe { ManuallyDrop::drop(&mut self.stream) };
+                }
+            }
+            impl Drop for Conn {
+                fn drop(&mut self) {
+                    self.pool.release(self.id);
+                }
+            }
+            struct Session { log: Log, file: Option<File> }
+            impl Drop for Session {
+                fn drop(&mut self) {
+                    self.log.flush();
+                    drop(self.file.take());
+                }
+            }
+            struct Plain { name: String }
+            impl Drop for Plain {
+                fn drop(&mut self) { println!("{}", self.name); }
+            }
+            "#,
+        )
+        .unwrap();
+        let messages: Vec<(Option<&str>, &str)> = metrics
+            .issues
+            .iter()
+            .filter(|i| i.kind == TemporalIssueKind::DropOrder)
+            .map(|i| (i.function.as_deref(), i.message.as_str()))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                (
+                    Some("close"),
+                    "`Conn` tears its fields down in a hand-written order: `drop()` uses `pool` then `id`; \
+                     `close()` releases `stream` with `ManuallyDrop::drop`; reordering the fields or these calls changes what is still alive"
+                ),
+                (
+                    Some("drop"),
+                    "`Session` tears its fields down in a hand-written order: `drop()` uses `log` then `file`; \
+                     `drop()` releases `file` with `drop`; reordering the fields or these calls changes what is still alive"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lock_order_inversion() {
+        let metrics = TemporalAnalyzer::analyze_source(
+            r#"
+            struct Bank { accounts: Mutex<Vec<u64>>, audit: RwLock<Log>, name: String }
+            static REGISTRY: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+            impl Bank {
+                fn transfer(&self) {
+                    let accounts = self.accounts.lock().unwrap();
+                    self.audit.write().unwrap().push("transfer");
+                }
+                fn report(&self) {
+                    let audit = self.audit.read().unwrap();
+                    let accounts = self.accounts.lock().unwrap();
+                }
+                fn released(&self) {
+                    let audit = self.audit.read().unwrap();
+                    drop(audit);
+                    let accounts = self.accounts.lock().unwrap();
+                    let name = self.name.read();
+                }
+                fn scoped(&self) {
+                    {
+                        let audit = self.audit.read().unwrap();
+                    }
+                    let accounts = self.accounts.lock().unwrap();
+                    let registry = REGISTRY.lock().unwrap();
+                }
+            }
+            "#,
+        )
+        .unwrap();
+        let inversions: Vec<&TemporalIssue> = metrics
+            .issues
+            .iter()
+            .filter(|i| i.kind == TemporalIssueKind::LockOrderInversion)
+            .collect();
+        assert_eq!(inversions.len(), 1);
+        assert_eq!(inversions[0].function.as_deref(), Some("report"));
+        assert_eq!(
+            inversions[0].message,
+            "`Bank` locks taken in opposite orders: `transfer` locks `accounts` then `audit` (lines 6 and 7), \
+             but `report` locks `audit` then `accounts` (lines 10 and 11); running both concurrently can deadlock"
+        );
+    }
+
+    #[test]
+    fn test_builder_patterns() {
+        let code = r#"
+            pub struct ClientBuilder { host: Option<String>, port: u16 }
+            impl ClientBuilder {
+                pub fn host(mut self, host: &str) -> Self { self.host = Some(host.into()); self }
+                pub fn port(&mut self, port: u16) -> &mut Self { self.port = port; self }
+                pub fn build(self) -> Client {
+                    Client { host: self.host.expect("host is required"), port: self.port }
+                }
+            }
+
+            pub struct RequestBuilder { url: String, timeout: Option<u64> }
+            impl RequestBuilder {
+                pub fn new(url: &str) -> Self { Self { url: url.into(), timeout: None } }
+                pub fn timeout(mut self, secs: u64) -> Self { self.timeout = Some(secs); self }
+                pub fn build(self) -> Request { Request { url: self.url } }
+            }
+
+            pub struct ConfigBuilder { path: Option<String> }
+            impl ConfigBuilder {
+                pub fn path(mut self, path: &str) -> Self { self.path = Some(path.into()); self }
+                pub fn build(self) -> Result<Config, Error> {
+                    Ok(Config { path: self.path.ok_or(Error::MissingPath)? })
+                }
+            }
+
+            pub struct Query<State> { sql: String, state: State }
+            impl<S> Query<S> {
+                pub fn select(self, sql: &str) -> Query<Selected> { todo!() }
+                pub fn build(self) -> String { self.sql }
+            }
+
+            pub struct Point { x: i32 }
+            impl Point {
+                pub fn build(&self) -> Shape { Shape }
+                pub fn clone_x(&self) -> Self { Point { x: self.x } }
+            }
+        "#;
+        let metrics = TemporalAnalyzer::analyze_source(code).unwrap();
+
+        let builders: Vec<(&str, RequiredFields, usize)> = metrics
+            .builders
+            .iter()
+            .map(|b| (b.name.as_str(), b.required_fields, b.setters.len()))
+            .collect();
+        assert_eq!(
+            builders,
+            vec![
+                ("ClientBuilder", RequiredFields::Panics, 2),
+                ("ConfigBuilder", RequiredFields::Checked, 1),
+                ("RequestBuilder", RequiredFields::Constructor, 1),
+            ]
+        );
+
+        assert_eq!(metrics.issues.len(), 1);
+        let issue = &metrics.issues[0];
+        assert_eq!(issue.kind, TemporalIssueKind::PanickingBuilder);
+        assert_eq!(issue.function.as_deref(), Some("ClientBuilder::build"));
+        assert_eq!(issue.line, 6);
+    }
+
+    #[test]
+    fn test_typestate_builder() {
+        let code = r#"
+            pub struct Missing;
+            pub struct Set(String);
+            pub struct MailBuilder<To> { to: To, subject: Option<String> }
+            impl MailBuilder<Missing> {
+                pub fn to(self, to: &str) -> MailBuilder<Set> { todo!() }
+            }
+            impl<T> MailBuilder<T> {
+                pub fn subject(mut self, s: &str) -> Self { self.subject = Some(s.into()); self }
+            }
+            impl MailBuilder<Set> {
+                pub fn build(self) -> Mail { Mail { to: self.to.0, subject: self.subject.unwrap() } }
+            }
+        "#;
+        let metrics = TemporalAnalyzer::analyze_source(code).unwrap();
+        assert_eq!(metrics.builders.len(), 1);
+        assert_eq!(
+            metrics.builders[0].required_fields,
+            RequiredFields::Typestate
+        );
+        assert!(metrics.issues.is_empty());
+    }
+}
@@ -0,0 +1,95 @@
+//! On-disk projects shared by tests that run the full analysis
+
+use std::fs;
+use std::path::Path;
+
+use tempfile::TempDir;
+
+/// Write `content` to `path` under `root`, creating parent directories
+pub fn write(root: &Path, path: &str, content: &str) {
+    let path = root.join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+/// Workspace whose two members both have a `src/lib.rs`
+///
+/// `app` calls `corelib::helper` and takes a `corelib::model::User`;
+/// `corelib::Kind` and `corelib::unused` are used by nobody.
+pub fn two_lib_workspace() -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    write(
+        root,
+        "Cargo.toml",
+        "[workspace]\nmembers = [\"corelib\", \"app\"]\nresolver = \"2\"\n",
+    );
+    write(
+        root,
+        "corelib/Cargo.toml",
+        "[package]\nname = \"corelib\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    );
+    write(
+        root,
+        "corelib/src/lib.rs",
+        "pub mod model;\n\n\
+         pub fn helper() -> usize {\n    1\n}\n\n\
+         pub fn unused() {}\n\n\
+         pub enum Kind {\n    A,\n    B,\n}\n",
+    );
+    write(
+        root,
+        "corelib/src/model.rs",
+        "pub struct User {\n    pub name: String,\n}\n",
+    );
+    write(
+        root,
+        "app/Cargo.toml",
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n\
+         [dependencies]\ncorelib = { path = \"../corelib\" }\n",
+    );
+    write(
+        root,
+        "app/src/lib.rs",
+        "use corelib::helper;\nuse corelib::model::User;\n\n\
+         pub fn greet(user: &User) -> usize {\n    helper() + user.name.len()\n}\n",
+    );
+    dir
+}
+
+/// Single crate whose `net` and `store` modules are `mod.rs` files
+///
+/// The imports form the cycle `net -> store -> net::client -> net`.
+pub fn mod_rs_crate() -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    write(
+        root,
+        "Cargo.toml",
+        "[package]\nname = \"netstore\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    );
+    write(
+        root,
+        "src/lib.rs",
+        "pub mod net;\npub mod store;\npub mod util;\n",
+    );
+    write(
+        root,
+        "src/net/mod.rs",
+        "pub mod client;\n\nuse crate::store::Store;\n\n\
+         pub struct Config {\n    pub retries: u32,\n}\n\n\
+         pub fn open(store: &Store) -> usize {\n    store.len()\n}\n",
+    );
+    write(
+        root,
+        "src/net/client.rs",
+        "use crate::net::Config;\n\npub struct Conn {\n    pub config: Config,\n}\n",
+    );
+    write(
+        root,
+        "src/store/mod.rs",
+        "use crate::net::client::Conn;\n\npub struct Store {\n    pub conns: Vec<Conn>,\n}\n",
+    );
+    write(root, "src/util.rs", "pub fn noop() {}\n");
+    dir
+}
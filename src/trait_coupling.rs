@@ -0,0 +1,564 @@
+//! Trait coupling between workspace crates
+//!
+//! Depending on another crate's trait is a different kind of coupling than
+//! depending on its types. Implementing a trait binds the implementing crate
+//! to every method signature of the trait; a generic bound only to the
+//! methods the generic code calls. Either way, changing the trait means
+//! touching every implementor and bound, while a type can often evolve
+//! behind its public methods.
+//!
+//! - **Trait impls**: `impl other_crate::Trait for LocalType`
+//! - **Trait bounds**: `T: other_crate::Trait` in generics and where clauses,
+//!   `impl Trait` and `dyn Trait`
+//! - **Type references**: every other coupling from the crate into the
+//!   other crate (imports, calls, field access), for comparison
+//!
+//! Trait paths are resolved through `use` statements; only traits of other
+//! workspace members are counted (standard library and external traits are
+//! not).
+//!
+//! The orphan rule forbids implementing a foreign trait for a foreign type,
+//! so crates wrap the type in a local newtype and implement the trait for
+//! the wrapper. Such an impl ties together three crates (the trait's, the
+//! wrapped type's and the implementing one) and breaks when either upstream
+//! crate changes; [`find_orphan_workarounds`] lists them.
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use crate::metrics::{IntegrationStrength, ModuleMetrics, ProjectMetrics, TraitUse};
+
+/// Crates that make up the standard library
+const STD_CRATES: &[&str] = &["std", "core", "alloc"];
+
+/// Prelude traits and types, usable without a `use`
+const PRELUDE: &[&str] = &[
+    "AsMut",
+    "AsRef",
+    "Box",
+    "Clone",
+    "Copy",
+    "Default",
+    "Drop",
+    "Eq",
+    "Extend",
+    "Fn",
+    "FnMut",
+    "FnOnce",
+    "From",
+    "Into",
+    "IntoIterator",
+    "Iterator",
+    "Option",
+    "Ord",
+    "PartialEq",
+    "PartialOrd",
+    "Result",
+    "Send",
+    "String",
+    "Sync",
+    "ToOwned",
+    "ToString",
+    "TryFrom",
+    "TryInto",
+    "Vec",
+];
+
+/// Trait and type coupling from one crate into another
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CrateTraitCoupling {
+    pub source: String,
+    pub target: String,
+    /// Impls of the target crate's traits
+    pub impls: usize,
+    /// Bounds requiring the target crate's traits
+    pub bounds: usize,
+    /// Non-trait couplings into the target crate
+    pub type_refs: usize,
+}
+
+/// A workspace trait used outside its crate
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SharedTrait {
+    /// Defining crate
+    pub krate: String,
+    /// Path inside the defining crate (`storage::Repository`)
+    pub path: String,
+    /// Implementing crate -> implementing types
+    pub implementors: BTreeMap<String, Vec<String>>,
+    /// Crate -> number of bounds requiring the trait
+    pub bounds: BTreeMap<String, usize>,
+}
+
+/// Trait coupling of a workspace
+#[derive(Debug, Clone, Default)]
+pub struct TraitCouplingReport {
+    /// Crate pairs with trait or type coupling, sorted by source and target
+    pub pairs: Vec<CrateTraitCoupling>,
+    /// Traits used by other crates, sorted by crate and path
+    pub traits: Vec<SharedTrait>,
+    /// Newtypes that exist to implement foreign traits
+    pub orphan_workarounds: Vec<OrphanWorkaround>,
+}
+
+impl TraitCouplingReport {
+    /// Collect trait impls and bounds that cross crate boundaries
+    pub fn build(metrics: &ProjectMetrics) -> Self {
+        let crates: BTreeMap<String, &str> = metrics
+            .workspace_members
+            .iter()
+            .map(|m| (m.replace('-', "_"), m.as_str()))
+            .collect();
+        let mut pairs: BTreeMap<(String, String), CrateTraitCoupling> = BTreeMap::new();
+        let mut traits: BTreeMap<(String, String), SharedTrait> = BTreeMap::new();
+
+        for module in metrics.modules.values() {
+            let Some(source) = module.crate_name.as_deref() else {
+                continue;
+            };
+            for trait_use in &module.trait_impls {
+                if let Some((target, path)) = foreign_trait(trait_use, source, &crates) {
+                    pair(&mut pairs, source, target).impls += 1;
+                    shared(&mut traits, target, path)
+                        .implementors
+                        .entry(source.to_string())
+                        .or_default()
+                        .push(trait_use.item.clone());
+                }
+            }
+            for trait_use in &module.trait_bounds {
+                if let Some((target, path)) = foreign_trait(trait_use, source, &crates) {
+                    pair(&mut pairs, source, target).bounds += 1;
+                    *shared(&mut traits, target, path)
+                        .bounds
+                        .entry(source.to_string())
+                        .or_default() += 1;
+                }
+            }
+        }
+
+        for coupling in &metrics.couplings {
+            if let (Some(source), Some(target)) = (&coupling.source_crate, &coupling.target_crate)
+                && source != target
+                && coupling.strength != IntegrationStrength::Contract
+                && metrics.workspace_members.contains(source)
+                && metrics.workspace_members.contains(target)
+            {
+                pair(&mut pairs, source, target).type_refs += 1;
+            }
+        }
+
+        let mut traits: Vec<SharedTrait> = traits.into_values().collect();
+        for shared in &mut traits {
+            for types in shared.implementors.values_mut() {
+                types.sort();
+                types.dedup();
+            }
+        }
+        Self {
+            pairs: pairs.into_values().collect(),
+            traits,
+            orphan_workarounds: find_orphan_workarounds(metrics),
+        }
+    }
+
+    /// Total trait impls across crate boundaries
+    pub fn total_impls(&self) -> usize {
+        self.pairs.iter().map(|p| p.impls).sum()
+    }
+
+    /// Total trait bounds across crate boundaries
+    pub fn total_bounds(&self) -> usize {
+        self.pairs.iter().map(|p| p.bounds).sum()
+    }
+}
+
+/// Defining crate and in-crate path of a trait from another workspace crate
+fn foreign_trait<'a>(
+    trait_use: &TraitUse,
+    source: &str,
+    crates: &BTreeMap<String, &'a str>,
+) -> Option<(&'a str, String)> {
+    let (first, rest) = trait_use.trait_path.split_once("::")?;
+    let target = *crates.get(first)?;
+    (target != source).then(|| (target, rest.to_string()))
+}
+
+fn pair<'a>(
+    pairs: &'a mut BTreeMap<(String, String), CrateTraitCoupling>,
+    source: &str,
+    target: &str,
+) -> &'a mut CrateTraitCoupling {
+    pairs
+        .entry((source.to_string(), target.to_string()))
+        .or_insert_with(|| CrateTraitCoupling {
+            source: source.to_string(),
+            target: target.to_string(),
+            ..Default::default()
+        })
+}
+
+fn shared<'a>(
+    traits: &'a mut BTreeMap<(String, String), SharedTrait>,
+    krate: &str,
+    path: String,
+) -> &'a mut SharedTrait {
+    traits
+        .entry((krate.to_string(), path.clone()))
+        .or_insert_with(|| SharedTrait {
+            krate: krate.to_string(),
+            path,
+            ..Default::default()
+        })
+}
+
+/// A foreign trait implemented for a local newtype around a foreign type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrphanWorkaround {
+    /// Crate containing the impl (workspace analysis only)
+    pub krate: Option<String>,
+    /// Module containing the impl
+    pub module: String,
+    /// Local newtype (`Wrapper` in `struct Wrapper(serde_json::Value)`)
+    pub wrapper: String,
+    /// Implemented trait (`serde::Serialize`)
+    pub trait_path: String,
+    /// Wrapped type (`serde_json::Value`)
+    pub inner_type: String,
+    pub file: PathBuf,
+    pub line: usize,
+}
+
+impl OrphanWorkaround {
+    /// Crate defining the trait
+    pub fn trait_crate(&self) -> &str {
+        crate_of(&self.trait_path)
+    }
+
+    /// Crate defining the wrapped type
+    pub fn type_crate(&self) -> &str {
+        crate_of(&self.inner_type)
+    }
+
+    /// Whether trait, wrapped type and impl live in three different crates,
+    /// none of them the standard library
+    pub fn is_three_way(&self) -> bool {
+        let (trait_crate, type_crate) = (self.trait_crate(), self.type_crate());
+        trait_crate != type_crate
+            && !STD_CRATES.contains(&trait_crate)
+            && !STD_CRATES.contains(&type_crate)
+    }
+}
+
+/// Crate a resolved foreign path comes from (prelude names are `std`)
+fn crate_of(path: &str) -> &str {
+    path.split_once("::").map_or("std", |(first, _)| first)
+}
+
+/// Whether a resolved path names an item outside the module's crate
+fn is_foreign(path: &str, module: &ModuleMetrics) -> bool {
+    match path.split_once("::") {
+        None => PRELUDE.contains(&path),
+        Some((first, _)) => {
+            !matches!(first, "crate" | "self" | "super" | "Self")
+                && !module.declared_modules.iter().any(|m| m == first)
+                && module
+                    .crate_name
+                    .as_ref()
+                    .is_none_or(|krate| krate.replace('-', "_") != first)
+        }
+    }
+}
+
+/// Find impls of foreign traits for local newtypes around foreign types
+///
+/// Wrappers of standard library types are left out: the wrapped type is
+/// stable, and such newtypes (visitors, collectors) usually exist for their
+/// own sake rather than for the impl.
+pub fn find_orphan_workarounds(metrics: &ProjectMetrics) -> Vec<OrphanWorkaround> {
+    let mut modules: Vec<&ModuleMetrics> = metrics.modules.values().collect();
+    modules.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let mut found = Vec::new();
+    for module in &modules {
+        for trait_impl in &module.trait_impls {
+            if !is_foreign(&trait_impl.trait_path, module) {
+                continue;
+            }
+            // The wrapper is usually defined next to the impl, else elsewhere in the crate
+            let wrapper = module.type_definitions.get(&trait_impl.item).or_else(|| {
+                modules
+                    .iter()
+                    .filter(|m| m.crate_name == module.crate_name)
+                    .find_map(|m| m.type_definitions.get(&trait_impl.item))
+            });
+            let Some(inner_type) = wrapper
+                .filter(|def| def.is_newtype)
+                .and_then(|def| def.inner_type.as_ref())
+            else {
+                continue;
+            };
+            if !is_foreign(inner_type, module) {
+                continue;
+            }
+
+            let workaround = OrphanWorkaround {
+                krate: module.crate_name.clone(),
+                module: module.name.clone(),
+                wrapper: trait_impl.item.clone(),
+                trait_path: trait_impl.trait_path.clone(),
+                inner_type: inner_type.clone(),
+                file: module.path.clone(),
+                line: trait_impl.line,
+            };
+            if !STD_CRATES.contains(&workaround.type_crate()) {
+                found.push(workaround);
+            }
+        }
+    }
+    found
+}
+
+/// Generate the trait coupling report to writer
+pub fn generate_trait_coupling_report<W: Write>(
+    report: &TraitCouplingReport,
+    writer: &mut W,
+) -> io::Result<()> {
+    writeln!(writer, "Trait Coupling Between Crates")?;
+    writeln!(writer, "=============================")?;
+    writeln!(writer)?;
+
+    if report.pairs.is_empty() {
+        writeln!(writer, "No coupling between workspace crates found.")?;
+        return write_orphan_workarounds(report, writer);
+    }
+
+    let labels: Vec<String> = report
+        .pairs
+        .iter()
+        .map(|p| format!("{} -> {}", p.source, p.target))
+        .collect();
+    let width = labels
+        .iter()
+        .map(String::len)
+        .max()
+        .unwrap_or(0)
+        .max("Crates".len());
+    writeln!(
+        writer,
+        "{:<width$}  {:>11}  {:>12}  {:>9}",
+        "Crates", "Trait impls", "Trait bounds", "Type refs"
+    )?;
+    writeln!(writer, "{}", "-".repeat(width + 38))?;
+    for (label, pair) in labels.iter().zip(&report.pairs) {
+        writeln!(
+            writer,
+            "{:<width$}  {:>11}  {:>12}  {:>9}",
+            label, pair.impls, pair.bounds, pair.type_refs
+        )?;
+    }
+
+    if !report.traits.is_empty() {
+        writeln!(writer)?;
+        writeln!(writer, "Traits used by other crates")?;
+        for shared in &report.traits {
+            let mut uses: Vec<String> = shared
+                .implementors
+                .iter()
+                .map(|(krate, types)| format!("implemented in {} ({})", krate, types.join(", ")))
+                .collect();
+            uses.extend(
+                shared
+                    .bounds
+                    .iter()
+                    .map(|(krate, count)| format!("{} bound(s) in {}", count, krate)),
+            );
+            writeln!(
+                writer,
+                "  {}::{}: {}",
+                shared.krate,
+                shared.path,
+                uses.join("; ")
+            )?;
+        }
+    }
+    write_orphan_workarounds(report, writer)?;
+
+    writeln!(writer)?;
+    writeln!(
+        writer,
+        "{} crate pairs, {} trait impls, {} trait bounds, {} type references",
+        report.pairs.len(),
+        report.total_impls(),
+        report.total_bounds(),
+        report.pairs.iter().map(|p| p.type_refs).sum::<usize>()
+    )?;
+    Ok(())
+}
+
+fn write_orphan_workarounds<W: Write>(
+    report: &TraitCouplingReport,
+    writer: &mut W,
+) -> io::Result<()> {
+    if report.orphan_workarounds.is_empty() {
+        return Ok(());
+    }
+    writeln!(writer)?;
+    writeln!(writer, "Orphan rule workarounds")?;
+    for found in &report.orphan_workarounds {
+        writeln!(
+            writer,
+            "  {} for {}({}) at {}:{}",
+            found.trait_path,
+            found.wrapper,
+            found.inner_type,
+            found.file.display(),
+            found.line
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::CouplingAnalyzer;
+    use crate::metrics::{CouplingMetrics, Distance, Volatility};
+
+    fn analyze(krate: &str, file: &str, code: &str) -> crate::metrics::ModuleMetrics {
+        let mut analyzer = CouplingAnalyzer::new(krate.to_string(), file.into());
+        analyzer.analyze_file(code).unwrap();
+        let mut module = analyzer.metrics;
+        module.crate_name = Some(krate.to_string());
+        module
+    }
+
+    fn workspace() -> ProjectMetrics {
+        let mut metrics = ProjectMetrics::new();
+        metrics.workspace_members = vec!["my-core".into(), "app".into()];
+        metrics.add_module(analyze(
+            "my-core",
+            "core/src/lib.rs",
+            "pub trait Repository { fn get(&self); } pub struct User;",
+        ));
+        metrics.add_module(analyze(
+            "app",
+            "app/src/lib.rs",
+            r#"
+            use my_core::Repository;
+            use std::fmt::Debug;
+            struct PgRepo;
+            impl Repository for PgRepo { fn get(&self) {} }
+            impl my_core::Repository for Vec<u8> { fn get(&self) {} }
+            fn load<R: Repository + Debug>(repo: &R) {}
+            fn boxed(repo: Box<dyn my_core::Repository>) {}
+            fn sized<T: ?Sized>(value: &T) {}
+            "#,
+        ));
+
+        let mut user = CouplingMetrics::new(
+            "app".into(),
+            "my_core::User".into(),
+            IntegrationStrength::Model,
+            Distance::DifferentCrate,
+            Volatility::Low,
+        );
+        user.source_crate = Some("app".into());
+        user.target_crate = Some("my-core".into());
+        metrics.couplings.push(user);
+        metrics
+    }
+
+    #[test]
+    fn test_trait_coupling_between_crates() {
+        let report = TraitCouplingReport::build(&workspace());
+        assert_eq!(
+            report.pairs,
+            vec![CrateTraitCoupling {
+                source: "app".into(),
+                target: "my-core".into(),
+                impls: 2,
+                bounds: 2,
+                type_refs: 1,
+            }]
+        );
+        assert_eq!(report.traits.len(), 1);
+        let repository = &report.traits[0];
+        assert_eq!(repository.path, "Repository");
+        assert_eq!(repository.implementors["app"], vec!["PgRepo", "Vec"]);
+        assert_eq!(repository.bounds["app"], 2);
+    }
+
+    #[test]
+    fn test_trait_coupling_report() {
+        let mut out = Vec::new();
+        generate_trait_coupling_report(&TraitCouplingReport::build(&workspace()), &mut out)
+            .unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("app -> my-core            2             2          1"));
+        assert!(text.contains(
+            "  my-core::Repository: implemented in app (PgRepo, Vec); 2 bound(s) in app"
+        ));
+        assert!(text.contains("1 crate pairs, 2 trait impls, 2 trait bounds, 1 type references"));
+
+        let mut out = Vec::new();
+        generate_trait_coupling_report(&TraitCouplingReport::default(), &mut out).unwrap();
+        assert!(
+            String::from_utf8(out)
+                .unwrap()
+                .contains("No coupling between workspace crates")
+        );
+    }
+
+    #[test]
+    fn test_orphan_workarounds() {
+        let mut metrics = ProjectMetrics::new();
+        metrics.add_module(analyze(
+            "app",
+            "app/src/lib.rs",
+            r#"
+            use serde::Serialize;
+            use serde_json::Value;
+            use std::fmt;
+            mod model;
+            pub struct Json(Value);
+            impl Serialize for Json {}
+            impl fmt::Display for Json {}
+            pub struct Names(Vec<String>);
+            impl fmt::Display for Names {}
+            pub struct Id(u64);
+            impl Serialize for Id {}
+            pub struct Local(model::User);
+            impl Serialize for Local {}
+            "#,
+        ));
+
+        let found = find_orphan_workarounds(&metrics);
+        let summary: Vec<(&str, &str, bool)> = found
+            .iter()
+            .map(|f| {
+                (
+                    f.trait_path.as_str(),
+                    f.inner_type.as_str(),
+                    f.is_three_way(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("serde::Serialize", "serde_json::Value", true),
+                ("std::fmt::Display", "serde_json::Value", false),
+            ]
+        );
+        assert_eq!(found[0].line, 7);
+
+        let mut out = Vec::new();
+        generate_trait_coupling_report(&TraitCouplingReport::build(&metrics), &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(
+            text.contains("  serde::Serialize for Json(serde_json::Value) at app/src/lib.rs:7")
+        );
+    }
+}
@@ -0,0 +1,227 @@
+//! Where the unsafe code sits in the module graph
+//!
+//! An `unsafe` block in a leaf module is a local concern; the same block in
+//! a module half the crate imports is a risk every importer shares, since
+//! they all rely on its invariants holding. This report lists every module
+//! with `unsafe` blocks or `unsafe fn`s next to its fan-in (modules
+//! importing from it, see [`ModuleGraph`]), and ranks them by the unsafe
+//! surface `(blocks + functions) × (1 + fan-in)`.
+//!
+//! The manual resource handling the temporal analyzer records is shown
+//! alongside, since it usually comes with the unsafe code: `Drop`
+//! implementations, and types tearing their fields down by hand
+//! (`ManuallyDrop::drop`, `mem::forget`, `ptr::drop_in_place`, see
+//! [`TemporalIssueKind::DropOrder`]).
+
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use crate::metrics::ProjectMetrics;
+use crate::module_graph::ModuleGraph;
+use crate::temporal::TemporalIssueKind;
+
+/// Unsafe code and manual teardown of one module
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UnsafeModule {
+    /// Qualified module name (`my_crate::ffi::buffer`)
+    pub module: String,
+    pub file: PathBuf,
+    /// Lines of `unsafe` blocks
+    pub unsafe_blocks: Vec<usize>,
+    /// Lines of `unsafe fn` definitions
+    pub unsafe_fns: Vec<usize>,
+    /// Types with a `Drop` implementation
+    pub drop_impls: usize,
+    /// Types releasing or tearing down fields by hand
+    pub manual_teardowns: usize,
+    /// Modules importing from this module
+    pub fan_in: usize,
+}
+
+impl UnsafeModule {
+    /// `unsafe` blocks and functions
+    pub fn unsafe_count(&self) -> usize {
+        self.unsafe_blocks.len() + self.unsafe_fns.len()
+    }
+
+    /// Unsafe code weighted by how many modules depend on it
+    pub fn surface(&self) -> usize {
+        self.unsafe_count() * (1 + self.fan_in)
+    }
+}
+
+/// Modules carrying unsafe code, largest surface first
+#[derive(Debug, Clone, Default)]
+pub struct UnsafeSurfaceReport {
+    pub modules: Vec<UnsafeModule>,
+}
+
+impl UnsafeSurfaceReport {
+    /// Collect the unsafe code of every module and its fan-in
+    pub fn build(metrics: &ProjectMetrics) -> Self {
+        let graph = ModuleGraph::build(metrics);
+        let mut modules: Vec<UnsafeModule> = graph
+            .nodes
+            .values()
+            .filter_map(|node| {
+                let module = metrics.modules.values().find(|m| m.path == node.file)?;
+                let temporal = &module.temporal;
+                let unsafe_module = UnsafeModule {
+                    module: node.name.clone(),
+                    file: node.file.clone(),
+                    unsafe_blocks: temporal.unsafe_blocks.clone(),
+                    unsafe_fns: temporal.unsafe_fns.clone(),
+                    drop_impls: temporal.drop_impls.len(),
+                    manual_teardowns: temporal
+                        .issues
+                        .iter()
+                        .filter(|i| i.kind == TemporalIssueKind::DropOrder)
+                        .count(),
+                    fan_in: graph.fan_in(&node.name),
+                };
+                (unsafe_module.unsafe_count() > 0).then_some(unsafe_module)
+            })
+            .collect();
+        modules.sort_by(|a, b| {
+            b.surface()
+                .cmp(&a.surface())
+                .then_with(|| a.module.cmp(&b.module))
+        });
+        Self { modules }
+    }
+
+    /// Sum of the surfaces of all modules
+    pub fn total_surface(&self) -> usize {
+        self.modules.iter().map(UnsafeModule::surface).sum()
+    }
+}
+
+/// Generate the unsafe surface report to writer
+pub fn generate_unsafe_surface_report<W: Write>(
+    report: &UnsafeSurfaceReport,
+    writer: &mut W,
+) -> io::Result<()> {
+    writeln!(writer, "Unsafe Surface")?;
+    writeln!(writer, "==============")?;
+    writeln!(writer)?;
+
+    if report.modules.is_empty() {
+        writeln!(writer, "No unsafe code found.")?;
+        return Ok(());
+    }
+
+    let width = report
+        .modules
+        .iter()
+        .map(|m| m.module.len())
+        .max()
+        .unwrap_or(0)
+        .max("Module".len());
+    writeln!(
+        writer,
+        "{:<width$}  {:>6}  {:>9}  {:>6}  {:>7}  {:>4}  {:>8}",
+        "Module", "Blocks", "Functions", "Fan-in", "Surface", "Drop", "Teardown"
+    )?;
+    writeln!(writer, "{}", "-".repeat(width + 56))?;
+    for module in &report.modules {
+        writeln!(
+            writer,
+            "{:<width$}  {:>6}  {:>9}  {:>6}  {:>7}  {:>4}  {:>8}",
+            module.module,
+            module.unsafe_blocks.len(),
+            module.unsafe_fns.len(),
+            module.fan_in,
+            module.surface(),
+            module.drop_impls,
+            module.manual_teardowns
+        )?;
+    }
+
+    writeln!(writer)?;
+    writeln!(
+        writer,
+        "{} modules with unsafe code, {} unsafe blocks, {} unsafe functions, total surface {}",
+        report.modules.len(),
+        report
+            .modules
+            .iter()
+            .map(|m| m.unsafe_blocks.len())
+            .sum::<usize>(),
+        report
+            .modules
+            .iter()
+            .map(|m| m.unsafe_fns.len())
+            .sum::<usize>(),
+        report.total_surface()
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::ModuleMetrics;
+    use crate::temporal::TemporalAnalyzer;
+
+    fn module(path: &str, uses: &[&str], code: &str) -> ModuleMetrics {
+        let name = path.trim_start_matches("src/").trim_end_matches(".rs");
+        let mut module = ModuleMetrics::new(PathBuf::from(path), name.into());
+        module.crate_name = Some("app".into());
+        module.use_paths = uses.iter().map(|u| u.to_string()).collect();
+        module.temporal = TemporalAnalyzer::analyze_source(code).unwrap();
+        module
+    }
+
+    #[test]
+    fn test_unsafe_surface() {
+        let mut metrics = ProjectMetrics::new();
+        metrics.add_module(module(
+            "src/buffer.rs",
+            &[],
+            r#"
+            struct Buffer { data: ManuallyDrop<Vec<u8>> }
+            impl Buffer {
+                pub unsafe fn get_unchecked(&self, i: usize) -> u8 { 0 }
+                fn free(&mut self) { unsafe { ManuallyDrop::drop(&mut self.data) } }
+            }
+            "#,
+        ));
+        metrics.add_module(module(
+            "src/leaf.rs",
+            &["crate::buffer::Buffer"],
+            "fn f() { unsafe { a() }; unsafe { b() }; }",
+        ));
+        metrics.add_module(module(
+            "src/user.rs",
+            &["crate::buffer::Buffer"],
+            "fn g() {}",
+        ));
+
+        let report = UnsafeSurfaceReport::build(&metrics);
+        let summary: Vec<(&str, usize, usize, usize, usize)> = report
+            .modules
+            .iter()
+            .map(|m| {
+                (
+                    m.module.as_str(),
+                    m.unsafe_count(),
+                    m.fan_in,
+                    m.surface(),
+                    m.manual_teardowns,
+                )
+            })
+            .collect();
+        // Modules without unsafe code are left out
+        assert_eq!(
+            summary,
+            vec![("app::buffer", 2, 2, 6, 1), ("app::leaf", 2, 0, 2, 0)]
+        );
+
+        let mut out = Vec::new();
+        generate_unsafe_surface_report(&report, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains(
+            "2 modules with unsafe code, 3 unsafe blocks, 1 unsafe functions, total surface 8"
+        ));
+    }
+}
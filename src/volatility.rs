@@ -2,12 +2,28 @@
 //!
 //! Analyzes git log to determine how frequently files change.
 //! Optimized for large repositories using streaming and git path filtering.
+//!
+//! History is read through a [`HistoryBackend`]. The default backend runs
+//! the `git` binary; with the `gix` feature an in-process backend is tried
+//! first. Backends are tried in order, so one that cannot run falls back to
+//! the next.
+//!
+//! The log is read commit by commit, so besides per-file change counts it
+//! also yields co-change (logical coupling) data: how often two files are
+//! modified in the same commit. Pairs that change together frequently but
+//! have no static dependency are a classic sign of hidden coupling. A few
+//! commit subjects are kept per pair as evidence of why they change together.
+//!
+//! The author of each commit is recorded per file as well. Files changed by
+//! many different people in the analysis window show where several teams
+//! have to coordinate (Conway's law).
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::io::{BufRead, BufReader};
 use std::path::Path;
 use std::process::{Command, Stdio};
 
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::metrics::Volatility;
@@ -23,33 +39,159 @@ pub enum VolatilityError {
 
     #[error("Not a git repository")]
     NotGitRepo,
+
+    #[error("Unknown git revision: {0}")]
+    UnknownRevision(String),
+
+    #[error("History backend failed: {0}")]
+    Backend(String),
 }
 
-/// Volatility analyzer using git history
-#[derive(Debug, Default)]
-pub struct VolatilityAnalyzer {
-    /// File path -> change count
-    pub file_changes: HashMap<String, usize>,
-    /// Analysis period in months
-    pub period_months: usize,
+/// Which part of the git history is analyzed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GitWindow {
+    /// Commits from the last N months
+    Months(usize),
+    /// The last N commits that touch Rust files
+    LastCommits(usize),
+    /// Commits after a tag (or any other revision) up to `HEAD`
+    SinceTag(String),
+    /// Commits between two dates (anything `git log --since` accepts)
+    Dates {
+        since: Option<String>,
+        until: Option<String>,
+    },
 }
 
-impl VolatilityAnalyzer {
-    /// Create a new volatility analyzer
-    pub fn new(period_months: usize) -> Self {
-        Self {
-            file_changes: HashMap::new(),
-            period_months,
+impl Default for GitWindow {
+    fn default() -> Self {
+        GitWindow::Months(6)
+    }
+}
+
+impl GitWindow {
+    /// Arguments selecting the window for `git log`
+    pub fn log_args(&self) -> Vec<String> {
+        match self {
+            GitWindow::Months(months) => vec![format!("--since={} months ago", months)],
+            GitWindow::LastCommits(count) => vec![format!("--max-count={}", count)],
+            GitWindow::SinceTag(tag) => vec![format!("{}..HEAD", tag)],
+            GitWindow::Dates { since, until } => since
+                .iter()
+                .map(|date| format!("--since={}", date))
+                .chain(until.iter().map(|date| format!("--until={}", date)))
+                .collect(),
         }
     }
+}
+
+impl std::fmt::Display for GitWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GitWindow::Months(months) => write!(f, "{} months", months),
+            GitWindow::LastCommits(count) => write!(f, "last {} commits", count),
+            GitWindow::SinceTag(tag) => write!(f, "since {}", tag),
+            GitWindow::Dates { since, until } => match (since, until) {
+                (Some(since), Some(until)) => write!(f, "{} to {}", since, until),
+                (Some(since), None) => write!(f, "since {}", since),
+                (None, Some(until)) => write!(f, "until {}", until),
+                (None, None) => write!(f, "all history"),
+            },
+        }
+    }
+}
+
+/// Commits touching more files than this are ignored for co-change analysis
+/// (mass renames, formatting runs and dependency bumps say nothing about
+/// logical coupling)
+pub const MAX_FILES_PER_COMMIT: usize = 50;
+
+/// Minimum number of shared commits before a pair is reported
+pub const MIN_CO_CHANGES: usize = 3;
+
+/// Minimum co-change ratio before a pair is reported
+pub const MIN_CO_CHANGE_RATIO: f64 = 0.5;
+
+/// Commit subjects kept per co-changing pair (the most recent ones)
+pub const MAX_CO_CHANGE_EXAMPLES: usize = 3;
+
+/// Two files that are frequently changed in the same commit
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoChangePair {
+    /// First file (lexicographically smaller path)
+    pub file_a: String,
+    /// Second file
+    pub file_b: String,
+    /// Number of commits touching both files
+    pub co_changes: usize,
+    /// Shared commits divided by the change count of the less frequently
+    /// changed file (0.0 - 1.0)
+    pub ratio: f64,
+    /// Subjects of recent commits touching both files, newest first
+    pub examples: Vec<String>,
+}
+
+/// A commit read from the history
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommitRecord {
+    pub author: String,
+    pub subject: String,
+    /// Changed `.rs` files, relative to the repository root
+    pub files: Vec<String>,
+}
+
+/// A way of reading commit history
+///
+/// [`VolatilityAnalyzer::analyze_with`] tries backends in order, so an
+/// in-process implementation can come first with `git` as the fallback.
+pub trait HistoryBackend {
+    /// Short name for diagnostics
+    fn name(&self) -> &'static str;
+
+    /// Pass every commit in `window` that adds, modifies, renames or copies
+    /// a `.rs` file to `record`, newest first
+    fn read_commits(
+        &self,
+        repo_path: &Path,
+        window: &GitWindow,
+        record: &mut dyn FnMut(CommitRecord),
+    ) -> Result<(), VolatilityError>;
+}
+
+/// Backends used by [`VolatilityAnalyzer::analyze`]
+///
+/// With the `gix` feature the history is read in-process first, with `git`
+/// as the fallback.
+pub fn default_backends() -> Vec<Box<dyn HistoryBackend>> {
+    vec![
+        #[cfg(feature = "gix")]
+        Box::new(crate::gix_backend::GixBackend),
+        Box::new(GitCommandBackend),
+    ]
+}
+
+/// Reads history by running `git log` and streaming its output
+///
+/// Optimizations applied:
+/// 1. Use `-- "*.rs"` to filter .rs files at git level
+/// 2. Use streaming with BufReader instead of loading all into memory
+/// 3. Use `--diff-filter=AMRC` to skip deleted files
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GitCommandBackend;
+
+impl HistoryBackend for GitCommandBackend {
+    fn name(&self) -> &'static str {
+        "git"
+    }
+
+    fn read_commits(
+        &self,
+        repo_path: &Path,
+        window: &GitWindow,
+        record: &mut dyn FnMut(CommitRecord),
+    ) -> Result<(), VolatilityError> {
+        let _span = tracing::debug_span!("git", args = "log").entered();
 
-    /// Analyze git history for a repository (optimized version)
-    ///
-    /// Optimizations applied:
-    /// 1. Use `-- "*.rs"` to filter .rs files at git level
-    /// 2. Use streaming with BufReader instead of loading all into memory
-    /// 3. Use `--diff-filter=AMRC` to skip deleted files
-    pub fn analyze(&mut self, repo_path: &Path) -> Result<(), VolatilityError> {
         // Check if it's a git repo
         let git_check = Command::new("git")
             .args(["rev-parse", "--git-dir"])
@@ -61,15 +203,34 @@ impl VolatilityAnalyzer {
             return Err(VolatilityError::NotGitRepo);
         }
 
-        // Optimized: use --diff-filter and path spec to reduce output
+        if let GitWindow::SinceTag(tag) = window {
+            let tag_check = Command::new("git")
+                .args([
+                    "rev-parse",
+                    "--verify",
+                    "--quiet",
+                    &format!("{}^{{commit}}", tag),
+                ])
+                .current_dir(repo_path)
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()?;
+            if !tag_check.success() {
+                return Err(VolatilityError::UnknownRevision(tag.clone()));
+            }
+        }
+
         // --diff-filter=AMRC: Added, Modified, Renamed, Copied (skip Deleted)
         let mut child = Command::new("git")
+            .arg("log")
+            .args(window.log_args())
             .args([
-                "log",
-                "--pretty=format:",
+                // Record separator marks the start of each commit, followed
+                // by the (mailmap-resolved) author name and, after a unit
+                // separator, the subject
+                "--pretty=format:%x1e%aN%x1f%s",
                 "--name-only",
                 "--diff-filter=AMRC",
-                &format!("--since={} months ago", self.period_months),
                 "--",
                 "*.rs",
             ])
@@ -78,9 +239,10 @@ impl VolatilityAnalyzer {
             .stderr(Stdio::null())
             .spawn()?;
 
-        // Stream processing with BufReader
+        // Stream processing with BufReader, one commit at a time
         if let Some(stdout) = child.stdout.take() {
             let reader = BufReader::with_capacity(64 * 1024, stdout); // 64KB buffer
+            let mut commit = CommitRecord::default();
 
             for line in reader.lines() {
                 let line = match line {
@@ -88,11 +250,20 @@ impl VolatilityAnalyzer {
                     Err(_) => continue,
                 };
 
+                if let Some(header) = line.strip_prefix('\x1e') {
+                    record(std::mem::take(&mut commit));
+                    let (name, summary) = header.split_once('\x1f').unwrap_or((header, ""));
+                    commit.author = name.trim().to_string();
+                    commit.subject = summary.trim().to_string();
+                    continue;
+                }
+
                 let line = line.trim();
                 if !line.is_empty() && line.ends_with(".rs") {
-                    *self.file_changes.entry(line.to_string()).or_insert(0) += 1;
+                    commit.files.push(line.to_string());
                 }
             }
+            record(commit);
         }
 
         // Wait for git to finish
@@ -100,6 +271,199 @@ impl VolatilityAnalyzer {
 
         Ok(())
     }
+}
+
+/// Volatility analyzer using git history
+#[derive(Debug, Default)]
+pub struct VolatilityAnalyzer {
+    /// File path -> change count
+    pub file_changes: BTreeMap<String, usize>,
+    /// (file, file) -> number of commits touching both (keys are ordered)
+    pub co_changes: HashMap<(String, String), usize>,
+    /// (file, file) -> subjects of the first commits recorded for the pair
+    pub co_change_subjects: HashMap<(String, String), Vec<String>>,
+    /// File path -> author name -> number of commits
+    pub file_authors: BTreeMap<String, BTreeMap<String, usize>>,
+    /// Number of commits analyzed
+    pub commit_count: usize,
+    /// Part of the history that is analyzed
+    pub window: GitWindow,
+    /// Name of the backend that read the history
+    pub backend: Option<&'static str>,
+}
+
+impl VolatilityAnalyzer {
+    /// Create a new volatility analyzer for the last `period_months` months
+    pub fn new(period_months: usize) -> Self {
+        Self::with_window(GitWindow::Months(period_months))
+    }
+
+    /// Create a new volatility analyzer for a custom history window
+    pub fn with_window(window: GitWindow) -> Self {
+        Self {
+            window,
+            ..Default::default()
+        }
+    }
+
+    /// Analyze git history for a repository with the default backends
+    pub fn analyze(&mut self, repo_path: &Path) -> Result<(), VolatilityError> {
+        self.analyze_with(repo_path, &default_backends())
+    }
+
+    /// Analyze git history, trying each backend in turn
+    ///
+    /// A backend that cannot run at all ([`VolatilityError::GitCommand`],
+    /// e.g. no `git` binary) or fails while reading
+    /// ([`VolatilityError::Backend`]) falls back to the next one; any other
+    /// error is returned as is.
+    pub fn analyze_with(
+        &mut self,
+        repo_path: &Path,
+        backends: &[Box<dyn HistoryBackend>],
+    ) -> Result<(), VolatilityError> {
+        let window = self.window.clone();
+        let _span = tracing::info_span!("git_history", window = %window).entered();
+        let mut last_error = None;
+        for backend in backends {
+            // Commits read before a failure must not count twice once the
+            // next backend reads them again
+            let mut commits = Vec::new();
+            match backend.read_commits(repo_path, &window, &mut |commit| commits.push(commit)) {
+                Ok(()) => {
+                    for commit in &commits {
+                        self.record(commit);
+                    }
+                    self.backend = Some(backend.name());
+                    return Ok(());
+                }
+                Err(e @ (VolatilityError::GitCommand(_) | VolatilityError::Backend(_))) => {
+                    tracing::debug!(backend = backend.name(), error = %e, "history backend failed");
+                    last_error = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error.unwrap_or(VolatilityError::NotGitRepo))
+    }
+
+    /// Record a commit read by a backend
+    pub fn record(&mut self, commit: &CommitRecord) {
+        self.record_authored_commit(&commit.author, &commit.files);
+        self.record_subject(&commit.subject, &commit.files);
+    }
+
+    /// Record the files changed by a single commit
+    pub fn record_commit(&mut self, files: &[String]) {
+        if files.is_empty() {
+            return;
+        }
+        self.commit_count += 1;
+
+        let mut files: Vec<&String> = files.iter().collect();
+        files.sort();
+        files.dedup();
+
+        for file in &files {
+            *self.file_changes.entry((*file).clone()).or_insert(0) += 1;
+        }
+
+        if files.len() > MAX_FILES_PER_COMMIT {
+            return;
+        }
+        for (i, a) in files.iter().enumerate() {
+            for b in &files[i + 1..] {
+                *self
+                    .co_changes
+                    .entry(((*a).clone(), (*b).clone()))
+                    .or_insert(0) += 1;
+            }
+        }
+    }
+
+    /// Record a commit and its author
+    pub fn record_authored_commit(&mut self, author: &str, files: &[String]) {
+        self.record_commit(files);
+        if author.is_empty() {
+            return;
+        }
+        for file in files {
+            *self
+                .file_authors
+                .entry(file.clone())
+                .or_default()
+                .entry(author.to_string())
+                .or_insert(0) += 1;
+        }
+    }
+
+    /// Keep a commit subject as an example for every pair of its files
+    ///
+    /// Commits are read newest first, so the first subjects are the most
+    /// recent. Large commits are skipped like in [`Self::record_commit`].
+    pub fn record_subject(&mut self, subject: &str, files: &[String]) {
+        if subject.is_empty() || files.len() > MAX_FILES_PER_COMMIT {
+            return;
+        }
+        let mut files: Vec<&String> = files.iter().collect();
+        files.sort();
+        files.dedup();
+        for (i, a) in files.iter().enumerate() {
+            for b in &files[i + 1..] {
+                let examples = self
+                    .co_change_subjects
+                    .entry(((*a).clone(), (*b).clone()))
+                    .or_default();
+                if examples.len() < MAX_CO_CHANGE_EXAMPLES && !examples.iter().any(|s| s == subject)
+                {
+                    examples.push(subject.to_string());
+                }
+            }
+        }
+    }
+
+    /// Number of distinct authors that changed a file
+    pub fn author_count(&self, file_path: &str) -> usize {
+        self.file_authors.get(file_path).map_or(0, BTreeMap::len)
+    }
+
+    /// Get file pairs that frequently change together
+    ///
+    /// Returns pairs with at least `min_co_changes` shared commits and a
+    /// co-change ratio of at least `min_ratio`, strongest first.
+    pub fn co_change_pairs(&self, min_co_changes: usize, min_ratio: f64) -> Vec<CoChangePair> {
+        let mut pairs: Vec<CoChangePair> = self
+            .co_changes
+            .iter()
+            .filter(|&(_, &count)| count >= min_co_changes)
+            .filter_map(|((a, b), &count)| {
+                let changes_a = self.get_change_count(a);
+                let changes_b = self.get_change_count(b);
+                let ratio = count as f64 / changes_a.min(changes_b).max(1) as f64;
+                (ratio >= min_ratio).then(|| CoChangePair {
+                    file_a: a.clone(),
+                    file_b: b.clone(),
+                    co_changes: count,
+                    ratio,
+                    examples: self
+                        .co_change_subjects
+                        .get(&(a.clone(), b.clone()))
+                        .cloned()
+                        .unwrap_or_default(),
+                })
+            })
+            .collect();
+
+        pairs.sort_by(|x, y| {
+            y.ratio
+                .partial_cmp(&x.ratio)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| y.co_changes.cmp(&x.co_changes))
+                .then_with(|| x.file_a.cmp(&y.file_a))
+                .then_with(|| x.file_b.cmp(&y.file_b))
+        });
+        pairs
+    }
 
     /// Get volatility level for a file
     pub fn get_volatility(&self, file_path: &str) -> Volatility {
@@ -123,11 +487,31 @@ impl VolatilityAnalyzer {
 
     /// Get volatility statistics
     pub fn statistics(&self) -> VolatilityStats {
-        if self.file_changes.is_empty() {
-            return VolatilityStats::default();
+        VolatilityStats::from_counts(self.file_changes.values().copied())
+    }
+}
+
+/// Statistics about volatility across the project
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VolatilityStats {
+    pub total_files: usize,
+    pub total_changes: usize,
+    pub max_changes: usize,
+    pub min_changes: usize,
+    pub avg_changes: f64,
+    pub low_volatility_count: usize,
+    pub medium_volatility_count: usize,
+    pub high_volatility_count: usize,
+}
+
+impl VolatilityStats {
+    /// Statistics of per-file change counts
+    pub fn from_counts(counts: impl IntoIterator<Item = usize>) -> Self {
+        let counts: Vec<usize> = counts.into_iter().collect();
+        if counts.is_empty() {
+            return Self::default();
         }
 
-        let counts: Vec<usize> = self.file_changes.values().copied().collect();
         let total: usize = counts.iter().sum();
         let max = counts.iter().max().copied().unwrap_or(0);
         let min = counts.iter().min().copied().unwrap_or(0);
@@ -137,7 +521,7 @@ impl VolatilityAnalyzer {
         let medium_count = counts.iter().filter(|&&c| c > 2 && c <= 10).count();
         let high_count = counts.iter().filter(|&&c| c > 10).count();
 
-        VolatilityStats {
+        Self {
             total_files: counts.len(),
             total_changes: total,
             max_changes: max,
@@ -150,19 +534,6 @@ impl VolatilityAnalyzer {
     }
 }
 
-/// Statistics about volatility across the project
-#[derive(Debug, Default)]
-pub struct VolatilityStats {
-    pub total_files: usize,
-    pub total_changes: usize,
-    pub max_changes: usize,
-    pub min_changes: usize,
-    pub avg_changes: f64,
-    pub low_volatility_count: usize,
-    pub medium_volatility_count: usize,
-    pub high_volatility_count: usize,
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -209,4 +580,192 @@ mod tests {
         assert_eq!(stats.medium_volatility_count, 1);
         assert_eq!(stats.high_volatility_count, 1);
     }
+
+    #[test]
+    fn test_record_commit_counts_pairs() {
+        let mut analyzer = VolatilityAnalyzer::new(6);
+        let commit = |files: &[&str]| files.iter().map(|f| f.to_string()).collect::<Vec<_>>();
+
+        for _ in 0..3 {
+            analyzer.record_commit(&commit(&["src/b.rs", "src/a.rs"]));
+        }
+        analyzer.record_commit(&commit(&["src/a.rs", "src/c.rs"]));
+
+        assert_eq!(analyzer.commit_count, 4);
+        assert_eq!(analyzer.get_change_count("src/a.rs"), 4);
+        assert_eq!(
+            analyzer
+                .co_changes
+                .get(&("src/a.rs".to_string(), "src/b.rs".to_string())),
+            Some(&3)
+        );
+
+        let pairs = analyzer.co_change_pairs(MIN_CO_CHANGES, MIN_CO_CHANGE_RATIO);
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].file_a, "src/a.rs");
+        assert_eq!(pairs[0].file_b, "src/b.rs");
+        assert!((pairs[0].ratio - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_record_authored_commit() {
+        let mut analyzer = VolatilityAnalyzer::new(6);
+        let files = vec!["src/a.rs".to_string(), "src/b.rs".to_string()];
+        analyzer.record_authored_commit("alice", &files);
+        analyzer.record_authored_commit("bob", &files[..1]);
+        analyzer.record_authored_commit("alice", &files[..1]);
+
+        assert_eq!(analyzer.get_change_count("src/a.rs"), 3);
+        assert_eq!(analyzer.author_count("src/a.rs"), 2);
+        assert_eq!(analyzer.author_count("src/b.rs"), 1);
+        assert_eq!(analyzer.file_authors["src/a.rs"]["alice"], 2);
+    }
+
+    #[test]
+    fn test_co_change_examples() {
+        let mut analyzer = VolatilityAnalyzer::new(6);
+        let files = vec!["src/b.rs".to_string(), "src/a.rs".to_string()];
+        for subject in [
+            "fix serialization",
+            "fix serialization",
+            "bump wire version",
+            "",
+            "a",
+            "b",
+        ] {
+            analyzer.record_authored_commit("alice", &files);
+            analyzer.record_subject(subject, &files);
+        }
+
+        let pairs = analyzer.co_change_pairs(MIN_CO_CHANGES, MIN_CO_CHANGE_RATIO);
+        assert_eq!(pairs[0].co_changes, 6);
+        assert_eq!(
+            pairs[0].examples,
+            vec!["fix serialization", "bump wire version", "a"]
+        );
+    }
+
+    #[test]
+    fn test_git_window_args() {
+        assert_eq!(
+            GitWindow::default().log_args(),
+            vec!["--since=6 months ago"]
+        );
+        assert_eq!(
+            GitWindow::LastCommits(500).log_args(),
+            vec!["--max-count=500"]
+        );
+        assert_eq!(
+            GitWindow::SinceTag("v1.0.0".into()).log_args(),
+            vec!["v1.0.0..HEAD"]
+        );
+        let dates = GitWindow::Dates {
+            since: Some("2024-01-01".into()),
+            until: Some("2024-06-30".into()),
+        };
+        assert_eq!(
+            dates.log_args(),
+            vec!["--since=2024-01-01", "--until=2024-06-30"]
+        );
+        assert_eq!(dates.to_string(), "2024-01-01 to 2024-06-30");
+    }
+
+    struct FakeBackend(Option<Vec<CommitRecord>>);
+
+    impl HistoryBackend for FakeBackend {
+        fn name(&self) -> &'static str {
+            "fake"
+        }
+
+        fn read_commits(
+            &self,
+            _: &Path,
+            _: &GitWindow,
+            record: &mut dyn FnMut(CommitRecord),
+        ) -> Result<(), VolatilityError> {
+            let commits = self
+                .0
+                .as_ref()
+                .ok_or_else(|| VolatilityError::GitCommand(std::io::ErrorKind::NotFound.into()))?;
+            commits.iter().cloned().for_each(record);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_backend_fallback() {
+        let commit = CommitRecord {
+            author: "alice".into(),
+            subject: "fix".into(),
+            files: vec!["src/a.rs".into()],
+        };
+        let backends: Vec<Box<dyn HistoryBackend>> = vec![
+            Box::new(FakeBackend(None)),
+            Box::new(FakeBackend(Some(vec![commit.clone(), commit]))),
+        ];
+        let mut analyzer = VolatilityAnalyzer::new(6);
+        analyzer.analyze_with(Path::new("."), &backends).unwrap();
+        assert_eq!(analyzer.get_change_count("src/a.rs"), 2);
+        assert_eq!(analyzer.backend, Some("fake"));
+
+        let unavailable: Vec<Box<dyn HistoryBackend>> = vec![Box::new(FakeBackend(None))];
+        assert!(matches!(
+            VolatilityAnalyzer::new(6).analyze_with(Path::new("."), &unavailable),
+            Err(VolatilityError::GitCommand(_))
+        ));
+    }
+
+    /// Reads one commit, then fails
+    struct FailingBackend(CommitRecord);
+
+    impl HistoryBackend for FailingBackend {
+        fn name(&self) -> &'static str {
+            "failing"
+        }
+
+        fn read_commits(
+            &self,
+            _: &Path,
+            _: &GitWindow,
+            record: &mut dyn FnMut(CommitRecord),
+        ) -> Result<(), VolatilityError> {
+            record(self.0.clone());
+            Err(VolatilityError::Backend("corrupt object".into()))
+        }
+    }
+
+    #[test]
+    fn test_failed_backend_records_nothing() {
+        let commit = CommitRecord {
+            author: "alice".into(),
+            subject: "fix".into(),
+            files: vec!["src/a.rs".into(), "src/b.rs".into()],
+        };
+        let backends: Vec<Box<dyn HistoryBackend>> = vec![
+            Box::new(FailingBackend(commit.clone())),
+            Box::new(FakeBackend(Some(vec![commit]))),
+        ];
+        let mut analyzer = VolatilityAnalyzer::new(6);
+        analyzer.analyze_with(Path::new("."), &backends).unwrap();
+        assert_eq!(analyzer.backend, Some("fake"));
+        assert_eq!(analyzer.commit_count, 1);
+        assert_eq!(analyzer.get_change_count("src/a.rs"), 1);
+        assert_eq!(analyzer.file_authors["src/a.rs"]["alice"], 1);
+        assert_eq!(
+            analyzer.co_changes[&("src/a.rs".to_string(), "src/b.rs".to_string())],
+            1
+        );
+    }
+
+    #[test]
+    fn test_large_commits_skip_pairs() {
+        let mut analyzer = VolatilityAnalyzer::new(6);
+        let files: Vec<String> = (0..=MAX_FILES_PER_COMMIT)
+            .map(|i| format!("src/f{}.rs", i))
+            .collect();
+        analyzer.record_commit(&files);
+
+        assert_eq!(analyzer.file_changes.len(), MAX_FILES_PER_COMMIT + 1);
+        assert!(analyzer.co_changes.is_empty());
+    }
 }
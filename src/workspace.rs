@@ -2,13 +2,26 @@
 //!
 //! This module uses `cargo metadata` to understand the project structure,
 //! including workspace members, dependencies, and module organization.
+//!
+//! Dependencies come from cargo's resolve graph, so they are the ones that
+//! build with the selected features ([`FeatureSelection`]): optional
+//! dependencies that no enabled feature turns on are left out, and
+//! dependencies restricted to a platform (`[target.'cfg(windows)'.dependencies]`)
+//! are listed separately as well.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
-use cargo_metadata::{Metadata, MetadataCommand, PackageId};
+use cargo_metadata::{
+    CargoOpt, DependencyKind, Metadata, MetadataCommand, Node, Package, PackageId,
+};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::edition::Edition;
+use crate::graph::{DependencyCycle, WeightedGraph, find_cycles};
+use crate::paths::{PathFilter, rs_files};
+
 /// Errors that can occur during workspace analysis
 #[derive(Error, Debug)]
 pub enum WorkspaceError {
@@ -33,12 +46,176 @@ pub struct CrateInfo {
     pub src_path: PathBuf,
     /// Path to Cargo.toml
     pub manifest_path: PathBuf,
-    /// Direct dependencies (crate names)
+    /// Direct normal and build dependencies (crate names)
     pub dependencies: Vec<String>,
     /// Dev dependencies
     pub dev_dependencies: Vec<String>,
+    /// Dependencies among `dependencies` that only the build script uses
+    pub build_dependencies: Vec<String>,
+    /// Dependencies among `dependencies` that only apply on some platforms
+    pub target_dependencies: Vec<TargetDependency>,
+    /// Optional dependencies not enabled by the selected features, by the
+    /// name the code refers to them with
+    pub inactive_dependencies: Vec<String>,
+    /// Code name and version of each dependency, by crate name
+    pub resolved_dependencies: HashMap<String, ResolvedDependency>,
     /// Is this a workspace member?
     pub is_workspace_member: bool,
+    /// Edition the crate is written in
+    pub edition: Edition,
+    /// Is this a procedural macro crate?
+    pub is_proc_macro: bool,
+}
+
+/// A dependency that only applies on some platforms
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct TargetDependency {
+    pub name: String,
+    /// `cfg(...)` expression or target triple
+    pub platform: String,
+}
+
+/// How a crate refers to a dependency, and which version it gets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedDependency {
+    /// Name in code: the library name, or the name it is renamed to
+    /// (`serde_json`)
+    pub library: String,
+    /// Resolved version (`None` without a resolve graph)
+    pub version: Option<String>,
+}
+
+/// Features `cargo metadata` resolves dependencies with, like the cargo
+/// `--features`, `--all-features` and `--no-default-features` flags
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeatureSelection {
+    pub features: Vec<String>,
+    pub all_features: bool,
+    pub no_default_features: bool,
+}
+
+impl FeatureSelection {
+    fn apply(&self, command: &mut MetadataCommand) {
+        if self.all_features {
+            command.features(CargoOpt::AllFeatures);
+        }
+        if self.no_default_features {
+            command.features(CargoOpt::NoDefaultFeatures);
+        }
+        if !self.features.is_empty() {
+            command.features(CargoOpt::SomeFeatures(self.features.clone()));
+        }
+    }
+}
+
+/// Dependencies of one package, split by kind
+#[derive(Debug, Default)]
+struct PackageDeps {
+    normal: Vec<String>,
+    dev: Vec<String>,
+    /// Build-dependencies, minus normal dependencies once finished
+    build: Vec<String>,
+    /// Normal (not build or dev) dependencies
+    linked: HashSet<String>,
+    target: Vec<TargetDependency>,
+    inactive: Vec<String>,
+    resolved: HashMap<String, ResolvedDependency>,
+    /// Normal dependencies that apply on every platform
+    unconditional: HashSet<String>,
+}
+
+impl PackageDeps {
+    /// Dependencies as declared in the manifest, for metadata without a
+    /// resolve graph
+    fn declared(package: &Package) -> Self {
+        let mut deps = Self::default();
+        for dep in &package.dependencies {
+            deps.add(
+                &dep.name,
+                dep.kind,
+                dep.target.as_ref().map(ToString::to_string),
+            );
+            let library = dep.rename.as_ref().unwrap_or(&dep.name).replace('-', "_");
+            deps.resolved
+                .entry(dep.name.clone())
+                .or_insert(ResolvedDependency {
+                    library,
+                    version: None,
+                });
+        }
+        deps.finish()
+    }
+
+    /// Dependencies cargo resolved for the selected features
+    fn resolved(package: &Package, node: &Node, packages: &HashMap<&PackageId, &Package>) -> Self {
+        let mut deps = Self::default();
+        for dep in &node.deps {
+            let Some(target) = packages.get(&dep.pkg) else {
+                continue;
+            };
+            for kind in &dep.dep_kinds {
+                deps.add(
+                    &target.name,
+                    kind.kind,
+                    kind.target.as_ref().map(ToString::to_string),
+                );
+            }
+            deps.resolved.insert(
+                target.name.clone(),
+                ResolvedDependency {
+                    library: dep.name.clone(),
+                    version: Some(target.version.to_string()),
+                },
+            );
+        }
+        deps.inactive = package
+            .dependencies
+            .iter()
+            .filter(|dep| {
+                dep.optional && !deps.normal.contains(&dep.name) && !deps.dev.contains(&dep.name)
+            })
+            .map(|dep| dep.rename.clone().unwrap_or_else(|| dep.name.clone()))
+            .collect();
+        deps.finish()
+    }
+
+    fn add(&mut self, name: &str, kind: DependencyKind, platform: Option<String>) {
+        let list = if kind == DependencyKind::Development {
+            &mut self.dev
+        } else {
+            if kind == DependencyKind::Build {
+                if !self.build.iter().any(|dep| dep == name) {
+                    self.build.push(name.to_string());
+                }
+            } else {
+                self.linked.insert(name.to_string());
+            }
+            match platform {
+                Some(platform) => self.target.push(TargetDependency {
+                    name: name.to_string(),
+                    platform,
+                }),
+                None => {
+                    self.unconditional.insert(name.to_string());
+                }
+            }
+            &mut self.normal
+        };
+        if !list.iter().any(|dep| dep == name) {
+            list.push(name.to_string());
+        }
+    }
+
+    /// Drop platform entries of dependencies that also apply everywhere,
+    /// and build entries of dependencies the crate itself uses
+    fn finish(mut self) -> Self {
+        self.build.retain(|dep| !self.linked.contains(dep));
+        self.target
+            .retain(|dep| !self.unconditional.contains(&dep.name));
+        self.target.sort();
+        self.target.dedup();
+        self
+    }
 }
 
 /// Information about the entire workspace
@@ -46,26 +223,42 @@ pub struct CrateInfo {
 pub struct WorkspaceInfo {
     /// Root directory of the workspace
     pub root: PathBuf,
+    /// Directory cargo writes build output to
+    pub target_directory: PathBuf,
     /// All crates in the workspace
-    pub crates: HashMap<String, CrateInfo>,
+    pub crates: BTreeMap<String, CrateInfo>,
     /// Workspace members (crate names)
     pub members: Vec<String>,
+    /// Members cargo builds without `--package` or `--workspace`: the
+    /// `default-members` list, else the root package, else (in a virtual
+    /// workspace) every member
+    pub default_members: Vec<String>,
     /// Dependency graph: crate name -> dependencies
-    pub dependency_graph: HashMap<String, HashSet<String>>,
+    pub dependency_graph: BTreeMap<String, BTreeSet<String>>,
     /// Reverse dependency graph: crate name -> dependents
-    pub reverse_deps: HashMap<String, HashSet<String>>,
+    pub reverse_deps: BTreeMap<String, BTreeSet<String>>,
 }
 
 impl WorkspaceInfo {
-    /// Analyze a workspace from a path
+    /// Analyze a workspace from a path, with the default features
     pub fn from_path(path: &Path) -> Result<Self, WorkspaceError> {
+        Self::from_path_with(path, &FeatureSelection::default())
+    }
+
+    /// Analyze a workspace from a path, resolving dependencies for `features`
+    pub fn from_path_with(
+        path: &Path,
+        features: &FeatureSelection,
+    ) -> Result<Self, WorkspaceError> {
         // Find Cargo.toml
         let manifest_path = find_cargo_toml(path)?;
 
         // Run cargo metadata
-        let metadata = MetadataCommand::new()
-            .manifest_path(&manifest_path)
-            .exec()?;
+        let _span = tracing::debug_span!("cargo_metadata").entered();
+        let mut command = MetadataCommand::new();
+        command.manifest_path(&manifest_path);
+        features.apply(&mut command);
+        let metadata = command.exec()?;
 
         Self::from_metadata(metadata)
     }
@@ -73,14 +266,26 @@ impl WorkspaceInfo {
     /// Create workspace info from cargo metadata
     pub fn from_metadata(metadata: Metadata) -> Result<Self, WorkspaceError> {
         let root = metadata.workspace_root.as_std_path().to_path_buf();
+        let target_directory = metadata.target_directory.as_std_path().to_path_buf();
 
-        let mut crates = HashMap::new();
+        let mut crates = BTreeMap::new();
         let mut members = Vec::new();
-        let mut dependency_graph: HashMap<String, HashSet<String>> = HashMap::new();
-        let mut reverse_deps: HashMap<String, HashSet<String>> = HashMap::new();
+        let mut dependency_graph: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        let mut reverse_deps: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
 
         // Collect workspace members
         let workspace_member_ids: HashSet<_> = metadata.workspace_members.iter().collect();
+        let packages: HashMap<&PackageId, &Package> = metadata
+            .packages
+            .iter()
+            .map(|package| (&package.id, package))
+            .collect();
+        let nodes: HashMap<&PackageId, &Node> = metadata
+            .resolve
+            .iter()
+            .flat_map(|resolve| &resolve.nodes)
+            .map(|node| (&node.id, node))
+            .collect();
 
         // Process all packages
         for package in &metadata.packages {
@@ -98,25 +303,21 @@ impl WorkspaceInfo {
                 .unwrap_or_default();
 
             // Collect dependencies
-            let mut deps = Vec::new();
-            let mut dev_deps = Vec::new();
-
-            for dep in &package.dependencies {
-                if dep.kind == cargo_metadata::DependencyKind::Development {
-                    dev_deps.push(dep.name.clone());
-                } else {
-                    deps.push(dep.name.clone());
-                }
+            let deps = match nodes.get(&package.id) {
+                Some(node) => PackageDeps::resolved(package, node, &packages),
+                None => PackageDeps::declared(package),
+            };
 
+            for dep in deps.normal.iter().chain(&deps.dev) {
                 // Build dependency graph
                 dependency_graph
                     .entry(package.name.clone())
                     .or_default()
-                    .insert(dep.name.clone());
+                    .insert(dep.clone());
 
                 // Build reverse dependency graph
                 reverse_deps
-                    .entry(dep.name.clone())
+                    .entry(dep.clone())
                     .or_default()
                     .insert(package.name.clone());
             }
@@ -126,18 +327,39 @@ impl WorkspaceInfo {
                 id: package.id.clone(),
                 src_path,
                 manifest_path: package.manifest_path.as_std_path().to_path_buf(),
-                dependencies: deps,
-                dev_dependencies: dev_deps,
+                dependencies: deps.normal,
+                dev_dependencies: deps.dev,
+                build_dependencies: deps.build,
+                target_dependencies: deps.target,
+                inactive_dependencies: deps.inactive,
+                resolved_dependencies: deps.resolved,
                 is_workspace_member,
+                edition: package.edition.into(),
+                is_proc_macro: package.targets.iter().any(|t| t.is_proc_macro()),
             };
 
             crates.insert(package.name.clone(), crate_info);
         }
 
+        // Cargo older than 1.71 does not report default members
+        let default_members = if metadata.workspace_default_members.is_available() {
+            let ids: HashSet<_> = metadata.workspace_default_members.iter().collect();
+            metadata
+                .packages
+                .iter()
+                .filter(|package| ids.contains(&package.id))
+                .map(|package| package.name.clone())
+                .collect()
+        } else {
+            members.clone()
+        };
+
         Ok(Self {
             root,
+            target_directory,
             crates,
             members,
+            default_members,
             dependency_graph,
             reverse_deps,
         })
@@ -153,13 +375,24 @@ impl WorkspaceInfo {
         self.members.contains(&name.to_string())
     }
 
+    /// Whether `dependency` is an optional dependency of `krate` that the
+    /// selected features leave disabled
+    pub fn is_inactive_dependency(&self, krate: &str, dependency: &str) -> bool {
+        let normalized = dependency.replace('-', "_");
+        self.crates.get(krate).is_some_and(|info| {
+            info.inactive_dependencies
+                .iter()
+                .any(|dep| dep.replace('-', "_") == normalized)
+        })
+    }
+
     /// Get direct dependencies of a crate
-    pub fn get_dependencies(&self, name: &str) -> Option<&HashSet<String>> {
+    pub fn get_dependencies(&self, name: &str) -> Option<&BTreeSet<String>> {
         self.dependency_graph.get(name)
     }
 
     /// Get crates that depend on this crate
-    pub fn get_dependents(&self, name: &str) -> Option<&HashSet<String>> {
+    pub fn get_dependents(&self, name: &str) -> Option<&BTreeSet<String>> {
         self.reverse_deps.get(name)
     }
 
@@ -204,24 +437,86 @@ impl WorkspaceInfo {
         None
     }
 
-    /// Get all source files for workspace members
-    pub fn get_all_source_files(&self) -> Vec<PathBuf> {
+    /// Dependency cycles between workspace members
+    ///
+    /// Cargo rejects cycles of normal dependencies, but a dev-dependency can
+    /// close one (`a` dev-depends on `b`, which depends on `a`). Edges that
+    /// are only dev-dependencies weigh 1 and normal dependencies 2, so the
+    /// suggested edge to break is a dev-dependency where possible.
+    pub fn crate_cycles(&self) -> Vec<DependencyCycle> {
+        let mut graph = WeightedGraph::new();
+        for member in &self.members {
+            let Some(info) = self.crates.get(member) else {
+                continue;
+            };
+            let edges = graph.entry(member.clone()).or_default();
+            for dep in &info.dev_dependencies {
+                if self.is_workspace_member(dep) {
+                    edges.insert(dep.clone(), 1);
+                }
+            }
+            for dep in &info.dependencies {
+                if self.is_workspace_member(dep) {
+                    edges.insert(dep.clone(), 2);
+                }
+            }
+        }
+        find_cycles(&graph)
+    }
+
+    /// Workspace members selected by `--package` specs, in member order
+    ///
+    /// Specs are package names or glob patterns (`my-*`); `-` and `_` are
+    /// interchangeable. No specs select the default members. A spec that
+    /// matches no member is an error.
+    pub fn select_members(&self, specs: &[String]) -> Result<Vec<String>, WorkspaceError> {
+        if specs.is_empty() {
+            return Ok(self
+                .members
+                .iter()
+                .filter(|m| self.default_members.contains(m))
+                .cloned()
+                .collect());
+        }
+
+        let normalize = |name: &str| name.replace('_', "-");
+        let mut selected = HashSet::new();
+        for spec in specs {
+            let spec = normalize(spec);
+            let pattern = glob::Pattern::new(&spec).ok();
+            let matches: Vec<&String> = self
+                .members
+                .iter()
+                .filter(|m| {
+                    let name = normalize(m);
+                    name == spec || pattern.as_ref().is_some_and(|p| p.matches(&name))
+                })
+                .collect();
+            if matches.is_empty() {
+                return Err(WorkspaceError::PackageNotFound(spec));
+            }
+            selected.extend(matches);
+        }
+
+        Ok(self
+            .members
+            .iter()
+            .filter(|m| selected.contains(m))
+            .cloned()
+            .collect())
+    }
+
+    /// Get all source files for workspace members that pass `filter`
+    pub fn get_all_source_files(&self, filter: &PathFilter) -> Vec<PathBuf> {
         let mut files = Vec::new();
 
         for member in &self.members {
             if let Some(crate_info) = self.crates.get(member)
                 && crate_info.src_path.exists()
             {
-                for entry in walkdir::WalkDir::new(&crate_info.src_path)
-                    .follow_links(true)
-                    .into_iter()
-                    .filter_map(|e| e.ok())
-                {
-                    let path = entry.path();
-                    if path.extension().is_some_and(|ext| ext == "rs") {
-                        files.push(path.to_path_buf());
-                    }
-                }
+                files.extend(
+                    rs_files(&crate_info.src_path).filter(|path| filter.allows(path, &self.root)),
+                );
             }
         }
 
@@ -288,6 +583,11 @@ pub fn resolve_crate_from_path(
                 }
             }
 
+            // A bare name (`User`) is an item in scope, not a crate
+            if parts.len() == 1 {
+                return None;
+            }
+
             // Assume it's an external crate
             Some(first_segment.to_string())
         }
@@ -306,14 +606,108 @@ mod tests {
         assert!(result.unwrap().ends_with("Cargo.toml"));
     }
 
+    #[test]
+    fn test_feature_and_target_dependencies() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let write = |path: &str, content: &str| {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write(
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"app\", \"helper\", \"win\"]\nresolver = \"2\"\n",
+        );
+        write(
+            "app/Cargo.toml",
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n\
+             [dependencies]\nhelper = { path = \"../helper\", optional = true }\n\n\
+             [target.'cfg(windows)'.dependencies]\nwin = { path = \"../win\" }\n",
+        );
+        for name in ["helper", "win"] {
+            write(
+                &format!("{name}/Cargo.toml"),
+                &format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\n"),
+            );
+        }
+        for name in ["app", "helper", "win"] {
+            write(&format!("{name}/src/lib.rs"), "");
+        }
+
+        let workspace = WorkspaceInfo::from_path(root).unwrap();
+        let app = workspace.get_crate("app").unwrap();
+        assert_eq!(app.dependencies, vec!["win"]);
+        assert_eq!(
+            app.target_dependencies,
+            vec![TargetDependency {
+                name: "win".into(),
+                platform: "cfg(windows)".into(),
+            }]
+        );
+        assert!(workspace.is_inactive_dependency("app", "helper"));
+        assert_eq!(
+            app.resolved_dependencies["win"],
+            ResolvedDependency {
+                library: "win".into(),
+                version: Some("0.1.0".into()),
+            }
+        );
+        assert_eq!(workspace.get_crate("win").unwrap().edition, Edition::E2015);
+
+        let features = FeatureSelection {
+            features: vec!["helper".into()],
+            ..FeatureSelection::default()
+        };
+        let workspace = WorkspaceInfo::from_path_with(&root.join("app"), &features).unwrap();
+        let mut deps = workspace.get_crate("app").unwrap().dependencies.clone();
+        deps.sort();
+        assert_eq!(deps, vec!["helper", "win"]);
+        assert!(!workspace.is_inactive_dependency("app", "helper"));
+    }
+
+    #[test]
+    fn test_select_members() {
+        let workspace = WorkspaceInfo {
+            root: PathBuf::new(),
+            target_directory: PathBuf::new(),
+            crates: BTreeMap::new(),
+            members: vec!["my-app".into(), "my-lib".into(), "tools".into()],
+            default_members: vec!["tools".into(), "my-app".into()],
+            dependency_graph: BTreeMap::new(),
+            reverse_deps: BTreeMap::new(),
+        };
+
+        assert_eq!(
+            workspace.select_members(&[]).unwrap(),
+            vec!["my-app", "tools"]
+        );
+        assert_eq!(
+            workspace
+                .select_members(&["tools".into(), "my_lib".into()])
+                .unwrap(),
+            vec!["my-lib", "tools"]
+        );
+        assert_eq!(
+            workspace.select_members(&["my-*".into()]).unwrap(),
+            vec!["my-app", "my-lib"]
+        );
+        assert!(matches!(
+            workspace.select_members(&["missing".into()]),
+            Err(WorkspaceError::PackageNotFound(name)) if name == "missing"
+        ));
+    }
+
     #[test]
     fn test_resolve_crate_from_path() {
         let workspace = WorkspaceInfo {
             root: PathBuf::new(),
-            crates: HashMap::new(),
+            target_directory: PathBuf::new(),
+            crates: BTreeMap::new(),
             members: vec!["my-app".to_string(), "my-lib".to_string()],
-            dependency_graph: HashMap::new(),
-            reverse_deps: HashMap::new(),
+            default_members: Vec::new(),
+            dependency_graph: BTreeMap::new(),
+            reverse_deps: BTreeMap::new(),
         };
 
         // Internal reference
@@ -333,5 +727,8 @@ mod tests {
             resolve_crate_from_path("serde::Serialize", "my-app", &workspace),
             Some("serde".to_string())
         );
+
+        // Item in scope
+        assert_eq!(resolve_crate_from_path("User", "my-app", &workspace), None);
     }
 }